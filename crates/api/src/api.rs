@@ -0,0 +1,175 @@
+//! Query surface shared by [`MorphoApiClient`] and test doubles.
+//!
+//! Application code that selects or rebalances vaults can be written against
+//! [`MorphoApi`] instead of a concrete client, so it can be unit tested with
+//! `MockMorphoApi` (feature `test-util`) without network access.
+
+use alloy_primitives::Address;
+
+use crate::client::MorphoApiClient;
+use crate::error::Result;
+use crate::filters::{VaultFiltersV1, VaultFiltersV2, VaultQueryOptionsV1, VaultQueryOptionsV2};
+use crate::types::vault::Vault as VaultTrait;
+use crate::types::{NamedChain, UserAccountOverview, UserVaultPositions, VaultV1, VaultV2};
+
+/// Read-only Morpho vault and position queries.
+///
+/// The combined queries ([`get_vaults_by_chain`](Self::get_vaults_by_chain),
+/// [`get_whitelisted_vaults`](Self::get_whitelisted_vaults),
+/// [`get_v2_underlying_v1_vaults`](Self::get_v2_underlying_v1_vaults)) have default
+/// implementations in terms of the V1/V2 queries.
+#[allow(async_fn_in_trait)]
+pub trait MorphoApi {
+    /// Get V1 vaults matching the given filters.
+    async fn get_vaults_v1(&self, filters: Option<VaultFiltersV1>) -> Result<Vec<VaultV1>>;
+
+    /// Get V1 vaults with filters, ordering, and limit.
+    async fn get_vaults_v1_with_options(
+        &self,
+        options: VaultQueryOptionsV1,
+    ) -> Result<Vec<VaultV1>>;
+
+    /// Get a single V1 vault by address and chain.
+    async fn get_vault_v1(&self, address: Address, chain: NamedChain) -> Result<VaultV1>;
+
+    /// Get V2 vaults matching the given filters.
+    async fn get_vaults_v2(&self, filters: Option<VaultFiltersV2>) -> Result<Vec<VaultV2>>;
+
+    /// Get V2 vaults with filters, ordering, limit, and client-side filters.
+    async fn get_vaults_v2_with_options(
+        &self,
+        options: VaultQueryOptionsV2,
+    ) -> Result<Vec<VaultV2>>;
+
+    /// Get a single V2 vault by address and chain.
+    async fn get_vault_v2(&self, address: Address, chain: NamedChain) -> Result<VaultV2>;
+
+    /// Get all vault positions (V1 and V2) for a user, on one chain or all chains.
+    async fn get_user_vault_positions(
+        &self,
+        address: Address,
+        chain: Option<NamedChain>,
+    ) -> Result<UserVaultPositions>;
+
+    /// Get complete account overview for a user on a specific chain.
+    async fn get_user_account_overview(
+        &self,
+        address: Address,
+        chain: NamedChain,
+    ) -> Result<UserAccountOverview>;
+
+    /// Get vaults (V1 and V2) on a specific chain as unified Vault trait objects.
+    async fn get_vaults_by_chain(&self, chain: NamedChain) -> Result<Vec<Box<dyn VaultTrait>>> {
+        let v1 = self
+            .get_vaults_v1(Some(VaultFiltersV1::new().chain(chain)))
+            .await?;
+        let v2 = self
+            .get_vaults_v2(Some(VaultFiltersV2::new().chain(chain)))
+            .await?;
+        Ok(boxed(v1, v2))
+    }
+
+    /// Get whitelisted vaults (V1 and V2) as unified Vault trait objects.
+    async fn get_whitelisted_vaults(
+        &self,
+        chain: Option<NamedChain>,
+    ) -> Result<Vec<Box<dyn VaultTrait>>> {
+        let mut v1_filters = VaultFiltersV1::new().listed(true);
+        let mut v2_filters = VaultFiltersV2::new().listed(true);
+        if let Some(c) = chain {
+            v1_filters = v1_filters.chain(c);
+            v2_filters = v2_filters.chain(c);
+        }
+        let v1 = self.get_vaults_v1(Some(v1_filters)).await?;
+        let v2 = self.get_vaults_v2(Some(v2_filters)).await?;
+        Ok(boxed(v1, v2))
+    }
+
+    /// Get the V1 (MetaMorpho) vaults underlying a V2 vault.
+    async fn get_v2_underlying_v1_vaults(
+        &self,
+        vault: Address,
+        chain: NamedChain,
+    ) -> Result<Vec<VaultV1>> {
+        let vault_v2 = self.get_vault_v2(vault, chain).await?;
+        let mut vaults = Vec::new();
+        for address in vault_v2.underlying_v1_vault_addresses() {
+            vaults.push(self.get_vault_v1(address, chain).await?);
+        }
+        Ok(vaults)
+    }
+}
+
+fn boxed(v1: Vec<VaultV1>, v2: Vec<VaultV2>) -> Vec<Box<dyn VaultTrait>> {
+    let mut vaults: Vec<Box<dyn VaultTrait>> = Vec::with_capacity(v1.len() + v2.len());
+    vaults.extend(v1.into_iter().map(|v| Box::new(v) as Box<dyn VaultTrait>));
+    vaults.extend(v2.into_iter().map(|v| Box::new(v) as Box<dyn VaultTrait>));
+    vaults
+}
+
+impl MorphoApi for MorphoApiClient {
+    async fn get_vaults_v1(&self, filters: Option<VaultFiltersV1>) -> Result<Vec<VaultV1>> {
+        self.v1.get_vaults(filters).await
+    }
+
+    async fn get_vaults_v1_with_options(
+        &self,
+        options: VaultQueryOptionsV1,
+    ) -> Result<Vec<VaultV1>> {
+        self.v1.get_vaults_with_options(options).await
+    }
+
+    async fn get_vault_v1(&self, address: Address, chain: NamedChain) -> Result<VaultV1> {
+        self.v1.get_vault(address, chain).await
+    }
+
+    async fn get_vaults_v2(&self, filters: Option<VaultFiltersV2>) -> Result<Vec<VaultV2>> {
+        self.v2.get_vaults(filters).await
+    }
+
+    async fn get_vaults_v2_with_options(
+        &self,
+        options: VaultQueryOptionsV2,
+    ) -> Result<Vec<VaultV2>> {
+        self.v2.get_vaults_with_options(options).await
+    }
+
+    async fn get_vault_v2(&self, address: Address, chain: NamedChain) -> Result<VaultV2> {
+        self.v2.get_vault(address, chain).await
+    }
+
+    async fn get_user_vault_positions(
+        &self,
+        address: Address,
+        chain: Option<NamedChain>,
+    ) -> Result<UserVaultPositions> {
+        MorphoApiClient::get_user_vault_positions(self, address, chain).await
+    }
+
+    async fn get_user_account_overview(
+        &self,
+        address: Address,
+        chain: NamedChain,
+    ) -> Result<UserAccountOverview> {
+        MorphoApiClient::get_user_account_overview(self, address, chain).await
+    }
+
+    async fn get_vaults_by_chain(&self, chain: NamedChain) -> Result<Vec<Box<dyn VaultTrait>>> {
+        MorphoApiClient::get_vaults_by_chain(self, chain).await
+    }
+
+    async fn get_whitelisted_vaults(
+        &self,
+        chain: Option<NamedChain>,
+    ) -> Result<Vec<Box<dyn VaultTrait>>> {
+        MorphoApiClient::get_whitelisted_vaults(self, chain).await
+    }
+
+    async fn get_v2_underlying_v1_vaults(
+        &self,
+        vault: Address,
+        chain: NamedChain,
+    ) -> Result<Vec<VaultV1>> {
+        MorphoApiClient::get_v2_underlying_v1_vaults(self, vault, chain).await
+    }
+}
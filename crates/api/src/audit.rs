@@ -0,0 +1,753 @@
+//! Structured audit log of on-chain actions.
+//!
+//! [`AuditBackend`] wraps an [`ExecutionBackend`] and records every transaction and
+//! user operation it executes as an [`AuditRecord`]: chain, sender, target contract,
+//! decoded function and arguments, transaction hash, receipt status, and the sender's
+//! balance of the target token (vault shares for vault calls) before and after. Records
+//! go to a pluggable [`AuditSink`]; [`JsonlAuditSink`] appends them to a JSON Lines file,
+//! `SqliteAuditSink` (with the `sqlite` feature) inserts them into a SQLite table, and
+//! [`MemoryAuditSink`] keeps them in memory. Implement [`AuditSink`] for other
+//! destinations.
+//!
+//! [`MorphoClientConfig::with_audit_sink`](crate::MorphoClientConfig::with_audit_sink)
+//! wraps the client's execution backend, so every transaction sent by
+//! [`VaultV1Operations`](crate::VaultV1Operations),
+//! [`VaultV2Operations`](crate::VaultV2Operations), and reward claims is recorded.
+//!
+//! The transaction has already been sent when its record is written, so a sink failure
+//! does not fail the operation; the record and the error are passed to the handler set
+//! with [`AuditBackend::with_on_failure`] (or
+//! [`MorphoClientConfig::with_audit_failure_handler`](crate::MorphoClientConfig::with_audit_failure_handler))
+//! instead, and dropped if there is none.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use morpho_rs_api::{JsonlAuditSink, MorphoClient, MorphoClientConfig};
+//!
+//! # fn main() -> Result<(), morpho_rs_api::ApiError> {
+//! let config = MorphoClientConfig::new()
+//!     .with_rpc_url("https://eth.llamarpc.com")
+//!     .with_private_key("0x...")
+//!     .with_audit_sink(Arc::new(JsonlAuditSink::new("audit.jsonl")));
+//! let client = MorphoClient::with_config(config)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::Debug;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use futures::future;
+use morpho_rs_contracts::{AccountCall, CallDescription, HttpProvider};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ApiError, Result};
+use crate::execution::{Execution, ExecutionBackend, UserOperation};
+use crate::platform::{BoxFuture, SystemTime, UNIX_EPOCH};
+
+sol! {
+    interface IAuditBalance {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// Signatures tried when decoding user operation calls, which carry raw calldata.
+const KNOWN_SIGNATURES: &[&str] = &[
+    "deposit(uint256,address)",
+    "mint(uint256,address)",
+    "withdraw(uint256,address,address)",
+    "redeem(uint256,address,address)",
+    "approve(address,uint256)",
+    "transfer(address,uint256)",
+    "deposit()",
+    "withdraw(uint256)",
+];
+
+/// Outcome of an audited call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "error", rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// The transaction was included and succeeded.
+    Success,
+    /// The transaction was included and reverted.
+    Reverted,
+    /// The call failed before a receipt was obtained, with this error.
+    Failed(String),
+}
+
+/// One on-chain action executed by a client operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unix timestamp (seconds) at which the action completed.
+    pub timestamp: u64,
+    /// Chain ID, if it could be read.
+    pub chain_id: Option<u64>,
+    /// Signer, or smart account for user operations.
+    pub from: Address,
+    /// Contract called: the vault for vault calls, the token for approvals.
+    pub to: Address,
+    /// Function name, or the hex selector if the call could not be decoded.
+    pub function: String,
+    /// Full function signature, if decoded.
+    pub signature: Option<String>,
+    /// Decoded arguments in human-readable form.
+    pub args: Vec<String>,
+    /// Native currency sent with the call, in wei.
+    pub value: U256,
+    /// Whether the call was part of an ERC-4337 user operation.
+    pub user_operation: bool,
+    /// Hash of the transaction, if a receipt was obtained.
+    pub tx_hash: Option<B256>,
+    /// Block the transaction was included in, if a receipt was obtained.
+    pub block_number: Option<u64>,
+    /// Gas used by the transaction, if a receipt was obtained.
+    pub gas_used: Option<u64>,
+    /// Outcome of the call.
+    #[serde(flatten)]
+    pub status: AuditStatus,
+    /// `from`'s balance of `to` before the call, if `to` is a token.
+    pub balance_before: Option<U256>,
+    /// `from`'s balance of `to` after the call, if `to` is a token.
+    pub balance_after: Option<U256>,
+}
+
+impl AuditRecord {
+    /// Change in `from`'s balance of `to`, if both balances are known.
+    pub fn balance_delta(&self) -> Option<(U256, bool)> {
+        let before = self.balance_before?;
+        let after = self.balance_after?;
+        Some(if after >= before {
+            (after - before, true)
+        } else {
+            (before - after, false)
+        })
+    }
+}
+
+/// Destination of [`AuditRecord`]s.
+pub trait AuditSink: Debug + Send + Sync {
+    /// Persist a record.
+    fn record<'a>(&'a self, record: &'a AuditRecord) -> BoxFuture<'a, Result<()>>;
+}
+
+/// [`AuditSink`] appending records to a JSON Lines file, one record per line.
+#[derive(Debug)]
+pub struct JsonlAuditSink {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonlAuditSink {
+    /// Create a sink appending to `path`. The file is created on the first record.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the sink's file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn append(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)
+            .map_err(|e| ApiError::Storage(format!("failed to serialize audit record: {e}")))?;
+        line.push('\n');
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| ApiError::Storage("audit log lock poisoned".to_string()))?;
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| {
+                ApiError::Storage(format!(
+                    "failed to write audit log {}: {e}",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+impl AuditSink for JsonlAuditSink {
+    fn record<'a>(&'a self, record: &'a AuditRecord) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.append(record)))
+    }
+}
+
+/// [`AuditSink`] inserting records into the `audit_log` table of a SQLite database.
+///
+/// Amounts are stored as decimal strings, arguments as a JSON array, and the status as
+/// `success`, `reverted`, or `failed` with the error in the `error` column.
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteAuditSink {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteAuditSink {
+    /// Open the database at `path`, creating it and the `audit_log` table if missing.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = rusqlite::Connection::open(path.as_ref()).map_err(sqlite_err)?;
+        Self::with_connection(conn)
+    }
+
+    /// Use an open connection, creating the `audit_log` table if missing.
+    pub fn with_connection(conn: rusqlite::Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                chain_id INTEGER,
+                from_address TEXT NOT NULL,
+                to_address TEXT NOT NULL,
+                function TEXT NOT NULL,
+                signature TEXT,
+                args TEXT NOT NULL,
+                value TEXT NOT NULL,
+                user_operation INTEGER NOT NULL,
+                tx_hash TEXT,
+                block_number INTEGER,
+                gas_used INTEGER,
+                status TEXT NOT NULL,
+                error TEXT,
+                balance_before TEXT,
+                balance_after TEXT
+            );",
+        )
+        .map_err(sqlite_err)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Records stored so far, in insertion order.
+    pub fn records(&self) -> Result<Vec<AuditRecord>> {
+        let conn = self.lock()?;
+        let mut query = conn
+            .prepare(
+                "SELECT timestamp, chain_id, from_address, to_address, function, signature,
+                    args, value, user_operation, tx_hash, block_number, gas_used, status, error,
+                    balance_before, balance_after
+                 FROM audit_log ORDER BY id",
+            )
+            .map_err(sqlite_err)?;
+        let rows = query
+            .query_map([], |row| {
+                Ok(SqliteAuditRow {
+                    timestamp: row.get(0)?,
+                    chain_id: row.get(1)?,
+                    from: row.get(2)?,
+                    to: row.get(3)?,
+                    function: row.get(4)?,
+                    signature: row.get(5)?,
+                    args: row.get(6)?,
+                    value: row.get(7)?,
+                    user_operation: row.get(8)?,
+                    tx_hash: row.get(9)?,
+                    block_number: row.get(10)?,
+                    gas_used: row.get(11)?,
+                    status: row.get(12)?,
+                    error: row.get(13)?,
+                    balance_before: row.get(14)?,
+                    balance_after: row.get(15)?,
+                })
+            })
+            .map_err(sqlite_err)?;
+        rows.map(|row| row.map_err(sqlite_err)?.into_record())
+            .collect()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, rusqlite::Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| ApiError::Storage("audit database lock poisoned".to_string()))
+    }
+
+    fn insert(&self, record: &AuditRecord) -> Result<()> {
+        let args = serde_json::to_string(&record.args)
+            .map_err(|e| ApiError::Storage(format!("failed to serialize audit arguments: {e}")))?;
+        let (status, error) = match &record.status {
+            AuditStatus::Success => ("success", None),
+            AuditStatus::Reverted => ("reverted", None),
+            AuditStatus::Failed(error) => ("failed", Some(error.as_str())),
+        };
+        self.lock()?
+            .execute(
+                "INSERT INTO audit_log (timestamp, chain_id, from_address, to_address, function,
+                    signature, args, value, user_operation, tx_hash, block_number, gas_used,
+                    status, error, balance_before, balance_after)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+                rusqlite::params![
+                    record.timestamp as i64,
+                    record.chain_id.map(|id| id as i64),
+                    record.from.to_string(),
+                    record.to.to_string(),
+                    record.function,
+                    record.signature,
+                    args,
+                    record.value.to_string(),
+                    record.user_operation,
+                    record.tx_hash.map(|hash| hash.to_string()),
+                    record.block_number.map(|n| n as i64),
+                    record.gas_used.map(|gas| gas as i64),
+                    status,
+                    error,
+                    record.balance_before.map(|b| b.to_string()),
+                    record.balance_after.map(|b| b.to_string()),
+                ],
+            )
+            .map(|_| ())
+            .map_err(sqlite_err)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl AuditSink for SqliteAuditSink {
+    fn record<'a>(&'a self, record: &'a AuditRecord) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.insert(record)))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_err(e: rusqlite::Error) -> ApiError {
+    ApiError::Storage(format!("audit database error: {e}"))
+}
+
+/// An `audit_log` row as stored.
+#[cfg(feature = "sqlite")]
+struct SqliteAuditRow {
+    timestamp: i64,
+    chain_id: Option<i64>,
+    from: String,
+    to: String,
+    function: String,
+    signature: Option<String>,
+    args: String,
+    value: String,
+    user_operation: bool,
+    tx_hash: Option<String>,
+    block_number: Option<i64>,
+    gas_used: Option<i64>,
+    status: String,
+    error: Option<String>,
+    balance_before: Option<String>,
+    balance_after: Option<String>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteAuditRow {
+    fn into_record(self) -> Result<AuditRecord> {
+        fn parse<T: std::str::FromStr>(column: &str, value: &str) -> Result<T> {
+            value
+                .parse()
+                .map_err(|_| ApiError::Storage(format!("invalid {column} in audit log: {value}")))
+        }
+        let status = match (self.status.as_str(), self.error) {
+            ("success", _) => AuditStatus::Success,
+            ("reverted", _) => AuditStatus::Reverted,
+            (_, error) => AuditStatus::Failed(error.unwrap_or(self.status)),
+        };
+        Ok(AuditRecord {
+            timestamp: self.timestamp as u64,
+            chain_id: self.chain_id.map(|id| id as u64),
+            from: parse("from_address", &self.from)?,
+            to: parse("to_address", &self.to)?,
+            function: self.function,
+            signature: self.signature,
+            args: serde_json::from_str(&self.args)
+                .map_err(|e| ApiError::Storage(format!("invalid args in audit log: {e}")))?,
+            value: parse("value", &self.value)?,
+            user_operation: self.user_operation,
+            tx_hash: self
+                .tx_hash
+                .map(|hash| parse("tx_hash", &hash))
+                .transpose()?,
+            block_number: self.block_number.map(|n| n as u64),
+            gas_used: self.gas_used.map(|gas| gas as u64),
+            status,
+            balance_before: self
+                .balance_before
+                .map(|b| parse("balance_before", &b))
+                .transpose()?,
+            balance_after: self
+                .balance_after
+                .map(|b| parse("balance_after", &b))
+                .transpose()?,
+        })
+    }
+}
+
+/// [`AuditSink`] keeping records in memory.
+#[derive(Debug, Default)]
+pub struct MemoryAuditSink {
+    records: Mutex<Vec<AuditRecord>>,
+}
+
+impl MemoryAuditSink {
+    /// Create an empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records received so far, in order.
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records
+            .lock()
+            .map(|records| records.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn record<'a>(&'a self, record: &'a AuditRecord) -> BoxFuture<'a, Result<()>> {
+        if let Ok(mut records) = self.records.lock() {
+            records.push(record.clone());
+        }
+        Box::pin(future::ready(Ok(())))
+    }
+}
+
+/// Handler receiving a record that an [`AuditSink`] failed to write, with the error.
+pub type AuditFailureHandler = Arc<dyn Fn(&AuditRecord, &ApiError) + Send + Sync>;
+
+/// [`ExecutionBackend`] recording every call executed by another backend to an
+/// [`AuditSink`].
+pub struct AuditBackend {
+    inner: Arc<dyn ExecutionBackend>,
+    sink: Arc<dyn AuditSink>,
+    on_failure: Option<AuditFailureHandler>,
+}
+
+impl AuditBackend {
+    /// Record the calls executed by `inner` to `sink`.
+    pub fn new(inner: Arc<dyn ExecutionBackend>, sink: Arc<dyn AuditSink>) -> Self {
+        Self {
+            inner,
+            sink,
+            on_failure: None,
+        }
+    }
+
+    /// Pass records the sink fails to write to `on_failure`, with the error.
+    ///
+    /// The call has already been executed by then, so the failure does not fail it.
+    pub fn with_on_failure(mut self, on_failure: AuditFailureHandler) -> Self {
+        self.on_failure = Some(on_failure);
+        self
+    }
+
+    /// The wrapped backend.
+    pub fn inner(&self) -> &Arc<dyn ExecutionBackend> {
+        &self.inner
+    }
+
+    async fn write(&self, record: &AuditRecord) {
+        if let Err(e) = self.sink.record(record).await {
+            if let Some(on_failure) = &self.on_failure {
+                on_failure(record, &e);
+            }
+        }
+    }
+}
+
+impl Debug for AuditBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditBackend")
+            .field("inner", &self.inner)
+            .field("sink", &self.sink)
+            .field("on_failure", &self.on_failure.as_ref().map(|_| "Fn"))
+            .finish()
+    }
+}
+
+impl ExecutionBackend for AuditBackend {
+    fn execute<'a>(
+        &'a self,
+        execution: Execution<'a>,
+    ) -> BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(async move {
+            let provider = execution.provider();
+            let from = execution.from;
+            let description = execution.description.clone();
+            let chain_id = provider.get_chain_id().await.ok();
+            let before = balance_of(provider, description.to, from).await;
+
+            let result = self.inner.execute(execution).await;
+
+            let after = balance_of(provider, description.to, from).await;
+            let mut record = new_record(chain_id, from, &description, false, &result);
+            record.balance_before = before;
+            record.balance_after = after;
+            self.write(&record).await;
+            result
+        })
+    }
+
+    fn rpc_url(&self) -> Option<&str> {
+        self.inner.rpc_url()
+    }
+
+    fn supports_user_operations(&self) -> bool {
+        self.inner.supports_user_operations()
+    }
+
+    fn is_live(&self) -> bool {
+        self.inner.is_live()
+    }
+
+    fn execute_user_operation<'a>(
+        &'a self,
+        operation: UserOperation<'a>,
+    ) -> BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(async move {
+            let provider = operation.provider();
+            let account = operation.account;
+            let calls = operation.calls;
+            let chain_id = provider.get_chain_id().await.ok();
+            let mut before = Vec::with_capacity(calls.len());
+            for call in calls {
+                before.push(balance_of(provider, call.to, account).await);
+            }
+
+            let result = self.inner.execute_user_operation(operation).await;
+
+            for (call, before) in calls.iter().zip(before) {
+                let description = describe_account_call(call);
+                let mut record = new_record(chain_id, account, &description, true, &result);
+                record.balance_before = before;
+                record.balance_after = balance_of(provider, call.to, account).await;
+                self.write(&record).await;
+            }
+            result
+        })
+    }
+}
+
+/// Record of `description` sent from `from` with outcome `result`, without balances.
+fn new_record(
+    chain_id: Option<u64>,
+    from: Address,
+    description: &CallDescription,
+    user_operation: bool,
+    result: &Result<TransactionReceipt>,
+) -> AuditRecord {
+    let (receipt, status) = match result {
+        Ok(receipt) if receipt.status() => (Some(receipt), AuditStatus::Success),
+        Ok(receipt) => (Some(receipt), AuditStatus::Reverted),
+        Err(e) => (None, AuditStatus::Failed(e.to_string())),
+    };
+    let signature = if description.signature.is_empty() {
+        None
+    } else {
+        Some(description.signature.to_string())
+    };
+    AuditRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        chain_id,
+        from,
+        to: description.to,
+        function: description.function.clone(),
+        signature,
+        args: description.formatted_args(),
+        value: description.value,
+        user_operation,
+        tx_hash: receipt.map(|r| r.transaction_hash),
+        block_number: receipt.and_then(|r| r.block_number),
+        gas_used: receipt.map(|r| r.gas_used),
+        status,
+        balance_before: None,
+        balance_after: None,
+    }
+}
+
+/// Decode a raw call against [`KNOWN_SIGNATURES`], falling back to its selector.
+fn describe_account_call(call: &AccountCall) -> CallDescription {
+    KNOWN_SIGNATURES
+        .iter()
+        .find_map(|signature| CallDescription::decode(call.to, signature, &call.data, call.value))
+        .unwrap_or_else(|| CallDescription {
+            to: call.to,
+            function: format!(
+                "0x{}",
+                alloy::hex::encode(call.data.get(..4).unwrap_or_default())
+            ),
+            signature: "",
+            args: Vec::new(),
+            value: call.value,
+        })
+}
+
+/// `account`'s balance of `token`, or `None` if `token` is not a token.
+async fn balance_of(provider: &HttpProvider, token: Address, account: Address) -> Option<U256> {
+    let request = TransactionRequest::default()
+        .to(token)
+        .input(IAuditBalance::balanceOfCall { account }.abi_encode().into());
+    let output = provider.call(request).await.ok()?;
+    IAuditBalance::balanceOfCall::abi_decode_returns(&output).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> AuditRecord {
+        AuditRecord {
+            timestamp: 1_700_000_000,
+            chain_id: Some(1),
+            from: Address::repeat_byte(0x01),
+            to: Address::repeat_byte(0x02),
+            function: "deposit".to_string(),
+            signature: Some("deposit(uint256,address)".to_string()),
+            args: vec![
+                "1000000".to_string(),
+                Address::repeat_byte(0x01).to_string(),
+            ],
+            value: U256::ZERO,
+            user_operation: false,
+            tx_hash: Some(B256::repeat_byte(0xaa)),
+            block_number: Some(100),
+            gas_used: Some(90_000),
+            status: AuditStatus::Success,
+            balance_before: Some(U256::from(10)),
+            balance_after: Some(U256::from(25)),
+        }
+    }
+
+    #[test]
+    fn test_balance_delta() {
+        let mut record = record();
+        assert_eq!(record.balance_delta(), Some((U256::from(15), true)));
+        record.balance_after = Some(U256::from(4));
+        assert_eq!(record.balance_delta(), Some((U256::from(6), false)));
+        record.balance_before = None;
+        assert_eq!(record.balance_delta(), None);
+    }
+
+    #[test]
+    fn test_describe_account_call() {
+        let vault = Address::repeat_byte(0x02);
+        let receiver = Address::repeat_byte(0x01);
+        let data = sol_data_deposit(U256::from(5), receiver);
+        let description = describe_account_call(&AccountCall {
+            to: vault,
+            value: U256::ZERO,
+            data: data.into(),
+        });
+        assert_eq!(description.function, "deposit");
+        assert_eq!(
+            description.formatted_args(),
+            vec!["5".to_string(), receiver.to_string()]
+        );
+
+        let unknown = describe_account_call(&AccountCall {
+            to: vault,
+            value: U256::ZERO,
+            data: vec![0xde, 0xad, 0xbe, 0xef, 0x00].into(),
+        });
+        assert_eq!(unknown.function, "0xdeadbeef");
+        assert!(unknown.signature.is_empty());
+    }
+
+    fn sol_data_deposit(assets: U256, receiver: Address) -> Vec<u8> {
+        sol! {
+            function deposit(uint256 assets, address receiver) external returns (uint256);
+        }
+        depositCall { assets, receiver }.abi_encode()
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_audit_sink_appends() {
+        let path =
+            std::env::temp_dir().join(format!("morpho-rs-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = JsonlAuditSink::new(&path);
+
+        sink.record(&record()).await.expect("first record");
+        let mut reverted = record();
+        reverted.status = AuditStatus::Failed("execution reverted".to_string());
+        sink.record(&reverted).await.expect("second record");
+
+        let contents = std::fs::read_to_string(&path).expect("read log");
+        let records: Vec<AuditRecord> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("valid record"))
+            .collect();
+        assert_eq!(records, vec![record(), reverted]);
+        assert!(contents.contains(r#""status":"failed","error":"execution reverted""#));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_audit_sink_round_trip() {
+        let sink = SqliteAuditSink::with_connection(
+            rusqlite::Connection::open_in_memory().expect("in-memory database"),
+        )
+        .expect("create table");
+
+        sink.record(&record()).await.expect("first record");
+        let mut failed = record();
+        failed.status = AuditStatus::Failed("execution reverted".to_string());
+        failed.tx_hash = None;
+        failed.balance_after = None;
+        sink.record(&failed).await.expect("second record");
+
+        assert_eq!(
+            sink.records().expect("read records"),
+            vec![record(), failed]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_audit_sink() {
+        let sink = MemoryAuditSink::new();
+        sink.record(&record()).await.expect("record");
+        assert_eq!(sink.records(), vec![record()]);
+    }
+
+    #[derive(Debug)]
+    struct FailingSink;
+
+    impl AuditSink for FailingSink {
+        fn record<'a>(&'a self, _record: &'a AuditRecord) -> BoxFuture<'a, Result<()>> {
+            Box::pin(future::ready(Err(ApiError::Storage("disk full".to_string()))))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_backend_reports_sink_failures() {
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let seen = failures.clone();
+        let backend = AuditBackend::new(Arc::new(crate::LiveBackend), Arc::new(FailingSink))
+            .with_on_failure(Arc::new(move |record, e| {
+                seen.lock().unwrap().push((record.clone(), e.to_string()));
+            }));
+
+        backend.write(&record()).await;
+
+        let failures = failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, record());
+        assert!(failures[0].1.contains("disk full"));
+    }
+}
@@ -0,0 +1,385 @@
+//! Signed-message (SIWE) authentication for private API tiers.
+//!
+//! [`SiweAuthenticator`] signs an [EIP-4361](https://eips.ethereum.org/EIPS/eip-4361)
+//! "Sign-In with Ethereum" message with a local private key, exchanges it for a
+//! session token, and caches the token until shortly before it expires. The API
+//! clients attach the token as a bearer token on every GraphQL request and
+//! transparently sign in again when the token expires or is rejected.
+//!
+//! The sign-in flow expects two endpoints:
+//!
+//! - `GET nonce_url` returning `{"nonce": "..."}`
+//! - `POST login_url` with `{"message": "...", "signature": "0x..."}`, returning
+//!   `{"token": "...", "expiresAt": <unix seconds>}`
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::{ClientConfig, SiweAuthConfig, SiweAuthenticator, VaultV1Client};
+//! use url::Url;
+//!
+//! # fn main() -> Result<(), morpho_rs_api::ApiError> {
+//! let auth_config = SiweAuthConfig::new(
+//!     Url::parse("https://auth.example.com/nonce").unwrap(),
+//!     Url::parse("https://auth.example.com/login").unwrap(),
+//!     "example.com",
+//! )
+//! .with_statement("Sign in to the Morpho API");
+//!
+//! let auth = SiweAuthenticator::new("0x...", auth_config)?;
+//! let client = VaultV1Client::with_config(ClientConfig::new().with_siwe_auth(auth));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use alloy::primitives::{hex, Address};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::error::{ApiError, Result};
+use crate::platform::{format_timestamp, unix_now};
+
+/// Default number of seconds before expiry at which a session is refreshed.
+pub const DEFAULT_REFRESH_MARGIN_SECS: u64 = 60;
+
+/// Settings for the SIWE sign-in flow.
+#[derive(Debug, Clone)]
+pub struct SiweAuthConfig {
+    /// Endpoint returning a fresh sign-in nonce.
+    pub nonce_url: Url,
+    /// Endpoint exchanging a signed message for a session token.
+    pub login_url: Url,
+    /// Domain requesting the signature (the `domain` field of the SIWE message).
+    pub domain: String,
+    /// URI the session is scoped to. Defaults to the login URL.
+    pub uri: String,
+    /// Chain ID the signature is bound to. Defaults to Ethereum mainnet.
+    pub chain_id: u64,
+    /// Optional human-readable statement included in the message.
+    pub statement: Option<String>,
+    /// Seconds before expiry at which the cached token is considered stale.
+    pub refresh_margin_secs: u64,
+}
+
+impl SiweAuthConfig {
+    /// Create a new configuration with default chain ID and refresh margin.
+    pub fn new(nonce_url: Url, login_url: Url, domain: impl Into<String>) -> Self {
+        let uri = login_url.to_string();
+        Self {
+            nonce_url,
+            login_url,
+            domain: domain.into(),
+            uri,
+            chain_id: 1,
+            statement: None,
+            refresh_margin_secs: DEFAULT_REFRESH_MARGIN_SECS,
+        }
+    }
+
+    /// Set the URI the session is scoped to.
+    pub fn with_uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = uri.into();
+        self
+    }
+
+    /// Set the chain ID the signature is bound to.
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Set the statement included in the message.
+    pub fn with_statement(mut self, statement: impl Into<String>) -> Self {
+        self.statement = Some(statement.into());
+        self
+    }
+
+    /// Set how many seconds before expiry the token is refreshed.
+    pub fn with_refresh_margin_secs(mut self, secs: u64) -> Self {
+        self.refresh_margin_secs = secs;
+        self
+    }
+}
+
+/// A cached session token.
+#[derive(Clone)]
+struct Session {
+    token: String,
+    expires_at: u64,
+}
+
+#[derive(Deserialize)]
+struct NonceResponse {
+    nonce: String,
+}
+
+#[derive(Serialize)]
+struct LoginRequest<'a> {
+    message: &'a str,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginResponse {
+    token: String,
+    expires_at: u64,
+}
+
+/// Signs in to the API with a local key and caches the resulting session token.
+pub struct SiweAuthenticator {
+    config: SiweAuthConfig,
+    signer: PrivateKeySigner,
+    session: Mutex<Option<Session>>,
+}
+
+impl fmt::Debug for SiweAuthenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SiweAuthenticator")
+            .field("config", &self.config)
+            .field("address", &self.signer.address())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SiweAuthenticator {
+    /// Create an authenticator signing with the given private key.
+    pub fn new(private_key: &str, config: SiweAuthConfig) -> Result<Self> {
+        let signer: PrivateKeySigner = private_key
+            .parse()
+            .map_err(|_| ApiError::Authentication("invalid private key".to_string()))?;
+        Ok(Self {
+            config,
+            signer,
+            session: Mutex::new(None),
+        })
+    }
+
+    /// The address that signs in.
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// The sign-in configuration.
+    pub fn config(&self) -> &SiweAuthConfig {
+        &self.config
+    }
+
+    /// Build the EIP-4361 message for a nonce and issuance time (unix seconds).
+    pub fn build_message(&self, nonce: &str, issued_at: u64) -> String {
+        let statement = match &self.config.statement {
+            Some(statement) => format!("{}\n", statement),
+            None => String::new(),
+        };
+        format!(
+            "{} wants you to sign in with your Ethereum account:\n{}\n\n{}\nURI: {}\nVersion: 1\nChain ID: {}\nNonce: {}\nIssued At: {}",
+            self.config.domain,
+            self.signer.address().to_checksum(None),
+            statement,
+            self.config.uri,
+            self.config.chain_id,
+            nonce,
+            format_timestamp(issued_at),
+        )
+    }
+
+    /// Return a valid session token, signing in if none is cached or it is about to expire.
+    ///
+    /// Concurrent callers wait for a single in-flight sign-in rather than each signing in.
+    pub async fn token(&self, http_client: &Client) -> Result<String> {
+        let mut session = self.session.lock().await;
+        let now = unix_now();
+        if let Some(current) = session.as_ref() {
+            if now.saturating_add(self.config.refresh_margin_secs) < current.expires_at {
+                return Ok(current.token.clone());
+            }
+        }
+
+        let fresh = self.sign_in(http_client, now).await?;
+        let token = fresh.token.clone();
+        *session = Some(fresh);
+        Ok(token)
+    }
+
+    /// Drop the cached token so that the next request signs in again.
+    pub async fn invalidate(&self) {
+        *self.session.lock().await = None;
+    }
+
+    async fn sign_in(&self, http_client: &Client, now: u64) -> Result<Session> {
+        let nonce: NonceResponse = http_client
+            .get(self.config.nonce_url.as_str())
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| ApiError::Authentication(format!("nonce request failed: {}", e)))?
+            .json()
+            .await?;
+
+        let message = self.build_message(&nonce.nonce, now);
+        let signature = self
+            .signer
+            .sign_message(message.as_bytes())
+            .await
+            .map_err(|e| ApiError::Authentication(format!("failed to sign message: {}", e)))?;
+
+        let login: LoginResponse = http_client
+            .post(self.config.login_url.as_str())
+            .json(&LoginRequest {
+                message: &message,
+                signature: hex::encode_prefixed(signature.as_bytes()),
+            })
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| ApiError::Authentication(format!("login rejected: {}", e)))?
+            .json()
+            .await?;
+
+        Ok(Session {
+            token: login.token,
+            expires_at: login.expires_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    // Anvil's default account 0 private key
+    const TEST_PRIVATE_KEY: &str =
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn test_config(base: &str) -> SiweAuthConfig {
+        SiweAuthConfig::new(
+            Url::parse(&format!("{}/nonce", base)).unwrap(),
+            Url::parse(&format!("{}/login", base)).unwrap(),
+            "example.com",
+        )
+    }
+
+    #[test]
+    fn test_invalid_private_key() {
+        let result = SiweAuthenticator::new("not a key", test_config("http://localhost"));
+        assert!(matches!(result, Err(ApiError::Authentication(_))));
+    }
+
+    #[test]
+    fn test_build_message_with_statement() {
+        let config = test_config("https://auth.example.com")
+            .with_uri("https://example.com")
+            .with_chain_id(8453)
+            .with_statement("Sign in");
+        let auth = SiweAuthenticator::new(TEST_PRIVATE_KEY, config).unwrap();
+
+        let message = auth.build_message("abc123", 0);
+        assert_eq!(
+            message,
+            "example.com wants you to sign in with your Ethereum account:\n\
+             0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266\n\
+             \n\
+             Sign in\n\
+             \n\
+             URI: https://example.com\n\
+             Version: 1\n\
+             Chain ID: 8453\n\
+             Nonce: abc123\n\
+             Issued At: 1970-01-01T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_build_message_without_statement() {
+        let auth =
+            SiweAuthenticator::new(TEST_PRIVATE_KEY, test_config("https://auth.example.com"))
+                .unwrap();
+        let message = auth.build_message("n", 0);
+        assert!(message.contains("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266\n\n\nURI: "));
+    }
+
+    #[test]
+    fn test_debug_does_not_leak_key() {
+        let auth =
+            SiweAuthenticator::new(TEST_PRIVATE_KEY, test_config("https://auth.example.com"))
+                .unwrap();
+        let debug = format!("{:?}", auth);
+        assert!(!debug.contains("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"));
+    }
+
+    #[tokio::test]
+    async fn test_token_is_cached_until_expiry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/nonce"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"nonce":"n1"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let body = format!(r#"{{"token":"tok","expiresAt":{}}}"#, unix_now() + 3600);
+        Mock::given(method("POST"))
+            .and(path("/login"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let auth = SiweAuthenticator::new(TEST_PRIVATE_KEY, test_config(&server.uri())).unwrap();
+        let client = Client::new();
+
+        assert_eq!(auth.token(&client).await.unwrap(), "tok");
+        assert_eq!(auth.token(&client).await.unwrap(), "tok");
+    }
+
+    #[tokio::test]
+    async fn test_token_refreshed_after_invalidate() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/nonce"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"nonce":"n1"}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let body = format!(r#"{{"token":"tok","expiresAt":{}}}"#, unix_now() + 3600);
+        Mock::given(method("POST"))
+            .and(path("/login"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let auth = SiweAuthenticator::new(TEST_PRIVATE_KEY, test_config(&server.uri())).unwrap();
+        let client = Client::new();
+
+        auth.token(&client).await.unwrap();
+        auth.invalidate().await;
+        auth.token(&client).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_login_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/nonce"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"nonce":"n1"}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/login"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let auth = SiweAuthenticator::new(TEST_PRIVATE_KEY, test_config(&server.uri())).unwrap();
+        let result = auth.token(&Client::new()).await;
+        assert!(matches!(result, Err(ApiError::Authentication(_))));
+    }
+}
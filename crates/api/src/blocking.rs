@@ -0,0 +1,297 @@
+//! Blocking (synchronous) wrapper around [`MorphoClient`](crate::MorphoClient).
+//!
+//! This module is available with the `blocking` feature. The blocking client owns a
+//! dedicated single-threaded Tokio runtime and drives the async client on it, so it can
+//! be used from scripts, backtests, and other non-async code without writing
+//! `Runtime::block_on` boilerplate.
+//!
+//! The blocking client must not be used from within an async context: calling its
+//! methods (or dropping it) inside a Tokio runtime will panic.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::blocking::MorphoClient;
+//! use morpho_rs_api::NamedChain;
+//!
+//! fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoClient::new()?;
+//!     let vaults = client.get_vaults_v1_by_chain(NamedChain::Mainnet)?;
+//!     for vault in &vaults {
+//!         println!("{}: {:?}", vault.name, vault.state.as_ref().map(|s| s.net_apy));
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+use std::ops::Range;
+
+use alloy::primitives::Address;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::client::{MorphoClient as AsyncMorphoClient, MorphoClientConfig};
+use crate::error::{ApiError, Result};
+use crate::filters::{VaultFiltersV1, VaultFiltersV2, VaultQueryOptionsV1, VaultQueryOptionsV2};
+use crate::types::vault::Vault as VaultTrait;
+use crate::types::{
+    Asset, AssetPriceHistory, NamedChain, SharePriceHistory, TransactionKind,
+    UserAccountOverview, UserMarketState, UserTransactionHistory, UserVaultPositions, VaultFeeInfo,
+    VaultV1, VaultV2,
+};
+
+/// Synchronous Morpho client backed by a dedicated runtime.
+pub struct MorphoClient {
+    inner: AsyncMorphoClient,
+    runtime: Runtime,
+}
+
+impl MorphoClient {
+    /// Create a blocking client with default API configuration (no transaction support).
+    pub fn new() -> Result<Self> {
+        Self::from_async(AsyncMorphoClient::new())
+    }
+
+    /// Create a blocking client with custom configuration.
+    pub fn with_config(config: MorphoClientConfig) -> Result<Self> {
+        Self::from_async(AsyncMorphoClient::with_config(config)?)
+    }
+
+    /// Wrap an existing async client.
+    pub fn from_async(inner: AsyncMorphoClient) -> Result<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(ApiError::Runtime)?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Get the wrapped async client.
+    pub fn inner(&self) -> &AsyncMorphoClient {
+        &self.inner
+    }
+
+    /// Run an arbitrary future on the client's runtime.
+    ///
+    /// Useful for methods without a blocking counterpart, e.g. transaction operations:
+    ///
+    /// ```no_run
+    /// # use morpho_rs_api::blocking::MorphoClient;
+    /// # use alloy::primitives::Address;
+    /// # fn main() -> Result<(), morpho_rs_api::ApiError> {
+    /// # let client = MorphoClient::new()?;
+    /// # let vault = Address::ZERO;
+    /// let balance = client.block_on(async { client.inner().vault_v1()?.balance(vault).await })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Get vaults (V1 and V2) on a specific chain as unified Vault trait objects.
+    pub fn get_vaults_by_chain(&self, chain: NamedChain) -> Result<Vec<Box<dyn VaultTrait>>> {
+        self.block_on(self.inner.get_vaults_by_chain(chain))
+    }
+
+    /// Get whitelisted vaults (V1 and V2) as unified Vault trait objects.
+    pub fn get_whitelisted_vaults(
+        &self,
+        chain: Option<NamedChain>,
+    ) -> Result<Vec<Box<dyn VaultTrait>>> {
+        self.block_on(self.inner.get_whitelisted_vaults(chain))
+    }
+
+    /// Get V1 vaults matching the given filters.
+    pub fn get_vaults_v1(&self, filters: Option<VaultFiltersV1>) -> Result<Vec<VaultV1>> {
+        self.block_on(self.inner.api().v1.get_vaults(filters))
+    }
+
+    /// Get V1 vaults on a specific chain.
+    pub fn get_vaults_v1_by_chain(&self, chain: NamedChain) -> Result<Vec<VaultV1>> {
+        self.block_on(self.inner.api().v1.get_vaults_by_chain(chain))
+    }
+
+    /// Get V1 vaults with filters, ordering, and limit.
+    pub fn get_vaults_v1_with_options(&self, options: VaultQueryOptionsV1) -> Result<Vec<VaultV1>> {
+        self.block_on(self.inner.api().v1.get_vaults_with_options(options))
+    }
+
+    /// Get a single V1 vault by address.
+    pub fn get_vault_v1(&self, address: Address, chain: NamedChain) -> Result<VaultV1> {
+        self.block_on(self.inner.api().v1.get_vault(address, chain))
+    }
+
+    /// Get a V1 vault's fee configuration and fee recipient.
+    pub fn get_vault_v1_fee_info(
+        &self,
+        address: Address,
+        chain: NamedChain,
+    ) -> Result<VaultFeeInfo> {
+        self.block_on(self.inner.api().v1.get_vault_fee_info(address, chain))
+    }
+
+    /// Reconstruct a V1 vault as of `timestamp` (unix seconds) from its historical state.
+    pub fn get_vault_v1_at(
+        &self,
+        address: Address,
+        chain: NamedChain,
+        timestamp: u64,
+    ) -> Result<VaultV1> {
+        self.block_on(self.inner.api().v1.get_vault_at(address, chain, timestamp))
+    }
+
+    /// Get a V1 vault's share price and advertised net APY over `range` (unix seconds).
+    pub fn get_vault_v1_share_price_history(
+        &self,
+        address: Address,
+        chain: NamedChain,
+        range: Range<u64>,
+    ) -> Result<SharePriceHistory> {
+        self.block_on(
+            self.inner
+                .api()
+                .v1
+                .get_vault_share_price_history(address, chain, range),
+        )
+    }
+
+    /// Get V2 vaults matching the given filters.
+    pub fn get_vaults_v2(&self, filters: Option<VaultFiltersV2>) -> Result<Vec<VaultV2>> {
+        self.block_on(self.inner.api().v2.get_vaults(filters))
+    }
+
+    /// Get V2 vaults on a specific chain.
+    pub fn get_vaults_v2_by_chain(&self, chain: NamedChain) -> Result<Vec<VaultV2>> {
+        self.block_on(self.inner.api().v2.get_vaults_by_chain(chain))
+    }
+
+    /// Get V2 vaults with filters, ordering, and limit.
+    pub fn get_vaults_v2_with_options(&self, options: VaultQueryOptionsV2) -> Result<Vec<VaultV2>> {
+        self.block_on(self.inner.api().v2.get_vaults_with_options(options))
+    }
+
+    /// Get a single V2 vault by address.
+    pub fn get_vault_v2(&self, address: Address, chain: NamedChain) -> Result<VaultV2> {
+        self.block_on(self.inner.api().v2.get_vault(address, chain))
+    }
+
+    /// Get a V2 vault's share price and advertised net APY over `range` (unix seconds).
+    pub fn get_vault_v2_share_price_history(
+        &self,
+        address: Address,
+        chain: NamedChain,
+        range: Range<u64>,
+    ) -> Result<SharePriceHistory> {
+        self.block_on(
+            self.inner
+                .api()
+                .v2
+                .get_vault_share_price_history(address, chain, range),
+        )
+    }
+
+    /// Get all vault positions (V1 and V2) for a user.
+    pub fn get_user_vault_positions(
+        &self,
+        address: Address,
+        chain: Option<NamedChain>,
+    ) -> Result<UserVaultPositions> {
+        self.block_on(self.inner.get_user_vault_positions(address, chain))
+    }
+
+    /// Get complete account overview for a user on a specific chain.
+    pub fn get_user_account_overview(
+        &self,
+        address: Address,
+        chain: NamedChain,
+    ) -> Result<UserAccountOverview> {
+        self.block_on(self.inner.get_user_account_overview(address, chain))
+    }
+
+    /// Get a user's position in a market together with the market's current state.
+    pub fn get_user_market_state(
+        &self,
+        address: Address,
+        market_unique_key: &str,
+        chain: NamedChain,
+    ) -> Result<UserMarketState> {
+        self.block_on(
+            self.inner
+                .get_user_market_state(address, market_unique_key, chain),
+        )
+    }
+
+    /// Maximum amount of each market operation `user` can perform, and what limits it.
+    #[cfg(feature = "sim")]
+    pub fn get_position_capacities(
+        &self,
+        user: Address,
+        market_unique_key: &str,
+        chain: NamedChain,
+    ) -> Result<morpho_rs_sim::PositionCapacities> {
+        self.block_on(
+            self.inner
+                .get_position_capacities(user, market_unique_key, chain),
+        )
+    }
+
+    /// Get a user's transactions on a chain, oldest first.
+    pub fn get_user_transactions(
+        &self,
+        address: Address,
+        chain: NamedChain,
+        kind: Option<TransactionKind>,
+        since: Option<u64>,
+    ) -> Result<UserTransactionHistory> {
+        self.block_on(
+            self.inner
+                .get_user_transactions(address, chain, kind, since),
+        )
+    }
+
+    /// Get assets on a chain by symbol, with their current USD price.
+    pub fn get_assets(&self, chain: NamedChain, symbols: &[&str]) -> Result<Vec<Asset>> {
+        self.block_on(self.inner.get_assets(chain, symbols))
+    }
+
+    /// Get an asset by address, with its current USD price.
+    pub fn get_asset(&self, address: Address, chain: NamedChain) -> Result<Asset> {
+        self.block_on(self.inner.get_asset(address, chain))
+    }
+
+    /// Get an asset with its USD price history over `range` (unix seconds).
+    pub fn get_asset_price_history(
+        &self,
+        address: Address,
+        chain: NamedChain,
+        range: Range<u64>,
+    ) -> Result<AssetPriceHistory> {
+        self.block_on(self.inner.get_asset_price_history(address, chain, range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_creates_runtime() {
+        let client = MorphoClient::new().unwrap();
+        assert!(!client.inner().has_transaction_support());
+    }
+
+    #[test]
+    fn test_block_on_runs_future() {
+        let client = MorphoClient::new().unwrap();
+        assert_eq!(client.block_on(async { 21 * 2 }), 42);
+    }
+
+    #[test]
+    fn test_with_config_propagates_errors() {
+        let config = MorphoClientConfig::new()
+            .with_rpc_url("http://localhost:8545")
+            .with_private_key("invalid_key");
+        assert!(MorphoClient::with_config(config).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Per-chain Morpho deployments and network defaults.
+//!
+//! [`chain_config`] returns the canonical contract addresses (Morpho Blue, Bundler3,
+//! Public Allocator, Universal Rewards Distributor, wrapped native token), a default public RPC URL, and the
+//! average block time for a [`NamedChain`], so callers don't have to hardcode them.
+//! Addresses are taken from the Morpho documentation; contracts that are not deployed
+//! on a chain are `None`.
+//!
+//! # Example
+//!
+//! ```
+//! use morpho_rs_api::chains::chain_config;
+//! use morpho_rs_api::NamedChain;
+//!
+//! let base = chain_config(NamedChain::Base).unwrap();
+//! assert_eq!(base.default_rpc_url, "https://mainnet.base.org");
+//! assert!(base.bundler3.is_some());
+//! ```
+
+use std::time::Duration;
+
+use alloy_chains::NamedChain;
+use alloy_primitives::{address, Address};
+
+/// Morpho deployment and network defaults for one chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainConfig {
+    /// The chain.
+    pub chain: NamedChain,
+    /// Morpho Blue singleton.
+    pub morpho: Address,
+    /// Bundler3, used to batch multiple actions into one transaction.
+    pub bundler3: Option<Address>,
+    /// Public Allocator, used to reallocate vault liquidity on demand.
+    pub public_allocator: Option<Address>,
+    /// Universal Rewards Distributor used for Morpho rewards.
+    pub urd: Option<Address>,
+    /// Wrapped native token (WETH, or WPOL on Polygon).
+    pub wrapped_native: Address,
+    /// Default public RPC URL. Rate-limited; use a dedicated endpoint in production.
+    pub default_rpc_url: &'static str,
+    /// Average block time.
+    pub block_time: Duration,
+}
+
+impl ChainConfig {
+    /// Chain ID.
+    pub fn chain_id(&self) -> u64 {
+        self.chain.into()
+    }
+}
+
+/// Chains with a known Morpho deployment.
+pub const CHAIN_CONFIGS: &[ChainConfig] = &[
+    ChainConfig {
+        chain: NamedChain::Mainnet,
+        morpho: address!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"),
+        bundler3: Some(address!("6566194141eefa99Af43Bb5Aa71460Ca2Dc90245")),
+        public_allocator: Some(address!("fd32fA2ca22c76dD6E550706Ad913FC6CE91c75D")),
+        urd: Some(address!("330eefa8a787552DC5cAd3C3cA644844B1E61Ddb")),
+        wrapped_native: address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+        default_rpc_url: "https://eth.llamarpc.com",
+        block_time: Duration::from_secs(12),
+    },
+    ChainConfig {
+        chain: NamedChain::Base,
+        morpho: address!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"),
+        bundler3: Some(address!("6BFd8137e702540E7A42B74178A4a49Ba43920C4")),
+        public_allocator: Some(address!("A090dD1a701408Df1d4d0B85b716c87565f90467")),
+        urd: Some(address!("5400dBb270c956E8985184335A1C62AcA6Ce1333")),
+        wrapped_native: address!("4200000000000000000000000000000000000006"),
+        default_rpc_url: "https://mainnet.base.org",
+        block_time: Duration::from_secs(2),
+    },
+    ChainConfig {
+        chain: NamedChain::Arbitrum,
+        morpho: address!("6c247b1F6182318877311737BaC0844bAa518F5e"),
+        bundler3: Some(address!("1FA4431bC113D308beE1d46B0e98Cb805FB48C13")),
+        public_allocator: Some(address!("769583Af5e9D03589F159EbEC31Cc2c23E8C355E")),
+        urd: None,
+        wrapped_native: address!("82aF49447D8a07e3bd95BD0d56f35241523fBab1"),
+        default_rpc_url: "https://arb1.arbitrum.io/rpc",
+        block_time: Duration::from_millis(250),
+    },
+    ChainConfig {
+        chain: NamedChain::Optimism,
+        morpho: address!("ce95AfbB8EA029495c66020883F87aaE8864AF92"),
+        bundler3: Some(address!("FBCd3C258feB131D8E038F2A3a670A7bE0507C05")),
+        public_allocator: Some(address!("0d68a97324E602E02799CD83B42D337207B40658")),
+        urd: None,
+        wrapped_native: address!("4200000000000000000000000000000000000006"),
+        default_rpc_url: "https://mainnet.optimism.io",
+        block_time: Duration::from_secs(2),
+    },
+    ChainConfig {
+        chain: NamedChain::Polygon,
+        morpho: address!("1bF0c2541F820E775182832f06c0B7Fc27A25f67"),
+        bundler3: Some(address!("2d9C3A9E67c966C711208cc78b34fB9E9f8db589")),
+        public_allocator: Some(address!("fac15aff53ADd2ff80C2962127C434E8615Df0d3")),
+        urd: None,
+        wrapped_native: address!("0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270"),
+        default_rpc_url: "https://polygon-rpc.com",
+        block_time: Duration::from_secs(2),
+    },
+];
+
+/// Morpho deployment and network defaults for `chain`, if known.
+pub fn chain_config(chain: NamedChain) -> Option<&'static ChainConfig> {
+    CHAIN_CONFIGS.iter().find(|config| config.chain == chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SUPPORTED_CHAINS;
+
+    #[test]
+    fn test_chain_config_lookup() {
+        let mainnet = chain_config(NamedChain::Mainnet).unwrap();
+        assert_eq!(mainnet.chain_id(), 1);
+        assert_eq!(
+            mainnet.morpho,
+            "0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(mainnet.block_time, Duration::from_secs(12));
+
+        assert!(chain_config(NamedChain::Sepolia).is_none());
+    }
+
+    #[test]
+    fn test_chain_configs_are_unique_and_supported() {
+        for (i, config) in CHAIN_CONFIGS.iter().enumerate() {
+            assert!(SUPPORTED_CHAINS.contains(&config.chain));
+            assert!(!config.morpho.is_zero());
+            assert!(!config.wrapped_native.is_zero());
+            assert!(config.default_rpc_url.starts_with("https://"));
+            assert!(CHAIN_CONFIGS[i + 1..]
+                .iter()
+                .all(|other| other.chain != config.chain));
+        }
+    }
+}
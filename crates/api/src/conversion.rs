@@ -0,0 +1,79 @@
+//! Conversion of GraphQL vault responses and diagnostics for data that fails to parse.
+//!
+//! The API occasionally returns vaults with fields the client cannot parse (a malformed
+//! address, a non-numeric bigint, an unsupported chain). How the vault clients handle
+//! them is set with [`ClientConfig::with_conversion_mode`](crate::ClientConfig::with_conversion_mode):
+//!
+//! - [`ConversionMode::Lenient`] (default): the vault, or the nested item (allocation,
+//!   reward, allocator, adapter), is skipped and a [`ConversionError`] is recorded.
+//!   Queries such as [`VaultV1Client::get_vaults_with_diagnostics`](crate::VaultV1Client::get_vaults_with_diagnostics)
+//!   return the recorded errors alongside the vaults in a [`Converted`].
+//! - [`ConversionMode::Strict`]: the first conversion failure fails the query with
+//!   [`ApiError::Conversion`](crate::ApiError::Conversion).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::{ClientConfig, ConversionMode, VaultV1Client};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = VaultV1Client::new();
+//!     let converted = client.get_vaults_with_diagnostics(None).await?;
+//!     for skipped in &converted.diagnostics {
+//!         eprintln!("skipped: {skipped}");
+//!     }
+//!
+//!     let strict = VaultV1Client::with_config(
+//!         ClientConfig::new().with_conversion_mode(ConversionMode::Strict),
+//!     );
+//!     let vaults = strict.get_vaults(None).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How vault clients handle response data that fails to convert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConversionMode {
+    /// Skip items that fail to convert and record a [`ConversionError`] for each.
+    #[default]
+    Lenient,
+    /// Fail the query on the first item that fails to convert.
+    Strict,
+}
+
+/// A response field that could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[error("cannot convert {field} = {value:?} of vault {vault_address}")]
+pub struct ConversionError {
+    /// GraphQL path of the field, relative to the vault (e.g. `state.totalAssets`).
+    pub field: String,
+    /// Raw value of the field (for skipped nested items, the item's key or address).
+    pub value: String,
+    /// Address of the vault being converted, as returned by the API.
+    pub vault_address: String,
+}
+
+/// Query results together with the conversion errors recorded while building them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Converted<T> {
+    /// The converted results.
+    pub value: T,
+    /// Items skipped in [`ConversionMode::Lenient`], in response order.
+    pub diagnostics: Vec<ConversionError>,
+}
+
+impl<T> Converted<T> {
+    /// Returns true if nothing was skipped.
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Discard the diagnostics.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
@@ -0,0 +1,164 @@
+//! Gas cost estimates in USD for planned vault operations.
+//!
+//! [`CostEstimate`] combines an RPC gas estimate and gas price with the native token's USD
+//! price from the API, and relates the cost to the USD value of the amount moved, so
+//! callers can tell whether a deposit, withdrawal, or rebalance is worth its gas. The
+//! operations wrappers build them with `estimate_operation_cost()`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_api::PlannedOperation;
+//!
+//! let estimate = client
+//!     .vault_v1()?
+//!     .estimate_operation_cost(PlannedOperation::Deposit { vault, amount })
+//!     .await?;
+//! if estimate.pct_of_amount.is_some_and(|pct| pct > 0.01) {
+//!     println!("gas is {} of the deposit", estimate);
+//! }
+//! ```
+
+use std::fmt;
+
+use alloy_primitives::{Address, U256};
+
+/// Gas assumed for a deposit that cannot be simulated yet because its approval has not
+/// been sent.
+pub const DEFAULT_DEPOSIT_GAS: u64 = 300_000;
+
+/// A vault operation to estimate the cost of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlannedOperation {
+    /// Deposit `amount` of the vault's asset.
+    Deposit { vault: Address, amount: U256 },
+    /// Withdraw `amount` of the vault's asset.
+    Withdraw { vault: Address, amount: U256 },
+}
+
+impl PlannedOperation {
+    /// The vault the operation acts on.
+    pub fn vault(&self) -> Address {
+        match *self {
+            Self::Deposit { vault, .. } | Self::Withdraw { vault, .. } => vault,
+        }
+    }
+
+    /// The amount of the vault's asset the operation moves.
+    pub fn amount(&self) -> U256 {
+        match *self {
+            Self::Deposit { amount, .. } | Self::Withdraw { amount, .. } => amount,
+        }
+    }
+}
+
+/// Estimated gas cost of an operation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostEstimate {
+    /// Estimated gas, summed over every transaction the operation sends.
+    pub gas: u64,
+    /// Current gas price, in wei.
+    pub gas_price: u128,
+    /// USD price of the chain's native token, if the API knows it.
+    pub native_usd: Option<f64>,
+    /// Gas cost as a fraction of the USD value of the amount moved (`0.01` = 1%), if
+    /// both are known.
+    pub pct_of_amount: Option<f64>,
+}
+
+impl CostEstimate {
+    /// Build an estimate, relating its cost to `amount_usd` when known.
+    pub fn new(
+        gas: u64,
+        gas_price: u128,
+        native_usd: Option<f64>,
+        amount_usd: Option<f64>,
+    ) -> Self {
+        let mut estimate = Self {
+            gas,
+            gas_price,
+            native_usd,
+            pct_of_amount: None,
+        };
+        estimate.pct_of_amount = match (estimate.cost_usd(), amount_usd) {
+            (Some(cost), Some(amount)) if amount > 0.0 => Some(cost / amount),
+            _ => None,
+        };
+        estimate
+    }
+
+    /// Gas cost in wei.
+    pub fn cost_wei(&self) -> U256 {
+        U256::from(self.gas) * U256::from(self.gas_price)
+    }
+
+    /// Gas cost in native token units (e.g. ETH).
+    pub fn cost_native(&self) -> f64 {
+        self.gas as f64 * self.gas_price as f64 / 1e18
+    }
+
+    /// Gas cost in USD, if the native token price is known.
+    pub fn cost_usd(&self) -> Option<f64> {
+        Some(self.cost_native() * self.native_usd?)
+    }
+}
+
+impl fmt::Display for CostEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} gas at {:.2} gwei",
+            self.gas,
+            self.gas_price as f64 / 1e9
+        )?;
+        if let Some(cost) = self.cost_usd() {
+            write!(f, " (${:.2}", cost)?;
+            if let Some(pct) = self.pct_of_amount {
+                write!(f, ", {:.3}% of amount", pct * 100.0)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_estimate() {
+        // 200k gas at 10 gwei = 0.002 ETH = $6 at $3000
+        let estimate = CostEstimate::new(200_000, 10_000_000_000, Some(3000.0), Some(600.0));
+        assert_eq!(estimate.cost_wei(), U256::from(2_000_000_000_000_000u64));
+        assert!((estimate.cost_native() - 0.002).abs() < 1e-12);
+        assert!((estimate.cost_usd().unwrap() - 6.0).abs() < 1e-9);
+        assert!((estimate.pct_of_amount.unwrap() - 0.01).abs() < 1e-12);
+        assert_eq!(
+            estimate.to_string(),
+            "200000 gas at 10.00 gwei ($6.00, 1.000% of amount)"
+        );
+    }
+
+    #[test]
+    fn test_cost_estimate_without_prices() {
+        let estimate = CostEstimate::new(200_000, 10_000_000_000, None, Some(600.0));
+        assert!(estimate.cost_usd().is_none());
+        assert!(estimate.pct_of_amount.is_none());
+        assert_eq!(estimate.to_string(), "200000 gas at 10.00 gwei");
+
+        let estimate = CostEstimate::new(200_000, 10_000_000_000, Some(3000.0), Some(0.0));
+        assert!(estimate.pct_of_amount.is_none());
+    }
+
+    #[test]
+    fn test_planned_operation_accessors() {
+        let vault = Address::repeat_byte(0x01);
+        let op = PlannedOperation::Withdraw {
+            vault,
+            amount: U256::from(5u64),
+        };
+        assert_eq!(op.vault(), vault);
+        assert_eq!(op.amount(), U256::from(5u64));
+    }
+}
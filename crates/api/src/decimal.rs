@@ -0,0 +1,187 @@
+//! Decimal USD values for aggregation without floating-point drift.
+//!
+//! The API reports USD values as `f64`, and summing many of them in floating point
+//! accumulates rounding error (`0.1 + 0.2 != 0.3`). With the `decimal` feature, each
+//! value is converted to a [`Decimal`] at its shortest decimal representation, and
+//! totals are summed in decimal. Missing values count as zero in totals.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::decimal::total_net_value_usd;
+//! use morpho_rs_api::{MorphoApiClient, NamedChain};
+//! use alloy_primitives::address;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoApiClient::new();
+//!     let user = address!("0x0000000000000000000000000000000000000001");
+//!     let mut overviews = Vec::new();
+//!     for chain in [NamedChain::Mainnet, NamedChain::Base] {
+//!         overviews.push(client.get_user_account_overview(user, chain).await?);
+//!     }
+//!     println!("net value: ${}", total_net_value_usd(&overviews).round_dp(2));
+//!     Ok(())
+//! }
+//! ```
+
+pub use rust_decimal::Decimal;
+
+use crate::types::{
+    UserAccountOverview, UserMarketPosition, UserVaultPositions, UserVaultV1Position,
+    UserVaultV2Position, VaultV1, VaultV2,
+};
+
+/// Convert a USD value reported by the API to a [`Decimal`].
+///
+/// Uses the shortest decimal representation of the value (the one `f64` displays as),
+/// so `0.1` converts to exactly `0.1`. Returns `None` for NaN, infinite, and
+/// out-of-range values.
+pub fn usd_to_decimal(value: f64) -> Option<Decimal> {
+    value.to_string().parse().ok()
+}
+
+/// Sum USD values in decimal. Missing and unconvertible values count as zero.
+pub fn sum_usd<I>(values: I) -> Decimal
+where
+    I: IntoIterator<Item = Option<f64>>,
+{
+    values
+        .into_iter()
+        .filter_map(|v| v.and_then(usd_to_decimal))
+        .sum()
+}
+
+/// Net USD value of several account overviews, typically one per chain.
+///
+/// See [`UserAccountOverview::net_value_usd_decimal`].
+pub fn total_net_value_usd<'a, I>(overviews: I) -> Decimal
+where
+    I: IntoIterator<Item = &'a UserAccountOverview>,
+{
+    overviews
+        .into_iter()
+        .map(UserAccountOverview::net_value_usd_decimal)
+        .sum()
+}
+
+impl VaultV1 {
+    /// Total assets in USD as a [`Decimal`]. `None` if the vault has no state or price.
+    pub fn total_assets_usd_decimal(&self) -> Option<Decimal> {
+        self.state
+            .as_ref()?
+            .total_assets_usd
+            .and_then(usd_to_decimal)
+    }
+}
+
+impl VaultV2 {
+    /// Total assets in USD as a [`Decimal`]. `None` if the API has no price.
+    pub fn total_assets_usd_decimal(&self) -> Option<Decimal> {
+        self.total_assets_usd.and_then(usd_to_decimal)
+    }
+}
+
+impl UserVaultV1Position {
+    /// Position value in USD as a [`Decimal`].
+    pub fn assets_usd_decimal(&self) -> Option<Decimal> {
+        self.assets_usd.and_then(usd_to_decimal)
+    }
+
+    /// Profit and loss in USD as a [`Decimal`].
+    pub fn pnl_usd_decimal(&self) -> Option<Decimal> {
+        self.state.as_ref()?.pnl_usd.and_then(usd_to_decimal)
+    }
+}
+
+impl UserVaultV2Position {
+    /// Position value in USD as a [`Decimal`].
+    pub fn assets_usd_decimal(&self) -> Option<Decimal> {
+        self.assets_usd.and_then(usd_to_decimal)
+    }
+
+    /// Profit and loss in USD as a [`Decimal`].
+    pub fn pnl_usd_decimal(&self) -> Option<Decimal> {
+        self.pnl_usd.and_then(usd_to_decimal)
+    }
+}
+
+impl UserMarketPosition {
+    /// Supplied assets in USD as a [`Decimal`].
+    pub fn supply_assets_usd_decimal(&self) -> Option<Decimal> {
+        self.supply_assets_usd.and_then(usd_to_decimal)
+    }
+
+    /// Borrowed assets in USD as a [`Decimal`].
+    pub fn borrow_assets_usd_decimal(&self) -> Option<Decimal> {
+        self.borrow_assets_usd.and_then(usd_to_decimal)
+    }
+
+    /// Collateral in USD as a [`Decimal`].
+    pub fn collateral_usd_decimal(&self) -> Option<Decimal> {
+        self.collateral_usd.and_then(usd_to_decimal)
+    }
+}
+
+impl UserVaultPositions {
+    /// Value of all V1 and V2 vault positions in USD.
+    pub fn total_assets_usd_decimal(&self) -> Decimal {
+        vault_assets_usd(&self.vault_positions, &self.vault_v2_positions)
+    }
+
+    /// Profit and loss of all V1 and V2 vault positions in USD.
+    pub fn total_pnl_usd_decimal(&self) -> Decimal {
+        vault_pnl_usd(&self.vault_positions, &self.vault_v2_positions)
+    }
+}
+
+impl UserAccountOverview {
+    /// Value of all V1 and V2 vault positions in USD.
+    pub fn vault_assets_usd_decimal(&self) -> Decimal {
+        vault_assets_usd(&self.vault_positions, &self.vault_v2_positions)
+    }
+
+    /// Profit and loss of all V1 and V2 vault positions in USD.
+    pub fn vault_pnl_usd_decimal(&self) -> Decimal {
+        vault_pnl_usd(&self.vault_positions, &self.vault_v2_positions)
+    }
+
+    /// Net USD value of the account: vault positions, market supply, and collateral,
+    /// minus market borrows.
+    pub fn net_value_usd_decimal(&self) -> Decimal {
+        let markets = &self.market_positions;
+        self.vault_assets_usd_decimal()
+            + sum_usd(markets.iter().map(|p| p.supply_assets_usd))
+            + sum_usd(markets.iter().map(|p| p.collateral_usd))
+            - sum_usd(markets.iter().map(|p| p.borrow_assets_usd))
+    }
+}
+
+fn vault_assets_usd(v1: &[UserVaultV1Position], v2: &[UserVaultV2Position]) -> Decimal {
+    sum_usd(v1.iter().map(|p| p.assets_usd)) + sum_usd(v2.iter().map(|p| p.assets_usd))
+}
+
+fn vault_pnl_usd(v1: &[UserVaultV1Position], v2: &[UserVaultV2Position]) -> Decimal {
+    sum_usd(v1.iter().map(|p| p.state.as_ref().and_then(|s| s.pnl_usd)))
+        + sum_usd(v2.iter().map(|p| p.pnl_usd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_usd_has_no_float_drift() {
+        assert_eq!(sum_usd([Some(0.1), Some(0.2)]), Decimal::new(3, 1));
+        assert_eq!(
+            sum_usd([Some(1_000_000.01), None, Some(f64::NAN), Some(-0.01)]),
+            Decimal::new(1_000_000, 0)
+        );
+    }
+
+    #[test]
+    fn test_usd_to_decimal() {
+        assert_eq!(usd_to_decimal(1234.56), Some(Decimal::new(123_456, 2)));
+        assert_eq!(usd_to_decimal(f64::INFINITY), None);
+    }
+}
@@ -0,0 +1,203 @@
+//! Human-readable descriptions of vault transactions.
+//!
+//! [`TransactionDescription`] enriches a decoded [`CallDescription`] with what a person
+//! approving the transaction needs to see: the vault's name from the API, the token's
+//! symbol, the amount in whole tokens, and the spender of an approval. The operations
+//! wrappers build them with `describe()`, `describe_deposit()`, and `describe_withdraw()`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! for description in client.vault_v1()?.describe_deposit(vault, amount).await? {
+//!     println!("{}", description); // deposit 1000.5 USDC into Steakhouse USDC (0xBEEF…64CB)
+//! }
+//! ```
+
+use std::fmt;
+
+use alloy_primitives::{Address, U256};
+use morpho_rs_contracts::CallDescription;
+
+use crate::labels::format_labeled;
+use crate::units::TokenAmount;
+
+/// What a vault-related call moves, read from its signature and arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CallSubject {
+    /// `approve(spender, amount)` on the token itself
+    Approval { spender: Address },
+    /// An ERC-4626 call whose first argument is an amount of the vault's asset
+    Assets { vault: Address },
+    /// An ERC-4626 call whose first argument is an amount of the vault's shares
+    Shares { vault: Address },
+    /// Any other call, possibly to a vault
+    Other { vault: Address },
+}
+
+impl CallSubject {
+    /// Classifies a call by its signature.
+    pub(crate) fn of(call: &CallDescription) -> Self {
+        match call.signature {
+            "approve(address,uint256)" => match call.address_arg(0) {
+                Some(spender) => Self::Approval { spender },
+                None => Self::Other { vault: call.to },
+            },
+            "deposit(uint256,address)" | "withdraw(uint256,address,address)" => {
+                Self::Assets { vault: call.to }
+            }
+            "mint(uint256,address)" | "redeem(uint256,address,address)" => {
+                Self::Shares { vault: call.to }
+            }
+            _ => Self::Other { vault: call.to },
+        }
+    }
+
+    /// The vault the call acts on (the spender, for approvals).
+    pub(crate) fn vault(&self) -> Address {
+        match *self {
+            Self::Approval { spender } => spender,
+            Self::Assets { vault } | Self::Shares { vault } | Self::Other { vault } => vault,
+        }
+    }
+
+    /// Raw amount moved by the call, if it moves one.
+    pub(crate) fn amount(&self, call: &CallDescription) -> Option<U256> {
+        match self {
+            Self::Approval { .. } => call.uint_arg(1),
+            Self::Assets { .. } | Self::Shares { .. } => call.uint_arg(0),
+            Self::Other { .. } => None,
+        }
+    }
+}
+
+/// A vault transaction described for logs and approval prompts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionDescription {
+    /// The decoded call
+    pub call: CallDescription,
+    /// Vault the call acts on (the spender, for approvals)
+    pub vault: Address,
+    /// Vault name from the API (`None` if the vault is not indexed)
+    pub vault_name: Option<String>,
+    /// Token the amount is denominated in: the asset, or the vault's shares for
+    /// `mint`/`redeem`
+    pub token: Option<Address>,
+    /// Symbol of `token`
+    pub token_symbol: Option<String>,
+    /// Amount moved, with the token's decimals
+    pub amount: Option<TokenAmount>,
+    /// Spender of an approval
+    pub spender: Option<Address>,
+}
+
+impl TransactionDescription {
+    /// Function name, e.g. `deposit`.
+    pub fn function(&self) -> &str {
+        &self.call.function
+    }
+
+    /// Amount in whole tokens followed by the token symbol, e.g. `"1000.5 USDC"`.
+    pub fn formatted_amount(&self) -> Option<String> {
+        self.amount.map(|amount| match &self.token_symbol {
+            Some(symbol) => format!("{} {}", amount, symbol),
+            None => amount.to_string(),
+        })
+    }
+
+    /// Vault name and shortened address, e.g. `"Steakhouse USDC (0xBEEF…64CB)"`, or
+    /// the full address if the vault's name is unknown.
+    fn vault_label(&self) -> String {
+        format_labeled(self.vault_name.as_deref(), self.vault)
+    }
+}
+
+impl fmt::Display for TransactionDescription {
+    /// One line such as `deposit 1000.5 USDC into Steakhouse USDC (0xBEEF…64CB)`; calls
+    /// without an amount fall back to the decoded call.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(amount) = self.formatted_amount() else {
+            return write!(f, "{}", self.call);
+        };
+        let preposition = match self.function() {
+            "approve" => "for",
+            "deposit" | "mint" => "into",
+            "withdraw" | "redeem" => "from",
+            _ => "on",
+        };
+        write!(
+            f,
+            "{} {} {} {}",
+            self.function(),
+            amount,
+            preposition,
+            self.vault_label()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::dyn_abi::DynSolValue;
+
+    fn call(signature: &'static str, args: Vec<DynSolValue>) -> CallDescription {
+        CallDescription {
+            to: Address::repeat_byte(0x11),
+            function: signature[..signature.find('(').unwrap()].to_string(),
+            signature,
+            args,
+            value: U256::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_call_subject() {
+        let spender = Address::repeat_byte(0x22);
+        let approve = call(
+            "approve(address,uint256)",
+            vec![spender.into(), U256::from(5).into()],
+        );
+        let subject = CallSubject::of(&approve);
+        assert_eq!(subject, CallSubject::Approval { spender });
+        assert_eq!(subject.vault(), spender);
+        assert_eq!(subject.amount(&approve), Some(U256::from(5)));
+
+        let owner = Address::repeat_byte(0x33);
+        let redeem = call(
+            "redeem(uint256,address,address)",
+            vec![U256::from(7).into(), owner.into(), owner.into()],
+        );
+        let subject = CallSubject::of(&redeem);
+        assert_eq!(subject, CallSubject::Shares { vault: redeem.to });
+        assert_eq!(subject.amount(&redeem), Some(U256::from(7)));
+
+        let other = call("setFee(uint256)", vec![U256::from(1).into()]);
+        assert_eq!(CallSubject::of(&other).amount(&other), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let deposit = call(
+            "deposit(uint256,address)",
+            vec![U256::from(1_000_500_000u64).into(), Address::ZERO.into()],
+        );
+        let mut description = TransactionDescription {
+            vault: deposit.to,
+            call: deposit,
+            vault_name: Some("Steakhouse USDC".to_string()),
+            token: Some(Address::repeat_byte(0x44)),
+            token_symbol: Some("USDC".to_string()),
+            amount: Some(TokenAmount::new(U256::from(1_000_500_000u64), 6)),
+            spender: None,
+        };
+
+        assert_eq!(description.formatted_amount().unwrap(), "1000.5 USDC");
+        assert_eq!(
+            description.to_string(),
+            "deposit 1000.5 USDC into Steakhouse USDC (0x1111…1111)"
+        );
+
+        description.amount = None;
+        assert_eq!(description.to_string(), description.call.to_string());
+    }
+}
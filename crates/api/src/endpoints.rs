@@ -0,0 +1,303 @@
+//! Failover between GraphQL API endpoints with per-endpoint circuit breakers.
+//!
+//! A [`ClientConfig`](crate::ClientConfig) can list mirror endpoints (for example a
+//! self-hosted indexer) after the primary `api_url`. Each query goes to the first
+//! endpoint whose circuit is not open, and an attempt that fails is retried on the next
+//! such endpoint without waiting for the retry backoff. An endpoint that fails
+//! [`CircuitBreaker::failure_threshold`] times in a row (transport errors, 5xx
+//! responses, undecodable bodies, or GraphQL errors) is skipped for
+//! [`CircuitBreaker::cooldown_secs`], after which the next query tries it again. One
+//! success closes the circuit.
+//!
+//! When every circuit is open, the endpoint that will recover soonest is used rather
+//! than failing outright, so a single-endpoint configuration behaves as if there were
+//! no circuit breaker.
+//!
+//! Health is tracked in an [`EndpointHealth`] registry shared by every clone of the
+//! configuration, so the V1 and V2 clients of a
+//! [`MorphoApiClient`](crate::MorphoApiClient) see each other's failures.
+//! [`MorphoApiClient::check_endpoints`](crate::MorphoApiClient::check_endpoints) probes
+//! every endpoint and records the results.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::{ClientConfig, MorphoApiClient};
+//! use url::Url;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = ClientConfig::new()
+//!     .with_fallback_api_url(Url::parse("https://indexer.example.com/graphql")?)
+//!     .with_circuit_breaker(5, 60);
+//! let client = MorphoApiClient::with_config(config);
+//!
+//! for status in client.check_endpoints().await {
+//!     println!("{}: {:?} ({:?})", status.url, status.state, status.latency);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use url::Url;
+
+use crate::error::{ApiError, Result};
+use crate::platform::Instant;
+
+/// Default number of consecutive failures that opens an endpoint's circuit.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Default seconds an open circuit waits before the endpoint is tried again.
+pub const DEFAULT_COOLDOWN_SECS: u64 = 30;
+
+/// Longest cooldown, one year. Longer cooldowns are clamped to it, which keeps the
+/// circuit open for all practical purposes without overflowing the clock.
+pub const MAX_COOLDOWN_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// When to stop sending queries to a failing endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    /// Consecutive failures that open the circuit.
+    pub failure_threshold: u32,
+    /// Seconds the circuit stays open before the endpoint is tried again, at most
+    /// [`MAX_COOLDOWN_SECS`].
+    pub cooldown_secs: u64,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown_secs: DEFAULT_COOLDOWN_SECS,
+        }
+    }
+}
+
+/// Circuit state of an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointState {
+    /// Queries are sent to the endpoint.
+    Closed,
+    /// The endpoint failed repeatedly and is skipped until its cooldown ends.
+    Open,
+    /// The cooldown ended; the next query is a trial that closes or reopens the circuit.
+    HalfOpen,
+}
+
+/// Health of one endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointStatus {
+    /// Endpoint URL.
+    pub url: Url,
+    /// Circuit state.
+    pub state: EndpointState,
+    /// Failures since the last success.
+    pub consecutive_failures: u32,
+    /// Round-trip time of the last health check, if it succeeded.
+    pub latency: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+impl Health {
+    fn state(&self, now: Instant) -> EndpointState {
+        match self.open_until {
+            Some(until) if now < until => EndpointState::Open,
+            Some(_) => EndpointState::HalfOpen,
+            None => EndpointState::Closed,
+        }
+    }
+}
+
+/// Shared registry of endpoint health, keyed by URL.
+#[derive(Debug, Default)]
+pub struct EndpointHealth {
+    endpoints: Mutex<HashMap<String, Health>>,
+}
+
+impl EndpointHealth {
+    /// Create an empty registry (every endpoint starts closed).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The endpoint to send the next query to: the first one that is not open, or the
+    /// one whose cooldown ends soonest if all are open.
+    ///
+    /// Returns [`ApiError::NoApiEndpoints`] if `urls` is empty.
+    pub fn select<'a>(&self, urls: &'a [Url]) -> Result<&'a Url> {
+        let endpoints = self.lock();
+        let now = Instant::now();
+        urls.iter()
+            .find(|url| {
+                endpoints
+                    .get(url.as_str())
+                    .is_none_or(|h| h.state(now) != EndpointState::Open)
+            })
+            .or_else(|| {
+                urls.iter()
+                    .min_by_key(|url| endpoints.get(url.as_str()).and_then(|h| h.open_until))
+            })
+            .ok_or(ApiError::NoApiEndpoints)
+    }
+
+    /// Returns `true` unless the endpoint's circuit is open.
+    pub fn is_available(&self, url: &Url) -> bool {
+        self.lock()
+            .get(url.as_str())
+            .is_none_or(|h| h.state(Instant::now()) != EndpointState::Open)
+    }
+
+    /// Record a successful request, closing the endpoint's circuit.
+    pub fn record_success(&self, url: &Url) {
+        let mut endpoints = self.lock();
+        let health = endpoints.entry(url.to_string()).or_default();
+        health.consecutive_failures = 0;
+        health.open_until = None;
+    }
+
+    /// Record a failed request, opening the circuit once the threshold is reached.
+    pub fn record_failure(&self, url: &Url, breaker: &CircuitBreaker) {
+        let mut endpoints = self.lock();
+        let health = endpoints.entry(url.to_string()).or_default();
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+        if health.consecutive_failures >= breaker.failure_threshold.max(1) {
+            let cooldown = Duration::from_secs(breaker.cooldown_secs.min(MAX_COOLDOWN_SECS));
+            health.open_until = Some(Instant::now() + cooldown);
+        }
+    }
+
+    /// Current status of each endpoint, in the order given.
+    pub fn status(&self, urls: &[Url]) -> Vec<EndpointStatus> {
+        let endpoints = self.lock();
+        let now = Instant::now();
+        urls.iter()
+            .map(|url| {
+                let health = endpoints.get(url.as_str());
+                EndpointStatus {
+                    url: url.clone(),
+                    state: health.map_or(EndpointState::Closed, |h| h.state(now)),
+                    consecutive_failures: health.map_or(0, |h| h.consecutive_failures),
+                    latency: health.and_then(|h| h.latency),
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn record_latency(&self, url: &Url, latency: Option<Duration>) {
+        self.lock().entry(url.to_string()).or_default().latency = latency;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Health>> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls() -> Vec<Url> {
+        vec![
+            Url::parse("https://primary.example.com/graphql").unwrap(),
+            Url::parse("https://mirror.example.com/graphql").unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_selects_primary_while_closed() {
+        let health = EndpointHealth::new();
+        let urls = urls();
+        let breaker = CircuitBreaker::default();
+
+        health.record_failure(&urls[0], &breaker);
+        health.record_failure(&urls[0], &breaker);
+        assert_eq!(health.select(&urls).unwrap(), &urls[0]);
+        assert_eq!(health.status(&urls)[0].consecutive_failures, 2);
+    }
+
+    #[test]
+    fn test_long_cooldown_is_clamped() {
+        let health = EndpointHealth::new();
+        let urls = urls();
+        let breaker = CircuitBreaker {
+            failure_threshold: 1,
+            cooldown_secs: u64::MAX,
+        };
+
+        health.record_failure(&urls[0], &breaker);
+        assert_eq!(health.status(&urls)[0].state, EndpointState::Open);
+        assert_eq!(health.select(&urls).unwrap(), &urls[1]);
+    }
+
+    #[test]
+    fn test_fails_over_when_circuit_opens() {
+        let health = EndpointHealth::new();
+        let urls = urls();
+        let breaker = CircuitBreaker {
+            failure_threshold: 2,
+            cooldown_secs: 60,
+        };
+
+        health.record_failure(&urls[0], &breaker);
+        health.record_failure(&urls[0], &breaker);
+        assert_eq!(health.select(&urls).unwrap(), &urls[1]);
+        assert_eq!(health.status(&urls)[0].state, EndpointState::Open);
+
+        health.record_success(&urls[0]);
+        assert_eq!(health.select(&urls).unwrap(), &urls[0]);
+        assert_eq!(health.status(&urls)[0].state, EndpointState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_after_cooldown() {
+        let health = EndpointHealth::new();
+        let urls = urls();
+        let breaker = CircuitBreaker {
+            failure_threshold: 1,
+            cooldown_secs: 0,
+        };
+
+        health.record_failure(&urls[0], &breaker);
+        assert_eq!(health.status(&urls)[0].state, EndpointState::HalfOpen);
+        assert_eq!(health.select(&urls).unwrap(), &urls[0]);
+    }
+
+    #[test]
+    fn test_all_open_selects_soonest_recovery() {
+        let health = EndpointHealth::new();
+        let urls = urls();
+
+        health.record_failure(
+            &urls[0],
+            &CircuitBreaker {
+                failure_threshold: 1,
+                cooldown_secs: 600,
+            },
+        );
+        health.record_failure(
+            &urls[1],
+            &CircuitBreaker {
+                failure_threshold: 1,
+                cooldown_secs: 60,
+            },
+        );
+        assert_eq!(health.select(&urls).unwrap(), &urls[1]);
+    }
+
+    #[test]
+    fn test_select_without_endpoints() {
+        let health = EndpointHealth::new();
+        assert!(matches!(health.select(&[]), Err(ApiError::NoApiEndpoints)));
+    }
+}
@@ -10,6 +10,7 @@
 //! so callers only need to handle `ApiError` for unified error classification via
 //! [`error_category()`](ApiError::error_category).
 
+use alloy_primitives::Address;
 use thiserror::Error;
 
 /// High-level classification of errors across all Morpho crates.
@@ -31,6 +32,8 @@ pub enum ErrorCategory {
     Contract,
     /// Simulation engine error.
     Simulation,
+    /// Local storage (files, databases) error.
+    Storage,
 }
 
 /// Errors that can occur when using the Morpho API client.
@@ -48,10 +51,18 @@ pub enum ApiError {
     #[error("Failed to parse response: {0}")]
     Parse(String),
 
+    /// A response field failed to convert (in strict conversion mode).
+    #[error("Conversion failed: {0}")]
+    Conversion(#[from] crate::conversion::ConversionError),
+
     /// Vault not found.
     #[error("Vault not found: {address} on chain {chain_id}")]
     VaultNotFound { address: String, chain_id: i64 },
 
+    /// Market not found.
+    #[error("Market not found: {unique_key} on chain {chain_id}")]
+    MarketNotFound { unique_key: String, chain_id: i64 },
+
     /// Invalid address format.
     #[error("Invalid address format: {0}")]
     InvalidAddress(String),
@@ -60,6 +71,37 @@ pub enum ApiError {
     #[error("Invalid chain ID: {0}")]
     InvalidChainId(i64),
 
+    /// Chain ID does not fit in a GraphQL `Int` (signed 32-bit), so the API cannot be
+    /// queried for it.
+    #[error("Unsupported chain ID {chain_id}: exceeds the GraphQL Int range")]
+    UnsupportedChain { chain_id: u64 },
+
+    /// Invalid token amount.
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+
+    /// Arithmetic between amounts with different decimals.
+    #[error("Decimals mismatch: {left} vs {right}")]
+    DecimalsMismatch { left: u8, right: u8 },
+
+    /// Two vaults that must share an underlying asset do not.
+    #[error("Asset mismatch: vault {from_vault} holds {from_asset}, vault {to_vault} holds {to_asset}")]
+    AssetMismatch {
+        from_vault: Address,
+        from_asset: Address,
+        to_vault: Address,
+        to_asset: Address,
+    },
+
+    /// A native-currency deposit or withdrawal targeted a vault whose asset is not the
+    /// chain's wrapped native token.
+    #[error("Vault {vault} holds {asset}, not the wrapped native token {wrapped_native}")]
+    NotWrappedNativeVault {
+        vault: Address,
+        asset: Address,
+        wrapped_native: Address,
+    },
+
     /// Contract error.
     #[error("Contract error: {0}")]
     Contract(#[from] morpho_rs_contracts::ContractError),
@@ -72,6 +114,39 @@ pub enum ApiError {
     /// Transaction support not configured.
     #[error("Transaction support not configured: RPC URL and private key required")]
     TransactionNotConfigured,
+
+    /// No RPC URL configured for the requested chain.
+    #[error("Transaction support not configured for chain {0}")]
+    ChainNotConfigured(alloy_chains::NamedChain),
+
+    /// No GraphQL API endpoint to send a query to.
+    #[error("No API endpoints configured")]
+    NoApiEndpoints,
+
+    /// Failed to start the runtime backing the blocking client.
+    #[cfg(feature = "blocking")]
+    #[error("Failed to start runtime: {0}")]
+    Runtime(std::io::Error),
+
+    /// Signed-message authentication failed.
+    #[error("Authentication failed: {0}")]
+    Authentication(String),
+
+    /// The configured execution backend cannot be used for the operation.
+    #[error("Not supported by the execution backend: {0}")]
+    ExecutionNotSupported(String),
+
+    /// An operation with this idempotency key is in flight or was interrupted.
+    #[error("Operation {0} is pending; check the chain and release the key to retry")]
+    OperationPending(String),
+
+    /// An idempotency key was reused for an operation with different parameters.
+    #[error("Operation key {0} was already used with different parameters")]
+    OperationKeyReused(String),
+
+    /// Reading or writing local state (operation stores, audit logs, exports) failed.
+    #[error("Storage error: {0}")]
+    Storage(String),
 }
 
 impl ApiError {
@@ -91,7 +166,13 @@ impl ApiError {
     pub fn is_user_error(&self) -> bool {
         match self {
             ApiError::InvalidAddress(_) | ApiError::InvalidChainId(_) => true,
-            ApiError::TransactionNotConfigured => true,
+            ApiError::UnsupportedChain { .. } => true,
+            ApiError::InvalidAmount(_) | ApiError::DecimalsMismatch { .. } => true,
+            ApiError::AssetMismatch { .. } | ApiError::NotWrappedNativeVault { .. } => true,
+            ApiError::TransactionNotConfigured | ApiError::ChainNotConfigured(_) => true,
+            ApiError::NoApiEndpoints => true,
+            ApiError::ExecutionNotSupported(_) => true,
+            ApiError::OperationPending(_) | ApiError::OperationKeyReused(_) => true,
             ApiError::Contract(e) => e.is_user_error(),
             #[cfg(feature = "sim")]
             ApiError::Simulation(e) => e.is_user_error(),
@@ -105,13 +186,32 @@ impl ApiError {
     pub fn error_category(&self) -> ErrorCategory {
         match self {
             ApiError::Request(_) => ErrorCategory::Network,
-            ApiError::GraphQL(_) | ApiError::Parse(_) => ErrorCategory::Api,
-            ApiError::VaultNotFound { .. } => ErrorCategory::NotFound,
-            ApiError::InvalidAddress(_) | ApiError::InvalidChainId(_) => ErrorCategory::Validation,
-            ApiError::TransactionNotConfigured => ErrorCategory::Configuration,
+            ApiError::GraphQL(_)
+            | ApiError::Parse(_)
+            | ApiError::Conversion(_)
+            | ApiError::Authentication(_) => ErrorCategory::Api,
+            ApiError::VaultNotFound { .. } | ApiError::MarketNotFound { .. } => {
+                ErrorCategory::NotFound
+            }
+            ApiError::InvalidAddress(_)
+            | ApiError::InvalidChainId(_)
+            | ApiError::UnsupportedChain { .. }
+            | ApiError::InvalidAmount(_)
+            | ApiError::DecimalsMismatch { .. }
+            | ApiError::AssetMismatch { .. }
+            | ApiError::NotWrappedNativeVault { .. }
+            | ApiError::OperationPending(_)
+            | ApiError::OperationKeyReused(_) => ErrorCategory::Validation,
+            ApiError::TransactionNotConfigured
+            | ApiError::ChainNotConfigured(_)
+            | ApiError::NoApiEndpoints
+            | ApiError::ExecutionNotSupported(_) => ErrorCategory::Configuration,
+            #[cfg(feature = "blocking")]
+            ApiError::Runtime(_) => ErrorCategory::Configuration,
             ApiError::Contract(_) => ErrorCategory::Contract,
             #[cfg(feature = "sim")]
             ApiError::Simulation(_) => ErrorCategory::Simulation,
+            ApiError::Storage(_) => ErrorCategory::Storage,
         }
     }
 }
@@ -196,6 +296,45 @@ mod tests {
         assert!(ApiError::InvalidChainId(999).is_user_error());
     }
 
+    #[test]
+    fn test_is_user_error_unsupported_chain() {
+        let err = ApiError::UnsupportedChain {
+            chain_id: 11_297_108_109,
+        };
+        assert!(err.is_user_error());
+        assert!(!err.is_retryable());
+        assert_eq!(err.error_category(), ErrorCategory::Validation);
+    }
+
+    #[test]
+    fn test_is_user_error_invalid_amount() {
+        assert!(ApiError::InvalidAmount("1.2.3".to_string()).is_user_error());
+        assert!(ApiError::DecimalsMismatch { left: 6, right: 18 }.is_user_error());
+    }
+
+    #[test]
+    fn test_asset_mismatch_is_validation_error() {
+        let err = ApiError::AssetMismatch {
+            from_vault: Address::repeat_byte(0x01),
+            from_asset: Address::repeat_byte(0x02),
+            to_vault: Address::repeat_byte(0x03),
+            to_asset: Address::repeat_byte(0x04),
+        };
+        assert!(err.is_user_error());
+        assert_eq!(err.error_category(), ErrorCategory::Validation);
+    }
+
+    #[test]
+    fn test_not_wrapped_native_vault_is_validation_error() {
+        let err = ApiError::NotWrappedNativeVault {
+            vault: Address::repeat_byte(0x01),
+            asset: Address::repeat_byte(0x02),
+            wrapped_native: Address::repeat_byte(0x03),
+        };
+        assert!(err.is_user_error());
+        assert_eq!(err.error_category(), ErrorCategory::Validation);
+    }
+
     #[test]
     fn test_is_user_error_transaction_not_configured() {
         assert!(ApiError::TransactionNotConfigured.is_user_error());
@@ -246,6 +385,42 @@ mod tests {
             ApiError::Parse("err".to_string()).error_category(),
             ErrorCategory::Api
         );
+        assert_eq!(
+            ApiError::Authentication("err".to_string()).error_category(),
+            ErrorCategory::Api
+        );
+    }
+
+    #[test]
+    fn test_conversion_error() {
+        let err = ApiError::from(crate::conversion::ConversionError {
+            field: "state.totalAssets".to_string(),
+            value: "abc".to_string(),
+            vault_address: "0x123".to_string(),
+        });
+        assert_eq!(err.error_category(), ErrorCategory::Api);
+        assert!(!err.is_retryable());
+        assert_eq!(
+            err.to_string(),
+            "Conversion failed: cannot convert state.totalAssets = \"abc\" of vault 0x123"
+        );
+    }
+
+    #[test]
+    fn test_storage_error() {
+        let err = ApiError::Storage("operation store lock poisoned".to_string());
+        assert_eq!(err.error_category(), ErrorCategory::Storage);
+        assert!(!err.is_retryable());
+        assert!(!err.is_user_error());
+        assert_eq!(err.to_string(), "Storage error: operation store lock poisoned");
+    }
+
+    #[test]
+    fn test_authentication_error_not_retryable() {
+        let err = ApiError::Authentication("login rejected".to_string());
+        assert!(!err.is_retryable());
+        assert!(!err.is_user_error());
+        assert_eq!(err.to_string(), "Authentication failed: login rejected");
     }
 
     #[test]
@@ -255,6 +430,13 @@ mod tests {
             chain_id: 1,
         };
         assert_eq!(err.error_category(), ErrorCategory::NotFound);
+
+        let err = ApiError::MarketNotFound {
+            unique_key: "0xabc".to_string(),
+            chain_id: 1,
+        };
+        assert_eq!(err.error_category(), ErrorCategory::NotFound);
+        assert_eq!(err.to_string(), "Market not found: 0xabc on chain 1");
     }
 
     #[test]
@@ -267,6 +449,10 @@ mod tests {
             ApiError::InvalidChainId(999).error_category(),
             ErrorCategory::Validation
         );
+        assert_eq!(
+            ApiError::InvalidAmount("abc".to_string()).error_category(),
+            ErrorCategory::Validation
+        );
     }
 
     #[test]
@@ -275,6 +461,30 @@ mod tests {
             ApiError::TransactionNotConfigured.error_category(),
             ErrorCategory::Configuration
         );
+        assert_eq!(
+            ApiError::ChainNotConfigured(alloy_chains::NamedChain::Base).error_category(),
+            ErrorCategory::Configuration
+        );
+        assert_eq!(
+            ApiError::NoApiEndpoints.error_category(),
+            ErrorCategory::Configuration
+        );
+        let err = ApiError::ExecutionNotSupported("user operations".to_string());
+        assert!(err.is_user_error());
+        assert_eq!(err.error_category(), ErrorCategory::Configuration);
+    }
+
+    #[test]
+    fn test_operation_pending() {
+        let err = ApiError::OperationPending("rebalance-1".to_string());
+        assert!(err.is_user_error());
+        assert!(!err.is_retryable());
+        assert_eq!(err.error_category(), ErrorCategory::Validation);
+        assert!(err.to_string().contains("rebalance-1"));
+
+        let err = ApiError::OperationKeyReused("rebalance-1".to_string());
+        assert!(err.is_user_error());
+        assert_eq!(err.error_category(), ErrorCategory::Validation);
     }
 
     #[test]
@@ -0,0 +1,595 @@
+//! Pluggable execution backends for state-changing operations.
+//!
+//! Every transaction sent by [`MorphoClient`](crate::MorphoClient) operations goes
+//! through the [`ExecutionBackend`] set with
+//! [`MorphoClientConfig::with_execution_backend`](crate::MorphoClientConfig::with_execution_backend):
+//!
+//! - [`LiveBackend`] (the default) signs and sends transactions to the configured RPC
+//!   endpoint.
+//! - [`DryRunBackend`] sends nothing. Each call is simulated with `eth_simulateV1` on top
+//!   of the calls simulated before it, so an approval followed by a deposit behaves as it
+//!   would on-chain, and operations receive a receipt carrying the simulated status, gas,
+//!   and logs. Every call is [recorded](DryRunBackend::records) with its calldata and
+//!   result. With the `sim` feature, ERC4626 deposits and withdrawals into V1 vaults are
+//!   also run through a [`VaultSimulation`](morpho_rs_sim::VaultSimulation) hydrated from
+//!   on-chain state.
+//! - [`ForkBackend`] sends transactions, and makes every read, against a local fork
+//!   such as `anvil --fork-url <rpc>`, in place of the configured RPC endpoints.
+//!
+//! Together they let a strategy run end-to-end against real state without risking
+//! funds. Non-live backends do not support ERC-4337 user operations, and a
+//! [`ForkBackend`] disables per-chain operations, since both would reach real networks.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//!
+//! use morpho_rs_api::{DryRunBackend, MorphoClient, MorphoClientConfig};
+//!
+//! let dry_run = Arc::new(DryRunBackend::new());
+//! let client = MorphoClient::with_config(
+//!     MorphoClientConfig::new()
+//!         .with_rpc_url(rpc_url)
+//!         .with_private_key(private_key)
+//!         .with_execution_backend(dry_run.clone()),
+//! )?;
+//!
+//! // Approves and deposits in simulation only; the outcome is decoded from the
+//! // simulated Deposit event.
+//! let outcome = client.vault_v1()?.deposit_with_outcome(vault, amount).await?;
+//! for record in dry_run.records() {
+//!     println!("{}", record);
+//! }
+//! ```
+
+use std::fmt::{self, Debug};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use alloy::consensus::{Receipt, ReceiptEnvelope, ReceiptWithBloom};
+use alloy::primitives::{Address, Bloom, Bytes, B256};
+use alloy::providers::Provider;
+use alloy::rpc::types::simulate::{SimBlock, SimCallResult, SimulatePayload};
+use alloy::rpc::types::{Log, TransactionReceipt, TransactionRequest};
+use alloy::sol_types::SolCall;
+use morpho_rs_contracts::{
+    AccountCall, CallDescription, ContractError, HttpProvider, PreparedCall, SendOptions,
+    UserOperationClient,
+};
+
+use crate::error::Result;
+use crate::platform::BoxFuture;
+
+/// Default RPC URL of a local fork (`anvil`'s default).
+pub const DEFAULT_FORK_RPC_URL: &str = "http://127.0.0.1:8545";
+
+/// Executes the transactions prepared by client operations.
+///
+/// Implementations either send the transaction for real with [`Execution::send`] or
+/// handle the call themselves and return a receipt describing the result.
+pub trait ExecutionBackend: Debug + Send + Sync {
+    /// Execute a prepared transaction.
+    fn execute<'a>(&'a self, execution: Execution<'a>)
+        -> BoxFuture<'a, Result<TransactionReceipt>>;
+
+    /// RPC URL that replaces the configured ones, so that reads see the state
+    /// transactions are executed against.
+    fn rpc_url(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether ERC-4337 user operations, which are sent through a bundler rather than
+    /// this backend, may be used with it.
+    fn supports_user_operations(&self) -> bool {
+        false
+    }
+
+    /// Whether transactions executed by this backend reach the configured network.
+    ///
+    /// Idempotency keys are only recorded for live backends, so that simulated receipts
+    /// never stand in for a real operation.
+    fn is_live(&self) -> bool {
+        true
+    }
+
+    /// Execute a user operation. Only called on backends that
+    /// [`supports_user_operations`](Self::supports_user_operations); the default sends
+    /// it through the bundler.
+    fn execute_user_operation<'a>(
+        &'a self,
+        operation: UserOperation<'a>,
+    ) -> BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(operation.send())
+    }
+}
+
+/// A transaction prepared by a client operation, handed to an [`ExecutionBackend`].
+pub struct Execution<'a> {
+    /// Call to execute.
+    pub call: AccountCall,
+    /// Account the call is sent from.
+    pub from: Address,
+    /// Decoded function and arguments of the call.
+    pub description: CallDescription,
+    /// How the transaction is sent and confirmed by [`send`](Self::send).
+    pub options: SendOptions,
+    provider: &'a HttpProvider,
+    send: BoxFuture<'a, Result<TransactionReceipt>>,
+}
+
+impl<'a> Execution<'a> {
+    /// Prepare `call` for execution from the signer of `provider`, sent according to
+    /// `options`.
+    pub fn new<C: SolCall + Send + Sync + 'a>(
+        provider: &'a HttpProvider,
+        from: Address,
+        call: PreparedCall<'a, C>,
+        options: SendOptions,
+    ) -> Self {
+        Self {
+            call: AccountCall {
+                to: call.to(),
+                value: call.value(),
+                data: call.calldata(),
+            },
+            from,
+            description: call.describe(),
+            options,
+            provider,
+            send: Box::pin(async move { Ok(call.send_with(options).await?) }),
+        }
+    }
+
+    /// Provider of the client that prepared the call.
+    pub fn provider(&self) -> &'a HttpProvider {
+        self.provider
+    }
+
+    /// Sign and send the transaction, waiting for its receipt per the
+    /// [`SendOptions`] it was prepared with.
+    pub async fn send(self) -> Result<TransactionReceipt> {
+        self.send.await
+    }
+}
+
+impl Debug for Execution<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Execution")
+            .field("call", &self.call)
+            .field("from", &self.from)
+            .field("description", &self.description)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Calls batched into one ERC-4337 user operation by a client operation, handed to an
+/// [`ExecutionBackend`].
+pub struct UserOperation<'a> {
+    /// Calls executed atomically by the smart account, in order.
+    pub calls: &'a [AccountCall],
+    /// Smart account the calls are executed from.
+    pub account: Address,
+    provider: &'a HttpProvider,
+    client: &'a UserOperationClient,
+}
+
+impl<'a> UserOperation<'a> {
+    /// Prepare `calls` for execution through `client`. `provider` is the operations'
+    /// client on the same chain, for reads.
+    pub fn new(
+        client: &'a UserOperationClient,
+        provider: &'a HttpProvider,
+        calls: &'a [AccountCall],
+    ) -> Self {
+        Self {
+            calls,
+            account: client.account(),
+            provider,
+            client,
+        }
+    }
+
+    /// Provider of the client that prepared the calls.
+    pub fn provider(&self) -> &'a HttpProvider {
+        self.provider
+    }
+
+    /// Send the user operation through the bundler, waiting for the receipt of the
+    /// bundle transaction that included it.
+    pub async fn send(self) -> Result<TransactionReceipt> {
+        Ok(self.client.execute(self.calls).await?.receipt)
+    }
+}
+
+impl Debug for UserOperation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserOperation")
+            .field("calls", &self.calls)
+            .field("account", &self.account)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Send `call` from `from` through `backend`, according to `options`.
+pub(crate) async fn execute_call<C: SolCall + Send + Sync>(
+    backend: &dyn ExecutionBackend,
+    provider: &HttpProvider,
+    from: Address,
+    call: PreparedCall<'_, C>,
+    options: SendOptions,
+) -> Result<TransactionReceipt> {
+    backend.execute(Execution::new(provider, from, call, options)).await
+}
+
+/// [`ExecutionBackend`] that sends transactions to the configured RPC endpoint.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LiveBackend;
+
+impl ExecutionBackend for LiveBackend {
+    fn execute<'a>(
+        &'a self,
+        execution: Execution<'a>,
+    ) -> BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(execution.send())
+    }
+
+    fn supports_user_operations(&self) -> bool {
+        true
+    }
+}
+
+/// [`ExecutionBackend`] that sends transactions to a local fork instead of the
+/// configured RPC endpoint.
+///
+/// The fork must be of the chain operations are meant for, and the signer needs native
+/// currency on it for gas (e.g. `anvil_setBalance`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkBackend {
+    rpc_url: String,
+}
+
+impl ForkBackend {
+    /// Send to the fork served at `rpc_url`.
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+        }
+    }
+
+    /// Send to a fork served at [`DEFAULT_FORK_RPC_URL`].
+    pub fn local() -> Self {
+        Self::new(DEFAULT_FORK_RPC_URL)
+    }
+}
+
+impl ExecutionBackend for ForkBackend {
+    fn execute<'a>(
+        &'a self,
+        execution: Execution<'a>,
+    ) -> BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(execution.send())
+    }
+
+    fn rpc_url(&self) -> Option<&str> {
+        Some(&self.rpc_url)
+    }
+
+    fn is_live(&self) -> bool {
+        false
+    }
+}
+
+/// A call simulated by a [`DryRunBackend`].
+#[derive(Debug, Clone)]
+pub struct DryRunRecord {
+    /// Account the call was simulated from.
+    pub from: Address,
+    /// Target, value, and calldata of the call.
+    pub call: AccountCall,
+    /// Whether the call succeeded.
+    pub success: bool,
+    /// Gas used by the call.
+    pub gas_used: u64,
+    /// Data returned by the call.
+    pub return_data: Bytes,
+    /// Revert reason, if the call failed.
+    pub error: Option<String>,
+    /// Logs emitted by the call.
+    pub logs: Vec<Log>,
+    /// Effect of the call on its vault per the sim crate, for ERC4626 deposits and
+    /// withdrawals into V1 vaults.
+    #[cfg(feature = "sim")]
+    pub vault_impact: Option<VaultImpact>,
+}
+
+impl fmt::Display for DryRunRecord {
+    /// `dry run: 0x... -> 0x... (0x<calldata>): ok, 51234 gas`, followed by the vault
+    /// impact when simulated.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "dry run: {} -> {}", self.from, self.call.to)?;
+        if !self.call.value.is_zero() {
+            write!(f, " with {} wei", self.call.value)?;
+        }
+        write!(f, " ({})", self.call.data)?;
+        match &self.error {
+            Some(error) => write!(f, ": reverted ({})", error)?,
+            None if !self.success => write!(f, ": reverted")?,
+            None => write!(f, ": ok, {} gas", self.gas_used)?,
+        }
+        #[cfg(feature = "sim")]
+        if let Some(impact) = &self.vault_impact {
+            write!(f, "; {}", impact)?;
+        }
+        Ok(())
+    }
+}
+
+/// Effect of a deposit or withdrawal on a V1 vault, from a
+/// [`VaultSimulation`](morpho_rs_sim::VaultSimulation).
+#[cfg(feature = "sim")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VaultImpact {
+    /// Shares minted by a deposit or burned by a withdrawal.
+    pub shares: alloy::primitives::U256,
+    /// Vault net APY before the call.
+    pub net_apy_before: f64,
+    /// Vault net APY after the call.
+    pub net_apy_after: f64,
+}
+
+#[cfg(feature = "sim")]
+impl fmt::Display for VaultImpact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} shares, net APY {:.2}% -> {:.2}%",
+            self.shares,
+            self.net_apy_before * 100.0,
+            self.net_apy_after * 100.0
+        )
+    }
+}
+
+/// [`ExecutionBackend`] that simulates transactions instead of sending them.
+///
+/// Calls are simulated in order with `eth_simulateV1` against the latest block, each on
+/// top of the successful calls before it, so the RPC endpoint must support that method
+/// (geth, reth, and anvil do). Reads made by operations between calls (allowances,
+/// previews) still see on-chain state. A reverting call fails the operation like a
+/// failed gas estimate would, and is not kept for later simulations.
+#[derive(Debug, Default)]
+pub struct DryRunBackend {
+    history: Mutex<Vec<TransactionRequest>>,
+    records: Mutex<Vec<DryRunRecord>>,
+}
+
+impl DryRunBackend {
+    /// Create a dry run backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls simulated so far, in order.
+    pub fn records(&self) -> Vec<DryRunRecord> {
+        lock(&self.records).clone()
+    }
+
+    /// Forget previous calls, so the next one is simulated against on-chain state only.
+    pub fn reset(&self) {
+        lock(&self.history).clear();
+        lock(&self.records).clear();
+    }
+
+    async fn simulate(&self, execution: Execution<'_>) -> Result<TransactionReceipt> {
+        let Execution {
+            call,
+            from,
+            provider,
+            ..
+        } = execution;
+        let to = call.to;
+        let request = TransactionRequest::default()
+            .from(from)
+            .to(call.to)
+            .value(call.value)
+            .input(call.data.clone().into());
+
+        let calls = {
+            let mut calls = lock(&self.history).clone();
+            calls.push(request.clone());
+            calls
+        };
+        let payload = SimulatePayload::default().extend(SimBlock::default().extend_calls(calls));
+        let result = provider
+            .simulate(&payload)
+            .await
+            .map_err(|e| ContractError::RpcConnection(format!("Failed to simulate call: {}", e)))?
+            .into_iter()
+            .next()
+            .and_then(|block| block.calls.into_iter().last())
+            .ok_or_else(|| {
+                ContractError::TransactionFailed("Simulation returned no result".to_string())
+            })?;
+
+        let record = DryRunRecord {
+            from,
+            success: result.status,
+            gas_used: result.gas_used,
+            return_data: result.return_data.clone(),
+            error: result.error.as_ref().map(|e| e.message.clone()),
+            logs: result.logs.clone(),
+            #[cfg(feature = "sim")]
+            vault_impact: vault_impact(provider, &call).await,
+            call,
+        };
+        let error = (!record.success).then(|| record.error.clone().unwrap_or_default());
+        lock(&self.records).push(record);
+
+        if let Some(error) = error {
+            return Err(
+                ContractError::TransactionFailed(format!("Dry run reverted: {}", error)).into(),
+            );
+        }
+        lock(&self.history).push(request);
+        Ok(simulated_receipt(from, to, &result))
+    }
+}
+
+/// Lock a dry run's call list. The lists are only pushed to or cleared while locked, so
+/// they stay consistent even if another thread panicked while holding the lock.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl ExecutionBackend for DryRunBackend {
+    fn execute<'a>(
+        &'a self,
+        execution: Execution<'a>,
+    ) -> BoxFuture<'a, Result<TransactionReceipt>> {
+        Box::pin(self.simulate(execution))
+    }
+
+    fn is_live(&self) -> bool {
+        false
+    }
+}
+
+/// Receipt for a simulated call, with a zero transaction hash and no block.
+fn simulated_receipt(from: Address, to: Address, result: &SimCallResult) -> TransactionReceipt {
+    let receipt = Receipt {
+        status: result.status.into(),
+        cumulative_gas_used: result.gas_used,
+        logs: result.logs.clone(),
+    };
+    TransactionReceipt {
+        inner: ReceiptEnvelope::Eip1559(ReceiptWithBloom {
+            receipt,
+            logs_bloom: Bloom::ZERO,
+        }),
+        transaction_hash: B256::ZERO,
+        transaction_index: None,
+        block_hash: None,
+        block_number: None,
+        gas_used: result.gas_used,
+        effective_gas_price: 0,
+        blob_gas_used: None,
+        blob_gas_price: None,
+        from,
+        to: Some(to),
+        contract_address: None,
+    }
+}
+
+/// Simulate an ERC4626 deposit or withdrawal on a V1 vault hydrated from the latest
+/// block, or `None` if the call is neither or the target is not a V1 vault.
+#[cfg(feature = "sim")]
+async fn vault_impact(provider: &HttpProvider, call: &AccountCall) -> Option<VaultImpact> {
+    use alloy::rpc::types::{BlockId, BlockNumberOrTag};
+    use morpho_rs_contracts::erc4626::IERC4626;
+    use morpho_rs_sim::{RoundingDirection, VaultSimulation};
+
+    use crate::onchain::OnchainVaultSimulation;
+
+    let deposit = IERC4626::depositCall::abi_decode(&call.data).ok();
+    let withdraw = IERC4626::withdrawCall::abi_decode(&call.data).ok();
+    if deposit.is_none() && withdraw.is_none() {
+        return None;
+    }
+
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Latest)
+        .await
+        .ok()??;
+    let timestamp = block.header.timestamp;
+    let simulation =
+        VaultSimulation::from_onchain(provider, call.to, BlockId::number(block.header.number))
+            .await
+            .ok()?;
+    let net_apy_before = simulation.get_net_apy(timestamp).ok()?;
+    let (after, shares) = match (deposit, withdraw) {
+        (Some(deposit), _) => simulation
+            .simulate_deposit(deposit.assets, timestamp)
+            .ok()?,
+        (None, Some(withdraw)) => {
+            let shares = simulation
+                .vault
+                .to_shares(withdraw.assets, RoundingDirection::Up);
+            let (after, _) = simulation.simulate_withdraw(shares, timestamp).ok()?;
+            (after, shares)
+        }
+        (None, None) => return None,
+    };
+    Some(VaultImpact {
+        shares,
+        net_apy_before,
+        net_apy_after: after.get_net_apy(timestamp).ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U256;
+
+    #[test]
+    fn test_backend_defaults() {
+        assert_eq!(LiveBackend.rpc_url(), None);
+        assert!(LiveBackend.supports_user_operations());
+        assert!(LiveBackend.is_live());
+
+        let fork = ForkBackend::local();
+        assert_eq!(fork.rpc_url(), Some(DEFAULT_FORK_RPC_URL));
+        assert!(!fork.supports_user_operations());
+        assert!(!fork.is_live());
+
+        let dry_run = DryRunBackend::new();
+        assert_eq!(dry_run.rpc_url(), None);
+        assert!(!dry_run.supports_user_operations());
+        assert!(!dry_run.is_live());
+        assert!(dry_run.records().is_empty());
+    }
+
+    #[test]
+    fn test_simulated_receipt() {
+        let from = Address::repeat_byte(0x01);
+        let to = Address::repeat_byte(0x02);
+        let result = SimCallResult {
+            return_data: Bytes::new(),
+            logs: Vec::new(),
+            gas_used: 51_234,
+            status: true,
+            error: None,
+        };
+
+        let receipt = simulated_receipt(from, to, &result);
+        assert!(receipt.status());
+        assert_eq!(receipt.gas_used, 51_234);
+        assert_eq!(receipt.transaction_hash, B256::ZERO);
+        assert_eq!(receipt.to, Some(to));
+    }
+
+    #[test]
+    fn test_record_display() {
+        let record = DryRunRecord {
+            from: Address::repeat_byte(0x01),
+            call: AccountCall {
+                to: Address::repeat_byte(0x02),
+                value: U256::ZERO,
+                data: Bytes::from(vec![0x6e, 0x55, 0x3f, 0x65]),
+            },
+            success: false,
+            gas_used: 21_000,
+            return_data: Bytes::new(),
+            error: Some("execution reverted".to_string()),
+            logs: Vec::new(),
+            #[cfg(feature = "sim")]
+            vault_impact: None,
+        };
+
+        let display = record.to_string();
+        assert!(display.starts_with("dry run: 0x0101"));
+        assert!(display.contains("(0x6e553f65)"));
+        assert!(display.ends_with(": reverted (execution reverted)"));
+    }
+}
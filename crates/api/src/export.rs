@@ -0,0 +1,1088 @@
+//! Bulk export of vault and market snapshots.
+//!
+//! [`fetch_snapshot`] queries every V1 and V2 vault on the given chains (all
+//! [`SUPPORTED_CHAINS`] by default) and flattens them into an [`ExportSnapshot`] of
+//! [`VaultRow`]s and [`MarketRow`]s, ready to load into a database or dataframe without
+//! walking the typed structs.
+//!
+//! Tables have a stable schema, listed in [`VAULT_COLUMNS`] and [`MARKET_COLUMNS`]:
+//! columns are only ever appended, and [`SCHEMA_VERSION`] is bumped when they are.
+//! Token amounts (`U256`) are exported as decimal strings in the token's smallest unit so
+//! no precision is lost, and addresses as checksummed hex strings.
+//!
+//! Snapshots are written as:
+//!
+//! - `ExportFormat::Sqlite` (feature `sqlite`): a SQLite database. The `vaults` and
+//!   `markets` tables are created if missing and rows are appended in one transaction, so
+//!   exporting into the same database over time builds a history keyed by `snapshot_at`.
+//! - `ExportFormat::Parquet` (feature `parquet`): one table per file, Snappy-compressed,
+//!   with `INTEGER`, `REAL`, and `TEXT` columns stored as `Int64`, `Float64`, and `Utf8`.
+//! - [`ExportFormat::Sql`]: a SQLite script with the same tables, for loading with
+//!   `sqlite3 morpho.db < snapshot.sql` without the `sqlite` feature.
+//! - [`ExportFormat::Csv`]: one table per file, with a header row.
+//! - [`ExportFormat::Jsonl`]: one table per file, one JSON object per row.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::export::{fetch_snapshot, ExportFormat, ExportTable};
+//! use morpho_rs_api::{MorphoApiClient, NamedChain};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoApiClient::new();
+//!     let snapshot = fetch_snapshot(&client, &[NamedChain::Mainnet, NamedChain::Base]).await?;
+//!
+//!     snapshot.save("vaults.csv", ExportFormat::Csv, Some(ExportTable::Vaults))?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use alloy_chains::NamedChain;
+use serde::{Deserialize, Serialize};
+
+use crate::api::MorphoApi;
+use crate::platform::unix_now;
+use crate::error::{ApiError, Result};
+use crate::filters::{VaultFiltersV1, VaultFiltersV2};
+use crate::types::{
+    MarketStateV1, VaultAllocation, VaultV1, VaultV2, VaultVersion, SUPPORTED_CHAINS,
+};
+
+/// Version of the exported table schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// File format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// SQLite database, appended to if it exists.
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+    /// Apache Parquet.
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// SQLite script (`CREATE TABLE IF NOT EXISTS` and `INSERT`s in a transaction).
+    Sql,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// JSON Lines, one object per row.
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// Infer the format from a file extension (`.sql`, `.csv`, `.jsonl`/`.ndjson`, and
+    /// with their features `.db`/`.sqlite`/`.sqlite3` and `.parquet`).
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            #[cfg(feature = "sqlite")]
+            "db" | "sqlite" | "sqlite3" => Some(Self::Sqlite),
+            #[cfg(feature = "parquet")]
+            "parquet" => Some(Self::Parquet),
+            "sql" => Some(Self::Sql),
+            "csv" => Some(Self::Csv),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/// An exported table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTable {
+    /// One row per V1 and V2 vault.
+    Vaults,
+    /// One row per market allocated to by a V1 vault.
+    Markets,
+}
+
+impl ExportTable {
+    /// The table's name in SQL exports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportTable::Vaults => "vaults",
+            ExportTable::Markets => "markets",
+        }
+    }
+
+    /// The table's columns.
+    pub fn columns(&self) -> &'static [Column] {
+        match self {
+            ExportTable::Vaults => VAULT_COLUMNS,
+            ExportTable::Markets => MARKET_COLUMNS,
+        }
+    }
+
+    /// `CREATE TABLE IF NOT EXISTS` statement for the table.
+    fn create_sql(&self) -> String {
+        let definitions: Vec<String> = self
+            .columns()
+            .iter()
+            .map(|c| {
+                let null = if c.nullable { "" } else { " NOT NULL" };
+                format!("{} {}{}", c.name, c.ty.sql(), null)
+            })
+            .collect();
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} ({});",
+            self.name(),
+            definitions.join(", ")
+        )
+    }
+
+    /// Comma-separated column names.
+    fn column_list(&self) -> String {
+        let names: Vec<&str> = self.columns().iter().map(|c| c.name).collect();
+        names.join(", ")
+    }
+}
+
+/// SQL storage type of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// 64-bit integer (booleans are exported as 0/1).
+    Integer,
+    /// 64-bit float.
+    Real,
+    /// UTF-8 text, including addresses and `U256` amounts.
+    Text,
+}
+
+impl ColumnType {
+    fn sql(&self) -> &'static str {
+        match self {
+            ColumnType::Integer => "INTEGER",
+            ColumnType::Real => "REAL",
+            ColumnType::Text => "TEXT",
+        }
+    }
+}
+
+/// A column of an exported table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    /// Column name.
+    pub name: &'static str,
+    /// Storage type.
+    pub ty: ColumnType,
+    /// Whether the column may be null.
+    pub nullable: bool,
+}
+
+const fn column(name: &'static str, ty: ColumnType, nullable: bool) -> Column {
+    Column { name, ty, nullable }
+}
+
+/// Columns of the `vaults` table, in order.
+pub const VAULT_COLUMNS: &[Column] = &[
+    column("snapshot_at", ColumnType::Integer, false),
+    column("chain_id", ColumnType::Integer, false),
+    column("address", ColumnType::Text, false),
+    column("version", ColumnType::Text, false),
+    column("name", ColumnType::Text, false),
+    column("symbol", ColumnType::Text, false),
+    column("listed", ColumnType::Integer, false),
+    column("whitelisted", ColumnType::Integer, false),
+    column("asset_address", ColumnType::Text, false),
+    column("asset_symbol", ColumnType::Text, false),
+    column("asset_decimals", ColumnType::Integer, false),
+    column("asset_price_usd", ColumnType::Real, true),
+    column("curator", ColumnType::Text, true),
+    column("owner", ColumnType::Text, true),
+    column("total_assets", ColumnType::Text, true),
+    column("total_assets_usd", ColumnType::Real, true),
+    column("total_supply", ColumnType::Text, true),
+    column("apy", ColumnType::Real, true),
+    column("net_apy", ColumnType::Real, true),
+    column("performance_fee", ColumnType::Real, true),
+    column("management_fee", ColumnType::Real, true),
+    column("timelock", ColumnType::Integer, true),
+    column("liquidity", ColumnType::Text, true),
+    column("liquidity_usd", ColumnType::Real, true),
+    column("warnings", ColumnType::Integer, false),
+];
+
+/// Columns of the `markets` table, in order.
+pub const MARKET_COLUMNS: &[Column] = &[
+    column("snapshot_at", ColumnType::Integer, false),
+    column("chain_id", ColumnType::Integer, false),
+    column("market_id", ColumnType::Text, false),
+    column("loan_asset_address", ColumnType::Text, true),
+    column("loan_asset_symbol", ColumnType::Text, true),
+    column("collateral_asset_address", ColumnType::Text, true),
+    column("collateral_asset_symbol", ColumnType::Text, true),
+    column("lltv", ColumnType::Text, false),
+    column("total_supply_assets", ColumnType::Text, false),
+    column("total_borrow_assets", ColumnType::Text, false),
+    column("total_supply_shares", ColumnType::Text, false),
+    column("total_borrow_shares", ColumnType::Text, false),
+    column("liquidity", ColumnType::Text, false),
+    column("fee", ColumnType::Text, false),
+    column("rate_at_target", ColumnType::Text, true),
+    column("price", ColumnType::Text, true),
+    column("last_update", ColumnType::Integer, false),
+];
+
+/// A row of the `vaults` table.
+///
+/// Fields a vault version does not report (e.g. V1 management fee, V1 liquidity) are
+/// `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultRow {
+    /// Unix timestamp of the snapshot.
+    pub snapshot_at: u64,
+    /// Chain ID.
+    pub chain_id: u64,
+    /// The vault's contract address.
+    pub address: String,
+    /// `"V1"` or `"V2"`.
+    pub version: String,
+    /// The vault's name.
+    pub name: String,
+    /// The vault's symbol.
+    pub symbol: String,
+    /// Whether the vault is listed on the Morpho UI.
+    pub listed: bool,
+    /// Whether the vault is whitelisted.
+    pub whitelisted: bool,
+    /// The underlying asset's address.
+    pub asset_address: String,
+    /// The underlying asset's symbol.
+    pub asset_symbol: String,
+    /// The underlying asset's decimals.
+    pub asset_decimals: u8,
+    /// The underlying asset's price in USD.
+    pub asset_price_usd: Option<f64>,
+    /// The curator's address.
+    pub curator: Option<String>,
+    /// The owner's address.
+    pub owner: Option<String>,
+    /// Total assets, in the asset's smallest unit.
+    pub total_assets: Option<String>,
+    /// Total assets in USD.
+    pub total_assets_usd: Option<f64>,
+    /// Total supply of vault shares.
+    pub total_supply: Option<String>,
+    /// Gross APY (as a fraction).
+    pub apy: Option<f64>,
+    /// Net APY after fees (as a fraction).
+    pub net_apy: Option<f64>,
+    /// Performance fee (as a fraction).
+    pub performance_fee: Option<f64>,
+    /// Management fee (as a fraction, V2 only).
+    pub management_fee: Option<f64>,
+    /// Timelock in seconds (V1 only).
+    pub timelock: Option<u64>,
+    /// Liquidity available for withdrawal, in the asset's smallest unit (V2 only).
+    pub liquidity: Option<String>,
+    /// Liquidity in USD (V2 only).
+    pub liquidity_usd: Option<f64>,
+    /// Number of active warnings.
+    pub warnings: u32,
+}
+
+impl VaultRow {
+    /// Flatten a V1 vault.
+    pub fn from_v1(vault: &VaultV1, snapshot_at: u64) -> Self {
+        let state = vault.state.as_ref();
+        Self {
+            snapshot_at,
+            chain_id: u64::from(vault.chain),
+            address: vault.address.to_string(),
+            version: VaultVersion::V1.to_string(),
+            name: vault.name.clone(),
+            symbol: vault.symbol.clone(),
+            listed: vault.listed,
+            whitelisted: vault.whitelisted,
+            asset_address: vault.asset.address.to_string(),
+            asset_symbol: vault.asset.symbol.clone(),
+            asset_decimals: vault.asset.decimals,
+            asset_price_usd: vault.asset.price_usd,
+            curator: state.and_then(|s| s.curator).map(|a| a.to_string()),
+            owner: state.and_then(|s| s.owner).map(|a| a.to_string()),
+            total_assets: state.map(|s| s.total_assets.to_string()),
+            total_assets_usd: state.and_then(|s| s.total_assets_usd),
+            total_supply: state.map(|s| s.total_supply.to_string()),
+            apy: state.map(|s| s.apy),
+            net_apy: state.map(|s| s.net_apy),
+            performance_fee: state.map(|s| s.fee),
+            management_fee: None,
+            timelock: state.map(|s| s.timelock),
+            liquidity: None,
+            liquidity_usd: None,
+            warnings: vault.warnings.len() as u32,
+        }
+    }
+
+    /// Flatten a V2 vault.
+    pub fn from_v2(vault: &VaultV2, snapshot_at: u64) -> Self {
+        Self {
+            snapshot_at,
+            chain_id: u64::from(vault.chain),
+            address: vault.address.to_string(),
+            version: VaultVersion::V2.to_string(),
+            name: vault.name.clone(),
+            symbol: vault.symbol.clone(),
+            listed: vault.listed,
+            whitelisted: vault.whitelisted,
+            asset_address: vault.asset.address.to_string(),
+            asset_symbol: vault.asset.symbol.clone(),
+            asset_decimals: vault.asset.decimals,
+            asset_price_usd: vault.asset.price_usd,
+            curator: vault.curator.map(|a| a.to_string()),
+            owner: vault.owner.map(|a| a.to_string()),
+            total_assets: Some(vault.total_assets.to_string()),
+            total_assets_usd: vault.total_assets_usd,
+            total_supply: Some(vault.total_supply.to_string()),
+            apy: vault.apy,
+            net_apy: vault.net_apy,
+            performance_fee: vault.performance_fee,
+            management_fee: vault.management_fee,
+            timelock: None,
+            liquidity: Some(vault.liquidity.to_string()),
+            liquidity_usd: vault.liquidity_usd,
+            warnings: vault.warnings.len() as u32,
+        }
+    }
+
+    fn values(&self) -> Vec<Value> {
+        vec![
+            Value::Integer(self.snapshot_at as i64),
+            Value::Integer(self.chain_id as i64),
+            Value::text(&self.address),
+            Value::text(&self.version),
+            Value::text(&self.name),
+            Value::text(&self.symbol),
+            Value::Integer(self.listed as i64),
+            Value::Integer(self.whitelisted as i64),
+            Value::text(&self.asset_address),
+            Value::text(&self.asset_symbol),
+            Value::Integer(self.asset_decimals as i64),
+            Value::real(self.asset_price_usd),
+            Value::opt_text(self.curator.as_deref()),
+            Value::opt_text(self.owner.as_deref()),
+            Value::opt_text(self.total_assets.as_deref()),
+            Value::real(self.total_assets_usd),
+            Value::opt_text(self.total_supply.as_deref()),
+            Value::real(self.apy),
+            Value::real(self.net_apy),
+            Value::real(self.performance_fee),
+            Value::real(self.management_fee),
+            self.timelock
+                .map_or(Value::Null, |t| Value::Integer(t as i64)),
+            Value::opt_text(self.liquidity.as_deref()),
+            Value::real(self.liquidity_usd),
+            Value::Integer(self.warnings as i64),
+        ]
+    }
+}
+
+/// A row of the `markets` table.
+///
+/// WAD-scaled values (`lltv`, `fee`, `rate_at_target`) and the oracle `price` (scaled by
+/// 1e36) are exported unscaled, as on-chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketRow {
+    /// Unix timestamp of the snapshot.
+    pub snapshot_at: u64,
+    /// Chain ID.
+    pub chain_id: u64,
+    /// Market ID (32-byte hex).
+    pub market_id: String,
+    /// Loan asset address.
+    pub loan_asset_address: Option<String>,
+    /// Loan asset symbol.
+    pub loan_asset_symbol: Option<String>,
+    /// Collateral asset address.
+    pub collateral_asset_address: Option<String>,
+    /// Collateral asset symbol.
+    pub collateral_asset_symbol: Option<String>,
+    /// Liquidation LTV (WAD-scaled).
+    pub lltv: String,
+    /// Total loan assets supplied.
+    pub total_supply_assets: String,
+    /// Total loan assets borrowed.
+    pub total_borrow_assets: String,
+    /// Total supply shares.
+    pub total_supply_shares: String,
+    /// Total borrow shares.
+    pub total_borrow_shares: String,
+    /// Available liquidity.
+    pub liquidity: String,
+    /// Protocol fee (WAD-scaled).
+    pub fee: String,
+    /// Rate at target utilization for the Adaptive Curve IRM.
+    pub rate_at_target: Option<String>,
+    /// Oracle price (collateral/loan, scaled by 1e36).
+    pub price: Option<String>,
+    /// Timestamp of the last interest accrual.
+    pub last_update: u64,
+}
+
+impl MarketRow {
+    /// Flatten the market state of a V1 vault allocation, if present.
+    pub fn from_allocation(
+        allocation: &VaultAllocation,
+        chain: NamedChain,
+        snapshot_at: u64,
+    ) -> Option<Self> {
+        let market: &MarketStateV1 = allocation.market_state.as_ref()?;
+        Some(Self {
+            snapshot_at,
+            chain_id: u64::from(chain),
+            market_id: market.id.to_string(),
+            loan_asset_address: allocation.loan_asset_address.map(|a| a.to_string()),
+            loan_asset_symbol: allocation.loan_asset_symbol.clone(),
+            collateral_asset_address: allocation.collateral_asset_address.map(|a| a.to_string()),
+            collateral_asset_symbol: allocation.collateral_asset_symbol.clone(),
+            lltv: market.lltv.to_string(),
+            total_supply_assets: market.total_supply_assets.to_string(),
+            total_borrow_assets: market.total_borrow_assets.to_string(),
+            total_supply_shares: market.total_supply_shares.to_string(),
+            total_borrow_shares: market.total_borrow_shares.to_string(),
+            liquidity: market.liquidity.to_string(),
+            fee: market.fee.to_string(),
+            rate_at_target: market.rate_at_target.map(|r| r.to_string()),
+            price: market.price.map(|p| p.to_string()),
+            last_update: market.last_update,
+        })
+    }
+
+    fn values(&self) -> Vec<Value> {
+        vec![
+            Value::Integer(self.snapshot_at as i64),
+            Value::Integer(self.chain_id as i64),
+            Value::text(&self.market_id),
+            Value::opt_text(self.loan_asset_address.as_deref()),
+            Value::opt_text(self.loan_asset_symbol.as_deref()),
+            Value::opt_text(self.collateral_asset_address.as_deref()),
+            Value::opt_text(self.collateral_asset_symbol.as_deref()),
+            Value::text(&self.lltv),
+            Value::text(&self.total_supply_assets),
+            Value::text(&self.total_borrow_assets),
+            Value::text(&self.total_supply_shares),
+            Value::text(&self.total_borrow_shares),
+            Value::text(&self.liquidity),
+            Value::text(&self.fee),
+            Value::opt_text(self.rate_at_target.as_deref()),
+            Value::opt_text(self.price.as_deref()),
+            Value::Integer(self.last_update as i64),
+        ]
+    }
+}
+
+/// A cell value, rendered per format.
+enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+impl Value {
+    fn text(s: &str) -> Self {
+        Value::Text(s.to_string())
+    }
+
+    fn opt_text(s: Option<&str>) -> Self {
+        s.map_or(Value::Null, Value::text)
+    }
+
+    /// Non-finite floats are exported as null.
+    fn real(v: Option<f64>) -> Self {
+        match v {
+            Some(v) if v.is_finite() => Value::Real(v),
+            _ => Value::Null,
+        }
+    }
+
+    fn sql_literal(&self) -> String {
+        match self {
+            Value::Null => "NULL".to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Real(r) => format!("{r:?}"),
+            Value::Text(s) => format!("'{}'", s.replace('\'', "''")),
+        }
+    }
+
+    fn csv_field(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Integer(i) => i.to_string(),
+            Value::Real(r) => format!("{r:?}"),
+            Value::Text(s) if s.contains([',', '"', '\n', '\r']) => {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            }
+            Value::Text(s) => s.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl rusqlite::ToSql for Value {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        use rusqlite::types::{ToSqlOutput, ValueRef};
+
+        Ok(ToSqlOutput::Borrowed(match self {
+            Value::Null => ValueRef::Null,
+            Value::Integer(i) => ValueRef::Integer(*i),
+            Value::Real(r) => ValueRef::Real(*r),
+            Value::Text(s) => ValueRef::Text(s.as_bytes()),
+        }))
+    }
+}
+
+/// Vault and market rows captured at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportSnapshot {
+    /// Unix timestamp of the snapshot.
+    pub snapshot_at: u64,
+    /// One row per vault, V1 vaults first.
+    pub vaults: Vec<VaultRow>,
+    /// One row per market, deduplicated across vaults and ordered by chain and market ID.
+    pub markets: Vec<MarketRow>,
+}
+
+impl ExportSnapshot {
+    /// Flatten vaults into a snapshot taken at `snapshot_at`.
+    ///
+    /// Markets are collected from the V1 vaults' allocations.
+    pub fn from_vaults(v1: &[VaultV1], v2: &[VaultV2], snapshot_at: u64) -> Self {
+        let mut vaults: Vec<VaultRow> = v1
+            .iter()
+            .map(|v| VaultRow::from_v1(v, snapshot_at))
+            .collect();
+        vaults.extend(v2.iter().map(|v| VaultRow::from_v2(v, snapshot_at)));
+
+        let mut markets = BTreeMap::new();
+        for vault in v1 {
+            let allocations = vault.state.iter().flat_map(|s| &s.allocation);
+            for allocation in allocations {
+                if let Some(row) = MarketRow::from_allocation(allocation, vault.chain, snapshot_at)
+                {
+                    markets
+                        .entry((row.chain_id, row.market_id.clone()))
+                        .or_insert(row);
+                }
+            }
+        }
+
+        Self {
+            snapshot_at,
+            vaults,
+            markets: markets.into_values().collect(),
+        }
+    }
+
+    fn rows(&self, table: ExportTable) -> Vec<Vec<Value>> {
+        match table {
+            ExportTable::Vaults => self.vaults.iter().map(VaultRow::values).collect(),
+            ExportTable::Markets => self.markets.iter().map(MarketRow::values).collect(),
+        }
+    }
+
+    /// Write to the file at `path`.
+    ///
+    /// `table` selects the table to export. With `None`, SQLite databases and scripts
+    /// get both tables and other formats the `vaults` table.
+    pub fn save(
+        &self,
+        path: impl AsRef<Path>,
+        format: ExportFormat,
+        table: Option<ExportTable>,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        #[cfg(feature = "sqlite")]
+        if format == ExportFormat::Sqlite {
+            return match table {
+                Some(table) => self.write_sqlite_tables(path, &[table]),
+                None => self.write_sqlite(path),
+            };
+        }
+
+        let file = File::create(path).map_err(|e| {
+            ApiError::Storage(format!("failed to create export {}: {e}", path.display()))
+        })?;
+        let out = BufWriter::new(file);
+        match (format, table) {
+            (ExportFormat::Sql, None) => self.write_sql(out),
+            (format, table) => self.write(table.unwrap_or(ExportTable::Vaults), format, out),
+        }
+    }
+
+    /// Write one table in `format`.
+    ///
+    /// A [`ExportFormat::Sql`] script written this way only creates and fills `table`;
+    /// use [`ExportSnapshot::write_sql`] for both tables. SQLite databases cannot be
+    /// written to a stream; use [`ExportSnapshot::save`] instead.
+    pub fn write<W: Write + Send>(
+        &self,
+        table: ExportTable,
+        format: ExportFormat,
+        mut out: W,
+    ) -> Result<()> {
+        match format {
+            #[cfg(feature = "sqlite")]
+            ExportFormat::Sqlite => Err(ApiError::Parse(
+                "SQLite exports must be written to a path with ExportSnapshot::save".to_string(),
+            )),
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => self.write_parquet(table, out),
+            ExportFormat::Sql => {
+                let mut script = String::from("BEGIN TRANSACTION;\n");
+                self.push_sql(table, &mut script);
+                script.push_str("COMMIT;\n");
+                write_all(&mut out, &script)
+            }
+            ExportFormat::Csv => {
+                let mut csv = table
+                    .columns()
+                    .iter()
+                    .map(|c| c.name)
+                    .collect::<Vec<_>>()
+                    .join(",");
+                csv.push('\n');
+                for row in self.rows(table) {
+                    let cells: Vec<String> = row.iter().map(Value::csv_field).collect();
+                    csv.push_str(&cells.join(","));
+                    csv.push('\n');
+                }
+                write_all(&mut out, &csv)
+            }
+            ExportFormat::Jsonl => {
+                let lines = match table {
+                    ExportTable::Vaults => to_jsonl(&self.vaults)?,
+                    ExportTable::Markets => to_jsonl(&self.markets)?,
+                };
+                write_all(&mut out, &lines)
+            }
+        }
+    }
+
+    /// Write both tables as one SQLite script.
+    pub fn write_sql<W: Write>(&self, mut out: W) -> Result<()> {
+        let mut script = String::from("BEGIN TRANSACTION;\n");
+        self.push_sql(ExportTable::Vaults, &mut script);
+        self.push_sql(ExportTable::Markets, &mut script);
+        script.push_str("COMMIT;\n");
+        write_all(&mut out, &script)
+    }
+
+    fn push_sql(&self, table: ExportTable, script: &mut String) {
+        script.push_str(&table.create_sql());
+        script.push('\n');
+        let names = table.column_list();
+        for row in self.rows(table) {
+            let values: Vec<String> = row.iter().map(Value::sql_literal).collect();
+            script.push_str(&format!(
+                "INSERT INTO {} ({}) VALUES ({});\n",
+                table.name(),
+                names,
+                values.join(", ")
+            ));
+        }
+    }
+
+    /// Append both tables to the SQLite database at `path`, creating it and the tables
+    /// if missing.
+    #[cfg(feature = "sqlite")]
+    pub fn write_sqlite(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.write_sqlite_tables(path.as_ref(), &[ExportTable::Vaults, ExportTable::Markets])
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn write_sqlite_tables(&self, path: &Path, tables: &[ExportTable]) -> Result<()> {
+        let sqlite_err = |e: rusqlite::Error| {
+            ApiError::Storage(format!(
+                "failed to write SQLite export {}: {e}",
+                path.display()
+            ))
+        };
+        let mut conn = rusqlite::Connection::open(path).map_err(sqlite_err)?;
+        let tx = conn.transaction().map_err(sqlite_err)?;
+        for &table in tables {
+            tx.execute_batch(&table.create_sql()).map_err(sqlite_err)?;
+            let placeholders = vec!["?"; table.columns().len()].join(", ");
+            let mut insert = tx
+                .prepare(&format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    table.name(),
+                    table.column_list(),
+                    placeholders
+                ))
+                .map_err(sqlite_err)?;
+            for row in self.rows(table) {
+                insert
+                    .execute(rusqlite::params_from_iter(row.iter()))
+                    .map_err(sqlite_err)?;
+            }
+        }
+        tx.commit().map_err(sqlite_err)
+    }
+
+    #[cfg(feature = "parquet")]
+    fn write_parquet<W: Write + Send>(&self, table: ExportTable, out: W) -> Result<()> {
+        use std::sync::Arc;
+
+        use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+        use arrow_schema::{DataType, Field, Schema};
+        use parquet::arrow::ArrowWriter;
+        use parquet::basic::Compression;
+        use parquet::file::properties::WriterProperties;
+
+        let parquet_err = |e: &dyn std::fmt::Display| {
+            ApiError::Storage(format!("failed to write Parquet export: {e}"))
+        };
+        let columns = table.columns();
+        let rows = self.rows(table);
+
+        let fields: Vec<Field> = columns
+            .iter()
+            .map(|c| {
+                let data_type = match c.ty {
+                    ColumnType::Integer => DataType::Int64,
+                    ColumnType::Real => DataType::Float64,
+                    ColumnType::Text => DataType::Utf8,
+                };
+                Field::new(c.name, data_type, c.nullable)
+            })
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+
+        let arrays: Vec<ArrayRef> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| -> ArrayRef {
+                let cells = rows.iter().filter_map(|row| row.get(i));
+                match c.ty {
+                    ColumnType::Integer => Arc::new(
+                        cells
+                            .map(|v| match v {
+                                Value::Integer(n) => Some(*n),
+                                _ => None,
+                            })
+                            .collect::<Int64Array>(),
+                    ),
+                    ColumnType::Real => Arc::new(
+                        cells
+                            .map(|v| match v {
+                                Value::Real(r) => Some(*r),
+                                _ => None,
+                            })
+                            .collect::<Float64Array>(),
+                    ),
+                    ColumnType::Text => Arc::new(
+                        cells
+                            .map(|v| match v {
+                                Value::Text(s) => Some(s.as_str()),
+                                _ => None,
+                            })
+                            .collect::<StringArray>(),
+                    ),
+                }
+            })
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), arrays).map_err(|e| parquet_err(&e))?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(out, schema, Some(properties)).map_err(|e| parquet_err(&e))?;
+        writer.write(&batch).map_err(|e| parquet_err(&e))?;
+        writer.close().map_err(|e| parquet_err(&e))?;
+        Ok(())
+    }
+}
+
+fn to_jsonl<T: Serialize>(rows: &[T]) -> Result<String> {
+    let mut out = String::new();
+    for row in rows {
+        let line = serde_json::to_string(row)
+            .map_err(|e| ApiError::Storage(format!("failed to serialize export row: {e}")))?;
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn write_all<W: Write>(out: &mut W, contents: &str) -> Result<()> {
+    out.write_all(contents.as_bytes())
+        .and_then(|()| out.flush())
+        .map_err(|e| ApiError::Storage(format!("failed to write export: {e}")))
+}
+
+/// Fetch every V1 and V2 vault on `chains` (all [`SUPPORTED_CHAINS`] if empty) and
+/// flatten them into a snapshot taken now.
+pub async fn fetch_snapshot<A: MorphoApi>(
+    api: &A,
+    chains: &[NamedChain],
+) -> Result<ExportSnapshot> {
+    let chains = if chains.is_empty() {
+        SUPPORTED_CHAINS
+    } else {
+        chains
+    };
+    let (v1, v2) = futures::try_join!(
+        api.get_vaults_v1(Some(VaultFiltersV1::new().chains(chains.iter().copied()))),
+        api.get_vaults_v2(Some(VaultFiltersV2::new().chains(chains.iter().copied()))),
+    )?;
+    Ok(ExportSnapshot::from_vaults(&v1, &v2, unix_now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, B256, U256};
+
+    use crate::types::{Asset, VaultStateV1};
+
+    fn asset() -> Asset {
+        Asset {
+            address: Address::repeat_byte(0xAA),
+            symbol: "USDC".to_string(),
+            name: None,
+            decimals: 6,
+            price_usd: Some(1.0),
+        }
+    }
+
+    fn allocation(market: u8) -> VaultAllocation {
+        VaultAllocation {
+            market_key: B256::repeat_byte(market).to_string(),
+            loan_asset_symbol: Some("USDC".to_string()),
+            loan_asset_address: Some(Address::repeat_byte(0xAA)),
+            collateral_asset_symbol: Some("WETH, wrapped".to_string()),
+            collateral_asset_address: None,
+            supply_assets: U256::from(100),
+            supply_assets_usd: None,
+            supply_cap: U256::from(1000),
+            enabled: true,
+            supply_queue_index: Some(0),
+            withdraw_queue_index: Some(0),
+            market_state: Some(MarketStateV1 {
+                id: B256::repeat_byte(market),
+                total_supply_assets: U256::from(1000),
+                total_borrow_assets: U256::from(800),
+                total_supply_shares: U256::from(1000),
+                total_borrow_shares: U256::from(800),
+                last_update: 1_700_000_000,
+                fee: U256::ZERO,
+                rate_at_target: None,
+                price: Some(U256::from(10).pow(U256::from(36))),
+                lltv: U256::from(860_000_000_000_000_000u64),
+                liquidity: U256::from(200),
+                realized_bad_debt: None,
+            }),
+        }
+    }
+
+    fn vault(byte: u8, markets: &[u8]) -> VaultV1 {
+        VaultV1 {
+            address: Address::repeat_byte(byte),
+            name: "Steakhouse's USDC".to_string(),
+            symbol: "steakUSDC".to_string(),
+            chain: NamedChain::Mainnet,
+            listed: true,
+            featured: false,
+            whitelisted: true,
+            asset: asset(),
+            state: Some(VaultStateV1 {
+                curator: Some(Address::repeat_byte(0xC0)),
+                owner: None,
+                guardian: None,
+                total_assets: U256::from(10).pow(U256::from(30)),
+                total_assets_usd: Some(1e24),
+                total_supply: U256::from(1000),
+                fee: 0.1,
+                timelock: 86_400,
+                apy: 0.05,
+                net_apy: f64::NAN,
+                share_price: U256::from(1),
+                allocation: markets.iter().map(|&m| allocation(m)).collect(),
+                rewards: Vec::new(),
+            }),
+            allocators: Vec::new(),
+            warnings: Vec::new(),
+            public_allocator_config: None,
+        }
+    }
+
+    fn snapshot() -> ExportSnapshot {
+        ExportSnapshot::from_vaults(&[vault(1, &[2, 1]), vault(2, &[1])], &[], 1_700_000_000)
+    }
+
+    #[test]
+    fn test_columns_match_rows() {
+        let snapshot = snapshot();
+        assert_eq!(snapshot.vaults[0].values().len(), VAULT_COLUMNS.len());
+        assert_eq!(snapshot.markets[0].values().len(), MARKET_COLUMNS.len());
+    }
+
+    #[test]
+    fn test_markets_deduplicated_and_ordered() {
+        let snapshot = snapshot();
+        assert_eq!(snapshot.vaults.len(), 2);
+        let ids: Vec<_> = snapshot
+            .markets
+            .iter()
+            .map(|m| m.market_id.clone())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                B256::repeat_byte(1).to_string(),
+                B256::repeat_byte(2).to_string()
+            ]
+        );
+        assert_eq!(
+            snapshot.markets[0].price.as_deref(),
+            Some("1000000000000000000000000000000000000")
+        );
+    }
+
+    #[test]
+    fn test_csv_quotes_and_nulls() {
+        let mut out = Vec::new();
+        snapshot()
+            .write(ExportTable::Markets, ExportFormat::Csv, &mut out)
+            .unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("snapshot_at,chain_id,market_id,"));
+        let row = lines.next().unwrap();
+        assert!(row.contains(",USDC,,\"WETH, wrapped\",860000000000000000,"));
+        assert_eq!(csv.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_sql_escapes_text_and_exports_nan_as_null() {
+        let mut out = Vec::new();
+        snapshot().write_sql(&mut out).unwrap();
+        let sql = String::from_utf8(out).unwrap();
+        assert!(sql.starts_with("BEGIN TRANSACTION;\n"));
+        assert!(sql.ends_with("COMMIT;\n"));
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS vaults (snapshot_at INTEGER NOT NULL,"));
+        assert!(sql.contains("'Steakhouse''s USDC'"));
+        assert!(sql.contains("1000000000000000000000000000000"));
+        assert!(sql.contains("0.05, NULL, 0.1, NULL, 86400"));
+        assert_eq!(sql.matches("INSERT INTO markets").count(), 2);
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let snapshot = snapshot();
+        let mut out = Vec::new();
+        snapshot
+            .write(ExportTable::Vaults, ExportFormat::Jsonl, &mut out)
+            .unwrap();
+        let rows: Vec<VaultRow> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].total_assets, snapshot.vaults[0].total_assets);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_appends_snapshots() {
+        let path = std::env::temp_dir().join(format!("morpho-rs-export-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let snapshot = snapshot();
+        snapshot.save(&path, ExportFormat::Sqlite, None).unwrap();
+        snapshot.write_sqlite(&path).unwrap();
+
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        let (vaults, null_apys): (i64, i64) = conn
+            .query_row(
+                "SELECT COUNT(*), SUM(net_apy IS NULL) FROM vaults",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(vaults, 4);
+        assert_eq!(null_apys, 4);
+        let name: String = conn
+            .query_row("SELECT name FROM vaults LIMIT 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(name, "Steakhouse's USDC");
+        let markets: i64 = conn
+            .query_row("SELECT COUNT(*) FROM markets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(markets, 4);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use arrow_array::{Array, Float64Array, StringArray};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let path =
+            std::env::temp_dir().join(format!("morpho-rs-export-{}.parquet", std::process::id()));
+        snapshot().save(&path, ExportFormat::Parquet, None).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(|b| b.unwrap()).collect();
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), VAULT_COLUMNS.len());
+        let total_assets = batch
+            .column_by_name("total_assets")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(total_assets.value(0), "1000000000000000000000000000000");
+        let net_apy = batch
+            .column_by_name("net_apy")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert!(net_apy.is_null(0));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ExportFormat::from_path("out/vaults.SQL"),
+            Some(ExportFormat::Sql)
+        );
+        assert_eq!(
+            ExportFormat::from_path("vaults.csv"),
+            Some(ExportFormat::Csv)
+        );
+        assert_eq!(
+            ExportFormat::from_path("vaults.ndjson"),
+            Some(ExportFormat::Jsonl)
+        );
+        assert_eq!(ExportFormat::from_path("vaults"), None);
+        #[cfg(feature = "sqlite")]
+        assert_eq!(
+            ExportFormat::from_path("morpho.db"),
+            Some(ExportFormat::Sqlite)
+        );
+        #[cfg(feature = "parquet")]
+        assert_eq!(
+            ExportFormat::from_path("vaults.parquet"),
+            Some(ExportFormat::Parquet)
+        );
+    }
+}
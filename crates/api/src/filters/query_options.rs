@@ -1,7 +1,8 @@
 //! Query options for vault queries, combining filters, ordering, and pagination.
 
 use crate::filters::{VaultFiltersV1, VaultFiltersV2};
-use crate::types::ordering::{OrderDirection, VaultOrderByV1, VaultOrderByV2};
+use crate::types::ordering::{OrderDirection, VaultOrderByV1, VaultOrderByV2, VaultRankByV2};
+use crate::types::VaultV2;
 
 /// Options for V1 vault queries.
 ///
@@ -113,6 +114,8 @@ pub struct VaultQueryOptionsV2 {
     pub asset_symbols: Option<Vec<String>>,
     /// Curator addresses to filter by (client-side, API doesn't support this).
     pub curator_addresses: Option<Vec<String>>,
+    /// Value to rank vaults by client-side, after filtering.
+    pub rank_by: Option<VaultRankByV2>,
 }
 
 impl VaultQueryOptionsV2 {
@@ -184,6 +187,15 @@ impl VaultQueryOptionsV2 {
         self
     }
 
+    /// Rank vaults client-side by a computed value, in `order_direction`.
+    ///
+    /// Queries with a ranking fetch every page before applying the limit, so the limit
+    /// returns the top vaults among all that match the filters.
+    pub fn rank_by(mut self, rank_by: VaultRankByV2) -> Self {
+        self.rank_by = Some(rank_by);
+        self
+    }
+
     /// Create options for fetching top vaults by APY.
     pub fn top_by_apy(limit: i64) -> Self {
         Self::new()
@@ -220,6 +232,35 @@ impl VaultQueryOptionsV2 {
     pub fn has_asset_filter(&self) -> bool {
         self.has_client_filter()
     }
+
+    /// Keep only the vaults matching the client-side filters.
+    pub(crate) fn retain_matching(&self, vaults: &mut Vec<VaultV2>) {
+        if let Some(ref symbols) = self.asset_symbols {
+            vaults.retain(|v| {
+                symbols
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&v.asset.symbol))
+            });
+        }
+        if let Some(ref addresses) = self.asset_addresses {
+            vaults.retain(|v| {
+                addresses
+                    .iter()
+                    .any(|a| v.asset.address.to_string().eq_ignore_ascii_case(a))
+            });
+        }
+        if let Some(ref curators) = self.curator_addresses {
+            vaults.retain(|v| {
+                v.curator
+                    .map(|c| {
+                        curators
+                            .iter()
+                            .any(|a| c.to_string().eq_ignore_ascii_case(a))
+                    })
+                    .unwrap_or(false)
+            });
+        }
+    }
 }
 
 #[cfg(test)]
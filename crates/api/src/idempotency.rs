@@ -0,0 +1,562 @@
+//! Idempotency keys for vault operations.
+//!
+//! A bot that crashes between sending a deposit and recording it would deposit twice on
+//! restart. Tagging the operation with a key via
+//! [`VaultV1Operations::with_operation_key`](crate::VaultV1Operations::with_operation_key)
+//! makes re-invoking it safe: the key is claimed in an [`OperationStore`] before anything
+//! is sent, and the receipt is stored when the operation completes.
+//!
+//! Re-invoking an operation with the same key then:
+//! - returns the stored receipt if the operation completed, without sending anything
+//! - fails with [`ApiError::OperationPending`] if the operation is still in flight, or
+//!   failed or was interrupted (revert, receipt timeout, crash, RPC connection loss)
+//!   after its first transaction was submitted, since that transaction may already be
+//!   on chain; check the chain and [`OperationStore::release`] the key to retry
+//! - runs the operation again if it previously failed before sending anything
+//!   (validation, failed reads), since the key is released on those
+//! - fails with [`ApiError::OperationKeyReused`] if the key was claimed by an operation
+//!   with different parameters (another vault or amount)
+//!
+//! Operations sending several transactions record the receipts of their earlier legs
+//! under derived keys (`<key>/<leg>`, e.g. `<key>/redeem` for a migration), so that a
+//! replay can return the whole outcome.
+//!
+//! Keys are only recorded for [live](crate::ExecutionBackend::is_live) backends, so a
+//! dry run does not complete the key of the live run that follows it.
+//!
+//! The client defaults to an [`InMemoryOperationStore`], which only guards against
+//! duplicates within one process. Use a [`FileOperationStore`] (or your own store backed
+//! by a database) to survive restarts.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use alloy_primitives::{address, U256};
+//! use morpho_rs_api::{FileOperationStore, MorphoClient, MorphoClientConfig};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let config = MorphoClientConfig::new()
+//!         .with_rpc_url("https://eth.llamarpc.com")
+//!         .with_private_key("0x...")
+//!         .with_operation_store(Arc::new(FileOperationStore::new("operations.json")));
+//!     let client = MorphoClient::with_config(config)?;
+//!
+//!     let vault = address!("BEEF01735c132Ada46AA9aA4c54623cAA92A64CB");
+//!     // Safe to re-run after a crash: completes at most once
+//!     let receipt = client
+//!         .vault_v1()?
+//!         .with_operation_key("rebalance-2026-10-16")
+//!         .deposit(vault, U256::from(1_000_000))
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use alloy::primitives::{keccak256, B256};
+use alloy::rpc::types::TransactionReceipt;
+use futures::future;
+use morpho_rs_contracts::ContractError;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ApiError, Result};
+use crate::platform::BoxFuture;
+
+/// State of an operation claimed in an [`OperationStore`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum OperationState {
+    /// The operation was started and has not completed.
+    Pending {
+        /// Hash of the operation's parameters.
+        params: B256,
+    },
+    /// The operation completed with this receipt.
+    Completed {
+        /// Hash of the operation's parameters.
+        params: B256,
+        /// Receipt of the operation's final transaction.
+        receipt: Box<TransactionReceipt>,
+    },
+}
+
+impl OperationState {
+    /// Hash of the parameters of the operation that claimed the key.
+    pub fn params(&self) -> B256 {
+        match self {
+            OperationState::Pending { params } | OperationState::Completed { params, .. } => {
+                *params
+            }
+        }
+    }
+}
+
+/// Persists the state of keyed operations.
+///
+/// Implementations must make [`claim`](Self::claim) atomic: of several concurrent
+/// claims of one key, only one may return `None`.
+pub trait OperationStore: Debug + Send + Sync {
+    /// Mark `key` as [`OperationState::Pending`] with the parameter hash `params` if it
+    /// is unknown.
+    ///
+    /// Returns `None` if the key was claimed, or its existing state otherwise.
+    fn claim<'a>(
+        &'a self,
+        key: &'a str,
+        params: B256,
+    ) -> BoxFuture<'a, Result<Option<OperationState>>>;
+
+    /// Record that the operation under `key` completed with `receipt`.
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        params: B256,
+        receipt: &'a TransactionReceipt,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    /// Forget `key`, so the operation can run again.
+    fn release<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// Current state of `key`, if known.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<OperationState>>>;
+}
+
+/// Leg under which a non-atomic migration records its redemption.
+pub(crate) const MIGRATION_REDEEM_LEG: &str = "redeem";
+
+/// Key under which the `leg` of the operation keyed `key` is recorded.
+pub(crate) fn leg_key(key: &str, leg: &str) -> String {
+    format!("{key}/{leg}")
+}
+
+/// Run `operation` under `key`, per the [module documentation](self).
+///
+/// `params` describes the operation's parameters, and `sent` must be set by the
+/// operation before it submits its first transaction.
+pub(crate) async fn run_keyed<F>(
+    store: &dyn OperationStore,
+    key: &str,
+    params: &str,
+    sent: &AtomicBool,
+    operation: F,
+) -> Result<TransactionReceipt>
+where
+    F: Future<Output = Result<TransactionReceipt>>,
+{
+    let params = keccak256(params.as_bytes());
+    match store.claim(key, params).await? {
+        None => {}
+        Some(state) if state.params() != params => {
+            return Err(ApiError::OperationKeyReused(key.to_string()))
+        }
+        Some(OperationState::Completed { receipt, .. }) => return Ok(*receipt),
+        Some(OperationState::Pending { .. }) => {
+            return Err(ApiError::OperationPending(key.to_string()))
+        }
+    }
+    sent.store(false, Ordering::SeqCst);
+    match operation.await {
+        // A reverted transaction was still mined, so the key stays pending for the
+        // caller to check what happened before releasing it
+        Ok(receipt) if !receipt.status() => Err(ContractError::TransactionFailed(format!(
+            "Transaction {} reverted",
+            receipt.transaction_hash
+        ))
+        .into()),
+        Ok(receipt) => {
+            store.complete(key, params, &receipt).await?;
+            Ok(receipt)
+        }
+        // A failed receipt wait or a dropped connection may hit after a transaction was
+        // broadcast, so the key stays pending rather than risk sending it again
+        Err(e) if sent.load(Ordering::SeqCst) => Err(e),
+        Err(e) => {
+            store.release(key).await?;
+            Err(e)
+        }
+    }
+}
+
+/// [`OperationStore`] held in memory.
+///
+/// Guards against duplicate submissions within one process only.
+#[derive(Debug, Default)]
+pub struct InMemoryOperationStore {
+    operations: Mutex<HashMap<String, OperationState>>,
+}
+
+impl InMemoryOperationStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_operations<T>(
+        &self,
+        f: impl FnOnce(&mut HashMap<String, OperationState>) -> T,
+    ) -> Result<T> {
+        let mut operations = self
+            .operations
+            .lock()
+            .map_err(|_| ApiError::Storage("operation store lock poisoned".to_string()))?;
+        Ok(f(&mut operations))
+    }
+}
+
+impl OperationStore for InMemoryOperationStore {
+    fn claim<'a>(
+        &'a self,
+        key: &'a str,
+        params: B256,
+    ) -> BoxFuture<'a, Result<Option<OperationState>>> {
+        Box::pin(future::ready(
+            self.with_operations(|operations| claim_in(operations, key, params)),
+        ))
+    }
+
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        params: B256,
+        receipt: &'a TransactionReceipt,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.with_operations(|operations| {
+            operations.insert(key.to_string(), completed(params, receipt));
+        })))
+    }
+
+    fn release<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.with_operations(|operations| {
+            operations.remove(key);
+        })))
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<OperationState>>> {
+        Box::pin(future::ready(
+            self.with_operations(|operations| operations.get(key).cloned()),
+        ))
+    }
+}
+
+/// [`OperationStore`] persisted as a JSON file.
+///
+/// Every change rewrites the file (through a temporary file and a rename), so the state
+/// survives crashes. Claims are atomic within one process; do not share the file
+/// between processes. Not available on `wasm32`, which has no filesystem.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct FileOperationStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileOperationStore {
+    /// Create a store at `path`. The file is created on the first claim.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the store's file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the operations, apply `f`, and write them back if `f` changed them.
+    fn update<T>(
+        &self,
+        f: impl FnOnce(&mut HashMap<String, OperationState>) -> (T, bool),
+    ) -> Result<T> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| ApiError::Storage("operation store lock poisoned".to_string()))?;
+        let mut operations = self.load()?;
+        let (value, changed) = f(&mut operations);
+        if changed {
+            self.save(&operations)?;
+        }
+        Ok(value)
+    }
+
+    fn load(&self) -> Result<HashMap<String, OperationState>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| {
+                ApiError::Storage(format!(
+                    "invalid operation store {}: {e}",
+                    self.path.display()
+                ))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(ApiError::Storage(format!(
+                "failed to read operation store {}: {e}",
+                self.path.display()
+            ))),
+        }
+    }
+
+    fn save(&self, operations: &HashMap<String, OperationState>) -> Result<()> {
+        let contents = serde_json::to_string_pretty(operations).map_err(|e| {
+            ApiError::Storage(format!("failed to serialize operation store: {e}"))
+        })?;
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents)
+            .and_then(|()| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| {
+                ApiError::Storage(format!(
+                    "failed to write operation store {}: {e}",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl OperationStore for FileOperationStore {
+    fn claim<'a>(
+        &'a self,
+        key: &'a str,
+        params: B256,
+    ) -> BoxFuture<'a, Result<Option<OperationState>>> {
+        Box::pin(future::ready(self.update(|operations| {
+            let existing = claim_in(operations, key, params);
+            let changed = existing.is_none();
+            (existing, changed)
+        })))
+    }
+
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        params: B256,
+        receipt: &'a TransactionReceipt,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.update(|operations| {
+            operations.insert(key.to_string(), completed(params, receipt));
+            ((), true)
+        })))
+    }
+
+    fn release<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(self.update(|operations| {
+            let changed = operations.remove(key).is_some();
+            ((), changed)
+        })))
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<OperationState>>> {
+        Box::pin(future::ready(
+            self.update(|operations| (operations.get(key).cloned(), false)),
+        ))
+    }
+}
+
+fn claim_in(
+    operations: &mut HashMap<String, OperationState>,
+    key: &str,
+    params: B256,
+) -> Option<OperationState> {
+    if let Some(existing) = operations.get(key) {
+        return Some(existing.clone());
+    }
+    operations.insert(key.to_string(), OperationState::Pending { params });
+    None
+}
+
+fn completed(params: B256, receipt: &TransactionReceipt) -> OperationState {
+    OperationState::Completed {
+        params,
+        receipt: Box::new(receipt.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    fn receipt() -> TransactionReceipt {
+        receipt_with_status("0x1")
+    }
+
+    fn receipt_with_status(status: &str) -> TransactionReceipt {
+        serde_json::from_value(serde_json::json!({
+            "transactionHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "transactionIndex": "0x0",
+            "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000002",
+            "blockNumber": "0x1",
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "cumulativeGasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "status": status,
+            "type": "0x2"
+        }))
+        .expect("valid receipt")
+    }
+
+    #[tokio::test]
+    async fn test_run_keyed_returns_stored_receipt() {
+        let store = InMemoryOperationStore::new();
+        let sent = AtomicBool::new(false);
+        let sends = AtomicUsize::new(0);
+        let send = || async {
+            sends.fetch_add(1, Ordering::SeqCst);
+            Ok(receipt())
+        };
+
+        let first = run_keyed(&store, "op", "deposit", &sent, send())
+            .await
+            .expect("first run");
+        let second = run_keyed(&store, "op", "deposit", &sent, send())
+            .await
+            .expect("second run");
+        assert_eq!(first.transaction_hash, second.transaction_hash);
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+
+        run_keyed(&store, "other", "deposit", &sent, send())
+            .await
+            .expect("other key");
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_keyed_pending_is_rejected() {
+        let store = InMemoryOperationStore::new();
+        let sent = AtomicBool::new(false);
+        let params = keccak256("deposit");
+        assert_eq!(store.claim("op", params).await.expect("claim"), None);
+
+        let result = run_keyed(&store, "op", "deposit", &sent, async { Ok(receipt()) }).await;
+        assert!(matches!(result, Err(ApiError::OperationPending(key)) if key == "op"));
+    }
+
+    #[tokio::test]
+    async fn test_run_keyed_rejects_other_params() {
+        let store = InMemoryOperationStore::new();
+        let sent = AtomicBool::new(false);
+        run_keyed(&store, "op", "deposit(1)", &sent, async { Ok(receipt()) })
+            .await
+            .expect("run");
+
+        let result = run_keyed(&store, "op", "deposit(2)", &sent, async { Ok(receipt()) }).await;
+        assert!(matches!(result, Err(ApiError::OperationKeyReused(key)) if key == "op"));
+    }
+
+    #[tokio::test]
+    async fn test_run_keyed_failure_handling() {
+        let store = InMemoryOperationStore::new();
+        let sent = AtomicBool::new(false);
+
+        // Failures before anything is sent release the key
+        let unsent = run_keyed(&store, "op", "deposit", &sent, async {
+            Err(ContractError::RpcConnection("connection reset".to_string()).into())
+        })
+        .await;
+        assert!(unsent.is_err());
+        assert_eq!(store.get("op").await.expect("get"), None);
+
+        // Reverts after a send leave it pending
+        let reverted = run_keyed(&store, "op", "deposit", &sent, async {
+            sent.store(true, Ordering::SeqCst);
+            Err(ContractError::TransactionFailed("reverted".to_string()).into())
+        })
+        .await;
+        assert!(reverted.is_err());
+        assert!(matches!(
+            store.get("op").await.expect("get"),
+            Some(OperationState::Pending { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_keyed_reverted_receipt_stays_pending() {
+        let store = InMemoryOperationStore::new();
+        let sent = AtomicBool::new(false);
+
+        let reverted = run_keyed(&store, "op", "deposit", &sent, async {
+            sent.store(true, Ordering::SeqCst);
+            Ok(receipt_with_status("0x0"))
+        })
+        .await;
+        assert!(matches!(
+            reverted,
+            Err(ApiError::Contract(ContractError::TransactionFailed(msg))) if msg.ends_with("reverted")
+        ));
+        assert!(matches!(
+            store.get("op").await.expect("get"),
+            Some(OperationState::Pending { .. })
+        ));
+
+        let retry = run_keyed(&store, "op", "deposit", &sent, async { Ok(receipt()) }).await;
+        assert!(matches!(retry, Err(ApiError::OperationPending(_))));
+    }
+
+    #[tokio::test]
+    async fn test_run_keyed_receipt_failure_stays_pending() {
+        let store = InMemoryOperationStore::new();
+        let sent = AtomicBool::new(false);
+        let sends = AtomicUsize::new(0);
+        let send = || async {
+            sends.fetch_add(1, Ordering::SeqCst);
+            sent.store(true, Ordering::SeqCst);
+            Err(
+                ContractError::TransactionFailed("Failed to get receipt: closed".to_string())
+                    .into(),
+            )
+        };
+
+        let first = run_keyed(&store, "op", "withdraw", &sent, send()).await;
+        assert!(matches!(first, Err(ApiError::Contract(_))));
+        let retry = run_keyed(&store, "op", "withdraw", &sent, send()).await;
+        assert!(matches!(retry, Err(ApiError::OperationPending(_))));
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_operation_store_persists() {
+        let path =
+            std::env::temp_dir().join(format!("morpho-rs-operations-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sent = AtomicBool::new(false);
+        let params = keccak256("deposit");
+
+        let store = FileOperationStore::new(&path);
+        run_keyed(&store, "op", "deposit", &sent, async { Ok(receipt()) })
+            .await
+            .expect("run");
+        assert_eq!(store.claim("pending", params).await.expect("claim"), None);
+
+        let reopened = FileOperationStore::new(&path);
+        assert!(matches!(
+            reopened.get("op").await.expect("get"),
+            Some(OperationState::Completed { params: stored, .. }) if stored == params
+        ));
+        assert_eq!(
+            reopened.get("pending").await.expect("get"),
+            Some(OperationState::Pending { params })
+        );
+        reopened.release("pending").await.expect("release");
+        assert_eq!(reopened.get("pending").await.expect("get"), None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
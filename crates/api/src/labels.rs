@@ -0,0 +1,230 @@
+//! Local address book of vault, curator, and token labels.
+//!
+//! [`AddressBook`] maps addresses to human-readable names so that logs, CLI output, and
+//! transaction descriptions render an address the same way everywhere:
+//! `Steakhouse USDC (0xBEEF…64CB)`. Labels can be added by hand, imported from the API's
+//! vault and curator metadata with [`MorphoApiClient::get_address_book`], and saved to
+//! and loaded from a JSON file.
+//!
+//! Labels are keyed by address alone; contracts deployed at the same address on several
+//! chains share a label.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_api::{AddressBook, LabelKind, NamedChain};
+//!
+//! let mut book = client.api().get_address_book(NamedChain::Mainnet).await?;
+//! book.insert(my_safe, LabelKind::Other, "Treasury Safe");
+//! book.save("labels.json")?;
+//!
+//! println!("{}", book.display(vault)); // Steakhouse USDC (0xBEEF…64CB)
+//! ```
+//!
+//! [`MorphoApiClient::get_address_book`]: crate::MorphoApiClient::get_address_book
+
+use std::collections::BTreeMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ApiError, Result};
+use crate::types::Vault;
+
+/// What a labeled address is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelKind {
+    /// A V1 or V2 vault.
+    Vault,
+    /// A curator's address.
+    Curator,
+    /// An ERC20 token.
+    Token,
+    /// Any other address, such as a user's own wallet.
+    Other,
+}
+
+/// A name for an address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    /// Human-readable name.
+    pub name: String,
+    /// What the address is.
+    pub kind: LabelKind,
+}
+
+/// Labels by address.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressBook {
+    /// Labels by address.
+    #[serde(default)]
+    pub labels: BTreeMap<Address, Label>,
+}
+
+impl AddressBook {
+    /// Create an empty address book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load an address book from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// Load an address book from a JSON file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| ApiError::Parse(format!("{}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Serialize the address book as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// Save the address book to a JSON file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?)
+            .map_err(|e| ApiError::Parse(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Label `address`, replacing any existing label.
+    pub fn insert(&mut self, address: Address, kind: LabelKind, name: impl Into<String>) {
+        self.labels.insert(
+            address,
+            Label {
+                name: name.into(),
+                kind,
+            },
+        );
+    }
+
+    /// Label `address` unless it already has a label, so hand-written labels survive
+    /// imports.
+    pub fn insert_if_missing(&mut self, address: Address, kind: LabelKind, name: &str) {
+        self.labels.entry(address).or_insert_with(|| Label {
+            name: name.to_string(),
+            kind,
+        });
+    }
+
+    /// Add every label from `other`, keeping existing labels.
+    pub fn merge(&mut self, other: AddressBook) {
+        for (address, label) in other.labels {
+            self.labels.entry(address).or_insert(label);
+        }
+    }
+
+    /// Label each vault with its name and its asset with the asset's symbol.
+    pub fn import_vaults<'a>(&mut self, vaults: impl IntoIterator<Item = &'a dyn Vault>) {
+        for vault in vaults {
+            self.insert_if_missing(vault.address(), LabelKind::Vault, vault.name());
+            let asset = vault.asset();
+            self.insert_if_missing(asset.address, LabelKind::Token, &asset.symbol);
+        }
+    }
+
+    /// The label of `address`.
+    pub fn get(&self, address: Address) -> Option<&Label> {
+        self.labels.get(&address)
+    }
+
+    /// The name `address` is labeled with.
+    pub fn name(&self, address: Address) -> Option<&str> {
+        self.get(address).map(|label| label.name.as_str())
+    }
+
+    /// `address` with its label, e.g. `Steakhouse USDC (0xBEEF…64CB)`, or the full
+    /// address if it has no label.
+    pub fn display(&self, address: Address) -> String {
+        format_labeled(self.name(address), address)
+    }
+
+    /// Number of labeled addresses.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Whether no address is labeled.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+/// Shorten an address to its first and last four checksummed hex digits, e.g.
+/// `0xBEEF…64CB`.
+pub fn short_address(address: Address) -> String {
+    let checksummed = address.to_checksum(None);
+    format!("{}…{}", &checksummed[..6], &checksummed[38..])
+}
+
+/// `name` followed by the shortened address, e.g. `Steakhouse USDC (0xBEEF…64CB)`, or the
+/// full address if there is no name.
+pub fn format_labeled(name: Option<&str>, address: Address) -> String {
+    match name {
+        Some(name) => format!("{} ({})", name, short_address(address)),
+        None => address.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    const VAULT: Address = address!("BEEF01735c132Ada46AA9aA4c54623cAA92A64CB");
+
+    #[test]
+    fn test_short_address() {
+        assert_eq!(short_address(VAULT), "0xBEEF…64CB");
+        assert_eq!(
+            format_labeled(Some("Steakhouse USDC"), VAULT),
+            "Steakhouse USDC (0xBEEF…64CB)"
+        );
+        assert_eq!(format_labeled(None, VAULT), VAULT.to_string());
+    }
+
+    #[test]
+    fn test_insert_and_lookup() {
+        let mut book = AddressBook::new();
+        assert!(book.is_empty());
+        book.insert(VAULT, LabelKind::Vault, "Steakhouse USDC");
+        assert_eq!(book.name(VAULT), Some("Steakhouse USDC"));
+        assert_eq!(book.get(VAULT).unwrap().kind, LabelKind::Vault);
+        assert_eq!(book.display(VAULT), "Steakhouse USDC (0xBEEF…64CB)");
+
+        // Imports do not overwrite existing labels
+        book.insert_if_missing(VAULT, LabelKind::Vault, "Other name");
+        assert_eq!(book.name(VAULT), Some("Steakhouse USDC"));
+
+        let mut other = AddressBook::new();
+        other.insert(VAULT, LabelKind::Vault, "Imported");
+        other.insert(Address::repeat_byte(0x11), LabelKind::Token, "USDC");
+        book.merge(other);
+        assert_eq!(book.name(VAULT), Some("Steakhouse USDC"));
+        assert_eq!(book.name(Address::repeat_byte(0x11)), Some("USDC"));
+        assert_eq!(book.len(), 2);
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut book = AddressBook::new();
+        book.insert(VAULT, LabelKind::Vault, "Steakhouse USDC");
+        book.insert(Address::repeat_byte(0x22), LabelKind::Curator, "Steakhouse");
+
+        let json = book.to_json().unwrap();
+        assert!(json.contains("\"kind\": \"curator\""));
+        assert_eq!(AddressBook::from_json(&json).unwrap(), book);
+        assert!(AddressBook::from_json("{\"labels\": []}").is_err());
+        assert!(AddressBook::from_json("{}").unwrap().is_empty());
+    }
+}
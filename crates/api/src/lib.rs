@@ -38,9 +38,13 @@
 //!
 //! - **`sim`** — Enables simulation support via the `morpho-rs-sim` crate. When enabled,
 //!   [`VaultV1`] and [`VaultV2`] gain a `to_vault_simulation()` method that converts API
-//!   response data into a `VaultSimulation` for computing projected APY and simulating
-//!   deposits/withdrawals. Also adds an `ApiError::Simulation` variant for propagating
-//!   simulation errors.
+//!   response data into a `VaultSimulation` (`VaultV2Simulation` for V2) for computing
+//!   projected APY and simulating deposits/withdrawals. Also adds an `ApiError::Simulation` variant for propagating
+//!   simulation errors, and the [`onchain`] module, which hydrates a `VaultSimulation`
+//!   directly from RPC state at any block (see `OnchainVaultSimulation::from_onchain`)
+//!   and reconstructs historical snapshots for backtesting (see `SnapshotClient`).
+//!   Dry runs ([`DryRunBackend`]) also report the `VaultImpact` of V1 deposits and
+//!   withdrawals.
 //!
 //!   To enable, add this to your `Cargo.toml`:
 //!
@@ -61,6 +65,141 @@
 //!   }
 //!   ```
 //!
+//! - **`blocking`** — Adds the [`blocking`] module with a synchronous `MorphoClient` that
+//!   owns a dedicated Tokio runtime, for scripts and backtests that are not async:
+//!
+//!   ```ignore
+//!   let client = morpho_rs_api::blocking::MorphoClient::new()?;
+//!   let vaults = client.get_vaults_v1_by_chain(NamedChain::Mainnet)?;
+//!   ```
+//!
+//! - **`test-util`** — Adds the [`mock`] module with `MockMorphoApi`, an in-memory
+//!   implementation of the [`MorphoApi`] query trait backed by JSON fixtures, for unit
+//!   testing code that depends on this crate without network access:
+//!
+//!   ```toml
+//!   [dev-dependencies]
+//!   morpho-rs-api = { version = "0.8", features = ["test-util"] }
+//!   ```
+//!
+//! - **`audit`** — Adds the `audit` module, which records every transaction sent by
+//!   operations (chain, function, arguments, transaction hash, status, and balances
+//!   before and after) to a pluggable `AuditSink`, such as a JSON Lines file, for
+//!   compliance reporting. Enable it with `MorphoClientConfig::with_audit_sink`. With the
+//!   `sqlite` feature as well, `SqliteAuditSink` stores records in a SQLite table.
+//!
+//! - **`sqlite`** / **`parquet`** — Let the [`export`] module write snapshots to SQLite
+//!   databases (via bundled `rusqlite`) and Parquet files (via `parquet` and `arrow`).
+//!
+//! # Offline Fixtures
+//!
+//! Every GraphQL query goes through a [`Transport`] when one is set with
+//! [`ClientConfig::with_transport`]. [`FixtureClient`] answers queries from canned JSON
+//! responses (in memory or one file per operation in a directory), so code that depends
+//! on [`MorphoApiClient`] can be unit tested without hitting the live endpoint. See the
+//! [`transport`] module.
+//!
+//! # Serialization
+//!
+//! Every response type ([`VaultV1`], [`VaultV2`], [`UserAccountOverview`], allocations,
+//! adapters, market state, ...) implements `Serialize` and `Deserialize`, so results can
+//! be cached to disk or sent over internal APIs as-is. Chains are serialized as chain IDs
+//! (see [`chain_serde`]) and `U256` amounts as hex strings.
+//!
+//! # Conversion Diagnostics
+//!
+//! Vaults and nested items the client cannot convert (malformed addresses, non-numeric
+//! amounts, unsupported chains) are skipped by default. Each skip is recorded as a
+//! [`ConversionError`], returned by [`VaultV1Client::get_vaults_with_diagnostics`] and
+//! [`VaultV2Client::get_vaults_with_diagnostics`]. Set [`ConversionMode::Strict`] with
+//! [`ClientConfig::with_conversion_mode`] to fail queries instead. See the [`conversion`]
+//! module.
+//!
+//! # WebAssembly
+//!
+//! The API clients compile to `wasm32-unknown-unknown`, using reqwest's `fetch` backend
+//! and browser timers for retry backoff. The request timeout and the `blocking` feature
+//! are not available there, and transaction support is native-only.
+//!
+//! # Endpoint Failover
+//!
+//! [`ClientConfig::with_fallback_api_url`] adds mirror GraphQL endpoints (such as a
+//! self-hosted indexer). Queries fail over to the next endpoint on transport errors, 5xx
+//! responses, and GraphQL errors, and a per-endpoint [`CircuitBreaker`] skips endpoints
+//! that fail repeatedly. [`MorphoApiClient::check_endpoints`] probes every endpoint. See
+//! the [`endpoints`] module.
+//!
+//! # Metrics
+//!
+//! Implement the [`Metrics`] trait and pass it to [`ClientConfig::with_metrics`] to receive
+//! callbacks for API requests (with latency), retries, GraphQL errors, and transaction
+//! submissions/confirmations. The default is [`NoopMetrics`].
+//!
+//! # Middleware
+//!
+//! [`ClientConfig::with_middleware`] registers a [`QueryMiddleware`] whose hooks run
+//! before and after every GraphQL query, with the operation name, variables, duration,
+//! and outcome. Middleware can add headers (e.g. request signatures) or fail a query,
+//! for logging, auditing, or fault injection in tests. See the [`middleware`] module.
+//!
+//! # Authentication
+//!
+//! For private API tiers, [`SiweAuthenticator`] signs in with a private key using a
+//! Sign-In with Ethereum message and attaches the session token to every query, signing
+//! in again when the token expires. Enable it with [`ClientConfig::with_siwe_auth`] or
+//! [`MorphoClientConfig::with_siwe_auth`] (which reuses the configured private key).
+//!
+//! # Field Selection
+//!
+//! [`VaultFieldSelection`] builds V1 vault queries at runtime that fetch only the chosen
+//! field groups, for pollers that do not need full allocation and market state. See
+//! [`VaultV1Client::get_vaults_with_fields`] and the [`queries::selection`] module.
+//!
+//! # Reports
+//!
+//! The [`reports`] module builds periodic snapshots of tracked users' positions and
+//! tracked vaults' APY changes, estimated fee accrual, and warnings, rendered as JSON or
+//! markdown. See [`ReportGenerator`].
+//!
+//! # Export
+//!
+//! The [`export`] module flattens vaults and their markets across chains into tables
+//! with a stable schema and writes them to SQLite or Parquet (with the `sqlite` and
+//! `parquet` features), a SQL script, CSV, or JSON Lines, for bulk analysis without
+//! custom ETL. See [`export::fetch_snapshot`].
+//!
+//! # Monitoring
+//!
+//! The [`monitor`] module polls tracked vaults and sends an [`Alert`] through a channel
+//! when a [`WatchRule`] triggers: a net APY drop, a TVL outflow within a time window, or
+//! a new red warning. See [`VaultMonitor`]. The [`notify`] module delivers alerts to
+//! webhooks, Slack, Discord, or stdout instead of a channel.
+//!
+//! # Idempotent Operations
+//!
+//! Deposits and withdrawals tagged with an idempotency key run at most once: re-invoking
+//! a completed operation with the same key returns its original receipt from an
+//! [`OperationStore`], so bots can retry after a crash without double-spending. See the
+//! [`idempotency`] module.
+//!
+//! # Chains
+//!
+//! [`chain_config`] returns the Morpho Blue, Bundler3, Public Allocator, and Universal
+//! Rewards Distributor addresses for a chain, along with a default public RPC URL and
+//! block time. See the [`chains`] module.
+//!
+//! # Units
+//!
+//! [`TokenAmount`] converts between human-readable amounts (`"1,234.5 USDC"`) and raw
+//! token units, formats amounts for display, and refuses arithmetic between amounts with
+//! different decimals. See the [`units`] module.
+//!
+//! # Decimal USD Values
+//!
+//! USD values are reported as `f64`. With the `decimal` feature, the `decimal` module
+//! converts them to `rust_decimal::Decimal` and sums positions and account overviews
+//! across chains without floating-point drift.
+//!
 //! # Error Handling
 //!
 //! All errors are unified through [`ApiError`], which wraps errors from the contracts and
@@ -68,25 +207,115 @@
 //! and [`ApiError::is_retryable()`] to determine retry eligibility. See [`ErrorCategory`]
 //! for the full set of categories.
 
+pub mod api;
+#[cfg(feature = "audit")]
+pub mod audit;
+pub mod auth;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+pub mod chains;
 pub mod client;
+pub mod conversion;
+pub mod cost;
+pub mod description;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+pub mod endpoints;
 pub mod error;
+pub mod execution;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod export;
 pub mod filters;
+pub mod idempotency;
+pub mod labels;
+pub mod middleware;
+#[cfg(feature = "test-util")]
+pub mod mock;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod monitor;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod notify;
+#[cfg(feature = "sim")]
+pub mod onchain;
+mod platform;
 pub mod queries;
+pub mod reports;
+pub mod transport;
 pub mod types;
+pub mod units;
 
 // Re-export main types at crate root
+pub use api::MorphoApi;
+#[cfg(feature = "audit")]
+pub use audit::{
+    AuditBackend, AuditFailureHandler, AuditRecord, AuditSink, AuditStatus, JsonlAuditSink,
+    MemoryAuditSink,
+};
+#[cfg(all(feature = "audit", feature = "sqlite"))]
+pub use audit::SqliteAuditSink;
+pub use auth::{SiweAuthConfig, SiweAuthenticator};
+pub use chains::{chain_config, ChainConfig};
 pub use client::{
     ClientConfig, MorphoApiClient, MorphoClient, MorphoClientConfig, VaultV1Client,
-    VaultV1Operations, VaultV2Client, VaultV2Operations, DEFAULT_API_URL,
+    VaultV1Operations, VaultV2Client, VaultV2Operations, API_KEY_HEADER, DEFAULT_API_URL,
+    DEFAULT_REWARDS_API_URL, GATE_REGISTRATION_TTL_SECS,
 };
+pub use conversion::{ConversionError, ConversionMode, Converted};
+pub use cost::{CostEstimate, PlannedOperation, DEFAULT_DEPOSIT_GAS};
+pub use description::TransactionDescription;
+pub use endpoints::{CircuitBreaker, EndpointHealth, EndpointState, EndpointStatus};
 pub use error::{ApiError, ErrorCategory, Result};
+#[cfg(feature = "sim")]
+pub use execution::VaultImpact;
+pub use execution::{
+    DryRunBackend, DryRunRecord, Execution, ExecutionBackend, ForkBackend, LiveBackend,
+    UserOperation, DEFAULT_FORK_RPC_URL,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use export::{ExportFormat, ExportSnapshot, ExportTable, MarketRow, VaultRow};
 pub use filters::{VaultFiltersV1, VaultFiltersV2, VaultQueryOptionsV1, VaultQueryOptionsV2};
-pub use morpho_rs_contracts::{Erc4626Client, VaultV1TransactionClient, VaultV2TransactionClient};
+#[cfg(not(target_arch = "wasm32"))]
+pub use idempotency::FileOperationStore;
+pub use idempotency::{InMemoryOperationStore, OperationState, OperationStore};
+pub use labels::{AddressBook, Label, LabelKind};
+pub use middleware::{QueryMiddleware, QueryOutcome, QueryRequest};
+#[cfg(feature = "test-util")]
+pub use mock::MockMorphoApi;
+#[cfg(not(target_arch = "wasm32"))]
+pub use monitor::{
+    Alert, MonitorConfig, MonitorFailure, PollError, PollReport, VaultMonitor, WatchRule,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use notify::{
+    NotificationSink, Notifier, SinkConfig, StdoutSink, WebhookFormat, WebhookSink,
+};
+#[cfg(feature = "sim")]
+pub use onchain::{
+    check_simulation_prices, find_pre_liquidation, OnchainVaultSimulation, PreLiquidation,
+    SnapshotClient, VaultSnapshot,
+};
+pub use queries::VaultFieldSelection;
+pub use reports::{Report, ReportConfig, ReportFormat, ReportGenerator, TrackedVault};
+pub use transport::{FixtureClient, RecordedRequest, Transport};
+pub use morpho_rs_contracts::{
+    CallDescription, DepositOutcome, Erc20Client, Erc4626Client, GateKind, GateRequirement,
+    Metrics, MigrationOutcome, NoopMetrics, PreLiquidationClient, PriceDeviation, ReportedPrice,
+    SendOptions, TokenMetadata, UniversalRewardsDistributorClient, VaultV1TransactionClient,
+    VaultV2TransactionClient, WithdrawOutcome,
+};
 pub use types::{
-    chain_from_id, chain_serde, Asset, MarketInfo, MarketStateV1, MarketStateV2,
-    MetaMorphoAllocation, MorphoMarketPosition, NamedChain, OrderDirection, UserAccountOverview,
-    UserMarketPosition, UserState, UserVaultPositions, UserVaultV1Position, UserVaultV2Position,
-    Vault, VaultAdapter, VaultAdapterData, VaultAllocation, VaultAllocator, VaultInfo,
-    VaultOrderByV1, VaultOrderByV2, VaultPositionState, VaultReward, VaultStateV1, VaultV1,
-    VaultV2, VaultV2Warning, VaultVersion, VaultWarning, SUPPORTED_CHAINS,
+    chain_from_id, chain_serde, ApyBreakdown, Asset, AssetPriceHistory, CapHeadroom, CapRoom,
+    ChainResults, ClaimableReward, DepositCapacity, DepositLimit, FieldChange, HistoryPoint,
+    InstantLiquidity, MarketCapHeadroom, MarketChange, MarketInfo, MarketInstantLiquidity,
+    MarketStateV1, MarketStateV2, MetaMorphoAllocation, MorphoMarketPosition, NamedChain,
+    OrderDirection, Page, PricePoint,
+    PublicAllocatorFlowCap, RewardApr,
+    SharePriceHistory, TransactionData, TransactionKind, UserAccountOverview, UserMarketPosition,
+    UserMarketState, UserState, UserTransaction, UserTransactionHistory, UserVaultPositions, UserVaultV1Position,
+    UserVaultV2Position, Vault, VaultAdapter, VaultAdapterData, VaultAllocation, VaultAllocator,
+    VaultCap, VaultCapData, VaultDiff, VaultFeeInfo, VaultInfo, VaultOrderByV1, VaultOrderByV2,
+    VaultPositionState, VaultPublicAllocatorConfig, VaultRankByV2, VaultReward, VaultStateV1, VaultV1, VaultV2, VaultV2Warning,
+    VaultVersion, VaultWarning, WarningLevel, WarningType, YieldRealization, YieldWindow,
+    SUPPORTED_CHAINS, VAULT_SHARE_UNIT,
 };
+pub use units::TokenAmount;
@@ -0,0 +1,144 @@
+//! Request/response hooks for GraphQL queries.
+//!
+//! A [`QueryMiddleware`] registered with [`ClientConfig::with_middleware`] sees every
+//! GraphQL query the API clients send: [`before`](QueryMiddleware::before) runs once per
+//! query with its operation name and variables, and [`after`](QueryMiddleware::after)
+//! runs when the query completes, with its total duration (including retries) and
+//! outcome. Use it for custom logging, auditing, request signing, or fault injection in
+//! tests.
+//!
+//! `before` hooks run in registration order and may add headers to the outgoing HTTP
+//! requests or fail the query with an error, in which case later `before` hooks and the
+//! request itself are skipped. `after` hooks run in reverse order and are always called,
+//! including for queries failed by a `before` hook. Headers are not sent when a
+//! [`Transport`](crate::transport::Transport) replaces HTTP.
+//!
+//! The rewards API and endpoint health probes are not GraphQL queries and bypass
+//! middleware.
+//!
+//! [`ClientConfig::with_middleware`]: crate::ClientConfig::with_middleware
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! use morpho_rs_api::{ClientConfig, QueryMiddleware, QueryOutcome, QueryRequest};
+//!
+//! #[derive(Debug)]
+//! struct Logger;
+//!
+//! impl QueryMiddleware for Logger {
+//!     fn after(&self, request: &QueryRequest, duration: Duration, outcome: QueryOutcome<'_>) {
+//!         println!("{} took {:?}: {:?}", request.operation(), duration, outcome);
+//!     }
+//! }
+//!
+//! let config = ClientConfig::new().with_middleware(Arc::new(Logger));
+//! ```
+
+use std::fmt::Debug;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::error::{ApiError, Result};
+
+/// Hooks invoked around every GraphQL query.
+///
+/// Both methods default to no-ops. They are called inline on the request path, so
+/// implementations must be cheap and non-blocking.
+pub trait QueryMiddleware: Debug + Send + Sync {
+    /// Called before the query is sent. Returning an error fails the query with it.
+    fn before(&self, _request: &mut QueryRequest) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the query has completed, successfully or not.
+    fn after(&self, _request: &QueryRequest, _duration: Duration, _outcome: QueryOutcome<'_>) {}
+}
+
+/// A GraphQL query as seen by [`QueryMiddleware`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryRequest {
+    operation: String,
+    variables: Value,
+    headers: Vec<(String, String)>,
+}
+
+impl QueryRequest {
+    pub(crate) fn new(operation: impl Into<String>, variables: Value) -> Self {
+        Self {
+            operation: operation.into(),
+            variables,
+            headers: Vec::new(),
+        }
+    }
+
+    /// GraphQL operation name, e.g. `GetVaultsV1`.
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    /// Query variables (`null` for queries without variables).
+    pub fn variables(&self) -> &Value {
+        &self.variables
+    }
+
+    /// Headers added by middleware, in insertion order.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Add a header to every HTTP attempt of this query, e.g. a request signature.
+    pub fn insert_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.headers.push((name.into(), value.into()));
+    }
+}
+
+/// How a GraphQL query completed.
+#[derive(Debug, Clone, Copy)]
+pub enum QueryOutcome<'a> {
+    /// The query returned data.
+    Success,
+    /// The query failed, after any retries.
+    Failure(&'a ApiError),
+}
+
+impl QueryOutcome<'_> {
+    /// Whether the query returned data.
+    pub fn is_success(&self) -> bool {
+        matches!(self, QueryOutcome::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query_request_headers() {
+        let mut request = QueryRequest::new("GetVaultsV1", json!({ "first": 100 }));
+        request.insert_header("x-signature", "abc");
+        request.insert_header("x-trace", "1");
+
+        assert_eq!(request.operation(), "GetVaultsV1");
+        assert_eq!(request.variables()["first"], 100);
+        assert_eq!(
+            request.headers(),
+            [
+                ("x-signature".to_string(), "abc".to_string()),
+                ("x-trace".to_string(), "1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_query_outcome_is_success() {
+        let err = ApiError::GraphQL("boom".to_string());
+        assert!(QueryOutcome::Success.is_success());
+        assert!(!QueryOutcome::Failure(&err).is_success());
+    }
+}
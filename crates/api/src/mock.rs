@@ -0,0 +1,497 @@
+//! In-memory [`MorphoApi`] implementation for downstream unit tests.
+//!
+//! Available with the `test-util` feature. [`MockMorphoApi`] answers the same queries as
+//! [`MorphoApiClient`](crate::MorphoApiClient) from fixture data held in memory, so
+//! vault-selection and rebalancing logic written against [`MorphoApi`] can be tested
+//! deterministically without network access or a mock HTTP server.
+//!
+//! Fixtures are the crate's own serialized types, so they can be captured from real API
+//! responses with `serde_json::to_string(&vaults)`:
+//!
+//! ```json
+//! {
+//!   "vaults_v1": [ ... ],
+//!   "vaults_v2": [ ... ],
+//!   "user_positions": [ ... ],
+//!   "account_overviews": [ { "chain": 1, "overview": { ... } } ]
+//! }
+//! ```
+//!
+//! Filters are applied in memory with the same semantics as the API. Orderings use the
+//! corresponding field where the vault type has one; orderings on fields the types do not
+//! carry (e.g. `AvgApy`, `CredoraRiskScore`) keep fixture order.
+//!
+//! # Example
+//!
+//! ```
+//! use morpho_rs_api::mock::MockMorphoApi;
+//! use morpho_rs_api::{MorphoApi, NamedChain};
+//!
+//! async fn best_vault_name(api: &impl MorphoApi) -> Option<String> {
+//!     let vaults = api.get_vaults_by_chain(NamedChain::Mainnet).await.ok()?;
+//!     vaults
+//!         .iter()
+//!         .max_by(|a, b| a.net_apy().total_cmp(&b.net_apy()))
+//!         .map(|v| v.name().to_string())
+//! }
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let api = MockMorphoApi::new();
+//! assert_eq!(best_vault_name(&api).await, None);
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use alloy_chains::NamedChain;
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::api::MorphoApi;
+use crate::error::{ApiError, Result};
+use crate::filters::{VaultFiltersV1, VaultFiltersV2, VaultQueryOptionsV1, VaultQueryOptionsV2};
+use crate::types::chain::chain_serde;
+use crate::types::ordering::{OrderDirection, VaultOrderByV1, VaultOrderByV2};
+use crate::types::{UserAccountOverview, UserVaultPositions, VaultV1, VaultV2};
+
+/// An account overview fixture for a user on a specific chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MockAccountOverview {
+    /// The chain the overview was captured on.
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// The account overview.
+    pub overview: UserAccountOverview,
+}
+
+/// Fixture-backed [`MorphoApi`] for unit tests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MockMorphoApi {
+    /// V1 vaults returned by vault queries.
+    #[serde(default)]
+    pub vaults_v1: Vec<VaultV1>,
+    /// V2 vaults returned by vault queries.
+    #[serde(default)]
+    pub vaults_v2: Vec<VaultV2>,
+    /// Per-user vault positions (across all chains).
+    #[serde(default)]
+    pub user_positions: Vec<UserVaultPositions>,
+    /// Per-user, per-chain account overviews.
+    #[serde(default)]
+    pub account_overviews: Vec<MockAccountOverview>,
+}
+
+impl MockMorphoApi {
+    /// Create an empty mock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load fixtures from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// Load fixtures from a JSON file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| ApiError::Parse(format!("{}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Add a V1 vault.
+    pub fn with_vault_v1(mut self, vault: VaultV1) -> Self {
+        self.vaults_v1.push(vault);
+        self
+    }
+
+    /// Add a V2 vault.
+    pub fn with_vault_v2(mut self, vault: VaultV2) -> Self {
+        self.vaults_v2.push(vault);
+        self
+    }
+
+    /// Add a user's vault positions.
+    pub fn with_user_positions(mut self, positions: UserVaultPositions) -> Self {
+        self.user_positions.push(positions);
+        self
+    }
+
+    /// Add a user's account overview on a chain.
+    pub fn with_account_overview(
+        mut self,
+        chain: NamedChain,
+        overview: UserAccountOverview,
+    ) -> Self {
+        self.account_overviews
+            .push(MockAccountOverview { chain, overview });
+        self
+    }
+}
+
+impl MorphoApi for MockMorphoApi {
+    async fn get_vaults_v1(&self, filters: Option<VaultFiltersV1>) -> Result<Vec<VaultV1>> {
+        Ok(self
+            .vaults_v1
+            .iter()
+            .filter(|v| filters.as_ref().is_none_or(|f| matches_v1(f, v)))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_vaults_v1_with_options(
+        &self,
+        options: VaultQueryOptionsV1,
+    ) -> Result<Vec<VaultV1>> {
+        let mut vaults = self.get_vaults_v1(options.filters).await?;
+        let order_by = options.order_by.unwrap_or_default();
+        let direction = options.order_direction.unwrap_or_default();
+        vaults.sort_by(|a, b| directed(compare_v1(order_by, a, b), direction));
+        if let Some(limit) = options.limit {
+            vaults.truncate(limit.max(0) as usize);
+        }
+        Ok(vaults)
+    }
+
+    async fn get_vault_v1(&self, address: Address, chain: NamedChain) -> Result<VaultV1> {
+        self.vaults_v1
+            .iter()
+            .find(|v| v.address == address && v.chain == chain)
+            .cloned()
+            .ok_or_else(|| not_found(address, chain))
+    }
+
+    async fn get_vaults_v2(&self, filters: Option<VaultFiltersV2>) -> Result<Vec<VaultV2>> {
+        Ok(self
+            .vaults_v2
+            .iter()
+            .filter(|v| filters.as_ref().is_none_or(|f| matches_v2(f, v)))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_vaults_v2_with_options(
+        &self,
+        options: VaultQueryOptionsV2,
+    ) -> Result<Vec<VaultV2>> {
+        let mut vaults = self.get_vaults_v2(options.filters.clone()).await?;
+        if let Some(ref symbols) = options.asset_symbols {
+            vaults.retain(|v| {
+                symbols
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&v.asset.symbol))
+            });
+        }
+        if let Some(ref addresses) = options.asset_addresses {
+            vaults.retain(|v| contains_address(addresses, &v.asset.address));
+        }
+        if let Some(ref curators) = options.curator_addresses {
+            vaults.retain(|v| v.curator.is_some_and(|c| contains_address(curators, &c)));
+        }
+        let order_by = options.order_by.unwrap_or_default();
+        let direction = options.order_direction.unwrap_or_default();
+        vaults.sort_by(|a, b| directed(compare_v2(order_by, a, b), direction));
+        if let Some(limit) = options.limit {
+            vaults.truncate(limit.max(0) as usize);
+        }
+        Ok(vaults)
+    }
+
+    async fn get_vault_v2(&self, address: Address, chain: NamedChain) -> Result<VaultV2> {
+        self.vaults_v2
+            .iter()
+            .find(|v| v.address == address && v.chain == chain)
+            .cloned()
+            .ok_or_else(|| not_found(address, chain))
+    }
+
+    async fn get_user_vault_positions(
+        &self,
+        address: Address,
+        chain: Option<NamedChain>,
+    ) -> Result<UserVaultPositions> {
+        let mut positions = self
+            .user_positions
+            .iter()
+            .find(|p| p.address == address)
+            .cloned()
+            .unwrap_or(UserVaultPositions {
+                address,
+                vault_positions: Vec::new(),
+                vault_v2_positions: Vec::new(),
+            });
+        if let Some(c) = chain {
+            positions.vault_positions.retain(|p| p.vault.chain == c);
+            positions.vault_v2_positions.retain(|p| p.vault.chain == c);
+        }
+        Ok(positions)
+    }
+
+    async fn get_user_account_overview(
+        &self,
+        address: Address,
+        chain: NamedChain,
+    ) -> Result<UserAccountOverview> {
+        self.account_overviews
+            .iter()
+            .find(|o| o.chain == chain && o.overview.address == address)
+            .map(|o| o.overview.clone())
+            // Mirrors the API's response for users with no activity on the chain
+            .ok_or_else(|| ApiError::GraphQL("No results matching given parameters".to_string()))
+    }
+}
+
+fn not_found(address: Address, chain: NamedChain) -> ApiError {
+    ApiError::VaultNotFound {
+        address: address.to_string(),
+        chain_id: u64::from(chain) as i64,
+    }
+}
+
+fn contains_address(addresses: &[String], address: &Address) -> bool {
+    addresses
+        .iter()
+        .any(|a| a.parse::<Address>().is_ok_and(|a| a == *address))
+}
+
+fn in_chains(chain_ids: &Option<Vec<i64>>, chain: NamedChain) -> bool {
+    chain_ids
+        .as_ref()
+        .is_none_or(|ids| ids.contains(&(u64::from(chain) as i64)))
+}
+
+fn in_range(value: Option<f64>, gte: Option<f64>, lte: Option<f64>) -> bool {
+    match value {
+        Some(v) => gte.is_none_or(|min| v >= min) && lte.is_none_or(|max| v <= max),
+        None => gte.is_none() && lte.is_none(),
+    }
+}
+
+fn matches_v1(filters: &VaultFiltersV1, vault: &VaultV1) -> bool {
+    let state = vault.state.as_ref();
+    in_chains(&filters.chain_ids, vault.chain)
+        && filters
+            .addresses
+            .as_ref()
+            .is_none_or(|a| contains_address(a, &vault.address))
+        && filters.listed.is_none_or(|l| vault.listed == l)
+        && filters.featured.is_none_or(|f| vault.featured == f)
+        && filters.curator_addresses.as_ref().is_none_or(|a| {
+            state
+                .and_then(|s| s.curator)
+                .is_some_and(|c| contains_address(a, &c))
+        })
+        && filters.owner_addresses.as_ref().is_none_or(|a| {
+            state
+                .and_then(|s| s.owner)
+                .is_some_and(|o| contains_address(a, &o))
+        })
+        && filters
+            .asset_addresses
+            .as_ref()
+            .is_none_or(|a| contains_address(a, &vault.asset.address))
+        && filters.asset_symbols.as_ref().is_none_or(|symbols| {
+            symbols
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(&vault.asset.symbol))
+        })
+        && in_range(state.map(|s| s.apy), filters.apy_gte, filters.apy_lte)
+        && filters.search.as_ref().is_none_or(|q| {
+            let q = q.to_lowercase();
+            vault.name.to_lowercase().contains(&q) || vault.symbol.to_lowercase().contains(&q)
+        })
+}
+
+fn matches_v2(filters: &VaultFiltersV2, vault: &VaultV2) -> bool {
+    in_chains(&filters.chain_ids, vault.chain)
+        && filters
+            .addresses
+            .as_ref()
+            .is_none_or(|a| contains_address(a, &vault.address))
+        && filters.listed.is_none_or(|l| vault.listed == l)
+        && in_range(
+            vault.total_assets_usd,
+            filters.total_assets_usd_gte,
+            filters.total_assets_usd_lte,
+        )
+        && in_range(
+            vault.liquidity_usd,
+            filters.liquidity_usd_gte,
+            filters.liquidity_usd_lte,
+        )
+        && in_range(vault.apy, filters.apy_gte, filters.apy_lte)
+}
+
+fn directed(ordering: Ordering, direction: OrderDirection) -> Ordering {
+    match direction {
+        OrderDirection::Asc => ordering,
+        OrderDirection::Desc => ordering.reverse(),
+    }
+}
+
+fn compare_f64(a: Option<f64>, b: Option<f64>) -> Ordering {
+    a.unwrap_or(f64::MIN).total_cmp(&b.unwrap_or(f64::MIN))
+}
+
+fn compare_v1(order_by: VaultOrderByV1, a: &VaultV1, b: &VaultV1) -> Ordering {
+    let (sa, sb) = (a.state.as_ref(), b.state.as_ref());
+    match order_by {
+        VaultOrderByV1::Address => a.address.cmp(&b.address),
+        VaultOrderByV1::Name => a.name.cmp(&b.name),
+        VaultOrderByV1::TotalAssets => sa.map(|s| s.total_assets).cmp(&sb.map(|s| s.total_assets)),
+        VaultOrderByV1::TotalAssetsUsd => compare_f64(
+            sa.and_then(|s| s.total_assets_usd),
+            sb.and_then(|s| s.total_assets_usd),
+        ),
+        VaultOrderByV1::TotalSupply => sa.map(|s| s.total_supply).cmp(&sb.map(|s| s.total_supply)),
+        VaultOrderByV1::Fee => compare_f64(sa.map(|s| s.fee), sb.map(|s| s.fee)),
+        VaultOrderByV1::Apy => compare_f64(sa.map(|s| s.apy), sb.map(|s| s.apy)),
+        VaultOrderByV1::NetApy => compare_f64(sa.map(|s| s.net_apy), sb.map(|s| s.net_apy)),
+        VaultOrderByV1::Curator => sa.and_then(|s| s.curator).cmp(&sb.and_then(|s| s.curator)),
+        _ => Ordering::Equal,
+    }
+}
+
+fn compare_v2(order_by: VaultOrderByV2, a: &VaultV2, b: &VaultV2) -> Ordering {
+    match order_by {
+        VaultOrderByV2::Address => a.address.cmp(&b.address),
+        VaultOrderByV2::TotalAssets => a.total_assets.cmp(&b.total_assets),
+        VaultOrderByV2::TotalAssetsUsd => compare_f64(a.total_assets_usd, b.total_assets_usd),
+        VaultOrderByV2::TotalSupply => a.total_supply.cmp(&b.total_supply),
+        VaultOrderByV2::Liquidity => a.liquidity.cmp(&b.liquidity),
+        VaultOrderByV2::LiquidityUsd => compare_f64(a.liquidity_usd, b.liquidity_usd),
+        VaultOrderByV2::Apy => compare_f64(a.apy, b.apy),
+        VaultOrderByV2::NetApy => compare_f64(a.net_apy, b.net_apy),
+        _ => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Asset, VaultStateV1};
+    use alloy_primitives::U256;
+
+    fn asset(symbol: &str) -> Asset {
+        Asset {
+            address: Address::repeat_byte(0xAA),
+            symbol: symbol.to_string(),
+            name: None,
+            decimals: 6,
+            price_usd: Some(1.0),
+        }
+    }
+
+    fn vault_v1(byte: u8, chain: NamedChain, symbol: &str, net_apy: f64, listed: bool) -> VaultV1 {
+        VaultV1 {
+            address: Address::repeat_byte(byte),
+            name: format!("Vault {byte}"),
+            symbol: format!("v{byte}"),
+            chain,
+            listed,
+            featured: false,
+            whitelisted: listed,
+            asset: asset(symbol),
+            state: Some(VaultStateV1 {
+                curator: Some(Address::repeat_byte(0xC0)),
+                owner: None,
+                guardian: None,
+                total_assets: U256::from(byte),
+                total_assets_usd: Some(byte as f64 * 1000.0),
+                total_supply: U256::from(byte),
+                fee: 0.1,
+                timelock: 86_400,
+                apy: net_apy / 0.9,
+                net_apy,
+                share_price: U256::from(1),
+                allocation: Vec::new(),
+                rewards: Vec::new(),
+            }),
+            allocators: Vec::new(),
+            warnings: Vec::new(),
+            public_allocator_config: None,
+        }
+    }
+
+    fn mock() -> MockMorphoApi {
+        MockMorphoApi::new()
+            .with_vault_v1(vault_v1(0x01, NamedChain::Mainnet, "USDC", 0.04, true))
+            .with_vault_v1(vault_v1(0x02, NamedChain::Mainnet, "WETH", 0.06, false))
+            .with_vault_v1(vault_v1(0x03, NamedChain::Base, "USDC", 0.08, true))
+    }
+
+    #[tokio::test]
+    async fn test_get_vaults_v1_filters() {
+        let api = mock();
+        let filters = VaultFiltersV1::new()
+            .chain(NamedChain::Mainnet)
+            .asset_symbols(["usdc"]);
+        let vaults = api.get_vaults_v1(Some(filters)).await.unwrap();
+        assert_eq!(vaults.len(), 1);
+        assert_eq!(vaults[0].address, Address::repeat_byte(0x01));
+
+        let all = api.get_vaults_v1(None).await.unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_vaults_v1_with_options_orders_and_limits() {
+        let api = mock();
+        let options = VaultQueryOptionsV1::new()
+            .order_by(VaultOrderByV1::NetApy)
+            .order_direction(OrderDirection::Desc)
+            .limit(2);
+        let vaults = api.get_vaults_v1_with_options(options).await.unwrap();
+        let addresses: Vec<_> = vaults.iter().map(|v| v.address).collect();
+        assert_eq!(
+            addresses,
+            vec![Address::repeat_byte(0x03), Address::repeat_byte(0x02)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_vault_v1_not_found() {
+        let api = mock();
+        let address = Address::repeat_byte(0x01);
+        assert!(api.get_vault_v1(address, NamedChain::Mainnet).await.is_ok());
+        let result = api.get_vault_v1(address, NamedChain::Base).await;
+        assert!(matches!(result, Err(ApiError::VaultNotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_default_combined_queries() {
+        let api = mock();
+        let mainnet = api.get_vaults_by_chain(NamedChain::Mainnet).await.unwrap();
+        assert_eq!(mainnet.len(), 2);
+        let whitelisted = api.get_whitelisted_vaults(None).await.unwrap();
+        assert_eq!(whitelisted.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_user_positions_default_empty() {
+        let api = mock();
+        let user = Address::repeat_byte(0x99);
+        let positions = api.get_user_vault_positions(user, None).await.unwrap();
+        assert_eq!(positions.address, Address::repeat_byte(0x99));
+        assert!(positions.vault_positions.is_empty());
+
+        let result = api
+            .get_user_account_overview(user, NamedChain::Mainnet)
+            .await;
+        assert!(matches!(result, Err(ApiError::GraphQL(msg)) if msg.contains("No results")));
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let api = mock();
+        let json = serde_json::to_string(&api).unwrap();
+        assert_eq!(MockMorphoApi::from_json(&json).unwrap(), api);
+        assert_eq!(
+            MockMorphoApi::from_json("{}").unwrap(),
+            MockMorphoApi::new()
+        );
+    }
+}
@@ -0,0 +1,686 @@
+//! Polling alerts on vault APY, TVL, and warnings.
+//!
+//! A [`VaultMonitor`] polls the vaults in a [`MonitorConfig`] and evaluates its
+//! [`WatchRule`]s against each new observation:
+//!
+//! - [`WatchRule::ApyDrop`]: a vault's net APY fell by at least `threshold` (as a
+//!   fraction, so `0.01` is one percentage point) since the previous poll.
+//! - [`WatchRule::TvlOutflow`]: a vault's total assets fell by at least `pct` (as a
+//!   fraction) from their highest point within the last `window_secs`. Assets are
+//!   compared in the vault's underlying token, so price moves do not trigger it.
+//! - [`WatchRule::NewRedWarning`]: a vault reports a red warning it did not report on
+//!   the previous poll.
+//!
+//! The first poll only records a baseline. [`VaultMonitor::run`] polls on the configured
+//! interval and sends each [`Alert`] through a Tokio channel;
+//! [`VaultMonitor::run_notifying`] instead delivers them to webhook, Slack, Discord, or
+//! stdout sinks (see [`crate::notify`]). [`VaultMonitor::poll`] performs a single poll for
+//! callers that schedule polling themselves.
+//!
+//! A vault that cannot be fetched is reported as a [`PollError`] and fetched again on the
+//! next poll, while the other vaults are still evaluated, so a transient API error never
+//! stops a running monitor.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::monitor::{MonitorConfig, VaultMonitor, WatchRule};
+//! use morpho_rs_api::reports::TrackedVault;
+//! use morpho_rs_api::{MorphoApiClient, NamedChain, VaultVersion};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let vault = "0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB".parse().unwrap();
+//!     let config = MonitorConfig::new()
+//!         .with_vault(TrackedVault {
+//!             address: vault,
+//!             chain: NamedChain::Mainnet,
+//!             version: VaultVersion::V1,
+//!         })
+//!         .with_rule(WatchRule::ApyDrop { vault, threshold: 0.01 })
+//!         .with_rule(WatchRule::TvlOutflow { pct: 0.2, window_secs: 3_600 })
+//!         .with_rule(WatchRule::NewRedWarning);
+//!
+//!     let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+//!     tokio::spawn(async move {
+//!         while let Some(alert) = rx.recv().await {
+//!             println!("{alert:?}");
+//!         }
+//!     });
+//!
+//!     let client = MorphoApiClient::new();
+//!     VaultMonitor::new(config)
+//!         .run(&client, tx, |e| eprintln!("{e}"))
+//!         .await;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use alloy_chains::NamedChain;
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::platform::unix_now;
+use crate::client::MorphoApiClient;
+use crate::error::ApiError;
+use crate::notify::{Notifier, SinkConfig};
+use crate::platform;
+use crate::reports::TrackedVault;
+use crate::types::chain::chain_serde;
+use crate::types::vault_v1::u256_to_f64;
+use crate::types::{VaultV1, VaultV2, VaultVersion, WarningType};
+
+/// Default seconds between polls (one minute).
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+fn default_poll_interval_secs() -> u64 {
+    DEFAULT_POLL_INTERVAL_SECS
+}
+
+/// A condition to alert on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WatchRule {
+    /// Net APY of `vault` fell by at least `threshold` since the previous poll.
+    ApyDrop {
+        /// The vault's contract address.
+        vault: Address,
+        /// Minimum drop in net APY (as a fraction).
+        threshold: f64,
+    },
+    /// Total assets of any watched vault fell by at least `pct` from their peak within
+    /// the last `window_secs`.
+    TvlOutflow {
+        /// Minimum outflow as a fraction of the peak.
+        pct: f64,
+        /// Look-back window in seconds.
+        window_secs: u64,
+    },
+    /// Any watched vault reports a red warning it did not report on the previous poll.
+    NewRedWarning,
+}
+
+/// Vaults to poll, rules to evaluate, and how often to poll.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    /// Vaults to poll.
+    #[serde(default)]
+    pub vaults: Vec<TrackedVault>,
+    /// Rules evaluated on every poll.
+    #[serde(default)]
+    pub rules: Vec<WatchRule>,
+    /// Seconds between polls in [`VaultMonitor::run`].
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Where [`VaultMonitor::run_notifying`] delivers alerts.
+    #[serde(default)]
+    pub notifications: Vec<SinkConfig>,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            vaults: Vec::new(),
+            rules: Vec::new(),
+            poll_interval_secs: DEFAULT_POLL_INTERVAL_SECS,
+            notifications: Vec::new(),
+        }
+    }
+}
+
+impl MonitorConfig {
+    /// Create an empty configuration with [`DEFAULT_POLL_INTERVAL_SECS`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a vault to poll.
+    pub fn with_vault(mut self, vault: TrackedVault) -> Self {
+        self.vaults.push(vault);
+        self
+    }
+
+    /// Add a rule.
+    pub fn with_rule(mut self, rule: WatchRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set the seconds between polls.
+    pub fn with_poll_interval_secs(mut self, secs: u64) -> Self {
+        self.poll_interval_secs = secs;
+        self
+    }
+
+    /// Add a notification sink.
+    pub fn with_notification(mut self, sink: SinkConfig) -> Self {
+        self.notifications.push(sink);
+        self
+    }
+}
+
+/// A triggered watch rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Alert {
+    /// Net APY fell by at least the rule's threshold.
+    ApyDrop {
+        /// The vault's contract address.
+        vault: Address,
+        /// The chain the vault is deployed on (serialized as chain ID).
+        #[serde(with = "chain_serde")]
+        chain: NamedChain,
+        /// Net APY at the previous poll (as a fraction).
+        previous: f64,
+        /// Net APY now (as a fraction).
+        current: f64,
+    },
+    /// Total assets fell by at least the rule's percentage within its window.
+    TvlOutflow {
+        /// The vault's contract address.
+        vault: Address,
+        /// The chain the vault is deployed on (serialized as chain ID).
+        #[serde(with = "chain_serde")]
+        chain: NamedChain,
+        /// Highest total assets within the window.
+        peak: U256,
+        /// Total assets now.
+        current: U256,
+        /// Outflow as a fraction of the peak.
+        outflow_pct: f64,
+    },
+    /// A red warning appeared.
+    NewRedWarning {
+        /// The vault's contract address.
+        vault: Address,
+        /// The chain the vault is deployed on (serialized as chain ID).
+        #[serde(with = "chain_serde")]
+        chain: NamedChain,
+        /// The new warning.
+        warning_type: WarningType,
+    },
+}
+
+impl Alert {
+    /// Address of the vault the alert refers to.
+    pub fn vault(&self) -> Address {
+        match self {
+            Self::ApyDrop { vault, .. }
+            | Self::TvlOutflow { vault, .. }
+            | Self::NewRedWarning { vault, .. } => *vault,
+        }
+    }
+
+    /// Chain of the vault the alert refers to.
+    pub fn chain(&self) -> NamedChain {
+        match self {
+            Self::ApyDrop { chain, .. }
+            | Self::TvlOutflow { chain, .. }
+            | Self::NewRedWarning { chain, .. } => *chain,
+        }
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ApyDrop {
+                vault,
+                chain,
+                previous,
+                current,
+            } => write!(
+                f,
+                "Net APY of vault {vault} on {chain} fell from {:.2}% to {:.2}%",
+                previous * 100.0,
+                current * 100.0
+            ),
+            Self::TvlOutflow {
+                vault,
+                chain,
+                peak,
+                current,
+                outflow_pct,
+            } => write!(
+                f,
+                "Total assets of vault {vault} on {chain} fell {:.1}% from their peak \
+                 ({peak} -> {current})",
+                outflow_pct * 100.0
+            ),
+            Self::NewRedWarning {
+                vault,
+                chain,
+                warning_type,
+            } => write!(
+                f,
+                "Vault {vault} on {chain} reports a new red warning: {warning_type}"
+            ),
+        }
+    }
+}
+
+/// The state of a vault at one poll.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultObservation {
+    /// The vault's contract address.
+    pub address: Address,
+    /// The chain the vault is deployed on (serialized as chain ID).
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// Unix timestamp of the poll in seconds.
+    pub timestamp: u64,
+    /// Net APY (as a fraction).
+    pub net_apy: f64,
+    /// Total assets in the underlying token.
+    pub total_assets: U256,
+    /// Types of the red warnings the vault reports.
+    pub red_warnings: Vec<WarningType>,
+}
+
+impl VaultObservation {
+    /// Observe a V1 vault at `timestamp`.
+    pub fn from_v1(vault: &VaultV1, timestamp: u64) -> Self {
+        let state = vault.state.as_ref();
+        Self {
+            address: vault.address,
+            chain: vault.chain,
+            timestamp,
+            net_apy: state.map(|s| s.net_apy).unwrap_or(0.0),
+            total_assets: state.map(|s| s.total_assets).unwrap_or(U256::ZERO),
+            red_warnings: vault
+                .warnings
+                .iter()
+                .filter(|w| w.is_red())
+                .map(|w| w.warning_type.clone())
+                .collect(),
+        }
+    }
+
+    /// Observe a V2 vault at `timestamp`.
+    pub fn from_v2(vault: &VaultV2, timestamp: u64) -> Self {
+        Self {
+            address: vault.address,
+            chain: vault.chain,
+            timestamp,
+            net_apy: vault.avg_net_apy.or(vault.net_apy).unwrap_or(0.0),
+            total_assets: vault.total_assets,
+            red_warnings: vault
+                .warnings
+                .iter()
+                .filter(|w| w.is_red())
+                .map(|w| w.warning_type.clone())
+                .collect(),
+        }
+    }
+}
+
+/// A watched vault that [`VaultMonitor::poll`] failed to fetch.
+#[derive(Debug)]
+pub struct PollError {
+    /// The vault that could not be fetched.
+    pub vault: TrackedVault,
+    /// Why it could not be fetched.
+    pub error: ApiError,
+}
+
+impl fmt::Display for PollError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to poll vault {} on {}: {}",
+            self.vault.address, self.vault.chain, self.error
+        )
+    }
+}
+
+/// A failure [`VaultMonitor::run_notifying`] recovers from.
+#[derive(Debug)]
+pub enum MonitorFailure<'a> {
+    /// A watched vault could not be fetched.
+    Poll(&'a PollError),
+    /// An alert could not be delivered to every sink.
+    Delivery {
+        /// The alert.
+        alert: &'a Alert,
+        /// The first delivery error.
+        error: &'a ApiError,
+    },
+}
+
+impl fmt::Display for MonitorFailure<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Poll(e) => e.fmt(f),
+            Self::Delivery { alert, error } => {
+                write!(f, "Failed to deliver alert ({alert}): {error}")
+            }
+        }
+    }
+}
+
+/// Outcome of one [`VaultMonitor::poll`].
+#[derive(Debug, Default)]
+pub struct PollReport {
+    /// Alerts triggered by the vaults that were fetched.
+    pub alerts: Vec<Alert>,
+    /// Vaults that could not be fetched. They are fetched again on the next poll.
+    pub errors: Vec<PollError>,
+}
+
+/// Polls vaults and evaluates [`WatchRule`]s against the observations.
+#[derive(Debug, Clone)]
+pub struct VaultMonitor {
+    config: MonitorConfig,
+    history: HashMap<(Address, NamedChain), VecDeque<VaultObservation>>,
+}
+
+impl VaultMonitor {
+    /// Create a monitor for the given configuration.
+    pub fn new(config: MonitorConfig) -> Self {
+        Self {
+            config,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Get the monitor configuration.
+    pub fn config(&self) -> &MonitorConfig {
+        &self.config
+    }
+
+    /// Fetch every watched vault once and return the alerts their states trigger.
+    ///
+    /// A vault that fails to fetch does not stop the others from being evaluated; its
+    /// error is returned in [`PollReport::errors`].
+    pub async fn poll(&mut self, client: &MorphoApiClient) -> PollReport {
+        let timestamp = unix_now();
+        let futures = self.config.vaults.iter().map(|tracked| async move {
+            let observation = match tracked.version {
+                VaultVersion::V1 => client
+                    .v1
+                    .get_vault(tracked.address, tracked.chain)
+                    .await
+                    .map(|v| VaultObservation::from_v1(&v, timestamp)),
+                VaultVersion::V2 => client
+                    .v2
+                    .get_vault(tracked.address, tracked.chain)
+                    .await
+                    .map(|v| VaultObservation::from_v2(&v, timestamp)),
+            };
+            observation.map_err(|error| PollError {
+                vault: tracked.clone(),
+                error,
+            })
+        });
+        let results = futures::future::join_all(futures).await;
+
+        let mut report = PollReport::default();
+        for result in results {
+            match result {
+                Ok(observation) => report.alerts.extend(self.observe(observation)),
+                Err(e) => report.errors.push(e),
+            }
+        }
+        report
+    }
+
+    /// Poll on the configured interval, sending alerts to `alerts`.
+    ///
+    /// Vaults that fail to fetch are passed to `on_error` and polled again next time.
+    /// Returns once the receiver is dropped.
+    pub async fn run<F>(
+        &mut self,
+        client: &MorphoApiClient,
+        alerts: mpsc::Sender<Alert>,
+        mut on_error: F,
+    ) where
+        F: FnMut(&PollError),
+    {
+        let interval = std::time::Duration::from_secs(self.config.poll_interval_secs.max(1));
+        loop {
+            let report = self.poll(client).await;
+            report.errors.iter().for_each(&mut on_error);
+            for alert in report.alerts {
+                if alerts.send(alert).await.is_err() {
+                    return;
+                }
+            }
+            if alerts.is_closed() {
+                return;
+            }
+            platform::sleep(interval).await;
+        }
+    }
+
+    /// Poll on the configured interval, delivering alerts to every sink of `notifier`.
+    ///
+    /// Vaults that fail to fetch and alerts that fail to deliver are passed to
+    /// `on_failure` and do not stop the monitor, so this only returns if the future is
+    /// dropped.
+    pub async fn run_notifying<F>(
+        &mut self,
+        client: &MorphoApiClient,
+        notifier: &Notifier,
+        mut on_failure: F,
+    ) where
+        F: FnMut(MonitorFailure<'_>),
+    {
+        let interval = std::time::Duration::from_secs(self.config.poll_interval_secs.max(1));
+        loop {
+            let report = self.poll(client).await;
+            for e in &report.errors {
+                on_failure(MonitorFailure::Poll(e));
+            }
+            for alert in &report.alerts {
+                if let Err(error) = notifier.notify(alert).await {
+                    on_failure(MonitorFailure::Delivery {
+                        alert,
+                        error: &error,
+                    });
+                }
+            }
+            platform::sleep(interval).await;
+        }
+    }
+
+    /// Record an observation and return the alerts it triggers.
+    ///
+    /// Observations older than the longest [`WatchRule::TvlOutflow`] window are
+    /// discarded.
+    pub fn observe(&mut self, observation: VaultObservation) -> Vec<Alert> {
+        let key = (observation.address, observation.chain);
+        let history = self.history.entry(key).or_default();
+
+        let mut alerts = Vec::new();
+        if let Some(previous) = history.back() {
+            for rule in &self.config.rules {
+                match rule {
+                    WatchRule::ApyDrop { vault, threshold } => {
+                        if *vault == observation.address
+                            && previous.net_apy - observation.net_apy >= *threshold
+                        {
+                            alerts.push(Alert::ApyDrop {
+                                vault: observation.address,
+                                chain: observation.chain,
+                                previous: previous.net_apy,
+                                current: observation.net_apy,
+                            });
+                        }
+                    }
+                    WatchRule::TvlOutflow { pct, window_secs } => {
+                        let since = observation.timestamp.saturating_sub(*window_secs);
+                        let peak = history
+                            .iter()
+                            .filter(|o| o.timestamp >= since)
+                            .map(|o| o.total_assets)
+                            .max()
+                            .unwrap_or(U256::ZERO);
+                        if peak > observation.total_assets {
+                            let outflow_pct = u256_to_f64(peak - observation.total_assets)
+                                / u256_to_f64(peak);
+                            if outflow_pct >= *pct {
+                                alerts.push(Alert::TvlOutflow {
+                                    vault: observation.address,
+                                    chain: observation.chain,
+                                    peak,
+                                    current: observation.total_assets,
+                                    outflow_pct,
+                                });
+                            }
+                        }
+                    }
+                    WatchRule::NewRedWarning => {
+                        for warning_type in &observation.red_warnings {
+                            if !previous.red_warnings.contains(warning_type) {
+                                alerts.push(Alert::NewRedWarning {
+                                    vault: observation.address,
+                                    chain: observation.chain,
+                                    warning_type: warning_type.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let retention = self
+            .config
+            .rules
+            .iter()
+            .filter_map(|rule| match rule {
+                WatchRule::TvlOutflow { window_secs, .. } => Some(*window_secs),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let since = observation.timestamp.saturating_sub(retention);
+        history.push_back(observation);
+        while history.len() > 1 && history.front().is_some_and(|o| o.timestamp < since) {
+            history.pop_front();
+        }
+
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT: Address = Address::repeat_byte(0x11);
+
+    fn observation(timestamp: u64, net_apy: f64, total_assets: u64) -> VaultObservation {
+        VaultObservation {
+            address: VAULT,
+            chain: NamedChain::Mainnet,
+            timestamp,
+            net_apy,
+            total_assets: U256::from(total_assets),
+            red_warnings: vec![],
+        }
+    }
+
+    fn monitor_with(rule: WatchRule) -> VaultMonitor {
+        VaultMonitor::new(MonitorConfig::new().with_rule(rule))
+    }
+
+    #[test]
+    fn test_first_observation_is_baseline() {
+        let mut monitor = monitor_with(WatchRule::NewRedWarning);
+        let mut first = observation(0, 0.05, 1_000);
+        first.red_warnings = vec![WarningType::BadDebtUnrealized];
+        assert!(monitor.observe(first).is_empty());
+    }
+
+    #[test]
+    fn test_apy_drop() {
+        let mut monitor = monitor_with(WatchRule::ApyDrop {
+            vault: VAULT,
+            threshold: 0.01,
+        });
+        assert!(monitor.observe(observation(0, 0.05, 1_000)).is_empty());
+        assert!(monitor.observe(observation(60, 0.045, 1_000)).is_empty());
+
+        let alerts = monitor.observe(observation(120, 0.03, 1_000));
+        assert_eq!(
+            alerts,
+            vec![Alert::ApyDrop {
+                vault: VAULT,
+                chain: NamedChain::Mainnet,
+                previous: 0.045,
+                current: 0.03,
+            }]
+        );
+
+        let mut other = monitor_with(WatchRule::ApyDrop {
+            vault: Address::repeat_byte(0x22),
+            threshold: 0.01,
+        });
+        other.observe(observation(0, 0.05, 1_000));
+        assert!(other.observe(observation(60, 0.0, 1_000)).is_empty());
+    }
+
+    #[test]
+    fn test_tvl_outflow_from_peak_within_window() {
+        let mut monitor = monitor_with(WatchRule::TvlOutflow {
+            pct: 0.2,
+            window_secs: 3_600,
+        });
+        monitor.observe(observation(0, 0.05, 1_000));
+        assert!(monitor.observe(observation(600, 0.05, 900)).is_empty());
+
+        let alerts = monitor.observe(observation(1_200, 0.05, 800));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].vault(), VAULT);
+        assert!(matches!(
+            alerts[0],
+            Alert::TvlOutflow { peak, outflow_pct, .. }
+                if peak == U256::from(1_000) && (outflow_pct - 0.2).abs() < 1e-9
+        ));
+
+        // The 1_000 peak has left the window; 900 -> 750 is a ~17% outflow
+        assert!(monitor.observe(observation(4_000, 0.05, 750)).is_empty());
+    }
+
+    #[test]
+    fn test_new_red_warning() {
+        let mut monitor = monitor_with(WatchRule::NewRedWarning);
+        monitor.observe(observation(0, 0.05, 1_000));
+
+        let mut with_warning = observation(60, 0.05, 1_000);
+        with_warning.red_warnings = vec![WarningType::BadDebtUnrealized];
+        let alerts = monitor.observe(with_warning.clone());
+        assert_eq!(
+            alerts,
+            vec![Alert::NewRedWarning {
+                vault: VAULT,
+                chain: NamedChain::Mainnet,
+                warning_type: WarningType::BadDebtUnrealized,
+            }]
+        );
+
+        with_warning.timestamp = 120;
+        assert!(monitor.observe(with_warning).is_empty());
+    }
+
+    #[test]
+    fn test_config_json_roundtrip() {
+        let config = MonitorConfig::new()
+            .with_rule(WatchRule::TvlOutflow {
+                pct: 0.1,
+                window_secs: 600,
+            })
+            .with_rule(WatchRule::NewRedWarning);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""kind":"tvl_outflow""#));
+        assert_eq!(serde_json::from_str::<MonitorConfig>(&json).unwrap(), config);
+
+        let defaults: MonitorConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(defaults.poll_interval_secs, DEFAULT_POLL_INTERVAL_SECS);
+    }
+}
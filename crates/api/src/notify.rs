@@ -0,0 +1,345 @@
+//! Notification sinks delivering monitor [`Alert`]s to operators.
+//!
+//! A [`NotificationSink`] delivers one alert. The built-in sinks are:
+//!
+//! - [`WebhookSink`]: POSTs the alert to a URL, formatted per [`WebhookFormat`] as the
+//!   alert's JSON, a Slack incoming-webhook message, or a Discord webhook message.
+//! - [`StdoutSink`]: prints the alert's JSON on its own line.
+//!
+//! A [`Notifier`] fans an alert out to several sinks. Sinks can be built in code or
+//! described by [`SinkConfig`]s, which is how [`MonitorConfig::notifications`] and config
+//! files list them. Webhooks are sent with the timeout, proxy, and root certificates of a
+//! [`ClientConfig`], so a hung endpoint cannot stall the monitor.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::monitor::{MonitorConfig, VaultMonitor, WatchRule};
+//! use morpho_rs_api::notify::{Notifier, SinkConfig};
+//! use morpho_rs_api::{ClientConfig, MorphoApiClient};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let config = MonitorConfig::new()
+//!         .with_rule(WatchRule::NewRedWarning)
+//!         .with_notification(SinkConfig::Slack {
+//!             url: "https://hooks.slack.com/services/T000/B000/XXXX".parse().unwrap(),
+//!         })
+//!         .with_notification(SinkConfig::Stdout);
+//!
+//!     let client_config = ClientConfig::new();
+//!     let notifier = Notifier::from_configs(&config.notifications, &client_config)?;
+//!     let client = MorphoApiClient::with_config(client_config);
+//!     VaultMonitor::new(config)
+//!         .run_notifying(&client, &notifier, |failure| eprintln!("{failure}"))
+//!         .await;
+//!     Ok(())
+//! }
+//! ```
+
+use std::fmt::Debug;
+use std::future;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::client::ClientConfig;
+use crate::error::{ApiError, Result};
+use crate::monitor::Alert;
+use crate::platform;
+
+/// Destination of monitor [`Alert`]s.
+pub trait NotificationSink: Debug + Send + Sync {
+    /// Deliver an alert.
+    fn notify<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Body a [`WebhookSink`] POSTs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// The alert serialized as JSON.
+    #[default]
+    Json,
+    /// A Slack incoming-webhook message (`{"text": ...}`).
+    Slack,
+    /// A Discord webhook message (`{"content": ...}`).
+    Discord,
+}
+
+impl WebhookFormat {
+    /// Build the request body for an alert.
+    pub fn payload(&self, alert: &Alert) -> Result<Value> {
+        Ok(match self {
+            Self::Json => {
+                serde_json::to_value(alert).map_err(|e| ApiError::Parse(e.to_string()))?
+            }
+            Self::Slack => json!({ "text": format!(":rotating_light: {alert}") }),
+            Self::Discord => json!({ "content": format!(":rotating_light: {alert}") }),
+        })
+    }
+}
+
+/// [`NotificationSink`] POSTing alerts to a webhook URL.
+///
+/// Non-success HTTP statuses and requests exceeding the client's timeout are returned as
+/// errors.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    client: Client,
+    url: Url,
+    format: WebhookFormat,
+}
+
+impl WebhookSink {
+    /// Create a sink POSTing alerts formatted as `format` to `url`, with the timeout,
+    /// proxy, and root certificates of `config`.
+    pub fn new(url: Url, format: WebhookFormat, config: &ClientConfig) -> Result<Self> {
+        Ok(Self {
+            client: platform::client_builder(config).build()?,
+            url,
+            format,
+        })
+    }
+
+    /// Use a custom HTTP client.
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// URL alerts are POSTed to.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Body format of the requests.
+    pub fn format(&self) -> WebhookFormat {
+        self.format
+    }
+
+    async fn post(&self, alert: &Alert) -> Result<()> {
+        let body = self.format.payload(alert)?;
+        self.client
+            .post(self.url.clone())
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.post(alert))
+    }
+}
+
+/// [`NotificationSink`] printing each alert as one line of JSON on stdout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+impl NotificationSink for StdoutSink {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        let result = serde_json::to_string(alert)
+            .map(|line| println!("{line}"))
+            .map_err(|e| ApiError::Parse(e.to_string()));
+        Box::pin(future::ready(result))
+    }
+}
+
+/// A notification sink described in configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// POST the alert's JSON to `url`.
+    Webhook {
+        /// Webhook URL.
+        url: Url,
+    },
+    /// POST a Slack message to an incoming-webhook `url`.
+    Slack {
+        /// Slack incoming-webhook URL.
+        url: Url,
+    },
+    /// POST a Discord message to a webhook `url`.
+    Discord {
+        /// Discord webhook URL.
+        url: Url,
+    },
+    /// Print the alert's JSON on stdout.
+    Stdout,
+}
+
+impl SinkConfig {
+    /// Build the described sink, sending webhooks with the HTTP settings of `config`.
+    pub fn build(&self, config: &ClientConfig) -> Result<Arc<dyn NotificationSink>> {
+        let webhook = |url: &Url, format| -> Result<Arc<dyn NotificationSink>> {
+            Ok(Arc::new(WebhookSink::new(url.clone(), format, config)?))
+        };
+        match self {
+            Self::Webhook { url } => webhook(url, WebhookFormat::Json),
+            Self::Slack { url } => webhook(url, WebhookFormat::Slack),
+            Self::Discord { url } => webhook(url, WebhookFormat::Discord),
+            Self::Stdout => Ok(Arc::new(StdoutSink)),
+        }
+    }
+}
+
+/// Delivers alerts to every one of its sinks.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    sinks: Vec<Arc<dyn NotificationSink>>,
+}
+
+impl Notifier {
+    /// Create a notifier without sinks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a notifier with the sinks described by `configs`, sending webhooks with
+    /// the HTTP settings of `config`.
+    pub fn from_configs(configs: &[SinkConfig], config: &ClientConfig) -> Result<Self> {
+        Ok(Self {
+            sinks: configs
+                .iter()
+                .map(|sink| sink.build(config))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Add a sink.
+    pub fn with_sink(mut self, sink: Arc<dyn NotificationSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// The notifier's sinks.
+    pub fn sinks(&self) -> &[Arc<dyn NotificationSink>] {
+        &self.sinks
+    }
+
+    /// Deliver an alert to every sink concurrently.
+    ///
+    /// A failing sink does not prevent delivery to the others; the first error is
+    /// returned once all deliveries have finished.
+    pub async fn notify(&self, alert: &Alert) -> Result<()> {
+        let results =
+            futures::future::join_all(self.sinks.iter().map(|sink| sink.notify(alert))).await;
+        results.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_chains::NamedChain;
+    use alloy_primitives::Address;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn alert() -> Alert {
+        Alert::ApyDrop {
+            vault: Address::repeat_byte(0x11),
+            chain: NamedChain::Mainnet,
+            previous: 0.05,
+            current: 0.03,
+        }
+    }
+
+    #[test]
+    fn test_payloads() {
+        let alert = alert();
+        let json = WebhookFormat::Json.payload(&alert).unwrap();
+        assert_eq!(json["kind"], "apy_drop");
+        assert_eq!(json["chain"], 1);
+
+        let message = format!(
+            ":rotating_light: Net APY of vault {} on mainnet fell from 5.00% to 3.00%",
+            Address::repeat_byte(0x11)
+        );
+        assert_eq!(
+            WebhookFormat::Slack.payload(&alert).unwrap(),
+            json!({ "text": message })
+        );
+        assert_eq!(
+            WebhookFormat::Discord.payload(&alert).unwrap(),
+            json!({ "content": message })
+        );
+    }
+
+    #[test]
+    fn test_sink_config_json() {
+        let configs: Vec<SinkConfig> = serde_json::from_str(
+            r#"[{"kind": "slack", "url": "https://hooks.slack.com/x"}, {"kind": "stdout"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            configs,
+            vec![
+                SinkConfig::Slack {
+                    url: "https://hooks.slack.com/x".parse().unwrap()
+                },
+                SinkConfig::Stdout,
+            ]
+        );
+        let notifier = Notifier::from_configs(&configs, &ClientConfig::new()).unwrap();
+        assert_eq!(notifier.sinks().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_notifier_delivers_to_every_sink() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/discord"))
+            .and(body_json(WebhookFormat::Discord.payload(&alert()).unwrap()))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = |p: &str| format!("{}{p}", server.uri()).parse().unwrap();
+        let config = ClientConfig::new();
+        let notifier = Notifier::new()
+            .with_sink(Arc::new(
+                WebhookSink::new(url("/down"), WebhookFormat::Json, &config).unwrap(),
+            ))
+            .with_sink(
+                SinkConfig::Discord {
+                    url: url("/discord"),
+                }
+                .build(&config)
+                .unwrap(),
+            );
+
+        let err = notifier.notify(&alert()).await.unwrap_err();
+        assert!(matches!(err, ApiError::Request(_)));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink_times_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(204).set_delay(std::time::Duration::from_secs(5)))
+            .mount(&server)
+            .await;
+
+        let config = ClientConfig::new().with_request_timeout_secs(1);
+        let sink =
+            WebhookSink::new(server.uri().parse().unwrap(), WebhookFormat::Json, &config).unwrap();
+        let err = sink.notify(&alert()).await.unwrap_err();
+        assert!(matches!(err, ApiError::Request(e) if e.is_timeout()));
+    }
+}
@@ -0,0 +1,688 @@
+//! Trustless vault simulation hydration from on-chain state.
+//!
+//! [`OnchainVaultSimulation::from_onchain`] builds a [`VaultSimulation`] for a V1
+//! (MetaMorpho) vault by reading the vault, its Morpho Blue markets, their IRMs, and
+//! their oracles over RPC at a given block, without going through the GraphQL API.
+//! This makes it possible to simulate against forks, archive blocks, or chains the API
+//! does not index.
+//!
+//! Vault and market state is batched through Multicall3. IRM and oracle reads are made
+//! per market and tolerate failures: markets whose IRM does not expose `rateAtTarget`
+//! or whose oracle reverts are hydrated without a rate at target or price.
+//!
+//! [`SnapshotClient`] wraps the same hydration for backtesting: it reconstructs a vault's
+//! simulation, with its block timestamp, at any past block an archive node can serve.
+//!
+//! [`check_simulation_prices`] cross-checks the oracle prices a simulation was hydrated
+//! with (e.g. from the API) against the markets' oracles on-chain, returning a
+//! [`PriceDeviation`] for each market whose price is off by more than a threshold.
+//!
+//! [`find_pre_liquidation`] detects whether a borrower has opted into a pre-liquidation
+//! contract, returning parameters ready for [`Market::pre_liquidate`].
+//!
+//! # Feature Flag
+//!
+//! This module is only available when the `sim` feature is enabled.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use alloy::providers::ProviderBuilder;
+//! use alloy::rpc::types::BlockId;
+//! use morpho_rs_api::onchain::OnchainVaultSimulation;
+//! use morpho_rs_sim::VaultSimulation;
+//!
+//! let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+//! let simulation = VaultSimulation::from_onchain(&provider, vault, BlockId::number(21_000_000)).await?;
+//! let apy = simulation.get_net_apy(timestamp)?;
+//! ```
+
+#![allow(async_fn_in_trait)]
+
+use std::collections::HashMap;
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::{Provider, RootProvider};
+use alloy::rpc::types::{BlockId, BlockNumberOrTag};
+use alloy_chains::NamedChain;
+use futures::future::join_all;
+use morpho_rs_contracts::erc20::IERC20;
+use morpho_rs_contracts::metamorpho::IMetaMorpho;
+use morpho_rs_contracts::morpho_blue::{IAdaptiveCurveIrm, IMorpho, IOracle};
+use morpho_rs_contracts::oracle::{check_oracle_prices, PriceDeviation, ReportedPrice};
+use morpho_rs_contracts::pre_liquidation::IPreLiquidation;
+use morpho_rs_contracts::public_allocator::bindings::IPublicAllocator;
+use morpho_rs_contracts::ContractError;
+use morpho_rs_sim::{
+    Market, PreLiquidationParams, PublicAllocatorConfig, PublicAllocatorMarketConfig,
+    RoundingDirection, Vault, VaultMarketConfig, VaultSimulation,
+};
+
+use crate::error::Result;
+
+/// Construct a simulation type from on-chain state.
+pub trait OnchainVaultSimulation: Sized {
+    /// Read a V1 vault's queues, caps, allocations, and market states at `block`.
+    ///
+    /// Allocations are the vault's Morpho Blue supply shares converted to assets
+    /// (rounded down, as the vault does). The public allocator configuration and flow
+    /// caps are read from the chain's [Public Allocator](crate::chains::ChainConfig)
+    /// when it is an allocator of the vault; they are left unset on chains without a
+    /// known deployment, or if the reads fail (e.g. `block` predates the deployment).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::Contract`](crate::ApiError::Contract) wrapping
+    /// [`ContractError::RpcConnection`] if a vault or Morpho Blue read fails (e.g. the
+    /// address is not a MetaMorpho vault, or the node has no state for `block`).
+    async fn from_onchain<P: Provider>(
+        provider: &P,
+        vault: Address,
+        block: BlockId,
+    ) -> Result<Self>;
+}
+
+impl OnchainVaultSimulation for VaultSimulation {
+    async fn from_onchain<P: Provider>(
+        provider: &P,
+        vault: Address,
+        block: BlockId,
+    ) -> Result<Self> {
+        let metamorpho = IMetaMorpho::new(vault, provider);
+
+        let (
+            morpho,
+            asset,
+            total_assets,
+            total_supply,
+            last_total_assets,
+            fee,
+            owner,
+            supply_queue_length,
+            withdraw_queue_length,
+        ) = provider
+            .multicall()
+            .add(metamorpho.MORPHO())
+            .add(metamorpho.asset())
+            .add(metamorpho.totalAssets())
+            .add(metamorpho.totalSupply())
+            .add(metamorpho.lastTotalAssets())
+            .add(metamorpho.fee())
+            .add(metamorpho.owner())
+            .add(metamorpho.supplyQueueLength())
+            .add(metamorpho.withdrawQueueLength())
+            .block(block)
+            .aggregate()
+            .await
+            .map_err(|e| read_error("vault state", e))?;
+
+        let asset_decimals = IERC20::new(asset, provider)
+            .decimals()
+            .call()
+            .block(block)
+            .await
+            .map_err(|e| read_error("asset decimals", e))?;
+
+        let mut supply_queue_calls = provider
+            .multicall()
+            .dynamic::<IMetaMorpho::supplyQueueCall>()
+            .block(block);
+        for i in 0..supply_queue_length.saturating_to::<u64>() {
+            supply_queue_calls =
+                supply_queue_calls.add_dynamic(metamorpho.supplyQueue(U256::from(i)));
+        }
+        let mut withdraw_queue_calls = provider
+            .multicall()
+            .dynamic::<IMetaMorpho::withdrawQueueCall>()
+            .block(block);
+        for i in 0..withdraw_queue_length.saturating_to::<u64>() {
+            withdraw_queue_calls =
+                withdraw_queue_calls.add_dynamic(metamorpho.withdrawQueue(U256::from(i)));
+        }
+        let (supply_queue, withdraw_queue): (Vec<B256>, Vec<B256>) = futures::try_join!(
+            supply_queue_calls.aggregate(),
+            withdraw_queue_calls.aggregate()
+        )
+        .map_err(|e| read_error("vault queues", e))?;
+
+        // Every market with a non-zero cap is in the withdraw queue.
+        let morpho_contract = IMorpho::new(morpho, provider);
+        let mut config_calls = provider
+            .multicall()
+            .dynamic::<IMetaMorpho::configCall>()
+            .block(block);
+        let mut market_calls = provider
+            .multicall()
+            .dynamic::<IMorpho::marketCall>()
+            .block(block);
+        let mut params_calls = provider
+            .multicall()
+            .dynamic::<IMorpho::idToMarketParamsCall>()
+            .block(block);
+        let mut position_calls = provider
+            .multicall()
+            .dynamic::<IMorpho::positionCall>()
+            .block(block);
+        for &id in &withdraw_queue {
+            config_calls = config_calls.add_dynamic(metamorpho.config(id));
+            market_calls = market_calls.add_dynamic(morpho_contract.market(id));
+            params_calls = params_calls.add_dynamic(morpho_contract.idToMarketParams(id));
+            position_calls = position_calls.add_dynamic(morpho_contract.position(id, vault));
+        }
+        let (configs, market_states, market_params, positions) = futures::try_join!(
+            config_calls.aggregate(),
+            market_calls.aggregate(),
+            params_calls.aggregate(),
+            position_calls.aggregate()
+        )
+        .map_err(|e| read_error("market state", e))?;
+
+        let rates_at_target = join_all(market_params.iter().zip(&withdraw_queue).map(
+            |(params, &id)| async move {
+                if params.irm == Address::ZERO {
+                    return None;
+                }
+                let rate = IAdaptiveCurveIrm::new(params.irm, provider)
+                    .rateAtTarget(id)
+                    .call()
+                    .block(block)
+                    .await
+                    .ok()?;
+                (!rate.is_negative()).then(|| rate.into_raw())
+            },
+        ));
+        let prices = join_all(market_params.iter().map(|params| async move {
+            if params.oracle == Address::ZERO {
+                return None;
+            }
+            IOracle::new(params.oracle, provider)
+                .price()
+                .call()
+                .block(block)
+                .await
+                .ok()
+        }));
+        let public_allocator = read_public_allocator(provider, vault, &withdraw_queue, block);
+        let (rates_at_target, prices, public_allocator) =
+            futures::join!(rates_at_target, prices, public_allocator);
+        let (public_allocator_config, flow_caps) = match public_allocator {
+            Some((config, flow_caps)) => (Some(config), flow_caps.into_iter().map(Some).collect()),
+            None => (None, vec![None; withdraw_queue.len()]),
+        };
+
+        let mut markets = HashMap::new();
+        let mut allocations = HashMap::new();
+        for (i, &id) in withdraw_queue.iter().enumerate() {
+            let state = &market_states[i];
+            let market = Market::new_with_oracle(
+                id,
+                U256::from(state.totalSupplyAssets),
+                U256::from(state.totalBorrowAssets),
+                U256::from(state.totalSupplyShares),
+                U256::from(state.totalBorrowShares),
+                state.lastUpdate as u64,
+                U256::from(state.fee),
+                rates_at_target[i],
+                prices[i],
+                market_params[i].lltv,
+            );
+            let supply_assets =
+                market.to_supply_assets(positions[i].supplyShares, RoundingDirection::Down);
+
+            allocations.insert(
+                id,
+                VaultMarketConfig {
+                    market_id: id,
+                    cap: U256::from(configs[i].cap),
+                    supply_assets,
+                    enabled: configs[i].enabled,
+                    public_allocator_config: flow_caps[i].clone(),
+                },
+            );
+            markets.insert(id, market);
+        }
+
+        let vault = Vault {
+            address: vault,
+            asset_decimals,
+            fee: U256::from(fee),
+            management_fee: U256::ZERO,
+            total_assets,
+            total_supply,
+            last_total_assets,
+            supply_queue,
+            withdraw_queue,
+            allocations,
+            owner,
+            public_allocator_config,
+        };
+
+        Ok(VaultSimulation::new(vault, markets))
+    }
+}
+
+/// A vault simulation reconstructed at a past block.
+#[derive(Debug, Clone)]
+pub struct VaultSnapshot {
+    /// Block the state was read at.
+    pub block: u64,
+    /// Timestamp of that block.
+    pub timestamp: u64,
+    /// The vault's simulation at that block.
+    pub simulation: VaultSimulation,
+}
+
+/// Client that reconstructs vault simulations at historical blocks.
+///
+/// Reads are pinned to the requested block, so the provider must be backed by an archive
+/// node (or a fork) for blocks outside the node's pruning window.
+///
+/// # Example
+///
+/// ```ignore
+/// use morpho_rs_api::onchain::SnapshotClient;
+///
+/// let client = SnapshotClient::new("https://eth.llamarpc.com")?;
+/// let snapshots = client.snapshots_between(vault, 21_000_000, 21_070_000, 7_200).await?;
+/// for snapshot in &snapshots {
+///     let apy = snapshot.simulation.get_net_apy(snapshot.timestamp)?;
+///     println!("{}: {:.2}%", snapshot.block, apy * 100.0);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnapshotClient<P = RootProvider> {
+    provider: P,
+}
+
+impl SnapshotClient {
+    /// Create a snapshot client reading from an HTTP RPC endpoint.
+    pub fn new(rpc_url: &str) -> Result<Self> {
+        let url: url::Url = rpc_url
+            .parse()
+            .map_err(|e| ContractError::RpcConnection(format!("{}", e)))?;
+        Ok(Self::with_provider(RootProvider::new_http(url)))
+    }
+}
+
+impl<P: Provider> SnapshotClient<P> {
+    /// Create a snapshot client from an existing provider.
+    pub fn with_provider(provider: P) -> Self {
+        Self { provider }
+    }
+
+    /// Get the underlying provider.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Reconstruct a vault's simulation at `block`.
+    pub async fn snapshot(&self, vault: Address, block: u64) -> Result<VaultSnapshot> {
+        let header = self
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Number(block))
+            .await
+            .map_err(|e| ContractError::RpcConnection(format!("Failed to get block: {}", e)))?
+            .ok_or_else(|| ContractError::RpcConnection(format!("Block {} not found", block)))?
+            .header;
+        let simulation =
+            VaultSimulation::from_onchain(&self.provider, vault, BlockId::number(block)).await?;
+
+        Ok(VaultSnapshot {
+            block,
+            timestamp: header.timestamp,
+            simulation,
+        })
+    }
+
+    /// Reconstruct a vault's simulation at each of `blocks`, in order.
+    ///
+    /// Snapshots are read one at a time to stay within archive node rate limits.
+    pub async fn snapshots(
+        &self,
+        vault: Address,
+        blocks: impl IntoIterator<Item = u64>,
+    ) -> Result<Vec<VaultSnapshot>> {
+        let mut snapshots = Vec::new();
+        for block in blocks {
+            snapshots.push(self.snapshot(vault, block).await?);
+        }
+        Ok(snapshots)
+    }
+
+    /// Reconstruct a vault's simulation every `step` blocks from `from_block` to
+    /// `to_block` (inclusive of both ends).
+    ///
+    /// A `step` of zero is treated as one.
+    pub async fn snapshots_between(
+        &self,
+        vault: Address,
+        from_block: u64,
+        to_block: u64,
+        step: u64,
+    ) -> Result<Vec<VaultSnapshot>> {
+        self.snapshots(vault, snapshot_blocks(from_block, to_block, step))
+            .await
+    }
+}
+
+/// Blocks sampled by [`SnapshotClient::snapshots_between`].
+fn snapshot_blocks(from_block: u64, to_block: u64, step: u64) -> Vec<u64> {
+    let mut blocks: Vec<u64> = (from_block..=to_block)
+        .step_by(step.max(1) as usize)
+        .collect();
+    if from_block <= to_block && blocks.last() != Some(&to_block) {
+        blocks.push(to_block);
+    }
+    blocks
+}
+
+/// Compare the oracle prices in `simulation` with its markets' oracles on-chain.
+///
+/// Market oracles are read from Morpho Blue (`morpho`). Markets without a price in the
+/// simulation, or without an oracle, are skipped. Returns the markets whose simulated
+/// price deviates from the on-chain price by more than `max_deviation` (relative, e.g.
+/// [`DEFAULT_MAX_PRICE_DEVIATION`](morpho_rs_contracts::oracle::DEFAULT_MAX_PRICE_DEVIATION)).
+///
+/// # Errors
+///
+/// Returns [`ApiError::Contract`](crate::ApiError::Contract) if a market or oracle read
+/// fails.
+pub async fn check_simulation_prices<P: Provider>(
+    provider: &P,
+    morpho: Address,
+    simulation: &VaultSimulation,
+    max_deviation: f64,
+    block: BlockId,
+) -> Result<Vec<PriceDeviation>> {
+    let priced: Vec<(B256, U256)> = simulation
+        .markets
+        .values()
+        .filter_map(|market| Some((market.id, market.price?)))
+        .collect();
+
+    let morpho_contract = IMorpho::new(morpho, provider);
+    let mut params_calls = provider
+        .multicall()
+        .dynamic::<IMorpho::idToMarketParamsCall>()
+        .block(block);
+    for &(id, _) in &priced {
+        params_calls = params_calls.add_dynamic(morpho_contract.idToMarketParams(id));
+    }
+    let market_params = params_calls
+        .aggregate()
+        .await
+        .map_err(|e| read_error("market params", e))?;
+
+    let reported: Vec<ReportedPrice> = priced
+        .iter()
+        .zip(&market_params)
+        .filter(|(_, params)| params.oracle != Address::ZERO)
+        .map(|(&(market_id, price), params)| ReportedPrice {
+            market_id,
+            oracle: params.oracle,
+            price,
+        })
+        .collect();
+
+    Ok(check_oracle_prices(provider, &reported, max_deviation, block).await?)
+}
+
+/// A pre-liquidation contract a borrower has authorized on Morpho Blue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreLiquidation {
+    /// Pre-liquidation contract.
+    pub address: Address,
+    /// Oracle the contract prices collateral with.
+    pub oracle: Address,
+    /// Parameters for [`Market::pre_liquidate`].
+    pub params: PreLiquidationParams,
+}
+
+/// Find the pre-liquidation contract `borrower` has opted into for a market.
+///
+/// `candidates` are pre-liquidation contracts to check, e.g. those listed by
+/// [`get_pre_liquidations`](morpho_rs_contracts::pre_liquidation::get_pre_liquidations).
+/// Candidates for other markets are ignored. A borrower has opted in when they
+/// authorized the contract on Morpho Blue (`morpho`); the first such candidate is
+/// returned, or `None` if the borrower has not opted into any.
+///
+/// # Errors
+///
+/// Returns [`ApiError::Contract`](crate::ApiError::Contract) if a candidate is not a
+/// pre-liquidation contract or a read fails.
+pub async fn find_pre_liquidation<P: Provider>(
+    provider: &P,
+    morpho: Address,
+    market_id: B256,
+    borrower: Address,
+    candidates: &[Address],
+    block: BlockId,
+) -> Result<Option<PreLiquidation>> {
+    let morpho_contract = IMorpho::new(morpho, provider);
+    let mut id_calls = provider
+        .multicall()
+        .dynamic::<IPreLiquidation::IDCall>()
+        .block(block);
+    let mut authorized_calls = provider
+        .multicall()
+        .dynamic::<IMorpho::isAuthorizedCall>()
+        .block(block);
+    for &candidate in candidates {
+        id_calls = id_calls.add_dynamic(IPreLiquidation::new(candidate, provider).ID());
+        authorized_calls =
+            authorized_calls.add_dynamic(morpho_contract.isAuthorized(borrower, candidate));
+    }
+    let (ids, authorized) = futures::try_join!(id_calls.aggregate(), authorized_calls.aggregate())
+        .map_err(|e| read_error("pre-liquidation authorizations", e))?;
+
+    let Some(i) = (0..candidates.len()).find(|&i| ids[i] == market_id && authorized[i]) else {
+        return Ok(None);
+    };
+    let params = IPreLiquidation::new(candidates[i], provider)
+        .preLiquidationParams()
+        .call()
+        .block(block)
+        .await
+        .map_err(|e| read_error("pre-liquidation params", e))?;
+
+    Ok(Some(PreLiquidation {
+        address: candidates[i],
+        oracle: params.preLiquidationOracle,
+        params: PreLiquidationParams {
+            pre_lltv: params.preLltv,
+            pre_lcf_1: params.preLCF1,
+            pre_lcf_2: params.preLCF2,
+            pre_lif_1: params.preLIF1,
+            pre_lif_2: params.preLIF2,
+        },
+    }))
+}
+
+/// Read the public allocator fee and the vault's flow caps in `market_ids`.
+///
+/// Returns `None` if the chain has no known Public Allocator, it is not an allocator of
+/// the vault, or a read fails.
+async fn read_public_allocator<P: Provider>(
+    provider: &P,
+    vault: Address,
+    market_ids: &[B256],
+    block: BlockId,
+) -> Option<(PublicAllocatorConfig, Vec<PublicAllocatorMarketConfig>)> {
+    let chain = NamedChain::try_from(provider.get_chain_id().await.ok()?).ok()?;
+    let address = crate::chains::chain_config(chain)?.public_allocator?;
+    let public_allocator = IPublicAllocator::new(address, provider);
+    let metamorpho = IMetaMorpho::new(vault, provider);
+
+    let mut flow_cap_calls = provider
+        .multicall()
+        .dynamic::<IPublicAllocator::flowCapsCall>()
+        .block(block);
+    for &id in market_ids {
+        flow_cap_calls = flow_cap_calls.add_dynamic(public_allocator.flowCaps(vault, id));
+    }
+    let config_calls = provider
+        .multicall()
+        .add(metamorpho.isAllocator(address))
+        .add(public_allocator.fee(vault))
+        .add(public_allocator.accruedFee(vault))
+        .block(block);
+    let ((is_allocator, fee, accrued_fee), flow_caps) =
+        futures::try_join!(config_calls.aggregate(), flow_cap_calls.aggregate()).ok()?;
+    if !is_allocator {
+        return None;
+    }
+
+    let flow_caps = flow_caps
+        .into_iter()
+        .map(|caps| PublicAllocatorMarketConfig {
+            max_in: U256::from(caps.maxIn),
+            max_out: U256::from(caps.maxOut),
+        })
+        .collect();
+    Some((PublicAllocatorConfig { fee, accrued_fee }, flow_caps))
+}
+
+fn read_error(what: &str, e: impl std::fmt::Display) -> ContractError {
+    ContractError::RpcConnection(format!("Failed to read {}: {}", what, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{Bytes, U64};
+    use alloy::providers::ProviderBuilder;
+    use alloy::sol_types::SolValue;
+    use alloy::transports::mock::Asserter;
+
+    #[test]
+    fn test_snapshot_blocks_includes_both_ends() {
+        assert_eq!(snapshot_blocks(100, 120, 10), vec![100, 110, 120]);
+        assert_eq!(snapshot_blocks(100, 125, 10), vec![100, 110, 120, 125]);
+    }
+
+    #[test]
+    fn test_snapshot_blocks_edge_cases() {
+        assert_eq!(snapshot_blocks(100, 100, 10), vec![100]);
+        assert_eq!(snapshot_blocks(100, 102, 0), vec![100, 101, 102]);
+        assert!(snapshot_blocks(120, 100, 10).is_empty());
+    }
+
+    fn aggregate(results: Vec<Vec<u8>>) -> Bytes {
+        let results: Vec<Bytes> = results.into_iter().map(Bytes::from).collect();
+        (U256::from(1), results).abi_encode_params().into()
+    }
+
+    /// Queue the responses `from_onchain` reads for a vault with one market whose IRM
+    /// and oracle are unset, on a chain without a known Public Allocator.
+    fn push_vault_with_one_market(asserter: &Asserter, id: B256) {
+        let morpho = Address::repeat_byte(0xBB);
+        let asset = Address::repeat_byte(0xAA);
+        asserter.push_success(&aggregate(vec![
+            morpho.abi_encode(),
+            asset.abi_encode(),
+            U256::from(1_000u64).abi_encode(),
+            U256::from(1_000_000u64).abi_encode(),
+            U256::from(990u64).abi_encode(),
+            U256::from(10u64).pow(U256::from(17)).abi_encode(),
+            Address::repeat_byte(0x0E).abi_encode(),
+            U256::from(1).abi_encode(),
+            U256::from(1).abi_encode(),
+        ]));
+        asserter.push_success(&Bytes::from(U256::from(6).abi_encode()));
+        asserter.push_success(&aggregate(vec![id.abi_encode()]));
+        asserter.push_success(&aggregate(vec![id.abi_encode()]));
+        // config(id): cap, enabled, removableAt
+        asserter.push_success(&aggregate(vec![
+            (U256::from(5_000u64), true, 0u64).abi_encode()
+        ]));
+        // market(id): supply assets/shares, borrow assets/shares, last update, fee
+        asserter.push_success(&aggregate(vec![(
+            2_000u128,
+            2_000_000_000u128,
+            500u128,
+            500_000_000u128,
+            1_700_000_000u128,
+            0u128,
+        )
+            .abi_encode()]));
+        // idToMarketParams(id), with no oracle or IRM
+        asserter.push_success(&aggregate(vec![(
+            asset,
+            Address::repeat_byte(0xCC),
+            Address::ZERO,
+            Address::ZERO,
+            U256::from(860_000_000_000_000_000u64),
+        )
+            .abi_encode()]));
+        // position(id, vault): supply shares, borrow shares, collateral
+        asserter.push_success(&aggregate(vec![(
+            U256::from(1_000_000_000u64),
+            0u128,
+            0u128,
+        )
+            .abi_encode()]));
+        // Anvil's chain ID, which has no Public Allocator
+        asserter.push_success(&U64::from(31337));
+    }
+
+    #[tokio::test]
+    async fn test_from_onchain_with_mocked_provider() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        let vault = Address::repeat_byte(0x11);
+        let id = B256::repeat_byte(0x01);
+        push_vault_with_one_market(&asserter, id);
+
+        let sim = VaultSimulation::from_onchain(&provider, vault, BlockId::latest())
+            .await
+            .unwrap();
+        assert_eq!(sim.vault.address, vault);
+        assert_eq!(sim.vault.asset_decimals, 6);
+        assert_eq!(sim.vault.total_assets, U256::from(1_000u64));
+        assert_eq!(sim.vault.last_total_assets, U256::from(990u64));
+        assert_eq!(sim.vault.fee, U256::from(10u64).pow(U256::from(17)));
+        assert_eq!(sim.vault.supply_queue, vec![id]);
+        assert_eq!(sim.vault.withdraw_queue, vec![id]);
+        assert!(sim.vault.public_allocator_config.is_none());
+
+        let allocation = &sim.vault.allocations[&id];
+        assert_eq!(allocation.cap, U256::from(5_000u64));
+        assert!(allocation.enabled);
+        // Half of the market's supply shares
+        assert_eq!(allocation.supply_assets, U256::from(1_000u64));
+        assert!(allocation.public_allocator_config.is_none());
+
+        let market = &sim.markets[&id];
+        assert_eq!(market.total_supply_assets, U256::from(2_000u64));
+        assert_eq!(market.total_borrow_assets, U256::from(500u64));
+        assert_eq!(market.last_update, 1_700_000_000);
+        assert_eq!(market.rate_at_target, None);
+        assert_eq!(market.price, None);
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_from_onchain_read_failure_is_retryable() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+        asserter.push_failure_msg("connection reset");
+
+        let result =
+            VaultSimulation::from_onchain(&provider, Address::repeat_byte(0x11), BlockId::latest())
+                .await;
+        let Err(crate::ApiError::Contract(error)) = result else {
+            panic!("expected a contract error");
+        };
+        assert!(matches!(error, ContractError::RpcConnection(_)));
+        assert!(error.is_retryable());
+    }
+
+    #[test]
+    fn test_snapshot_client_invalid_url() {
+        let result = SnapshotClient::new("not a valid url");
+        assert!(matches!(
+            result,
+            Err(crate::ApiError::Contract(ContractError::RpcConnection(_)))
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+//! Platform shims for native and `wasm32-unknown-unknown` targets.
+//!
+//! On wasm, `std::time::Instant`/`SystemTime` panic and there is no Tokio timer, so
+//! timing goes through `web-time` and retry delays through `gloo-timers`. reqwest's
+//! wasm backend (the browser `fetch` API) has no client-level timeout either, nor
+//! proxies, custom root certificates, or connection pooling settings.
+//!
+//! The crate's wall-clock helpers ([`unix_now`], [`format_timestamp`]) live here too.
+
+use std::time::Duration;
+
+use reqwest::ClientBuilder;
+
+use crate::client::ClientConfig;
+
+pub(crate) use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Future returned by the crate's pluggable backends (transports, execution backends,
+/// operation stores, audit sinks). It is `Send` on native targets; on wasm, alloy and
+/// reqwest futures are not, so it is a local future there.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use futures::future::BoxFuture;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use futures::future::LocalBoxFuture as BoxFuture;
+
+/// HTTP client builder with the configured timeout, proxy, root certificates, and pool
+/// limits.
+///
+/// The proxy and certificates can only be set through the [`ClientConfig`] setters that
+/// validate them, so converting them again here cannot fail.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn client_builder(config: &ClientConfig) -> ClientBuilder {
+    let mut builder =
+        reqwest::Client::builder().timeout(Duration::from_secs(config.request_timeout_secs));
+    if let Some(url) = config.proxy_url() {
+        let proxy = reqwest::Proxy::all(url.as_str()).expect("proxy URL validated by with_proxy");
+        builder = builder.proxy(proxy);
+    }
+    for pem in config.root_certificates_pem() {
+        let certificate = reqwest::Certificate::from_pem(pem)
+            .expect("root certificate validated by with_root_certificate_pem");
+        builder = builder.add_root_certificate(certificate);
+    }
+    if let Some(max_idle) = config.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if let Some(idle_timeout_secs) = config.pool_idle_timeout_secs {
+        builder = builder.pool_idle_timeout(Duration::from_secs(idle_timeout_secs));
+    }
+    builder
+}
+
+/// HTTP client builder; the browser controls timeouts, proxies, certificates, and pooling.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn client_builder(_config: &ClientConfig) -> ClientBuilder {
+    reqwest::Client::builder()
+}
+
+/// Sleep for `duration` without blocking the executor.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Sleep for `duration` without blocking the executor.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// Current unix time in seconds (0 if the clock is before the epoch).
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Format unix seconds as an RFC 3339 UTC timestamp (`YYYY-MM-DDTHH:MM:SSZ`).
+pub(crate) fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (hour, minute, second) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp_epoch() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_format_timestamp_leap_day() {
+        // 2024-02-29T12:34:56Z
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29T12:34:56Z");
+    }
+}
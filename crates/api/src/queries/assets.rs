@@ -0,0 +1,36 @@
+//! Asset GraphQL queries.
+
+use graphql_client::GraphQLQuery;
+
+/// Custom scalar type mappings for GraphQL.
+pub type Address = String;
+
+/// Query for fetching assets on a chain, optionally by symbol.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/assets.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetAssets;
+
+/// Query for fetching an asset by address with its current USD price.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/assets.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetAssetByAddress;
+
+/// Query for fetching an asset with its historical USD price.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/assets.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetAssetPriceHistory;
@@ -0,0 +1,13 @@
+//! Curator GraphQL queries.
+
+use graphql_client::GraphQLQuery;
+
+/// Query for fetching curators with their addresses on a chain.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/curators.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetCurators;
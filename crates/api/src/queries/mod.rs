@@ -1,9 +1,15 @@
 //! GraphQL query definitions.
 
+pub mod assets;
+pub mod curators;
+pub mod selection;
 pub mod user;
 pub mod v1;
 pub mod v2;
 
+pub use assets::{GetAssetPriceHistory, GetAssets};
+pub use curators::GetCurators;
+pub use selection::VaultFieldSelection;
 pub use user::{GetUserAccountOverview, GetUserVaultPositions};
-pub use v1::{GetVaultV1ByAddress, GetVaultsV1};
+pub use v1::{GetVaultV1ByAddress, GetVaultV1FeeInfo, GetVaultsV1};
 pub use v2::{GetVaultV2ByAddress, GetVaultsV2};
@@ -0,0 +1,556 @@
+//! Dynamically generated V1 vault queries with custom field selection.
+//!
+//! The generated [`GetVaultsV1`](super::GetVaultsV1) and
+//! [`GetVaultV1ByAddress`](super::GetVaultV1ByAddress) queries always fetch every vault
+//! field, including per-market state for each allocation. High-frequency pollers that
+//! only need a few fields can describe them with a [`VaultFieldSelection`] instead; the
+//! client builds a matching GraphQL document and deserializes the response into a
+//! partially-populated [`VaultV1`].
+//!
+//! Fields that are not selected keep their default values: `false` for flags, empty
+//! lists for allocators, warnings, allocations, and rewards, `None` for optional fields
+//! and market state, and zero for numbers. The vault address, name, symbol, chain, and
+//! asset are always selected.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::{NamedChain, VaultFieldSelection, VaultFiltersV1, VaultV1Client};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = VaultV1Client::new();
+//!     let filters = VaultFiltersV1::new().chain(NamedChain::Mainnet);
+//!
+//!     // Only totals and APYs
+//!     let vaults = client
+//!         .get_vaults_with_fields(Some(filters), &VaultFieldSelection::minimal())
+//!         .await?;
+//!
+//!     // Allocations plus warnings, without market state
+//!     let selection = VaultFieldSelection {
+//!         warnings: true,
+//!         ..VaultFieldSelection::with_allocations()
+//!     };
+//!     let address = "0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB".parse().unwrap();
+//!     let vault = client
+//!         .get_vault_with_fields(address, NamedChain::Mainnet, &selection)
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use alloy_primitives::B256;
+use serde::Deserialize;
+use std::str::FromStr;
+
+use crate::client::fee_to_wad;
+use crate::types::scalars::{parse_bigint, FlexBigInt};
+use crate::types::{
+    Asset, MarketStateV1, VaultAllocation, VaultAllocator, VaultReward, VaultStateV1, VaultV1,
+    VaultWarning, WarningLevel, WarningType,
+};
+
+/// Operation name of the generated vault list query.
+pub const VAULTS_OPERATION: &str = "GetVaultsV1Selection";
+
+/// Operation name of the generated single-vault query.
+pub const VAULT_BY_ADDRESS_OPERATION: &str = "GetVaultV1ByAddressSelection";
+
+/// Which V1 vault fields a generated query fetches.
+///
+/// Start from a preset and adjust individual groups with struct update syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VaultFieldSelection {
+    /// `listed`, `featured`, and `whitelisted` flags.
+    pub flags: bool,
+    /// State totals and APYs (`totalAssets`, `totalAssetsUsd`, `apy`, `netApy`).
+    pub state: bool,
+    /// Governance and share fields (`totalSupply`, `sharePrice`, `fee`, `timelock`,
+    /// `curator`, `owner`, `guardian`).
+    pub governance: bool,
+    /// Per-market allocations (implies `state`).
+    pub allocations: bool,
+    /// Market state and LLTV for each allocation (implies `allocations`).
+    pub market_state: bool,
+    /// Reward programs (implies `state`).
+    pub rewards: bool,
+    /// Allocator addresses.
+    pub allocators: bool,
+    /// Warning flags.
+    pub warnings: bool,
+}
+
+impl VaultFieldSelection {
+    /// Identity, asset, totals, and APYs.
+    pub fn minimal() -> Self {
+        Self {
+            state: true,
+            ..Self::default()
+        }
+    }
+
+    /// [`minimal`](Self::minimal) plus per-market allocations, without market state.
+    pub fn with_allocations() -> Self {
+        Self {
+            allocations: true,
+            ..Self::minimal()
+        }
+    }
+
+    /// Every field fetched by the fixed queries.
+    pub fn full() -> Self {
+        Self {
+            flags: true,
+            state: true,
+            governance: true,
+            allocations: true,
+            market_state: true,
+            rewards: true,
+            allocators: true,
+            warnings: true,
+        }
+    }
+
+    /// GraphQL selection set for a `Vault` object.
+    pub fn vault_fields(&self) -> String {
+        let mut fields = String::from(
+            "address name symbol chain { id } \
+             asset { address symbol name decimals priceUsd }",
+        );
+        if self.flags {
+            fields.push_str(" listed featured whitelisted");
+        }
+        if self.state || self.governance || self.allocations || self.market_state || self.rewards {
+            fields.push_str(" state { totalAssets totalAssetsUsd apy netApy");
+            if self.governance {
+                fields.push_str(" totalSupply sharePrice fee timelock curator owner guardian");
+            }
+            if self.rewards {
+                fields
+                    .push_str(" rewards { asset { address symbol } supplyApr yearlySupplyTokens }");
+            }
+            if self.allocations || self.market_state {
+                fields.push_str(
+                    " allocation { supplyAssets supplyAssetsUsd supplyCap enabled \
+                     supplyQueueIndex withdrawQueueIndex market { uniqueKey \
+                     loanAsset { address symbol } collateralAsset { address symbol }",
+                );
+                if self.market_state {
+                    fields.push_str(
+                        " lltv state { supplyAssets borrowAssets supplyShares borrowShares \
+                         timestamp fee rateAtTarget price } realizedBadDebt { underlying }",
+                    );
+                }
+                fields.push_str(" } }");
+            }
+            fields.push_str(" }");
+        }
+        if self.allocators {
+            fields.push_str(" allocators { address }");
+        }
+        if self.warnings {
+            fields.push_str(" warnings { type level }");
+        }
+        fields
+    }
+
+    /// Paginated vault list query, taking the same variables as
+    /// [`GetVaultsV1`](super::GetVaultsV1).
+    pub fn vaults_query(&self) -> String {
+        format!(
+            "query {VAULTS_OPERATION}($first: Int, $skip: Int, $where: VaultFilters, \
+             $orderBy: VaultOrderBy, $orderDirection: OrderDirection) {{ \
+             vaults(first: $first, skip: $skip, where: $where, orderBy: $orderBy, \
+             orderDirection: $orderDirection) {{ items {{ {} }} pageInfo {{ count countTotal }} }} }}",
+            self.vault_fields()
+        )
+    }
+
+    /// Single-vault query, taking the same variables as
+    /// [`GetVaultV1ByAddress`](super::GetVaultV1ByAddress).
+    pub fn vault_by_address_query(&self) -> String {
+        format!(
+            "query {VAULT_BY_ADDRESS_OPERATION}($address: String!, $chainId: Int!) {{ \
+             vaultByAddress(address: $address, chainId: $chainId) {{ {} }} }}",
+            self.vault_fields()
+        )
+    }
+}
+
+/// Response data of [`VaultFieldSelection::vaults_query`].
+#[derive(Debug, Deserialize)]
+pub(crate) struct SelectedVaultsData {
+    pub(crate) vaults: SelectedVaultsPage,
+}
+
+/// A page of vaults.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SelectedVaultsPage {
+    pub(crate) items: Option<Vec<SelectedVault>>,
+    pub(crate) page_info: Option<SelectedPageInfo>,
+}
+
+/// Pagination info.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SelectedPageInfo {
+    pub(crate) count_total: i64,
+}
+
+/// Response data of [`VaultFieldSelection::vault_by_address_query`].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SelectedVaultData {
+    pub(crate) vault_by_address: SelectedVault,
+}
+
+/// A vault with only the selected fields present.
+#[derive(Debug, Deserialize)]
+pub(crate) struct SelectedVault {
+    address: String,
+    name: String,
+    symbol: String,
+    chain: SelectedChain,
+    asset: SelectedAsset,
+    #[serde(default)]
+    listed: bool,
+    #[serde(default)]
+    featured: bool,
+    #[serde(default)]
+    whitelisted: bool,
+    #[serde(default)]
+    state: Option<SelectedState>,
+    #[serde(default)]
+    allocators: Vec<SelectedAllocator>,
+    #[serde(default)]
+    warnings: Vec<SelectedWarning>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectedChain {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SelectedAsset {
+    address: String,
+    symbol: String,
+    name: Option<String>,
+    decimals: f64,
+    price_usd: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SelectedState {
+    total_assets: FlexBigInt,
+    total_assets_usd: Option<f64>,
+    apy: f64,
+    net_apy: f64,
+    #[serde(default)]
+    total_supply: Option<FlexBigInt>,
+    #[serde(default)]
+    share_price: Option<FlexBigInt>,
+    #[serde(default)]
+    fee: Option<f64>,
+    #[serde(default)]
+    timelock: Option<FlexBigInt>,
+    #[serde(default)]
+    curator: Option<String>,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    guardian: Option<String>,
+    #[serde(default)]
+    rewards: Vec<SelectedReward>,
+    #[serde(default)]
+    allocation: Vec<SelectedAllocation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SelectedReward {
+    asset: SelectedToken,
+    supply_apr: Option<f64>,
+    yearly_supply_tokens: FlexBigInt,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SelectedAllocation {
+    market: SelectedMarket,
+    supply_assets: FlexBigInt,
+    supply_assets_usd: Option<f64>,
+    supply_cap: FlexBigInt,
+    enabled: bool,
+    supply_queue_index: Option<i64>,
+    withdraw_queue_index: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SelectedMarket {
+    unique_key: String,
+    loan_asset: SelectedToken,
+    collateral_asset: Option<SelectedToken>,
+    #[serde(default)]
+    lltv: Option<FlexBigInt>,
+    #[serde(default)]
+    state: Option<SelectedMarketState>,
+    #[serde(default)]
+    realized_bad_debt: Option<SelectedBadDebt>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SelectedMarketState {
+    supply_assets: FlexBigInt,
+    borrow_assets: FlexBigInt,
+    supply_shares: FlexBigInt,
+    borrow_shares: FlexBigInt,
+    timestamp: FlexBigInt,
+    fee: f64,
+    rate_at_target: Option<FlexBigInt>,
+    price: Option<FlexBigInt>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectedBadDebt {
+    underlying: FlexBigInt,
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectedToken {
+    address: String,
+    symbol: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectedAllocator {
+    address: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectedWarning {
+    #[serde(rename = "type")]
+    warning_type: String,
+    level: String,
+}
+
+impl SelectedVault {
+    /// Convert into a [`VaultV1`], leaving unselected fields at their defaults.
+    ///
+    /// Returns `None` if the address or asset cannot be parsed or the chain is
+    /// unsupported.
+    pub(crate) fn into_vault(self) -> Option<VaultV1> {
+        let asset = Asset::from_gql(
+            &self.asset.address,
+            self.asset.symbol,
+            self.asset.name,
+            self.asset.decimals,
+            self.asset.price_usd,
+        )?;
+        VaultV1::from_gql(
+            &self.address,
+            self.name,
+            self.symbol,
+            self.chain.id,
+            self.listed,
+            self.featured,
+            self.whitelisted,
+            asset,
+            self.state.and_then(SelectedState::into_state),
+            self.allocators
+                .iter()
+                .filter_map(|a| VaultAllocator::from_gql(&a.address))
+                .collect(),
+            self.warnings
+                .iter()
+                .map(|w| VaultWarning {
+                    warning_type: WarningType::from(w.warning_type.as_str()),
+                    level: WarningLevel::from(w.level.as_str()),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl SelectedState {
+    fn into_state(self) -> Option<VaultStateV1> {
+        VaultStateV1::from_gql(
+            self.curator.as_deref(),
+            self.owner.as_deref(),
+            self.guardian.as_deref(),
+            &self.total_assets,
+            self.total_assets_usd,
+            self.total_supply.as_deref().unwrap_or("0"),
+            self.fee.unwrap_or(0.0),
+            self.timelock.as_deref().unwrap_or("0"),
+            self.apy,
+            self.net_apy,
+            self.share_price.as_deref().unwrap_or("0"),
+            self.allocation
+                .into_iter()
+                .filter_map(SelectedAllocation::into_allocation)
+                .collect(),
+            self.rewards
+                .iter()
+                .filter_map(|r| {
+                    VaultReward::from_gql(
+                        &r.asset.address,
+                        r.asset.symbol.clone(),
+                        r.supply_apr,
+                        r.yearly_supply_tokens.parse::<f64>().ok(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl SelectedAllocation {
+    fn into_allocation(self) -> Option<VaultAllocation> {
+        let market = self.market;
+        let market_state = market_state(&market);
+        VaultAllocation::from_gql(
+            market.unique_key,
+            Some(market.loan_asset.symbol),
+            Some(market.loan_asset.address.as_str()),
+            market.collateral_asset.as_ref().map(|c| c.symbol.clone()),
+            market.collateral_asset.as_ref().map(|c| c.address.as_str()),
+            &self.supply_assets,
+            self.supply_assets_usd,
+            &self.supply_cap,
+            self.enabled,
+            self.supply_queue_index.map(|i| i as i32),
+            self.withdraw_queue_index.map(|i| i as i32),
+            market_state,
+        )
+    }
+}
+
+fn market_state(market: &SelectedMarket) -> Option<MarketStateV1> {
+    let ms = market.state.as_ref()?;
+    let total_supply_assets = parse_bigint(&ms.supply_assets)?;
+    let total_borrow_assets = parse_bigint(&ms.borrow_assets)?;
+    Some(MarketStateV1 {
+        id: B256::from_str(&market.unique_key).ok()?,
+        total_supply_assets,
+        total_borrow_assets,
+        total_supply_shares: parse_bigint(&ms.supply_shares)?,
+        total_borrow_shares: parse_bigint(&ms.borrow_shares)?,
+        last_update: ms.timestamp.parse().ok()?,
+        fee: fee_to_wad(ms.fee),
+        rate_at_target: ms.rate_at_target.as_deref().and_then(parse_bigint),
+        price: ms.price.as_deref().and_then(parse_bigint),
+        lltv: parse_bigint(market.lltv.as_deref()?)?,
+        liquidity: total_supply_assets.saturating_sub(total_borrow_assets),
+        realized_bad_debt: market
+            .realized_bad_debt
+            .as_ref()
+            .and_then(|b| parse_bigint(&b.underlying)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_minimal_fields() {
+        let fields = VaultFieldSelection::minimal().vault_fields();
+        assert!(fields.contains("state { totalAssets totalAssetsUsd apy netApy }"));
+        assert!(!fields.contains("allocation"));
+        assert!(!fields.contains("warnings"));
+        assert!(!fields.contains("listed"));
+    }
+
+    #[test]
+    fn test_with_allocations_fields() {
+        let fields = VaultFieldSelection::with_allocations().vault_fields();
+        assert!(fields.contains("allocation {"));
+        assert!(fields.contains("uniqueKey"));
+        assert!(!fields.contains("lltv"));
+        assert!(!fields.contains("borrowShares"));
+    }
+
+    #[test]
+    fn test_full_fields() {
+        let fields = VaultFieldSelection::full().vault_fields();
+        for field in [
+            "listed",
+            "sharePrice",
+            "rewards {",
+            "lltv state {",
+            "realizedBadDebt { underlying }",
+            "allocators { address }",
+            "warnings { type level }",
+        ] {
+            assert!(fields.contains(field), "missing {field}");
+        }
+    }
+
+    #[test]
+    fn test_braces_balanced() {
+        for selection in [
+            VaultFieldSelection::default(),
+            VaultFieldSelection::minimal(),
+            VaultFieldSelection::with_allocations(),
+            VaultFieldSelection::full(),
+        ] {
+            for query in [selection.vaults_query(), selection.vault_by_address_query()] {
+                let open = query.matches('{').count();
+                let close = query.matches('}').count();
+                assert_eq!(open, close, "{query}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_operation_names() {
+        let selection = VaultFieldSelection::minimal();
+        assert!(selection
+            .vaults_query()
+            .starts_with("query GetVaultsV1Selection("));
+        assert!(selection
+            .vault_by_address_query()
+            .starts_with("query GetVaultV1ByAddressSelection("));
+    }
+
+    #[test]
+    fn test_minimal_response_conversion() {
+        let json = r#"{
+            "address": "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+            "name": "Steakhouse USDC",
+            "symbol": "steakUSDC",
+            "chain": { "id": 1 },
+            "asset": {
+                "address": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "symbol": "USDC",
+                "name": "USD Coin",
+                "decimals": 6,
+                "priceUsd": 1.0
+            },
+            "state": {
+                "totalAssets": 1000000,
+                "totalAssetsUsd": 1.0,
+                "apy": 0.05,
+                "netApy": 0.045
+            }
+        }"#;
+        let vault: SelectedVault = serde_json::from_str(json).unwrap();
+        let vault = vault.into_vault().unwrap();
+        let state = vault.state.unwrap();
+        assert_eq!(state.total_assets, U256::from(1_000_000u64));
+        assert_eq!(state.net_apy, 0.045);
+        assert_eq!(state.timelock, 0);
+        assert!(state.curator.is_none());
+        assert!(state.allocation.is_empty());
+        assert!(vault.warnings.is_empty());
+        assert!(!vault.listed);
+    }
+}
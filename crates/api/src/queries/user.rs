@@ -29,3 +29,23 @@ pub struct GetUserVaultPositions;
     variables_derives = "Debug, Clone"
 )]
 pub struct GetUserAccountOverview;
+
+/// Query for fetching a user's transactions on a chain.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/user.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetUserTransactions;
+
+/// Query for fetching a user's V2 vault deposits and withdrawals on a chain.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/user.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetUserVaultV2Transactions;
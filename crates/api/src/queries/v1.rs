@@ -29,3 +29,43 @@ pub struct GetVaultsV1;
     variables_derives = "Debug, Clone"
 )]
 pub struct GetVaultV1ByAddress;
+
+/// Query for fetching a V1 vault's fee configuration and fee recipient.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/vaults_v1.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetVaultV1FeeInfo;
+
+/// Query for fetching a V1 vault's historical state over a time window.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/vaults_v1.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetVaultV1History;
+
+/// Query for fetching a V1 vault's share price and net APY history.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/vaults_v1.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetVaultV1SharePriceHistory;
+
+/// Query for fetching a market's state and a user's position in it.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/vaults_v1.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetUserMarketState;
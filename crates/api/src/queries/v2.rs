@@ -29,3 +29,13 @@ pub struct GetVaultsV2;
     variables_derives = "Debug, Clone"
 )]
 pub struct GetVaultV2ByAddress;
+
+/// Query for fetching a V2 vault's share price and net APY history.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/morpho.graphql",
+    query_path = "queries/vaults_v2.graphql",
+    response_derives = "Debug, Clone",
+    variables_derives = "Debug, Clone"
+)]
+pub struct GetVaultV2SharePriceHistory;
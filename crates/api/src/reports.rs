@@ -0,0 +1,641 @@
+//! Periodic structured reports of positions, vault APYs, fee accrual, and warnings.
+//!
+//! A [`ReportGenerator`] takes a [`ReportConfig`] (user addresses to track, vaults of
+//! interest, and a cadence) and produces a [`Report`] snapshot. When given the previous
+//! report, APY changes and fee accrual are computed over the elapsed period, so a cron
+//! job can run a single generation per invocation and persist the JSON output as state
+//! for the next run. [`ReportGenerator::run`] produces reports on the configured cadence
+//! for long-running processes.
+//!
+//! Reports render to JSON ([`Report::to_json`]) or markdown ([`Report::to_markdown`]).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::reports::{ReportConfig, ReportGenerator, TrackedVault};
+//! use morpho_rs_api::{MorphoApiClient, NamedChain, VaultVersion};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let config = ReportConfig {
+//!         users: vec!["0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".parse().unwrap()],
+//!         vaults: vec![TrackedVault {
+//!             address: "0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB".parse().unwrap(),
+//!             chain: NamedChain::Mainnet,
+//!             version: VaultVersion::V1,
+//!         }],
+//!         cadence_secs: 86_400,
+//!     };
+//!
+//!     let client = MorphoApiClient::new();
+//!     let report = ReportGenerator::new(config).generate(&client, None).await?;
+//!     println!("{}", report.to_markdown());
+//!     Ok(())
+//! }
+//! ```
+
+use alloy_chains::NamedChain;
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::platform::{format_timestamp, unix_now};
+use crate::client::MorphoApiClient;
+use crate::error::{ApiError, Result};
+use crate::platform;
+use crate::types::chain::chain_serde;
+use crate::types::{
+    UserVaultPositions, VaultV1, VaultV2, VaultVersion, WarningLevel, WarningType,
+};
+
+/// Seconds in a (365-day) year, used to pro-rate APYs over a report period.
+const SECONDS_PER_YEAR: f64 = 31_536_000.0;
+
+/// Default report cadence (one day).
+pub const DEFAULT_CADENCE_SECS: u64 = 86_400;
+
+fn default_cadence_secs() -> u64 {
+    DEFAULT_CADENCE_SECS
+}
+
+/// A vault to include in reports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackedVault {
+    /// The vault's contract address.
+    pub address: Address,
+    /// The chain the vault is deployed on (serialized as chain ID).
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// The vault version.
+    pub version: VaultVersion,
+}
+
+/// What to include in reports and how often to produce them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportConfig {
+    /// User addresses whose vault positions are reported (across all chains).
+    #[serde(default)]
+    pub users: Vec<Address>,
+    /// Vaults whose APY, fees, and warnings are reported.
+    #[serde(default)]
+    pub vaults: Vec<TrackedVault>,
+    /// Seconds between reports in scheduled mode.
+    #[serde(default = "default_cadence_secs")]
+    pub cadence_secs: u64,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            users: Vec::new(),
+            vaults: Vec::new(),
+            cadence_secs: DEFAULT_CADENCE_SECS,
+        }
+    }
+}
+
+/// Output format for rendered reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Pretty-printed JSON.
+    Json,
+    /// Markdown tables.
+    Markdown,
+}
+
+/// A vault warning included in a report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportWarning {
+    /// Warning type.
+    pub warning_type: WarningType,
+    /// Warning level.
+    pub level: WarningLevel,
+}
+
+/// Report entry for a tracked vault.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultReportEntry {
+    /// The vault's contract address.
+    pub address: Address,
+    /// The chain the vault is deployed on.
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// The vault version.
+    pub version: VaultVersion,
+    /// The vault's name.
+    pub name: String,
+    /// The underlying asset symbol.
+    pub asset_symbol: String,
+    /// Total assets in USD.
+    pub total_assets_usd: Option<f64>,
+    /// Gross APY (as a fraction).
+    pub apy: f64,
+    /// Net APY after fees (as a fraction).
+    pub net_apy: f64,
+    /// Change in net APY since the previous report (as a fraction).
+    pub net_apy_change: Option<f64>,
+    /// Performance fee (as a fraction).
+    pub performance_fee: f64,
+    /// Management fee (as a fraction, V2 only).
+    pub management_fee: f64,
+    /// Estimated fees accrued to the curator over the report period, in USD.
+    pub estimated_fee_accrual_usd: Option<f64>,
+    /// Active vault warnings.
+    pub warnings: Vec<ReportWarning>,
+}
+
+/// A single vault position in a user's report section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionReportEntry {
+    /// The vault's contract address.
+    pub vault_address: Address,
+    /// The vault's name.
+    pub vault_name: String,
+    /// The chain the vault is deployed on.
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// The vault version.
+    pub version: VaultVersion,
+    /// Position value in USD.
+    pub assets_usd: Option<f64>,
+    /// Profit and loss in USD.
+    pub pnl_usd: Option<f64>,
+}
+
+/// Report section for a tracked user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserReport {
+    /// The user's address.
+    pub address: Address,
+    /// Sum of position values in USD.
+    pub total_assets_usd: f64,
+    /// Sum of position PnL in USD.
+    pub total_pnl_usd: f64,
+    /// Change in total position value since the previous report, in USD.
+    pub total_assets_usd_change: Option<f64>,
+    /// Individual vault positions.
+    pub positions: Vec<PositionReportEntry>,
+}
+
+/// A point-in-time report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// Unix timestamp at which the report was generated.
+    pub generated_at: u64,
+    /// Seconds covered by this report (since the previous report, or the cadence).
+    pub period_secs: u64,
+    /// Tracked vaults.
+    pub vaults: Vec<VaultReportEntry>,
+    /// Tracked users.
+    pub users: Vec<UserReport>,
+}
+
+impl Report {
+    /// All warnings across tracked vaults, paired with the vault name.
+    pub fn warnings(&self) -> Vec<(&str, &ReportWarning)> {
+        self.vaults
+            .iter()
+            .flat_map(|v| v.warnings.iter().map(move |w| (v.name.as_str(), w)))
+            .collect()
+    }
+
+    /// Render the report as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// Parse a report previously rendered with [`Report::to_json`].
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// Render the report in the given format.
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Markdown => Ok(self.to_markdown()),
+        }
+    }
+
+    /// Render the report as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# Morpho Report\n\n");
+        out.push_str(&format!(
+            "Generated at {} (period: {})\n",
+            format_timestamp(self.generated_at),
+            format_duration(self.period_secs)
+        ));
+
+        if !self.vaults.is_empty() {
+            out.push_str("\n## Vaults\n\n");
+            out.push_str(
+                "| Vault | Chain | Version | TVL (USD) | Net APY | Change | Est. Fees (USD) | Warnings |\n",
+            );
+            out.push_str("|---|---|---|---|---|---|---|---|\n");
+            for v in &self.vaults {
+                out.push_str(&format!(
+                    "| {} ({}) | {} | {} | {} | {:.2}% | {} | {} | {} |\n",
+                    v.name,
+                    v.asset_symbol,
+                    v.chain.as_str(),
+                    v.version,
+                    format_usd(v.total_assets_usd),
+                    v.net_apy * 100.0,
+                    v.net_apy_change
+                        .map(|c| format!("{:+.2}pp", c * 100.0))
+                        .unwrap_or_else(|| "-".to_string()),
+                    format_usd(v.estimated_fee_accrual_usd),
+                    v.warnings.len(),
+                ));
+            }
+        }
+
+        for user in &self.users {
+            out.push_str(&format!("\n## Positions: {}\n\n", user.address));
+            if user.positions.is_empty() {
+                out.push_str("No positions.\n");
+                continue;
+            }
+            out.push_str("| Vault | Chain | Version | Assets (USD) | PnL (USD) |\n");
+            out.push_str("|---|---|---|---|---|\n");
+            for p in &user.positions {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    p.vault_name,
+                    p.chain.as_str(),
+                    p.version,
+                    format_usd(p.assets_usd),
+                    format_usd(p.pnl_usd),
+                ));
+            }
+            out.push_str(&format!(
+                "\nTotal: {} (PnL {})",
+                format_usd(Some(user.total_assets_usd)),
+                format_usd(Some(user.total_pnl_usd)),
+            ));
+            if let Some(change) = user.total_assets_usd_change {
+                out.push_str(&format!(", change since last report: {:+.2} USD", change));
+            }
+            out.push('\n');
+        }
+
+        let warnings = self.warnings();
+        if !warnings.is_empty() {
+            out.push_str("\n## Warnings\n\n");
+            for (vault, w) in warnings {
+                out.push_str(&format!(
+                    "- **{}**: {} ({})\n",
+                    vault, w.warning_type, w.level
+                ));
+            }
+        }
+
+        out
+    }
+}
+
+/// Produces [`Report`]s from a [`ReportConfig`].
+#[derive(Debug, Clone)]
+pub struct ReportGenerator {
+    config: ReportConfig,
+}
+
+impl ReportGenerator {
+    /// Create a generator for the given configuration.
+    pub fn new(config: ReportConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the report configuration.
+    pub fn config(&self) -> &ReportConfig {
+        &self.config
+    }
+
+    /// Generate a single report.
+    ///
+    /// If `previous` is given, APY and position value changes are computed against it
+    /// and fee accrual is estimated over the time elapsed since it was generated.
+    /// Otherwise fee accrual is estimated over one cadence period.
+    pub async fn generate(
+        &self,
+        client: &MorphoApiClient,
+        previous: Option<&Report>,
+    ) -> Result<Report> {
+        let generated_at = unix_now();
+        let period_secs = previous
+            .map(|p| generated_at.saturating_sub(p.generated_at))
+            .unwrap_or(self.config.cadence_secs);
+
+        let vault_futures = self.config.vaults.iter().map(|tracked| async move {
+            match tracked.version {
+                VaultVersion::V1 => client
+                    .v1
+                    .get_vault(tracked.address, tracked.chain)
+                    .await
+                    .map(|v| v1_entry(&v)),
+                VaultVersion::V2 => client
+                    .v2
+                    .get_vault(tracked.address, tracked.chain)
+                    .await
+                    .map(|v| v2_entry(&v)),
+            }
+        });
+        let mut vaults = futures::future::try_join_all(vault_futures).await?;
+
+        let user_futures = self
+            .config
+            .users
+            .iter()
+            .map(|user| client.get_user_vault_positions(*user, None));
+        let positions = futures::future::try_join_all(user_futures).await?;
+        let mut users: Vec<UserReport> = positions.iter().map(user_report).collect();
+
+        for entry in &mut vaults {
+            entry.estimated_fee_accrual_usd = estimate_fee_accrual(entry, period_secs);
+            entry.net_apy_change = previous
+                .and_then(|p| {
+                    p.vaults
+                        .iter()
+                        .find(|v| v.address == entry.address && v.chain == entry.chain)
+                })
+                .map(|prev| entry.net_apy - prev.net_apy);
+        }
+        for user in &mut users {
+            user.total_assets_usd_change = previous
+                .and_then(|p| p.users.iter().find(|u| u.address == user.address))
+                .map(|prev| user.total_assets_usd - prev.total_assets_usd);
+        }
+
+        Ok(Report {
+            generated_at,
+            period_secs,
+            vaults,
+            users,
+        })
+    }
+
+    /// Generate reports on the configured cadence, passing each to `on_report`.
+    ///
+    /// Each report is diffed against the one before it. Runs until `on_report`
+    /// returns an error or report generation fails.
+    pub async fn run<F>(&self, client: &MorphoApiClient, mut on_report: F) -> Result<()>
+    where
+        F: FnMut(&Report) -> Result<()>,
+    {
+        let cadence = std::time::Duration::from_secs(self.config.cadence_secs.max(1));
+        let mut previous: Option<Report> = None;
+        loop {
+            let report = self.generate(client, previous.as_ref()).await?;
+            on_report(&report)?;
+            previous = Some(report);
+            platform::sleep(cadence).await;
+        }
+    }
+}
+
+/// Estimate fees accrued to the curator over `period_secs`.
+///
+/// Performance fees are taken on interest (`TVL * gross APY * fee`) and management
+/// fees on assets (`TVL * fee`), both pro-rated over the period.
+fn estimate_fee_accrual(entry: &VaultReportEntry, period_secs: u64) -> Option<f64> {
+    let tvl = entry.total_assets_usd?;
+    let year_fraction = period_secs as f64 / SECONDS_PER_YEAR;
+    let annual_fees = tvl * (entry.apy * entry.performance_fee + entry.management_fee);
+    Some(annual_fees * year_fraction)
+}
+
+fn v1_entry(vault: &VaultV1) -> VaultReportEntry {
+    let state = vault.state.as_ref();
+    VaultReportEntry {
+        address: vault.address,
+        chain: vault.chain,
+        version: VaultVersion::V1,
+        name: vault.name.clone(),
+        asset_symbol: vault.asset.symbol.clone(),
+        total_assets_usd: state.and_then(|s| s.total_assets_usd),
+        apy: state.map(|s| s.apy).unwrap_or(0.0),
+        net_apy: state.map(|s| s.net_apy).unwrap_or(0.0),
+        net_apy_change: None,
+        performance_fee: state.map(|s| s.fee).unwrap_or(0.0),
+        management_fee: 0.0,
+        estimated_fee_accrual_usd: None,
+        warnings: vault
+            .warnings
+            .iter()
+            .map(|w| ReportWarning {
+                warning_type: w.warning_type.clone(),
+                level: w.level.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn v2_entry(vault: &VaultV2) -> VaultReportEntry {
+    VaultReportEntry {
+        address: vault.address,
+        chain: vault.chain,
+        version: VaultVersion::V2,
+        name: vault.name.clone(),
+        asset_symbol: vault.asset.symbol.clone(),
+        total_assets_usd: vault.total_assets_usd,
+        apy: vault.avg_apy.or(vault.apy).unwrap_or(0.0),
+        net_apy: vault.avg_net_apy.or(vault.net_apy).unwrap_or(0.0),
+        net_apy_change: None,
+        performance_fee: vault.performance_fee.unwrap_or(0.0),
+        management_fee: vault.management_fee.unwrap_or(0.0),
+        estimated_fee_accrual_usd: None,
+        warnings: vault
+            .warnings
+            .iter()
+            .map(|w| ReportWarning {
+                warning_type: w.warning_type.clone(),
+                level: w.level.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn user_report(positions: &UserVaultPositions) -> UserReport {
+    let mut entries: Vec<PositionReportEntry> = positions
+        .vault_positions
+        .iter()
+        .map(|p| PositionReportEntry {
+            vault_address: p.vault.address,
+            vault_name: p.vault.name.clone(),
+            chain: p.vault.chain,
+            version: VaultVersion::V1,
+            assets_usd: p.assets_usd,
+            pnl_usd: p.state.as_ref().and_then(|s| s.pnl_usd),
+        })
+        .collect();
+    entries.extend(
+        positions
+            .vault_v2_positions
+            .iter()
+            .map(|p| PositionReportEntry {
+                vault_address: p.vault.address,
+                vault_name: p.vault.name.clone(),
+                chain: p.vault.chain,
+                version: VaultVersion::V2,
+                assets_usd: p.assets_usd,
+                pnl_usd: p.pnl_usd,
+            }),
+    );
+
+    UserReport {
+        address: positions.address,
+        total_assets_usd: entries.iter().filter_map(|p| p.assets_usd).sum(),
+        total_pnl_usd: entries.iter().filter_map(|p| p.pnl_usd).sum(),
+        total_assets_usd_change: None,
+        positions: entries,
+    }
+}
+
+fn format_usd(value: Option<f64>) -> String {
+    match value {
+        Some(v) => format!("${:.2}", v),
+        None => "-".to_string(),
+    }
+}
+
+fn format_duration(secs: u64) -> String {
+    if secs >= 86_400 && secs.is_multiple_of(86_400) {
+        format!("{}d", secs / 86_400)
+    } else if secs >= 3600 {
+        format!("{:.1}h", secs as f64 / 3600.0)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_entry(net_apy: f64) -> VaultReportEntry {
+        VaultReportEntry {
+            address: Address::repeat_byte(0x11),
+            chain: NamedChain::Mainnet,
+            version: VaultVersion::V1,
+            name: "Test Vault".to_string(),
+            asset_symbol: "USDC".to_string(),
+            total_assets_usd: Some(1_000_000.0),
+            apy: 0.05,
+            net_apy,
+            net_apy_change: None,
+            performance_fee: 0.1,
+            management_fee: 0.0,
+            estimated_fee_accrual_usd: None,
+            warnings: vec![ReportWarning {
+                warning_type: WarningType::UnrecognizedOracle,
+                level: WarningLevel::Yellow,
+            }],
+        }
+    }
+
+    fn test_report() -> Report {
+        Report {
+            generated_at: 1_700_000_000,
+            period_secs: 86_400,
+            vaults: vec![test_entry(0.045)],
+            users: vec![UserReport {
+                address: Address::repeat_byte(0x22),
+                total_assets_usd: 1500.0,
+                total_pnl_usd: 25.0,
+                total_assets_usd_change: Some(10.0),
+                positions: vec![PositionReportEntry {
+                    vault_address: Address::repeat_byte(0x11),
+                    vault_name: "Test Vault".to_string(),
+                    chain: NamedChain::Mainnet,
+                    version: VaultVersion::V1,
+                    assets_usd: Some(1500.0),
+                    pnl_usd: Some(25.0),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_estimate_fee_accrual_performance_fee() {
+        let entry = test_entry(0.045);
+        // 1M * 5% * 10% = 5000/year -> one year period
+        let fees = estimate_fee_accrual(&entry, 31_536_000).unwrap();
+        assert!((fees - 5000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_estimate_fee_accrual_management_fee() {
+        let mut entry = test_entry(0.045);
+        entry.performance_fee = 0.0;
+        entry.management_fee = 0.02;
+        // 1M * 2% over half a year
+        let fees = estimate_fee_accrual(&entry, 15_768_000).unwrap();
+        assert!((fees - 10_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_estimate_fee_accrual_requires_tvl() {
+        let mut entry = test_entry(0.045);
+        entry.total_assets_usd = None;
+        assert!(estimate_fee_accrual(&entry, 86_400).is_none());
+    }
+
+    #[test]
+    fn test_report_json_roundtrip() {
+        let report = test_report();
+        let json = report.to_json().unwrap();
+        assert_eq!(Report::from_json(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn test_report_markdown_sections() {
+        let markdown = test_report().to_markdown();
+        assert!(markdown.starts_with("# Morpho Report"));
+        assert!(markdown.contains("Generated at 2023-11-14T22:13:20Z (period: 1d)"));
+        assert!(markdown.contains("| Test Vault (USDC) | mainnet | V1 | $1000000.00 | 4.50% |"));
+        assert!(markdown.contains("## Positions: 0x2222222222222222222222222222222222222222"));
+        assert!(markdown.contains("change since last report: +10.00 USD"));
+        assert!(markdown.contains("- **Test Vault**: unrecognized_oracle (YELLOW)"));
+    }
+
+    #[test]
+    fn test_report_warnings() {
+        let report = test_report();
+        let warnings = report.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].0, "Test Vault");
+    }
+
+    #[test]
+    fn test_report_config_defaults() {
+        let config: ReportConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.users.is_empty());
+        assert!(config.vaults.is_empty());
+        assert_eq!(config.cadence_secs, DEFAULT_CADENCE_SECS);
+    }
+
+    #[test]
+    fn test_report_config_parse() {
+        let json = r#"{
+            "users": ["0x2222222222222222222222222222222222222222"],
+            "vaults": [{
+                "address": "0x1111111111111111111111111111111111111111",
+                "chain": 8453,
+                "version": "V2"
+            }],
+            "cadence_secs": 3600
+        }"#;
+        let config: ReportConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.users, vec![Address::repeat_byte(0x22)]);
+        assert_eq!(config.vaults[0].chain, NamedChain::Base);
+        assert_eq!(config.vaults[0].version, VaultVersion::V2);
+        assert_eq!(config.cadence_secs, 3600);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(86_400), "1d");
+        assert_eq!(format_duration(7_200), "2.0h");
+        assert_eq!(format_duration(30), "30s");
+    }
+}
@@ -0,0 +1,196 @@
+//! Pluggable transport for GraphQL requests.
+//!
+//! By default the API clients POST GraphQL requests to [`ClientConfig::api_url`] over
+//! HTTP. Setting a [`Transport`] with [`ClientConfig::with_transport`] routes every
+//! GraphQL query through it instead, so code that depends on
+//! [`MorphoApiClient`](crate::MorphoApiClient) or the V1/V2 clients can be unit tested
+//! with canned responses. Retries, metrics, and response handling (GraphQL errors,
+//! missing data) behave exactly as they do over HTTP; authentication is skipped.
+//!
+//! [`FixtureClient`] is a ready-made transport that answers each operation with a
+//! GraphQL response loaded from memory or from a directory of JSON fixtures named after
+//! the operation (`GetVaultsV1.json`, `GetVaultV2ByAddress.json`, ...).
+//!
+//! The rewards API used by
+//! [`get_user_claimable_rewards`](crate::MorphoApiClient::get_user_claimable_rewards) is
+//! plain REST and always goes over HTTP.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use morpho_rs_api::{ClientConfig, FixtureClient, MorphoApiClient, NamedChain};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let fixtures = FixtureClient::from_dir("tests/fixtures/graphql");
+//!     let config = ClientConfig::new().with_transport(Arc::new(fixtures));
+//!     let client = MorphoApiClient::with_config(config);
+//!
+//!     // Answered from tests/fixtures/graphql/GetVaultsV1.json
+//!     let vaults = client.v1.get_vaults_by_chain(NamedChain::Mainnet).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use futures::future;
+use serde_json::Value;
+
+use crate::error::{ApiError, Result};
+use crate::platform::BoxFuture;
+
+/// Sends a GraphQL request and returns the raw GraphQL response.
+///
+/// `request` is the JSON request body (`query`, `variables`, `operationName`). The
+/// returned value is the full response object, with `data` and/or `errors`. Errors
+/// returned here are retried when [`ApiError::is_retryable`] is true.
+pub trait Transport: Debug + Send + Sync {
+    /// Execute the named operation.
+    fn execute<'a>(&'a self, operation: &'a str, request: Value) -> BoxFuture<'a, Result<Value>>;
+}
+
+/// A request received by a [`FixtureClient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// GraphQL operation name.
+    pub operation: String,
+    /// Request variables.
+    pub variables: Value,
+}
+
+/// [`Transport`] that answers GraphQL operations from JSON fixtures.
+///
+/// Responses registered with [`with_response`](Self::with_response) take precedence over
+/// files in the fixture directory. Every request is recorded and can be inspected with
+/// [`requests`](Self::requests).
+#[derive(Debug, Default)]
+pub struct FixtureClient {
+    #[cfg(not(target_arch = "wasm32"))]
+    dir: Option<PathBuf>,
+    responses: HashMap<String, Value>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl FixtureClient {
+    /// Create a fixture client with no responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a fixture client that loads `<dir>/<operation>.json` for each request.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Answer `operation` with a GraphQL response object.
+    pub fn with_response(mut self, operation: impl Into<String>, response: Value) -> Self {
+        self.responses.insert(operation.into(), response);
+        self
+    }
+
+    /// Answer `operation` with a GraphQL response parsed from a JSON string.
+    pub fn with_response_json(self, operation: impl Into<String>, json: &str) -> Result<Self> {
+        let response = serde_json::from_str(json).map_err(|e| ApiError::Parse(e.to_string()))?;
+        Ok(self.with_response(operation, response))
+    }
+
+    /// Answer `operation` with a GraphQL response loaded from a JSON file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_response_file(
+        self,
+        operation: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let response = read_fixture(path.as_ref())?;
+        Ok(self.with_response(operation, response))
+    }
+
+    /// Requests received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default()
+    }
+
+    fn response(&self, operation: &str) -> Result<Value> {
+        if let Some(response) = self.responses.get(operation) {
+            return Ok(response.clone());
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(dir) = &self.dir {
+            return read_fixture(&dir.join(format!("{operation}.json")));
+        }
+        Err(ApiError::Parse(format!(
+            "no fixture for operation {operation}"
+        )))
+    }
+}
+
+impl Transport for FixtureClient {
+    fn execute<'a>(&'a self, operation: &'a str, request: Value) -> BoxFuture<'a, Result<Value>> {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.push(RecordedRequest {
+                operation: operation.to_string(),
+                variables: request.get("variables").cloned().unwrap_or(Value::Null),
+            });
+        }
+        Box::pin(future::ready(self.response(operation)))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_fixture(path: &Path) -> Result<Value> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| ApiError::Parse(format!("{}: {}", path.display(), e)))?;
+    serde_json::from_str(&json).map_err(|e| ApiError::Parse(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_registered_response() {
+        let client = FixtureClient::new().with_response("GetVaultsV1", json!({ "data": {} }));
+        let response = client
+            .execute("GetVaultsV1", json!({ "variables": { "first": 10 } }))
+            .await
+            .unwrap();
+        assert_eq!(response, json!({ "data": {} }));
+        assert_eq!(
+            client.requests(),
+            vec![RecordedRequest {
+                operation: "GetVaultsV1".to_string(),
+                variables: json!({ "first": 10 }),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_fixture() {
+        let client = FixtureClient::new();
+        let err = client.execute("GetVaultsV1", json!({})).await.unwrap_err();
+        assert!(matches!(err, ApiError::Parse(_)));
+        assert_eq!(client.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        assert!(FixtureClient::new()
+            .with_response_json("GetVaultsV1", "not json")
+            .is_err());
+    }
+}
@@ -43,6 +43,35 @@ impl Asset {
     }
 }
 
+/// A USD price at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    /// Unix timestamp in seconds.
+    pub timestamp: u64,
+    /// Price in USD.
+    pub price_usd: f64,
+}
+
+/// An asset with its historical USD price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetPriceHistory {
+    /// The asset, with its current price.
+    pub asset: Asset,
+    /// Historical prices, oldest first. Points without a price are left out.
+    pub prices: Vec<PricePoint>,
+}
+
+impl AssetPriceHistory {
+    /// The latest price at or before `timestamp`, or `None` if the history starts later.
+    pub fn price_at(&self, timestamp: u64) -> Option<f64> {
+        self.prices
+            .iter()
+            .take_while(|p| p.timestamp <= timestamp)
+            .last()
+            .map(|p| p.price_usd)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,4 +90,33 @@ mod tests {
         assert_eq!(asset.symbol, "USDC");
         assert_eq!(asset.decimals, 6);
     }
+
+    #[test]
+    fn test_price_history_price_at() {
+        let history = AssetPriceHistory {
+            asset: Asset::from_gql(
+                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                "WETH".to_string(),
+                None,
+                18.0,
+                Some(3000.0),
+            )
+            .unwrap(),
+            prices: vec![
+                PricePoint {
+                    timestamp: 100,
+                    price_usd: 2000.0,
+                },
+                PricePoint {
+                    timestamp: 200,
+                    price_usd: 2500.0,
+                },
+            ],
+        };
+
+        assert_eq!(history.price_at(99), None);
+        assert_eq!(history.price_at(100), Some(2000.0));
+        assert_eq!(history.price_at(199), Some(2000.0));
+        assert_eq!(history.price_at(1_000), Some(2500.0));
+    }
 }
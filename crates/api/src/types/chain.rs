@@ -5,9 +5,12 @@
 
 use alloy_chains::NamedChain;
 
+use crate::error::ApiError;
+
 /// All chains supported by Morpho.
 ///
 /// This constant lists all the blockchain networks that Morpho vaults are deployed on.
+/// Every chain ID fits in a GraphQL `Int`, so each chain can be queried directly.
 pub const SUPPORTED_CHAINS: &[NamedChain] = &[
     NamedChain::Mainnet,
     NamedChain::Base,
@@ -36,6 +39,54 @@ pub const SUPPORTED_CHAINS: &[NamedChain] = &[
     NamedChain::Sepolia,
 ];
 
+/// Results of a query run separately on each chain.
+///
+/// Each chain succeeds or fails on its own, so one failing chain does not discard the
+/// results of the others.
+#[derive(Debug)]
+pub struct ChainResults<T> {
+    /// Results of the chains that succeeded, in query order.
+    pub results: Vec<(NamedChain, T)>,
+    /// Chains whose query failed, with the error.
+    pub errors: Vec<(NamedChain, ApiError)>,
+}
+
+impl<T> ChainResults<T> {
+    /// Returns true if every chain succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Chains whose query failed.
+    pub fn failed_chains(&self) -> impl Iterator<Item = NamedChain> + '_ {
+        self.errors.iter().map(|(chain, _)| *chain)
+    }
+}
+
+impl<T> ChainResults<Vec<T>> {
+    /// Merge the per-chain results into one list, discarding the errors.
+    pub fn flatten(self) -> Vec<T> {
+        self.results
+            .into_iter()
+            .flat_map(|(_, items)| items)
+            .collect()
+    }
+}
+
+impl<T> FromIterator<(NamedChain, Result<T, ApiError>)> for ChainResults<T> {
+    fn from_iter<I: IntoIterator<Item = (NamedChain, Result<T, ApiError>)>>(iter: I) -> Self {
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        for (chain, result) in iter {
+            match result {
+                Ok(value) => results.push((chain, value)),
+                Err(e) => errors.push((chain, e)),
+            }
+        }
+        Self { results, errors }
+    }
+}
+
 /// Try to create a NamedChain from a chain ID.
 ///
 /// This is a convenience wrapper around `NamedChain::try_from`.
@@ -104,6 +155,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_supported_chains_fit_graphql_int() {
+        for chain in SUPPORTED_CHAINS {
+            assert!(
+                u64::from(*chain) <= i32::MAX as u64,
+                "{chain} exceeds GraphQL Int"
+            );
+        }
+    }
+
+    #[test]
+    fn test_chain_results_collect() {
+        let results: ChainResults<Vec<u32>> = [
+            (NamedChain::Mainnet, Ok(vec![1, 2])),
+            (NamedChain::Base, Err(ApiError::Parse("bad".to_string()))),
+            (NamedChain::Polygon, Ok(vec![3])),
+        ]
+        .into_iter()
+        .collect();
+
+        assert!(!results.is_complete());
+        assert_eq!(
+            results.failed_chains().collect::<Vec<_>>(),
+            vec![NamedChain::Base]
+        );
+        assert_eq!(results.results[1].0, NamedChain::Polygon);
+        assert_eq!(results.flatten(), vec![1, 2, 3]);
+    }
+
     #[test]
     fn test_chain_serde_roundtrip() {
         use serde::{Deserialize, Serialize};
@@ -20,28 +20,48 @@
 //! `to_vault_simulation()` method that converts API data into a `VaultSimulation` for
 //! offline APY calculations and deposit/withdrawal simulations. This method builds
 //! supply/withdraw queues from the allocation data and converts the fee from the API's
-//! fractional format to WAD-scaled.
+//! fractional format to WAD-scaled. V2 vaults convert to a `VaultV2Simulation` instead,
+//! which keeps each adapter, the liquidity adapter, and the vault's caps.
 
 pub mod asset;
 pub mod chain;
 pub mod ordering;
+pub mod page;
+pub mod rewards;
 pub mod scalars;
+pub mod share_price;
+pub mod transaction;
 pub mod user;
 pub mod vault;
+pub mod vault_diff;
 pub mod vault_v1;
 pub mod vault_v2;
+pub mod warning;
 
 pub use alloy_chains::NamedChain;
-pub use asset::Asset;
-pub use chain::{chain_from_id, chain_serde, SUPPORTED_CHAINS};
-pub use ordering::{OrderDirection, VaultOrderByV1, VaultOrderByV2};
+pub use asset::{Asset, AssetPriceHistory, PricePoint};
+pub use chain::{chain_from_id, chain_serde, ChainResults, SUPPORTED_CHAINS};
+pub use ordering::{OrderDirection, VaultOrderByV1, VaultOrderByV2, VaultRankByV2};
+pub use page::Page;
+pub use rewards::ClaimableReward;
+pub use share_price::{HistoryPoint, SharePriceHistory, YieldRealization, YieldWindow};
+pub use transaction::{TransactionData, TransactionKind, UserTransaction, UserTransactionHistory};
 pub use user::{
-    MarketInfo, UserAccountOverview, UserMarketPosition, UserState, UserVaultPositions,
-    UserVaultV1Position, UserVaultV2Position, VaultInfo, VaultPositionState,
+    MarketInfo, UserAccountOverview, UserMarketPosition, UserMarketState, UserState,
+    UserVaultPositions, UserVaultV1Position, UserVaultV2Position, VaultInfo, VaultPositionState,
+};
+pub use vault::{
+    ApyBreakdown, CapRoom, DepositCapacity, DepositLimit, RewardApr, Vault, VaultVersion,
+    VAULT_SHARE_UNIT,
+};
+pub use vault_diff::{FieldChange, MarketChange, VaultDiff};
+pub use vault_v1::{
+    CapHeadroom, InstantLiquidity, MarketCapHeadroom, MarketInstantLiquidity, MarketStateV1,
+    PublicAllocatorFlowCap, VaultAllocation, VaultAllocator, VaultFeeInfo,
+    VaultPublicAllocatorConfig, VaultStateV1, VaultV1, VaultWarning,
 };
-pub use vault::{Vault, VaultVersion};
-pub use vault_v1::{MarketStateV1, VaultAllocation, VaultAllocator, VaultStateV1, VaultV1, VaultWarning};
 pub use vault_v2::{
     MarketStateV2, MetaMorphoAllocation, MorphoMarketPosition, VaultAdapter, VaultAdapterData,
-    VaultReward, VaultV2, VaultV2Warning,
+    VaultCap, VaultCapData, VaultReward, VaultV2, VaultV2Warning,
 };
+pub use warning::{WarningLevel, WarningType};
@@ -6,6 +6,8 @@ use crate::queries::v1::get_vaults_v1::{
 use crate::queries::v2::get_vaults_v2::{
     OrderDirection as OrderDirectionV2, VaultV2OrderBy as VaultV2OrderByGql,
 };
+use crate::types::vault_v2::VaultV2;
+use crate::types::warning::WarningLevel;
 
 /// Order direction for queries.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -140,3 +142,51 @@ impl VaultOrderByV2 {
         }
     }
 }
+
+/// Client-side ranking for V2 vaults on values the API cannot order by.
+///
+/// Set with [`VaultQueryOptionsV2::rank_by`](crate::VaultQueryOptionsV2::rank_by). Vaults
+/// are ranked after client-side filtering, so a limit returns the top vaults among those
+/// that match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultRankByV2 {
+    /// Rank by total APY: lending APY after fees plus reward APR.
+    TotalApy,
+    /// Rank by reward APR.
+    RewardsApr,
+    /// Rank by the share of total assets that is immediately withdrawable.
+    LiquidityRatio,
+    /// Rank by warning-based risk score: 10 per red warning and 1 per yellow warning.
+    RiskScore,
+}
+
+impl VaultRankByV2 {
+    /// The value a vault is ranked by.
+    pub fn value(self, vault: &VaultV2) -> f64 {
+        match self {
+            VaultRankByV2::TotalApy => vault.apy_breakdown().total_apy(),
+            VaultRankByV2::RewardsApr => vault.apy_breakdown().rewards_apr(),
+            VaultRankByV2::LiquidityRatio => vault.liquidity_ratio(),
+            VaultRankByV2::RiskScore => vault
+                .warnings
+                .iter()
+                .map(|w| match w.level {
+                    WarningLevel::Red => 10.0,
+                    WarningLevel::Yellow => 1.0,
+                    _ => 0.0,
+                })
+                .sum(),
+        }
+    }
+
+    /// Sort vaults by this value. Ties keep their existing order.
+    pub(crate) fn sort(self, vaults: &mut [VaultV2], direction: OrderDirection) {
+        vaults.sort_by(|a, b| {
+            let ordering = self.value(a).total_cmp(&self.value(b));
+            match direction {
+                OrderDirection::Asc => ordering,
+                OrderDirection::Desc => ordering.reverse(),
+            }
+        });
+    }
+}
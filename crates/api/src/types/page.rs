@@ -0,0 +1,64 @@
+//! A single page of query results.
+
+use serde::{Deserialize, Serialize};
+
+/// One page of results from a paginated query.
+///
+/// Pass [`next_cursor`](Self::next_cursor) back to the same query to fetch the next page.
+/// The cursor is the offset of the next item, so it stays valid across processes as long
+/// as the query options are unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items on this page.
+    pub items: Vec<T>,
+    /// Cursor of the next page, or `None` if this is the last page.
+    pub next_cursor: Option<i64>,
+    /// Total number of results matching the server-side filters, as reported by the API.
+    pub count_total: i64,
+}
+
+impl<T> Page<T> {
+    /// Build a page from the raw page size, offset, and total reported by the API.
+    ///
+    /// `fetched` is the number of items the API returned, before any client-side
+    /// filtering or skipped conversions.
+    pub(crate) fn new(
+        items: Vec<T>,
+        skip: i64,
+        fetched: i64,
+        first: i64,
+        count_total: i64,
+    ) -> Self {
+        let next = skip + fetched;
+        let next_cursor = (fetched > 0 && fetched >= first && next < count_total).then_some(next);
+        Self {
+            items,
+            next_cursor,
+            count_total,
+        }
+    }
+
+    /// Returns true if more pages follow.
+    pub fn has_more(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_next_cursor() {
+        let page = Page::new(vec![1, 2], 0, 2, 2, 5);
+        assert_eq!(page.next_cursor, Some(2));
+        assert!(page.has_more());
+
+        // Short page
+        assert_eq!(Page::new(vec![5], 4, 1, 2, 5).next_cursor, None);
+        // Full page reaching the total
+        assert_eq!(Page::new(vec![3, 4], 2, 2, 2, 4).next_cursor, None);
+        // Client-side filtering removed items, but the API returned a full page
+        assert_eq!(Page::new(Vec::<u8>::new(), 0, 2, 2, 5).next_cursor, Some(2));
+    }
+}
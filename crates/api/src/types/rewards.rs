@@ -0,0 +1,92 @@
+//! User reward types from the Morpho rewards API.
+
+use alloy_chains::NamedChain;
+use alloy_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+
+use super::chain::{chain_from_id, chain_serde};
+use super::scalars::{parse_address, parse_bigint};
+
+/// A reward a user can claim from a Universal Rewards Distributor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaimableReward {
+    /// Chain the distributor is deployed on.
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// Universal Rewards Distributor holding the reward.
+    pub distributor: Address,
+    /// Reward token address.
+    pub reward_token: Address,
+    /// Cumulative claimable amount in the distributor's Merkle tree (includes
+    /// amounts already claimed).
+    pub claimable: U256,
+    /// Merkle proof for `claimable`.
+    pub proof: Vec<B256>,
+}
+
+impl ClaimableReward {
+    /// Create a ClaimableReward from rewards API response fields.
+    ///
+    /// Returns `None` if the chain, an address, the amount, or a proof node is invalid.
+    pub fn from_api(
+        chain_id: i64,
+        distributor: &str,
+        reward_token: &str,
+        claimable: &str,
+        proof: &[String],
+    ) -> Option<Self> {
+        Some(Self {
+            chain: chain_from_id(chain_id)?,
+            distributor: parse_address(distributor)?,
+            reward_token: parse_address(reward_token)?,
+            claimable: parse_bigint(claimable)?,
+            proof: proof
+                .iter()
+                .map(|node| node.parse().ok())
+                .collect::<Option<Vec<B256>>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISTRIBUTOR: &str = "0x330eefa8a787552DC5cAd3C3cA644844B1E61Ddb";
+    const MORPHO_TOKEN: &str = "0x58D97B57BB95320F9a05dC918Aef65434969c2B2";
+    const NODE: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_claimable_reward_from_api() {
+        let reward = ClaimableReward::from_api(
+            1,
+            DISTRIBUTOR,
+            MORPHO_TOKEN,
+            "1500000000000000000",
+            &[NODE.to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(reward.chain, NamedChain::Mainnet);
+        assert_eq!(reward.distributor, parse_address(DISTRIBUTOR).unwrap());
+        assert_eq!(reward.claimable, U256::from(1_500_000_000_000_000_000u128));
+        assert_eq!(reward.proof, vec![B256::repeat_byte(0x11)]);
+    }
+
+    #[test]
+    fn test_claimable_reward_from_api_invalid() {
+        assert!(ClaimableReward::from_api(1, "bad", MORPHO_TOKEN, "1", &[]).is_none());
+        assert!(ClaimableReward::from_api(1, DISTRIBUTOR, MORPHO_TOKEN, "abc", &[]).is_none());
+        assert!(ClaimableReward::from_api(
+            1,
+            DISTRIBUTOR,
+            MORPHO_TOKEN,
+            "1",
+            &["0x12".to_string()]
+        )
+        .is_none());
+        assert!(
+            ClaimableReward::from_api(9999999999999, DISTRIBUTOR, MORPHO_TOKEN, "1", &[]).is_none()
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Vault share price history and realized yield.
+
+use std::ops::Range;
+
+use alloy_chains::NamedChain;
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use super::chain::chain_serde;
+
+/// Seconds in a 365-day year, used to annualize realized returns.
+const SECONDS_PER_YEAR: f64 = 31_536_000.0;
+
+/// A data point of a timeseries.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// Unix timestamp in seconds.
+    pub timestamp: u64,
+    /// Value at `timestamp`.
+    pub value: f64,
+}
+
+/// A vault's share price and advertised net APY over time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharePriceHistory {
+    /// The vault's contract address.
+    pub vault: Address,
+    /// The chain the vault is deployed on (serialized as chain ID).
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// Assets per share, oldest first. Only ratios between points are meaningful.
+    pub share_prices: Vec<HistoryPoint>,
+    /// Net APY the API reported, oldest first.
+    pub net_apys: Vec<HistoryPoint>,
+}
+
+/// Realized and advertised APY over one window of a [`SharePriceHistory`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct YieldWindow {
+    /// Start of the window (unix seconds).
+    pub start: u64,
+    /// End of the window (unix seconds).
+    pub end: u64,
+    /// Annualized return of the share price over the window.
+    pub realized_apy: f64,
+    /// Average net APY advertised over the window.
+    pub advertised_net_apy: f64,
+}
+
+impl YieldWindow {
+    /// Advertised minus realized APY; positive when the vault under-delivered.
+    pub fn shortfall(&self) -> f64 {
+        self.advertised_net_apy - self.realized_apy
+    }
+}
+
+/// Realized against advertised APY over consecutive windows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YieldRealization {
+    /// Windows with both share prices and an advertised APY, oldest first.
+    pub windows: Vec<YieldWindow>,
+    /// Shortfall above which a window counts as lagging.
+    pub tolerance: f64,
+}
+
+impl YieldRealization {
+    /// Windows whose realized APY fell short of the advertised one by more than the tolerance.
+    pub fn lagging_windows(&self) -> impl Iterator<Item = &YieldWindow> {
+        self.windows
+            .iter()
+            .filter(|w| w.shortfall() > self.tolerance)
+    }
+
+    /// Share of windows that lag, between 0 and 1 (0 without windows).
+    pub fn lag_fraction(&self) -> f64 {
+        if self.windows.is_empty() {
+            return 0.0;
+        }
+        self.lagging_windows().count() as f64 / self.windows.len() as f64
+    }
+
+    /// Average shortfall over all windows, or `None` without windows.
+    pub fn mean_shortfall(&self) -> Option<f64> {
+        (!self.windows.is_empty()).then(|| {
+            self.windows.iter().map(YieldWindow::shortfall).sum::<f64>()
+                / self.windows.len() as f64
+        })
+    }
+
+    /// Whether at least `min_fraction` of the windows lag.
+    ///
+    /// Always false without windows.
+    pub fn consistently_lags(&self, min_fraction: f64) -> bool {
+        !self.windows.is_empty() && self.lag_fraction() >= min_fraction
+    }
+}
+
+impl SharePriceHistory {
+    /// The latest share price at or before `timestamp`, or `None` if the history starts later.
+    pub fn share_price_at(&self, timestamp: u64) -> Option<f64> {
+        latest_at(&self.share_prices, timestamp)
+    }
+
+    /// Annualized (compounded) return of the share price between `range.start` and
+    /// `range.end`.
+    ///
+    /// Uses the latest share price at or before each end. Returns `None` if either price
+    /// is missing or not positive, or if the range is empty.
+    pub fn realized_apy(&self, range: Range<u64>) -> Option<f64> {
+        if range.end <= range.start {
+            return None;
+        }
+        let start = self.share_price_at(range.start)?;
+        let end = self.share_price_at(range.end)?;
+        if start <= 0.0 || end <= 0.0 {
+            return None;
+        }
+        let years = (range.end - range.start) as f64 / SECONDS_PER_YEAR;
+        Some((end / start).powf(1.0 / years) - 1.0)
+    }
+
+    /// Average advertised net APY over `range`.
+    ///
+    /// Averages the points inside the range, falling back to the latest point before it.
+    pub fn advertised_net_apy(&self, range: Range<u64>) -> Option<f64> {
+        let inside: Vec<f64> = self
+            .net_apys
+            .iter()
+            .filter(|p| range.contains(&p.timestamp))
+            .map(|p| p.value)
+            .collect();
+        if inside.is_empty() {
+            latest_at(&self.net_apys, range.start)
+        } else {
+            Some(inside.iter().sum::<f64>() / inside.len() as f64)
+        }
+    }
+
+    /// Compare realized and advertised APY over consecutive windows of `window_secs`,
+    /// starting at the first share price.
+    ///
+    /// A trailing window shorter than `window_secs` is left out, as are windows without
+    /// share prices or an advertised APY. A window lags when its
+    /// [`shortfall`](YieldWindow::shortfall) exceeds `tolerance`.
+    pub fn yield_realization(&self, window_secs: u64, tolerance: f64) -> YieldRealization {
+        let mut windows = Vec::new();
+        if let (Some(first), Some(last), true) = (
+            self.share_prices.first(),
+            self.share_prices.last(),
+            window_secs > 0,
+        ) {
+            let mut start = first.timestamp;
+            while start + window_secs <= last.timestamp {
+                let range = start..start + window_secs;
+                if let (Some(realized_apy), Some(advertised_net_apy)) = (
+                    self.realized_apy(range.clone()),
+                    self.advertised_net_apy(range.clone()),
+                ) {
+                    windows.push(YieldWindow {
+                        start: range.start,
+                        end: range.end,
+                        realized_apy,
+                        advertised_net_apy,
+                    });
+                }
+                start += window_secs;
+            }
+        }
+        YieldRealization { windows, tolerance }
+    }
+}
+
+/// The latest value at or before `timestamp` of a series sorted oldest first.
+fn latest_at(points: &[HistoryPoint], timestamp: u64) -> Option<f64> {
+    points
+        .iter()
+        .take_while(|p| p.timestamp <= timestamp)
+        .last()
+        .map(|p| p.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    fn point(timestamp: u64, value: f64) -> HistoryPoint {
+        HistoryPoint { timestamp, value }
+    }
+
+    /// Share price growing at `daily` per day for `days`, advertising `net_apy`.
+    fn history(days: u64, daily: f64, net_apy: f64) -> SharePriceHistory {
+        SharePriceHistory {
+            vault: Address::repeat_byte(0x11),
+            chain: NamedChain::Mainnet,
+            share_prices: (0..=days)
+                .map(|d| point(d * DAY, (1.0 + daily).powi(d as i32)))
+                .collect(),
+            net_apys: (0..=days).map(|d| point(d * DAY, net_apy)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_realized_apy() {
+        let history = history(365, 0.0001, 0.05);
+        let apy = history.realized_apy(0..365 * DAY).unwrap();
+        assert!((apy - (1.0001f64.powi(365) - 1.0)).abs() < 1e-9);
+
+        // Same daily growth annualizes to the same APY over a shorter window
+        let week = history.realized_apy(7 * DAY..14 * DAY).unwrap();
+        assert!((week - apy).abs() < 1e-9);
+
+        assert_eq!(history.realized_apy(DAY..DAY), None);
+        assert_eq!(history.share_price_at(DAY + 1), Some(1.0001));
+    }
+
+    #[test]
+    fn test_advertised_net_apy() {
+        let mut history = history(2, 0.0, 0.05);
+        history.net_apys = vec![point(0, 0.04), point(DAY, 0.06)];
+        assert_eq!(history.advertised_net_apy(0..2 * DAY), Some(0.05));
+        assert_eq!(history.advertised_net_apy(2 * DAY..3 * DAY), Some(0.06));
+    }
+
+    #[test]
+    fn test_yield_realization_flags_lagging_vaults() {
+        // ~3.7% realized against 5% advertised
+        let lagging = history(90, 0.0001, 0.05).yield_realization(7 * DAY, 0.005);
+        assert_eq!(lagging.windows.len(), 12);
+        assert_eq!(lagging.lag_fraction(), 1.0);
+        assert!(lagging.consistently_lags(0.75));
+        assert!(lagging.mean_shortfall().unwrap() > 0.01);
+
+        // ~5.1% realized against 5% advertised
+        let on_target = history(90, 0.000137, 0.05).yield_realization(7 * DAY, 0.005);
+        assert_eq!(on_target.lag_fraction(), 0.0);
+        assert!(!on_target.consistently_lags(0.75));
+
+        let empty = history(3, 0.0001, 0.05).yield_realization(7 * DAY, 0.005);
+        assert!(empty.windows.is_empty());
+        assert!(!empty.consistently_lags(0.0));
+        assert_eq!(empty.mean_shortfall(), None);
+    }
+}
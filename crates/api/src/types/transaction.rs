@@ -0,0 +1,250 @@
+//! User transaction history.
+
+use alloy_chains::NamedChain;
+use alloy_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+
+use super::chain::chain_serde;
+use super::scalars::parse_bigint;
+
+/// Kind of a user transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    /// Deposit into a vault.
+    VaultDeposit,
+    /// Withdrawal from a vault.
+    VaultWithdraw,
+    /// Transfer of V1 vault shares.
+    VaultTransfer,
+    /// Fee shares minted to a V1 vault's fee recipient.
+    VaultFee,
+    /// Supply of loan assets to a market.
+    MarketSupply,
+    /// Withdrawal of supplied loan assets from a market.
+    MarketWithdraw,
+    /// Borrow from a market.
+    MarketBorrow,
+    /// Repayment of a market borrow.
+    MarketRepay,
+    /// Collateral supplied to a market.
+    MarketSupplyCollateral,
+    /// Collateral withdrawn from a market.
+    MarketWithdrawCollateral,
+    /// Liquidation of a market borrow.
+    MarketLiquidation,
+}
+
+/// What a transaction moved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransactionData {
+    /// Vault shares and the assets they were worth.
+    Vault {
+        /// The vault's contract address.
+        vault: Address,
+        /// Vault shares minted, burned, or transferred.
+        shares: U256,
+        /// Assets deposited, withdrawn, or represented by the shares.
+        assets: U256,
+        /// `assets` in USD at the time of the transaction.
+        assets_usd: Option<f64>,
+    },
+    /// Supply or borrow shares of a market and their loan assets.
+    Market {
+        /// The market's unique key.
+        market: String,
+        /// Supply or borrow shares.
+        shares: U256,
+        /// Loan assets.
+        assets: U256,
+        /// `assets` in USD at the time of the transaction.
+        assets_usd: Option<f64>,
+    },
+    /// Collateral moved into or out of a market.
+    Collateral {
+        /// The market's unique key.
+        market: String,
+        /// Collateral assets.
+        assets: U256,
+        /// `assets` in USD at the time of the transaction.
+        assets_usd: Option<f64>,
+    },
+    /// Debt repaid and collateral seized by a liquidator.
+    Liquidation {
+        /// The market's unique key.
+        market: String,
+        /// Loan assets repaid.
+        repaid_assets: U256,
+        /// Borrow shares repaid.
+        repaid_shares: U256,
+        /// `repaid_assets` in USD at the time of the liquidation.
+        repaid_assets_usd: Option<f64>,
+        /// Collateral seized.
+        seized_assets: U256,
+        /// `seized_assets` in USD at the time of the liquidation.
+        seized_assets_usd: Option<f64>,
+        /// Loan assets realized as bad debt.
+        bad_debt_assets: U256,
+        /// The liquidator's address.
+        liquidator: Address,
+    },
+}
+
+/// A deposit, withdrawal, borrow, repayment, or liquidation involving a user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserTransaction {
+    /// Transaction hash.
+    pub hash: B256,
+    /// Unix timestamp of the block in seconds.
+    pub timestamp: u64,
+    /// Block number.
+    pub block_number: u64,
+    /// Index of the event's log in the block.
+    pub log_index: u64,
+    /// The chain the transaction happened on (serialized as chain ID).
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// Kind of the transaction.
+    pub kind: TransactionKind,
+    /// What the transaction moved.
+    pub data: TransactionData,
+}
+
+impl UserTransaction {
+    /// Convert GraphQL response fields into a [`UserTransaction`].
+    ///
+    /// Parses the hex `hash` into a [`B256`] and the bigint `timestamp` and
+    /// `block_number` into integers. Returns `None` if any of them cannot be parsed.
+    pub fn from_gql(
+        hash: &str,
+        timestamp: &str,
+        block_number: &str,
+        log_index: i64,
+        chain: NamedChain,
+        kind: TransactionKind,
+        data: TransactionData,
+    ) -> Option<Self> {
+        Some(UserTransaction {
+            hash: hash.parse().ok()?,
+            timestamp: u64::try_from(parse_bigint(timestamp)?).ok()?,
+            block_number: u64::try_from(parse_bigint(block_number)?).ok()?,
+            log_index: u64::try_from(log_index).ok()?,
+            chain,
+            kind,
+            data,
+        })
+    }
+}
+
+/// A user's transactions on a chain, with whether any are missing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserTransactionHistory {
+    /// Transactions, oldest first.
+    pub transactions: Vec<UserTransaction>,
+    /// Number of transactions returned by the API that could not be parsed.
+    pub skipped: usize,
+    /// Whether the history was cut off at the pagination limit.
+    pub truncated: bool,
+}
+
+impl UserTransactionHistory {
+    /// Whether no transaction was skipped or cut off.
+    pub fn is_complete(&self) -> bool {
+        self.skipped == 0 && !self.truncated
+    }
+
+    /// Add the transactions of `other`, keeping the history ordered oldest first.
+    pub fn merge(&mut self, other: UserTransactionHistory) {
+        self.transactions.extend(other.transactions);
+        self.transactions
+            .sort_by_key(|tx| (tx.timestamp, tx.block_number, tx.log_index));
+        self.skipped += other.skipped;
+        self.truncated |= other.truncated;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKET: &str = "0xmarket";
+
+    fn tx(block_number: u64, kind: TransactionKind, data: TransactionData) -> UserTransaction {
+        UserTransaction {
+            hash: B256::repeat_byte(block_number as u8),
+            timestamp: block_number * 12,
+            block_number,
+            log_index: 0,
+            chain: NamedChain::Mainnet,
+            kind,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let collateral = |block_number| {
+            let data = TransactionData::Collateral {
+                market: MARKET.to_string(),
+                assets: U256::from(1),
+                assets_usd: None,
+            };
+            tx(block_number, TransactionKind::MarketSupplyCollateral, data)
+        };
+        let mut history = UserTransactionHistory {
+            transactions: vec![collateral(1), collateral(3)],
+            ..Default::default()
+        };
+        assert!(history.is_complete());
+
+        history.merge(UserTransactionHistory {
+            transactions: vec![collateral(2)],
+            skipped: 1,
+            truncated: true,
+        });
+        let blocks: Vec<u64> = history
+            .transactions
+            .iter()
+            .map(|t| t.block_number)
+            .collect();
+        assert_eq!(blocks, [1, 2, 3]);
+        assert_eq!(history.skipped, 1);
+        assert!(history.truncated);
+        assert!(!history.is_complete());
+    }
+
+    #[test]
+    fn test_from_gql() {
+        let data = TransactionData::Collateral {
+            market: MARKET.to_string(),
+            assets: U256::from(1),
+            assets_usd: None,
+        };
+        let hash = format!("{}", B256::repeat_byte(0xab));
+        let tx = UserTransaction::from_gql(
+            &hash,
+            "1700000000",
+            "18000000",
+            3,
+            NamedChain::Base,
+            TransactionKind::MarketSupplyCollateral,
+            data.clone(),
+        )
+        .unwrap();
+        assert_eq!(tx.timestamp, 1_700_000_000);
+        assert_eq!(tx.block_number, 18_000_000);
+        assert_eq!(tx.log_index, 3);
+
+        assert!(UserTransaction::from_gql(
+            "0xnothex",
+            "1",
+            "1",
+            0,
+            NamedChain::Base,
+            TransactionKind::MarketSupplyCollateral,
+            data,
+        )
+        .is_none());
+    }
+}
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use super::chain::{chain_from_id, chain_serde};
 use super::scalars::{parse_address, parse_bigint};
+use super::vault_v1::MarketStateV1;
 
 /// Basic vault info for positions.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -122,6 +123,17 @@ pub struct UserMarketPosition {
     pub market: MarketInfo,
 }
 
+/// A user's position in a market together with the market's current state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserMarketState {
+    /// The user's address.
+    pub user: Address,
+    /// The user's position (all zero if the user has none).
+    pub position: UserMarketPosition,
+    /// The market's state.
+    pub market: MarketStateV1,
+}
+
 /// User's aggregated state across all positions.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserState {
@@ -348,6 +360,51 @@ impl UserMarketPosition {
     }
 }
 
+// Simulation conversion methods (only available with "sim" feature)
+#[cfg(feature = "sim")]
+mod sim_conversion {
+    use super::*;
+    use morpho_rs_sim::{Market, Position, PositionCapacities};
+
+    impl UserMarketState {
+        /// Convert the market state to a simulation [`Market`].
+        pub fn to_sim_market(&self) -> Market {
+            self.market.to_sim_market()
+        }
+
+        /// Convert the user's position to a simulation [`Position`].
+        pub fn to_sim_position(&self) -> Position {
+            Position::new(
+                self.user,
+                self.market.id,
+                self.position.supply_shares,
+                self.position.borrow_shares,
+                self.position.collateral,
+            )
+        }
+
+        /// Maximum amount of each market operation the user can perform, and what limits it.
+        ///
+        /// `loan_balance` and `collateral_balance` are the user's wallet balances of the
+        /// market's loan and collateral assets. See [`Position::get_capacities`].
+        ///
+        /// # Feature Flag
+        ///
+        /// This method is only available when the `sim` feature is enabled.
+        pub fn capacities(
+            &self,
+            loan_balance: U256,
+            collateral_balance: U256,
+        ) -> PositionCapacities {
+            self.to_sim_position().get_capacities(
+                &self.to_sim_market(),
+                loan_balance,
+                collateral_balance,
+            )
+        }
+    }
+}
+
 impl UserState {
     /// Convert GraphQL response fields into a [`UserState`].
     ///
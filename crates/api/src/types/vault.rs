@@ -1,10 +1,11 @@
 //! Unified vault abstraction for both V1 and V2 vaults.
 
 use alloy_chains::NamedChain;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, U256};
 use serde::{Deserialize, Serialize};
 
 use super::asset::Asset;
+use super::vault_v2::VaultReward;
 
 /// Vault version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -54,7 +55,7 @@ pub trait Vault: Send + Sync {
     fn net_apy(&self) -> f64;
     /// Returns the available liquidity.
     fn liquidity(&self) -> U256;
-    /// Returns whether the vault has any critical warnings.
+    /// Returns whether the vault has any critical (red) warnings.
     fn has_critical_warnings(&self) -> bool;
 
     /// Clones the vault into a boxed trait object.
@@ -67,6 +68,176 @@ impl Clone for Box<dyn Vault> {
     }
 }
 
+/// A vault's yield split into lending interest, incentives, and fees.
+///
+/// Returned by `VaultV1::apy_breakdown` and `VaultV2::apy_breakdown`. Reward APRs are
+/// paid in incentive tokens and typically end when the campaign does;
+/// [`native_apy`](Self::native_apy) is the part of the yield that comes from borrowers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApyBreakdown {
+    /// Lending APY from market interest, before fees (as a fraction).
+    pub base_apy: f64,
+    /// Incentive APR per reward token.
+    pub rewards: Vec<RewardApr>,
+    /// APY lost to vault fees (as a fraction).
+    pub fee_drag: f64,
+}
+
+/// Incentive APR paid in one reward token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewardApr {
+    /// Reward token address.
+    pub asset_address: Address,
+    /// Reward token symbol.
+    pub asset_symbol: String,
+    /// Supply APR from this token (as a fraction).
+    pub apr: f64,
+}
+
+impl ApyBreakdown {
+    /// Build a breakdown, keeping only rewards with a known APR.
+    pub(crate) fn new(base_apy: f64, rewards: &[VaultReward], fee_drag: f64) -> Self {
+        Self {
+            base_apy,
+            rewards: rewards
+                .iter()
+                .filter_map(|r| {
+                    Some(RewardApr {
+                        asset_address: r.asset_address,
+                        asset_symbol: r.asset_symbol.clone(),
+                        apr: r.supply_apr?,
+                    })
+                })
+                .collect(),
+            fee_drag,
+        }
+    }
+
+    /// Lending APY after fees, excluding rewards.
+    pub fn native_apy(&self) -> f64 {
+        self.base_apy - self.fee_drag
+    }
+
+    /// Sum of the reward APRs.
+    pub fn rewards_apr(&self) -> f64 {
+        self.rewards.iter().map(|r| r.apr).sum()
+    }
+
+    /// Native APY plus reward APR.
+    pub fn total_apy(&self) -> f64 {
+        self.native_apy() + self.rewards_apr()
+    }
+}
+
+/// Room left under one supply cap of a vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapRoom {
+    /// Market unique key for V1 vaults, cap id for V2 vaults.
+    pub id: B256,
+    /// Assets that can still be supplied under the cap.
+    pub room: U256,
+}
+
+/// What limits a deposit below the requested amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DepositLimit {
+    /// The vault's on-chain `maxDeposit` for the receiver.
+    MaxDeposit,
+    /// Room left under the vault's supply caps, per the API.
+    SupplyCaps,
+}
+
+/// How much of a deposit a vault can accept.
+///
+/// Returned by `VaultV1Operations::check_deposit` and `VaultV2Operations::check_deposit`.
+/// Cap room comes from the API snapshot, so deposits by others since then are not
+/// included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositCapacity {
+    /// Assets the caller wants to deposit.
+    pub requested: U256,
+    /// Assets the vault can accept (at most `requested`).
+    pub accepted: U256,
+    /// On-chain `maxDeposit`, if the vault reports a meaningful one.
+    pub max_deposit: Option<U256>,
+    /// Total room under the supply caps, or `None` if the caps do not limit deposits.
+    pub cap_room: Option<U256>,
+    /// Room under each cap a deposit is allocated through.
+    pub caps: Vec<CapRoom>,
+    /// The binding limit if `accepted` is below `requested`.
+    pub limited_by: Option<DepositLimit>,
+}
+
+impl DepositCapacity {
+    /// Combine the on-chain and cap limits for `requested` assets.
+    ///
+    /// When both limits bind equally, the on-chain `maxDeposit` is reported.
+    pub(crate) fn new(
+        requested: U256,
+        max_deposit: Option<U256>,
+        cap_room: Option<U256>,
+        caps: Vec<CapRoom>,
+    ) -> Self {
+        let max_deposit_limit = max_deposit.unwrap_or(U256::MAX);
+        let cap_limit = cap_room.unwrap_or(U256::MAX);
+        let limit = max_deposit_limit.min(cap_limit);
+        let limited_by = (limit < requested).then(|| {
+            if max_deposit_limit <= cap_limit {
+                DepositLimit::MaxDeposit
+            } else {
+                DepositLimit::SupplyCaps
+            }
+        });
+        Self {
+            requested,
+            accepted: requested.min(limit),
+            max_deposit,
+            cap_room,
+            caps,
+            limited_by,
+        }
+    }
+
+    /// Returns true if the whole requested amount can be deposited.
+    pub fn is_fully_accepted(&self) -> bool {
+        self.limited_by.is_none()
+    }
+}
+
+/// One whole vault share (vault shares always have 18 decimals).
+pub const VAULT_SHARE_UNIT: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// ERC-4626 share conversion from a vault's reported totals.
+///
+/// V1 and V2 vaults both add one virtual asset and `10^(18 - asset decimals)` virtual
+/// shares, and round conversions down, so this mirrors on-chain `convertToAssets` /
+/// `convertToShares` as of the API snapshot. Interest accrued since then is not
+/// included.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShareConversion {
+    pub(crate) total_assets: U256,
+    pub(crate) total_supply: U256,
+    pub(crate) asset_decimals: u8,
+}
+
+impl ShareConversion {
+    fn virtual_shares(&self) -> U256 {
+        U256::from(10).pow(U256::from(18u8.saturating_sub(self.asset_decimals)))
+    }
+
+    /// Assets redeemable for `shares`, rounded down.
+    pub(crate) fn to_assets(self, shares: U256) -> U256 {
+        shares.saturating_mul(self.total_assets + U256::from(1))
+            / (self.total_supply + self.virtual_shares())
+    }
+
+    /// Shares minted for `assets`, rounded down.
+    pub(crate) fn to_shares(self, assets: U256) -> U256 {
+        assets.saturating_mul(self.total_supply + self.virtual_shares())
+            / (self.total_assets + U256::from(1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +247,55 @@ mod tests {
         assert_eq!(VaultVersion::V1.to_string(), "V1");
         assert_eq!(VaultVersion::V2.to_string(), "V2");
     }
+
+    #[test]
+    fn test_apy_breakdown_totals() {
+        let reward = |symbol: &str, apr| VaultReward {
+            asset_address: Address::repeat_byte(0x58),
+            asset_symbol: symbol.to_string(),
+            supply_apr: apr,
+            yearly_supply_tokens: None,
+        };
+        let breakdown = ApyBreakdown::new(
+            0.05,
+            &[
+                reward("MORPHO", Some(0.02)),
+                reward("WELL", None),
+                reward("OP", Some(0.01)),
+            ],
+            0.005,
+        );
+
+        assert_eq!(breakdown.rewards.len(), 2);
+        assert!((breakdown.native_apy() - 0.045).abs() < 1e-12);
+        assert!((breakdown.rewards_apr() - 0.03).abs() < 1e-12);
+        assert!((breakdown.total_apy() - 0.075).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_deposit_capacity_limits() {
+        let caps = vec![CapRoom {
+            id: B256::repeat_byte(1),
+            room: U256::from(300),
+        }];
+        let capacity =
+            DepositCapacity::new(U256::from(100), Some(U256::from(500)), Some(U256::from(300)), caps.clone());
+        assert_eq!(capacity.accepted, U256::from(100));
+        assert!(capacity.is_fully_accepted());
+
+        let capacity =
+            DepositCapacity::new(U256::from(1000), Some(U256::from(500)), Some(U256::from(300)), caps);
+        assert_eq!(capacity.accepted, U256::from(300));
+        assert_eq!(capacity.limited_by, Some(DepositLimit::SupplyCaps));
+
+        let capacity =
+            DepositCapacity::new(U256::from(1000), Some(U256::from(200)), Some(U256::from(300)), vec![]);
+        assert_eq!(capacity.accepted, U256::from(200));
+        assert_eq!(capacity.limited_by, Some(DepositLimit::MaxDeposit));
+
+        // Neither limit known
+        let capacity = DepositCapacity::new(U256::from(1000), None, None, vec![]);
+        assert_eq!(capacity.accepted, U256::from(1000));
+        assert!(capacity.is_fully_accepted());
+    }
 }
@@ -0,0 +1,311 @@
+//! Comparison of two V1 vault snapshots.
+//!
+//! [`VaultV1::diff`] reports what changed between an older and a newer snapshot of the
+//! same vault: fee, roles, timelock, supply caps, queue order, allocations, and
+//! warnings. Snapshots come from `VaultV1Client::get_vault` (current state) and
+//! `VaultV1Client::get_vault_at` (historical state), so curator governance changes can
+//! be monitored without indexing events.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::{NamedChain, VaultV1Client};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = VaultV1Client::new();
+//!     let address = "0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB".parse().unwrap();
+//!     let before = client.get_vault_at(address, NamedChain::Mainnet, 1_700_000_000).await?;
+//!     let after = client.get_vault(address, NamedChain::Mainnet).await?;
+//!
+//!     let diff = before.diff(&after);
+//!     if let Some(fee) = &diff.fee {
+//!         println!("fee: {} -> {}", fee.before, fee.after);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use super::vault_v1::{VaultAllocation, VaultStateV1, VaultV1};
+use super::warning::WarningType;
+
+/// A value that differs between two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange<T> {
+    /// Value in the older snapshot.
+    pub before: T,
+    /// Value in the newer snapshot.
+    pub after: T,
+}
+
+/// A per-market value that differs between two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketChange {
+    /// Market unique key.
+    pub market_key: String,
+    /// Value in the older snapshot (`None` if the market was not in the vault).
+    pub before: Option<U256>,
+    /// Value in the newer snapshot (`None` if the market is no longer in the vault).
+    pub after: Option<U256>,
+}
+
+/// Changes between two snapshots of a V1 vault (see [`VaultV1::diff`]).
+///
+/// State fields are only compared when both snapshots have state.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VaultDiff {
+    /// Performance fee (as a fraction).
+    pub fee: Option<FieldChange<f64>>,
+    /// Timelock duration in seconds.
+    pub timelock: Option<FieldChange<u64>>,
+    /// Curator address.
+    pub curator: Option<FieldChange<Option<Address>>>,
+    /// Owner address.
+    pub owner: Option<FieldChange<Option<Address>>>,
+    /// Guardian address.
+    pub guardian: Option<FieldChange<Option<Address>>>,
+    /// Supply cap changes, including markets added to or removed from the vault.
+    pub caps: Vec<MarketChange>,
+    /// Supply (allocated assets) changes per market.
+    pub allocations: Vec<MarketChange>,
+    /// Supply queue, as market keys in queue order.
+    pub supply_queue: Option<FieldChange<Vec<String>>>,
+    /// Withdraw queue, as market keys in queue order.
+    pub withdraw_queue: Option<FieldChange<Vec<String>>>,
+    /// Warnings present only in the newer snapshot.
+    pub warnings_added: Vec<WarningType>,
+    /// Warnings present only in the older snapshot.
+    pub warnings_removed: Vec<WarningType>,
+}
+
+impl VaultDiff {
+    /// Returns true if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        *self == VaultDiff::default()
+    }
+}
+
+impl VaultV1 {
+    /// Compare this (older) snapshot with a newer snapshot of the same vault.
+    pub fn diff(&self, newer: &VaultV1) -> VaultDiff {
+        let mut diff = VaultDiff {
+            warnings_added: missing_warnings(newer, self),
+            warnings_removed: missing_warnings(self, newer),
+            ..VaultDiff::default()
+        };
+
+        let (Some(before), Some(after)) = (&self.state, &newer.state) else {
+            return diff;
+        };
+
+        diff.fee = changed(before.fee, after.fee);
+        diff.timelock = changed(before.timelock, after.timelock);
+        diff.curator = changed(before.curator, after.curator);
+        diff.owner = changed(before.owner, after.owner);
+        diff.guardian = changed(before.guardian, after.guardian);
+        diff.caps = market_changes(before, after, |a| a.supply_cap);
+        diff.allocations = market_changes(before, after, |a| a.supply_assets);
+        diff.supply_queue = changed(
+            queue(before, |a| a.supply_queue_index),
+            queue(after, |a| a.supply_queue_index),
+        );
+        diff.withdraw_queue = changed(
+            queue(before, |a| a.withdraw_queue_index),
+            queue(after, |a| a.withdraw_queue_index),
+        );
+        diff
+    }
+}
+
+fn changed<T: PartialEq>(before: T, after: T) -> Option<FieldChange<T>> {
+    (before != after).then_some(FieldChange { before, after })
+}
+
+/// Warning types in `vault` that `other` doesn't have.
+fn missing_warnings(vault: &VaultV1, other: &VaultV1) -> Vec<WarningType> {
+    vault
+        .warnings
+        .iter()
+        .map(|w| &w.warning_type)
+        .filter(|t| !other.has_warning(t))
+        .cloned()
+        .collect()
+}
+
+/// Per-market changes in `value`, ordered by market key.
+fn market_changes(
+    before: &VaultStateV1,
+    after: &VaultStateV1,
+    value: impl Fn(&VaultAllocation) -> U256,
+) -> Vec<MarketChange> {
+    let lookup = |state: &VaultStateV1, key: &str| {
+        state
+            .allocation
+            .iter()
+            .find(|a| a.market_key == key)
+            .map(&value)
+    };
+    let keys: BTreeSet<&str> = before
+        .allocation
+        .iter()
+        .chain(&after.allocation)
+        .map(|a| a.market_key.as_str())
+        .collect();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let (before, after) = (lookup(before, key), lookup(after, key));
+            (before != after).then(|| MarketChange {
+                market_key: key.to_string(),
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+/// Market keys in queue order, for the queue position given by `index`.
+fn queue(state: &VaultStateV1, index: impl Fn(&VaultAllocation) -> Option<i32>) -> Vec<String> {
+    let mut queued: Vec<(i32, &str)> = state
+        .allocation
+        .iter()
+        .filter_map(|a| Some((index(a)?, a.market_key.as_str())))
+        .collect();
+    queued.sort();
+    queued.into_iter().map(|(_, key)| key.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Asset, VaultWarning, WarningLevel};
+
+    fn allocation(key: &str, queue: Option<i32>, supply: u64, cap: u64) -> VaultAllocation {
+        VaultAllocation {
+            market_key: key.to_string(),
+            loan_asset_symbol: None,
+            loan_asset_address: None,
+            collateral_asset_symbol: None,
+            collateral_asset_address: None,
+            supply_assets: U256::from(supply),
+            supply_assets_usd: None,
+            supply_cap: U256::from(cap),
+            enabled: true,
+            supply_queue_index: queue,
+            withdraw_queue_index: queue,
+            market_state: None,
+        }
+    }
+
+    fn vault(fee: f64, allocation: Vec<VaultAllocation>, warnings: Vec<WarningType>) -> VaultV1 {
+        let mut vault = VaultV1::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            false,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            vec![],
+            warnings
+                .into_iter()
+                .map(|warning_type| VaultWarning {
+                    warning_type,
+                    level: WarningLevel::Yellow,
+                })
+                .collect(),
+        )
+        .unwrap();
+        vault.state = VaultStateV1::from_gql(
+            Some("0x1111111111111111111111111111111111111111"),
+            None,
+            None,
+            "1000",
+            None,
+            "1000",
+            fee,
+            "86400",
+            0.05,
+            0.045,
+            "1000000000000000000",
+            allocation,
+            vec![],
+        );
+        vault
+    }
+
+    #[test]
+    fn test_diff_identical_is_empty() {
+        let v = vault(0.1, vec![allocation("a", Some(0), 100, 1000)], vec![]);
+        assert!(v.diff(&v).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_changes() {
+        let before = vault(
+            0.1,
+            vec![
+                allocation("a", Some(0), 100, 1000),
+                allocation("b", Some(1), 200, 2000),
+            ],
+            vec![WarningType::ShortTimelock],
+        );
+        let mut after = vault(
+            0.15,
+            vec![
+                allocation("c", Some(0), 0, 500),
+                allocation("a", Some(1), 100, 5000),
+            ],
+            vec![WarningType::NotWhitelisted],
+        );
+        after.state.as_mut().unwrap().curator = None;
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.fee,
+            Some(FieldChange {
+                before: 0.1,
+                after: 0.15
+            })
+        );
+        assert!(diff.timelock.is_none());
+        assert_eq!(diff.curator.as_ref().unwrap().after, None);
+
+        let caps: Vec<(&str, Option<U256>, Option<U256>)> = diff
+            .caps
+            .iter()
+            .map(|c| (c.market_key.as_str(), c.before, c.after))
+            .collect();
+        assert_eq!(
+            caps,
+            vec![
+                ("a", Some(U256::from(1000)), Some(U256::from(5000))),
+                ("b", Some(U256::from(2000)), None),
+                ("c", None, Some(U256::from(500))),
+            ]
+        );
+        // "a" kept its supply; "b" was removed and "c" added.
+        assert_eq!(diff.allocations.len(), 2);
+
+        let supply_queue = diff.supply_queue.unwrap();
+        assert_eq!(supply_queue.before, vec!["a", "b"]);
+        assert_eq!(supply_queue.after, vec!["c", "a"]);
+
+        assert_eq!(diff.warnings_added, vec![WarningType::NotWhitelisted]);
+        assert_eq!(diff.warnings_removed, vec![WarningType::ShortTimelock]);
+    }
+}
@@ -2,11 +2,15 @@
 
 use alloy_chains::NamedChain;
 use alloy_primitives::{Address, B256, U256};
+use morpho_rs_contracts::MarketParams;
 use serde::{Deserialize, Serialize};
 
 use super::asset::Asset;
 use super::chain::{chain_from_id, chain_serde};
 use super::scalars::{parse_address, parse_bigint};
+use super::vault::{ApyBreakdown, CapRoom, DepositCapacity, ShareConversion, VAULT_SHARE_UNIT};
+use super::vault_v2::VaultReward;
+use super::warning::{WarningLevel, WarningType};
 
 /// Represents a Morpho V1 (MetaMorpho) vault.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -34,6 +38,9 @@ pub struct VaultV1 {
     pub allocators: Vec<VaultAllocator>,
     /// Vault warnings.
     pub warnings: Vec<VaultWarning>,
+    /// Public allocator configuration, if the public allocator manages the vault.
+    #[serde(default)]
+    pub public_allocator_config: Option<VaultPublicAllocatorConfig>,
 }
 
 /// Current state of a V1 vault.
@@ -63,6 +70,9 @@ pub struct VaultStateV1 {
     pub share_price: U256,
     /// Allocation across markets.
     pub allocation: Vec<VaultAllocation>,
+    /// Reward programs paying incentives to depositors.
+    #[serde(default)]
+    pub rewards: Vec<VaultReward>,
 }
 
 /// Market state data needed for simulation.
@@ -90,6 +100,9 @@ pub struct MarketStateV1 {
     pub lltv: U256,
     /// Available liquidity (total_supply_assets - total_borrow_assets).
     pub liquidity: U256,
+    /// Bad debt already realized and socialized to suppliers. None if unavailable.
+    #[serde(default)]
+    pub realized_bad_debt: Option<U256>,
 }
 
 /// Vault allocation to a specific market.
@@ -128,13 +141,95 @@ pub struct VaultAllocator {
     pub address: Address,
 }
 
+/// Public allocator configuration of a V1 vault.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultPublicAllocatorConfig {
+    /// Fee paid on each reallocation (in native token, e.g. wei).
+    pub fee: U256,
+    /// Fees accrued and not yet withdrawn by the vault's admin.
+    pub accrued_fee: U256,
+    /// Flow caps per market.
+    pub flow_caps: Vec<PublicAllocatorFlowCap>,
+}
+
+/// Public allocator flow caps of a vault in one market.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicAllocatorFlowCap {
+    /// Market unique identifier (32-byte hash).
+    pub market_id: B256,
+    /// Market loan token.
+    pub loan_token: Address,
+    /// Market collateral token (zero for idle markets).
+    pub collateral_token: Address,
+    /// Market oracle.
+    pub oracle: Address,
+    /// Market interest rate model.
+    pub irm: Address,
+    /// Market liquidation LTV (WAD-scaled).
+    pub lltv: U256,
+    /// Maximum assets the public allocator can move into the market.
+    pub max_in: U256,
+    /// Maximum assets the public allocator can move out of the market.
+    pub max_out: U256,
+}
+
+impl PublicAllocatorFlowCap {
+    /// Convert GraphQL response fields into a [`PublicAllocatorFlowCap`].
+    ///
+    /// A missing collateral asset (idle market) maps to the zero address. Returns `None`
+    /// if the market key, an address, or a bigint field cannot be parsed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_gql(
+        market_key: &str,
+        loan_token: &str,
+        collateral_token: Option<&str>,
+        oracle: &str,
+        irm: &str,
+        lltv: &str,
+        max_in: &str,
+        max_out: &str,
+    ) -> Option<Self> {
+        Some(PublicAllocatorFlowCap {
+            market_id: market_key.parse().ok()?,
+            loan_token: parse_address(loan_token)?,
+            collateral_token: match collateral_token {
+                Some(address) => parse_address(address)?,
+                None => Address::ZERO,
+            },
+            oracle: parse_address(oracle)?,
+            irm: parse_address(irm)?,
+            lltv: parse_bigint(lltv)?,
+            max_in: parse_bigint(max_in)?,
+            max_out: parse_bigint(max_out)?,
+        })
+    }
+
+    /// The market's parameters, as Morpho Blue and the public allocator take them.
+    pub fn market_params(&self) -> MarketParams {
+        MarketParams {
+            loanToken: self.loan_token,
+            collateralToken: self.collateral_token,
+            oracle: self.oracle,
+            irm: self.irm,
+            lltv: self.lltv,
+        }
+    }
+}
+
 /// Vault warning.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VaultWarning {
     /// Warning type.
-    pub warning_type: String,
+    pub warning_type: WarningType,
     /// Warning level.
-    pub level: String,
+    pub level: WarningLevel,
+}
+
+impl VaultWarning {
+    /// Returns true if this is a red (severe) warning.
+    pub fn is_red(&self) -> bool {
+        self.level == WarningLevel::Red
+    }
 }
 
 impl VaultV1 {
@@ -168,11 +263,418 @@ impl VaultV1 {
             state,
             allocators,
             warnings,
+            public_allocator_config: None,
+        })
+    }
+
+    /// Parameters of a market the public allocator can move the vault's liquidity into
+    /// or out of, as `reallocateTo` takes them.
+    ///
+    /// Returns `None` if the vault has no public allocator configuration or no flow
+    /// caps for the market.
+    pub fn public_allocator_market_params(&self, market_id: B256) -> Option<MarketParams> {
+        self.public_allocator_config
+            .as_ref()?
+            .flow_caps
+            .iter()
+            .find(|cap| cap.market_id == market_id)
+            .map(PublicAllocatorFlowCap::market_params)
+    }
+
+    /// Returns true if the vault has any red (severe) warnings.
+    pub fn has_red_warnings(&self) -> bool {
+        self.warnings.iter().any(|w| w.is_red())
+    }
+
+    /// Returns true if the vault has a warning of the given type.
+    pub fn has_warning(&self, warning_type: &WarningType) -> bool {
+        self.warnings.iter().any(|w| &w.warning_type == warning_type)
+    }
+
+    /// Exit liquidity: how much of the vault can be withdrawn immediately.
+    ///
+    /// For each allocation (in withdraw queue order), the vault's supply is split into
+    /// the part covered by the market's free liquidity and the part blocked by
+    /// utilization. Allocations without market state count as fully blocked.
+    ///
+    /// Returns `None` if the vault has no state.
+    pub fn instant_liquidity(&self) -> Option<InstantLiquidity> {
+        let state = self.state.as_ref()?;
+
+        let mut allocations: Vec<&VaultAllocation> = state.allocation.iter().collect();
+        allocations.sort_by_key(|a| a.withdraw_queue_index.unwrap_or(i32::MAX));
+
+        let markets: Vec<MarketInstantLiquidity> = allocations
+            .into_iter()
+            .map(|a| {
+                let liquidity = a.market_state.as_ref().map_or(U256::ZERO, |m| m.liquidity);
+                let withdrawable = a.supply_assets.min(liquidity);
+                MarketInstantLiquidity {
+                    market_key: a.market_key.clone(),
+                    supply_assets: a.supply_assets,
+                    withdrawable,
+                    blocked: a.supply_assets - withdrawable,
+                }
+            })
+            .collect();
+
+        let withdrawable = markets
+            .iter()
+            .fold(U256::ZERO, |acc, m| acc + m.withdrawable);
+        let blocked = markets.iter().fold(U256::ZERO, |acc, m| acc + m.blocked);
+        let redeemable_fraction = if state.total_assets.is_zero() {
+            1.0
+        } else {
+            (u256_to_f64(withdrawable) / u256_to_f64(state.total_assets)).min(1.0)
+        };
+
+        Some(InstantLiquidity {
+            markets,
+            withdrawable,
+            blocked,
+            redeemable_fraction,
+        })
+    }
+
+    /// Supply cap usage of each allocation, in allocation order.
+    ///
+    /// Returns `None` if the vault has no state.
+    pub fn cap_headroom(&self) -> Option<Vec<MarketCapHeadroom>> {
+        let state = self.state.as_ref()?;
+        Some(
+            state
+                .allocation
+                .iter()
+                .map(|a| MarketCapHeadroom {
+                    market_key: a.market_key.clone(),
+                    supply_cap: a.supply_cap,
+                    supply_assets: a.supply_assets,
+                    room: a.supply_cap.saturating_sub(a.supply_assets),
+                    utilization: cap_utilization(a.supply_assets, a.supply_cap),
+                    in_supply_queue: a.enabled && a.supply_queue_index.is_some(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Supply cap usage summed over the markets deposits can reach (enabled markets in
+    /// the supply queue).
+    ///
+    /// Returns `None` if the vault has no state.
+    pub fn total_headroom(&self) -> Option<CapHeadroom> {
+        let (supply_cap, supply_assets, room) = self
+            .cap_headroom()?
+            .iter()
+            .filter(|m| m.in_supply_queue)
+            .fold(
+                (U256::ZERO, U256::ZERO, U256::ZERO),
+                |(cap, supply, room), m| {
+                    (
+                        cap.saturating_add(m.supply_cap),
+                        supply.saturating_add(m.supply_assets),
+                        room.saturating_add(m.room),
+                    )
+                },
+            );
+        Some(CapHeadroom {
+            supply_cap,
+            supply_assets,
+            room,
+            utilization: cap_utilization(supply_assets, supply_cap),
+        })
+    }
+
+    /// How much of a `requested` deposit the vault can accept, given its on-chain
+    /// `max_deposit`.
+    ///
+    /// Deposits are supplied along the supply queue, so the cap room is the sum of
+    /// the room under each enabled supply queue market's cap. Without vault state the
+    /// cap room is unknown and only `max_deposit` applies.
+    pub fn deposit_capacity(&self, requested: U256, max_deposit: U256) -> DepositCapacity {
+        let Some(state) = self.state.as_ref() else {
+            return DepositCapacity::new(requested, Some(max_deposit), None, Vec::new());
+        };
+
+        let mut allocations: Vec<&VaultAllocation> = state
+            .allocation
+            .iter()
+            .filter(|a| a.enabled && a.supply_queue_index.is_some())
+            .collect();
+        allocations.sort_by_key(|a| a.supply_queue_index);
+
+        let caps: Vec<CapRoom> = allocations
+            .into_iter()
+            .filter_map(|a| {
+                let id = a
+                    .market_state
+                    .as_ref()
+                    .map(|m| m.id)
+                    .or_else(|| a.market_key.parse().ok())?;
+                Some(CapRoom {
+                    id,
+                    room: a.supply_cap.saturating_sub(a.supply_assets),
+                })
+            })
+            .collect();
+        let cap_room = caps
+            .iter()
+            .fold(U256::ZERO, |acc, c| acc.saturating_add(c.room));
+
+        DepositCapacity::new(requested, Some(max_deposit), Some(cap_room), caps)
+    }
+
+    /// Split the vault's yield into lending APY, per-token reward APR, and fee drag.
+    ///
+    /// `base_apy` is the state's `apy` (market interest before fees) and `fee_drag` is
+    /// the performance fee's share of it. Returns `None` if the vault has no state.
+    pub fn apy_breakdown(&self) -> Option<ApyBreakdown> {
+        let state = self.state.as_ref()?;
+        Some(ApyBreakdown::new(
+            state.apy,
+            &state.rewards,
+            state.apy * state.fee,
+        ))
+    }
+
+    /// Assets redeemable for one whole share ([`VAULT_SHARE_UNIT`]), in the asset's
+    /// smallest unit.
+    ///
+    /// Computed from the API's totals, so interest accrued since the snapshot is not
+    /// included; use `VaultV1Operations::convert_to_assets` for the live on-chain value.
+    /// Returns `None` if the vault has no state.
+    pub fn share_price_scaled(&self) -> Option<U256> {
+        self.assets_for_shares(VAULT_SHARE_UNIT)
+    }
+
+    /// Assets redeemable for `shares`, rounded down like `convertToAssets`.
+    ///
+    /// Returns `None` if the vault has no state.
+    pub fn assets_for_shares(&self, shares: U256) -> Option<U256> {
+        Some(self.share_conversion()?.to_assets(shares))
+    }
+
+    /// Shares minted for `assets`, rounded down like `convertToShares`.
+    ///
+    /// Returns `None` if the vault has no state.
+    pub fn shares_for_assets(&self, assets: U256) -> Option<U256> {
+        Some(self.share_conversion()?.to_shares(assets))
+    }
+
+    fn share_conversion(&self) -> Option<ShareConversion> {
+        let state = self.state.as_ref()?;
+        Some(ShareConversion {
+            total_assets: state.total_assets,
+            total_supply: state.total_supply,
+            asset_decimals: self.asset.decimals,
         })
     }
 }
 
+/// Exit liquidity of a V1 vault (see [`VaultV1::instant_liquidity`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstantLiquidity {
+    /// Per-market liquidity, in withdraw queue order.
+    pub markets: Vec<MarketInstantLiquidity>,
+    /// Total assets that can be withdrawn immediately.
+    pub withdrawable: U256,
+    /// Total assets blocked by utilization.
+    pub blocked: U256,
+    /// Fraction of the vault's total assets that is immediately redeemable (0.0 to 1.0).
+    pub redeemable_fraction: f64,
+}
+
+/// Exit liquidity of a vault's allocation to one market.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketInstantLiquidity {
+    /// Market unique key.
+    pub market_key: String,
+    /// Assets the vault supplies to this market.
+    pub supply_assets: U256,
+    /// Assets that can be withdrawn immediately.
+    pub withdrawable: U256,
+    /// Assets blocked by utilization (lent out to borrowers).
+    pub blocked: U256,
+}
+
+/// Supply cap usage of a V1 vault's allocation to one market.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketCapHeadroom {
+    /// Market unique key.
+    pub market_key: String,
+    /// Supply cap.
+    pub supply_cap: U256,
+    /// Assets the vault supplies to this market.
+    pub supply_assets: U256,
+    /// Assets that can still be supplied under the cap.
+    pub room: U256,
+    /// Share of the cap in use (above 1.0 if the cap was lowered below the supply, 1.0
+    /// for a zero cap).
+    pub utilization: f64,
+    /// Whether deposits can reach the market (enabled and in the supply queue).
+    pub in_supply_queue: bool,
+}
+
+/// Supply cap usage summed over a V1 vault's markets (see [`VaultV1::total_headroom`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapHeadroom {
+    /// Sum of the supply caps.
+    pub supply_cap: U256,
+    /// Sum of the vault's supply.
+    pub supply_assets: U256,
+    /// Sum of the room under each cap.
+    pub room: U256,
+    /// Share of the summed caps in use.
+    pub utilization: f64,
+}
+
+/// Share of `cap` used by `supply`; 1.0 for a zero cap.
+fn cap_utilization(supply: U256, cap: U256) -> f64 {
+    if cap.is_zero() {
+        1.0
+    } else {
+        u256_to_f64(supply) / u256_to_f64(cap)
+    }
+}
+
+/// Performance fee configuration of a V1 vault and its fee recipient.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultFeeInfo {
+    /// The vault's contract address.
+    pub address: Address,
+    /// The blockchain the vault is deployed on.
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+    /// Performance fee (as a fraction, e.g., 0.1 = 10%).
+    pub fee: f64,
+    /// Address receiving the fee shares.
+    pub fee_recipient: Address,
+    /// The curator's address.
+    pub curator: Address,
+    /// The owner's address.
+    pub owner: Address,
+    /// Total assets in the vault (in asset's smallest unit).
+    pub total_assets: U256,
+    /// Total assets in USD.
+    pub total_assets_usd: Option<f64>,
+    /// Total assets at the last fee accrual.
+    pub last_total_assets: U256,
+    /// Total supply of vault shares.
+    pub total_supply: U256,
+}
+
+impl VaultFeeInfo {
+    /// Convert GraphQL response fields into a [`VaultFeeInfo`].
+    ///
+    /// Returns `None` if an address or bigint field cannot be parsed or the chain ID is
+    /// unsupported.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_gql(
+        address: &str,
+        chain_id: i64,
+        fee: f64,
+        fee_recipient: &str,
+        curator: &str,
+        owner: &str,
+        total_assets: &str,
+        total_assets_usd: Option<f64>,
+        last_total_assets: &str,
+        total_supply: &str,
+    ) -> Option<Self> {
+        Some(VaultFeeInfo {
+            address: parse_address(address)?,
+            chain: chain_from_id(chain_id)?,
+            fee,
+            fee_recipient: parse_address(fee_recipient)?,
+            curator: parse_address(curator)?,
+            owner: parse_address(owner)?,
+            total_assets: parse_bigint(total_assets)?,
+            total_assets_usd,
+            last_total_assets: parse_bigint(last_total_assets)?,
+            total_supply: parse_bigint(total_supply)?,
+        })
+    }
+
+    /// Interest earned since the last fee accrual.
+    pub fn pending_interest(&self) -> U256 {
+        self.total_assets.saturating_sub(self.last_total_assets)
+    }
+
+    /// Fee (in assets) the recipient will receive on the next accrual at the current fee.
+    pub fn pending_fee_assets(&self) -> U256 {
+        let fee_wad = U256::from((self.fee * 1e18).round() as u128);
+        self.pending_interest() * fee_wad / U256::from(1_000_000_000_000_000_000u128)
+    }
+}
+
+pub(crate) fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(0.0)
+}
+
+impl MarketStateV1 {
+    /// Share of the market's supply that is borrowed, between 0 and 1.
+    ///
+    /// A market with no supply returns 0.
+    pub fn utilization(&self) -> f64 {
+        if self.total_supply_assets.is_zero() {
+            0.0
+        } else {
+            (u256_to_f64(self.total_borrow_assets) / u256_to_f64(self.total_supply_assets)).min(1.0)
+        }
+    }
+}
+
 impl VaultStateV1 {
+    /// Total assets supplied to the vault's markets.
+    pub fn allocated_assets(&self) -> U256 {
+        self.allocation
+            .iter()
+            .fold(U256::ZERO, |acc, a| acc + a.supply_assets)
+    }
+
+    /// Assets not supplied to any market in [`allocation`](Self::allocation).
+    ///
+    /// Saturates at zero, since the API's totals and allocations can be rounded
+    /// differently. Assets in an idle market (one without collateral) count as allocated.
+    pub fn idle_assets(&self) -> U256 {
+        self.total_assets.saturating_sub(self.allocated_assets())
+    }
+
+    /// Borrow utilization of the vault's markets, weighted by the vault's supply in each.
+    ///
+    /// Allocations without market state are left out. Returns `None` if no allocation
+    /// with market state has any supply.
+    pub fn utilization(&self) -> Option<f64> {
+        let (weighted, supplied) = self
+            .allocation
+            .iter()
+            .filter_map(|a| {
+                let market = a.market_state.as_ref()?;
+                let supply = u256_to_f64(a.supply_assets);
+                Some((supply * market.utilization(), supply))
+            })
+            .fold((0.0, 0.0), |(w, s), (dw, ds)| (w + dw, s + ds));
+        (supplied > 0.0).then(|| weighted / supplied)
+    }
+
+    /// Each allocation's share of the vault's total assets (between 0 and 1), keyed by
+    /// market, in allocation order.
+    ///
+    /// All shares are zero for an empty vault.
+    pub fn allocation_shares(&self) -> Vec<(&str, f64)> {
+        let total = u256_to_f64(self.total_assets);
+        self.allocation
+            .iter()
+            .map(|a| {
+                let share = if total > 0.0 {
+                    u256_to_f64(a.supply_assets) / total
+                } else {
+                    0.0
+                };
+                (a.market_key.as_str(), share)
+            })
+            .collect()
+    }
+
     /// Convert GraphQL response fields into a [`VaultStateV1`].
     ///
     /// Parses bigint strings (`total_assets`, `total_supply`, `share_price`, `timelock`)
@@ -192,6 +694,7 @@ impl VaultStateV1 {
         net_apy: f64,
         share_price: &str,
         allocation: Vec<VaultAllocation>,
+        rewards: Vec<VaultReward>,
     ) -> Option<Self> {
         Some(VaultStateV1 {
             curator: curator.and_then(parse_address),
@@ -206,6 +709,7 @@ impl VaultStateV1 {
             net_apy,
             share_price: parse_bigint(share_price)?,
             allocation,
+            rewards,
         })
     }
 }
@@ -334,7 +838,7 @@ impl Vault for VaultV1 {
     }
 
     fn has_critical_warnings(&self) -> bool {
-        self.warnings.iter().any(|w| w.level == "CRITICAL")
+        self.has_red_warnings()
     }
 
     fn clone_box(&self) -> Box<dyn Vault> {
@@ -346,9 +850,34 @@ impl Vault for VaultV1 {
 #[cfg(feature = "sim")]
 mod sim_conversion {
     use super::*;
-    use morpho_rs_sim::{Market, Vault, VaultMarketConfig, VaultSimulation};
+    use morpho_rs_sim::{
+        Market, PublicAllocatorConfig, PublicAllocatorMarketConfig, PublicReallocation, Vault,
+        VaultMarketConfig, VaultSimulation,
+    };
     use std::collections::HashMap;
 
+    impl MarketStateV1 {
+        /// Convert this market state to a simulation [`Market`].
+        ///
+        /// # Feature Flag
+        ///
+        /// This method is only available when the `sim` feature is enabled.
+        pub fn to_sim_market(&self) -> Market {
+            Market::new_with_oracle(
+                self.id,
+                self.total_supply_assets,
+                self.total_borrow_assets,
+                self.total_supply_shares,
+                self.total_borrow_shares,
+                self.last_update,
+                self.fee,
+                self.rate_at_target,
+                self.price,
+                self.lltv,
+            )
+        }
+    }
+
     impl VaultV1 {
         /// Convert this vault to a [`VaultSimulation`] for APY and deposit/withdrawal calculations.
         ///
@@ -411,7 +940,7 @@ mod sim_conversion {
                             cap: alloc.supply_cap,
                             supply_assets: alloc.supply_assets,
                             enabled: alloc.enabled,
-                            public_allocator_config: None, // Not available from API
+                            public_allocator_config: self.public_allocator_flow_cap(market_id),
                         },
                     );
                 }
@@ -421,19 +950,7 @@ mod sim_conversion {
             let mut markets = HashMap::new();
             for alloc in &state.allocation {
                 if let Some(ms) = &alloc.market_state {
-                    let market = Market::new_with_oracle(
-                        ms.id,
-                        ms.total_supply_assets,
-                        ms.total_borrow_assets,
-                        ms.total_supply_shares,
-                        ms.total_borrow_shares,
-                        ms.last_update,
-                        ms.fee,
-                        ms.rate_at_target,
-                        ms.price,
-                        ms.lltv,
-                    );
-                    markets.insert(ms.id, market);
+                    markets.insert(ms.id, ms.to_sim_market());
                 }
             }
 
@@ -445,6 +962,7 @@ mod sim_conversion {
                 address: self.address,
                 asset_decimals: self.asset.decimals,
                 fee: fee_wad,
+                management_fee: U256::ZERO, // MetaMorpho V1 has no management fee
                 total_assets: state.total_assets,
                 total_supply: state.total_supply,
                 last_total_assets: state.total_assets, // Assume current state is synced
@@ -452,17 +970,62 @@ mod sim_conversion {
                 withdraw_queue,
                 allocations,
                 owner: state.owner.unwrap_or(Address::ZERO),
-                public_allocator_config: None, // Not available from API
+                public_allocator_config: self.public_allocator_config.as_ref().map(|pa| {
+                    PublicAllocatorConfig {
+                        fee: pa.fee,
+                        accrued_fee: pa.accrued_fee,
+                    }
+                }),
             };
 
             Some(VaultSimulation::new(vault, markets))
         }
+
+        /// Resolve the withdrawals of a reallocation planned by
+        /// [`find_public_allocator_route`](morpho_rs_sim::find_public_allocator_route) for
+        /// this vault into the market parameters `reallocateTo` takes.
+        ///
+        /// Returns `None` if a withdrawn market has no flow caps in the vault's public
+        /// allocator configuration.
+        ///
+        /// # Feature Flag
+        ///
+        /// This method is only available when the `sim` feature is enabled.
+        pub fn public_reallocation_withdrawals(
+            &self,
+            reallocation: &PublicReallocation,
+        ) -> Option<Vec<(MarketParams, U256)>> {
+            reallocation
+                .withdrawals
+                .iter()
+                .map(|(market_id, amount)| {
+                    Some((self.public_allocator_market_params(*market_id)?, *amount))
+                })
+                .collect()
+        }
+
+        fn public_allocator_flow_cap(
+            &self,
+            market_id: B256,
+        ) -> Option<PublicAllocatorMarketConfig> {
+            let cap = self
+                .public_allocator_config
+                .as_ref()?
+                .flow_caps
+                .iter()
+                .find(|cap| cap.market_id == market_id)?;
+            Some(PublicAllocatorMarketConfig {
+                max_in: cap.max_in,
+                max_out: cap.max_out,
+            })
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::vault::DepositLimit;
 
     #[test]
     fn test_vault_allocator_from_gql() {
@@ -473,4 +1036,588 @@ mod tests {
             parse_address("0x1234567890123456789012345678901234567890").unwrap()
         );
     }
+
+    #[test]
+    fn test_has_red_warnings() {
+        let warning = |warning_type, level| VaultWarning { warning_type, level };
+        let vault = |warnings| {
+            VaultV1::from_gql(
+                "0x1234567890123456789012345678901234567890",
+                "Vault".to_string(),
+                "V".to_string(),
+                1,
+                true,
+                false,
+                true,
+                Asset::from_gql(
+                    "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                    "USDC".to_string(),
+                    None,
+                    6.0,
+                    None,
+                )
+                .unwrap(),
+                None,
+                vec![],
+                warnings,
+            )
+            .unwrap()
+        };
+
+        let yellow = vault(vec![warning(WarningType::ShortTimelock, WarningLevel::Yellow)]);
+        assert!(!yellow.has_red_warnings());
+        assert!(!Vault::has_critical_warnings(&yellow));
+        assert!(yellow.has_warning(&WarningType::ShortTimelock));
+
+        let red = vault(vec![
+            warning(WarningType::ShortTimelock, WarningLevel::Yellow),
+            warning(WarningType::BadDebtUnrealized, WarningLevel::Red),
+        ]);
+        assert!(red.has_red_warnings());
+        assert!(Vault::has_critical_warnings(&red));
+        assert!(!red.has_warning(&WarningType::UnrecognizedOracle));
+    }
+
+    #[test]
+    fn test_instant_liquidity() {
+        let allocation = |key: &str, queue: i32, supply: u64, liquidity: Option<u64>| {
+            VaultAllocation {
+                market_key: key.to_string(),
+                loan_asset_symbol: None,
+                loan_asset_address: None,
+                collateral_asset_symbol: None,
+                collateral_asset_address: None,
+                supply_assets: U256::from(supply),
+                supply_assets_usd: None,
+                supply_cap: U256::MAX,
+                enabled: true,
+                supply_queue_index: Some(queue),
+                withdraw_queue_index: Some(queue),
+                market_state: liquidity.map(|l| MarketStateV1 {
+                    id: B256::ZERO,
+                    total_supply_assets: U256::from(l),
+                    total_borrow_assets: U256::ZERO,
+                    total_supply_shares: U256::ZERO,
+                    total_borrow_shares: U256::ZERO,
+                    last_update: 0,
+                    fee: U256::ZERO,
+                    rate_at_target: None,
+                    price: None,
+                    lltv: U256::ZERO,
+                    liquidity: U256::from(l),
+                    realized_bad_debt: None,
+                }),
+            }
+        };
+
+        let mut vault = VaultV1::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            false,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert!(vault.instant_liquidity().is_none());
+
+        vault.state = VaultStateV1::from_gql(
+            None,
+            None,
+            None,
+            "1000",
+            None,
+            "1000",
+            0.1,
+            "86400",
+            0.05,
+            0.045,
+            "1000000000000000000",
+            vec![
+                allocation("b", 1, 400, Some(1000)),
+                allocation("a", 0, 500, Some(200)),
+                allocation("c", 2, 100, None),
+            ],
+            vec![],
+        );
+
+        let liquidity = vault.instant_liquidity().unwrap();
+        let keys: Vec<&str> = liquidity.markets.iter().map(|m| m.market_key.as_str()).collect();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+        assert_eq!(liquidity.markets[0].withdrawable, U256::from(200));
+        assert_eq!(liquidity.markets[0].blocked, U256::from(300));
+        assert_eq!(liquidity.markets[1].withdrawable, U256::from(400));
+        assert_eq!(liquidity.markets[2].blocked, U256::from(100));
+        assert_eq!(liquidity.withdrawable, U256::from(600));
+        assert_eq!(liquidity.blocked, U256::from(400));
+        assert!((liquidity.redeemable_fraction - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_deposit_capacity() {
+        let allocation = |id: u8, queue: Option<i32>, cap: u64, supply: u64| VaultAllocation {
+            market_key: B256::repeat_byte(id).to_string(),
+            loan_asset_symbol: None,
+            loan_asset_address: None,
+            collateral_asset_symbol: None,
+            collateral_asset_address: None,
+            supply_assets: U256::from(supply),
+            supply_assets_usd: None,
+            supply_cap: U256::from(cap),
+            enabled: true,
+            supply_queue_index: queue,
+            withdraw_queue_index: Some(0),
+            market_state: None,
+        };
+
+        let mut vault = VaultV1::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            false,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let capacity = vault.deposit_capacity(U256::from(1000), U256::from(500));
+        assert_eq!(capacity.cap_room, None);
+        assert_eq!(capacity.accepted, U256::from(500));
+        assert_eq!(capacity.limited_by, Some(DepositLimit::MaxDeposit));
+
+        vault.state = VaultStateV1::from_gql(
+            None,
+            None,
+            None,
+            "1000",
+            None,
+            "1000",
+            0.1,
+            "86400",
+            0.05,
+            0.045,
+            "1000000000000000000",
+            vec![
+                allocation(2, Some(1), 500, 400),
+                allocation(1, Some(0), 300, 500),
+                // Not in the supply queue
+                allocation(3, None, 1000, 0),
+            ],
+            vec![],
+        );
+
+        let capacity = vault.deposit_capacity(U256::from(1000), U256::MAX);
+        let ids: Vec<B256> = capacity.caps.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![B256::repeat_byte(1), B256::repeat_byte(2)]);
+        // Over-cap markets have no room
+        assert_eq!(capacity.caps[0].room, U256::ZERO);
+        assert_eq!(capacity.cap_room, Some(U256::from(100)));
+        assert_eq!(capacity.accepted, U256::from(100));
+        assert_eq!(capacity.limited_by, Some(DepositLimit::SupplyCaps));
+        assert!(vault.deposit_capacity(U256::from(100), U256::MAX).is_fully_accepted());
+    }
+
+    #[test]
+    fn test_cap_headroom() {
+        let allocation = |id: u8, queue: Option<i32>, cap: u64, supply: u64| VaultAllocation {
+            market_key: B256::repeat_byte(id).to_string(),
+            loan_asset_symbol: None,
+            loan_asset_address: None,
+            collateral_asset_symbol: None,
+            collateral_asset_address: None,
+            supply_assets: U256::from(supply),
+            supply_assets_usd: None,
+            supply_cap: U256::from(cap),
+            enabled: true,
+            supply_queue_index: queue,
+            withdraw_queue_index: Some(0),
+            market_state: None,
+        };
+
+        let mut vault = VaultV1::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            false,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(vault.cap_headroom(), None);
+        assert_eq!(vault.total_headroom(), None);
+
+        vault.state = VaultStateV1::from_gql(
+            None,
+            None,
+            None,
+            "1000",
+            None,
+            "1000",
+            0.1,
+            "86400",
+            0.05,
+            0.045,
+            "1000000000000000000",
+            vec![
+                allocation(1, Some(0), 1000, 250),
+                allocation(2, Some(1), 400, 500),
+                allocation(3, Some(2), 0, 0),
+                // Not in the supply queue
+                allocation(4, None, 1000, 0),
+            ],
+            vec![],
+        );
+
+        let markets = vault.cap_headroom().unwrap();
+        assert_eq!(markets.len(), 4);
+        assert_eq!(markets[0].room, U256::from(750));
+        assert_eq!(markets[0].utilization, 0.25);
+        // Over-cap markets have no room
+        assert_eq!(markets[1].room, U256::ZERO);
+        assert_eq!(markets[1].utilization, 1.25);
+        assert_eq!(markets[2].utilization, 1.0);
+        assert!(!markets[3].in_supply_queue);
+
+        let total = vault.total_headroom().unwrap();
+        assert_eq!(total.supply_cap, U256::from(1400));
+        assert_eq!(total.supply_assets, U256::from(750));
+        assert_eq!(total.room, U256::from(750));
+        assert!((total.utilization - 750.0 / 1400.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_state_allocation_metrics() {
+        let allocation = |key: &str, supply: u64, market: Option<(u64, u64)>| VaultAllocation {
+            market_key: key.to_string(),
+            loan_asset_symbol: None,
+            loan_asset_address: None,
+            collateral_asset_symbol: None,
+            collateral_asset_address: None,
+            supply_assets: U256::from(supply),
+            supply_assets_usd: None,
+            supply_cap: U256::MAX,
+            enabled: true,
+            supply_queue_index: None,
+            withdraw_queue_index: None,
+            market_state: market.map(|(total_supply, total_borrow)| MarketStateV1 {
+                id: B256::ZERO,
+                total_supply_assets: U256::from(total_supply),
+                total_borrow_assets: U256::from(total_borrow),
+                total_supply_shares: U256::ZERO,
+                total_borrow_shares: U256::ZERO,
+                last_update: 0,
+                fee: U256::ZERO,
+                rate_at_target: None,
+                price: None,
+                lltv: U256::ZERO,
+                liquidity: U256::from(total_supply - total_borrow),
+                realized_bad_debt: None,
+            }),
+        };
+
+        let mut state = VaultStateV1::from_gql(
+            None,
+            None,
+            None,
+            "1000",
+            None,
+            "1000",
+            0.1,
+            "86400",
+            0.05,
+            0.045,
+            "1000000000000000000",
+            vec![
+                allocation("a", 600, Some((1000, 900))),
+                allocation("b", 200, Some((1000, 400))),
+                allocation("c", 100, None),
+            ],
+            vec![],
+        )
+        .unwrap();
+
+        assert_eq!(state.allocated_assets(), U256::from(900));
+        assert_eq!(state.idle_assets(), U256::from(100));
+        // (600 * 0.9 + 200 * 0.4) / 800; the market without state is left out
+        assert!((state.utilization().unwrap() - 0.775).abs() < 1e-12);
+        let shares = state.allocation_shares();
+        assert_eq!(shares[0].0, "a");
+        assert!((shares[0].1 - 0.6).abs() < 1e-12);
+        assert!((shares[2].1 - 0.1).abs() < 1e-12);
+
+        // Allocations reported above total assets leave no idle assets
+        state.total_assets = U256::from(800);
+        assert_eq!(state.idle_assets(), U256::ZERO);
+
+        state.allocation.clear();
+        assert!(state.utilization().is_none());
+    }
+
+    #[test]
+    fn test_apy_breakdown() {
+        let mut vault = VaultV1::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            false,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert!(vault.apy_breakdown().is_none());
+
+        let morpho = VaultReward::from_gql(
+            "0x58D97B57BB95320F9a05dC918Aef65434969c2B2",
+            "MORPHO".to_string(),
+            Some(0.02),
+            None,
+        )
+        .unwrap();
+        vault.state = VaultStateV1::from_gql(
+            None,
+            None,
+            None,
+            "1000",
+            None,
+            "1000",
+            0.1,
+            "86400",
+            0.05,
+            0.065,
+            "1000000000000000000",
+            vec![],
+            vec![morpho],
+        );
+
+        let breakdown = vault.apy_breakdown().unwrap();
+        assert_eq!(breakdown.base_apy, 0.05);
+        assert_eq!(breakdown.rewards.len(), 1);
+        assert_eq!(breakdown.rewards[0].asset_symbol, "MORPHO");
+        assert!((breakdown.fee_drag - 0.005).abs() < 1e-12);
+        assert!((breakdown.native_apy() - 0.045).abs() < 1e-12);
+        assert!((breakdown.total_apy() - 0.065).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_share_conversion() {
+        let mut vault = VaultV1::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            false,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert!(vault.share_price_scaled().is_none());
+        assert!(vault.assets_for_shares(VAULT_SHARE_UNIT).is_none());
+
+        // 1,050 USDC backing 1,000 shares
+        vault.state = VaultStateV1::from_gql(
+            None,
+            None,
+            None,
+            "1050000000",
+            None,
+            "1000000000000000000000",
+            0.1,
+            "86400",
+            0.05,
+            0.045,
+            "1050000",
+            vec![],
+            vec![],
+        );
+
+        // Virtual shares and assets make conversions round slightly down
+        assert_eq!(vault.share_price_scaled(), Some(U256::from(1_049_999)));
+        assert_eq!(
+            vault.assets_for_shares(U256::from(10).pow(U256::from(21))),
+            Some(U256::from(1_049_999_999))
+        );
+        assert_eq!(
+            vault.shares_for_assets(U256::from(1_000_000)),
+            Some(U256::from(952_380_952_426_303_854u64))
+        );
+    }
+
+    #[cfg(feature = "sim")]
+    #[test]
+    fn test_public_allocator_config_reaches_simulation() {
+        let wad = U256::from(10u64).pow(U256::from(18));
+        let market_state = |id: u8, supply: u64, borrow: u64| MarketStateV1 {
+            id: B256::repeat_byte(id),
+            total_supply_assets: U256::from(supply),
+            total_borrow_assets: U256::from(borrow),
+            total_supply_shares: U256::from(supply) * U256::from(1_000_000u64),
+            total_borrow_shares: U256::from(borrow) * U256::from(1_000_000u64),
+            last_update: 1_700_000_000,
+            fee: U256::ZERO,
+            rate_at_target: None,
+            price: None,
+            lltv: wad * U256::from(86u64) / U256::from(100u64),
+            liquidity: U256::from(supply - borrow),
+            realized_bad_debt: None,
+        };
+        let allocation = |id: u8, supply: u64, borrow: u64| VaultAllocation {
+            market_key: B256::repeat_byte(id).to_string(),
+            loan_asset_symbol: None,
+            loan_asset_address: None,
+            collateral_asset_symbol: None,
+            collateral_asset_address: None,
+            supply_assets: U256::from(supply),
+            supply_assets_usd: None,
+            supply_cap: U256::from(1_000_000u64),
+            enabled: true,
+            supply_queue_index: Some(id as i32),
+            withdraw_queue_index: Some(id as i32),
+            market_state: Some(market_state(id, supply, borrow)),
+        };
+        let flow_cap = |id: u8| PublicAllocatorFlowCap {
+            market_id: B256::repeat_byte(id),
+            loan_token: Address::repeat_byte(0xAA),
+            collateral_token: Address::repeat_byte(id),
+            oracle: Address::repeat_byte(0xBB),
+            irm: Address::repeat_byte(0xCC),
+            lltv: wad * U256::from(86u64) / U256::from(100u64),
+            max_in: U256::from(10_000u64),
+            max_out: U256::from(10_000u64),
+        };
+
+        let mut vault = VaultV1::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            false,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            VaultStateV1::from_gql(
+                None,
+                None,
+                None,
+                "2000",
+                None,
+                "2000",
+                0.0,
+                "86400",
+                0.05,
+                0.05,
+                "1000000000000000000",
+                vec![allocation(1, 1_000, 0), allocation(2, 1_000, 900)],
+                vec![],
+            ),
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert!(vault
+            .to_vault_simulation()
+            .unwrap()
+            .vault
+            .public_allocator_config
+            .is_none());
+
+        vault.public_allocator_config = Some(VaultPublicAllocatorConfig {
+            fee: U256::from(1_000u64),
+            accrued_fee: U256::ZERO,
+            flow_caps: vec![flow_cap(1), flow_cap(2)],
+        });
+        let sim = vault.to_vault_simulation().unwrap();
+        assert_eq!(
+            sim.vault.public_allocator_config.as_ref().unwrap().fee,
+            U256::from(1_000u64)
+        );
+
+        let route = morpho_rs_sim::find_public_allocator_route(
+            &[&sim],
+            B256::repeat_byte(2),
+            U256::from(500u64),
+            1_700_000_000,
+        )
+        .unwrap();
+        assert!(route.is_complete());
+        let withdrawals = vault
+            .public_reallocation_withdrawals(&route.reallocations[0])
+            .unwrap();
+        assert_eq!(
+            withdrawals,
+            vec![(flow_cap(1).market_params(), U256::from(500u64))]
+        );
+        assert_eq!(
+            vault.public_allocator_market_params(route.supply_market_id),
+            Some(flow_cap(2).market_params())
+        );
+        assert_eq!(
+            vault.public_allocator_market_params(B256::repeat_byte(3)),
+            None
+        );
+    }
 }
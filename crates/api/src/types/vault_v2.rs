@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 use super::asset::Asset;
 use super::chain::{chain_from_id, chain_serde};
 use super::scalars::{parse_address, parse_bigint};
+use super::vault::{ApyBreakdown, CapRoom, DepositCapacity, ShareConversion, VAULT_SHARE_UNIT};
+use super::vault_v1::u256_to_f64;
+use super::warning::{WarningLevel, WarningType};
 
 /// Represents a Morpho V2 vault.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -54,6 +57,15 @@ pub struct VaultV2 {
     pub liquidity: U256,
     /// Liquidity in USD.
     pub liquidity_usd: Option<f64>,
+    /// Assets held idle in the vault itself, outside any adapter.
+    #[serde(default)]
+    pub idle_assets: U256,
+    /// Adapter that deposits are allocated to and withdrawals are served from.
+    #[serde(default)]
+    pub liquidity_adapter: Option<Address>,
+    /// Allocation caps by id.
+    #[serde(default)]
+    pub caps: Vec<VaultCap>,
     /// Vault adapters.
     pub adapters: Vec<VaultAdapter>,
     /// Vault rewards.
@@ -129,9 +141,16 @@ pub enum VaultAdapterData {
     },
     /// MetaMorpho adapter allocations.
     MetaMorpho {
+        /// Address of the underlying V1 (MetaMorpho) vault.
+        vault_address: Address,
         /// Allocations to underlying markets.
         allocations: Vec<MetaMorphoAllocation>,
     },
+    /// Adapter of a type the SDK does not model; only its assets are known.
+    Unknown {
+        /// Assets held by the adapter.
+        assets: U256,
+    },
 }
 
 /// Vault adapter configuration.
@@ -151,6 +170,45 @@ pub struct VaultAdapter {
     pub data: Option<VaultAdapterData>,
 }
 
+/// What a cap id limits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum VaultCapData {
+    /// All allocations through one adapter.
+    Adapter {
+        /// Adapter address.
+        adapter: Address,
+    },
+    /// All allocations to markets with one collateral token.
+    Collateral {
+        /// Collateral token address.
+        collateral: Address,
+    },
+    /// Allocations to one Morpho market through one adapter.
+    MarketV1 {
+        /// Adapter address.
+        adapter: Address,
+        /// Market unique key.
+        market_id: B256,
+        /// Collateral token of the market.
+        collateral: Address,
+    },
+}
+
+/// Allocation cap on one id of a V2 vault.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultCap {
+    /// Cap id.
+    pub id: B256,
+    /// Maximum assets allocated to the id.
+    pub absolute_cap: U256,
+    /// Maximum fraction of total assets allocated to the id (WAD-scaled).
+    pub relative_cap: U256,
+    /// Assets currently allocated to the id.
+    pub allocation: U256,
+    /// What the id limits, if known.
+    pub data: Option<VaultCapData>,
+}
+
 /// Vault reward configuration.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VaultReward {
@@ -168,18 +226,25 @@ pub struct VaultReward {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VaultV2Warning {
     /// Warning type.
-    pub warning_type: String,
+    pub warning_type: WarningType,
     /// Warning level.
-    pub level: String,
+    pub level: WarningLevel,
+}
+
+impl VaultV2Warning {
+    /// Returns true if this is a red (severe) warning.
+    pub fn is_red(&self) -> bool {
+        self.level == WarningLevel::Red
+    }
 }
 
 impl VaultV2 {
     /// Convert GraphQL response fields into a [`VaultV2`].
     ///
     /// Parses hex string fields (`address`, `curator`, `owner`) into their typed
-    /// representations and bigint strings (`total_assets`, `total_supply`, `liquidity`)
-    /// into [`U256`]. Returns `None` if the address is invalid or the chain ID is
-    /// unsupported.
+    /// representations and bigint strings (`total_assets`, `total_supply`, `liquidity`,
+    /// `idle_assets`) into [`U256`]. An invalid `liquidity_adapter` address is dropped.
+    /// Returns `None` if the address is invalid or the chain ID is unsupported.
     #[allow(clippy::too_many_arguments)]
     pub fn from_gql(
         address: &str,
@@ -203,6 +268,9 @@ impl VaultV2 {
         net_apy: Option<f64>,
         liquidity: &str,
         liquidity_usd: Option<f64>,
+        idle_assets: &str,
+        liquidity_adapter: Option<&str>,
+        caps: Vec<VaultCap>,
         adapters: Vec<VaultAdapter>,
         rewards: Vec<VaultReward>,
         warnings: Vec<VaultV2Warning>,
@@ -229,6 +297,9 @@ impl VaultV2 {
             net_apy,
             liquidity: parse_bigint(liquidity).unwrap_or(U256::ZERO),
             liquidity_usd,
+            idle_assets: parse_bigint(idle_assets).unwrap_or(U256::ZERO),
+            liquidity_adapter: liquidity_adapter.and_then(parse_address),
+            caps,
             adapters,
             rewards,
             warnings,
@@ -236,6 +307,144 @@ impl VaultV2 {
     }
 }
 
+impl VaultV2 {
+    /// Addresses of the V1 (MetaMorpho) vaults this vault allocates into via
+    /// MetaMorpho adapters, in adapter order and without duplicates.
+    pub fn underlying_v1_vault_addresses(&self) -> Vec<Address> {
+        let mut addresses = Vec::new();
+        for adapter in &self.adapters {
+            if let Some(VaultAdapterData::MetaMorpho { vault_address, .. }) = &adapter.data {
+                if !addresses.contains(vault_address) {
+                    addresses.push(*vault_address);
+                }
+            }
+        }
+        addresses
+    }
+
+    /// Share of total assets that is immediately withdrawable, between 0 and 1.
+    ///
+    /// An empty vault returns 1.
+    pub fn liquidity_ratio(&self) -> f64 {
+        if self.total_assets.is_zero() {
+            1.0
+        } else {
+            (u256_to_f64(self.liquidity) / u256_to_f64(self.total_assets)).min(1.0)
+        }
+    }
+
+    /// Returns true if the vault has any red (severe) warnings.
+    pub fn has_red_warnings(&self) -> bool {
+        self.warnings.iter().any(|w| w.is_red())
+    }
+
+    /// Returns true if the vault has a warning of the given type.
+    pub fn has_warning(&self, warning_type: &WarningType) -> bool {
+        self.warnings.iter().any(|w| &w.warning_type == warning_type)
+    }
+
+    /// How much of a `requested` deposit the vault can accept under its caps.
+    ///
+    /// Deposits are allocated to the liquidity adapter, so the cap room is the tightest
+    /// room under the caps on that adapter, on the market it supplies most to (for
+    /// MorphoMarketV1 adapters), and on that market's collateral. Relative caps are
+    /// measured against total assets before the deposit. Without a liquidity adapter
+    /// deposits stay idle and the caps do not limit them; neither do caps the API does
+    /// not describe.
+    ///
+    /// The on-chain `maxDeposit` of V2 vaults always returns zero, because gates can
+    /// make any deposit revert, so it is not part of the result.
+    pub fn deposit_capacity(&self, requested: U256) -> DepositCapacity {
+        let Some(adapter) = self
+            .liquidity_adapter
+            .and_then(|address| self.adapters.iter().find(|a| a.address == address))
+        else {
+            return DepositCapacity::new(requested, None, None, Vec::new());
+        };
+        let market = match &adapter.data {
+            Some(VaultAdapterData::MorphoMarketV1 { positions }) => positions
+                .iter()
+                .max_by_key(|p| p.supply_assets)
+                .map(|p| p.market_id),
+            _ => None,
+        };
+        let collateral = self.caps.iter().find_map(|cap| match &cap.data {
+            Some(VaultCapData::MarketV1 {
+                market_id,
+                collateral,
+                ..
+            }) if Some(*market_id) == market => Some(*collateral),
+            _ => None,
+        });
+
+        let wad = U256::from(1_000_000_000_000_000_000u64);
+        let caps: Vec<CapRoom> = self
+            .caps
+            .iter()
+            .filter(|cap| match &cap.data {
+                Some(VaultCapData::Adapter { adapter: a }) => *a == adapter.address,
+                Some(VaultCapData::MarketV1 {
+                    adapter: a,
+                    market_id,
+                    ..
+                }) => *a == adapter.address && Some(*market_id) == market,
+                Some(VaultCapData::Collateral { collateral: c }) => Some(*c) == collateral,
+                None => false,
+            })
+            .map(|cap| {
+                let mut room = cap.absolute_cap.saturating_sub(cap.allocation);
+                if cap.relative_cap < wad {
+                    let relative = self.total_assets.saturating_mul(cap.relative_cap) / wad;
+                    room = room.min(relative.saturating_sub(cap.allocation));
+                }
+                CapRoom { id: cap.id, room }
+            })
+            .collect();
+        let cap_room = caps.iter().map(|c| c.room).min();
+
+        DepositCapacity::new(requested, None, cap_room, caps)
+    }
+
+    /// Split the vault's yield into lending APY, per-token reward APR, and fee drag.
+    ///
+    /// `base_apy` is the average APY (falling back to the current APY) before fees.
+    /// `fee_drag` is the performance fee's share of it plus the management fee, which
+    /// is charged on assets rather than interest. Missing values count as zero.
+    pub fn apy_breakdown(&self) -> ApyBreakdown {
+        let base_apy = self.avg_apy.or(self.apy).unwrap_or(0.0);
+        let fee_drag =
+            base_apy * self.performance_fee.unwrap_or(0.0) + self.management_fee.unwrap_or(0.0);
+        ApyBreakdown::new(base_apy, &self.rewards, fee_drag)
+    }
+
+    /// Assets redeemable for one whole share ([`VAULT_SHARE_UNIT`]), in the asset's
+    /// smallest unit.
+    ///
+    /// Computed from the API's totals, so interest accrued since the snapshot is not
+    /// included; use `VaultV2Operations::convert_to_assets` for the live on-chain value.
+    pub fn share_price_scaled(&self) -> U256 {
+        self.assets_for_shares(VAULT_SHARE_UNIT)
+    }
+
+    /// Assets redeemable for `shares`, rounded down like `convertToAssets`.
+    pub fn assets_for_shares(&self, shares: U256) -> U256 {
+        self.share_conversion().to_assets(shares)
+    }
+
+    /// Shares minted for `assets`, rounded down like `convertToShares`.
+    pub fn shares_for_assets(&self, assets: U256) -> U256 {
+        self.share_conversion().to_shares(assets)
+    }
+
+    fn share_conversion(&self) -> ShareConversion {
+        ShareConversion {
+            total_assets: self.total_assets,
+            total_supply: self.total_supply,
+            asset_decimals: self.asset.decimals,
+        }
+    }
+}
+
 impl VaultAdapter {
     /// Convert GraphQL response fields into a [`VaultAdapter`].
     ///
@@ -261,6 +470,28 @@ impl VaultAdapter {
     }
 }
 
+impl VaultCap {
+    /// Convert GraphQL response fields into a [`VaultCap`].
+    ///
+    /// Parses the hex `id` string into a [`B256`] and the bigint strings into [`U256`].
+    /// Returns `None` if any of them is invalid.
+    pub fn from_gql(
+        id: &str,
+        absolute_cap: &str,
+        relative_cap: &str,
+        allocation: &str,
+        data: Option<VaultCapData>,
+    ) -> Option<Self> {
+        Some(VaultCap {
+            id: id.parse().ok()?,
+            absolute_cap: parse_bigint(absolute_cap)?,
+            relative_cap: parse_bigint(relative_cap)?,
+            allocation: parse_bigint(allocation)?,
+            data,
+        })
+    }
+}
+
 impl VaultReward {
     /// Convert GraphQL response fields into a [`VaultReward`].
     ///
@@ -342,7 +573,7 @@ impl Vault for VaultV2 {
     }
 
     fn has_critical_warnings(&self) -> bool {
-        self.warnings.iter().any(|w| w.level == "CRITICAL")
+        self.has_red_warnings()
     }
 
     fn clone_box(&self) -> Box<dyn Vault> {
@@ -354,18 +585,107 @@ impl Vault for VaultV2 {
 #[cfg(feature = "sim")]
 mod sim_conversion {
     use super::*;
-    use morpho_rs_sim::{Market, Vault, VaultMarketConfig, VaultSimulation};
+    use morpho_rs_sim::{
+        AdapterMarketPosition, CapId, Market, RoundingDirection, Vault, VaultMarketConfig,
+        VaultSimulation, VaultV2Adapter, VaultV2AdapterKind, VaultV2Cap, VaultV2Simulation,
+        SECONDS_PER_YEAR,
+    };
     use std::collections::HashMap;
 
+    fn market_from_state(ms: &MarketStateV2) -> Market {
+        Market::new_with_oracle(
+            ms.id,
+            ms.total_supply_assets,
+            ms.total_borrow_assets,
+            ms.total_supply_shares,
+            ms.total_borrow_shares,
+            ms.last_update,
+            ms.fee,
+            ms.rate_at_target,
+            ms.price,
+            ms.lltv,
+        )
+    }
+
+    /// The MetaMorpho vault behind an adapter, at one share per asset and without a fee
+    /// (the API reports neither its share supply nor its fee).
+    fn meta_morpho_simulation(
+        address: Address,
+        asset_decimals: u8,
+        allocations: &[&MetaMorphoAllocation],
+    ) -> VaultSimulation {
+        // Build supply and withdraw queues by sorting allocations by queue index
+        let mut supply_queue_items: Vec<_> = allocations
+            .iter()
+            .filter_map(|a| a.supply_queue_index.map(|idx| (idx, a.market_id)))
+            .collect();
+        supply_queue_items.sort_by_key(|(idx, _)| *idx);
+        let supply_queue = supply_queue_items.into_iter().map(|(_, id)| id).collect();
+
+        let mut withdraw_queue_items: Vec<_> = allocations
+            .iter()
+            .filter_map(|a| a.withdraw_queue_index.map(|idx| (idx, a.market_id)))
+            .collect();
+        withdraw_queue_items.sort_by_key(|(idx, _)| *idx);
+        let withdraw_queue = withdraw_queue_items.into_iter().map(|(_, id)| id).collect();
+
+        let mut configs = HashMap::new();
+        let mut markets = HashMap::new();
+        let mut total_assets = U256::ZERO;
+        for alloc in allocations {
+            configs.insert(
+                alloc.market_id,
+                VaultMarketConfig {
+                    market_id: alloc.market_id,
+                    cap: alloc.supply_cap,
+                    supply_assets: alloc.supply_assets,
+                    enabled: alloc.enabled,
+                    public_allocator_config: None, // Not available from API
+                },
+            );
+            if let Some(ms) = &alloc.market_state {
+                markets.insert(ms.id, market_from_state(ms));
+            }
+            total_assets += alloc.supply_assets;
+        }
+
+        let mut vault = Vault {
+            address,
+            asset_decimals,
+            fee: U256::ZERO,
+            management_fee: U256::ZERO,
+            total_assets,
+            total_supply: U256::ZERO,
+            last_total_assets: total_assets,
+            supply_queue,
+            withdraw_queue,
+            allocations: configs,
+            owner: Address::ZERO,
+            public_allocator_config: None,
+        };
+        vault.total_supply = total_assets * vault.virtual_shares();
+
+        VaultSimulation::new(vault, markets)
+    }
+
     impl VaultV2 {
-        /// Convert this vault to a [`VaultSimulation`] for APY and deposit/withdrawal calculations.
+        /// Convert this vault to a [`VaultV2Simulation`] for APY and deposit/withdrawal
+        /// calculations.
         ///
-        /// This method extracts allocation data from MetaMorpho adapters. V2 vaults may have
-        /// multiple adapter types; only MetaMorpho adapters contain the queue-based allocation
-        /// structure needed for simulation. The vault's performance fee is converted from the
-        /// API's fractional representation (e.g., 0.1 = 10%) to WAD-scaled (0.1 * 1e18).
+        /// Each adapter keeps its own allocation logic:
+        /// - MorphoMarketV1 adapters hold supply shares in their markets. If the liquidity
+        ///   adapter is one, deposits and withdrawals go through the market it supplies most
+        ///   to, since the API does not report the vault's liquidity data.
+        /// - MetaMorpho adapters hold shares of their MetaMorpho vault, rebuilt from its
+        ///   allocations with queues and supply caps, at one share per asset and without
+        ///   the MetaMorpho fee.
+        /// - Other adapters, and adapters whose market state is missing, keep their
+        ///   reported assets at 0% APY and cannot be deallocated.
         ///
-        /// Returns `None` if no MetaMorpho adapter with valid allocation data is found.
+        /// Caps are attached to the adapters, markets, and collateral tokens they limit.
+        /// Fees are converted from the API's fractional representation to WAD-scaled; the
+        /// management fee is reported per year and charged per second. The vault is
+        /// assumed to have accrued interest at its most recent market update.
         ///
         /// # Feature Flag
         ///
@@ -377,94 +697,169 @@ mod sim_conversion {
         /// use morpho_rs_api::VaultV2;
         ///
         /// let vault: VaultV2 = /* fetch from API */;
-        /// if let Some(simulation) = vault.to_vault_simulation() {
-        ///     let apy = simulation.get_net_apy(timestamp)?;
-        ///     let (new_sim, shares) = simulation.simulate_deposit(amount, timestamp)?;
-        /// }
+        /// let simulation = vault.to_vault_simulation();
+        /// let apy = simulation.get_net_apy(timestamp)?;
+        /// let (new_sim, shares) = simulation.simulate_deposit(amount, timestamp)?;
         /// ```
-        pub fn to_vault_simulation(&self) -> Option<VaultSimulation> {
-            // Find MetaMorpho adapter with allocations
-            let meta_morpho_allocations = self.adapters.iter().find_map(|adapter| {
-                match &adapter.data {
-                    Some(VaultAdapterData::MetaMorpho { allocations }) => Some(allocations.clone()),
+        pub fn to_vault_simulation(&self) -> VaultV2Simulation {
+            let mut markets = HashMap::new();
+            let mut adapters = Vec::with_capacity(self.adapters.len());
+            for adapter in &self.adapters {
+                adapters.push(VaultV2Adapter {
+                    address: adapter.address,
+                    cap_ids: self.cap_ids(|data| {
+                        matches!(data, VaultCapData::Adapter { adapter: a } if *a == adapter.address)
+                    }),
+                    kind: self.adapter_kind(adapter, &mut markets),
+                });
+            }
+
+            let liquidity_market = self
+                .liquidity_adapter
+                .and_then(|address| self.adapters.iter().find(|a| a.address == address))
+                .and_then(|adapter| match &adapter.data {
+                    Some(VaultAdapterData::MorphoMarketV1 { positions }) => positions
+                        .iter()
+                        .max_by_key(|p| p.supply_assets)
+                        .map(|p| p.market_id),
                     _ => None,
-                }
-            })?;
+                });
 
-            // Build supply and withdraw queues by sorting allocations by queue index
-            let mut supply_queue_items: Vec<_> = meta_morpho_allocations
+            let last_update = adapters
                 .iter()
-                .filter_map(|a| {
-                    a.supply_queue_index.map(|idx| (idx, a.market_id))
+                .flat_map(|adapter| match &adapter.kind {
+                    VaultV2AdapterKind::MetaMorpho { vault, .. } => {
+                        vault.markets.values().map(|m| m.last_update).max()
+                    }
+                    _ => None,
                 })
-                .collect();
-            supply_queue_items.sort_by_key(|(idx, _)| *idx);
-            let supply_queue: Vec<B256> = supply_queue_items.into_iter().map(|(_, id)| id).collect();
+                .chain(markets.values().map(|m| m.last_update))
+                .max()
+                .unwrap_or(0);
 
-            let mut withdraw_queue_items: Vec<_> = meta_morpho_allocations
+            let caps = self
+                .caps
                 .iter()
-                .filter_map(|a| {
-                    a.withdraw_queue_index.map(|idx| (idx, a.market_id))
+                .map(|cap| {
+                    (
+                        cap.id,
+                        VaultV2Cap {
+                            absolute_cap: cap.absolute_cap,
+                            relative_cap: cap.relative_cap,
+                        },
+                    )
                 })
                 .collect();
-            withdraw_queue_items.sort_by_key(|(idx, _)| *idx);
-            let withdraw_queue: Vec<B256> = withdraw_queue_items.into_iter().map(|(_, id)| id).collect();
-
-            // Build allocations HashMap
-            let mut allocations = HashMap::new();
-            for alloc in &meta_morpho_allocations {
-                allocations.insert(
-                    alloc.market_id,
-                    VaultMarketConfig {
-                        market_id: alloc.market_id,
-                        cap: alloc.supply_cap,
-                        supply_assets: alloc.supply_assets,
-                        enabled: alloc.enabled,
-                        public_allocator_config: None, // Not available from API
-                    },
-                );
-            }
-
-            // Build markets HashMap
-            let mut markets = HashMap::new();
-            for alloc in &meta_morpho_allocations {
-                if let Some(ms) = &alloc.market_state {
-                    let market = Market::new_with_oracle(
-                        ms.id,
-                        ms.total_supply_assets,
-                        ms.total_borrow_assets,
-                        ms.total_supply_shares,
-                        ms.total_borrow_shares,
-                        ms.last_update,
-                        ms.fee,
-                        ms.rate_at_target,
-                        ms.price,
-                        ms.lltv,
-                    );
-                    markets.insert(ms.id, market);
-                }
-            }
 
-            // Convert fee from fraction to WAD-scaled
-            // API returns fee as fraction (0.1 = 10%), sim expects WAD (0.1 * 1e18)
-            let fee = self.performance_fee.unwrap_or(0.0);
-            let fee_wad = U256::from((fee * 1e18) as u128);
+            // Convert fees from fractions to WAD-scaled
+            // API returns fees as fractions (0.1 = 10%), sim expects WAD (0.1 * 1e18)
+            let performance_fee = self.performance_fee.unwrap_or(0.0);
+            let management_fee = self.management_fee.unwrap_or(0.0) / SECONDS_PER_YEAR as f64;
 
-            let vault = Vault {
+            VaultV2Simulation {
                 address: self.address,
                 asset_decimals: self.asset.decimals,
-                fee: fee_wad,
+                performance_fee: U256::from((performance_fee * 1e18) as u128),
+                management_fee: U256::from((management_fee * 1e18) as u128),
                 total_assets: self.total_assets,
                 total_supply: self.total_supply,
-                last_total_assets: self.total_assets, // Assume current state is synced
-                supply_queue,
-                withdraw_queue,
-                allocations,
-                owner: self.owner.unwrap_or(Address::ZERO),
-                public_allocator_config: None, // Not available from API
-            };
+                last_update,
+                idle_assets: self.idle_assets,
+                adapters,
+                liquidity_adapter: self.liquidity_adapter,
+                liquidity_market,
+                caps,
+                markets,
+            }
+        }
+
+        /// Ids of the caps whose data matches `f`
+        fn cap_ids(&self, f: impl Fn(&VaultCapData) -> bool) -> Vec<CapId> {
+            self.caps
+                .iter()
+                .filter(|cap| cap.data.as_ref().is_some_and(&f))
+                .map(|cap| cap.id)
+                .collect()
+        }
 
-            Some(VaultSimulation::new(vault, markets))
+        /// Ids of the market and collateral caps limiting `market_id` through `adapter`
+        fn market_cap_ids(&self, adapter: Address, market_id: B256) -> Vec<CapId> {
+            let collateral = self.caps.iter().find_map(|cap| match &cap.data {
+                Some(VaultCapData::MarketV1 {
+                    market_id: id,
+                    collateral,
+                    ..
+                }) if *id == market_id => Some(*collateral),
+                _ => None,
+            });
+            self.cap_ids(|data| match data {
+                VaultCapData::MarketV1 {
+                    adapter: a,
+                    market_id: id,
+                    ..
+                } => *a == adapter && *id == market_id,
+                VaultCapData::Collateral { collateral: c } => Some(*c) == collateral,
+                VaultCapData::Adapter { .. } => false,
+            })
+        }
+
+        fn adapter_kind(
+            &self,
+            adapter: &VaultAdapter,
+            markets: &mut HashMap<B256, Market>,
+        ) -> VaultV2AdapterKind {
+            let fixed = VaultV2AdapterKind::Fixed {
+                assets: adapter.assets,
+            };
+            match &adapter.data {
+                Some(VaultAdapterData::MorphoMarketV1 { positions }) => {
+                    let Some(states) = positions
+                        .iter()
+                        .map(|p| p.market_state.as_ref())
+                        .collect::<Option<Vec<_>>>()
+                    else {
+                        return fixed;
+                    };
+                    for ms in states {
+                        markets.insert(ms.id, market_from_state(ms));
+                    }
+                    VaultV2AdapterKind::MorphoMarketV1 {
+                        positions: positions
+                            .iter()
+                            .map(|p| AdapterMarketPosition {
+                                market_id: p.market_id,
+                                supply_shares: p.supply_shares,
+                                cap_ids: self.market_cap_ids(adapter.address, p.market_id),
+                            })
+                            .collect(),
+                    }
+                }
+                Some(VaultAdapterData::MetaMorpho {
+                    vault_address,
+                    allocations,
+                }) => {
+                    if allocations
+                        .iter()
+                        .any(|a| a.market_state.is_none() && !a.supply_assets.is_zero())
+                    {
+                        return fixed;
+                    }
+                    let allocations: Vec<_> = allocations
+                        .iter()
+                        .filter(|a| a.market_state.is_some())
+                        .collect();
+                    let vault =
+                        meta_morpho_simulation(*vault_address, self.asset.decimals, &allocations);
+                    let shares = vault
+                        .vault
+                        .to_shares(adapter.assets, RoundingDirection::Down);
+                    VaultV2AdapterKind::MetaMorpho {
+                        vault: Box::new(vault),
+                        shares,
+                    }
+                }
+                _ => fixed,
+            }
         }
     }
 }
@@ -472,6 +867,7 @@ mod sim_conversion {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::vault::DepositLimit;
 
     #[test]
     fn test_vault_adapter_from_gql() {
@@ -501,4 +897,448 @@ mod tests {
         assert_eq!(reward.asset_symbol, "MORPHO");
         assert_eq!(reward.supply_apr, Some(0.05));
     }
+
+    fn adapter_with_data(address: &str, data: Option<VaultAdapterData>) -> VaultAdapter {
+        VaultAdapter::from_gql(
+            "adapter".to_string(),
+            address,
+            "MetaMorpho".to_string(),
+            "0",
+            None,
+            data,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_underlying_v1_vault_addresses() {
+        let v1_a = Address::repeat_byte(0xaa);
+        let v1_b = Address::repeat_byte(0xbb);
+        let meta_morpho = |vault_address| {
+            Some(VaultAdapterData::MetaMorpho {
+                vault_address,
+                allocations: vec![],
+            })
+        };
+
+        let vault = VaultV2::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            None,
+            "0",
+            None,
+            "0",
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            "0",
+            None,
+            "0",
+            None,
+            vec![],
+            vec![
+                adapter_with_data("0x0000000000000000000000000000000000000001", meta_morpho(v1_a)),
+                adapter_with_data(
+                    "0x0000000000000000000000000000000000000002",
+                    Some(VaultAdapterData::MorphoMarketV1 { positions: vec![] }),
+                ),
+                adapter_with_data("0x0000000000000000000000000000000000000003", meta_morpho(v1_b)),
+                adapter_with_data("0x0000000000000000000000000000000000000004", meta_morpho(v1_a)),
+            ],
+            vec![],
+            vec![],
+        )
+        .unwrap();
+
+        assert_eq!(vault.underlying_v1_vault_addresses(), vec![v1_a, v1_b]);
+    }
+
+    #[test]
+    fn test_apy_breakdown() {
+        let vault = VaultV2::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            None,
+            "0",
+            None,
+            "0",
+            None,
+            Some(0.1),
+            Some(0.002),
+            Some(0.06),
+            None,
+            Some(0.07),
+            None,
+            "0",
+            None,
+            "0",
+            None,
+            vec![],
+            vec![],
+            vec![VaultReward::from_gql(
+                "0x58D97B57BB95320F9a05dC918Aef65434969c2B2",
+                "MORPHO".to_string(),
+                Some(0.03),
+                None,
+            )
+            .unwrap()],
+            vec![],
+        )
+        .unwrap();
+
+        let breakdown = vault.apy_breakdown();
+        // Average APY takes precedence over the current APY.
+        assert_eq!(breakdown.base_apy, 0.06);
+        assert!((breakdown.fee_drag - 0.008).abs() < 1e-12);
+        assert!((breakdown.native_apy() - 0.052).abs() < 1e-12);
+        assert!((breakdown.rewards_apr() - 0.03).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_share_conversion() {
+        let mut vault = VaultV2::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            None,
+            "0",
+            None,
+            "0",
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            "0",
+            None,
+            "0",
+            None,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        // An empty vault converts one share to one unit of the asset
+        assert_eq!(vault.share_price_scaled(), U256::from(1_000_000));
+
+        // 1,050 USDC backing 1,000 shares
+        vault.total_assets = U256::from(1_050_000_000u64);
+        vault.total_supply = U256::from(10).pow(U256::from(21));
+        assert_eq!(vault.share_price_scaled(), U256::from(1_049_999));
+        assert_eq!(
+            vault.assets_for_shares(vault.total_supply),
+            U256::from(1_049_999_999)
+        );
+        assert_eq!(
+            vault.shares_for_assets(U256::from(1_000_000)),
+            U256::from(952_380_952_426_303_854u64)
+        );
+    }
+
+    #[test]
+    fn test_deposit_capacity() {
+        let market = B256::repeat_byte(0x11);
+        let collateral = Address::repeat_byte(0xcc);
+        let adapter = adapter_with_data(
+            "0x0000000000000000000000000000000000000001",
+            Some(VaultAdapterData::MorphoMarketV1 {
+                positions: vec![MorphoMarketPosition {
+                    supply_assets: U256::from(400),
+                    supply_shares: U256::from(400),
+                    market_id: market,
+                    market_state: None,
+                }],
+            }),
+        );
+        let cap = |id: u8, absolute_cap: u64, relative_cap: u64, data| VaultCap {
+            id: B256::repeat_byte(id),
+            absolute_cap: U256::from(absolute_cap),
+            relative_cap: U256::from(relative_cap),
+            allocation: U256::from(400),
+            data: Some(data),
+        };
+        let wad = 1_000_000_000_000_000_000u64;
+        let caps = vec![
+            cap(0xc1, 1_000, wad, VaultCapData::Adapter { adapter: adapter.address }),
+            cap(
+                0xc2,
+                450,
+                wad,
+                VaultCapData::MarketV1 {
+                    adapter: adapter.address,
+                    market_id: market,
+                    collateral,
+                },
+            ),
+            // 50% of 1400 total assets
+            cap(0xc3, 10_000, wad / 2, VaultCapData::Collateral { collateral }),
+            // Another adapter's cap does not limit deposits
+            cap(
+                0xc4,
+                0,
+                wad,
+                VaultCapData::Adapter {
+                    adapter: Address::repeat_byte(0x02),
+                },
+            ),
+        ];
+
+        let vault = |liquidity_adapter| {
+            VaultV2::from_gql(
+                "0x1234567890123456789012345678901234567890",
+                "Vault".to_string(),
+                "V".to_string(),
+                1,
+                true,
+                true,
+                Asset::from_gql(
+                    "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                    "USDC".to_string(),
+                    None,
+                    6.0,
+                    None,
+                )
+                .unwrap(),
+                None,
+                None,
+                "1400",
+                None,
+                "1400",
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                "0",
+                None,
+                "1000",
+                liquidity_adapter,
+                caps.clone(),
+                vec![adapter.clone()],
+                vec![],
+                vec![],
+            )
+            .unwrap()
+        };
+
+        let capacity =
+            vault(Some("0x0000000000000000000000000000000000000001")).deposit_capacity(U256::from(100));
+        let rooms: Vec<(u8, u64)> = capacity
+            .caps
+            .iter()
+            .map(|c| (c.id.0[0], c.room.to::<u64>()))
+            .collect();
+        assert_eq!(rooms, vec![(0xc1, 600), (0xc2, 50), (0xc3, 300)]);
+        assert_eq!(capacity.max_deposit, None);
+        assert_eq!(capacity.cap_room, Some(U256::from(50)));
+        assert_eq!(capacity.accepted, U256::from(50));
+        assert_eq!(capacity.limited_by, Some(DepositLimit::SupplyCaps));
+
+        // Idle deposits are not capped
+        let capacity = vault(None).deposit_capacity(U256::from(100));
+        assert!(capacity.caps.is_empty());
+        assert_eq!(capacity.cap_room, None);
+        assert!(capacity.is_fully_accepted());
+    }
+
+    #[cfg(feature = "sim")]
+    #[test]
+    fn test_to_vault_simulation_models_adapters_and_caps() {
+        use morpho_rs_sim::{SimError, VaultV2AdapterKind};
+
+        let market_a = B256::repeat_byte(0x11);
+        let market_b = B256::repeat_byte(0x22);
+        let market_state = |id| MarketStateV2 {
+            id,
+            total_supply_assets: U256::from(1_000),
+            total_borrow_assets: U256::from(500),
+            total_supply_shares: U256::from(1_000_000_000),
+            total_borrow_shares: U256::from(500_000_000),
+            last_update: 1000,
+            fee: U256::ZERO,
+            rate_at_target: Some(U256::from(1_268_391_679u64)),
+            price: None,
+            lltv: U256::ZERO,
+        };
+
+        let mut market_adapter = adapter_with_data(
+            "0x0000000000000000000000000000000000000001",
+            Some(VaultAdapterData::MorphoMarketV1 {
+                positions: vec![MorphoMarketPosition {
+                    supply_assets: U256::from(400),
+                    supply_shares: U256::from(400_000_000),
+                    market_id: market_a,
+                    market_state: Some(market_state(market_a)),
+                }],
+            }),
+        );
+        market_adapter.assets = U256::from(400);
+        let mut vault_adapter = adapter_with_data(
+            "0x0000000000000000000000000000000000000002",
+            Some(VaultAdapterData::MetaMorpho {
+                vault_address: Address::repeat_byte(0xaa),
+                allocations: vec![MetaMorphoAllocation {
+                    supply_assets: U256::from(600),
+                    supply_cap: U256::from(1_000),
+                    enabled: true,
+                    supply_queue_index: Some(0),
+                    withdraw_queue_index: Some(0),
+                    market_id: market_b,
+                    market_state: Some(market_state(market_b)),
+                }],
+            }),
+        );
+        vault_adapter.assets = U256::from(600);
+        let mut unknown_adapter = adapter_with_data(
+            "0x0000000000000000000000000000000000000003",
+            Some(VaultAdapterData::Unknown {
+                assets: U256::from(300),
+            }),
+        );
+        unknown_adapter.assets = U256::from(300);
+
+        let collateral = Address::repeat_byte(0xcc);
+        let cap = |id: u8, absolute_cap: u64, data| VaultCap {
+            id: B256::repeat_byte(id),
+            absolute_cap: U256::from(absolute_cap),
+            relative_cap: U256::from(1_000_000_000_000_000_000u64),
+            allocation: U256::ZERO,
+            data: Some(data),
+        };
+        let caps = vec![
+            cap(
+                0xc1,
+                1_000,
+                VaultCapData::Adapter {
+                    adapter: market_adapter.address,
+                },
+            ),
+            cap(
+                0xc2,
+                450,
+                VaultCapData::MarketV1 {
+                    adapter: market_adapter.address,
+                    market_id: market_a,
+                    collateral,
+                },
+            ),
+            cap(0xc3, 10_000, VaultCapData::Collateral { collateral }),
+        ];
+
+        let vault = VaultV2::from_gql(
+            "0x1234567890123456789012345678901234567890",
+            "Vault".to_string(),
+            "V".to_string(),
+            1,
+            true,
+            true,
+            Asset::from_gql(
+                "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "USDC".to_string(),
+                None,
+                6.0,
+                None,
+            )
+            .unwrap(),
+            None,
+            None,
+            "1400",
+            None,
+            "1400",
+            None,
+            Some(0.1),
+            Some(0.02),
+            None,
+            None,
+            None,
+            None,
+            "0",
+            None,
+            "100",
+            Some("0x0000000000000000000000000000000000000001"),
+            caps,
+            vec![market_adapter, vault_adapter, unknown_adapter],
+            vec![],
+            vec![],
+        )
+        .unwrap();
+
+        let sim = vault.to_vault_simulation();
+        assert_eq!(sim.liquidity_market, Some(market_a));
+        assert_eq!(sim.last_update, 1000);
+        assert_eq!(sim.adapters[0].cap_ids, vec![B256::repeat_byte(0xc1)]);
+        match &sim.adapters[0].kind {
+            VaultV2AdapterKind::MorphoMarketV1 { positions } => assert_eq!(
+                positions[0].cap_ids,
+                vec![B256::repeat_byte(0xc2), B256::repeat_byte(0xc3)]
+            ),
+            kind => panic!("unexpected adapter kind {kind:?}"),
+        }
+        assert!(matches!(
+            sim.adapters[1].kind,
+            VaultV2AdapterKind::MetaMorpho { .. }
+        ));
+        assert!(matches!(
+            sim.adapters[2].kind,
+            VaultV2AdapterKind::Fixed { assets } if assets == U256::from(300)
+        ));
+        assert_eq!(sim.real_assets().unwrap(), U256::from(1_400));
+
+        // Deposits go to market A until its 450 cap
+        assert!(sim.simulate_deposit(U256::from(50), 1000).is_ok());
+        assert!(matches!(
+            sim.simulate_deposit(U256::from(100), 1000),
+            Err(SimError::IdCapExceeded { .. })
+        ));
+
+        // Withdrawals: idle, then market A's liquidity; never the other adapters
+        assert_eq!(sim.max_withdraw().unwrap(), U256::from(500));
+    }
 }
@@ -0,0 +1,211 @@
+//! Typed vault warnings.
+//!
+//! The API reports warning types as free-form strings and levels as enums. Both are
+//! parsed case-insensitively into [`WarningType`] and [`WarningLevel`]; values this crate
+//! does not know about are preserved in an `Unknown` variant rather than rejected, so new
+//! warnings introduced by the API do not break deserialization.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Kind of vault warning.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WarningType {
+    /// The vault's deposit asset is not recognized.
+    UnrecognizedDepositAsset,
+    /// The vault's curator is not recognized.
+    UnrecognizedVaultCurator,
+    /// A market the vault allocates to uses an unrecognized oracle.
+    UnrecognizedOracle,
+    /// A market the vault allocates to is not recognized.
+    UnrecognizedMarket,
+    /// The vault is not whitelisted.
+    NotWhitelisted,
+    /// The vault's timelock is shorter than recommended.
+    ShortTimelock,
+    /// The vault's metadata URI is incorrect.
+    IncorrectUri,
+    /// The vault's name is invalid.
+    InvalidName,
+    /// The vault's symbol is invalid.
+    InvalidSymbol,
+    /// A market the vault allocates to has realized bad debt.
+    BadDebtRealized,
+    /// A market the vault allocates to has unrealized bad debt.
+    BadDebtUnrealized,
+    /// The vault has low withdrawable liquidity.
+    LowLiquidity,
+    /// A warning type not known to this crate (original string preserved).
+    Unknown(String),
+}
+
+impl WarningType {
+    /// The API string for this warning type.
+    pub fn as_str(&self) -> &str {
+        match self {
+            WarningType::UnrecognizedDepositAsset => "unrecognized_deposit_asset",
+            WarningType::UnrecognizedVaultCurator => "unrecognized_vault_curator",
+            WarningType::UnrecognizedOracle => "unrecognized_oracle",
+            WarningType::UnrecognizedMarket => "unrecognized_market",
+            WarningType::NotWhitelisted => "not_whitelisted",
+            WarningType::ShortTimelock => "short_timelock",
+            WarningType::IncorrectUri => "incorrect_uri",
+            WarningType::InvalidName => "invalid_name",
+            WarningType::InvalidSymbol => "invalid_symbol",
+            WarningType::BadDebtRealized => "bad_debt_realized",
+            WarningType::BadDebtUnrealized => "bad_debt_unrealized",
+            WarningType::LowLiquidity => "low_liquidity",
+            WarningType::Unknown(s) => s,
+        }
+    }
+}
+
+impl From<&str> for WarningType {
+    fn from(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "unrecognized_deposit_asset" => WarningType::UnrecognizedDepositAsset,
+            "unrecognized_vault_curator" => WarningType::UnrecognizedVaultCurator,
+            "unrecognized_oracle" => WarningType::UnrecognizedOracle,
+            "unrecognized_market" => WarningType::UnrecognizedMarket,
+            "not_whitelisted" => WarningType::NotWhitelisted,
+            "short_timelock" => WarningType::ShortTimelock,
+            "incorrect_uri" => WarningType::IncorrectUri,
+            "invalid_name" => WarningType::InvalidName,
+            "invalid_symbol" => WarningType::InvalidSymbol,
+            "bad_debt_realized" => WarningType::BadDebtRealized,
+            "bad_debt_unrealized" => WarningType::BadDebtUnrealized,
+            "low_liquidity" => WarningType::LowLiquidity,
+            _ => WarningType::Unknown(s.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for WarningType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for WarningType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for WarningType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(WarningType::from(s.as_str()))
+    }
+}
+
+/// Severity of a vault warning.
+///
+/// V1 warnings are `Yellow` or `Red`; V2 warnings may also be `Green` (informational).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WarningLevel {
+    /// Informational (V2 only).
+    Green,
+    /// Caution.
+    Yellow,
+    /// Severe; the vault should generally be avoided.
+    Red,
+    /// A level not known to this crate (original string preserved).
+    Unknown(String),
+}
+
+impl WarningLevel {
+    /// The API string for this level.
+    pub fn as_str(&self) -> &str {
+        match self {
+            WarningLevel::Green => "GREEN",
+            WarningLevel::Yellow => "YELLOW",
+            WarningLevel::Red => "RED",
+            WarningLevel::Unknown(s) => s,
+        }
+    }
+}
+
+impl From<&str> for WarningLevel {
+    fn from(s: &str) -> Self {
+        match s.to_ascii_uppercase().as_str() {
+            "GREEN" => WarningLevel::Green,
+            "YELLOW" => WarningLevel::Yellow,
+            "RED" => WarningLevel::Red,
+            _ => WarningLevel::Unknown(s.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for WarningLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for WarningLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for WarningLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(WarningLevel::from(s.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_type_parse_case_insensitive() {
+        assert_eq!(
+            WarningType::from("bad_debt_unrealized"),
+            WarningType::BadDebtUnrealized
+        );
+        assert_eq!(
+            WarningType::from("LOW_LIQUIDITY"),
+            WarningType::LowLiquidity
+        );
+        assert_eq!(
+            WarningType::from("Unrecognized_Oracle"),
+            WarningType::UnrecognizedOracle
+        );
+    }
+
+    #[test]
+    fn test_warning_type_unknown_preserved() {
+        let t = WarningType::from("brand_new_warning");
+        assert_eq!(t, WarningType::Unknown("brand_new_warning".to_string()));
+        assert_eq!(t.as_str(), "brand_new_warning");
+    }
+
+    #[test]
+    fn test_warning_level_parse() {
+        assert_eq!(WarningLevel::from("RED"), WarningLevel::Red);
+        assert_eq!(WarningLevel::from("Yellow"), WarningLevel::Yellow);
+        assert_eq!(WarningLevel::from("green"), WarningLevel::Green);
+        assert_eq!(
+            WarningLevel::from("ORANGE"),
+            WarningLevel::Unknown("ORANGE".to_string())
+        );
+    }
+
+    #[test]
+    fn test_warning_serde_roundtrip() {
+        let json = serde_json::to_string(&(WarningType::ShortTimelock, WarningLevel::Red)).unwrap();
+        assert_eq!(json, r#"["short_timelock","RED"]"#);
+        let parsed: (WarningType, WarningLevel) = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, (WarningType::ShortTimelock, WarningLevel::Red));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(WarningType::NotWhitelisted.to_string(), "not_whitelisted");
+        assert_eq!(WarningLevel::Yellow.to_string(), "YELLOW");
+    }
+}
@@ -0,0 +1,332 @@
+//! Token amount parsing, formatting, and arithmetic.
+//!
+//! [`TokenAmount`] pairs a raw on-chain integer amount with its token's decimals, so
+//! conversions between human-readable strings and smallest units happen in one place.
+//!
+//! # Example
+//!
+//! ```
+//! use morpho_rs_api::units::TokenAmount;
+//!
+//! let amount = TokenAmount::parse("1,234.5 USDC", 6).unwrap();
+//! assert_eq!(amount.raw.to_string(), "1234500000");
+//! assert_eq!(amount.format(2), "1,234.50");
+//! assert_eq!(amount.to_string(), "1234.5");
+//! assert_eq!(amount.value_usd(2.0), 2469.0);
+//! ```
+
+use std::fmt;
+
+use alloy_primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ApiError, Result};
+
+/// A token amount in the token's smallest unit, with its decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenAmount {
+    /// Amount in the token's smallest unit.
+    pub raw: U256,
+    /// Number of decimals of the token.
+    pub decimals: u8,
+}
+
+impl TokenAmount {
+    /// Create an amount from a raw value.
+    pub fn new(raw: U256, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// A zero amount.
+    pub fn zero(decimals: u8) -> Self {
+        Self::new(U256::ZERO, decimals)
+    }
+
+    /// Returns true if the amount is zero.
+    pub fn is_zero(&self) -> bool {
+        self.raw.is_zero()
+    }
+
+    /// Parse a human-readable amount such as `"1,234.5"` or `"1,234.5 USDC"`.
+    ///
+    /// Commas group thousands in the integer part, and an optional trailing symbol
+    /// (separated by whitespace) is ignored. Fractional digits beyond `decimals` are
+    /// truncated, matching how vaults round user amounts down.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ApiError::InvalidAmount`] if the input is empty, negative, contains
+    /// misplaced separators or non-digit characters, or overflows `U256`.
+    pub fn parse(input: &str, decimals: u8) -> Result<Self> {
+        let invalid = || ApiError::InvalidAmount(input.to_string());
+
+        let mut tokens = input.split_whitespace();
+        let number = tokens.next().ok_or_else(invalid)?;
+        // At most one trailing token (the symbol).
+        if tokens.nth(1).is_some() {
+            return Err(invalid());
+        }
+
+        let (integer_part, fractional_part) = match number.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (number, ""),
+        };
+        let integer_digits = strip_thousands_separators(integer_part).ok_or_else(invalid)?;
+        if (integer_digits.is_empty() && fractional_part.is_empty())
+            || !integer_digits.chars().all(|c| c.is_ascii_digit())
+            || !fractional_part.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let fractional = &fractional_part[..fractional_part.len().min(decimals as usize)];
+        let digits = format!(
+            "{}{:0<width$}",
+            integer_digits,
+            fractional,
+            width = decimals as usize
+        );
+        let digits = digits.trim_start_matches('0');
+        if digits.is_empty() {
+            return Ok(Self::zero(decimals));
+        }
+
+        let raw = U256::from_str_radix(digits, 10).map_err(|_| invalid())?;
+        Ok(Self::new(raw, decimals))
+    }
+
+    /// Format with exactly `precision` fractional digits (truncated) and thousands
+    /// separators, e.g. `"1,234.50"`.
+    pub fn format(&self, precision: usize) -> String {
+        let (integer, fraction) = self.split();
+        let mut fraction = fraction;
+        fraction.truncate(precision);
+        let fraction = format!("{:0<width$}", fraction, width = precision);
+
+        let integer = group_thousands(&integer);
+        if precision == 0 {
+            integer
+        } else {
+            format!("{}.{}", integer, fraction)
+        }
+    }
+
+    /// Approximate amount in whole tokens.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(0.0)
+    }
+
+    /// USD value given the token's USD price.
+    pub fn value_usd(&self, price_usd: f64) -> f64 {
+        self.to_f64() * price_usd
+    }
+
+    /// Add two amounts of the same token.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::DecimalsMismatch`] if the decimals differ
+    /// - [`ApiError::InvalidAmount`] on overflow
+    pub fn checked_add(self, other: Self) -> Result<Self> {
+        self.ensure_same_decimals(&other)?;
+        let raw = self
+            .raw
+            .checked_add(other.raw)
+            .ok_or_else(|| ApiError::InvalidAmount("addition overflows".to_string()))?;
+        Ok(Self::new(raw, self.decimals))
+    }
+
+    /// Subtract an amount of the same token.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::DecimalsMismatch`] if the decimals differ
+    /// - [`ApiError::InvalidAmount`] if `other` is larger than `self`
+    pub fn checked_sub(self, other: Self) -> Result<Self> {
+        self.ensure_same_decimals(&other)?;
+        let raw = self
+            .raw
+            .checked_sub(other.raw)
+            .ok_or_else(|| ApiError::InvalidAmount("subtraction underflows".to_string()))?;
+        Ok(Self::new(raw, self.decimals))
+    }
+
+    /// Subtract an amount of the same token, clamping at zero.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::DecimalsMismatch`] if the decimals differ
+    pub fn saturating_sub(self, other: Self) -> Result<Self> {
+        self.ensure_same_decimals(&other)?;
+        Ok(Self::new(self.raw.saturating_sub(other.raw), self.decimals))
+    }
+
+    fn ensure_same_decimals(&self, other: &Self) -> Result<()> {
+        if self.decimals != other.decimals {
+            return Err(ApiError::DecimalsMismatch {
+                left: self.decimals,
+                right: other.decimals,
+            });
+        }
+        Ok(())
+    }
+
+    /// Integer digits and full-precision fractional digits.
+    fn split(&self) -> (String, String) {
+        let digits = self.raw.to_string();
+        let decimals = self.decimals as usize;
+        if decimals == 0 {
+            return (digits, String::new());
+        }
+        let padded = format!("{:0>width$}", digits, width = decimals + 1);
+        let (integer, fraction) = padded.split_at(padded.len() - decimals);
+        (integer.to_string(), fraction.to_string())
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    /// Full precision without trailing zeros or separators, e.g. `"1234.5"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (integer, fraction) = self.split();
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{}", integer)
+        } else {
+            write!(f, "{}.{}", integer, fraction)
+        }
+    }
+}
+
+/// Remove thousands separators, checking that every group after the first has three
+/// digits. Returns `None` for misplaced separators.
+fn strip_thousands_separators(integer: &str) -> Option<String> {
+    if !integer.contains(',') {
+        return Some(integer.to_string());
+    }
+    let mut groups = integer.split(',');
+    let first = groups.next()?;
+    if first.is_empty() || first.len() > 3 {
+        return None;
+    }
+    let mut digits = first.to_string();
+    for group in groups {
+        if group.len() != 3 {
+            return None;
+        }
+        digits.push_str(group);
+    }
+    Some(digits)
+}
+
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with_separators_and_symbol() {
+        let amount = TokenAmount::parse("1,234.5 USDC", 6).unwrap();
+        assert_eq!(amount.raw, U256::from(1_234_500_000u64));
+        assert_eq!(amount.decimals, 6);
+    }
+
+    #[test]
+    fn test_parse_plain_and_fractional() {
+        assert_eq!(
+            TokenAmount::parse("100", 18).unwrap().raw,
+            U256::from(100_000_000_000_000_000_000u128)
+        );
+        assert_eq!(
+            TokenAmount::parse(".5", 18).unwrap().raw,
+            U256::from(500_000_000_000_000_000u128)
+        );
+        assert!(TokenAmount::parse("0.0", 18).unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_parse_truncates_excess_decimals() {
+        let amount = TokenAmount::parse("1.123456789", 6).unwrap();
+        assert_eq!(amount.raw, U256::from(1_123_456u64));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_input() {
+        for input in [
+            "",
+            ".",
+            "-1",
+            "1.2.3",
+            "100abc",
+            "1,23.0",
+            "12,3456",
+            ",123",
+            "1.0,00",
+            "1 USDC extra",
+        ] {
+            assert!(
+                matches!(
+                    TokenAmount::parse(input, 6),
+                    Err(ApiError::InvalidAmount(_))
+                ),
+                "{input:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        let amount = TokenAmount::new(U256::from(1_234_567_890u64), 6);
+        assert_eq!(amount.format(2), "1,234.56");
+        assert_eq!(amount.format(0), "1,234");
+        assert_eq!(amount.format(8), "1,234.56789000");
+        assert_eq!(TokenAmount::new(U256::from(5u64), 6).format(6), "0.000005");
+        assert_eq!(
+            TokenAmount::new(U256::from(1_234_567u64), 0).format(2),
+            "1,234,567.00"
+        );
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for input in ["0", "1", "1234.5", "0.000001"] {
+            let amount = TokenAmount::parse(input, 6).unwrap();
+            assert_eq!(amount.to_string(), input);
+            assert_eq!(TokenAmount::parse(&amount.to_string(), 6).unwrap(), amount);
+        }
+    }
+
+    #[test]
+    fn test_value_usd() {
+        let amount = TokenAmount::parse("2.5", 18).unwrap();
+        assert_eq!(amount.value_usd(2000.0), 5000.0);
+    }
+
+    #[test]
+    fn test_arithmetic_guards_decimals() {
+        let usdc = TokenAmount::parse("1", 6).unwrap();
+        let weth = TokenAmount::parse("1", 18).unwrap();
+        assert!(matches!(
+            usdc.checked_add(weth),
+            Err(ApiError::DecimalsMismatch { left: 6, right: 18 })
+        ));
+
+        let two = usdc.checked_add(usdc).unwrap();
+        assert_eq!(two.to_string(), "2");
+        assert_eq!(two.checked_sub(usdc).unwrap(), usdc);
+        assert!(matches!(
+            usdc.checked_sub(two),
+            Err(ApiError::InvalidAmount(_))
+        ));
+        assert!(usdc.saturating_sub(two).unwrap().is_zero());
+    }
+}
@@ -0,0 +1,136 @@
+//! Authentication integration tests: SIWE sessions, API keys, and custom headers.
+
+mod helpers;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use helpers::{client_config_with_mock, load_fixture, start_mock_server};
+use morpho_rs_api::{SiweAuthConfig, SiweAuthenticator, VaultV1Client};
+use url::Url;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// Anvil's default account 0 private key
+const TEST_PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+fn authenticator(server: &MockServer) -> SiweAuthenticator {
+    let config = SiweAuthConfig::new(
+        Url::parse(&format!("{}/nonce", server.uri())).unwrap(),
+        Url::parse(&format!("{}/login", server.uri())).unwrap(),
+        "example.com",
+    );
+    SiweAuthenticator::new(TEST_PRIVATE_KEY, config).unwrap()
+}
+
+async fn mock_sign_in(server: &MockServer, token: &str, expected_logins: u64) {
+    let expires_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 3600;
+    Mock::given(method("GET"))
+        .and(path("/nonce"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"nonce":"abc"}"#))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"{{"token":"{}","expiresAt":{}}}"#,
+            token, expires_at
+        )))
+        .expect(expected_logins)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_session_token_attached_to_queries() {
+    let server = start_mock_server().await;
+    mock_sign_in(&server, "session-1", 1).await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(header("authorization", "Bearer session-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_list")))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let config = client_config_with_mock(&server).with_siwe_auth(authenticator(&server));
+    let client = VaultV1Client::with_config(config);
+
+    // The second query reuses the cached session
+    assert_eq!(client.get_vaults(None).await.unwrap().len(), 2);
+    assert_eq!(client.get_vaults(None).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_rejected_session_signs_in_again() {
+    let server = start_mock_server().await;
+    mock_sign_in(&server, "session-1", 2).await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(401))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_list")))
+        .mount(&server)
+        .await;
+
+    let config = client_config_with_mock(&server)
+        .with_retry_base_delay_ms(10)
+        .with_siwe_auth(authenticator(&server));
+    let client = VaultV1Client::with_config(config);
+
+    assert_eq!(client.get_vaults(None).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_persistent_unauthorized_returns_authentication_error() {
+    let server = start_mock_server().await;
+    mock_sign_in(&server, "session-1", 2).await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let config = client_config_with_mock(&server)
+        .with_max_retries(1)
+        .with_retry_base_delay_ms(10)
+        .with_siwe_auth(authenticator(&server));
+    let client = VaultV1Client::with_config(config);
+
+    let result = client.get_vaults(None).await;
+    assert!(matches!(
+        result,
+        Err(morpho_rs_api::ApiError::Authentication(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_api_key_and_headers_attached_to_queries() {
+    let server = start_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(header(morpho_rs_api::API_KEY_HEADER, "key-1"))
+        .and(header("x-client", "dashboard"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_list")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = client_config_with_mock(&server)
+        .with_api_key("key-1")
+        .with_header("x-client", "dashboard");
+    let client = VaultV1Client::with_config(config);
+
+    assert_eq!(client.get_vaults(None).await.unwrap().len(), 2);
+}
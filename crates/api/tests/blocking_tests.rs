@@ -0,0 +1,49 @@
+//! Blocking client integration tests.
+
+#![cfg(feature = "blocking")]
+
+mod helpers;
+
+use alloy_primitives::address;
+use helpers::{client_config_with_mock, mock_graphql_response, start_mock_server};
+use morpho_rs_api::blocking::MorphoClient;
+use morpho_rs_api::{MorphoClientConfig, NamedChain};
+
+#[test]
+fn test_blocking_get_vaults_v1() {
+    // The mock server lives on its own runtime; the blocking client drives its own.
+    let server_runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = server_runtime.block_on(async {
+        let server = start_mock_server().await;
+        mock_graphql_response(&server, "v1_list").await;
+        server
+    });
+
+    let config = MorphoClientConfig::new().with_api_config(client_config_with_mock(&server));
+    let client = MorphoClient::with_config(config).unwrap();
+
+    let vaults = client.get_vaults_v1_by_chain(NamedChain::Mainnet).unwrap();
+    assert_eq!(vaults.len(), 2);
+    assert_eq!(vaults[0].name, "Steakhouse USDC");
+}
+
+#[test]
+fn test_blocking_get_vault_v2() {
+    let server_runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = server_runtime.block_on(async {
+        let server = start_mock_server().await;
+        mock_graphql_response(&server, "v2_info").await;
+        server
+    });
+
+    let config = MorphoClientConfig::new().with_api_config(client_config_with_mock(&server));
+    let client = MorphoClient::with_config(config).unwrap();
+
+    let vault = client
+        .get_vault_v2(
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
+            NamedChain::Mainnet,
+        )
+        .unwrap();
+    assert_eq!(vault.name, "Test V2 USDC Vault");
+}
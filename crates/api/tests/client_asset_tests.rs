@@ -0,0 +1,54 @@
+//! Asset query tests using wiremock.
+
+mod helpers;
+
+use alloy_primitives::address;
+use helpers::{client_config_with_mock, mock_graphql_response, start_mock_server};
+use morpho_rs_api::{MorphoApiClient, NamedChain};
+
+#[tokio::test]
+async fn test_get_assets() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "assets").await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    let assets = client
+        .get_assets(NamedChain::Mainnet, &["USDC", "WETH"])
+        .await
+        .unwrap();
+
+    // The asset with an invalid address is skipped
+    assert_eq!(assets.len(), 2);
+    assert_eq!(assets[0].symbol, "USDC");
+    assert_eq!(assets[0].decimals, 6);
+    assert_eq!(assets[0].price_usd, Some(1.0));
+    assert_eq!(assets[1].symbol, "WETH");
+    assert_eq!(assets[1].name.as_deref(), Some("Wrapped Ether"));
+    assert_eq!(assets[1].price_usd, Some(3000.0));
+}
+
+#[tokio::test]
+async fn test_get_asset_price_history() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "asset_price_history").await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    let history = client
+        .get_asset_price_history(
+            address!("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+            NamedChain::Mainnet,
+            1_700_000_000..1_700_200_000,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(history.asset.symbol, "WETH");
+    assert_eq!(history.asset.price_usd, Some(3000.0));
+
+    // Points are sorted and the one without a price is dropped
+    assert_eq!(history.prices.len(), 2);
+    assert_eq!(history.prices[0].timestamp, 1_700_000_000);
+    assert_eq!(history.prices[1].timestamp, 1_700_086_400);
+    assert_eq!(history.price_at(1_700_050_000), Some(2000.0));
+    assert_eq!(history.price_at(1_700_200_000), Some(2100.0));
+}
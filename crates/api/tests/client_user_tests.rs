@@ -2,9 +2,33 @@
 
 mod helpers;
 
-use alloy_primitives::U256;
+use alloy_primitives::{address, U256};
 use helpers::{client_config_with_mock, mock_graphql_response, start_mock_server};
-use morpho_rs_api::{MorphoApiClient, MorphoClient, NamedChain};
+use morpho_rs_api::{MorphoApiClient, MorphoClient, NamedChain, TransactionData, TransactionKind};
+use wiremock::matchers::{body_string_contains, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Mock the V1 and V2 transaction queries with their fixtures.
+async fn mock_user_transactions(server: &MockServer) {
+    for (operation, fixture) in [
+        (
+            r#""operationName":"GetUserTransactions""#,
+            "user_transactions",
+        ),
+        (
+            r#""operationName":"GetUserVaultV2Transactions""#,
+            "user_vault_v2_transactions",
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(body_string_contains(operation))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(helpers::load_fixture(fixture)),
+            )
+            .mount(server)
+            .await;
+    }
+}
 
 #[tokio::test]
 async fn test_get_user_vault_positions_single_chain() {
@@ -16,7 +40,7 @@ async fn test_get_user_vault_positions_single_chain() {
 
     let positions = client
         .get_user_vault_positions(
-            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
             Some(NamedChain::Mainnet),
         )
         .await
@@ -68,7 +92,7 @@ async fn test_get_user_positions_v1_and_v2() {
 
     let positions = client
         .get_user_vault_positions(
-            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
             Some(NamedChain::Mainnet),
         )
         .await
@@ -111,7 +135,7 @@ async fn test_morpho_client_user_positions() {
 
     let positions = client
         .get_user_vault_positions(
-            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
             Some(NamedChain::Mainnet),
         )
         .await
@@ -124,7 +148,7 @@ async fn test_morpho_client_user_positions() {
     mock_graphql_response(&server, "user_positions").await;
     let direct_positions = api_client
         .get_user_vault_positions(
-            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
             Some(NamedChain::Mainnet),
         )
         .await
@@ -162,7 +186,7 @@ async fn test_user_position_vault_info() {
 
     let positions = client
         .get_user_vault_positions(
-            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
             Some(NamedChain::Mainnet),
         )
         .await
@@ -187,3 +211,207 @@ async fn test_user_position_vault_info() {
     assert_eq!(v2_vault.name, "Test V2 USDC Vault");
     assert_eq!(v2_vault.symbol, "tv2USDC");
 }
+
+#[tokio::test]
+async fn test_get_user_transactions() {
+    let server = start_mock_server().await;
+    mock_user_transactions(&server).await;
+
+    let config = client_config_with_mock(&server);
+    let client = MorphoApiClient::with_config(config);
+
+    let history = client
+        .get_user_transactions(
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+            NamedChain::Mainnet,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // The repayment with an invalid hash is skipped
+    let transactions = &history.transactions;
+    assert_eq!(transactions.len(), 5);
+    assert_eq!(history.skipped, 1);
+    assert!(!history.truncated);
+    assert!(!history.is_complete());
+
+    let deposit = &transactions[0];
+    assert_eq!(deposit.kind, TransactionKind::VaultDeposit);
+    assert_eq!(deposit.timestamp, 1_700_000_000);
+    assert_eq!(deposit.block_number, 18_600_000);
+    assert_eq!(deposit.log_index, 4);
+    assert_eq!(deposit.chain, NamedChain::Mainnet);
+    assert_eq!(
+        deposit.data,
+        TransactionData::Vault {
+            vault: address!("0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB"),
+            shares: U256::from(1_000u64) * U256::from(10u64).pow(U256::from(18u64)),
+            assets: U256::from(1_000_000_000u64),
+            assets_usd: Some(1000.0),
+        }
+    );
+
+    // The V2 deposit is merged in timestamp order, without a USD value
+    let v2_deposit = &transactions[1];
+    assert_eq!(v2_deposit.kind, TransactionKind::VaultDeposit);
+    assert_eq!(v2_deposit.block_number, 19_000_000);
+    assert_eq!(
+        v2_deposit.data,
+        TransactionData::Vault {
+            vault: address!("0xabcdef1234567890abcdef1234567890abcdef12"),
+            shares: U256::from(2_000u64) * U256::from(10u64).pow(U256::from(18u64)),
+            assets: U256::from(2_000_000_000u64),
+            assets_usd: None,
+        }
+    );
+
+    assert_eq!(
+        transactions[3].kind,
+        TransactionKind::MarketSupplyCollateral
+    );
+    assert!(matches!(
+        transactions[4].data,
+        TransactionData::Market { assets_usd: Some(usd), .. } if usd == 1500.0
+    ));
+}
+
+#[tokio::test]
+async fn test_get_user_transactions_truncated() {
+    let server = start_mock_server().await;
+    // Every page is full and the total is never reached
+    let body = helpers::load_fixture("user_transactions")
+        .replace(r#""countTotal": 5"#, r#""countTotal": 1000000"#);
+    Mock::given(method("POST"))
+        .and(body_string_contains(
+            r#""operationName":"GetUserTransactions""#,
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server).with_page_size(5));
+    let history = client
+        .get_user_transactions(
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+            NamedChain::Mainnet,
+            Some(TransactionKind::MarketBorrow),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(history.truncated);
+    assert!(!history.is_complete());
+}
+
+#[tokio::test]
+async fn test_get_user_transactions_filters() {
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("GetUserTransactions"))
+        .and(body_string_contains(r#""types":["MarketBorrow"]"#))
+        .and(body_string_contains(r#""timestampGte":1710000000"#))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(helpers::load_fixture("user_transactions")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    client
+        .get_user_transactions(
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+            NamedChain::Mainnet,
+            Some(TransactionKind::MarketBorrow),
+            Some(1_710_000_000),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_user_market_state() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "user_market_state").await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    let user = address!("0x1234567890123456789012345678901234567890");
+    let key = "0xb323495f7e4148be5643a4ea4a8221eef163e4bccfdedc2a6f4696baacbc86cc";
+    let state = client
+        .get_user_market_state(user, key, NamedChain::Mainnet)
+        .await
+        .unwrap();
+
+    assert_eq!(state.user, user);
+    assert_eq!(
+        state.position.collateral,
+        U256::from(10u64).pow(U256::from(18))
+    );
+    assert_eq!(state.position.borrow_assets, U256::from(500_000_000u64));
+    assert_eq!(state.position.market.unique_key, key);
+    assert_eq!(
+        state.position.market.collateral_asset_address,
+        Some(address!("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"))
+    );
+    assert_eq!(state.market.liquidity, U256::from(200_000_000u64));
+
+    #[cfg(feature = "sim")]
+    {
+        use morpho_rs_sim::CapacityLimitReason;
+
+        let caps = state.capacities(U256::from(100_000_000u64), U256::ZERO);
+        // Collateral allows ~2080 USDC more, but only 200 USDC is available.
+        assert_eq!(caps.borrow.reason, CapacityLimitReason::Liquidity);
+        assert_eq!(caps.borrow.value, U256::from(200_000_000u64));
+        assert_eq!(caps.repay.reason, CapacityLimitReason::Balance);
+        assert_eq!(
+            caps.withdraw_collateral.reason,
+            CapacityLimitReason::Collateral
+        );
+        assert!(
+            caps.withdraw_collateral.value
+                > U256::from(8u64) * U256::from(10u64).pow(U256::from(17))
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_user_market_state_without_position_or_market_state() {
+    let server = start_mock_server().await;
+    let mut body: serde_json::Value =
+        serde_json::from_str(&helpers::load_fixture("user_market_state")).unwrap();
+    body["data"]["marketPositions"]["items"] = serde_json::json!([]);
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    body["data"]["marketByUniqueKey"]["state"] = serde_json::Value::Null;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+        .mount(&server)
+        .await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    let user = address!("0x1234567890123456789012345678901234567890");
+    let key = "0xb323495f7e4148be5643a4ea4a8221eef163e4bccfdedc2a6f4696baacbc86cc";
+
+    let state = client
+        .get_user_market_state(user, key, NamedChain::Mainnet)
+        .await
+        .unwrap();
+    assert_eq!(state.position.borrow_shares, U256::ZERO);
+    assert_eq!(state.position.collateral, U256::ZERO);
+
+    let err = client
+        .get_user_market_state(user, key, NamedChain::Mainnet)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        morpho_rs_api::ApiError::MarketNotFound { .. }
+    ));
+}
@@ -2,10 +2,11 @@
 
 mod helpers;
 
-use alloy_primitives::U256;
+use alloy_primitives::{address, Address, U256};
 use helpers::{client_config_with_mock, mock_graphql_response, start_mock_server};
 use morpho_rs_api::{
-    NamedChain, OrderDirection, VaultFiltersV1, VaultOrderByV1, VaultQueryOptionsV1, VaultV1Client,
+    ApiError, ConversionMode, NamedChain, OrderDirection, VaultFieldSelection, VaultFiltersV1,
+    VaultOrderByV1, VaultQueryOptionsV1, VaultV1Client, SUPPORTED_CHAINS,
 };
 
 #[tokio::test]
@@ -69,7 +70,7 @@ async fn test_get_vault_single() {
 
     let vault = client
         .get_vault(
-            "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
             NamedChain::Mainnet,
         )
         .await
@@ -84,6 +85,39 @@ async fn test_get_vault_single() {
     );
 }
 
+#[tokio::test]
+async fn test_get_vault_fee_info() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_fee_info").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV1Client::with_config(config);
+
+    let info = client
+        .get_vault_fee_info(
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(info.chain, NamedChain::Mainnet);
+    assert_eq!(info.fee, 0.15);
+    assert_eq!(
+        info.fee_recipient,
+        "0x255c7705e8BB334DfCae438197f7C4297988085a"
+            .parse::<Address>()
+            .unwrap()
+    );
+    assert_eq!(info.total_assets, U256::from(1_000_000_000_000u64));
+    assert_eq!(info.last_total_assets, U256::from(999_000_000_000u64));
+
+    // 1000 units of interest since the last accrual, 15% of it to the fee recipient
+    assert_eq!(info.pending_interest(), U256::from(1_000_000_000u64));
+    assert_eq!(info.pending_fee_assets(), U256::from(150_000_000u64));
+}
+
+
 #[tokio::test]
 async fn test_get_vault_with_allocations() {
     let server = start_mock_server().await;
@@ -94,7 +128,7 @@ async fn test_get_vault_with_allocations() {
 
     let vault = client
         .get_vault(
-            "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
             NamedChain::Mainnet,
         )
         .await
@@ -126,11 +160,116 @@ async fn test_get_vault_with_allocations() {
         market_state.liquidity,
         U256::from(500_000_000_000u64) // total_supply - total_borrow
     );
+    assert_eq!(
+        market_state.realized_bad_debt,
+        Some(U256::from(1_500_000u64))
+    );
 
     // Check second allocation
     let alloc2 = &state.allocation[1];
     assert_eq!(alloc2.collateral_asset_symbol, Some("wstETH".to_string()));
     assert_eq!(alloc2.supply_queue_index, Some(1));
+
+    // Check rewards
+    assert_eq!(state.rewards.len(), 1);
+    assert_eq!(state.rewards[0].asset_symbol, "MORPHO");
+    let breakdown = vault.apy_breakdown().unwrap();
+    assert!((breakdown.native_apy() - 0.068).abs() < 1e-9);
+    assert!((breakdown.total_apy() - state.net_apy).abs() < 1e-9);
+
+    // Check public allocator flow caps resolve to market params
+    let public_allocator = vault.public_allocator_config.as_ref().unwrap();
+    assert_eq!(public_allocator.fee, U256::from(1_000_000_000_000_000u64));
+    assert_eq!(public_allocator.flow_caps.len(), 1);
+    assert_eq!(
+        public_allocator.flow_caps[0].max_out,
+        U256::from(250_000_000_000u64)
+    );
+    let params = vault
+        .public_allocator_market_params(public_allocator.flow_caps[0].market_id)
+        .unwrap();
+    assert_eq!(
+        params.collateralToken,
+        address!("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2")
+    );
+    assert_eq!(params.lltv, U256::from(860_000_000_000_000_000u64));
+}
+
+#[tokio::test]
+async fn test_get_vault_at_and_diff() {
+    use helpers::load_fixture;
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains(r#""operationName":"GetVaultV1ByAddress""#))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(load_fixture("v1_info_with_markets")),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains(r#""operationName":"GetVaultV1History""#))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_history")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = VaultV1Client::with_config(client_config_with_mock(&server));
+    let address = address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458");
+    let before = client
+        .get_vault_at(address, NamedChain::Mainnet, 1_700_000_000)
+        .await
+        .unwrap();
+    let after = client
+        .get_vault(address, NamedChain::Mainnet)
+        .await
+        .unwrap();
+
+    let state = before.state.as_ref().unwrap();
+    assert_eq!(state.fee, 0.12);
+    assert_eq!(state.total_assets, U256::from(600_000_000_000u64));
+    // The guardian point has no value, so the current guardian is kept.
+    assert_eq!(state.guardian, after.state.as_ref().unwrap().guardian);
+    // The second market has no history at the requested time.
+    assert_eq!(state.allocation.len(), 2);
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.fee.as_ref().unwrap().after, 0.15);
+    assert!(diff.curator.is_some());
+    assert!(diff.owner.is_none());
+    let cap_keys: Vec<&str> = diff.caps.iter().map(|c| &c.market_key[..6]).collect();
+    assert_eq!(cap_keys, vec!["0x1234", "0x5555", "0xabcd"]);
+    assert!(diff.caps[1].after.is_none());
+    assert!(diff.caps[2].before.is_none());
+}
+
+#[tokio::test]
+async fn test_get_vault_share_price_history() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_share_price_history").await;
+
+    let client = VaultV1Client::with_config(client_config_with_mock(&server));
+    let history = client
+        .get_vault_share_price_history(
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
+            NamedChain::Mainnet,
+            1_700_000_000..1_701_209_600,
+        )
+        .await
+        .unwrap();
+
+    // Sorted, with the point without a value left out.
+    let timestamps: Vec<u64> = history.share_prices.iter().map(|p| p.timestamp).collect();
+    assert_eq!(timestamps, vec![1_700_000_000, 1_700_604_800, 1_701_209_600]);
+    assert_eq!(history.net_apys.len(), 3);
+
+    // 0.1% a week realizes ~5.3% against 8% advertised.
+    let realization = history.yield_realization(7 * 86_400, 0.005);
+    assert_eq!(realization.windows.len(), 2);
+    assert!((realization.windows[0].realized_apy - 0.0535).abs() < 1e-3);
+    assert!(realization.consistently_lags(1.0));
 }
 
 #[tokio::test]
@@ -150,6 +289,38 @@ async fn test_get_vaults_by_chain() {
     }
 }
 
+#[tokio::test]
+async fn test_get_vaults_all_chains_records_failed_chain() {
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains(r#""chainId_in":[8453]"#))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"{"errors":[{"message":"boom"}],"data":null}"#),
+        )
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    mock_graphql_response(&server, "v1_list").await;
+
+    let client = VaultV1Client::with_config(client_config_with_mock(&server));
+    let results = client
+        .get_vaults_all_chains(Some(VaultFiltersV1::new().listed(true)))
+        .await;
+
+    assert!(!results.is_complete());
+    assert_eq!(
+        results.failed_chains().collect::<Vec<_>>(),
+        vec![NamedChain::Base]
+    );
+    assert_eq!(results.results.len(), SUPPORTED_CHAINS.len() - 1);
+    assert_eq!(results.results[0].0, NamedChain::Mainnet);
+    assert_eq!(results.flatten().len(), 2 * (SUPPORTED_CHAINS.len() - 1));
+}
+
 #[tokio::test]
 async fn test_get_vaults_with_filters() {
     let server = start_mock_server().await;
@@ -260,7 +431,7 @@ async fn test_vault_trait_implementation() {
 
     let vault = client
         .get_vault(
-            "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
             NamedChain::Mainnet,
         )
         .await
@@ -279,3 +450,102 @@ async fn test_vault_trait_implementation() {
     assert_eq!(vault.net_apy(), 0.068);
     assert!(!vault.has_critical_warnings());
 }
+
+#[tokio::test]
+async fn test_get_vault_with_fields_full() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_info").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV1Client::with_config(config);
+
+    let vault = client
+        .get_vault_with_fields(
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
+            NamedChain::Mainnet,
+            &VaultFieldSelection::full(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(vault.name, "Steakhouse USDC");
+    assert!(vault.listed);
+    let state = vault.state.unwrap();
+    assert_eq!(state.fee, 0.15);
+    assert_eq!(state.timelock, 86400);
+    assert!(state.curator.is_some());
+}
+
+#[tokio::test]
+async fn test_get_vaults_with_fields_minimal() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_list_minimal").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV1Client::with_config(config);
+
+    let vaults = client
+        .get_vaults_with_fields(None, &VaultFieldSelection::minimal())
+        .await
+        .unwrap();
+
+    assert_eq!(vaults.len(), 2);
+    assert_eq!(vaults[0].name, "Steakhouse USDC");
+    assert!(!vaults[0].listed);
+    assert!(vaults[0].warnings.is_empty());
+
+    let state = vaults[0].state.as_ref().unwrap();
+    assert_eq!(state.total_assets, U256::from(1_000_000_000_000u64));
+    assert_eq!(state.net_apy, 0.068);
+    assert_eq!(state.fee, 0.0);
+    assert!(state.allocation.is_empty());
+    assert!(vaults[1].state.is_none());
+}
+
+#[tokio::test]
+async fn test_get_vaults_lenient_records_diagnostics() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_list_invalid").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV1Client::with_config(config);
+
+    let converted = client.get_vaults_with_diagnostics(None).await.unwrap();
+
+    assert_eq!(converted.value.len(), 1);
+    assert_eq!(converted.value[0].name, "Steakhouse USDC");
+    assert!(!converted.is_clean());
+    assert_eq!(converted.diagnostics.len(), 1);
+    let error = &converted.diagnostics[0];
+    assert_eq!(error.field, "address");
+    assert_eq!(error.value, "0xnot-an-address");
+    assert_eq!(error.vault_address, "0xnot-an-address");
+}
+
+#[tokio::test]
+async fn test_get_vaults_strict_fails_on_invalid_vault() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_list_invalid").await;
+
+    let config = client_config_with_mock(&server).with_conversion_mode(ConversionMode::Strict);
+    let client = VaultV1Client::with_config(config);
+
+    let err = client.get_vaults(None).await.unwrap_err();
+    match err {
+        ApiError::Conversion(error) => assert_eq!(error.field, "address"),
+        other => panic!("expected conversion error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_get_vaults_with_diagnostics_clean() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_list").await;
+
+    let config = client_config_with_mock(&server).with_conversion_mode(ConversionMode::Strict);
+    let client = VaultV1Client::with_config(config);
+
+    let converted = client.get_vaults_with_diagnostics(None).await.unwrap();
+    assert_eq!(converted.value.len(), 2);
+    assert!(converted.is_clean());
+}
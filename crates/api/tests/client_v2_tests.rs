@@ -2,10 +2,12 @@
 
 mod helpers;
 
-use alloy_primitives::U256;
+use alloy_primitives::{address, U256};
 use helpers::{client_config_with_mock, mock_graphql_response, start_mock_server};
 use morpho_rs_api::{
-    NamedChain, OrderDirection, VaultFiltersV2, VaultOrderByV2, VaultQueryOptionsV2, VaultV2Client,
+    ApiError, ConversionMode, NamedChain, OrderDirection, VaultCapData, VaultFiltersV2,
+    VaultOrderByV2, VaultQueryOptionsV2, VaultRankByV2, VaultV2Client, WarningLevel, WarningType,
+    SUPPORTED_CHAINS,
 };
 
 #[tokio::test]
@@ -64,7 +66,7 @@ async fn test_get_vault_v2_single() {
 
     let vault = client
         .get_vault(
-            "0xABCdef1234567890ABCdef1234567890ABCdef12",
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
             NamedChain::Mainnet,
         )
         .await
@@ -95,6 +97,44 @@ async fn test_get_vaults_v2_by_chain() {
     }
 }
 
+#[tokio::test]
+async fn test_get_vaults_v2_all_chains() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_list").await;
+
+    let client = VaultV2Client::with_config(client_config_with_mock(&server));
+    let results = client.get_vaults_all_chains(None).await;
+
+    assert!(results.is_complete());
+    let chains: Vec<NamedChain> = results.results.iter().map(|(c, _)| *c).collect();
+    assert_eq!(chains, SUPPORTED_CHAINS);
+    assert!(results.results.iter().all(|(_, vaults)| vaults.len() == 2));
+}
+
+#[tokio::test]
+async fn test_get_vaults_v2_ranked_client_side() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_list").await;
+
+    let client = VaultV2Client::with_config(client_config_with_mock(&server));
+
+    // The USDC vault comes first from the server, but the WETH vault keeps half of its
+    // assets liquid against a quarter for USDC.
+    let options = VaultQueryOptionsV2::new()
+        .rank_by(VaultRankByV2::LiquidityRatio)
+        .limit(1);
+    let vaults = client.get_vaults_with_options(options).await.unwrap();
+    assert_eq!(vaults.len(), 1);
+    assert_eq!(vaults[0].asset.symbol, "WETH");
+
+    let options = VaultQueryOptionsV2::new()
+        .rank_by(VaultRankByV2::TotalApy)
+        .order_direction(OrderDirection::Asc);
+    let vaults = client.get_vaults_with_options(options).await.unwrap();
+    let symbols: Vec<&str> = vaults.iter().map(|v| v.asset.symbol.as_str()).collect();
+    assert_eq!(symbols, vec!["WETH", "USDC"]);
+}
+
 #[tokio::test]
 async fn test_get_vaults_v2_with_filters() {
     let server = start_mock_server().await;
@@ -354,7 +394,7 @@ async fn test_vault_v2_trait_implementation() {
 
     let vault = client
         .get_vault(
-            "0xABCdef1234567890ABCdef1234567890ABCdef12",
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
             NamedChain::Mainnet,
         )
         .await
@@ -385,7 +425,7 @@ async fn test_vault_v2_rewards() {
 
     let vault = client
         .get_vault(
-            "0xABCdef1234567890ABCdef1234567890ABCdef12",
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
             NamedChain::Mainnet,
         )
         .await
@@ -407,14 +447,177 @@ async fn test_vault_v2_warnings() {
 
     let vault = client
         .get_vault(
-            "0xdef1234567890ABCdef1234567890ABCdef12345",
+            address!("0xdef1234567890ABCdef1234567890ABCdef12345"),
             NamedChain::Mainnet,
         )
         .await
         .unwrap();
 
     assert_eq!(vault.warnings.len(), 1);
-    assert_eq!(vault.warnings[0].warning_type, "LOW_LIQUIDITY");
-    // Level is formatted as Debug from the enum
-    assert!(vault.warnings[0].level.contains("Yellow"));
+    assert_eq!(vault.warnings[0].warning_type, WarningType::LowLiquidity);
+    assert_eq!(vault.warnings[0].level, WarningLevel::Yellow);
+    assert!(!vault.has_red_warnings());
+}
+
+#[tokio::test]
+async fn test_vault_v2_metamorpho_adapter_vault_address() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_info_metamorpho").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV2Client::with_config(config);
+
+    let vault = client
+        .get_vault(
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap();
+
+    let expected: alloy_primitives::Address = "0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB"
+        .parse()
+        .unwrap();
+    assert_eq!(vault.underlying_v1_vault_addresses(), vec![expected]);
+}
+
+#[tokio::test]
+async fn test_get_v2_underlying_v1_vaults() {
+    use helpers::load_fixture;
+    use morpho_rs_api::MorphoApiClient;
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("GetVaultV2ByAddress"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(load_fixture("v2_info_metamorpho")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("GetVaultV1ByAddress"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_info")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    let vaults = client
+        .get_v2_underlying_v1_vaults(
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(vaults.len(), 1);
+    assert_eq!(vaults[0].name, "Steakhouse USDC");
+}
+
+#[tokio::test]
+async fn test_get_vaults_lenient_records_diagnostics() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_list_invalid").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV2Client::with_config(config);
+
+    let converted = client.get_vaults_with_diagnostics(None).await.unwrap();
+
+    assert_eq!(converted.value.len(), 1);
+    assert_eq!(converted.value[0].name, "Test V2 USDC Vault");
+    assert_eq!(converted.diagnostics.len(), 1);
+    let error = &converted.diagnostics[0];
+    assert_eq!(error.field, "totalSupply");
+    assert_eq!(error.value, "not-a-number");
+    assert_eq!(
+        error.vault_address,
+        "0xdef1234567890ABCdef1234567890ABCdef12345"
+    );
+}
+
+#[tokio::test]
+async fn test_get_vaults_strict_fails_on_invalid_vault() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_list_invalid").await;
+
+    let config = client_config_with_mock(&server).with_conversion_mode(ConversionMode::Strict);
+    let client = VaultV2Client::with_config(config);
+
+    let err = client.get_vaults(None).await.unwrap_err();
+    assert!(matches!(err, ApiError::Conversion(ref e) if e.field == "totalSupply"));
+}
+
+#[tokio::test]
+async fn test_vault_v2_idle_assets() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_info_metamorpho").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV2Client::with_config(config);
+
+    let vault = client
+        .get_vault(
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(vault.idle_assets, U256::from(50_000_000_000u64));
+}
+
+#[tokio::test]
+async fn test_vault_v2_liquidity_adapter_and_caps() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_info_metamorpho").await;
+
+    let config = client_config_with_mock(&server);
+    let client = VaultV2Client::with_config(config);
+
+    let vault = client
+        .get_vault(
+            address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap();
+
+    let adapter = vault.adapters[0].address;
+    assert_eq!(vault.liquidity_adapter, Some(adapter));
+    assert_eq!(vault.caps.len(), 1);
+    assert_eq!(
+        vault.caps[0].absolute_cap,
+        U256::from(10_000_000_000_000u64)
+    );
+    assert_eq!(vault.caps[0].allocation, U256::from(5_000_000_000_000u64));
+    assert_eq!(vault.caps[0].data, Some(VaultCapData::Adapter { adapter }));
+}
+
+#[tokio::test]
+async fn test_get_vault_share_price_history() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v2_share_price_history").await;
+
+    let client = VaultV2Client::with_config(client_config_with_mock(&server));
+    let history = client
+        .get_vault_share_price_history(
+            address!("0x1234567890123456789012345678901234567890"),
+            NamedChain::Mainnet,
+            1_700_000_000..1_701_209_600,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(history.share_prices.len(), 3);
+    assert_eq!(history.net_apys.len(), 2);
+
+    let realization = history.yield_realization(7 * 86_400, 0.005);
+    assert_eq!(realization.windows.len(), 2);
+    assert_eq!(realization.windows[1].advertised_net_apy, 0.055);
+    assert_eq!(realization.lag_fraction(), 0.0);
+    assert!(!realization.consistently_lags(0.5));
 }
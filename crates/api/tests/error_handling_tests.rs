@@ -2,6 +2,7 @@
 
 mod helpers;
 
+use alloy_primitives::{address, Address};
 use helpers::{
     client_config_with_mock, mock_graphql_error, mock_graphql_errors, mock_null_data,
     start_mock_server,
@@ -78,9 +79,7 @@ async fn test_get_vault_not_found_graphql_error() {
     let config = client_config_with_mock(&server);
     let client = VaultV1Client::with_config(config);
 
-    let result = client
-        .get_vault("0x0000000000000000000000000000000000000000", NamedChain::Mainnet)
-        .await;
+    let result = client.get_vault(Address::ZERO, NamedChain::Mainnet).await;
 
     assert!(result.is_err());
     match result.unwrap_err() {
@@ -100,9 +99,7 @@ async fn test_get_vault_v2_not_found_graphql_error() {
     let config = client_config_with_mock(&server);
     let client = VaultV2Client::with_config(config);
 
-    let result = client
-        .get_vault("0x0000000000000000000000000000000000000000", NamedChain::Mainnet)
-        .await;
+    let result = client.get_vault(Address::ZERO, NamedChain::Mainnet).await;
 
     assert!(result.is_err());
     match result.unwrap_err() {
@@ -125,7 +122,7 @@ async fn test_get_user_positions_no_results_ignored() {
 
     // Query all chains (chain = None) - should return empty positions, not error
     let result = client
-        .get_user_vault_positions("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", None)
+        .get_user_vault_positions(address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"), None)
         .await;
 
     // The error filtering happens, so we get an Ok result with empty positions
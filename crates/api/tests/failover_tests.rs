@@ -0,0 +1,102 @@
+//! API endpoint failover and circuit breaker integration tests.
+
+mod helpers;
+
+use helpers::{load_fixture, mock_graphql_error, start_mock_server};
+use morpho_rs_api::{ClientConfig, EndpointState, MorphoApiClient, VaultV1Client};
+use url::Url;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Create a client config with `primary` as the API URL and `mirror` as its fallback.
+fn failover_config(primary: &MockServer, mirror: &MockServer) -> ClientConfig {
+    ClientConfig::new()
+        .with_api_urls([
+            Url::parse(&primary.uri()).unwrap(),
+            Url::parse(&mirror.uri()).unwrap(),
+        ])
+        .with_retry_base_delay_ms(10)
+}
+
+async fn mount_server_error(server: &MockServer, expected: u64) {
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .expect(expected)
+        .mount(server)
+        .await;
+}
+
+async fn mount_v1_list(server: &MockServer) {
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_list")))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_fails_over_on_server_error() {
+    let primary = start_mock_server().await;
+    let mirror = start_mock_server().await;
+    mount_server_error(&primary, 1).await;
+    mount_v1_list(&mirror).await;
+
+    let client = VaultV1Client::with_config(failover_config(&primary, &mirror));
+    let vaults = client.get_vaults(None).await.unwrap();
+    assert_eq!(vaults.len(), 2);
+}
+
+#[tokio::test]
+async fn test_fails_over_on_graphql_error() {
+    let primary = start_mock_server().await;
+    let mirror = start_mock_server().await;
+    mock_graphql_error(&primary, "Indexer unavailable").await;
+    mount_v1_list(&mirror).await;
+
+    let client = VaultV1Client::with_config(failover_config(&primary, &mirror));
+    let vaults = client.get_vaults(None).await.unwrap();
+    assert_eq!(vaults.len(), 2);
+}
+
+#[tokio::test]
+async fn test_open_circuit_skips_endpoint() {
+    let primary = start_mock_server().await;
+    let mirror = start_mock_server().await;
+    // Only the first query reaches the primary; its circuit is then open
+    mount_server_error(&primary, 1).await;
+    mount_v1_list(&mirror).await;
+
+    let config = failover_config(&primary, &mirror).with_circuit_breaker(1, 60);
+    let client = MorphoApiClient::with_config(config);
+    client.v1.get_vaults(None).await.unwrap();
+    client.v1.get_vaults(None).await.unwrap();
+
+    // V1 and V2 clients share one health registry
+    let config = client.v2.config();
+    let status = config.endpoint_health.status(&config.api_urls());
+    assert_eq!(status[0].state, EndpointState::Open);
+    assert_eq!(status[1].state, EndpointState::Closed);
+}
+
+#[tokio::test]
+async fn test_check_endpoints() {
+    let primary = start_mock_server().await;
+    let mirror = start_mock_server().await;
+    mount_server_error(&primary, 1).await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(r#"{"data":{"__typename":"Query"}}"#),
+        )
+        .mount(&mirror)
+        .await;
+
+    let config = failover_config(&primary, &mirror).with_circuit_breaker(1, 60);
+    let client = MorphoApiClient::with_config(config);
+    let status = client.check_endpoints().await;
+
+    assert_eq!(status.len(), 2);
+    assert_eq!(status[0].state, EndpointState::Open);
+    assert_eq!(status[0].consecutive_failures, 1);
+    assert!(status[0].latency.is_none());
+    assert_eq!(status[1].state, EndpointState::Closed);
+    assert!(status[1].latency.is_some());
+}
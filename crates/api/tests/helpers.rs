@@ -1,5 +1,7 @@
 //! Test helper utilities for API crate integration tests.
 
+#![allow(dead_code)]
+
 use morpho_rs_api::ClientConfig;
 use url::Url;
 use wiremock::matchers::method;
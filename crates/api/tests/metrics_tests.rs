@@ -0,0 +1,112 @@
+//! Metrics hook integration tests.
+
+mod helpers;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use helpers::{client_config_with_mock, load_fixture, mock_graphql_errors, start_mock_server};
+use morpho_rs_api::{Metrics, VaultV1Client};
+use wiremock::matchers::method;
+use wiremock::{Mock, ResponseTemplate};
+
+#[derive(Debug, Default)]
+struct RecordingMetrics {
+    successes: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    graphql_errors: AtomicU64,
+    operations: Mutex<Vec<String>>,
+}
+
+impl Metrics for RecordingMetrics {
+    fn record_api_request(&self, operation: &str, _latency: Duration, success: bool) {
+        if success {
+            self.successes.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        self.operations.lock().unwrap().push(operation.to_string());
+    }
+
+    fn record_api_retry(&self, _operation: &str, _attempt: u32) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_graphql_errors(&self, _operation: &str, count: usize) {
+        self.graphql_errors
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+}
+
+#[tokio::test]
+async fn test_metrics_record_successful_request() {
+    let server = start_mock_server().await;
+    let body = load_fixture("v1_list");
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+
+    let metrics = Arc::new(RecordingMetrics::default());
+    let config = client_config_with_mock(&server).with_metrics(metrics.clone());
+    let client = VaultV1Client::with_config(config);
+
+    client.get_vaults(None).await.unwrap();
+
+    assert_eq!(metrics.successes.load(Ordering::Relaxed), 1);
+    assert_eq!(metrics.failures.load(Ordering::Relaxed), 0);
+    assert_eq!(metrics.retries.load(Ordering::Relaxed), 0);
+    assert_eq!(
+        metrics.operations.lock().unwrap().as_slice(),
+        ["GetVaultsV1".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn test_metrics_record_retries_and_failures() {
+    let server = start_mock_server().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("Server Error"))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+
+    let body = load_fixture("v1_list");
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+
+    let metrics = Arc::new(RecordingMetrics::default());
+    let config = client_config_with_mock(&server)
+        .with_max_retries(3)
+        .with_retry_base_delay_ms(10)
+        .with_metrics(metrics.clone());
+    let client = VaultV1Client::with_config(config);
+
+    client.get_vaults(None).await.unwrap();
+
+    assert_eq!(metrics.failures.load(Ordering::Relaxed), 2);
+    assert_eq!(metrics.retries.load(Ordering::Relaxed), 2);
+    assert_eq!(metrics.successes.load(Ordering::Relaxed), 1);
+}
+
+#[tokio::test]
+async fn test_metrics_record_graphql_errors() {
+    let server = start_mock_server().await;
+    mock_graphql_errors(&server, &["First error", "Second error"]).await;
+
+    let metrics = Arc::new(RecordingMetrics::default());
+    let config = client_config_with_mock(&server).with_metrics(metrics.clone());
+    let client = VaultV1Client::with_config(config);
+
+    let result = client.get_vaults(None).await;
+    assert!(result.is_err());
+
+    assert_eq!(metrics.graphql_errors.load(Ordering::Relaxed), 2);
+    assert_eq!(metrics.failures.load(Ordering::Relaxed), 1);
+    assert_eq!(metrics.successes.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,132 @@
+//! Query middleware integration tests.
+
+mod helpers;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use helpers::{client_config_with_mock, load_fixture, start_mock_server};
+use morpho_rs_api::{ApiError, QueryMiddleware, QueryOutcome, QueryRequest, Result, VaultV1Client};
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, ResponseTemplate};
+
+/// Records hook calls as `"<name>:before:<operation>"` / `"<name>:after:<ok|err>"`.
+#[derive(Debug)]
+struct Recorder {
+    name: &'static str,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl QueryMiddleware for Recorder {
+    fn before(&self, request: &mut QueryRequest) -> Result<()> {
+        assert_eq!(request.variables()["first"], 100);
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{}:before:{}", self.name, request.operation()));
+        Ok(())
+    }
+
+    fn after(&self, _request: &QueryRequest, _duration: Duration, outcome: QueryOutcome<'_>) {
+        let outcome = if outcome.is_success() { "ok" } else { "err" };
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{}:after:{}", self.name, outcome));
+    }
+}
+
+#[derive(Debug)]
+struct Signer;
+
+impl QueryMiddleware for Signer {
+    fn before(&self, request: &mut QueryRequest) -> Result<()> {
+        let signature = format!("sig-{}", request.operation());
+        request.insert_header("x-signature", signature);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct FailAll;
+
+impl QueryMiddleware for FailAll {
+    fn before(&self, _request: &mut QueryRequest) -> Result<()> {
+        Err(ApiError::GraphQL("injected failure".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_middleware_hooks_run_in_order() {
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_list")))
+        .mount(&server)
+        .await;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let config = client_config_with_mock(&server)
+        .with_middleware(Arc::new(Recorder {
+            name: "outer",
+            events: events.clone(),
+        }))
+        .with_middleware(Arc::new(Recorder {
+            name: "inner",
+            events: events.clone(),
+        }));
+    let client = VaultV1Client::with_config(config);
+
+    client.get_vaults(None).await.unwrap();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "outer:before:GetVaultsV1",
+            "inner:before:GetVaultsV1",
+            "inner:after:ok",
+            "outer:after:ok",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_middleware_headers_sent_with_request() {
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(header("x-signature", "sig-GetVaultsV1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_list")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = client_config_with_mock(&server).with_middleware(Arc::new(Signer));
+    let client = VaultV1Client::with_config(config);
+
+    assert_eq!(client.get_vaults(None).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_middleware_error_skips_request() {
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_list")))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let config = client_config_with_mock(&server)
+        .with_middleware(Arc::new(Recorder {
+            name: "log",
+            events: events.clone(),
+        }))
+        .with_middleware(Arc::new(FailAll));
+    let client = VaultV1Client::with_config(config);
+
+    let result = client.get_vaults(None).await;
+    assert!(matches!(result, Err(ApiError::GraphQL(msg)) if msg == "injected failure"));
+    assert_eq!(
+        *events.lock().unwrap(),
+        ["log:before:GetVaultsV1", "log:after:err"]
+    );
+}
@@ -0,0 +1,57 @@
+//! Tests for VaultMonitor polling against a mock API.
+
+mod helpers;
+
+use alloy_chains::NamedChain;
+use alloy_primitives::address;
+use helpers::{client_config_with_mock, load_fixture, start_mock_server};
+use morpho_rs_api::reports::TrackedVault;
+use morpho_rs_api::{
+    ApiError, MonitorConfig, MorphoApiClient, VaultMonitor, VaultVersion, WatchRule,
+};
+use wiremock::matchers::{body_string_contains, method};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn test_poll_reports_failing_vault_and_keeps_others() {
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("GetVaultV2ByAddress"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"{"errors":[{"message":"upstream timeout"}],"data":null}"#),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture("v1_info")))
+        .mount(&server)
+        .await;
+
+    let v1 = address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458");
+    let v2 = address!("0x0000000000000000000000000000000000000002");
+    let config = MonitorConfig::new()
+        .with_vault(TrackedVault {
+            address: v1,
+            chain: NamedChain::Mainnet,
+            version: VaultVersion::V1,
+        })
+        .with_vault(TrackedVault {
+            address: v2,
+            chain: NamedChain::Mainnet,
+            version: VaultVersion::V2,
+        })
+        .with_rule(WatchRule::NewRedWarning);
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    let mut monitor = VaultMonitor::new(config);
+
+    let report = monitor.poll(&client).await;
+    assert!(report.alerts.is_empty());
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].vault.address, v2);
+    assert!(matches!(report.errors[0].error, ApiError::GraphQL(_)));
+
+    // The failing vault does not stop the next poll
+    let report = monitor.poll(&client).await;
+    assert_eq!(report.errors.len(), 1);
+}
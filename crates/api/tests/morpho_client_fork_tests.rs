@@ -139,6 +139,22 @@ fn test_vault_operations_signer_address() {
     );
 }
 
+#[tokio::test]
+async fn test_migrate_rejects_zero_shares() {
+    let config = MorphoClientConfig::new()
+        .with_rpc_url("http://localhost:8545")
+        .with_private_key(TEST_PRIVATE_KEY);
+    let client = MorphoClient::with_config(config).expect("Failed to create client");
+
+    // Rejected before any RPC call
+    let result = client
+        .vault_v1()
+        .expect("Failed to get v1 operations")
+        .migrate(STEAKHOUSE_USDC_VAULT, STEAKHOUSE_USDC_VAULT, U256::ZERO)
+        .await;
+    assert!(matches!(result, Err(ApiError::InvalidAmount(_))));
+}
+
 // ============================================================================
 // VaultV1Operations Fork Tests
 // ============================================================================
@@ -151,7 +167,7 @@ async fn test_v1_operations_get_asset() {
     };
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
 
@@ -169,7 +185,7 @@ async fn test_v1_operations_get_balance() {
     };
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
 
@@ -190,7 +206,7 @@ async fn test_v1_operations_deposit_with_auto_approve() {
     let anvil_provider = ProviderBuilder::new().connect_http(anvil.endpoint_url());
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY)
         .with_auto_approve(true);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
@@ -239,7 +255,7 @@ async fn test_v1_operations_withdraw() {
     let anvil_provider = ProviderBuilder::new().connect_http(anvil.endpoint_url());
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY)
         .with_auto_approve(true);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
@@ -275,6 +291,88 @@ async fn test_v1_operations_withdraw() {
     println!("✓ V1 withdraw: withdrew {} USDC", withdraw_amount);
 }
 
+#[tokio::test]
+#[ignore = "Requires ETH_RPC_URL environment variable"]
+async fn test_v1_operations_check_deposit() {
+    let Some(anvil) = spawn_forked_anvil() else {
+        return;
+    };
+
+    let anvil_provider = ProviderBuilder::new().connect_http(anvil.endpoint_url());
+
+    let config = MorphoClientConfig::new()
+        .with_rpc_url(anvil.endpoint())
+        .with_private_key(TEST_PRIVATE_KEY)
+        .with_auto_approve(true);
+    let client = MorphoClient::with_config(config).expect("Failed to create client");
+
+    let v1_ops = client.vault_v1().expect("Failed to get v1 operations");
+    let signer = v1_ops.signer_address();
+
+    let deposit_amount = U256::from(1_000_000_000u64); // 1,000 USDC
+    fund_account_with_usdc(&anvil_provider, signer, deposit_amount).await;
+
+    let capacity = v1_ops
+        .check_deposit(STEAKHOUSE_USDC_VAULT, deposit_amount)
+        .await
+        .expect("Failed to check deposit");
+    assert!(capacity.max_deposit.is_some());
+    assert!(!capacity.caps.is_empty());
+    assert_eq!(capacity.accepted, deposit_amount, "1,000 USDC fits under the caps");
+
+    let outcome = v1_ops
+        .deposit_max_available(STEAKHOUSE_USDC_VAULT, deposit_amount)
+        .await
+        .expect("Failed to deposit");
+    assert_eq!(outcome.assets_in, capacity.accepted);
+
+    println!("✓ V1 check_deposit: {} of {} accepted", capacity.accepted, deposit_amount);
+}
+
+#[tokio::test]
+#[ignore = "Requires ETH_RPC_URL environment variable"]
+async fn test_v1_operations_migrate() {
+    let Some(anvil) = spawn_forked_anvil() else {
+        return;
+    };
+
+    let anvil_provider = ProviderBuilder::new().connect_http(anvil.endpoint_url());
+
+    let config = MorphoClientConfig::new()
+        .with_rpc_url(anvil.endpoint())
+        .with_private_key(TEST_PRIVATE_KEY)
+        .with_auto_approve(true);
+    let client = MorphoClient::with_config(config).expect("Failed to create client");
+
+    let v1_ops = client.vault_v1().expect("Failed to get v1 operations");
+    let signer = v1_ops.signer_address();
+
+    let deposit_amount = U256::from(1_000_000_000u64); // 1,000 USDC
+    fund_account_with_usdc(&anvil_provider, signer, deposit_amount).await;
+    v1_ops
+        .deposit(STEAKHOUSE_USDC_VAULT, deposit_amount)
+        .await
+        .expect("Failed to deposit");
+
+    // Round-trip half the shares through the same vault: redeem, then re-deposit
+    let shares = v1_ops.balance(STEAKHOUSE_USDC_VAULT).await.expect("Failed to get balance") / U256::from(2);
+    let outcome = v1_ops
+        .migrate(STEAKHOUSE_USDC_VAULT, STEAKHOUSE_USDC_VAULT, shares)
+        .await
+        .expect("Failed to migrate");
+
+    assert!(!outcome.atomic, "EOA migrations run as two transactions");
+    assert_eq!(outcome.withdraw.shares_burned, shares);
+    assert_eq!(outcome.deposit.assets_in, outcome.withdraw.assets_out);
+    assert!(outcome.withdraw.effective_share_price > U256::ZERO);
+    assert!(outcome.deposit.effective_share_price > U256::ZERO);
+
+    println!(
+        "✓ V1 migrate: {} shares -> {} USDC -> {} shares",
+        shares, outcome.withdraw.assets_out, outcome.deposit.shares_minted
+    );
+}
+
 #[tokio::test]
 #[ignore = "Requires ETH_RPC_URL environment variable"]
 async fn test_v1_operations_get_decimals() {
@@ -283,7 +381,7 @@ async fn test_v1_operations_get_decimals() {
     };
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
 
@@ -302,7 +400,7 @@ async fn test_v1_operations_convert_to_assets() {
     };
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
 
@@ -338,7 +436,7 @@ async fn test_v2_operations_get_asset() {
     };
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
 
@@ -358,7 +456,7 @@ async fn test_v2_operations_deposit() {
     let anvil_provider = ProviderBuilder::new().connect_http(anvil.endpoint_url());
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY)
         .with_auto_approve(true);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
@@ -405,7 +503,7 @@ async fn test_v2_operations_get_allowance() {
     };
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
 
@@ -428,7 +526,7 @@ async fn test_v2_operations_approve_returns_none_when_sufficient() {
     };
 
     let config = MorphoClientConfig::new()
-        .with_rpc_url(&anvil.endpoint())
+        .with_rpc_url(anvil.endpoint())
         .with_private_key(TEST_PRIVATE_KEY);
     let client = MorphoClient::with_config(config).expect("Failed to create client");
 
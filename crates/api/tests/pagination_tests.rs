@@ -3,12 +3,9 @@
 mod helpers;
 
 use helpers::{client_config_with_mock, load_fixture, start_mock_server};
-use morpho_rs_api::{
-    ClientConfig, NamedChain, VaultFiltersV1, VaultFiltersV2, VaultQueryOptionsV1,
-    VaultQueryOptionsV2, VaultV1Client, VaultV2Client,
-};
+use morpho_rs_api::{VaultQueryOptionsV1, VaultQueryOptionsV2, VaultV1Client, VaultV2Client};
 use wiremock::matchers::{body_string_contains, method};
-use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::{Mock, ResponseTemplate};
 
 /// Create a V1 page response JSON with specified vault count and total.
 fn v1_page_fixture(vaults: &[(&str, &str, &str)], count_total: i64) -> String {
@@ -43,6 +40,7 @@ fn v1_page_fixture(vaults: &[(&str, &str, &str)], count_total: i64) -> String {
                         "timelock": "86400",
                         "apy": 0.05,
                         "netApy": 0.045,
+                        "rewards": [],
                         "sharePrice": "1000000",
                         "allocation": []
                     }},
@@ -95,6 +93,9 @@ fn v2_page_fixture(vaults: &[(&str, &str, &str)], count_total: i64) -> String {
                     "netApy": 0.057,
                     "liquidity": "500000000000",
                     "liquidityUsd": 500000.0,
+                    "idleAssets": "0",
+                    "liquidityAdapter": null,
+                    "caps": {{ "items": [] }},
                     "adapters": {{ "items": [] }},
                     "rewards": [],
                     "warnings": []
@@ -288,3 +289,75 @@ async fn test_v1_single_page_fits_all() {
     let vaults = client.get_vaults(None).await.unwrap();
     assert_eq!(vaults.len(), 2);
 }
+
+#[tokio::test]
+async fn test_v1_get_vaults_page_resumes_from_cursor() {
+    let server = start_mock_server().await;
+
+    let page1 = v1_page_fixture(
+        &[
+            ("0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "Vault A", "vA"),
+            ("0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb", "Vault B", "vB"),
+        ],
+        3,
+    );
+    let page2 = v1_page_fixture(
+        &[("0xcccccccccccccccccccccccccccccccccccccccc", "Vault C", "vC")],
+        3,
+    );
+
+    Mock::given(method("POST"))
+        .and(body_string_contains(r#""skip":0"#))
+        .respond_with(ResponseTemplate::new(200).set_body_string(page1))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains(r#""skip":2"#))
+        .respond_with(ResponseTemplate::new(200).set_body_string(page2))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = VaultV1Client::with_config(client_config_with_mock(&server));
+    let options = VaultQueryOptionsV1::new().limit(2);
+
+    let first = client.get_vaults_page(options.clone(), None).await.unwrap();
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.count_total, 3);
+    assert_eq!(first.next_cursor, Some(2));
+
+    let second = client
+        .get_vaults_page(options, first.next_cursor)
+        .await
+        .unwrap();
+    assert_eq!(second.items[0].name, "Vault C");
+    assert!(!second.has_more());
+}
+
+#[tokio::test]
+async fn test_v2_get_vaults_page_applies_client_filter() {
+    let server = start_mock_server().await;
+
+    let page = v2_page_fixture(
+        &[
+            ("0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", "Vault A", "vA"),
+            ("0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb", "Vault B", "vB"),
+        ],
+        5,
+    );
+    Mock::given(method("POST"))
+        .and(body_string_contains(r#""first":2"#))
+        .respond_with(ResponseTemplate::new(200).set_body_string(page))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = VaultV2Client::with_config(client_config_with_mock(&server).with_page_size(2));
+    let options = VaultQueryOptionsV2::new().asset_symbols(["WETH"]);
+
+    let page = client.get_vaults_page(options, None).await.unwrap();
+    // Every vault on the page is filtered out, but more pages follow.
+    assert!(page.items.is_empty());
+    assert_eq!(page.next_cursor, Some(2));
+}
@@ -0,0 +1,99 @@
+//! Serialization round trips for API response types.
+
+mod helpers;
+
+use alloy_primitives::address;
+use helpers::{client_config_with_mock, mock_graphql_response, start_mock_server};
+use morpho_rs_api::{
+    MorphoApiClient, NamedChain, UserVaultPositions, VaultV1, VaultV1Client, VaultV2, VaultV2Client,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn roundtrip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    let json = serde_json::to_string(value).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[tokio::test]
+async fn test_vault_v1_roundtrip() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_info_with_markets").await;
+
+    let client = VaultV1Client::with_config(client_config_with_mock(&server));
+    let vault = client
+        .get_vault(
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap();
+
+    let state = vault.state.as_ref().unwrap();
+    assert!(state.allocation.iter().any(|a| a.market_state.is_some()));
+    assert_eq!(roundtrip(&vault), vault);
+}
+
+#[tokio::test]
+async fn test_vault_v1_list_roundtrip() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_list").await;
+
+    let client = VaultV1Client::with_config(client_config_with_mock(&server));
+    let vaults = client.get_vaults(None).await.unwrap();
+
+    let json = serde_json::to_string(&vaults).unwrap();
+    let cached: Vec<VaultV1> = serde_json::from_str(&json).unwrap();
+    assert_eq!(cached, vaults);
+}
+
+#[tokio::test]
+async fn test_vault_v2_roundtrip() {
+    for fixture in ["v2_info_metamorpho", "v2_info_morpho_market"] {
+        let server = start_mock_server().await;
+        mock_graphql_response(&server, fixture).await;
+
+        let client = VaultV2Client::with_config(client_config_with_mock(&server));
+        let vault = client
+            .get_vault(
+                address!("0xABCdef1234567890ABCdef1234567890ABCdef12"),
+                NamedChain::Mainnet,
+            )
+            .await
+            .unwrap();
+
+        assert!(!vault.adapters.is_empty());
+        let cached: VaultV2 = roundtrip(&vault);
+        assert_eq!(cached, vault);
+    }
+}
+
+#[tokio::test]
+async fn test_user_positions_roundtrip() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "user_positions").await;
+
+    let client = MorphoApiClient::with_config(client_config_with_mock(&server));
+    let positions = client
+        .get_user_vault_positions(
+            address!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+            Some(NamedChain::Mainnet),
+        )
+        .await
+        .unwrap();
+
+    let cached: UserVaultPositions = roundtrip(&positions);
+    assert_eq!(cached, positions);
+}
+
+#[tokio::test]
+async fn test_converted_roundtrip() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_list_invalid").await;
+
+    let client = VaultV1Client::with_config(client_config_with_mock(&server));
+    let converted = client.get_vaults_with_diagnostics(None).await.unwrap();
+
+    assert_eq!(converted.diagnostics.len(), 1);
+    assert_eq!(roundtrip(&converted), converted);
+}
@@ -0,0 +1,52 @@
+//! On-chain simulation hydration fork tests.
+//!
+//! Run with: `cargo test --features sim --test snapshot_fork_tests -- --ignored`
+//! Requires `ETH_RPC_URL` environment variable to be set.
+
+#![cfg(feature = "sim")]
+
+#[allow(dead_code)]
+mod fork_helpers;
+
+use alloy::providers::{Provider, ProviderBuilder};
+use fork_helpers::{spawn_forked_anvil, STEAKHOUSE_USDC_VAULT};
+use morpho_rs_api::SnapshotClient;
+
+#[tokio::test]
+#[ignore = "Requires ETH_RPC_URL environment variable"]
+async fn test_snapshot_matches_vault_state() {
+    let Some(anvil) = spawn_forked_anvil() else {
+        return;
+    };
+
+    let provider = ProviderBuilder::new().connect_http(anvil.endpoint_url());
+    let block = provider
+        .get_block_number()
+        .await
+        .expect("Failed to get block");
+
+    let client = SnapshotClient::new(&anvil.endpoint()).expect("Failed to create client");
+    let snapshot = client
+        .snapshot(STEAKHOUSE_USDC_VAULT, block)
+        .await
+        .expect("Failed to read snapshot");
+
+    let vault = &snapshot.simulation.vault;
+    assert_eq!(snapshot.block, block);
+    assert_eq!(vault.address, STEAKHOUSE_USDC_VAULT);
+    assert_eq!(vault.asset_decimals, 6, "Vault asset should be USDC");
+    assert!(
+        !vault.withdraw_queue.is_empty(),
+        "Vault should have markets"
+    );
+    assert_eq!(vault.allocations.len(), vault.withdraw_queue.len());
+
+    // Allocations are rounded down, so they can only undershoot total assets.
+    let allocated = vault
+        .allocations
+        .values()
+        .fold(alloy::primitives::U256::ZERO, |acc, c| {
+            acc + c.supply_assets
+        });
+    assert!(allocated <= vault.total_assets);
+}
@@ -0,0 +1,98 @@
+//! Transport injection tests using `FixtureClient`.
+
+use std::sync::Arc;
+
+use alloy_primitives::address;
+use morpho_rs_api::{
+    ApiError, ClientConfig, FixtureClient, MorphoApiClient, NamedChain, VaultV1Client,
+    VaultV2Client,
+};
+
+fn fixture_path(name: &str) -> String {
+    format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    )
+}
+
+fn config_with(fixtures: Arc<FixtureClient>) -> ClientConfig {
+    ClientConfig::new()
+        .with_transport(fixtures)
+        .with_retry_base_delay_ms(1)
+}
+
+#[tokio::test]
+async fn test_fixture_vaults_v1() {
+    let fixtures = Arc::new(
+        FixtureClient::new()
+            .with_response_file("GetVaultsV1", fixture_path("v1_list"))
+            .unwrap(),
+    );
+    let client = VaultV1Client::with_config(config_with(fixtures.clone()));
+
+    let vaults = client
+        .get_vaults_by_chain(NamedChain::Mainnet)
+        .await
+        .unwrap();
+    assert_eq!(vaults.len(), 2);
+    assert_eq!(vaults[0].name, "Steakhouse USDC");
+
+    let requests = fixtures.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].operation, "GetVaultsV1");
+    assert_eq!(requests[0].variables["first"], 100);
+}
+
+#[tokio::test]
+async fn test_fixture_vault_v2() {
+    let fixtures = Arc::new(
+        FixtureClient::new()
+            .with_response_file("GetVaultV2ByAddress", fixture_path("v2_info"))
+            .unwrap(),
+    );
+    let client = VaultV2Client::with_config(config_with(fixtures));
+
+    let vault = client
+        .get_vault(
+            address!("0x1234567890123456789012345678901234567890"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap();
+    assert_eq!(vault.name, "Test V2 USDC Vault");
+}
+
+#[tokio::test]
+async fn test_fixture_graphql_error() {
+    let fixtures = Arc::new(
+        FixtureClient::new()
+            .with_response_file("GetVaultV1ByAddress", fixture_path("graphql_error"))
+            .unwrap(),
+    );
+    let client = MorphoApiClient::with_config(config_with(fixtures));
+
+    let err = client
+        .v1
+        .get_vault(
+            address!("0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458"),
+            NamedChain::Mainnet,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ApiError::GraphQL(_)));
+}
+
+#[tokio::test]
+async fn test_fixture_dir_missing_operation() {
+    let fixtures = Arc::new(FixtureClient::from_dir(format!(
+        "{}/tests/fixtures",
+        env!("CARGO_MANIFEST_DIR")
+    )));
+    let client = VaultV1Client::with_config(config_with(fixtures.clone()));
+
+    let err = client.get_vaults(None).await.unwrap_err();
+    assert!(matches!(err, ApiError::Parse(_)));
+    // Fixture errors are not retryable
+    assert_eq!(fixtures.requests().len(), 1);
+}
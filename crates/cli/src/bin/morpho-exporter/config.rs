@@ -0,0 +1,116 @@
+//! Exporter configuration.
+//!
+//! ```toml
+//! listen = "0.0.0.0:9898"
+//! interval_secs = 60
+//!
+//! [[vaults]]
+//! address = "0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB"
+//! chain = 1
+//! version = "V1"
+//!
+//! [[users]]
+//! address = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+//! chain = 8453
+//! ```
+
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+
+use alloy_chains::NamedChain;
+use alloy_primitives::Address;
+use anyhow::{Context, Result};
+use morpho_rs_api::chain_serde;
+use morpho_rs_api::reports::TrackedVault;
+use serde::Deserialize;
+
+/// Default address the metrics endpoint listens on.
+pub const DEFAULT_LISTEN: &str = "0.0.0.0:9898";
+
+/// Default seconds between polls.
+pub const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+fn default_listen() -> SocketAddr {
+    DEFAULT_LISTEN
+        .parse()
+        .unwrap_or(SocketAddr::from(([0, 0, 0, 0], 9898)))
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+/// A user whose positions on a chain are exported.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrackedUser {
+    /// The user's address.
+    pub address: Address,
+    /// The chain to export positions on (chain ID).
+    #[serde(with = "chain_serde")]
+    pub chain: NamedChain,
+}
+
+/// The exporter config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExporterConfig {
+    /// Address the metrics endpoint listens on.
+    #[serde(default = "default_listen")]
+    pub listen: SocketAddr,
+    /// Seconds between polls.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Morpho API URL (default: the public API).
+    pub api_url: Option<String>,
+    /// Vaults whose APY, TVL, and share price are exported.
+    #[serde(default)]
+    pub vaults: Vec<TrackedVault>,
+    /// Users whose vault balances and market health factors are exported.
+    #[serde(default)]
+    pub users: Vec<TrackedUser>,
+}
+
+impl ExporterConfig {
+    /// Read the config from a TOML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let toml = fs::read_to_string(path)
+            .with_context(|| format!("failed to read exporter config {}", path.display()))?;
+        toml::from_str(&toml).with_context(|| format!("invalid exporter config {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use morpho_rs_api::VaultVersion;
+
+    #[test]
+    fn test_parse_config() {
+        let config: ExporterConfig = toml::from_str(
+            r#"
+            interval_secs = 30
+
+            [[vaults]]
+            address = "0xBEEF01735c132Ada46AA9aA4c54623cAA92A64CB"
+            chain = 1
+            version = "V1"
+
+            [[users]]
+            address = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+            chain = 8453
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.listen, DEFAULT_LISTEN.parse().unwrap());
+        assert_eq!(config.interval_secs, 30);
+        assert_eq!(config.vaults[0].version, VaultVersion::V1);
+        assert_eq!(config.users[0].chain, NamedChain::Base);
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        assert!(toml::from_str::<ExporterConfig>("interval = 30").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+//! Morpho Prometheus exporter - poll vaults and positions and serve them as metrics.
+
+mod config;
+mod metrics;
+mod server;
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use morpho_rs_api::{ClientConfig, MorphoApiClient, VaultVersion};
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+
+use config::ExporterConfig;
+use metrics::Registry;
+
+/// Export Morpho vault and position metrics for Prometheus.
+#[derive(Parser, Debug)]
+#[command(name = "morpho-exporter")]
+#[command(version)]
+struct Args {
+    /// Path to the exporter config file (TOML).
+    #[arg(long, short)]
+    config: PathBuf,
+
+    /// Address to serve metrics on (overrides the config file).
+    #[arg(long)]
+    listen: Option<SocketAddr>,
+
+    /// Custom API URL (overrides the config file).
+    #[arg(long, env = "MORPHO_API_URL")]
+    api_url: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let config = ExporterConfig::load(&args.config)?;
+    let listen = args.listen.unwrap_or(config.listen);
+
+    let client = match args.api_url.as_deref().or(config.api_url.as_deref()) {
+        Some(url) => MorphoApiClient::with_config(ClientConfig::new().with_api_url(url.parse()?)),
+        None => MorphoApiClient::new(),
+    };
+
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("failed to listen on {listen}"))?;
+    eprintln!(
+        "Serving metrics for {} vaults and {} users on http://{}/metrics",
+        config.vaults.len(),
+        config.users.len(),
+        listener.local_addr()?
+    );
+
+    let rendered = Arc::new(RwLock::new(String::new()));
+    let mut server = tokio::spawn(server::serve(listener, Arc::clone(&rendered)));
+
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+    let mut errors_total = 0u64;
+    loop {
+        tokio::select! {
+            result = &mut server => return result?,
+            _ = interval.tick() => {}
+        }
+        let registry = poll(&client, &config, &mut errors_total).await;
+        *rendered.write().await = registry.render();
+    }
+}
+
+/// Query every configured vault and user and collect their metrics.
+///
+/// Failed queries are logged and counted; the remaining metrics are still exported.
+async fn poll(
+    client: &MorphoApiClient,
+    config: &ExporterConfig,
+    errors_total: &mut u64,
+) -> Registry {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let mut registry = Registry::default();
+    let mut errors = 0;
+
+    for tracked in &config.vaults {
+        let result = match tracked.version {
+            VaultVersion::V1 => client
+                .v1
+                .get_vault(tracked.address, tracked.chain)
+                .await
+                .map(|vault| metrics::record_vault_v1(&mut registry, &vault, now)),
+            VaultVersion::V2 => client
+                .v2
+                .get_vault(tracked.address, tracked.chain)
+                .await
+                .map(|vault| metrics::record_vault_v2(&mut registry, &vault, now)),
+        };
+        if let Err(e) = result {
+            errors += 1;
+            eprintln!(
+                "failed to fetch vault {} on {}: {e}",
+                tracked.address, tracked.chain
+            );
+        }
+    }
+
+    for user in &config.users {
+        match client
+            .get_user_account_overview(user.address, user.chain)
+            .await
+        {
+            Ok(overview) => metrics::record_account(&mut registry, &overview, user.chain.as_str()),
+            Err(e) => {
+                errors += 1;
+                eprintln!(
+                    "failed to fetch account {} on {}: {e}",
+                    user.address, user.chain
+                );
+            }
+        }
+    }
+
+    *errors_total += errors as u64;
+    metrics::record_poll(&mut registry, errors, *errors_total, now);
+    registry
+}
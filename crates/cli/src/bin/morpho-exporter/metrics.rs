@@ -0,0 +1,441 @@
+//! Prometheus metrics in the text exposition format.
+
+use alloy_primitives::Address;
+use morpho_rs_api::{TokenAmount, UserAccountOverview, VaultV1, VaultV2};
+
+/// Label names and values of a sample.
+type Labels = Vec<(&'static str, String)>;
+
+/// A metric and its samples.
+#[derive(Debug)]
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    samples: Vec<(Labels, f64)>,
+}
+
+/// Metrics collected in one poll, rendered for scraping.
+#[derive(Debug, Default)]
+pub struct Registry {
+    families: Vec<Family>,
+}
+
+impl Registry {
+    /// Record a gauge sample.
+    pub fn gauge(&mut self, name: &'static str, help: &'static str, labels: Labels, value: f64) {
+        self.sample(name, help, "gauge", labels, value);
+    }
+
+    /// Record a counter sample.
+    pub fn counter(&mut self, name: &'static str, help: &'static str, labels: Labels, value: f64) {
+        self.sample(name, help, "counter", labels, value);
+    }
+
+    fn sample(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        kind: &'static str,
+        labels: Labels,
+        value: f64,
+    ) {
+        match self.families.iter_mut().find(|f| f.name == name) {
+            Some(family) => family.samples.push((labels, value)),
+            None => self.families.push(Family {
+                name,
+                help,
+                kind,
+                samples: vec![(labels, value)],
+            }),
+        }
+    }
+
+    /// Render all metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for family in &self.families {
+            out.push_str(&format!("# HELP {} {}\n", family.name, family.help));
+            out.push_str(&format!("# TYPE {} {}\n", family.name, family.kind));
+            for (labels, value) in &family.samples {
+                out.push_str(family.name);
+                if !labels.is_empty() {
+                    let labels: Vec<String> = labels
+                        .iter()
+                        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+                        .collect();
+                    out.push_str(&format!("{{{}}}", labels.join(",")));
+                }
+                out.push_str(&format!(" {}\n", format_value(*value)));
+            }
+        }
+        out
+    }
+}
+
+/// Escape a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn vault_labels(address: Address, chain: &str, name: &str, asset: &str, version: &str) -> Labels {
+    vec![
+        ("chain", chain.to_string()),
+        ("vault", address.to_string()),
+        ("name", name.to_string()),
+        ("asset", asset.to_string()),
+        ("version", version.to_string()),
+    ]
+}
+
+/// Record a V1 vault's APY, TVL, and share price.
+///
+/// The projected net APY is simulated from the vault's market allocations at `now`.
+pub fn record_vault_v1(registry: &mut Registry, vault: &VaultV1, now: u64) {
+    let Some(state) = &vault.state else {
+        return;
+    };
+    let labels = vault_labels(
+        vault.address,
+        vault.chain.as_str(),
+        &vault.name,
+        &vault.asset.symbol,
+        "V1",
+    );
+    let decimals = vault.asset.decimals;
+
+    registry.gauge(APY, APY_HELP, labels.clone(), state.apy);
+    registry.gauge(NET_APY, NET_APY_HELP, labels.clone(), state.net_apy);
+    if let Some(projected) = vault
+        .to_vault_simulation()
+        .and_then(|sim| sim.get_net_apy(now).ok())
+    {
+        registry.gauge(
+            PROJECTED_NET_APY,
+            PROJECTED_NET_APY_HELP,
+            labels.clone(),
+            projected,
+        );
+    }
+    registry.gauge(
+        TOTAL_ASSETS,
+        TOTAL_ASSETS_HELP,
+        labels.clone(),
+        TokenAmount::new(state.total_assets, decimals).to_f64(),
+    );
+    if let Some(usd) = state.total_assets_usd {
+        registry.gauge(TOTAL_ASSETS_USD, TOTAL_ASSETS_USD_HELP, labels.clone(), usd);
+    }
+    if let Some(price) = vault.share_price_scaled() {
+        registry.gauge(
+            SHARE_PRICE,
+            SHARE_PRICE_HELP,
+            labels,
+            TokenAmount::new(price, decimals).to_f64(),
+        );
+    }
+}
+
+/// Record a V2 vault's APY, TVL, and share price.
+///
+/// The projected net APY is simulated from the vault's adapters at `now`.
+pub fn record_vault_v2(registry: &mut Registry, vault: &VaultV2, now: u64) {
+    let labels = vault_labels(
+        vault.address,
+        vault.chain.as_str(),
+        &vault.name,
+        &vault.asset.symbol,
+        "V2",
+    );
+    let decimals = vault.asset.decimals;
+
+    if let Some(apy) = vault.apy {
+        registry.gauge(APY, APY_HELP, labels.clone(), apy);
+    }
+    if let Some(net_apy) = vault.net_apy {
+        registry.gauge(NET_APY, NET_APY_HELP, labels.clone(), net_apy);
+    }
+    if let Ok(projected) = vault.to_vault_simulation().get_net_apy(now) {
+        registry.gauge(
+            PROJECTED_NET_APY,
+            PROJECTED_NET_APY_HELP,
+            labels.clone(),
+            projected,
+        );
+    }
+    registry.gauge(
+        TOTAL_ASSETS,
+        TOTAL_ASSETS_HELP,
+        labels.clone(),
+        TokenAmount::new(vault.total_assets, decimals).to_f64(),
+    );
+    if let Some(usd) = vault.total_assets_usd {
+        registry.gauge(TOTAL_ASSETS_USD, TOTAL_ASSETS_USD_HELP, labels.clone(), usd);
+    }
+    registry.gauge(
+        SHARE_PRICE,
+        SHARE_PRICE_HELP,
+        labels,
+        TokenAmount::new(vault.share_price_scaled(), decimals).to_f64(),
+    );
+}
+
+/// Record a user's vault balances and market positions on one chain.
+pub fn record_account(registry: &mut Registry, overview: &UserAccountOverview, chain: &str) {
+    let user = overview.address.to_string();
+    let v1 = overview
+        .vault_positions
+        .iter()
+        .map(|p| (&p.vault, p.assets_usd, "V1"));
+    let v2 = overview
+        .vault_v2_positions
+        .iter()
+        .map(|p| (&p.vault, p.assets_usd, "V2"));
+    for (vault, assets_usd, version) in v1.chain(v2) {
+        if let Some(usd) = assets_usd {
+            let labels = vec![
+                ("user", user.clone()),
+                ("chain", chain.to_string()),
+                ("vault", vault.address.to_string()),
+                ("name", vault.name.clone()),
+                ("version", version.to_string()),
+            ];
+            registry.gauge(
+                "morpho_user_vault_assets_usd",
+                "Value of the user's vault position in USD.",
+                labels,
+                usd,
+            );
+        }
+    }
+
+    for position in &overview.market_positions {
+        let market = &position.market;
+        let labels = vec![
+            ("user", user.clone()),
+            ("chain", chain.to_string()),
+            ("market", market.unique_key.clone()),
+            ("loan", market.loan_asset_symbol.clone().unwrap_or_default()),
+            (
+                "collateral",
+                market.collateral_asset_symbol.clone().unwrap_or_default(),
+            ),
+        ];
+        let values = [
+            (
+                "morpho_user_market_supply_usd",
+                "Loan assets the user supplied to the market, in USD.",
+                position.supply_assets_usd,
+            ),
+            (
+                "morpho_user_market_borrow_usd",
+                "Loan assets the user borrowed from the market, in USD.",
+                position.borrow_assets_usd,
+            ),
+            (
+                "morpho_user_market_collateral_usd",
+                "Collateral the user posted to the market, in USD.",
+                position.collateral_usd,
+            ),
+            (
+                "morpho_user_market_health_factor",
+                "Health factor of the user's borrow position; liquidatable below 1.",
+                position.health_factor,
+            ),
+        ];
+        for (name, help, value) in values {
+            if let Some(value) = value {
+                registry.gauge(name, help, labels.clone(), value);
+            }
+        }
+    }
+}
+
+/// Record the outcome of a poll.
+pub fn record_poll(registry: &mut Registry, errors: usize, errors_total: u64, timestamp: u64) {
+    registry.gauge(
+        "morpho_exporter_up",
+        "Whether every query of the last poll succeeded.",
+        Vec::new(),
+        if errors == 0 { 1.0 } else { 0.0 },
+    );
+    registry.counter(
+        "morpho_exporter_errors_total",
+        "Failed queries since the exporter started.",
+        Vec::new(),
+        errors_total as f64,
+    );
+    registry.gauge(
+        "morpho_exporter_last_poll_timestamp_seconds",
+        "Unix time of the last poll.",
+        Vec::new(),
+        timestamp as f64,
+    );
+}
+
+const APY: &str = "morpho_vault_apy";
+const APY_HELP: &str = "Gross vault APY as a fraction, as reported by the Morpho API.";
+const NET_APY: &str = "morpho_vault_net_apy";
+const NET_APY_HELP: &str = "Vault APY after fees as a fraction, as reported by the Morpho API.";
+const PROJECTED_NET_APY: &str = "morpho_vault_projected_net_apy";
+const PROJECTED_NET_APY_HELP: &str =
+    "Vault APY after fees as a fraction, simulated from current market state.";
+const TOTAL_ASSETS: &str = "morpho_vault_total_assets";
+const TOTAL_ASSETS_HELP: &str = "Total assets in the vault, in whole asset units.";
+const TOTAL_ASSETS_USD: &str = "morpho_vault_total_assets_usd";
+const TOTAL_ASSETS_USD_HELP: &str = "Total assets in the vault, in USD.";
+const SHARE_PRICE: &str = "morpho_vault_share_price";
+const SHARE_PRICE_HELP: &str = "Assets redeemable for one vault share, in whole asset units.";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_chains::NamedChain;
+    use alloy_primitives::U256;
+    use morpho_rs_api::{
+        Asset, MarketInfo, UserMarketPosition, UserState, VaultStateV1, VAULT_SHARE_UNIT,
+    };
+
+    fn vault() -> VaultV1 {
+        VaultV1 {
+            address: Address::repeat_byte(0x01),
+            name: "Steakhouse \"USDC\"".to_string(),
+            symbol: "steakUSDC".to_string(),
+            chain: NamedChain::Mainnet,
+            listed: true,
+            featured: false,
+            whitelisted: true,
+            asset: Asset {
+                address: Address::repeat_byte(0xAA),
+                symbol: "USDC".to_string(),
+                name: None,
+                decimals: 6,
+                price_usd: Some(1.0),
+            },
+            state: Some(VaultStateV1 {
+                curator: None,
+                owner: None,
+                guardian: None,
+                total_assets: U256::from(2_000_000_000u64),
+                total_assets_usd: Some(2000.0),
+                total_supply: VAULT_SHARE_UNIT * U256::from(1000),
+                fee: 0.1,
+                timelock: 86_400,
+                apy: 0.05,
+                net_apy: 0.045,
+                share_price: U256::from(1),
+                allocation: Vec::new(),
+                rewards: Vec::new(),
+            }),
+            allocators: Vec::new(),
+            warnings: Vec::new(),
+            public_allocator_config: None,
+        }
+    }
+
+    #[test]
+    fn test_render_vault_v1() {
+        let mut registry = Registry::default();
+        record_vault_v1(&mut registry, &vault(), 1_700_000_000);
+        let out = registry.render();
+
+        assert!(out.contains("# TYPE morpho_vault_net_apy gauge\n"));
+        let labels = format!(
+            "chain=\"mainnet\",vault=\"{}\",name=\"Steakhouse \\\"USDC\\\"\",asset=\"USDC\",version=\"V1\"",
+            Address::repeat_byte(0x01)
+        );
+        assert!(out.contains(&format!("morpho_vault_net_apy{{{labels}}} 0.045\n")));
+        assert!(out.contains(&format!("morpho_vault_total_assets{{{labels}}} 2000\n")));
+        let share_price: f64 = out
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("morpho_vault_share_price{{{labels}}} ")))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((share_price - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_render_account_and_poll() {
+        let overview = UserAccountOverview {
+            address: Address::repeat_byte(0x02),
+            state: UserState {
+                vaults_pnl_usd: 0.0,
+                vaults_roe_usd: 0.0,
+                vaults_assets_usd: 0.0,
+                vault_v2s_pnl_usd: 0.0,
+                vault_v2s_roe_usd: 0.0,
+                vault_v2s_assets_usd: 0.0,
+                markets_pnl_usd: 0.0,
+                markets_roe_usd: 0.0,
+                markets_supply_pnl_usd: 0.0,
+                markets_supply_roe_usd: 0.0,
+                markets_borrow_pnl_usd: 0.0,
+                markets_borrow_roe_usd: 0.0,
+                markets_collateral_pnl_usd: 0.0,
+                markets_collateral_roe_usd: 0.0,
+                markets_margin_pnl_usd: 0.0,
+                markets_margin_roe_usd: 0.0,
+                markets_collateral_usd: 0.0,
+                markets_supply_assets_usd: 0.0,
+                markets_borrow_assets_usd: 0.0,
+                markets_margin_usd: 0.0,
+            },
+            vault_positions: Vec::new(),
+            vault_v2_positions: Vec::new(),
+            market_positions: vec![UserMarketPosition {
+                id: "position".to_string(),
+                supply_shares: U256::ZERO,
+                supply_assets: U256::ZERO,
+                supply_assets_usd: None,
+                borrow_shares: U256::from(1),
+                borrow_assets: U256::from(1),
+                borrow_assets_usd: Some(500.0),
+                collateral: U256::from(1),
+                collateral_usd: Some(1000.0),
+                health_factor: Some(1.72),
+                market: MarketInfo {
+                    unique_key: "0xabc".to_string(),
+                    loan_asset_symbol: Some("USDC".to_string()),
+                    loan_asset_address: None,
+                    collateral_asset_symbol: Some("WETH".to_string()),
+                    collateral_asset_address: None,
+                },
+            }],
+        };
+
+        let mut registry = Registry::default();
+        record_account(&mut registry, &overview, "base");
+        record_poll(&mut registry, 1, 3, 1_700_000_000);
+        let out = registry.render();
+
+        assert!(out.contains("morpho_user_market_health_factor{user=\""));
+        assert!(out
+            .contains("chain=\"base\",market=\"0xabc\",loan=\"USDC\",collateral=\"WETH\"} 1.72\n"));
+        assert!(!out.contains("morpho_user_market_supply_usd"));
+        assert!(out.contains(
+            "# TYPE morpho_exporter_errors_total counter\nmorpho_exporter_errors_total 3\n"
+        ));
+        assert!(out.contains("morpho_exporter_up 0\n"));
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(f64::NAN), "NaN");
+        assert_eq!(format_value(f64::NEG_INFINITY), "-Inf");
+        assert_eq!(format_value(0.5), "0.5");
+    }
+}
@@ -0,0 +1,102 @@
+//! Minimal HTTP server for the metrics endpoint.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+
+/// Content type of the Prometheus text format.
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Serve the latest rendered metrics on `GET /metrics` until the listener fails.
+pub async fn serve(listener: TcpListener, metrics: Arc<RwLock<String>>) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let metrics = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, &metrics).await {
+                eprintln!("metrics request failed: {e}");
+            }
+        });
+    }
+}
+
+async fn handle(mut stream: TcpStream, metrics: &RwLock<String>) -> Result<()> {
+    let mut buf = vec![0u8; 4096];
+    let mut len = 0;
+    // Only the request line is needed; read until it is complete.
+    while !buf[..len].contains(&b'\n') && len < buf.len() {
+        let n = stream.read(&mut buf[len..]).await?;
+        if n == 0 {
+            break;
+        }
+        len += n;
+    }
+    let request = String::from_utf8_lossy(&buf[..len]);
+    let mut parts = request.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => ("200 OK", METRICS_CONTENT_TYPE, metrics.read().await.clone()),
+        ("GET", "/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            "<html><body><a href=\"/metrics\">Metrics</a></body></html>\n".to_string(),
+        ),
+        ("GET", _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_serves_metrics() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let metrics = Arc::new(RwLock::new("morpho_exporter_up 1\n".to_string()));
+        tokio::spawn(serve(listener, Arc::clone(&metrics)));
+
+        let response = get(addr, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(METRICS_CONTENT_TYPE));
+        assert!(response.ends_with("\r\n\r\nmorpho_exporter_up 1\n"));
+
+        *metrics.write().await = "morpho_exporter_up 0\n".to_string();
+        assert!(get(addr, "/metrics")
+            .await
+            .ends_with("morpho_exporter_up 0\n"));
+
+        assert!(get(addr, "/other")
+            .await
+            .starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -1,27 +1,51 @@
 //! CLI argument definitions using clap.
 
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use alloy_chains::NamedChain;
+use clap::builder::{EnumValueParser, TypedValueParser};
 use clap::{Parser, Subcommand, ValueEnum};
+use morpho_rs_api::{ExportFormat, ExportTable, LabelKind};
+use serde::{Deserialize, Deserializer};
+use url::Url;
 
 /// Morpho CLI - Query V1 and V2 vaults
 #[derive(Parser, Debug)]
 #[command(name = "morpho")]
 #[command(about = "CLI tool for querying Morpho vaults", long_about = None)]
 pub struct Cli {
-    /// Output format
-    #[arg(long, global = true, default_value = "table")]
-    pub format: OutputFormat,
+    /// Output format [default: profile format, or table]
+    #[arg(
+        long,
+        global = true,
+        value_parser = EnumValueParser::<OutputFormat>::new().map(FormatArg::Output)
+    )]
+    pub format: Option<FormatArg>,
 
     /// Morpho API URL (can also use MORPHO_API_URL env var)
     #[arg(long, global = true, env = "MORPHO_API_URL")]
     pub api_url: Option<String>,
 
+    /// Config file profile to use (can also use MORPHO_PROFILE env var)
+    #[arg(long, global = true, env = "MORPHO_PROFILE")]
+    pub profile: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+impl Cli {
+    /// Output format passed with `--format`, if any.
+    pub fn output_format(&self) -> Option<OutputFormat> {
+        match self.format {
+            Some(FormatArg::Output(format)) => Some(format),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Query V1 (MetaMorpho) vaults
@@ -39,6 +63,46 @@ pub enum Commands {
     /// Query user vault positions (V1 and V2)
     #[command(name = "positions")]
     Positions(PositionsArgs),
+    /// Generate a report of tracked positions and vaults
+    #[command(name = "report")]
+    Report(ReportArgs),
+    /// Export vault and market snapshots for bulk analysis
+    #[command(name = "export")]
+    Export(ExportArgs),
+    /// Watch vaults and send alerts to webhooks, Slack, Discord, or stdout
+    #[command(name = "monitor")]
+    Monitor(MonitorArgs),
+    /// Claim Morpho rewards
+    #[command(name = "rewards")]
+    Rewards {
+        #[command(subcommand)]
+        subcommand: RewardsSubcommand,
+    },
+    /// Interactive dashboard of best vaults, positions, and alerts
+    #[command(name = "tui")]
+    Tui(TuiArgs),
+    /// Manage the address book naming vaults, curators, and tokens in output
+    #[command(name = "labels")]
+    Labels {
+        #[command(subcommand)]
+        subcommand: LabelsSubcommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RewardsSubcommand {
+    /// Claim all outstanding rewards for the signer on a chain
+    Claim(ClaimRewardsArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LabelsSubcommand {
+    /// Import vault, asset, and curator names from the API (existing labels are kept)
+    Import(LabelsImportArgs),
+    /// Label an address, replacing any existing label
+    Set(LabelsSetArgs),
+    /// List labeled addresses
+    List,
 }
 
 #[derive(Subcommand, Debug)]
@@ -51,6 +115,11 @@ pub enum VaultV1Subcommand {
     Deposit(DepositArgs),
     /// Withdraw assets from a V1 vault
     Withdraw(WithdrawArgs),
+    /// Show changes to a V1 vault's configuration since a past time
+    Diff(DiffArgs),
+    /// Check a V1 vault's supply and withdraw queues for misconfigurations
+    #[cfg(feature = "sim")]
+    Lint(InfoArgs),
 }
 
 #[derive(Subcommand, Debug)]
@@ -89,9 +158,24 @@ pub struct InfoArgs {
     /// Vault address
     pub address: String,
 
-    /// Chain the vault is on (default: ethereum)
-    #[arg(long, default_value = "ethereum")]
-    pub chain: ChainArg,
+    /// Chain the vault is on (default: profile chain, or ethereum)
+    #[arg(long)]
+    pub chain: Option<ChainArg>,
+}
+
+#[derive(Parser, Debug)]
+pub struct DiffArgs {
+    /// Vault address
+    pub address: String,
+
+    /// Compare against the vault as of this time: a unix timestamp or a duration ago
+    /// (e.g., 7d, 12h, 30m)
+    #[arg(long)]
+    pub since: SinceArg,
+
+    /// Chain the vault is on (default: profile chain, or ethereum)
+    #[arg(long)]
+    pub chain: Option<ChainArg>,
 }
 
 #[derive(Parser, Debug)]
@@ -102,6 +186,194 @@ pub struct PositionsArgs {
     /// Chain to query (omit to query all chains)
     #[arg(long)]
     pub chain: Option<ChainArg>,
+
+    /// Keep running, re-querying at this interval (e.g., 30s, 5m, 1h) and showing APY,
+    /// PnL, and health factor changes
+    #[arg(long, value_name = "INTERVAL")]
+    pub watch: Option<IntervalArg>,
+}
+
+#[derive(Parser, Debug)]
+pub struct TuiArgs {
+    /// Chain to show vaults for (default: profile chain, or ethereum)
+    #[arg(long)]
+    pub chain: Option<ChainArg>,
+
+    /// User wallet address to show positions for (default: the signer's address)
+    #[arg(long)]
+    pub user: Option<String>,
+
+    /// Interval between refreshes (e.g., 30s, 5m, 1h)
+    #[arg(long, value_name = "INTERVAL", default_value = "60s")]
+    pub refresh: IntervalArg,
+
+    /// Number of best vaults shown per asset
+    #[arg(long, default_value_t = 3)]
+    pub per_asset: usize,
+
+    /// Private key for deposits and withdrawals (can also use PRIVATE_KEY env var)
+    #[arg(long, env = "PRIVATE_KEY")]
+    pub private_key: Option<String>,
+
+    /// Encrypted JSON keystore to sign with instead of a private key (can also use
+    /// MORPHO_KEYSTORE env var)
+    #[arg(long, env = "MORPHO_KEYSTORE")]
+    pub keystore: Option<PathBuf>,
+
+    /// RPC URL for the chain (can also use ETH_RPC_URL env var)
+    #[arg(long, env = "ETH_RPC_URL")]
+    pub rpc_url: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct LabelsImportArgs {
+    /// Chain to import from (omit to import from every supported chain)
+    #[arg(long)]
+    pub chain: Option<ChainArg>,
+}
+
+#[derive(Parser, Debug)]
+pub struct LabelsSetArgs {
+    /// Address to label
+    pub address: String,
+
+    /// Name to show for the address
+    pub name: String,
+
+    /// What the address is
+    #[arg(long, value_enum, default_value_t = LabelKindArg::Other)]
+    pub kind: LabelKindArg,
+}
+
+/// What a labeled address is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LabelKindArg {
+    Vault,
+    Curator,
+    Token,
+    Other,
+}
+
+impl From<LabelKindArg> for LabelKind {
+    fn from(kind: LabelKindArg) -> Self {
+        match kind {
+            LabelKindArg::Vault => LabelKind::Vault,
+            LabelKindArg::Curator => LabelKind::Curator,
+            LabelKindArg::Token => LabelKind::Token,
+            LabelKindArg::Other => LabelKind::Other,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct ReportArgs {
+    /// Path to a JSON report config (users, vaults, cadence_secs)
+    #[arg(long)]
+    pub config: PathBuf,
+
+    /// Path to the previous report (JSON); read to compute changes, then overwritten
+    #[arg(long)]
+    pub state: Option<PathBuf>,
+
+    /// Write the report to this file instead of stdout
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// Keep running and generate a report every cadence period
+    #[arg(long)]
+    pub watch: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct MonitorArgs {
+    /// Path to a JSON monitor config (vaults, rules, poll_interval_secs, notifications)
+    #[arg(long)]
+    pub config: PathBuf,
+
+    /// POST each alert as JSON to this URL (repeatable)
+    #[arg(long)]
+    pub webhook: Vec<Url>,
+
+    /// Post each alert to this Slack incoming-webhook URL (repeatable)
+    #[arg(long)]
+    pub slack: Vec<Url>,
+
+    /// Post each alert to this Discord webhook URL (repeatable)
+    #[arg(long)]
+    pub discord: Vec<Url>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ExportArgs {
+    /// File to write
+    #[arg(long)]
+    pub out: PathBuf,
+
+    /// File format [default: inferred from the --out extension]
+    #[arg(
+        long,
+        visible_alias = "out-format",
+        value_parser = EnumValueParser::<ExportFormatArg>::new().map(FormatArg::File)
+    )]
+    pub format: Option<FormatArg>,
+
+    /// Chains to export (repeatable) [default: all supported chains]
+    #[arg(long)]
+    pub chain: Vec<ChainArg>,
+
+    /// Table to export [default: vaults, or both tables for sqlite and sql]
+    #[arg(long, value_enum)]
+    pub table: Option<ExportTableArg>,
+}
+
+impl ExportArgs {
+    /// File format passed with `--format`, if any.
+    pub fn file_format(&self) -> Option<ExportFormatArg> {
+        match self.format {
+            Some(FormatArg::File(format)) => Some(format),
+            _ => None,
+        }
+    }
+}
+
+/// File format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormatArg {
+    /// SQLite database, appended to if it exists
+    Sqlite,
+    Parquet,
+    /// SQLite script, loadable with `sqlite3 morpho.db < out.sql`
+    Sql,
+    Csv,
+    Jsonl,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(format: ExportFormatArg) -> Self {
+        match format {
+            ExportFormatArg::Sqlite => ExportFormat::Sqlite,
+            ExportFormatArg::Parquet => ExportFormat::Parquet,
+            ExportFormatArg::Sql => ExportFormat::Sql,
+            ExportFormatArg::Csv => ExportFormat::Csv,
+            ExportFormatArg::Jsonl => ExportFormat::Jsonl,
+        }
+    }
+}
+
+/// An exported table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportTableArg {
+    Vaults,
+    Markets,
+}
+
+impl From<ExportTableArg> for ExportTable {
+    fn from(table: ExportTableArg) -> Self {
+        match table {
+            ExportTableArg::Vaults => ExportTable::Vaults,
+            ExportTableArg::Markets => ExportTable::Markets,
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -112,17 +384,27 @@ pub struct DepositArgs {
     /// Amount to deposit in human-readable units (e.g., "100.5")
     pub amount: String,
 
-    /// Chain the vault is on (optional, not used for transaction routing)
+    /// Chain the vault is on, selecting the profile's RPC URL (default: profile chain, or
+    /// ethereum)
     #[arg(long)]
     pub chain: Option<ChainArg>,
 
     /// Private key for signing transactions (can also use PRIVATE_KEY env var)
     #[arg(long, env = "PRIVATE_KEY")]
-    pub private_key: String,
+    pub private_key: Option<String>,
+
+    /// Encrypted JSON keystore to sign with instead of a private key (can also use
+    /// MORPHO_KEYSTORE env var)
+    #[arg(long, env = "MORPHO_KEYSTORE")]
+    pub keystore: Option<PathBuf>,
 
     /// RPC URL for the target chain (can also use ETH_RPC_URL env var)
     #[arg(long, env = "ETH_RPC_URL")]
-    pub rpc_url: String,
+    pub rpc_url: Option<String>,
+
+    /// Only estimate the deposit's gas cost in USD, without sending it
+    #[arg(long)]
+    pub estimate: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -133,28 +415,70 @@ pub struct WithdrawArgs {
     /// Amount to withdraw in human-readable units (e.g., "100.5")
     pub amount: String,
 
-    /// Chain the vault is on (optional, not used for transaction routing)
+    /// Chain the vault is on, selecting the profile's RPC URL (default: profile chain, or
+    /// ethereum)
+    #[arg(long)]
+    pub chain: Option<ChainArg>,
+
+    /// Private key for signing transactions (can also use PRIVATE_KEY env var)
+    #[arg(long, env = "PRIVATE_KEY")]
+    pub private_key: Option<String>,
+
+    /// Encrypted JSON keystore to sign with instead of a private key (can also use
+    /// MORPHO_KEYSTORE env var)
+    #[arg(long, env = "MORPHO_KEYSTORE")]
+    pub keystore: Option<PathBuf>,
+
+    /// RPC URL for the target chain (can also use ETH_RPC_URL env var)
+    #[arg(long, env = "ETH_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// Only estimate the withdrawal's gas cost in USD, without sending it
+    #[arg(long)]
+    pub estimate: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ClaimRewardsArgs {
+    /// Chain to claim on (default: profile chain, or ethereum)
     #[arg(long)]
     pub chain: Option<ChainArg>,
 
     /// Private key for signing transactions (can also use PRIVATE_KEY env var)
     #[arg(long, env = "PRIVATE_KEY")]
-    pub private_key: String,
+    pub private_key: Option<String>,
+
+    /// Encrypted JSON keystore to sign with instead of a private key (can also use
+    /// MORPHO_KEYSTORE env var)
+    #[arg(long, env = "MORPHO_KEYSTORE")]
+    pub keystore: Option<PathBuf>,
 
     /// RPC URL for the target chain (can also use ETH_RPC_URL env var)
     #[arg(long, env = "ETH_RPC_URL")]
-    pub rpc_url: String,
+    pub rpc_url: Option<String>,
 }
 
-#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     #[default]
     Table,
     Json,
 }
 
-/// Wrapper for NamedChain that implements FromStr with aliases
+/// Value of a `--format` flag.
+///
+/// `export` takes a file format under the same flag as the global output format. Clap
+/// copies a global flag's value between a command and its subcommands, so both parse
+/// into this type.
 #[derive(Clone, Copy, Debug)]
+pub enum FormatArg {
+    Output(OutputFormat),
+    File(ExportFormatArg),
+}
+
+/// Wrapper for NamedChain that implements FromStr with aliases
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ChainArg(pub NamedChain);
 
 impl FromStr for ChainArg {
@@ -199,12 +523,89 @@ impl FromStr for ChainArg {
     }
 }
 
+impl<'de> Deserialize<'de> for ChainArg {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl std::fmt::Display for ChainArg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0.as_str())
     }
 }
 
+/// A point in time: a unix timestamp or a duration before now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinceArg {
+    /// Unix timestamp in seconds.
+    Timestamp(u64),
+    /// Seconds before now.
+    Ago(u64),
+}
+
+impl SinceArg {
+    /// Resolve to a unix timestamp, given the current time.
+    pub fn timestamp(&self, now: u64) -> u64 {
+        match self {
+            SinceArg::Timestamp(timestamp) => *timestamp,
+            SinceArg::Ago(secs) => now.saturating_sub(*secs),
+        }
+    }
+}
+
+impl FromStr for SinceArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(timestamp) = s.parse::<u64>() {
+            return Ok(SinceArg::Timestamp(timestamp));
+        }
+
+        parse_duration_secs(s).map(SinceArg::Ago).ok_or_else(|| {
+            format!(
+                "Invalid time: {} (expected a unix timestamp or e.g. 7d, 12h, 30m)",
+                s
+            )
+        })
+    }
+}
+
+/// A polling interval: seconds or a duration (e.g., 30s, 5m, 1h).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntervalArg(pub Duration);
+
+impl FromStr for IntervalArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let secs = s.parse::<u64>().ok().or_else(|| parse_duration_secs(s));
+        match secs {
+            Some(secs) if secs > 0 => Ok(IntervalArg(Duration::from_secs(secs))),
+            _ => Err(format!(
+                "Invalid interval: {} (expected seconds or e.g. 30s, 5m, 1h)",
+                s
+            )),
+        }
+    }
+}
+
+/// Parse a duration like `7d`, `12h`, `30m`, `45s`, or `2w` into seconds.
+fn parse_duration_secs(s: &str) -> Option<u64> {
+    let unit_secs = match s.chars().last()? {
+        'w' => 7 * 86_400,
+        'd' => 86_400,
+        'h' => 3_600,
+        'm' => 60,
+        's' => 1,
+        _ => return None,
+    };
+    let count: u64 = s[..s.len() - 1].parse().ok()?;
+    count.checked_mul(unit_secs)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -447,7 +848,7 @@ mod tests {
         match cli.command {
             Commands::VaultV1 { subcommand: VaultV1Subcommand::Info(args) } => {
                 assert_eq!(args.address, "0x1234567890abcdef");
-                assert_eq!(args.chain.0, NamedChain::Mainnet); // default
+                assert!(args.chain.is_none()); // profile chain, or ethereum
             }
             _ => panic!("Expected VaultV1 Info command"),
         }
@@ -459,12 +860,53 @@ mod tests {
         match cli.command {
             Commands::VaultV1 { subcommand: VaultV1Subcommand::Info(args) } => {
                 assert_eq!(args.address, "0x1234");
-                assert_eq!(args.chain.0, NamedChain::Polygon);
+                assert_eq!(args.chain.unwrap().0, NamedChain::Polygon);
             }
             _ => panic!("Expected VaultV1 Info command"),
         }
     }
 
+    #[test]
+    fn test_since_arg() {
+        assert_eq!("1700000000".parse(), Ok(SinceArg::Timestamp(1_700_000_000)));
+        assert_eq!("7d".parse(), Ok(SinceArg::Ago(604_800)));
+        assert_eq!("12h".parse(), Ok(SinceArg::Ago(43_200)));
+        assert_eq!("2w".parse(), Ok(SinceArg::Ago(1_209_600)));
+        assert!("".parse::<SinceArg>().is_err());
+        assert!("7y".parse::<SinceArg>().is_err());
+        assert!("d".parse::<SinceArg>().is_err());
+
+        assert_eq!(SinceArg::Ago(100).timestamp(1_000), 900);
+        assert_eq!(SinceArg::Timestamp(5).timestamp(1_000), 5);
+    }
+
+    #[test]
+    fn test_interval_arg() {
+        assert_eq!("30".parse(), Ok(IntervalArg(Duration::from_secs(30))));
+        assert_eq!("30s".parse(), Ok(IntervalArg(Duration::from_secs(30))));
+        assert_eq!("5m".parse(), Ok(IntervalArg(Duration::from_secs(300))));
+        assert_eq!("1h".parse(), Ok(IntervalArg(Duration::from_secs(3_600))));
+        assert!("0".parse::<IntervalArg>().is_err());
+        assert!("0s".parse::<IntervalArg>().is_err());
+        assert!("5x".parse::<IntervalArg>().is_err());
+        assert!("".parse::<IntervalArg>().is_err());
+    }
+
+    #[test]
+    fn test_cli_vaultv1_diff() {
+        let cli = Cli::parse_from([
+            "morpho", "vaultv1", "diff", "0x1234", "--since", "30d", "--chain", "base",
+        ]);
+        match cli.command {
+            Commands::VaultV1 { subcommand: VaultV1Subcommand::Diff(args) } => {
+                assert_eq!(args.address, "0x1234");
+                assert_eq!(args.since, SinceArg::Ago(30 * 86_400));
+                assert_eq!(args.chain.unwrap().0, NamedChain::Base);
+            }
+            _ => panic!("Expected VaultV1 Diff command"),
+        }
+    }
+
     #[test]
     fn test_cli_vaultv2_list() {
         let cli = Cli::parse_from(["morpho", "vaultv2", "list"]);
@@ -483,6 +925,7 @@ mod tests {
             Commands::Positions(args) => {
                 assert_eq!(args.address, "0xuser1234");
                 assert!(args.chain.is_none());
+                assert!(args.watch.is_none());
             }
             _ => panic!("Expected Positions command"),
         }
@@ -500,16 +943,177 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_positions_watch() {
+        let cli = Cli::parse_from(["morpho", "positions", "0xuser", "--watch", "5m"]);
+        match cli.command {
+            Commands::Positions(args) => {
+                assert_eq!(args.watch, Some(IntervalArg(Duration::from_secs(300))));
+            }
+            _ => panic!("Expected Positions command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_tui() {
+        let cli = Cli::parse_from(["morpho", "tui"]);
+        match cli.command {
+            Commands::Tui(args) => {
+                assert!(args.chain.is_none());
+                assert!(args.user.is_none());
+                assert_eq!(args.refresh, IntervalArg(Duration::from_secs(60)));
+                assert_eq!(args.per_asset, 3);
+            }
+            _ => panic!("Expected Tui command"),
+        }
+
+        let cli = Cli::parse_from([
+            "morpho", "tui", "--chain", "base", "--user", "0xuser", "--refresh", "5m",
+            "--per-asset", "5",
+        ]);
+        match cli.command {
+            Commands::Tui(args) => {
+                assert_eq!(args.chain.unwrap().0, NamedChain::Base);
+                assert_eq!(args.user.as_deref(), Some("0xuser"));
+                assert_eq!(args.refresh, IntervalArg(Duration::from_secs(300)));
+                assert_eq!(args.per_asset, 5);
+            }
+            _ => panic!("Expected Tui command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_labels() {
+        let cli = Cli::parse_from(["morpho", "labels", "import", "--chain", "base"]);
+        match cli.command {
+            Commands::Labels {
+                subcommand: LabelsSubcommand::Import(args),
+            } => assert_eq!(args.chain.unwrap().0, NamedChain::Base),
+            _ => panic!("Expected Labels Import command"),
+        }
+
+        let cli = Cli::parse_from([
+            "morpho", "labels", "set", "0xabc", "Steakhouse", "--kind", "curator",
+        ]);
+        match cli.command {
+            Commands::Labels {
+                subcommand: LabelsSubcommand::Set(args),
+            } => {
+                assert_eq!(args.address, "0xabc");
+                assert_eq!(args.name, "Steakhouse");
+                assert_eq!(LabelKind::from(args.kind), LabelKind::Curator);
+            }
+            _ => panic!("Expected Labels Set command"),
+        }
+
+        let cli = Cli::parse_from(["morpho", "labels", "set", "0xabc", "Treasury"]);
+        match cli.command {
+            Commands::Labels {
+                subcommand: LabelsSubcommand::Set(args),
+            } => assert_eq!(args.kind, LabelKindArg::Other),
+            _ => panic!("Expected Labels Set command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_monitor() {
+        let cli = Cli::parse_from([
+            "morpho",
+            "monitor",
+            "--config",
+            "monitor.json",
+            "--slack",
+            "https://hooks.slack.com/a",
+            "--webhook",
+            "https://example.com/1",
+            "--webhook",
+            "https://example.com/2",
+        ]);
+        match cli.command {
+            Commands::Monitor(args) => {
+                assert_eq!(args.config, PathBuf::from("monitor.json"));
+                assert_eq!(args.webhook.len(), 2);
+                assert_eq!(args.slack[0].as_str(), "https://hooks.slack.com/a");
+                assert!(args.discord.is_empty());
+            }
+            _ => panic!("Expected Monitor command"),
+        }
+        assert!(Cli::try_parse_from([
+            "morpho", "monitor", "--config", "m.json", "--slack", "not a url",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_cli_report() {
+        let cli = Cli::parse_from(["morpho", "report", "--config", "report.json"]);
+        match cli.command {
+            Commands::Report(args) => {
+                assert_eq!(args.config, PathBuf::from("report.json"));
+                assert!(args.state.is_none());
+                assert!(args.output.is_none());
+                assert!(!args.watch);
+            }
+            _ => panic!("Expected Report command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_report_with_state_and_watch() {
+        let cli = Cli::parse_from([
+            "morpho",
+            "report",
+            "--config",
+            "report.json",
+            "--state",
+            "last.json",
+            "--output",
+            "report.md",
+            "--watch",
+        ]);
+        match cli.command {
+            Commands::Report(args) => {
+                assert_eq!(args.state, Some(PathBuf::from("last.json")));
+                assert_eq!(args.output, Some(PathBuf::from("report.md")));
+                assert!(args.watch);
+            }
+            _ => panic!("Expected Report command"),
+        }
+    }
+
     #[test]
     fn test_cli_output_format_table() {
         let cli = Cli::parse_from(["morpho", "vaultv1", "list"]);
-        assert!(matches!(cli.format, OutputFormat::Table));
+        assert!(cli.output_format().is_none()); // profile format, or table
     }
 
     #[test]
     fn test_cli_output_format_json() {
         let cli = Cli::parse_from(["morpho", "--format", "json", "vaultv1", "list"]);
-        assert!(matches!(cli.format, OutputFormat::Json));
+        assert!(matches!(cli.output_format(), Some(OutputFormat::Json)));
+    }
+
+    #[test]
+    fn test_cli_export_format() {
+        let cli = Cli::parse_from([
+            "morpho", "export", "--format", "parquet", "--out", "vaults.parquet",
+        ]);
+        assert!(cli.output_format().is_none());
+        match cli.command {
+            Commands::Export(args) => {
+                assert_eq!(args.file_format(), Some(ExportFormatArg::Parquet));
+                assert_eq!(args.out, PathBuf::from("vaults.parquet"));
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        // An output format before the subcommand is not a file format
+        let cli = Cli::parse_from(["morpho", "--format", "json", "export", "--out", "vaults.csv"]);
+        assert!(matches!(cli.output_format(), Some(OutputFormat::Json)));
+        match cli.command {
+            Commands::Export(args) => assert_eq!(args.file_format(), None),
+            _ => panic!("Expected Export command"),
+        }
     }
 
     #[test]
@@ -524,8 +1128,8 @@ mod tests {
             Commands::VaultV1 { subcommand: VaultV1Subcommand::Deposit(args) } => {
                 assert_eq!(args.vault, "0xvault");
                 assert_eq!(args.amount, "100.5");
-                assert_eq!(args.private_key, "0xprivkey");
-                assert_eq!(args.rpc_url, "http://localhost:8545");
+                assert_eq!(args.private_key.as_deref(), Some("0xprivkey"));
+                assert_eq!(args.rpc_url.as_deref(), Some("http://localhost:8545"));
             }
             _ => panic!("Expected VaultV1 Deposit command"),
         }
@@ -543,13 +1147,50 @@ mod tests {
             Commands::VaultV1 { subcommand: VaultV1Subcommand::Withdraw(args) } => {
                 assert_eq!(args.vault, "0xvault");
                 assert_eq!(args.amount, "50.0");
-                assert_eq!(args.private_key, "0xprivkey");
-                assert_eq!(args.rpc_url, "http://localhost:8545");
+                assert_eq!(args.private_key.as_deref(), Some("0xprivkey"));
+                assert_eq!(args.rpc_url.as_deref(), Some("http://localhost:8545"));
             }
             _ => panic!("Expected VaultV1 Withdraw command"),
         }
     }
 
+    #[test]
+    fn test_cli_rewards_claim_args() {
+        let cli = Cli::parse_from([
+            "morpho", "rewards", "claim",
+            "--chain", "base",
+            "--private-key", "0xprivkey",
+            "--rpc-url", "http://localhost:8545"
+        ]);
+        match cli.command {
+            Commands::Rewards { subcommand: RewardsSubcommand::Claim(args) } => {
+                assert_eq!(args.chain.unwrap().0, NamedChain::Base);
+                assert_eq!(args.private_key.as_deref(), Some("0xprivkey"));
+                assert_eq!(args.rpc_url.as_deref(), Some("http://localhost:8545"));
+            }
+            _ => panic!("Expected Rewards Claim command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_profile() {
+        let cli = Cli::parse_from(["morpho", "vaultv1", "list", "--profile", "work"]);
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_cli_keystore_args() {
+        let cli = Cli::parse_from([
+            "morpho", "vaultv2", "deposit", "0xvault", "1", "--keystore", "key.json",
+        ]);
+        match cli.command {
+            Commands::VaultV2 { subcommand: VaultV2Subcommand::Deposit(args) } => {
+                assert_eq!(args.keystore, Some(PathBuf::from("key.json")));
+            }
+            _ => panic!("Expected VaultV2 Deposit command"),
+        }
+    }
+
     #[test]
     fn test_cli_invalid_command() {
         let result = Cli::try_parse_from(["morpho", "invalid"]);
@@ -585,6 +1226,6 @@ mod tests {
             "vaultv2", "list"
         ]);
         assert_eq!(cli.api_url, Some("http://test.api".to_string()));
-        assert!(matches!(cli.format, OutputFormat::Json));
+        assert!(matches!(cli.output_format(), Some(OutputFormat::Json)));
     }
 }
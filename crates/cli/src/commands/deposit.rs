@@ -2,46 +2,29 @@
 
 use alloy_primitives::{Address, U256};
 use anyhow::{Context, Result};
-use morpho_rs_api::{Erc4626Client, VaultV1TransactionClient, VaultV2TransactionClient};
+use morpho_rs_api::{
+    Erc4626Client, PlannedOperation, TokenAmount, VaultV1TransactionClient,
+    VaultV2TransactionClient,
+};
 
 use crate::cli::DepositArgs;
+use crate::commands::estimate::{print_cost_estimate, VaultVersion};
+use crate::config::Profile;
 
 /// Parse a human-readable amount string to U256 given decimals.
 fn parse_amount(amount: &str, decimals: u8) -> Result<U256> {
-    let parts: Vec<&str> = amount.split('.').collect();
-
-    let (integer_part, fractional_part) = match parts.len() {
-        1 => (parts[0], ""),
-        2 => (parts[0], parts[1]),
-        _ => anyhow::bail!("Invalid amount format: {}", amount),
-    };
-
-    // Pad or truncate the fractional part to match decimals
-    let fractional_padded = if fractional_part.len() > decimals as usize {
-        &fractional_part[..decimals as usize]
-    } else {
-        fractional_part
-    };
-
-    let fractional_padded = format!("{:0<width$}", fractional_padded, width = decimals as usize);
-
-    let combined = format!("{}{}", integer_part, fractional_padded);
-    let combined = combined.trim_start_matches('0');
-
-    if combined.is_empty() {
-        return Ok(U256::ZERO);
-    }
-
-    U256::from_str_radix(combined, 10)
-        .with_context(|| format!("Failed to parse amount: {}", amount))
+    Ok(TokenAmount::parse(amount, decimals)?.raw)
 }
 
 /// Run the deposit command for a V1 vault.
-pub async fn run_v1_deposit(args: &DepositArgs) -> Result<()> {
+pub async fn run_v1_deposit(args: &DepositArgs, profile: &Profile) -> Result<()> {
     let vault: Address = args.vault.parse().context("Invalid vault address")?;
 
+    let rpc_url = profile.rpc_url(args.rpc_url.as_deref(), profile.chain_or(args.chain))?;
+    let private_key = profile.private_key(args.private_key.as_deref(), args.keystore.as_deref())?;
+
     println!("Connecting to RPC...");
-    let client = VaultV1TransactionClient::new(&args.rpc_url, &args.private_key)?;
+    let client = VaultV1TransactionClient::new(&rpc_url, &private_key)?;
 
     println!("Fetching vault asset...");
     let asset = client.get_asset(vault).await?;
@@ -50,6 +33,12 @@ pub async fn run_v1_deposit(args: &DepositArgs) -> Result<()> {
     let decimals = client.get_decimals(asset).await?;
 
     let amount = parse_amount(&args.amount, decimals)?;
+
+    if args.estimate {
+        let op = PlannedOperation::Deposit { vault, amount };
+        return print_cost_estimate(&rpc_url, &private_key, VaultVersion::V1, op).await;
+    }
+
     let signer = client.signer_address();
 
     println!("Checking balance...");
@@ -92,11 +81,14 @@ pub async fn run_v1_deposit(args: &DepositArgs) -> Result<()> {
 }
 
 /// Run the deposit command for a V2 vault.
-pub async fn run_v2_deposit(args: &DepositArgs) -> Result<()> {
+pub async fn run_v2_deposit(args: &DepositArgs, profile: &Profile) -> Result<()> {
     let vault: Address = args.vault.parse().context("Invalid vault address")?;
 
+    let rpc_url = profile.rpc_url(args.rpc_url.as_deref(), profile.chain_or(args.chain))?;
+    let private_key = profile.private_key(args.private_key.as_deref(), args.keystore.as_deref())?;
+
     println!("Connecting to RPC...");
-    let client = VaultV2TransactionClient::new(&args.rpc_url, &args.private_key)?;
+    let client = VaultV2TransactionClient::new(&rpc_url, &private_key)?;
 
     println!("Fetching vault asset...");
     let asset = client.get_asset(vault).await?;
@@ -105,6 +97,12 @@ pub async fn run_v2_deposit(args: &DepositArgs) -> Result<()> {
     let decimals = client.get_decimals(asset).await?;
 
     let amount = parse_amount(&args.amount, decimals)?;
+
+    if args.estimate {
+        let op = PlannedOperation::Deposit { vault, amount };
+        return print_cost_estimate(&rpc_url, &private_key, VaultVersion::V2, op).await;
+    }
+
     let signer = client.signer_address();
 
     println!("Checking balance...");
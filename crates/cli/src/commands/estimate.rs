@@ -0,0 +1,43 @@
+//! Gas cost estimation shared by the deposit and withdraw commands.
+
+use anyhow::Result;
+use morpho_rs_api::{MorphoClient, MorphoClientConfig, PlannedOperation};
+
+/// Vault version an operation targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultVersion {
+    V1,
+    V2,
+}
+
+/// Print the estimated gas cost of `op` without sending it.
+pub async fn print_cost_estimate(
+    rpc_url: &str,
+    private_key: &str,
+    version: VaultVersion,
+    op: PlannedOperation,
+) -> Result<()> {
+    let config = MorphoClientConfig::new()
+        .with_rpc_url(rpc_url)
+        .with_private_key(private_key);
+    let client = MorphoClient::with_config(config)?;
+
+    println!("Estimating gas cost...");
+    let estimate = match version {
+        VaultVersion::V1 => client.vault_v1()?.estimate_operation_cost(op).await?,
+        VaultVersion::V2 => client.vault_v2()?.estimate_operation_cost(op).await?,
+    };
+
+    println!("  Gas:       {}", estimate.gas);
+    println!("  Gas Price: {:.2} gwei", estimate.gas_price as f64 / 1e9);
+    println!("  Cost:      {:.6} native", estimate.cost_native());
+    match estimate.cost_usd() {
+        Some(cost) => println!("  Cost USD:  ${:.2}", cost),
+        None => println!("  Cost USD:  unknown (no native token price)"),
+    }
+    if let Some(pct) = estimate.pct_of_amount {
+        println!("  Of Amount: {:.3}%", pct * 100.0);
+    }
+
+    Ok(())
+}
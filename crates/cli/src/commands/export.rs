@@ -0,0 +1,57 @@
+//! Export command implementation.
+
+use anyhow::{bail, Result};
+use morpho_rs_api::export::fetch_snapshot;
+use morpho_rs_api::{ClientConfig, ExportFormat, ExportTable, MorphoApiClient, NamedChain};
+
+use crate::cli::ExportArgs;
+
+/// Create a MorphoApiClient with optional API URL.
+fn create_client(api_url: Option<&str>) -> Result<MorphoApiClient> {
+    if let Some(url) = api_url {
+        let config = ClientConfig::new().with_api_url(url.parse()?);
+        Ok(MorphoApiClient::with_config(config))
+    } else {
+        Ok(MorphoApiClient::new())
+    }
+}
+
+pub async fn run_export(args: &ExportArgs, api_url: Option<&str>) -> Result<()> {
+    let format = match args.file_format() {
+        Some(format) => format.into(),
+        None => match ExportFormat::from_path(&args.out) {
+            Some(format) => format,
+            None => bail!(
+                "cannot infer the format of {}; use a .db, .parquet, .sql, .csv, or .jsonl \
+                 file or pass --format",
+                args.out.display()
+            ),
+        },
+    };
+
+    let client = create_client(api_url)?;
+    let chains: Vec<NamedChain> = args.chain.iter().map(|c| c.0).collect();
+    let snapshot = fetch_snapshot(&client, &chains).await?;
+
+    let table = args.table.map(ExportTable::from);
+    snapshot.save(&args.out, format, table)?;
+
+    let both = table.is_none() && matches!(format, ExportFormat::Sqlite | ExportFormat::Sql);
+    let summary = if both {
+        format!(
+            "{} vaults and {} markets",
+            snapshot.vaults.len(),
+            snapshot.markets.len()
+        )
+    } else {
+        let table = table.unwrap_or(ExportTable::Vaults);
+        let rows = match table {
+            ExportTable::Vaults => snapshot.vaults.len(),
+            ExportTable::Markets => snapshot.markets.len(),
+        };
+        format!("{} {}", rows, table.name())
+    };
+
+    eprintln!("Exported {} to {}", summary, args.out.display());
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! Address book command implementations.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use alloy_primitives::Address;
+use anyhow::{anyhow, Context, Result};
+use morpho_rs_api::{AddressBook, ApiError, ClientConfig, MorphoApiClient, SUPPORTED_CHAINS};
+
+use crate::cli::{LabelsImportArgs, LabelsSetArgs, OutputFormat};
+use crate::config::Profile;
+use crate::output::format_labels_table;
+
+/// Create a MorphoApiClient with optional API URL.
+fn create_client(api_url: Option<&str>) -> Result<MorphoApiClient> {
+    if let Some(url) = api_url {
+        let config = ClientConfig::new().with_api_url(url.parse()?);
+        Ok(MorphoApiClient::with_config(config))
+    } else {
+        Ok(MorphoApiClient::new())
+    }
+}
+
+/// Path of the profile's address book, failing if there is no home directory.
+fn labels_path(profile: &Profile) -> Result<PathBuf> {
+    profile
+        .labels_path()
+        .ok_or_else(|| anyhow!("no address book path: set the profile's labels or MORPHO_CONFIG"))
+}
+
+/// Write the address book, creating its directory if needed.
+fn save(book: &AddressBook, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    book.save(path)?;
+    Ok(())
+}
+
+pub async fn run_labels_import(
+    args: &LabelsImportArgs,
+    api_url: Option<&str>,
+    profile: &Profile,
+) -> Result<()> {
+    let client = create_client(api_url)?;
+    let path = labels_path(profile)?;
+    let mut book = profile.address_book()?;
+    let before = book.len();
+
+    let chains = args
+        .chain
+        .map_or_else(|| SUPPORTED_CHAINS.to_vec(), |c| vec![c.0]);
+    for chain in chains {
+        match client.get_address_book(chain).await {
+            Ok(imported) => book.merge(imported),
+            // Nothing indexed on this chain
+            Err(ApiError::GraphQL(msg)) if msg.contains("No results") => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    save(&book, &path)?;
+    println!(
+        "Imported {} labels into {} ({} total)",
+        book.len() - before,
+        path.display(),
+        book.len()
+    );
+
+    Ok(())
+}
+
+pub fn run_labels_set(args: &LabelsSetArgs, profile: &Profile) -> Result<()> {
+    let address: Address = args.address.parse().context("Invalid address")?;
+    let path = labels_path(profile)?;
+    let mut book = profile.address_book()?;
+
+    book.insert(address, args.kind.into(), args.name.as_str());
+    save(&book, &path)?;
+    println!("{}", book.display(address));
+
+    Ok(())
+}
+
+pub fn run_labels_list(format: OutputFormat, profile: &Profile) -> Result<()> {
+    let book = profile.address_book()?;
+
+    match format {
+        OutputFormat::Table => {
+            println!("{}", format_labels_table(&book));
+        }
+        OutputFormat::Json => {
+            println!("{}", book.to_json()?);
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,26 @@
 //! Command implementations.
 
 pub mod deposit;
+pub mod estimate;
+pub mod export;
+pub mod labels;
+pub mod monitor;
 pub mod positions;
+pub mod report;
+pub mod rewards;
 pub mod vault_v1;
 pub mod vault_v2;
 pub mod withdraw;
 
 pub use deposit::{run_v1_deposit, run_v2_deposit};
+pub use export::run_export;
+pub use labels::{run_labels_import, run_labels_list, run_labels_set};
+pub use monitor::run_monitor;
 pub use positions::run_positions;
-pub use vault_v1::{run_v1_info, run_v1_list};
+pub use report::run_report;
+pub use rewards::run_rewards_claim;
+#[cfg(feature = "sim")]
+pub use vault_v1::run_v1_lint;
+pub use vault_v1::{run_v1_diff, run_v1_info, run_v1_list};
 pub use vault_v2::{run_v2_info, run_v2_list};
 pub use withdraw::{run_v1_withdraw, run_v2_withdraw};
@@ -0,0 +1,68 @@
+//! Monitor command implementation.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use morpho_rs_api::{
+    ClientConfig, MonitorConfig, MorphoApiClient, Notifier, SinkConfig, VaultMonitor,
+};
+
+use crate::cli::MonitorArgs;
+
+/// Create a ClientConfig with optional API URL.
+fn create_client_config(api_url: Option<&str>) -> Result<ClientConfig> {
+    let config = ClientConfig::new();
+    match api_url {
+        Some(url) => Ok(config.with_api_url(url.parse()?)),
+        None => Ok(config),
+    }
+}
+
+/// Load the monitor config and add the sinks given on the command line.
+///
+/// Alerts go to stdout when no sink is configured.
+fn load_config(args: &MonitorArgs) -> Result<MonitorConfig> {
+    let json = fs::read_to_string(&args.config)
+        .with_context(|| format!("failed to read monitor config {}", args.config.display()))?;
+    let mut config: MonitorConfig = serde_json::from_str(&json)
+        .with_context(|| format!("invalid monitor config {}", args.config.display()))?;
+
+    let webhooks = args
+        .webhook
+        .iter()
+        .map(|url| SinkConfig::Webhook { url: url.clone() });
+    let slack = args
+        .slack
+        .iter()
+        .map(|url| SinkConfig::Slack { url: url.clone() });
+    let discord = args
+        .discord
+        .iter()
+        .map(|url| SinkConfig::Discord { url: url.clone() });
+    config
+        .notifications
+        .extend(webhooks.chain(slack).chain(discord));
+    if config.notifications.is_empty() {
+        config.notifications.push(SinkConfig::Stdout);
+    }
+    Ok(config)
+}
+
+pub async fn run_monitor(args: &MonitorArgs, api_url: Option<&str>) -> Result<()> {
+    let config = load_config(args)?;
+    let client_config = create_client_config(api_url)?;
+    let notifier = Notifier::from_configs(&config.notifications, &client_config)?;
+    let client = MorphoApiClient::with_config(client_config);
+
+    eprintln!(
+        "Monitoring {} vaults with {} rules every {}s, notifying {} sinks",
+        config.vaults.len(),
+        config.rules.len(),
+        config.poll_interval_secs,
+        notifier.sinks().len()
+    );
+    VaultMonitor::new(config)
+        .run_notifying(&client, &notifier, |failure| eprintln!("{failure}"))
+        .await;
+    Ok(())
+}
@@ -1,11 +1,17 @@
 //! User positions command implementation.
 
+use std::time::{Duration, Instant};
+
+use alloy_chains::NamedChain;
 use alloy_primitives::Address;
-use anyhow::Result;
-use morpho_rs_api::{ClientConfig, MorphoClient, MorphoClientConfig, UserVaultPositions};
+use anyhow::{Context, Result};
+use morpho_rs_api::{
+    ApiError, ClientConfig, MorphoClient, MorphoClientConfig, UserAccountOverview,
+    UserVaultPositions, SUPPORTED_CHAINS,
+};
 
 use crate::cli::{OutputFormat, PositionsArgs};
-use crate::output::format_user_positions;
+use crate::output::{format_position_deltas, format_user_positions, WatchedPosition};
 
 /// Create a MorphoClient with optional API URL.
 fn create_client(api_url: Option<&str>) -> Result<MorphoClient> {
@@ -23,11 +29,16 @@ pub async fn run_positions(args: &PositionsArgs, format: OutputFormat, api_url:
 
     let chain = args.chain.map(|c| c.0);
 
-    let positions = match client.get_user_vault_positions(&args.address, chain).await {
+    let address: Address = args.address.parse().context("Invalid user address")?;
+
+    if let Some(interval) = args.watch {
+        return watch_positions(&client, address, chain, interval.0, format).await;
+    }
+
+    let positions = match client.get_user_vault_positions(address, chain).await {
         Ok(p) => p,
         Err(morpho_rs_api::ApiError::GraphQL(msg)) if msg.contains("No results") => {
             // User has no positions on this chain - return empty result
-            let address = args.address.parse().unwrap_or(Address::ZERO);
             UserVaultPositions {
                 address,
                 vault_positions: vec![],
@@ -49,3 +60,172 @@ pub async fn run_positions(args: &PositionsArgs, format: OutputFormat, api_url:
 
     Ok(())
 }
+
+/// Re-query a user's positions every `interval` and print what changed.
+async fn watch_positions(
+    client: &MorphoClient,
+    address: Address,
+    chain: Option<NamedChain>,
+    interval: Duration,
+    format: OutputFormat,
+) -> Result<()> {
+    let started = Instant::now();
+    let mut start: Option<Vec<WatchedPosition>> = None;
+    let mut previous: Vec<WatchedPosition> = Vec::new();
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+        let current = fetch_watched_positions(client, address, chain).await?;
+        let start = start.get_or_insert_with(|| current.clone());
+
+        match format {
+            OutputFormat::Table => {
+                println!(
+                    "{}",
+                    format_position_deltas(
+                        &address.to_string(),
+                        start,
+                        &previous,
+                        &current,
+                        started.elapsed().as_secs()
+                    )
+                );
+            }
+            OutputFormat::Json => {
+                println!("{}", watched_positions_json(&current, start));
+            }
+        }
+
+        previous = current;
+    }
+}
+
+/// Query the user's vault and market positions, with the current net APY of each vault.
+async fn fetch_watched_positions(
+    client: &MorphoClient,
+    address: Address,
+    chain: Option<NamedChain>,
+) -> Result<Vec<WatchedPosition>> {
+    let chains = chain.map_or_else(|| SUPPORTED_CHAINS.to_vec(), |c| vec![c]);
+    let mut watched = Vec::new();
+
+    for chain in chains {
+        let overview = match client.get_user_account_overview(address, chain).await {
+            Ok(overview) => overview,
+            // User has no positions on this chain
+            Err(ApiError::GraphQL(msg)) if msg.contains("No results") => continue,
+            Err(e) => return Err(e.into()),
+        };
+        watched.extend(watched_from_overview(client, &overview, chain).await);
+    }
+
+    Ok(watched)
+}
+
+/// Convert an account overview on `chain` into watched positions.
+///
+/// Vault APYs that cannot be fetched are left unknown.
+async fn watched_from_overview(
+    client: &MorphoClient,
+    overview: &UserAccountOverview,
+    chain: NamedChain,
+) -> Vec<WatchedPosition> {
+    let mut watched = Vec::new();
+
+    for pos in &overview.vault_positions {
+        let net_apy = client
+            .api()
+            .v1
+            .get_vault(pos.vault.address, pos.vault.chain)
+            .await
+            .ok()
+            .and_then(|v| v.state.map(|s| s.net_apy));
+        watched.push(WatchedPosition {
+            id: pos.id.clone(),
+            kind: "V1",
+            chain: pos.vault.chain.as_str().to_string(),
+            name: pos.vault.name.clone(),
+            value_usd: pos.assets_usd,
+            pnl_usd: pos.state.as_ref().and_then(|s| s.pnl_usd),
+            net_apy,
+            health_factor: None,
+        });
+    }
+
+    for pos in &overview.vault_v2_positions {
+        let net_apy = client
+            .api()
+            .v2
+            .get_vault(pos.vault.address, pos.vault.chain)
+            .await
+            .ok()
+            .and_then(|v| v.net_apy);
+        watched.push(WatchedPosition {
+            id: pos.id.clone(),
+            kind: "V2",
+            chain: pos.vault.chain.as_str().to_string(),
+            name: pos.vault.name.clone(),
+            value_usd: pos.assets_usd,
+            pnl_usd: pos.pnl_usd,
+            net_apy,
+            health_factor: None,
+        });
+    }
+
+    for pos in &overview.market_positions {
+        if pos.supply_assets.is_zero() && pos.borrow_assets.is_zero() && pos.collateral.is_zero() {
+            continue;
+        }
+        let value_usd = [
+            pos.supply_assets_usd,
+            pos.collateral_usd,
+            pos.borrow_assets_usd.map(|b| -b),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(|a, b| a + b);
+        watched.push(WatchedPosition {
+            id: pos.id.clone(),
+            kind: "Market",
+            chain: chain.as_str().to_string(),
+            name: format!(
+                "{}/{}",
+                pos.market.collateral_asset_symbol.as_deref().unwrap_or("?"),
+                pos.market.loan_asset_symbol.as_deref().unwrap_or("?")
+            ),
+            value_usd,
+            pnl_usd: None,
+            net_apy: None,
+            health_factor: pos.health_factor,
+        });
+    }
+
+    watched
+}
+
+/// One JSON line per watch update, with changes relative to the first query.
+fn watched_positions_json(current: &[WatchedPosition], start: &[WatchedPosition]) -> String {
+    let change = |p: &WatchedPosition, field: fn(&WatchedPosition) -> Option<f64>| -> Option<f64> {
+        let initial = start.iter().find(|s| s.id == p.id)?;
+        Some(field(p)? - field(initial)?)
+    };
+    let positions: Vec<_> = current
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "id": p.id,
+                "type": p.kind,
+                "chain": p.chain,
+                "name": p.name,
+                "value_usd": p.value_usd,
+                "pnl_usd": p.pnl_usd,
+                "pnl_usd_change": change(p, |p| p.pnl_usd),
+                "net_apy": p.net_apy,
+                "health_factor": p.health_factor,
+                "health_factor_change": change(p, |p| p.health_factor),
+            })
+        })
+        .collect();
+    serde_json::json!({ "positions": positions }).to_string()
+}
@@ -0,0 +1,89 @@
+//! Report command implementation.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use morpho_rs_api::{
+    ClientConfig, MorphoClient, MorphoClientConfig, Report, ReportConfig, ReportFormat,
+    ReportGenerator,
+};
+
+use crate::cli::{OutputFormat, ReportArgs};
+
+/// Create a MorphoClient with optional API URL.
+fn create_client(api_url: Option<&str>) -> Result<MorphoClient> {
+    if let Some(url) = api_url {
+        let api_config = ClientConfig::new().with_api_url(url.parse()?);
+        let config = MorphoClientConfig::new().with_api_config(api_config);
+        Ok(MorphoClient::with_config(config)?)
+    } else {
+        Ok(MorphoClient::new())
+    }
+}
+
+/// Load the previous report from the state file, if it exists.
+fn load_state(path: Option<&Path>) -> Result<Option<Report>> {
+    match path {
+        Some(path) if path.exists() => {
+            let json = fs::read_to_string(path)
+                .with_context(|| format!("failed to read state file {}", path.display()))?;
+            Ok(Some(Report::from_json(&json)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Write a report to the output file (or stdout) and persist it as state.
+fn emit_report(report: &Report, args: &ReportArgs, format: ReportFormat) -> Result<()> {
+    let rendered = report.render(format)?;
+    match &args.output {
+        Some(path) => fs::write(path, &rendered)
+            .with_context(|| format!("failed to write report to {}", path.display()))?,
+        None => println!("{}", rendered),
+    }
+
+    if let Some(path) = &args.state {
+        fs::write(path, report.to_json()?)
+            .with_context(|| format!("failed to write state file {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+pub async fn run_report(
+    args: &ReportArgs,
+    format: OutputFormat,
+    api_url: Option<&str>,
+) -> Result<()> {
+    let config_json = fs::read_to_string(&args.config)
+        .with_context(|| format!("failed to read report config {}", args.config.display()))?;
+    let config: ReportConfig = serde_json::from_str(&config_json)
+        .with_context(|| format!("invalid report config {}", args.config.display()))?;
+
+    let format = match format {
+        OutputFormat::Table => ReportFormat::Markdown,
+        OutputFormat::Json => ReportFormat::Json,
+    };
+
+    let client = create_client(api_url)?;
+    let generator = ReportGenerator::new(config);
+    let previous = load_state(args.state.as_deref())?;
+
+    if args.watch {
+        let mut previous = previous;
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+            generator.config().cadence_secs.max(1),
+        ));
+        loop {
+            interval.tick().await;
+            let report = generator.generate(client.api(), previous.as_ref()).await?;
+            emit_report(&report, args, format)?;
+            previous = Some(report);
+        }
+    }
+
+    // Single-shot mode: one report per invocation, suitable for cron
+    let report = generator.generate(client.api(), previous.as_ref()).await?;
+    emit_report(&report, args, format)
+}
@@ -0,0 +1,49 @@
+//! Rewards claim command implementation.
+
+use anyhow::Result;
+use morpho_rs_api::{ClientConfig, MorphoClient, MorphoClientConfig};
+
+use crate::cli::ClaimRewardsArgs;
+use crate::config::Profile;
+
+/// Run the rewards claim command.
+pub async fn run_rewards_claim(
+    args: &ClaimRewardsArgs,
+    api_url: Option<&str>,
+    profile: &Profile,
+) -> Result<()> {
+    let chain = profile.chain_or(args.chain);
+    let rpc_url = profile.rpc_url(args.rpc_url.as_deref(), chain)?;
+    let private_key = profile.private_key(args.private_key.as_deref(), args.keystore.as_deref())?;
+
+    let mut config = MorphoClientConfig::new()
+        .with_rpc_url(&rpc_url)
+        .with_private_key(&private_key);
+    if let Some(url) = api_url {
+        config = config.with_api_config(ClientConfig::new().with_api_url(url.parse()?));
+    }
+
+    println!("Connecting to RPC...");
+    let client = MorphoClient::with_config(config)?;
+
+    println!("Claiming rewards on {}...", chain.as_str());
+    println!("Waiting for confirmation...\n");
+
+    let receipts = client.claim_rewards(chain).await?;
+    if receipts.is_empty() {
+        println!("No rewards to claim.");
+        return Ok(());
+    }
+
+    for receipt in &receipts {
+        println!("Claim confirmed!");
+        println!("  Tx Hash:   {:#x}", receipt.transaction_hash);
+        println!("  Block:     {}", receipt.block_number.unwrap_or_default());
+        println!(
+            "  Status:    {}",
+            if receipt.status() { "Success" } else { "Failed" }
+        );
+    }
+
+    Ok(())
+}
@@ -1,11 +1,17 @@
 //! V1 vault command implementations.
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use alloy_chains::NamedChain;
-use anyhow::Result;
-use morpho_rs_api::{ClientConfig, VaultV1, VaultV1Client};
+use alloy_primitives::Address;
+use anyhow::{Context, Result};
+use morpho_rs_api::{AddressBook, ClientConfig, VaultV1, VaultV1Client};
 
-use crate::cli::{InfoArgs, ListArgs, OutputFormat};
-use crate::output::{format_v1_vault_detail, format_v1_vaults_table};
+use crate::cli::{DiffArgs, InfoArgs, ListArgs, OutputFormat};
+use crate::config::Profile;
+#[cfg(feature = "sim")]
+use crate::output::format_v1_queue_findings;
+use crate::output::{format_v1_vault_detail, format_v1_vault_diff, format_v1_vaults_table};
 
 /// Create a ClientConfig with a custom page size and optional API URL.
 fn client_config_with_page_size(page_size: i64, api_url: Option<&str>) -> Result<ClientConfig> {
@@ -27,7 +33,12 @@ fn client_config(api_url: Option<&str>) -> Result<ClientConfig> {
     }
 }
 
-pub async fn run_v1_list(args: &ListArgs, format: OutputFormat, api_url: Option<&str>) -> Result<()> {
+pub async fn run_v1_list(
+    args: &ListArgs,
+    format: OutputFormat,
+    api_url: Option<&str>,
+    labels: &AddressBook,
+) -> Result<()> {
     // Use smaller page size to avoid query complexity issues
     let config = client_config_with_page_size(args.limit as i64, api_url)?;
     let client = VaultV1Client::with_config(config);
@@ -62,7 +73,7 @@ pub async fn run_v1_list(args: &ListArgs, format: OutputFormat, api_url: Option<
     // Output
     match format {
         OutputFormat::Table => {
-            println!("{}", format_v1_vaults_table(&vaults));
+            println!("{}", format_v1_vaults_table(&vaults, labels));
         }
         OutputFormat::Json => {
             let json = serde_json::to_string_pretty(&vaults)?;
@@ -73,16 +84,24 @@ pub async fn run_v1_list(args: &ListArgs, format: OutputFormat, api_url: Option<
     Ok(())
 }
 
-pub async fn run_v1_info(args: &InfoArgs, format: OutputFormat, api_url: Option<&str>) -> Result<()> {
+pub async fn run_v1_info(
+    args: &InfoArgs,
+    format: OutputFormat,
+    api_url: Option<&str>,
+    profile: &Profile,
+    labels: &AddressBook,
+) -> Result<()> {
     let config = client_config(api_url)?;
     let client = VaultV1Client::with_config(config);
-    let chain: NamedChain = args.chain.0;
+    let chain: NamedChain = profile.chain_or(args.chain);
+
+    let address: Address = args.address.parse().context("Invalid vault address")?;
 
-    let vault = client.get_vault(&args.address, chain).await?;
+    let vault = client.get_vault(address, chain).await?;
 
     match format {
         OutputFormat::Table => {
-            println!("{}", format_v1_vault_detail(&vault));
+            println!("{}", format_v1_vault_detail(&vault, labels));
         }
         OutputFormat::Json => {
             let json = serde_json::to_string_pretty(&vault)?;
@@ -92,3 +111,79 @@ pub async fn run_v1_info(args: &InfoArgs, format: OutputFormat, api_url: Option<
 
     Ok(())
 }
+
+pub async fn run_v1_diff(
+    args: &DiffArgs,
+    format: OutputFormat,
+    api_url: Option<&str>,
+    profile: &Profile,
+    labels: &AddressBook,
+) -> Result<()> {
+    let config = client_config(api_url)?;
+    let client = VaultV1Client::with_config(config);
+    let chain: NamedChain = profile.chain_or(args.chain);
+
+    let address: Address = args.address.parse().context("Invalid vault address")?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let since = args.since.timestamp(now);
+
+    let before = client.get_vault_at(address, chain, since).await?;
+    let after = client.get_vault(address, chain).await?;
+    let diff = before.diff(&after);
+
+    match format {
+        OutputFormat::Table => {
+            println!("{}", format_v1_vault_diff(&after, since, &diff, labels));
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&diff)?;
+            println!("{}", json);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "sim")]
+pub async fn run_v1_lint(
+    args: &InfoArgs,
+    format: OutputFormat,
+    api_url: Option<&str>,
+    profile: &Profile,
+) -> Result<()> {
+    let config = client_config(api_url)?;
+    let client = VaultV1Client::with_config(config);
+    let chain: NamedChain = profile.chain_or(args.chain);
+
+    let address: Address = args.address.parse().context("Invalid vault address")?;
+
+    let vault = client.get_vault(address, chain).await?;
+    let simulation = vault
+        .to_vault_simulation()
+        .context("Vault has no state to lint")?;
+    let findings = simulation.validate_queues();
+
+    match format {
+        OutputFormat::Table => {
+            println!("{}", format_v1_queue_findings(&vault, &findings));
+        }
+        OutputFormat::Json => {
+            let findings: Vec<_> = findings
+                .iter()
+                .map(|f| {
+                    serde_json::json!({
+                        "market_id": f.market_id(),
+                        "message": f.to_string(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&findings)?);
+        }
+    }
+
+    if !findings.is_empty() {
+        anyhow::bail!("{} queue finding(s) in vault {}", findings.len(), address);
+    }
+    Ok(())
+}
@@ -1,10 +1,12 @@
 //! V2 vault command implementations.
 
 use alloy_chains::NamedChain;
-use anyhow::Result;
-use morpho_rs_api::{ClientConfig, VaultV2, VaultV2Client};
+use alloy_primitives::Address;
+use anyhow::{Context, Result};
+use morpho_rs_api::{AddressBook, ClientConfig, VaultV2, VaultV2Client};
 
 use crate::cli::{InfoArgs, ListArgs, OutputFormat};
+use crate::config::Profile;
 use crate::output::{format_v2_vault_detail, format_v2_vaults_table};
 
 /// Create a ClientConfig with a custom page size and optional API URL.
@@ -27,7 +29,12 @@ fn client_config(api_url: Option<&str>) -> Result<ClientConfig> {
     }
 }
 
-pub async fn run_v2_list(args: &ListArgs, format: OutputFormat, api_url: Option<&str>) -> Result<()> {
+pub async fn run_v2_list(
+    args: &ListArgs,
+    format: OutputFormat,
+    api_url: Option<&str>,
+    labels: &AddressBook,
+) -> Result<()> {
     // Use larger page size when client-side filtering is needed (e.g., curator filter)
     // to ensure we have enough results after filtering
     let page_size = if args.curator.is_some() {
@@ -70,7 +77,7 @@ pub async fn run_v2_list(args: &ListArgs, format: OutputFormat, api_url: Option<
     // Output
     match format {
         OutputFormat::Table => {
-            println!("{}", format_v2_vaults_table(&vaults));
+            println!("{}", format_v2_vaults_table(&vaults, labels));
         }
         OutputFormat::Json => {
             let json = serde_json::to_string_pretty(&vaults)?;
@@ -81,16 +88,24 @@ pub async fn run_v2_list(args: &ListArgs, format: OutputFormat, api_url: Option<
     Ok(())
 }
 
-pub async fn run_v2_info(args: &InfoArgs, format: OutputFormat, api_url: Option<&str>) -> Result<()> {
+pub async fn run_v2_info(
+    args: &InfoArgs,
+    format: OutputFormat,
+    api_url: Option<&str>,
+    profile: &Profile,
+    labels: &AddressBook,
+) -> Result<()> {
     let config = client_config(api_url)?;
     let client = VaultV2Client::with_config(config);
-    let chain: NamedChain = args.chain.0;
+    let chain: NamedChain = profile.chain_or(args.chain);
 
-    let vault = client.get_vault(&args.address, chain).await?;
+    let address: Address = args.address.parse().context("Invalid vault address")?;
+
+    let vault = client.get_vault(address, chain).await?;
 
     match format {
         OutputFormat::Table => {
-            println!("{}", format_v2_vault_detail(&vault));
+            println!("{}", format_v2_vault_detail(&vault, labels));
         }
         OutputFormat::Json => {
             let json = serde_json::to_string_pretty(&vault)?;
@@ -0,0 +1,320 @@
+//! Config file with named profiles.
+//!
+//! A profile holds defaults for the API URL, per-chain RPC URLs, the default chain, a
+//! keystore to sign with, the output format, and the address book file, so they need not
+//! be passed as flags. The config is read from `$MORPHO_CONFIG`,
+//! `$XDG_CONFIG_HOME/morpho/config.toml`, or `~/.config/morpho/config.toml`, in that
+//! order; a missing file is an empty config.
+//!
+//! ```toml
+//! default_profile = "main"
+//!
+//! [profiles.main]
+//! api_url = "https://api.morpho.org/graphql"
+//! chain = "base"
+//! keystore = "~/.foundry/keystores/main"
+//! format = "table"
+//! labels = "~/.config/morpho/labels.json"
+//!
+//! [profiles.main.rpc_urls]
+//! ethereum = "https://eth.llamarpc.com"
+//! base = "https://base.llamarpc.com"
+//! ```
+//!
+//! Flags and environment variables take precedence over the selected profile.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use alloy_chains::NamedChain;
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::{anyhow, bail, Context, Result};
+use morpho_rs_api::AddressBook;
+use serde::Deserialize;
+
+use crate::cli::{ChainArg, OutputFormat};
+
+/// Environment variable naming the config file.
+pub const CONFIG_ENV: &str = "MORPHO_CONFIG";
+
+/// Environment variable holding the keystore password; prompted for if unset.
+pub const KEYSTORE_PASSWORD_ENV: &str = "MORPHO_KEYSTORE_PASSWORD";
+
+/// Name of the profile used when none is selected and no `default_profile` is set.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Address book file read when the profile sets none, next to the config file.
+pub const DEFAULT_LABELS_FILE: &str = "labels.json";
+
+/// The config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Profile used when `--profile` is not given.
+    pub default_profile: Option<String>,
+    /// Profiles by name.
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+/// Defaults for one profile.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Morpho API URL.
+    pub api_url: Option<String>,
+    /// RPC URL per chain.
+    #[serde(default)]
+    pub rpc_urls: HashMap<ChainArg, String>,
+    /// Chain used by commands that act on a single chain.
+    pub chain: Option<ChainArg>,
+    /// Encrypted JSON keystore to sign transactions with.
+    pub keystore: Option<PathBuf>,
+    /// Output format.
+    pub format: Option<OutputFormat>,
+    /// Address book (JSON) naming vaults, curators, and tokens in output.
+    pub labels: Option<PathBuf>,
+}
+
+impl Config {
+    /// Path of the config file, if a home directory is known.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_ENV) {
+            return Some(PathBuf::from(path));
+        }
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_dir.join("morpho").join("config.toml"))
+    }
+
+    /// Path of the default address book, next to the config file.
+    pub fn labels_path() -> Option<PathBuf> {
+        Some(Self::path()?.parent()?.join(DEFAULT_LABELS_FILE))
+    }
+
+    /// Load the config file, or an empty config if there is none.
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load a config file, or an empty config if it does not exist.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let toml = fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        toml::from_str(&toml).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    /// Select a profile by name, falling back to `default_profile`, then to the profile
+    /// named [`DEFAULT_PROFILE`], then to an empty profile.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a profile is named (by `name` or `default_profile`) but not defined.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        match name.or(self.default_profile.as_deref()) {
+            Some(name) => self
+                .profiles
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("profile '{}' is not defined in the config file", name)),
+            None => Ok(self
+                .profiles
+                .get(DEFAULT_PROFILE)
+                .cloned()
+                .unwrap_or_default()),
+        }
+    }
+}
+
+impl Profile {
+    /// The chain from a flag, else the profile's chain, else Ethereum.
+    pub fn chain_or(&self, arg: Option<ChainArg>) -> NamedChain {
+        arg.or(self.chain).map_or(NamedChain::Mainnet, |c| c.0)
+    }
+
+    /// The RPC URL from a flag or `ETH_RPC_URL`, else the profile's URL for `chain`.
+    pub fn rpc_url(&self, arg: Option<&str>, chain: NamedChain) -> Result<String> {
+        arg.or_else(|| self.rpc_urls.get(&ChainArg(chain)).map(String::as_str))
+            .map(str::to_string)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no RPC URL for {}: pass --rpc-url, set ETH_RPC_URL, or add one to the \
+                     profile's rpc_urls",
+                    chain.as_str()
+                )
+            })
+    }
+
+    /// The private key from a flag or `PRIVATE_KEY`, else decrypted from the keystore
+    /// given by flag or profile.
+    ///
+    /// The keystore password is read from `MORPHO_KEYSTORE_PASSWORD`, or prompted for.
+    pub fn private_key(&self, arg: Option<&str>, keystore: Option<&Path>) -> Result<String> {
+        if let Some(key) = arg {
+            return Ok(key.to_string());
+        }
+        let Some(keystore) = keystore
+            .map(Path::to_path_buf)
+            .or_else(|| self.keystore.clone())
+        else {
+            bail!(
+                "no signer: pass --private-key, set PRIVATE_KEY, or configure a keystore \
+                 (--keystore or the profile's keystore)"
+            );
+        };
+        let keystore = expand_home(&keystore);
+
+        let password = match env::var(KEYSTORE_PASSWORD_ENV) {
+            Ok(password) => password,
+            Err(_) => rpassword::prompt_password(format!(
+                "Password for keystore {}: ",
+                keystore.display()
+            ))?,
+        };
+        let signer = PrivateKeySigner::decrypt_keystore(&keystore, password)
+            .with_context(|| format!("failed to decrypt keystore {}", keystore.display()))?;
+        Ok(signer.to_bytes().to_string())
+    }
+
+    /// Path of the profile's address book, else the default next to the config file.
+    pub fn labels_path(&self) -> Option<PathBuf> {
+        self.labels
+            .as_deref()
+            .map(expand_home)
+            .or_else(Config::labels_path)
+    }
+
+    /// Load the profile's address book, or an empty one if the file does not exist.
+    pub fn address_book(&self) -> Result<AddressBook> {
+        match self.labels_path() {
+            Some(path) if path.exists() => AddressBook::from_file(&path)
+                .with_context(|| format!("invalid address book {}", path.display())),
+            _ => Ok(AddressBook::new()),
+        }
+    }
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        default_profile = "main"
+
+        [profiles.main]
+        api_url = "http://api.test"
+        chain = "base"
+        keystore = "/keys/main"
+        format = "json"
+        labels = "/labels/main.json"
+
+        [profiles.main.rpc_urls]
+        ethereum = "http://eth.test"
+        base = "http://base.test"
+
+        [profiles.other]
+        chain = "arb"
+    "#;
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let profile = config.profile(None).unwrap();
+        assert_eq!(profile.api_url.as_deref(), Some("http://api.test"));
+        assert_eq!(profile.chain, Some(ChainArg(NamedChain::Base)));
+        assert_eq!(profile.keystore, Some(PathBuf::from("/keys/main")));
+        assert!(matches!(profile.format, Some(OutputFormat::Json)));
+        assert_eq!(
+            profile.labels_path(),
+            Some(PathBuf::from("/labels/main.json"))
+        );
+        assert_eq!(
+            profile
+                .rpc_urls
+                .get(&ChainArg(NamedChain::Mainnet))
+                .map(String::as_str),
+            Some("http://eth.test")
+        );
+
+        let other = config.profile(Some("other")).unwrap();
+        assert_eq!(other.chain, Some(ChainArg(NamedChain::Arbitrum)));
+        assert!(other.api_url.is_none());
+        assert!(config.profile(Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(toml::from_str::<Config>("[profiles.main]\nchain = \"nowhere\"").is_err());
+        assert!(toml::from_str::<Config>("[profiles.main]\nrpc = \"http://x\"").is_err());
+        assert!(toml::from_str::<Config>("[profiles.main]\nformat = \"xml\"").is_err());
+    }
+
+    #[test]
+    fn test_default_profile() {
+        let config: Config = toml::from_str("[profiles.default]\nchain = \"base\"").unwrap();
+        assert_eq!(
+            config.profile(None).unwrap().chain_or(None),
+            NamedChain::Base
+        );
+
+        let empty = Config::default().profile(None).unwrap();
+        assert_eq!(empty.chain_or(None), NamedChain::Mainnet);
+        assert!(Config::default().profile(Some("main")).is_err());
+    }
+
+    #[test]
+    fn test_flags_override_profile() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let profile = config.profile(None).unwrap();
+
+        assert_eq!(profile.chain_or(None), NamedChain::Base);
+        let optimism = Some(ChainArg(NamedChain::Optimism));
+        assert_eq!(profile.chain_or(optimism), NamedChain::Optimism);
+
+        assert_eq!(
+            profile.rpc_url(None, NamedChain::Base).unwrap(),
+            "http://base.test"
+        );
+        assert_eq!(
+            profile
+                .rpc_url(Some("http://flag.test"), NamedChain::Base)
+                .unwrap(),
+            "http://flag.test"
+        );
+        let err = profile.rpc_url(None, NamedChain::Optimism).unwrap_err();
+        assert!(err.to_string().contains("--rpc-url"));
+
+        assert_eq!(profile.private_key(Some("0xkey"), None).unwrap(), "0xkey");
+        let err = Profile::default().private_key(None, None).unwrap_err();
+        assert!(err.to_string().contains("--private-key"));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let config = Config::load_from(Path::new("/nonexistent/morpho/config.toml")).unwrap();
+        assert!(config.profiles.is_empty());
+
+        let profile = Profile {
+            labels: Some(PathBuf::from("/nonexistent/morpho/labels.json")),
+            ..Profile::default()
+        };
+        assert!(profile.address_book().unwrap().is_empty());
+    }
+}
@@ -2,54 +2,99 @@
 
 mod cli;
 mod commands;
+mod config;
 mod output;
+mod tui;
 
 use anyhow::Result;
 use clap::Parser;
 
-use cli::{Cli, Commands, VaultV1Subcommand, VaultV2Subcommand};
+use cli::{
+    Cli, Commands, LabelsSubcommand, RewardsSubcommand, VaultV1Subcommand, VaultV2Subcommand,
+};
+#[cfg(feature = "sim")]
+use commands::run_v1_lint;
+use config::Config;
 use commands::{
-    run_positions, run_v1_deposit, run_v1_info, run_v1_list, run_v1_withdraw, run_v2_deposit,
+    run_export, run_labels_import, run_labels_list, run_labels_set, run_monitor, run_positions, run_report, run_rewards_claim, run_v1_deposit, run_v1_diff, run_v1_info, run_v1_list, run_v1_withdraw, run_v2_deposit,
     run_v2_info, run_v2_list, run_v2_withdraw,
 };
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
-    let api_url = cli.api_url.as_deref();
+    let profile = Config::load()?.profile(cli.profile.as_deref())?;
+    let api_url = cli.api_url.as_deref().or(profile.api_url.as_deref());
+    let format = cli.output_format().or(profile.format).unwrap_or_default();
+    let labels = profile.address_book()?;
 
     match cli.command {
         Commands::VaultV1 { subcommand } => match subcommand {
             VaultV1Subcommand::List(args) => {
-                run_v1_list(&args, cli.format, api_url).await?;
+                run_v1_list(&args, format, api_url, &labels).await?;
             }
             VaultV1Subcommand::Info(args) => {
-                run_v1_info(&args, cli.format, api_url).await?;
+                run_v1_info(&args, format, api_url, &profile, &labels).await?;
             }
             VaultV1Subcommand::Deposit(args) => {
-                run_v1_deposit(&args).await?;
+                run_v1_deposit(&args, &profile).await?;
             }
             VaultV1Subcommand::Withdraw(args) => {
-                run_v1_withdraw(&args).await?;
+                run_v1_withdraw(&args, &profile).await?;
+            }
+            VaultV1Subcommand::Diff(args) => {
+                run_v1_diff(&args, format, api_url, &profile, &labels).await?;
+            }
+            #[cfg(feature = "sim")]
+            VaultV1Subcommand::Lint(args) => {
+                run_v1_lint(&args, format, api_url, &profile).await?;
             }
         },
         Commands::VaultV2 { subcommand } => match subcommand {
             VaultV2Subcommand::List(args) => {
-                run_v2_list(&args, cli.format, api_url).await?;
+                run_v2_list(&args, format, api_url, &labels).await?;
             }
             VaultV2Subcommand::Info(args) => {
-                run_v2_info(&args, cli.format, api_url).await?;
+                run_v2_info(&args, format, api_url, &profile, &labels).await?;
             }
             VaultV2Subcommand::Deposit(args) => {
-                run_v2_deposit(&args).await?;
+                run_v2_deposit(&args, &profile).await?;
             }
             VaultV2Subcommand::Withdraw(args) => {
-                run_v2_withdraw(&args).await?;
+                run_v2_withdraw(&args, &profile).await?;
             }
         },
         Commands::Positions(args) => {
-            run_positions(&args, cli.format, api_url).await?;
+            run_positions(&args, format, api_url).await?;
+        }
+        Commands::Report(args) => {
+            run_report(&args, format, api_url).await?;
         }
+        Commands::Export(args) => {
+            run_export(&args, api_url).await?;
+        }
+        Commands::Monitor(args) => {
+            run_monitor(&args, api_url).await?;
+        }
+        Commands::Rewards { subcommand } => match subcommand {
+            RewardsSubcommand::Claim(args) => {
+                run_rewards_claim(&args, api_url, &profile).await?;
+            }
+        },
+        Commands::Tui(args) => {
+            tui::run_tui(&args, api_url, &profile).await?;
+        }
+        Commands::Labels { subcommand } => match subcommand {
+            LabelsSubcommand::Import(args) => {
+                run_labels_import(&args, api_url, &profile).await?;
+            }
+            LabelsSubcommand::Set(args) => {
+                run_labels_set(&args, &profile)?;
+            }
+            LabelsSubcommand::List => {
+                run_labels_list(format, &profile)?;
+            }
+        },
     }
 
     Ok(())
@@ -1,7 +1,12 @@
 //! Detailed output formatting for single vault info.
 
-use morpho_rs_api::{VaultV1, VaultV2};
+use alloy_primitives::{Address, U256};
+use morpho_rs_api::{
+    AddressBook, FieldChange, MarketChange, TokenAmount, VaultDiff, VaultV1, VaultV2,
+};
 use colored::Colorize;
+#[cfg(feature = "sim")]
+use morpho_rs_sim::QueueFinding;
 
 fn format_address(addr: &impl std::fmt::Display) -> String {
     format!("{}", addr)
@@ -24,7 +29,7 @@ fn format_fee(fee: f64) -> String {
     format!("{:.2}%", fee * 100.0)
 }
 
-pub fn format_v1_vault_detail(vault: &VaultV1) -> String {
+pub fn format_v1_vault_detail(vault: &VaultV1, labels: &AddressBook) -> String {
     let mut output = String::new();
 
     // Header
@@ -52,13 +57,13 @@ pub fn format_v1_vault_detail(vault: &VaultV1) -> String {
         output.push_str(&format!("{}\n", "State & Metrics".cyan().bold()));
 
         if let Some(curator) = &state.curator {
-            output.push_str(&format!("  Curator:      {}\n", format_address(curator)));
+            output.push_str(&format!("  Curator:      {}\n", labels.display(*curator)));
         }
         if let Some(owner) = &state.owner {
-            output.push_str(&format!("  Owner:        {}\n", format_address(owner)));
+            output.push_str(&format!("  Owner:        {}\n", labels.display(*owner)));
         }
         if let Some(guardian) = &state.guardian {
-            output.push_str(&format!("  Guardian:     {}\n", format_address(guardian)));
+            output.push_str(&format!("  Guardian:     {}\n", labels.display(*guardian)));
         }
 
         output.push_str(&format!("  Fee:          {}\n", format_fee(state.fee)));
@@ -84,11 +89,51 @@ pub fn format_v1_vault_detail(vault: &VaultV1) -> String {
         }
     }
 
+    // Cap headroom
+    if let (Some(markets), Some(total)) = (vault.cap_headroom(), vault.total_headroom()) {
+        if !markets.is_empty() {
+            let decimals = vault.asset.decimals;
+            let symbol = &vault.asset.symbol;
+            let amount = |raw: U256| {
+                if raw == U256::MAX {
+                    "unlimited".to_string()
+                } else {
+                    format!("{} {}", TokenAmount::new(raw, decimals).format(2), symbol)
+                }
+            };
+            let allocations = vault.state.iter().flat_map(|s| &s.allocation);
+
+            output.push_str(&format!("{}\n", "Cap Headroom".cyan().bold()));
+            for (market, alloc) in markets.iter().zip(allocations) {
+                let collateral = alloc.collateral_asset_symbol.as_deref().unwrap_or("N/A");
+                let loan = alloc.loan_asset_symbol.as_deref().unwrap_or("N/A");
+                let queue = if market.in_supply_queue { "" } else { " (not in supply queue)" };
+                output.push_str(&format!(
+                    "  {} / {}{} - {} of {} cap, {} room ({:.2}%)\n",
+                    loan,
+                    collateral,
+                    queue,
+                    amount(market.supply_assets),
+                    amount(market.supply_cap),
+                    amount(market.room),
+                    market.utilization * 100.0
+                ));
+            }
+            output.push_str(&format!(
+                "  Total - {} of {} cap, {} room ({:.2}%)\n\n",
+                amount(total.supply_assets),
+                amount(total.supply_cap),
+                amount(total.room),
+                total.utilization * 100.0
+            ));
+        }
+    }
+
     // Allocators
     if !vault.allocators.is_empty() {
         output.push_str(&format!("{}\n", "Allocators".cyan().bold()));
         for allocator in &vault.allocators {
-            output.push_str(&format!("  {}\n", format_address(&allocator.address)));
+            output.push_str(&format!("  {}\n", labels.display(allocator.address)));
         }
         output.push('\n');
     }
@@ -99,7 +144,7 @@ pub fn format_v1_vault_detail(vault: &VaultV1) -> String {
         for warning in &vault.warnings {
             output.push_str(&format!(
                 "  [{}] {}\n",
-                warning.level.to_uppercase(),
+                warning.level,
                 warning.warning_type
             ));
         }
@@ -108,7 +153,29 @@ pub fn format_v1_vault_detail(vault: &VaultV1) -> String {
     output
 }
 
-pub fn format_v2_vault_detail(vault: &VaultV2) -> String {
+#[cfg(feature = "sim")]
+pub fn format_v1_queue_findings(vault: &VaultV1, findings: &[QueueFinding]) -> String {
+    let mut output = String::new();
+
+    // Header
+    output.push_str(&format!("{}\n", "=".repeat(60)));
+    output.push_str(&format!("{}\n", vault.name.bold()));
+    output.push_str(&format!("{}\n\n", "=".repeat(60)));
+
+    if findings.is_empty() {
+        output.push_str("No queue findings\n");
+        return output;
+    }
+
+    output.push_str(&format!("{}\n", "Queue Findings".yellow().bold()));
+    for finding in findings {
+        output.push_str(&format!("  {}\n", finding));
+    }
+
+    output
+}
+
+pub fn format_v2_vault_detail(vault: &VaultV2, labels: &AddressBook) -> String {
     let mut output = String::new();
 
     // Header
@@ -134,10 +201,10 @@ pub fn format_v2_vault_detail(vault: &VaultV2) -> String {
     output.push_str(&format!("{}\n", "State & Metrics".cyan().bold()));
 
     if let Some(curator) = &vault.curator {
-        output.push_str(&format!("  Curator:         {}\n", format_address(curator)));
+        output.push_str(&format!("  Curator:         {}\n", labels.display(*curator)));
     }
     if let Some(owner) = &vault.owner {
-        output.push_str(&format!("  Owner:           {}\n", format_address(owner)));
+        output.push_str(&format!("  Owner:           {}\n", labels.display(*owner)));
     }
 
     if let Some(perf_fee) = vault.performance_fee {
@@ -200,7 +267,7 @@ pub fn format_v2_vault_detail(vault: &VaultV2) -> String {
         for warning in &vault.warnings {
             output.push_str(&format!(
                 "  [{}] {}\n",
-                warning.level.to_uppercase(),
+                warning.level,
                 warning.warning_type
             ));
         }
@@ -208,3 +275,110 @@ pub fn format_v2_vault_detail(vault: &VaultV2) -> String {
 
     output
 }
+
+fn format_optional_address(addr: &Option<Address>, labels: &AddressBook) -> String {
+    addr.map(|a| labels.display(a))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_market_value(value: Option<impl std::fmt::Display>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn push_field_change<T>(
+    output: &mut String,
+    label: &str,
+    change: &Option<FieldChange<T>>,
+    format: impl Fn(&T) -> String,
+) {
+    if let Some(change) = change {
+        output.push_str(&format!(
+            "  {:<14}{} -> {}\n",
+            format!("{}:", label),
+            format(&change.before),
+            format(&change.after)
+        ));
+    }
+}
+
+fn push_market_changes(output: &mut String, title: &str, changes: &[MarketChange]) {
+    if changes.is_empty() {
+        return;
+    }
+    output.push_str(&format!("{}\n", title.cyan().bold()));
+    for change in changes {
+        output.push_str(&format!(
+            "  {}: {} -> {}\n",
+            change.market_key,
+            format_market_value(change.before),
+            format_market_value(change.after)
+        ));
+    }
+    output.push('\n');
+}
+
+pub fn format_v1_vault_diff(
+    vault: &VaultV1,
+    since: u64,
+    diff: &VaultDiff,
+    labels: &AddressBook,
+) -> String {
+    let mut output = String::new();
+
+    // Header
+    output.push_str(&format!("{}\n", "=".repeat(60)));
+    output.push_str(&format!("{}\n", vault.name.bold()));
+    output.push_str(&format!("Changes since {}\n", since));
+    output.push_str(&format!("{}\n\n", "=".repeat(60)));
+
+    if diff.is_empty() {
+        output.push_str("No changes\n");
+        return output;
+    }
+
+    // Configuration
+    if diff.fee.is_some()
+        || diff.timelock.is_some()
+        || diff.curator.is_some()
+        || diff.owner.is_some()
+        || diff.guardian.is_some()
+    {
+        output.push_str(&format!("{}\n", "Configuration".cyan().bold()));
+        push_field_change(&mut output, "Fee", &diff.fee, |f| format_fee(*f));
+        push_field_change(&mut output, "Timelock", &diff.timelock, |t| {
+            format!("{}s", t)
+        });
+        let address = |a: &Option<Address>| format_optional_address(a, labels);
+        push_field_change(&mut output, "Curator", &diff.curator, address);
+        push_field_change(&mut output, "Owner", &diff.owner, address);
+        push_field_change(&mut output, "Guardian", &diff.guardian, address);
+        output.push('\n');
+    }
+
+    push_market_changes(&mut output, "Supply Caps", &diff.caps);
+    push_market_changes(&mut output, "Allocations", &diff.allocations);
+
+    // Queues
+    if diff.supply_queue.is_some() || diff.withdraw_queue.is_some() {
+        output.push_str(&format!("{}\n", "Queues".cyan().bold()));
+        let queue = |q: &Vec<String>| format!("[{}]", q.join(", "));
+        push_field_change(&mut output, "Supply", &diff.supply_queue, queue);
+        push_field_change(&mut output, "Withdraw", &diff.withdraw_queue, queue);
+        output.push('\n');
+    }
+
+    // Warnings
+    if !diff.warnings_added.is_empty() || !diff.warnings_removed.is_empty() {
+        output.push_str(&format!("{}\n", "Warnings".yellow().bold()));
+        for warning in &diff.warnings_added {
+            output.push_str(&format!("  + {}\n", warning));
+        }
+        for warning in &diff.warnings_removed {
+            output.push_str(&format!("  - {}\n", warning));
+        }
+    }
+
+    output
+}
@@ -0,0 +1,67 @@
+//! Table formatting for the address book.
+
+use morpho_rs_api::{AddressBook, LabelKind};
+use tabled::{
+    settings::{object::Rows, Alignment, Modify, Style},
+    Table, Tabled,
+};
+
+#[derive(Tabled)]
+struct LabelRow {
+    #[tabled(rename = "Address")]
+    address: String,
+    #[tabled(rename = "Kind")]
+    kind: &'static str,
+    #[tabled(rename = "Name")]
+    name: String,
+}
+
+fn format_kind(kind: LabelKind) -> &'static str {
+    match kind {
+        LabelKind::Vault => "Vault",
+        LabelKind::Curator => "Curator",
+        LabelKind::Token => "Token",
+        LabelKind::Other => "Other",
+    }
+}
+
+pub fn format_labels_table(book: &AddressBook) -> String {
+    if book.is_empty() {
+        return "No labels found.".to_string();
+    }
+
+    let rows: Vec<LabelRow> = book
+        .labels
+        .iter()
+        .map(|(address, label)| LabelRow {
+            address: address.to_string(),
+            kind: format_kind(label.kind),
+            name: label.name.clone(),
+        })
+        .collect();
+
+    let mut table = Table::new(rows);
+    table
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Address;
+
+    #[test]
+    fn test_format_labels_table() {
+        let mut book = AddressBook::new();
+        assert_eq!(format_labels_table(&book), "No labels found.");
+
+        book.insert(Address::repeat_byte(0x11), LabelKind::Curator, "Steakhouse");
+        let table = format_labels_table(&book);
+        assert!(table.contains("Curator"));
+        assert!(table.contains("Steakhouse"));
+        assert!(table.contains(&Address::repeat_byte(0x11).to_string()));
+    }
+}
@@ -1,9 +1,13 @@
 //! Output formatting for CLI results.
 
 pub mod detail;
+pub mod labels;
 pub mod positions;
 pub mod table;
 
-pub use detail::{format_v1_vault_detail, format_v2_vault_detail};
-pub use positions::format_user_positions;
+#[cfg(feature = "sim")]
+pub use detail::format_v1_queue_findings;
+pub use detail::{format_v1_vault_detail, format_v1_vault_diff, format_v2_vault_detail};
+pub use labels::format_labels_table;
+pub use positions::{format_position_deltas, format_user_positions, WatchedPosition};
 pub use table::{format_v1_vaults_table, format_v2_vaults_table};
@@ -1,5 +1,8 @@
 //! Output formatting for user vault positions.
 
+use std::collections::HashMap;
+
+use colored::Colorize;
 use morpho_rs_api::UserVaultPositions;
 use tabled::{
     settings::{object::Rows, Alignment, Modify, Style},
@@ -102,10 +105,243 @@ pub fn format_user_positions(positions: &UserVaultPositions) -> String {
     )
 }
 
+/// Health factor below which a position is shown as at risk in watch mode.
+const LOW_HEALTH_FACTOR: f64 = 1.1;
+
+/// One position as seen by a `positions --watch` query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedPosition {
+    /// Position ID from the API, stable across queries.
+    pub id: String,
+    /// "V1", "V2", or "Market".
+    pub kind: &'static str,
+    /// Chain name.
+    pub chain: String,
+    /// Vault name, or collateral/loan symbols for markets.
+    pub name: String,
+    /// Position value in USD (net of borrows for markets).
+    pub value_usd: Option<f64>,
+    /// Profit and loss in USD.
+    pub pnl_usd: Option<f64>,
+    /// Net APY of the vault (as a fraction).
+    pub net_apy: Option<f64>,
+    /// Health factor of a market position with a borrow.
+    pub health_factor: Option<f64>,
+}
+
+#[derive(Tabled)]
+struct WatchRow {
+    #[tabled(rename = "Type")]
+    kind: String,
+    #[tabled(rename = "Chain")]
+    chain: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Value (USD)")]
+    value_usd: String,
+    #[tabled(rename = "PnL (USD)")]
+    pnl_usd: String,
+    #[tabled(rename = "PnL Change")]
+    pnl_change: String,
+    #[tabled(rename = "Net APY")]
+    net_apy: String,
+    #[tabled(rename = "APY Change")]
+    apy_change: String,
+    #[tabled(rename = "Health")]
+    health_factor: String,
+}
+
+fn format_usd_change(value: Option<f64>) -> String {
+    match value {
+        Some(v) if v >= 0.0 => format!("+{}", format_usd(Some(v))),
+        v => format_usd(v),
+    }
+}
+
+fn format_apy(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.2}%", v * 100.0))
+}
+
+fn format_apy_change(value: Option<f64>) -> String {
+    match value {
+        Some(v) if v.abs() >= 0.00005 => format!("{:+.2}%", v * 100.0),
+        _ => String::new(),
+    }
+}
+
+fn format_elapsed(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3_599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3_600, secs % 3_600 / 60),
+    }
+}
+
+fn change(current: Option<f64>, before: Option<f64>) -> Option<f64> {
+    Some(current? - before?)
+}
+
+/// Format one `positions --watch` update.
+///
+/// PnL and health factor changes are relative to the first query (`start`), APY changes
+/// to the previous one. Health factor drift is listed below the table in color: red when
+/// a position is close to liquidation, yellow when its health factor fell, green when it
+/// rose.
+pub fn format_position_deltas(
+    user: &str,
+    start: &[WatchedPosition],
+    previous: &[WatchedPosition],
+    current: &[WatchedPosition],
+    elapsed_secs: u64,
+) -> String {
+    let start: HashMap<&str, &WatchedPosition> = start.iter().map(|p| (p.id.as_str(), p)).collect();
+    let previous: HashMap<&str, &WatchedPosition> =
+        previous.iter().map(|p| (p.id.as_str(), p)).collect();
+
+    let mut output = format!(
+        "User: {} ({} since start)\n\n",
+        truncate_address(user),
+        format_elapsed(elapsed_secs)
+    );
+    if current.is_empty() {
+        output.push_str("No positions found.\n");
+        return output;
+    }
+
+    let rows: Vec<WatchRow> = current
+        .iter()
+        .map(|p| {
+            let started = start.get(p.id.as_str());
+            let prior = previous.get(p.id.as_str());
+            WatchRow {
+                kind: p.kind.to_string(),
+                chain: p.chain.clone(),
+                name: truncate_name(&p.name, 25),
+                value_usd: format_usd(p.value_usd),
+                pnl_usd: format_usd(p.pnl_usd),
+                pnl_change: format_usd_change(change(p.pnl_usd, started.and_then(|s| s.pnl_usd))),
+                net_apy: format_apy(p.net_apy),
+                apy_change: format_apy_change(change(p.net_apy, prior.and_then(|s| s.net_apy))),
+                health_factor: p
+                    .health_factor
+                    .map_or("-".to_string(), |hf| format!("{:.3}", hf)),
+            }
+        })
+        .collect();
+    let mut table = Table::new(rows);
+    table
+        .with(Style::rounded())
+        .with(Modify::new(Rows::new(1..)).with(Alignment::left()));
+    output.push_str(&format!("{}\n", table));
+
+    let pnl_change: f64 = current
+        .iter()
+        .filter_map(|p| change(p.pnl_usd, start.get(p.id.as_str())?.pnl_usd))
+        .sum();
+    let pnl_line = format!(
+        "PnL change since start: {}",
+        format_usd_change(Some(pnl_change))
+    );
+    let pnl_line = if pnl_change < 0.0 {
+        pnl_line.red()
+    } else {
+        pnl_line.green()
+    };
+    output.push_str(&format!("\n{}\n", pnl_line));
+
+    let drifts: Vec<String> = current
+        .iter()
+        .filter_map(|p| {
+            let hf = p.health_factor?;
+            let initial = start.get(p.id.as_str())?.health_factor?;
+            let line = format!(
+                "  {} ({}): {:.3} -> {:.3} ({:+.3})",
+                p.name,
+                p.chain,
+                initial,
+                hf,
+                hf - initial
+            );
+            if hf < LOW_HEALTH_FACTOR {
+                Some(line.red().bold().to_string())
+            } else if hf < initial {
+                Some(line.yellow().to_string())
+            } else if hf > initial {
+                Some(line.green().to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    if !drifts.is_empty() {
+        output.push_str(&format!("\n{}\n", "Health factor drift".bold()));
+        for drift in drifts {
+            output.push_str(&format!("{}\n", drift));
+        }
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn watched(
+        id: &str,
+        pnl_usd: Option<f64>,
+        net_apy: Option<f64>,
+        hf: Option<f64>,
+    ) -> WatchedPosition {
+        WatchedPosition {
+            id: id.to_string(),
+            kind: "V1",
+            chain: "mainnet".to_string(),
+            name: format!("Position {}", id),
+            value_usd: Some(1_000.0),
+            pnl_usd,
+            net_apy,
+            health_factor: hf,
+        }
+    }
+
+    #[test]
+    fn test_format_position_deltas() {
+        colored::control::set_override(false);
+        let start = [
+            watched("a", Some(10.0), Some(0.05), None),
+            watched("b", None, None, Some(1.5)),
+        ];
+        let previous = [watched("a", Some(12.0), Some(0.05), None)];
+        let current = [
+            watched("a", Some(15.0), Some(0.0525), None),
+            watched("b", None, None, Some(1.05)),
+            // Opened after the first query
+            watched("c", Some(1.0), Some(0.04), None),
+        ];
+
+        let output = format_position_deltas("0xuser", &start, &previous, &current, 125);
+        assert!(output.contains("2m 05s since start"));
+        assert!(output.contains("+$5.00"));
+        assert!(output.contains("+0.25%"));
+        assert!(output.contains("PnL change since start: +$5.00"));
+        assert!(output.contains("Health factor drift"));
+        assert!(output.contains("Position b (mainnet): 1.500 -> 1.050 (-0.450)"));
+    }
+
+    #[test]
+    fn test_format_position_deltas_empty() {
+        let output = format_position_deltas("0xuser", &[], &[], &[], 0);
+        assert!(output.contains("No positions found."));
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(45), "45s");
+        assert_eq!(format_elapsed(125), "2m 05s");
+        assert_eq!(format_elapsed(7_500), "2h 05m");
+    }
+
     // truncate_address tests (same function as table.rs but tested here for coverage)
     #[test]
     fn test_truncate_address_long() {
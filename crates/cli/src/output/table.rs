@@ -1,6 +1,7 @@
 //! Table formatting for vault lists.
 
-use morpho_rs_api::{VaultV1, VaultV2};
+use alloy_primitives::Address;
+use morpho_rs_api::{AddressBook, VaultV1, VaultV2};
 use tabled::{
     settings::{object::Rows, Alignment, Modify, Style},
     Table, Tabled,
@@ -67,7 +68,18 @@ fn format_usd(value: Option<f64>) -> String {
     }
 }
 
-pub fn format_v1_vaults_table(vaults: &[VaultV1]) -> String {
+/// Curator column: the curator's label if it has one, else the truncated address.
+fn format_curator(curator: Option<&Address>, labels: &AddressBook) -> String {
+    match curator {
+        Some(c) => labels
+            .name(*c)
+            .map(|name| truncate_name(name, 20))
+            .unwrap_or_else(|| truncate_address(&format!("{}", c))),
+        None => "-".to_string(),
+    }
+}
+
+pub fn format_v1_vaults_table(vaults: &[VaultV1], labels: &AddressBook) -> String {
     if vaults.is_empty() {
         return "No vaults found.".to_string();
     }
@@ -75,12 +87,7 @@ pub fn format_v1_vaults_table(vaults: &[VaultV1]) -> String {
     let rows: Vec<VaultV1Row> = vaults
         .iter()
         .map(|v| {
-            let curator = v
-                .state
-                .as_ref()
-                .and_then(|s| s.curator.as_ref())
-                .map(|c| truncate_address(&format!("{}", c)))
-                .unwrap_or_else(|| "-".to_string());
+            let curator = format_curator(v.state.as_ref().and_then(|s| s.curator.as_ref()), labels);
 
             let apy = v
                 .state
@@ -109,7 +116,7 @@ pub fn format_v1_vaults_table(vaults: &[VaultV1]) -> String {
     table.to_string()
 }
 
-pub fn format_v2_vaults_table(vaults: &[VaultV2]) -> String {
+pub fn format_v2_vaults_table(vaults: &[VaultV2], labels: &AddressBook) -> String {
     if vaults.is_empty() {
         return "No vaults found.".to_string();
     }
@@ -117,11 +124,7 @@ pub fn format_v2_vaults_table(vaults: &[VaultV2]) -> String {
     let rows: Vec<VaultV2Row> = vaults
         .iter()
         .map(|v| {
-            let curator = v
-                .curator
-                .as_ref()
-                .map(|c| truncate_address(&format!("{}", c)))
-                .unwrap_or_else(|| "-".to_string());
+            let curator = format_curator(v.curator.as_ref(), labels);
 
             let apy = v
                 .net_apy
@@ -150,6 +153,7 @@ pub fn format_v2_vaults_table(vaults: &[VaultV2]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use morpho_rs_api::LabelKind;
 
     // truncate_address tests
     #[test]
@@ -259,4 +263,18 @@ mod tests {
     fn test_format_usd_zero() {
         assert_eq!(format_usd(Some(0.0)), "$0.00");
     }
+
+    // format_curator tests
+    #[test]
+    fn test_format_curator_labeled() {
+        let curator = Address::repeat_byte(0x11);
+        let mut labels = AddressBook::new();
+        assert_eq!(format_curator(Some(&curator), &labels), "0x1111...1111");
+        labels.insert(curator, LabelKind::Curator, "Steakhouse Financial");
+        assert_eq!(
+            format_curator(Some(&curator), &labels),
+            "Steakhouse Financial"
+        );
+        assert_eq!(format_curator(None, &labels), "-");
+    }
 }
@@ -0,0 +1,471 @@
+//! Dashboard state and key handling.
+
+use std::collections::{BTreeMap, HashMap};
+
+use alloy_primitives::Address;
+use morpho_rs_api::{Alert, Vault, VaultVersion};
+use ratatui::crossterm::event::KeyCode;
+
+/// Most recent alerts kept on screen.
+const MAX_ALERTS: usize = 50;
+
+/// A vault as shown in the dashboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultRow {
+    pub address: Address,
+    pub version: VaultVersion,
+    pub name: String,
+    pub asset_symbol: String,
+    pub asset_decimals: u8,
+    pub net_apy: f64,
+    pub total_assets_usd: Option<f64>,
+    pub curator: Option<Address>,
+    pub listed: bool,
+    pub critical_warnings: bool,
+}
+
+impl VaultRow {
+    /// Build a row from any vault version.
+    pub fn from_vault(vault: &dyn Vault) -> Self {
+        Self {
+            address: vault.address(),
+            version: vault.version(),
+            name: vault.name().to_string(),
+            asset_symbol: vault.asset().symbol.clone(),
+            asset_decimals: vault.asset().decimals,
+            net_apy: vault.net_apy(),
+            total_assets_usd: vault.total_assets_usd(),
+            curator: vault.curator(),
+            listed: vault.listed(),
+            critical_warnings: vault.has_critical_warnings(),
+        }
+    }
+}
+
+/// A user's vault position as shown in the dashboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionRow {
+    pub vault: Address,
+    pub name: String,
+    pub assets_usd: Option<f64>,
+    pub pnl_usd: Option<f64>,
+}
+
+/// Panel with keyboard focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Vaults,
+    Positions,
+}
+
+/// Vault transaction started from the dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Deposit,
+    Withdraw,
+}
+
+impl Action {
+    pub fn verb(&self) -> &'static str {
+        match self {
+            Action::Deposit => "Deposit",
+            Action::Withdraw => "Withdraw",
+        }
+    }
+}
+
+/// What the dashboard is showing on top of the tables.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    Browse,
+    Detail(VaultRow),
+    EnterAmount {
+        action: Action,
+        vault: VaultRow,
+        input: String,
+    },
+    Confirm(PendingTransaction),
+}
+
+/// A transaction the user has typed an amount for and confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTransaction {
+    pub action: Action,
+    pub vault: VaultRow,
+    /// Amount in whole tokens, as typed.
+    pub amount: String,
+}
+
+/// Work the event loop must do after a key press.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppCommand {
+    Quit,
+    Refresh,
+    Submit(PendingTransaction),
+}
+
+/// Dashboard state.
+#[derive(Debug)]
+pub struct App {
+    /// Best vaults per asset, grouped by asset.
+    pub vaults: Vec<VaultRow>,
+    /// Every vault fetched on the chain, by address.
+    pub all_vaults: HashMap<Address, VaultRow>,
+    pub positions: Vec<PositionRow>,
+    /// Formatted alerts, newest first.
+    pub alerts: Vec<String>,
+    pub pane: Pane,
+    pub selected_vault: usize,
+    pub selected_position: usize,
+    pub mode: Mode,
+    pub status: String,
+    /// Whether a signer is configured for deposits and withdrawals.
+    pub can_transact: bool,
+    per_asset: usize,
+}
+
+impl App {
+    pub fn new(per_asset: usize, can_transact: bool) -> Self {
+        Self {
+            vaults: Vec::new(),
+            all_vaults: HashMap::new(),
+            positions: Vec::new(),
+            alerts: Vec::new(),
+            pane: Pane::Vaults,
+            selected_vault: 0,
+            selected_position: 0,
+            mode: Mode::Browse,
+            status: String::new(),
+            can_transact,
+            per_asset,
+        }
+    }
+
+    /// Replace the vault list, keeping the `per_asset` highest net APY listed vaults
+    /// without critical warnings for each asset.
+    pub fn set_vaults(&mut self, vaults: Vec<VaultRow>) {
+        let mut by_asset: BTreeMap<String, Vec<VaultRow>> = BTreeMap::new();
+        for vault in vaults.iter().filter(|v| v.listed && !v.critical_warnings) {
+            by_asset
+                .entry(vault.asset_symbol.clone())
+                .or_default()
+                .push(vault.clone());
+        }
+        self.vaults = by_asset
+            .into_values()
+            .flat_map(|mut group| {
+                group.sort_by(|a, b| b.net_apy.total_cmp(&a.net_apy));
+                group.truncate(self.per_asset);
+                group
+            })
+            .collect();
+        self.all_vaults = vaults.into_iter().map(|v| (v.address, v)).collect();
+        self.selected_vault = self.selected_vault.min(self.vaults.len().saturating_sub(1));
+    }
+
+    /// Replace the user's positions.
+    pub fn set_positions(&mut self, positions: Vec<PositionRow>) {
+        self.positions = positions;
+        self.selected_position = self
+            .selected_position
+            .min(self.positions.len().saturating_sub(1));
+    }
+
+    /// Add alerts, newest first.
+    pub fn push_alerts(&mut self, alerts: &[Alert]) {
+        for alert in alerts {
+            let line = self.format_alert(alert);
+            self.alerts.insert(0, line);
+        }
+        self.alerts.truncate(MAX_ALERTS);
+    }
+
+    fn format_alert(&self, alert: &Alert) -> String {
+        let name = self
+            .all_vaults
+            .get(&alert.vault())
+            .map_or_else(|| alert.vault().to_string(), |v| v.name.clone());
+        match alert {
+            Alert::ApyDrop {
+                previous, current, ..
+            } => format!(
+                "APY drop: {} {:.2}% -> {:.2}%",
+                name,
+                previous * 100.0,
+                current * 100.0
+            ),
+            Alert::TvlOutflow { outflow_pct, .. } => {
+                format!("TVL outflow: {} -{:.1}%", name, outflow_pct * 100.0)
+            }
+            Alert::NewRedWarning { warning_type, .. } => {
+                format!("Red warning: {} {}", name, warning_type)
+            }
+        }
+    }
+
+    /// The vault under the cursor in the focused pane.
+    pub fn selected(&self) -> Option<&VaultRow> {
+        match self.pane {
+            Pane::Vaults => self.vaults.get(self.selected_vault),
+            Pane::Positions => self
+                .positions
+                .get(self.selected_position)
+                .and_then(|p| self.all_vaults.get(&p.vault)),
+        }
+    }
+
+    /// Handle a key press, returning work for the event loop.
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<AppCommand> {
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => self.handle_browse_key(key),
+            Mode::Detail(vault) => match key {
+                KeyCode::Esc | KeyCode::Backspace | KeyCode::Enter => None,
+                KeyCode::Char('q') => Some(AppCommand::Quit),
+                KeyCode::Char('d') => self.start(Action::Deposit, vault),
+                KeyCode::Char('w') => self.start(Action::Withdraw, vault),
+                _ => {
+                    self.mode = Mode::Detail(vault);
+                    None
+                }
+            },
+            Mode::EnterAmount {
+                action,
+                vault,
+                mut input,
+            } => {
+                match key {
+                    KeyCode::Esc => {
+                        self.status = format!("{} cancelled", action.verb());
+                        return None;
+                    }
+                    KeyCode::Enter if !input.is_empty() => {
+                        self.mode = Mode::Confirm(PendingTransaction {
+                            action,
+                            vault,
+                            amount: input,
+                        });
+                        return None;
+                    }
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Char(c) if c.is_ascii_digit() || c == '.' || c == ',' => {
+                        input.push(c);
+                    }
+                    _ => {}
+                }
+                self.mode = Mode::EnterAmount {
+                    action,
+                    vault,
+                    input,
+                };
+                None
+            }
+            Mode::Confirm(pending) => match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.status = format!(
+                        "{} {} {} submitted...",
+                        pending.action.verb(),
+                        pending.amount,
+                        pending.vault.asset_symbol
+                    );
+                    Some(AppCommand::Submit(pending))
+                }
+                _ => {
+                    self.status = format!("{} cancelled", pending.action.verb());
+                    None
+                }
+            },
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyCode) -> Option<AppCommand> {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(AppCommand::Quit),
+            KeyCode::Char('r') => return Some(AppCommand::Refresh),
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.pane = match self.pane {
+                    Pane::Vaults => Pane::Positions,
+                    Pane::Positions => Pane::Vaults,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Enter => {
+                if let Some(vault) = self.selected().cloned() {
+                    self.mode = Mode::Detail(vault);
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(vault) = self.selected().cloned() {
+                    return self.start(Action::Deposit, vault);
+                }
+            }
+            KeyCode::Char('w') => {
+                if let Some(vault) = self.selected().cloned() {
+                    return self.start(Action::Withdraw, vault);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn start(&mut self, action: Action, vault: VaultRow) -> Option<AppCommand> {
+        if self.can_transact {
+            self.mode = Mode::EnterAmount {
+                action,
+                vault,
+                input: String::new(),
+            };
+        } else {
+            self.status = "No signer configured: pass --private-key or --keystore".to_string();
+        }
+        None
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (selected, len) = match self.pane {
+            Pane::Vaults => (&mut self.selected_vault, self.vaults.len()),
+            Pane::Positions => (&mut self.selected_position, self.positions.len()),
+        };
+        if len > 0 {
+            *selected = selected.saturating_add_signed(delta).min(len - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(byte: u8, asset: &str, net_apy: f64) -> VaultRow {
+        VaultRow {
+            address: Address::repeat_byte(byte),
+            version: VaultVersion::V1,
+            name: format!("Vault {}", byte),
+            asset_symbol: asset.to_string(),
+            asset_decimals: 6,
+            net_apy,
+            total_assets_usd: None,
+            curator: None,
+            listed: true,
+            critical_warnings: false,
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new(2, true);
+        let mut unlisted = vault(5, "USDC", 0.5);
+        unlisted.listed = false;
+        app.set_vaults(vec![
+            vault(1, "USDC", 0.04),
+            vault(2, "WETH", 0.02),
+            vault(3, "USDC", 0.06),
+            vault(4, "USDC", 0.05),
+            unlisted,
+        ]);
+        app
+    }
+
+    #[test]
+    fn test_best_vaults_per_asset() {
+        let app = app();
+        let addresses: Vec<u8> = app.vaults.iter().map(|v| v.address.0[0]).collect();
+        assert_eq!(addresses, vec![3, 4, 2]);
+        assert_eq!(app.all_vaults.len(), 5);
+    }
+
+    #[test]
+    fn test_navigation_and_detail() {
+        let mut app = app();
+        assert_eq!(app.handle_key(KeyCode::Down), None);
+        assert_eq!(app.handle_key(KeyCode::Down), None);
+        assert_eq!(app.handle_key(KeyCode::Down), None);
+        assert_eq!(app.selected_vault, 2);
+        app.handle_key(KeyCode::Up);
+        assert_eq!(app.selected_vault, 1);
+
+        app.handle_key(KeyCode::Enter);
+        assert!(matches!(&app.mode, Mode::Detail(v) if v.address == Address::repeat_byte(4)));
+        app.handle_key(KeyCode::Char('x'));
+        assert!(matches!(app.mode, Mode::Detail(_)));
+        app.handle_key(KeyCode::Esc);
+        assert_eq!(app.mode, Mode::Browse);
+
+        assert_eq!(
+            app.handle_key(KeyCode::Char('r')),
+            Some(AppCommand::Refresh)
+        );
+        assert_eq!(app.handle_key(KeyCode::Char('q')), Some(AppCommand::Quit));
+    }
+
+    #[test]
+    fn test_deposit_flow() {
+        let mut app = app();
+        app.handle_key(KeyCode::Char('d'));
+        for c in "1x0.5".chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Backspace);
+        assert!(matches!(&app.mode, Mode::EnterAmount { input, .. } if input == "10."));
+
+        app.handle_key(KeyCode::Char('5'));
+        app.handle_key(KeyCode::Enter);
+        assert!(matches!(app.mode, Mode::Confirm(_)));
+
+        let command = app.handle_key(KeyCode::Char('y'));
+        let Some(AppCommand::Submit(pending)) = command else {
+            panic!("expected a submitted transaction, got {:?}", command);
+        };
+        assert_eq!(pending.action, Action::Deposit);
+        assert_eq!(pending.amount, "10.5");
+        assert_eq!(pending.vault.address, Address::repeat_byte(3));
+        assert_eq!(app.mode, Mode::Browse);
+    }
+
+    #[test]
+    fn test_cancel_and_read_only() {
+        let mut app = app();
+        app.handle_key(KeyCode::Char('w'));
+        app.handle_key(KeyCode::Enter); // empty amount is ignored
+        assert!(matches!(app.mode, Mode::EnterAmount { .. }));
+        app.handle_key(KeyCode::Char('1'));
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.handle_key(KeyCode::Char('n')), None);
+        assert_eq!(app.mode, Mode::Browse);
+        assert_eq!(app.status, "Withdraw cancelled");
+
+        let mut app = App::new(2, false);
+        app.set_vaults(vec![vault(1, "USDC", 0.04)]);
+        app.handle_key(KeyCode::Char('d'));
+        assert_eq!(app.mode, Mode::Browse);
+        assert!(app.status.contains("No signer"));
+    }
+
+    #[test]
+    fn test_positions_pane_selects_position_vault() {
+        let mut app = app();
+        app.set_positions(vec![PositionRow {
+            vault: Address::repeat_byte(1),
+            name: "Vault 1".to_string(),
+            assets_usd: Some(100.0),
+            pnl_usd: None,
+        }]);
+        app.handle_key(KeyCode::Tab);
+        assert_eq!(app.pane, Pane::Positions);
+        assert_eq!(app.selected().unwrap().address, Address::repeat_byte(1));
+    }
+
+    #[test]
+    fn test_alerts_are_named() {
+        let mut app = app();
+        app.push_alerts(&[Alert::ApyDrop {
+            vault: Address::repeat_byte(3),
+            chain: alloy_chains::NamedChain::Mainnet,
+            previous: 0.06,
+            current: 0.04,
+        }]);
+        assert_eq!(app.alerts, vec!["APY drop: Vault 3 6.00% -> 4.00%"]);
+    }
+}
@@ -0,0 +1,269 @@
+//! Interactive dashboard (`morpho tui`).
+//!
+//! Shows the best whitelisted vaults per asset on one chain, the user's positions there,
+//! and alerts from a [`VaultMonitor`] fed on every refresh. Vaults can be inspected and,
+//! with a signer configured, deposited into or withdrawn from after typing and
+//! confirming an amount.
+
+mod app;
+mod ui;
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use alloy_chains::NamedChain;
+use alloy_primitives::Address;
+use anyhow::{Context, Result};
+use morpho_rs_api::monitor::VaultObservation;
+use morpho_rs_api::{
+    ApiError, ClientConfig, MonitorConfig, MorphoClient, MorphoClientConfig, TokenAmount,
+    UserVaultPositions, VaultMonitor, VaultVersion, WatchRule,
+};
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+use crate::cli::TuiArgs;
+use crate::config::Profile;
+
+use app::{Action, App, AppCommand, PendingTransaction, PositionRow, VaultRow};
+
+/// How long to wait for a key press before checking whether a refresh is due.
+const INPUT_POLL: Duration = Duration::from_millis(250);
+
+/// Net APY drop (as a fraction) between refreshes that raises an alert for a position.
+const APY_DROP_THRESHOLD: f64 = 0.01;
+
+/// TVL outflow (as a fraction of the peak) within [`TVL_OUTFLOW_WINDOW_SECS`] that
+/// raises an alert.
+const TVL_OUTFLOW_PCT: f64 = 0.1;
+
+/// Look-back window for TVL outflow alerts (one hour).
+const TVL_OUTFLOW_WINDOW_SECS: u64 = 3_600;
+
+/// Run the dashboard until the user quits.
+pub async fn run_tui(args: &TuiArgs, api_url: Option<&str>, profile: &Profile) -> Result<()> {
+    let chain = profile.chain_or(args.chain);
+
+    let mut config = MorphoClientConfig::new();
+    if let Some(url) = api_url {
+        config = config.with_api_config(ClientConfig::new().with_api_url(url.parse()?));
+    }
+    // Decrypt the keystore (which may prompt) before the terminal enters raw mode.
+    let has_signer =
+        args.private_key.is_some() || args.keystore.is_some() || profile.keystore.is_some();
+    if has_signer {
+        let private_key =
+            profile.private_key(args.private_key.as_deref(), args.keystore.as_deref())?;
+        config = config
+            .with_rpc_url(profile.rpc_url(args.rpc_url.as_deref(), chain)?)
+            .with_private_key(private_key);
+    }
+    let client = MorphoClient::with_config(config)?;
+
+    let user = match &args.user {
+        Some(user) => Some(user.parse::<Address>().context("Invalid user address")?),
+        None => client.signer_address(),
+    };
+
+    let mut dashboard = Dashboard {
+        client,
+        chain,
+        user,
+        app: App::new(args.per_asset, has_signer),
+        monitor: None,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = dashboard.run(&mut terminal, args.refresh.0).await;
+    ratatui::restore();
+    result
+}
+
+/// Dashboard state plus what it needs to refresh and transact.
+struct Dashboard {
+    client: MorphoClient,
+    chain: NamedChain,
+    user: Option<Address>,
+    app: App,
+    /// Created on the first refresh, once the user's position vaults are known.
+    monitor: Option<VaultMonitor>,
+}
+
+impl Dashboard {
+    async fn run(&mut self, terminal: &mut DefaultTerminal, refresh: Duration) -> Result<()> {
+        let mut last_refresh: Option<Instant> = None;
+
+        loop {
+            if last_refresh.is_none_or(|at| at.elapsed() >= refresh) {
+                self.app.status = "Refreshing...".to_string();
+                self.draw(terminal)?;
+                self.refresh().await;
+                last_refresh = Some(Instant::now());
+            }
+            self.draw(terminal)?;
+
+            if !event::poll(INPUT_POLL)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.app.handle_key(key.code) {
+                Some(AppCommand::Quit) => return Ok(()),
+                Some(AppCommand::Refresh) => last_refresh = None,
+                Some(AppCommand::Submit(pending)) => {
+                    self.draw(terminal)?;
+                    self.app.status = match self.submit(&pending).await {
+                        Ok(hash) => format!(
+                            "{} of {} {} confirmed: {}",
+                            pending.action.verb(),
+                            pending.amount,
+                            pending.vault.asset_symbol,
+                            hash
+                        ),
+                        Err(e) => format!("{} failed: {:#}", pending.action.verb(), e),
+                    };
+                    last_refresh = None;
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn draw(&self, terminal: &mut DefaultTerminal) -> Result<()> {
+        terminal.draw(|frame| ui::draw(frame, &self.app, self.chain, self.user))?;
+        Ok(())
+    }
+
+    /// Re-fetch vaults and positions and feed the monitor, reporting failures in the
+    /// status line.
+    async fn refresh(&mut self) {
+        self.app.status = match self.try_refresh().await {
+            Ok(()) => format!(
+                "Updated: {} vaults, {} positions",
+                self.app.all_vaults.len(),
+                self.app.positions.len()
+            ),
+            Err(e) => format!("Refresh failed: {:#}", e),
+        };
+    }
+
+    async fn try_refresh(&mut self) -> Result<()> {
+        let api = self.client.api();
+        let (v1_vaults, v2_vaults) = tokio::try_join!(
+            api.v1.get_whitelisted_vaults(Some(self.chain)),
+            api.v2.get_whitelisted_vaults(Some(self.chain)),
+        )?;
+
+        let positions = match self.user {
+            Some(user) => match self
+                .client
+                .get_user_vault_positions(user, Some(self.chain))
+                .await
+            {
+                Ok(positions) => position_rows(&positions),
+                // User has no positions on this chain
+                Err(ApiError::GraphQL(msg)) if msg.contains("No results") => Vec::new(),
+                Err(e) => return Err(e.into()),
+            },
+            None => Vec::new(),
+        };
+
+        let monitor = self.monitor.get_or_insert_with(|| {
+            let config = positions.iter().fold(
+                MonitorConfig::new()
+                    .with_rule(WatchRule::TvlOutflow {
+                        pct: TVL_OUTFLOW_PCT,
+                        window_secs: TVL_OUTFLOW_WINDOW_SECS,
+                    })
+                    .with_rule(WatchRule::NewRedWarning),
+                |config, position| {
+                    config.with_rule(WatchRule::ApyDrop {
+                        vault: position.vault,
+                        threshold: APY_DROP_THRESHOLD,
+                    })
+                },
+            );
+            VaultMonitor::new(config)
+        });
+        let now = unix_now();
+        let mut alerts = Vec::new();
+        for vault in &v1_vaults {
+            alerts.extend(monitor.observe(VaultObservation::from_v1(vault, now)));
+        }
+        for vault in &v2_vaults {
+            alerts.extend(monitor.observe(VaultObservation::from_v2(vault, now)));
+        }
+
+        let vaults = v1_vaults
+            .iter()
+            .map(|v| VaultRow::from_vault(v))
+            .chain(v2_vaults.iter().map(|v| VaultRow::from_vault(v)))
+            .collect();
+        self.app.set_vaults(vaults);
+        self.app.set_positions(positions);
+        self.app.push_alerts(&alerts);
+        Ok(())
+    }
+
+    /// Send a confirmed deposit or withdrawal, returning the transaction hash.
+    async fn submit(&self, pending: &PendingTransaction) -> Result<String> {
+        let vault = &pending.vault;
+        let amount = TokenAmount::parse(&pending.amount, vault.asset_decimals)?.raw;
+        let receipt = match (vault.version, pending.action) {
+            (VaultVersion::V1, Action::Deposit) => {
+                self.client
+                    .vault_v1()?
+                    .deposit(vault.address, amount)
+                    .await?
+            }
+            (VaultVersion::V1, Action::Withdraw) => {
+                self.client
+                    .vault_v1()?
+                    .withdraw(vault.address, amount)
+                    .await?
+            }
+            (VaultVersion::V2, Action::Deposit) => {
+                self.client
+                    .vault_v2()?
+                    .deposit(vault.address, amount)
+                    .await?
+            }
+            (VaultVersion::V2, Action::Withdraw) => {
+                self.client
+                    .vault_v2()?
+                    .withdraw(vault.address, amount)
+                    .await?
+            }
+        };
+        if !receipt.status() {
+            anyhow::bail!("transaction {:#x} reverted", receipt.transaction_hash);
+        }
+        Ok(format!("{:#x}", receipt.transaction_hash))
+    }
+}
+
+/// Flatten V1 and V2 positions into dashboard rows.
+fn position_rows(positions: &UserVaultPositions) -> Vec<PositionRow> {
+    let v1 = positions.vault_positions.iter().map(|p| PositionRow {
+        vault: p.vault.address,
+        name: p.vault.name.clone(),
+        assets_usd: p.assets_usd,
+        pnl_usd: p.state.as_ref().and_then(|s| s.pnl_usd),
+    });
+    let v2 = positions.vault_v2_positions.iter().map(|p| PositionRow {
+        vault: p.vault.address,
+        name: p.vault.name.clone(),
+        assets_usd: p.assets_usd,
+        pnl_usd: p.pnl_usd,
+    });
+    v1.chain(v2).collect()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
@@ -0,0 +1,242 @@
+//! Dashboard rendering.
+
+use alloy_chains::NamedChain;
+use alloy_primitives::Address;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{
+    Block, Cell, Clear, List, ListItem, Paragraph, Row, Table, TableState, Wrap,
+};
+use ratatui::Frame;
+
+use super::app::{App, Mode, Pane, VaultRow};
+
+const HELP: &str =
+    "q quit | Tab switch pane | ↑/↓ select | Enter detail | d deposit | w withdraw | r refresh";
+
+/// Draw the whole dashboard.
+pub fn draw(frame: &mut Frame, app: &App, chain: NamedChain, user: Option<Address>) {
+    let [header, body, alerts, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(8),
+        Constraint::Length(8),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+    let [vaults, positions] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(body);
+
+    let user = user.map_or_else(|| "no user".to_string(), |u| u.to_string());
+    frame.render_widget(
+        Line::from(format!("Morpho dashboard | {} | {}", chain.as_str(), user)).bold(),
+        header,
+    );
+    draw_vaults(frame, app, vaults);
+    draw_positions(frame, app, positions);
+    draw_alerts(frame, app, alerts);
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(app.status.as_str()),
+            Line::from(HELP).dim(),
+        ]),
+        footer,
+    );
+
+    match &app.mode {
+        Mode::Browse => {}
+        Mode::Detail(vault) => draw_detail(frame, vault),
+        Mode::EnterAmount {
+            action,
+            vault,
+            input,
+        } => draw_popup(
+            frame,
+            &format!("{} {}", action.verb(), vault.name),
+            vec![
+                Line::from(format!("Amount of {}:", vault.asset_symbol)),
+                Line::from(format!("> {}_", input)).bold(),
+                Line::from(""),
+                Line::from("Enter continue | Esc cancel").dim(),
+            ],
+        ),
+        Mode::Confirm(pending) => draw_popup(
+            frame,
+            "Confirm transaction",
+            vec![
+                Line::from(format!(
+                    "{} {} {}",
+                    pending.action.verb(),
+                    pending.amount,
+                    pending.vault.asset_symbol
+                )),
+                Line::from(format!(
+                    "Vault: {} ({})",
+                    pending.vault.name, pending.vault.address
+                )),
+                Line::from(""),
+                Line::from("Send? y / N").bold().fg(Color::Yellow),
+            ],
+        ),
+    }
+}
+
+fn draw_vaults(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.vaults.iter().map(|v| {
+        Row::new(vec![
+            Cell::from(v.asset_symbol.clone()),
+            Cell::from(v.name.clone()),
+            Cell::from(v.version.to_string()),
+            Cell::from(format!("{:.2}%", v.net_apy * 100.0)).fg(Color::Green),
+            Cell::from(format_usd(v.total_assets_usd)),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Length(8),
+            Constraint::Length(12),
+        ],
+    )
+    .header(Row::new(vec!["Asset", "Vault", "Ver", "Net APY", "TVL"]).bold())
+    .block(pane_block("Best vaults by asset", app.pane == Pane::Vaults));
+    draw_table(
+        frame,
+        table,
+        area,
+        app.pane == Pane::Vaults,
+        app.selected_vault,
+    );
+}
+
+fn draw_positions(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.positions.iter().map(|p| {
+        let pnl = match p.pnl_usd {
+            Some(pnl) if pnl < 0.0 => Cell::from(format_usd(Some(pnl))).fg(Color::Red),
+            pnl => Cell::from(format_usd(pnl)).fg(Color::Green),
+        };
+        Row::new(vec![
+            Cell::from(p.name.clone()),
+            Cell::from(format_usd(p.assets_usd)),
+            pnl,
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ],
+    )
+    .header(Row::new(vec!["Vault", "Value", "PnL"]).bold())
+    .block(pane_block("Positions", app.pane == Pane::Positions));
+    draw_table(
+        frame,
+        table,
+        area,
+        app.pane == Pane::Positions,
+        app.selected_position,
+    );
+}
+
+fn draw_table(frame: &mut Frame, table: Table, area: Rect, focused: bool, selected: usize) {
+    let mut state = TableState::default().with_selected(focused.then_some(selected));
+    let table = table.row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_alerts(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = if app.alerts.is_empty() {
+        vec![ListItem::new("No alerts").dim()]
+    } else {
+        app.alerts
+            .iter()
+            .map(|a| ListItem::new(a.as_str()).fg(Color::Yellow))
+            .collect()
+    };
+    frame.render_widget(
+        List::new(items).block(Block::bordered().title("Alerts")),
+        area,
+    );
+}
+
+fn draw_detail(frame: &mut Frame, vault: &VaultRow) {
+    let curator = vault
+        .curator
+        .map_or_else(|| "-".to_string(), |c| c.to_string());
+    draw_popup(
+        frame,
+        &vault.name,
+        vec![
+            Line::from(format!("Address:  {}", vault.address)),
+            Line::from(format!("Version:  {}", vault.version)),
+            Line::from(format!("Asset:    {}", vault.asset_symbol)),
+            Line::from(format!("Net APY:  {:.2}%", vault.net_apy * 100.0)),
+            Line::from(format!("TVL:      {}", format_usd(vault.total_assets_usd))),
+            Line::from(format!("Curator:  {}", curator)),
+            Line::from(format!(
+                "Listed:   {}",
+                if vault.listed { "yes" } else { "no" }
+            )),
+            Line::from(""),
+            Line::from("d deposit | w withdraw | Esc back").dim(),
+        ],
+    );
+}
+
+/// Draw a bordered popup centered over the dashboard.
+fn draw_popup(frame: &mut Frame, title: &str, lines: Vec<Line>) {
+    let height = lines.len() as u16 + 2;
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(title.to_string())),
+        area,
+    );
+}
+
+fn pane_block(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(title);
+    if focused {
+        block.border_style(Style::new().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+fn format_usd(value: Option<f64>) -> String {
+    match value {
+        Some(v) if v >= 1_000_000.0 => format!("${:.2}M", v / 1_000_000.0),
+        Some(v) if v >= 1_000.0 => format!("${:.2}K", v / 1_000.0),
+        Some(v) if v < 0.0 && v > -1_000.0 => format!("-${:.2}", v.abs()),
+        Some(v) if v <= -1_000.0 && v > -1_000_000.0 => format!("-${:.2}K", v.abs() / 1_000.0),
+        Some(v) if v <= -1_000_000.0 => format!("-${:.2}M", v.abs() / 1_000_000.0),
+        Some(v) => format!("${:.2}", v),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_usd() {
+        assert_eq!(format_usd(None), "-");
+        assert_eq!(format_usd(Some(12.5)), "$12.50");
+        assert_eq!(format_usd(Some(1_500.0)), "$1.50K");
+        assert_eq!(format_usd(Some(-2_000_000.0)), "-$2.00M");
+        assert_eq!(format_usd(Some(-12.5)), "-$12.50");
+    }
+}
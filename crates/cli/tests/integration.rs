@@ -23,6 +23,9 @@ mod integration {
     pub mod vault_v1_tests;
     pub mod vault_v2_tests;
     pub mod positions_tests;
+    pub mod report_tests;
+    pub mod export_tests;
+    pub mod monitor_tests;
     pub mod cli_validation_tests;
     pub mod e2e_transaction;
 }
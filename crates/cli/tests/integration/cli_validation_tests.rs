@@ -75,6 +75,15 @@ fn test_positions_missing_address() {
         .stderr(predicate::str::contains("required"));
 }
 
+#[test]
+fn test_vaultv1_info_invalid_address() {
+    morpho_cmd()
+        .args(["vaultv1", "info", "0xnotanaddress"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid vault address"));
+}
+
 #[test]
 fn test_invalid_chain_value() {
     morpho_cmd()
@@ -0,0 +1,130 @@
+//! Integration tests for export command.
+
+use predicates::prelude::*;
+use wiremock::matchers::{body_string_contains, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::helpers::{load_fixture, morpho_cmd, morpho_cmd_with_mock, start_mock_server};
+
+/// Mock the V1 and V2 vault list queries with their fixtures.
+async fn mock_vault_lists(server: &MockServer) {
+    for (operation, fixture) in [("GetVaultsV1", "v1_list"), ("GetVaultsV2", "v2_list")] {
+        Mock::given(method("POST"))
+            .and(body_string_contains(operation))
+            .respond_with(ResponseTemplate::new(200).set_body_string(load_fixture(fixture)))
+            .mount(server)
+            .await;
+    }
+}
+
+/// A per-test temp path.
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("morpho-export-{}-{}", std::process::id(), name))
+}
+
+#[tokio::test]
+async fn test_export_csv_vaults() {
+    let server = start_mock_server().await;
+    mock_vault_lists(&server).await;
+    let out = temp_path("vaults.csv");
+
+    morpho_cmd_with_mock(&server)
+        .args([
+            "export",
+            "--out",
+            out.to_str().unwrap(),
+            "--chain",
+            "ethereum",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Exported"));
+
+    let csv = std::fs::read_to_string(&out).unwrap();
+    assert!(csv.starts_with("snapshot_at,chain_id,address,version,"));
+    assert!(csv.contains("Steakhouse USDC"));
+    assert!(csv.contains("Test V2 USDC Vault"));
+}
+
+#[tokio::test]
+async fn test_export_sql_writes_both_tables() {
+    let server = start_mock_server().await;
+    mock_vault_lists(&server).await;
+    let out = temp_path("snapshot.sql");
+
+    morpho_cmd_with_mock(&server)
+        .args(["export", "--out", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let sql = std::fs::read_to_string(&out).unwrap();
+    assert!(sql.contains("CREATE TABLE IF NOT EXISTS vaults"));
+    assert!(sql.contains("CREATE TABLE IF NOT EXISTS markets"));
+    assert!(sql.contains("INSERT INTO vaults"));
+}
+
+#[tokio::test]
+async fn test_export_sqlite_database() {
+    let server = start_mock_server().await;
+    mock_vault_lists(&server).await;
+    let out = temp_path("snapshot.db");
+    let _ = std::fs::remove_file(&out);
+
+    morpho_cmd_with_mock(&server)
+        .args(["export", "--out", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("vaults and"));
+
+    let bytes = std::fs::read(&out).unwrap();
+    assert!(bytes.starts_with(b"SQLite format 3"));
+}
+
+#[tokio::test]
+async fn test_export_parquet_markets() {
+    let server = start_mock_server().await;
+    mock_vault_lists(&server).await;
+    let out = temp_path("markets.parquet");
+
+    morpho_cmd_with_mock(&server)
+        .args([
+            "export",
+            "--out",
+            out.to_str().unwrap(),
+            "--table",
+            "markets",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("markets"));
+
+    // Parquet files start with the "PAR1" magic bytes
+    let bytes = std::fs::read(&out).unwrap();
+    assert_eq!(&bytes[..4], b"PAR1");
+}
+
+#[tokio::test]
+async fn test_export_format_flag() {
+    let server = start_mock_server().await;
+    mock_vault_lists(&server).await;
+    let out = temp_path("vaults.parquet");
+
+    // `--format` names the file format here rather than the global output format
+    morpho_cmd_with_mock(&server)
+        .args(["export", "--format", "parquet", "--out", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Exported"));
+
+    let bytes = std::fs::read(&out).unwrap();
+    assert_eq!(&bytes[..4], b"PAR1");
+}
+
+#[test]
+fn test_export_unknown_extension() {
+    morpho_cmd()
+        .args(["export", "--out", "vaults.xlsx"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--format"));
+}
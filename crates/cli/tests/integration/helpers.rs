@@ -13,14 +13,19 @@ pub async fn start_mock_server() -> MockServer {
 
 /// Create a CLI command pointing to a mock server.
 pub fn morpho_cmd_with_mock(mock: &MockServer) -> Command {
-    let mut cmd = Command::cargo_bin("morpho").unwrap();
+    let mut cmd = morpho_cmd();
     cmd.env("MORPHO_API_URL", mock.uri());
     cmd
 }
 
 /// Create a CLI command without mock server (for validation tests).
+///
+/// The user's config file and profile selection are ignored.
 pub fn morpho_cmd() -> Command {
-    Command::cargo_bin("morpho").unwrap()
+    let mut cmd = Command::cargo_bin("morpho").unwrap();
+    cmd.env("MORPHO_CONFIG", "/nonexistent/morpho/config.toml")
+        .env_remove("MORPHO_PROFILE");
+    cmd
 }
 
 /// Load a fixture file as a string.
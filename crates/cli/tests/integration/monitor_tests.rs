@@ -0,0 +1,69 @@
+//! Integration tests for monitor command.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use predicates::prelude::*;
+
+use super::helpers::{mock_graphql_error, morpho_cmd_with_mock, start_mock_server};
+
+/// Write a file to a per-test temp path and return the path.
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("morpho-monitor-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_monitor_invalid_sink() {
+    let server = start_mock_server().await;
+    let config = temp_file(
+        "invalid-sink.json",
+        r#"{"notifications": [{"kind": "pager"}]}"#,
+    );
+
+    morpho_cmd_with_mock(&server)
+        .args(["monitor", "--config", config.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid monitor config"));
+}
+
+#[tokio::test]
+async fn test_monitor_keeps_polling_on_api_error() {
+    let server = start_mock_server().await;
+    mock_graphql_error(&server, "upstream timeout").await;
+    let config = temp_file(
+        "api-error.json",
+        r#"{
+            "vaults": [{
+                "address": "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+                "chain": 1,
+                "version": "V1"
+            }],
+            "rules": [{"kind": "new_red_warning"}],
+            "poll_interval_secs": 1
+        }"#,
+    );
+
+    morpho_cmd_with_mock(&server)
+        .args([
+            "monitor",
+            "--config",
+            config.to_str().unwrap(),
+            "--slack",
+            "https://hooks.slack.com/services/T000/B000/XXXX",
+        ])
+        .timeout(Duration::from_secs(3))
+        .assert()
+        .interrupted()
+        .stderr(predicate::str::contains(
+            "Monitoring 1 vaults with 1 rules every 1s, notifying 1 sinks",
+        ))
+        .stderr(predicate::function(|stderr: &str| {
+            stderr
+                .matches("Failed to poll vault 0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458")
+                .count()
+                >= 2
+        }));
+}
@@ -0,0 +1,90 @@
+//! Integration tests for report command.
+
+use std::path::PathBuf;
+
+use predicates::prelude::*;
+
+use super::helpers::{mock_graphql_response, morpho_cmd_with_mock, start_mock_server};
+
+const VAULT_CONFIG: &str = r#"{
+    "vaults": [{
+        "address": "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+        "chain": 1,
+        "version": "V1"
+    }]
+}"#;
+
+/// Write a file to a per-test temp path and return the path.
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("morpho-report-{}-{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_report_markdown() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_info").await;
+    let config = temp_file("markdown-config.json", VAULT_CONFIG);
+
+    morpho_cmd_with_mock(&server)
+        .args(["report", "--config", config.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Morpho Report"))
+        .stdout(predicate::str::contains("Steakhouse USDC"));
+}
+
+#[tokio::test]
+async fn test_report_json_writes_state() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_info").await;
+    let config = temp_file("state-config.json", VAULT_CONFIG);
+    let state =
+        std::env::temp_dir().join(format!("morpho-report-{}-state.json", std::process::id()));
+    let _ = std::fs::remove_file(&state);
+
+    morpho_cmd_with_mock(&server)
+        .args([
+            "report",
+            "--config",
+            config.to_str().unwrap(),
+            "--state",
+            state.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"net_apy_change\": null"));
+
+    // Second run diffs against the persisted state
+    morpho_cmd_with_mock(&server)
+        .args([
+            "report",
+            "--config",
+            config.to_str().unwrap(),
+            "--state",
+            state.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"net_apy_change\": 0.0"));
+
+    assert!(std::fs::read_to_string(&state)
+        .unwrap()
+        .contains("Steakhouse USDC"));
+}
+
+#[tokio::test]
+async fn test_report_missing_config() {
+    let server = start_mock_server().await;
+
+    morpho_cmd_with_mock(&server)
+        .args(["report", "--config", "/nonexistent/report.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("failed to read report config"));
+}
@@ -119,3 +119,41 @@ async fn test_v1_info_vault_not_found() {
         .failure()
         .stderr(predicate::str::contains("Vault not found"));
 }
+
+#[cfg(feature = "sim")]
+#[tokio::test]
+async fn test_v1_lint_clean_vault() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_info").await;
+
+    morpho_cmd_with_mock(&server)
+        .args([
+            "vaultv1",
+            "lint",
+            "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No queue findings"));
+}
+
+#[cfg(feature = "sim")]
+#[tokio::test]
+async fn test_v1_lint_reports_findings() {
+    let server = start_mock_server().await;
+    mock_graphql_response(&server, "v1_lint").await;
+
+    morpho_cmd_with_mock(&server)
+        .args([
+            "vaultv1",
+            "lint",
+            "0x8eB67A509616cd6A7c1B3c8C21D48FF57df3d458",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("has a zero cap"))
+        .stdout(predicate::str::contains("is not in the withdraw queue"))
+        .stderr(predicate::str::contains("2 queue finding(s)"));
+}
@@ -0,0 +1,583 @@
+//! Bundler3 bindings and flash-leverage client.
+//!
+//! [Bundler3](https://docs.morpho.org/bundler) executes a list of calls atomically,
+//! mostly against its GeneralAdapter1. [`BundlerClient::leverage`] uses it to open a
+//! leveraged collateral position in one transaction:
+//!
+//! 1. the user's collateral is pulled into the adapter;
+//! 2. the adapter supplies the *total* collateral on the user's behalf. Morpho calls
+//!    back into the adapter before collecting it, which acts as a free flash loan;
+//! 3. inside the callback the adapter borrows loan assets against the new collateral
+//!    and sends them to a swap, which must return at least the flash-loaned collateral
+//!    to the adapter;
+//! 4. leftovers of both tokens are returned to the user.
+//!
+//! Swap calls come from the caller (e.g. an aggregator adapter with a quote), since
+//! routing is out of scope here. Size `borrow_assets` and `min_collateral_out` for a
+//! target LTV with `morpho_rs_sim::simulate_leverage`.
+//!
+//! Before leveraging, the user must authorize the adapter on Morpho
+//! ([`BundlerClient::authorize`]) and approve it for the collateral token
+//! ([`BundlerClient::approve_if_needed`]).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::{BundlerClient, LeverageParams, LeverageSwap};
+//!
+//! let client = BundlerClient::new(rpc_url, private_key, bundler3, general_adapter)?;
+//! if !client.is_authorized(morpho).await? {
+//!     client.authorize(morpho).send().await?;
+//! }
+//! if let Some(approval) = client
+//!     .approve_if_needed(market.collateralToken, initial_collateral)
+//!     .await?
+//! {
+//!     approval.send().await?;
+//! }
+//!
+//! let swap = LeverageSwap::new(swap_adapter, swap_calls, min_collateral_out);
+//! let params = LeverageParams::new(market, initial_collateral, borrow_assets, swap);
+//! let receipt = client.leverage(&params).send().await?;
+//! ```
+
+use std::sync::Arc;
+
+use alloy::primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy::rpc::types::BlockId;
+use alloy::sol;
+use alloy::sol_types::{SolCall, SolValue};
+
+use crate::erc20::IERC20;
+use crate::error::{ContractError, Result};
+use crate::metamorpho::MarketParams;
+use crate::metrics::{Metrics, NoopMetrics};
+use crate::morpho_blue::IMorpho;
+use crate::prepared_call::PreparedCall;
+use crate::provider::{connect, HttpProvider};
+
+sol! {
+    /// A call executed by Bundler3.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Call {
+        address to;
+        bytes data;
+        uint256 value;
+        bool skipRevert;
+        bytes32 callbackHash;
+    }
+
+    #[sol(rpc)]
+    interface IBundler3 {
+        function multicall(Call[] calldata bundle) external payable;
+        function reenter(Call[] calldata bundle) external;
+    }
+}
+
+/// GeneralAdapter1 bindings. `sol!` cannot reference the crate's `MarketParams` from
+/// another invocation, so the adapter has its own copy of the struct.
+mod general_adapter {
+    use alloy::sol;
+
+    sol! {
+        struct MarketParams {
+            address loanToken;
+            address collateralToken;
+            address oracle;
+            address irm;
+            uint256 lltv;
+        }
+
+        #[sol(rpc)]
+        interface IGeneralAdapter1 {
+            function erc20Transfer(address token, address receiver, uint256 amount) external;
+            function erc20TransferFrom(address token, address receiver, uint256 amount) external;
+            function morphoSupplyCollateral(
+                MarketParams calldata marketParams,
+                uint256 assets,
+                address onBehalf,
+                bytes calldata data
+            ) external;
+            function morphoBorrow(
+                MarketParams calldata marketParams,
+                uint256 assets,
+                uint256 shares,
+                uint256 minSharePriceE27,
+                address receiver
+            ) external;
+        }
+    }
+
+    impl From<&crate::metamorpho::MarketParams> for MarketParams {
+        fn from(params: &crate::metamorpho::MarketParams) -> Self {
+            Self {
+                loanToken: params.loanToken,
+                collateralToken: params.collateralToken,
+                oracle: params.oracle,
+                irm: params.irm,
+                lltv: params.lltv,
+            }
+        }
+    }
+}
+
+use general_adapter::IGeneralAdapter1;
+
+impl Call {
+    /// A call to `to` that reverts the bundle on failure and expects no callback.
+    pub fn new<C: SolCall>(to: Address, call: &C) -> Self {
+        Self {
+            to,
+            data: call.abi_encode().into(),
+            value: U256::ZERO,
+            skipRevert: false,
+            callbackHash: B256::ZERO,
+        }
+    }
+
+    /// Let the bundle continue if this call reverts.
+    pub fn skip_revert(mut self) -> Self {
+        self.skipRevert = true;
+        self
+    }
+}
+
+/// Swap turning borrowed loan assets back into collateral during a leverage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeverageSwap {
+    /// Receiver of the borrowed loan assets, typically a swap adapter.
+    pub receiver: Address,
+    /// Bundler3 calls performing the swap. They must leave at least
+    /// `min_collateral_out` collateral on the general adapter.
+    pub calls: Vec<Call>,
+    /// Minimum collateral the swap returns; this is the flash-loaned amount.
+    pub min_collateral_out: U256,
+}
+
+impl LeverageSwap {
+    /// Create a swap sending borrowed assets to `receiver` and running `calls`.
+    pub fn new(receiver: Address, calls: Vec<Call>, min_collateral_out: U256) -> Self {
+        Self {
+            receiver,
+            calls,
+            min_collateral_out,
+        }
+    }
+}
+
+/// Parameters of a flash-leveraged collateral supply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeverageParams {
+    /// Market to supply collateral to and borrow from.
+    pub market: MarketParams,
+    /// Collateral pulled from the user.
+    pub initial_collateral: U256,
+    /// Loan assets borrowed to buy the flash-loaned collateral.
+    pub borrow_assets: U256,
+    /// Minimum loan assets per borrow share, scaled by 1e27. Zero disables the check.
+    pub min_borrow_share_price_e27: U256,
+    /// Swap from loan assets to collateral.
+    pub swap: LeverageSwap,
+}
+
+impl LeverageParams {
+    /// Create leverage parameters without a borrow share price bound.
+    pub fn new(
+        market: MarketParams,
+        initial_collateral: U256,
+        borrow_assets: U256,
+        swap: LeverageSwap,
+    ) -> Self {
+        Self {
+            market,
+            initial_collateral,
+            borrow_assets,
+            min_borrow_share_price_e27: U256::ZERO,
+            swap,
+        }
+    }
+
+    /// Set the minimum loan assets per borrow share, scaled by 1e27.
+    pub fn with_min_borrow_share_price_e27(mut self, price: U256) -> Self {
+        self.min_borrow_share_price_e27 = price;
+        self
+    }
+
+    /// Collateral supplied in total: the user's plus the flash-loaned amount.
+    pub fn total_collateral(&self) -> U256 {
+        self.initial_collateral + self.swap.min_collateral_out
+    }
+}
+
+/// Bundler3 calls opening the leveraged position described by `params` for `owner`.
+///
+/// `adapter` is the chain's GeneralAdapter1.
+pub fn leverage_bundle(adapter: Address, owner: Address, params: &LeverageParams) -> Vec<Call> {
+    let collateral_token = params.market.collateralToken;
+
+    let mut callbacks = vec![Call::new(
+        adapter,
+        &IGeneralAdapter1::morphoBorrowCall {
+            marketParams: (&params.market).into(),
+            assets: params.borrow_assets,
+            shares: U256::ZERO,
+            minSharePriceE27: params.min_borrow_share_price_e27,
+            receiver: params.swap.receiver,
+        },
+    )];
+    callbacks.extend(params.swap.calls.iter().cloned());
+    // Bundler3 only accepts the reentry whose calldata hashes to `callbackHash`
+    let callback_data = Bytes::from(callbacks.abi_encode());
+
+    let mut supply = Call::new(
+        adapter,
+        &IGeneralAdapter1::morphoSupplyCollateralCall {
+            marketParams: (&params.market).into(),
+            assets: params.total_collateral(),
+            onBehalf: owner,
+            data: callback_data.clone(),
+        },
+    );
+    supply.callbackHash = keccak256(&callback_data);
+
+    let skim = |token| {
+        Call::new(
+            adapter,
+            &IGeneralAdapter1::erc20TransferCall {
+                token,
+                receiver: owner,
+                amount: U256::MAX,
+            },
+        )
+        // Reverts when there is nothing to return
+        .skip_revert()
+    };
+
+    vec![
+        Call::new(
+            adapter,
+            &IGeneralAdapter1::erc20TransferFromCall {
+                token: collateral_token,
+                receiver: adapter,
+                amount: params.initial_collateral,
+            },
+        ),
+        supply,
+        skim(collateral_token),
+        skim(params.market.loanToken),
+    ]
+}
+
+/// Client for batched Morpho operations through Bundler3.
+pub struct BundlerClient {
+    provider: HttpProvider,
+    signer_address: Address,
+    bundler3: Address,
+    general_adapter: Address,
+    metrics: Arc<dyn Metrics>,
+    block: BlockId,
+}
+
+impl BundlerClient {
+    /// Create a client for the given Bundler3 and GeneralAdapter1 deployments.
+    pub fn new(
+        rpc_url: &str,
+        private_key: &str,
+        bundler3: Address,
+        general_adapter: Address,
+    ) -> Result<Self> {
+        Self::new_with_fallbacks(&[rpc_url], private_key, bundler3, general_adapter)
+    }
+
+    /// Create a client over several RPC endpoints, failing over between them.
+    pub fn new_with_fallbacks(
+        rpc_urls: &[&str],
+        private_key: &str,
+        bundler3: Address,
+        general_adapter: Address,
+    ) -> Result<Self> {
+        let (provider, signer_address) = connect(rpc_urls, private_key)?;
+
+        Ok(Self {
+            provider,
+            signer_address,
+            bundler3,
+            general_adapter,
+            metrics: Arc::new(NoopMetrics),
+            block: BlockId::latest(),
+        })
+    }
+
+    /// Set the metrics sink notified on transaction submission and confirmation.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Pin view reads to a block number instead of the latest block.
+    pub fn at_block(mut self, block: u64) -> Self {
+        self.block = BlockId::number(block);
+        self
+    }
+
+    /// Get the underlying provider.
+    pub fn provider(&self) -> &HttpProvider {
+        &self.provider
+    }
+
+    /// Get the signer's address.
+    pub fn signer_address(&self) -> Address {
+        self.signer_address
+    }
+
+    /// Get the Bundler3 address.
+    pub fn bundler3(&self) -> Address {
+        self.bundler3
+    }
+
+    /// Get the GeneralAdapter1 address.
+    pub fn general_adapter(&self) -> Address {
+        self.general_adapter
+    }
+
+    /// Check whether the general adapter may manage the signer's Morpho positions.
+    pub async fn is_authorized(&self, morpho: Address) -> Result<bool> {
+        IMorpho::new(morpho, &self.provider)
+            .isAuthorized(self.signer_address, self.general_adapter)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get authorization: {}", e))
+            })
+    }
+
+    /// Create a prepared transaction authorizing the general adapter on Morpho.
+    pub fn authorize(&self, morpho: Address) -> PreparedCall<'_, IMorpho::setAuthorizationCall> {
+        let call = IMorpho::setAuthorizationCall {
+            authorized: self.general_adapter,
+            newIsAuthorized: true,
+        };
+        PreparedCall::new(morpho, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Approve the general adapter to pull `amount` of `token` if needed.
+    /// Returns a `PreparedCall` if approval is needed, None otherwise.
+    pub async fn approve_if_needed(
+        &self,
+        token: Address,
+        amount: U256,
+    ) -> Result<Option<PreparedCall<'_, IERC20::approveCall>>> {
+        let allowance = IERC20::new(token, &self.provider)
+            .allowance(self.signer_address, self.general_adapter)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get allowance: {}", e))
+            })?;
+
+        if allowance >= amount {
+            return Ok(None);
+        }
+
+        let call = IERC20::approveCall {
+            spender: self.general_adapter,
+            amount,
+        };
+        Ok(Some(
+            PreparedCall::new(token, call, U256::ZERO, &self.provider)
+                .with_metrics(self.metrics.as_ref()),
+        ))
+    }
+
+    /// Create a prepared transaction executing `bundle` through Bundler3.
+    pub fn multicall(&self, bundle: Vec<Call>) -> PreparedCall<'_, IBundler3::multicallCall> {
+        let call = IBundler3::multicallCall { bundle };
+        PreparedCall::new(self.bundler3, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared transaction opening a leveraged position for the signer.
+    pub fn leverage(&self, params: &LeverageParams) -> PreparedCall<'_, IBundler3::multicallCall> {
+        self.multicall(leverage_bundle(
+            self.general_adapter,
+            self.signer_address,
+            params,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::network::EthereumWallet;
+    use alloy::providers::ProviderBuilder;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::transports::mock::Asserter;
+
+    const PRIVATE_KEY: &str = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn market() -> MarketParams {
+        MarketParams {
+            loanToken: Address::repeat_byte(0x01),
+            collateralToken: Address::repeat_byte(0x02),
+            oracle: Address::repeat_byte(0x03),
+            irm: Address::repeat_byte(0x04),
+            lltv: U256::from(860_000_000_000_000_000u64),
+        }
+    }
+
+    fn params() -> LeverageParams {
+        let swap_call = Call::new(
+            Address::repeat_byte(0x55),
+            &IERC20::approveCall {
+                spender: Address::repeat_byte(0x66),
+                amount: U256::from(1),
+            },
+        );
+        LeverageParams::new(
+            market(),
+            U256::from(100u64),
+            U256::from(300u64),
+            LeverageSwap::new(
+                Address::repeat_byte(0x55),
+                vec![swap_call],
+                U256::from(290u64),
+            ),
+        )
+    }
+
+    fn mocked_client(asserter: &Asserter) -> BundlerClient {
+        let signer: PrivateKeySigner = PRIVATE_KEY.parse().unwrap();
+        let signer_address = signer.address();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_mocked_client(asserter.clone());
+        BundlerClient {
+            provider,
+            signer_address,
+            bundler3: Address::repeat_byte(0xb3),
+            general_adapter: Address::repeat_byte(0xa1),
+            metrics: Arc::new(NoopMetrics),
+            block: BlockId::latest(),
+        }
+    }
+
+    #[test]
+    fn test_invalid_private_key() {
+        let result = BundlerClient::new(
+            "http://localhost:8545",
+            "invalid_key",
+            Address::ZERO,
+            Address::ZERO,
+        );
+        assert!(matches!(result, Err(ContractError::InvalidPrivateKey)));
+    }
+
+    #[test]
+    fn test_leverage_bundle_layout() {
+        let adapter = Address::repeat_byte(0xa1);
+        let owner = Address::repeat_byte(0xee);
+        let params = params();
+        let bundle = leverage_bundle(adapter, owner, &params);
+
+        assert_eq!(bundle.len(), 4);
+        assert!(bundle.iter().all(|call| call.to == adapter));
+
+        let pull = IGeneralAdapter1::erc20TransferFromCall::abi_decode(&bundle[0].data).unwrap();
+        assert_eq!(pull.token, market().collateralToken);
+        assert_eq!(pull.receiver, adapter);
+        assert_eq!(pull.amount, U256::from(100u64));
+
+        let supply =
+            IGeneralAdapter1::morphoSupplyCollateralCall::abi_decode(&bundle[1].data).unwrap();
+        assert_eq!(supply.assets, U256::from(390u64));
+        assert_eq!(supply.onBehalf, owner);
+        assert_eq!(bundle[1].callbackHash, keccak256(&supply.data));
+
+        // The callback borrows to the swap receiver, then swaps
+        let callbacks = Vec::<Call>::abi_decode(&supply.data).unwrap();
+        assert_eq!(callbacks.len(), 2);
+        let borrow = IGeneralAdapter1::morphoBorrowCall::abi_decode(&callbacks[0].data).unwrap();
+        assert_eq!(borrow.assets, U256::from(300u64));
+        assert_eq!(borrow.receiver, Address::repeat_byte(0x55));
+        assert_eq!(callbacks[1], params.swap.calls[0]);
+
+        // Leftovers go back to the owner without failing the bundle
+        for (call, token) in bundle[2..]
+            .iter()
+            .zip([market().collateralToken, market().loanToken])
+        {
+            let skim = IGeneralAdapter1::erc20TransferCall::abi_decode(&call.data).unwrap();
+            assert_eq!(skim.token, token);
+            assert_eq!(skim.receiver, owner);
+            assert_eq!(skim.amount, U256::MAX);
+            assert!(call.skipRevert);
+        }
+    }
+
+    #[test]
+    fn test_callback_hash_matches_reenter_calldata() {
+        let bundle = leverage_bundle(Address::repeat_byte(0xa1), Address::ZERO, &params());
+        let supply =
+            IGeneralAdapter1::morphoSupplyCollateralCall::abi_decode(&bundle[1].data).unwrap();
+        let callbacks = Vec::<Call>::abi_decode(&supply.data).unwrap();
+
+        // Bundler3 hashes `reenter` calldata without its selector
+        let reenter = IBundler3::reenterCall { bundle: callbacks }.abi_encode();
+        assert_eq!(bundle[1].callbackHash, keccak256(&reenter[4..]));
+    }
+
+    #[test]
+    fn test_leverage_targets_bundler() {
+        let asserter = Asserter::new();
+        let client = mocked_client(&asserter);
+        let prepared = client.leverage(&params());
+        assert_eq!(prepared.to(), Address::repeat_byte(0xb3));
+        assert_eq!(prepared.value(), U256::ZERO);
+        let (_, call) = prepared.prepare();
+        assert_eq!(call.bundle.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_approve_if_needed() {
+        let asserter = Asserter::new();
+        let client = mocked_client(&asserter);
+        let token = Address::repeat_byte(0x02);
+
+        asserter.push_success(&Bytes::from(U256::from(500u64).abi_encode()));
+        assert!(client
+            .approve_if_needed(token, U256::from(100u64))
+            .await
+            .unwrap()
+            .is_none());
+
+        asserter.push_success(&Bytes::from(U256::from(50u64).abi_encode()));
+        let (to, call) = client
+            .approve_if_needed(token, U256::from(100u64))
+            .await
+            .unwrap()
+            .unwrap()
+            .prepare();
+        assert_eq!(to, token);
+        assert_eq!(call.spender, client.general_adapter());
+        assert_eq!(call.amount, U256::from(100u64));
+    }
+
+    #[tokio::test]
+    async fn test_authorization() {
+        let asserter = Asserter::new();
+        let client = mocked_client(&asserter);
+        let morpho = Address::repeat_byte(0xbb);
+
+        asserter.push_success(&Bytes::from(true.abi_encode()));
+        assert!(client.is_authorized(morpho).await.unwrap());
+
+        let (to, call) = client.authorize(morpho).prepare();
+        assert_eq!(to, morpho);
+        assert_eq!(call.authorized, client.general_adapter());
+        assert!(call.newIsAuthorized);
+    }
+}
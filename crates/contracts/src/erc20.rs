@@ -1,15 +1,252 @@
-//! ERC20 interface definitions.
+//! ERC20 and WETH interface definitions and token client.
+//!
+//! [`Erc20Client`] reads balances, allowances, and token metadata and prepares transfers
+//! and approvals for any ERC20 token, including vault share tokens. Token metadata
+//! (symbol, name, decimals) is fixed at deployment for well-behaved tokens, so it is read
+//! once per token and cached for the client's lifetime.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::Erc20Client;
+//!
+//! let client = Erc20Client::new(rpc_url, private_key)?;
+//! let metadata = client.metadata(token).await?;
+//! let balance = client.balance_of(token, owner).await?;
+//! println!("{} {}", balance, metadata.symbol);
+//!
+//! client.transfer(token, recipient, amount).send().await?;
+//! ```
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::BlockId;
 use alloy::sol;
 
+use crate::error::{ContractError, Result};
+use crate::metrics::{Metrics, NoopMetrics};
+use crate::prepared_call::PreparedCall;
+use crate::provider::{connect, HttpProvider};
+
 sol! {
     #[sol(rpc)]
     interface IERC20 {
         function approve(address spender, uint256 amount) external returns (bool);
+        function transfer(address to, uint256 amount) external returns (bool);
         function allowance(address owner, address spender) external view returns (uint256);
         function balanceOf(address account) external view returns (uint256);
         function decimals() external view returns (uint8);
         function symbol() external view returns (string);
         function name() external view returns (string);
     }
+
+    /// Wrapped native token (WETH9 and its per-chain equivalents).
+    #[sol(rpc)]
+    interface IWETH {
+        function deposit() external payable;
+        function withdraw(uint256 amount) external;
+    }
+}
+
+/// Symbol, name, and decimals of an ERC20 token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    /// Token symbol.
+    pub symbol: String,
+    /// Token name.
+    pub name: String,
+    /// Token decimals.
+    pub decimals: u8,
+}
+
+/// Client for reading and transferring ERC20 tokens.
+pub struct Erc20Client {
+    provider: HttpProvider,
+    signer_address: Address,
+    metrics: Arc<dyn Metrics>,
+    block: BlockId,
+    metadata: Mutex<HashMap<Address, TokenMetadata>>,
+}
+
+impl Erc20Client {
+    /// Create a new ERC20 client.
+    pub fn new(rpc_url: &str, private_key: &str) -> Result<Self> {
+        Self::new_with_fallbacks(&[rpc_url], private_key)
+    }
+
+    /// Create a new ERC20 client over several RPC endpoints, failing over between them.
+    pub fn new_with_fallbacks(rpc_urls: &[&str], private_key: &str) -> Result<Self> {
+        let (provider, signer_address) = connect(rpc_urls, private_key)?;
+
+        Ok(Self {
+            provider,
+            signer_address,
+            metrics: Arc::new(NoopMetrics),
+            block: BlockId::latest(),
+            metadata: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Set the metrics sink notified on transaction submission and confirmation.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Pin balance and allowance reads to a block number instead of the latest block.
+    pub fn at_block(mut self, block: u64) -> Self {
+        self.block = BlockId::number(block);
+        self
+    }
+
+    /// Get the underlying provider.
+    pub fn provider(&self) -> &HttpProvider {
+        &self.provider
+    }
+
+    /// Get the signer's address.
+    pub fn signer_address(&self) -> Address {
+        self.signer_address
+    }
+
+    /// Get the balance of `token` held by `owner`.
+    pub async fn balance_of(&self, token: Address, owner: Address) -> Result<U256> {
+        IERC20::new(token, &self.provider)
+            .balanceOf(owner)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| ContractError::TransactionFailed(format!("Failed to get balance: {}", e)))
+    }
+
+    /// Get the amount of `token` that `spender` may spend on behalf of `owner`.
+    pub async fn allowance(
+        &self,
+        token: Address,
+        owner: Address,
+        spender: Address,
+    ) -> Result<U256> {
+        IERC20::new(token, &self.provider)
+            .allowance(owner, spender)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get allowance: {}", e))
+            })
+    }
+
+    /// Get the symbol, name, and decimals of `token`.
+    ///
+    /// The three reads are batched through Multicall3 on the first call for a token;
+    /// later calls are served from the client's cache.
+    pub async fn metadata(&self, token: Address) -> Result<TokenMetadata> {
+        if let Some(metadata) = self.cache().get(&token) {
+            return Ok(metadata.clone());
+        }
+
+        let contract = IERC20::new(token, &self.provider);
+        let (symbol, name, decimals) = self
+            .provider
+            .multicall()
+            .add(contract.symbol())
+            .add(contract.name())
+            .add(contract.decimals())
+            .aggregate()
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get token metadata: {}", e))
+            })?;
+        let metadata = TokenMetadata {
+            symbol,
+            name,
+            decimals,
+        };
+
+        self.cache().insert(token, metadata.clone());
+        Ok(metadata)
+    }
+
+    /// Get the decimals of `token` (cached, see [`metadata`](Self::metadata)).
+    pub async fn decimals(&self, token: Address) -> Result<u8> {
+        Ok(self.metadata(token).await?.decimals)
+    }
+
+    /// Get the symbol of `token` (cached, see [`metadata`](Self::metadata)).
+    pub async fn symbol(&self, token: Address) -> Result<String> {
+        Ok(self.metadata(token).await?.symbol)
+    }
+
+    /// Create a prepared transfer of `amount` of `token` from the signer to `to`.
+    pub fn transfer(
+        &self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> PreparedCall<'_, IERC20::transferCall> {
+        let call = IERC20::transferCall { to, amount };
+        PreparedCall::new(token, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared approval of `spender` for `amount` of `token`.
+    pub fn approve(
+        &self,
+        token: Address,
+        spender: Address,
+        amount: U256,
+    ) -> PreparedCall<'_, IERC20::approveCall> {
+        let call = IERC20::approveCall { spender, amount };
+        PreparedCall::new(token, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    fn cache(&self) -> MutexGuard<'_, HashMap<Address, TokenMetadata>> {
+        self.metadata
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_transfer_prepares_call() {
+        let client = Erc20Client::new("http://localhost:8545", PRIVATE_KEY).unwrap();
+        let token = Address::repeat_byte(0x11);
+        let to = Address::repeat_byte(0x22);
+
+        let prepared = client.transfer(token, to, U256::from(1_000u64));
+        assert_eq!(prepared.value(), U256::ZERO);
+
+        let (target, call) = prepared.prepare();
+        assert_eq!(target, token);
+        assert_eq!(call.to, to);
+        assert_eq!(call.amount, U256::from(1_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_served_from_cache() {
+        // Nothing listens on this port, so a cache miss would fail.
+        let client = Erc20Client::new("http://localhost:1", PRIVATE_KEY).unwrap();
+        let token = Address::repeat_byte(0x11);
+        let metadata = TokenMetadata {
+            symbol: "USDC".to_string(),
+            name: "USD Coin".to_string(),
+            decimals: 6,
+        };
+        client.cache().insert(token, metadata.clone());
+
+        assert_eq!(client.metadata(token).await.unwrap(), metadata);
+        assert_eq!(client.decimals(token).await.unwrap(), 6);
+        assert_eq!(client.symbol(token).await.unwrap(), "USDC");
+        assert!(client.metadata(Address::repeat_byte(0x22)).await.is_err());
+    }
 }
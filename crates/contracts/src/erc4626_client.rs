@@ -6,6 +6,7 @@
 #![allow(async_fn_in_trait)]
 
 use alloy::primitives::{Address, U256};
+use alloy::rpc::types::BlockId;
 
 use crate::erc4626::IERC4626;
 use crate::error::{ContractError, Result};
@@ -14,7 +15,8 @@ use crate::provider::HttpProvider;
 /// Trait for ERC-4626 vault client functionality.
 ///
 /// Provides default implementations for all ERC-4626 view functions.
-/// Implementors only need to provide `provider()` and `signer_address()`.
+/// Implementors only need to provide `provider()` and `signer_address()`, and can
+/// override `block()` to pin reads at a historical block.
 pub trait Erc4626Client {
     /// Returns a reference to the HTTP provider.
     fn provider(&self) -> &HttpProvider;
@@ -22,12 +24,18 @@ pub trait Erc4626Client {
     /// Returns the signer's address.
     fn signer_address(&self) -> Address;
 
+    /// Returns the block that view functions read state at (latest by default).
+    fn block(&self) -> BlockId {
+        BlockId::latest()
+    }
+
     /// Get the underlying asset address of a vault.
     async fn get_asset(&self, vault: Address) -> Result<Address> {
         let contract = IERC4626::new(vault, self.provider());
         let result = contract
             .asset()
             .call()
+            .block(self.block())
             .await
             .map_err(|e| ContractError::TransactionFailed(format!("Failed to get asset: {}", e)))?;
         Ok(result)
@@ -36,99 +44,154 @@ pub trait Erc4626Client {
     /// Get the total assets managed by a vault.
     async fn total_assets(&self, vault: Address) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.totalAssets().call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to get total assets: {}", e))
-        })?;
+        let result = contract
+            .totalAssets()
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get total assets: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Convert an asset amount to shares.
     async fn convert_to_shares(&self, vault: Address, assets: U256) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.convertToShares(assets).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to convert to shares: {}", e))
-        })?;
+        let result = contract
+            .convertToShares(assets)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to convert to shares: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Convert a share amount to assets.
     async fn convert_to_assets(&self, vault: Address, shares: U256) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.convertToAssets(shares).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to convert to assets: {}", e))
-        })?;
+        let result = contract
+            .convertToAssets(shares)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to convert to assets: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Get the maximum deposit amount for a receiver.
     async fn max_deposit(&self, vault: Address, receiver: Address) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.maxDeposit(receiver).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to get max deposit: {}", e))
-        })?;
+        let result = contract
+            .maxDeposit(receiver)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get max deposit: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Get the maximum withdraw amount for an owner.
     async fn max_withdraw(&self, vault: Address, owner: Address) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.maxWithdraw(owner).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to get max withdraw: {}", e))
-        })?;
+        let result = contract
+            .maxWithdraw(owner)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get max withdraw: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Get the maximum mint amount (in shares) for a receiver.
     async fn max_mint(&self, vault: Address, receiver: Address) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.maxMint(receiver).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to get max mint: {}", e))
-        })?;
+        let result = contract
+            .maxMint(receiver)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get max mint: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Get the maximum redeem amount (in shares) for an owner.
     async fn max_redeem(&self, vault: Address, owner: Address) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.maxRedeem(owner).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to get max redeem: {}", e))
-        })?;
+        let result = contract
+            .maxRedeem(owner)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get max redeem: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Preview the shares that would be received for a deposit.
     async fn preview_deposit(&self, vault: Address, assets: U256) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.previewDeposit(assets).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to preview deposit: {}", e))
-        })?;
+        let result = contract
+            .previewDeposit(assets)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to preview deposit: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Preview the assets required to mint a specific amount of shares.
     async fn preview_mint(&self, vault: Address, shares: U256) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.previewMint(shares).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to preview mint: {}", e))
-        })?;
+        let result = contract
+            .previewMint(shares)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to preview mint: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Preview the shares that would be burned for a withdrawal.
     async fn preview_withdraw(&self, vault: Address, assets: U256) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.previewWithdraw(assets).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to preview withdraw: {}", e))
-        })?;
+        let result = contract
+            .previewWithdraw(assets)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to preview withdraw: {}", e))
+            })?;
         Ok(result)
     }
 
     /// Preview the assets that would be received for redeeming shares.
     async fn preview_redeem(&self, vault: Address, shares: U256) -> Result<U256> {
         let contract = IERC4626::new(vault, self.provider());
-        let result = contract.previewRedeem(shares).call().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to preview redeem: {}", e))
-        })?;
+        let result = contract
+            .previewRedeem(shares)
+            .call()
+            .block(self.block())
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to preview redeem: {}", e))
+            })?;
         Ok(result)
     }
 }
@@ -137,7 +200,8 @@ pub trait Erc4626Client {
 ///
 /// This macro generates `deposit`, `withdraw`, `mint`, and `redeem` methods
 /// that return `PreparedCall` types. It's needed because trait methods cannot
-/// return types with lifetime parameters tied to `self`.
+/// return types with lifetime parameters tied to `self`. The target type must have
+/// `provider: HttpProvider` and `metrics: Arc<dyn Metrics>` fields.
 ///
 /// # Usage
 ///
@@ -166,6 +230,7 @@ macro_rules! impl_erc4626_transactions {
                     alloy::primitives::U256::ZERO,
                     &self.provider,
                 )
+                .with_metrics(self.metrics.as_ref())
             }
 
             /// Create a prepared withdraw transaction.
@@ -189,6 +254,7 @@ macro_rules! impl_erc4626_transactions {
                     alloy::primitives::U256::ZERO,
                     &self.provider,
                 )
+                .with_metrics(self.metrics.as_ref())
             }
 
             /// Create a prepared mint transaction.
@@ -206,6 +272,7 @@ macro_rules! impl_erc4626_transactions {
                     alloy::primitives::U256::ZERO,
                     &self.provider,
                 )
+                .with_metrics(self.metrics.as_ref())
             }
 
             /// Create a prepared redeem transaction.
@@ -229,6 +296,7 @@ macro_rules! impl_erc4626_transactions {
                     alloy::primitives::U256::ZERO,
                     &self.provider,
                 )
+                .with_metrics(self.metrics.as_ref())
             }
         }
     };
@@ -1,5 +1,6 @@
 //! Error types for the contracts crate.
 
+use alloy::primitives::{Address, B256, U256};
 use thiserror::Error;
 
 /// Errors that can occur when using contract clients.
@@ -16,6 +17,77 @@ pub enum ContractError {
     /// Invalid private key.
     #[error("Invalid private key")]
     InvalidPrivateKey,
+
+    /// A timelocked value cannot be accepted yet.
+    #[error("Timelock has not elapsed: pending value is valid at {valid_at}")]
+    TimelockNotElapsed {
+        /// Unix timestamp at which the pending value can be accepted.
+        valid_at: u64,
+    },
+
+    /// There is no pending value to accept.
+    #[error("No pending value")]
+    NoPendingValue,
+
+    /// A value is already pending for this setting.
+    #[error("A value is already pending")]
+    AlreadyPending,
+
+    /// The new value equals the current one.
+    #[error("Value already set")]
+    AlreadySet,
+
+    /// The fee exceeds the vault's maximum.
+    #[error("Fee {fee} exceeds maximum {max}")]
+    MaxFeeExceeded {
+        /// Requested fee (WAD-scaled).
+        fee: U256,
+        /// Maximum fee (WAD-scaled).
+        max: U256,
+    },
+
+    /// A non-zero fee requires a fee recipient.
+    #[error("Fee recipient cannot be zero while the fee is non-zero")]
+    ZeroFeeRecipient,
+
+    /// The previewed share amount is outside the caller's bound.
+    #[error("Slippage exceeded: previewed {previewed} shares, limit {limit}")]
+    SlippageExceeded {
+        /// Shares the vault previews for the operation.
+        previewed: U256,
+        /// Caller's bound (minimum minted or maximum burned).
+        limit: U256,
+    },
+
+    /// A vault gate does not allow the account, and the account is not on the gate's
+    /// signature allowlist.
+    #[error("Account {account} is not eligible to pass gate {gate}")]
+    GateNotEligible {
+        /// Gate contract.
+        gate: Address,
+        /// Account the gate rejects.
+        account: Address,
+    },
+
+    /// A sent transaction was not confirmed before the timeout.
+    ///
+    /// The transaction may still be mined; replace it with
+    /// [`speed_up_transaction`](crate::speed_up_transaction) or
+    /// [`cancel_transaction`](crate::cancel_transaction).
+    #[error("Transaction {hash} not confirmed before timeout")]
+    TransactionTimeout {
+        /// Hash of the last submission.
+        hash: B256,
+    },
+
+    /// A user operation was not included before the timeout.
+    ///
+    /// The bundler may still include it; the account nonce is consumed once it does.
+    #[error("User operation {hash} not included before timeout")]
+    UserOperationTimeout {
+        /// User operation hash returned by the bundler.
+        hash: B256,
+    },
 }
 
 impl ContractError {
@@ -29,11 +101,23 @@ impl ContractError {
 
     /// Returns `true` if the error is caused by invalid user input.
     ///
-    /// [`ContractError::InvalidPrivateKey`] is a user configuration error.
+    /// [`ContractError::InvalidPrivateKey`] is a user configuration error, and the
+    /// admin pre-flight errors (timelock, pending, fee checks) and
+    /// [`ContractError::GateNotEligible`] reject calls that the vault would revert.
     /// [`ContractError::TransactionFailed`] may be user error (insufficient funds, etc.)
     /// but could also be a contract-level revert, so it is not classified as user error.
     pub fn is_user_error(&self) -> bool {
-        matches!(self, ContractError::InvalidPrivateKey)
+        matches!(
+            self,
+            ContractError::InvalidPrivateKey
+                | ContractError::TimelockNotElapsed { .. }
+                | ContractError::NoPendingValue
+                | ContractError::AlreadyPending
+                | ContractError::AlreadySet
+                | ContractError::MaxFeeExceeded { .. }
+                | ContractError::ZeroFeeRecipient
+                | ContractError::GateNotEligible { .. }
+        )
     }
 }
 
@@ -98,4 +182,78 @@ mod tests {
         let error = ContractError::TransactionFailed("reverted".to_string());
         assert!(!error.is_user_error());
     }
+
+    #[test]
+    fn test_error_display_timelock_not_elapsed() {
+        let error = ContractError::TimelockNotElapsed {
+            valid_at: 1700000000,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Timelock has not elapsed: pending value is valid at 1700000000"
+        );
+    }
+
+    #[test]
+    fn test_is_user_error_admin_preflight() {
+        assert!(ContractError::TimelockNotElapsed { valid_at: 1 }.is_user_error());
+        assert!(ContractError::NoPendingValue.is_user_error());
+        assert!(ContractError::AlreadyPending.is_user_error());
+        assert!(ContractError::AlreadySet.is_user_error());
+        assert!(ContractError::ZeroFeeRecipient.is_user_error());
+        assert!(ContractError::MaxFeeExceeded {
+            fee: U256::from(2),
+            max: U256::from(1),
+        }
+        .is_user_error());
+        assert!(!ContractError::NoPendingValue.is_retryable());
+    }
+
+    #[test]
+    fn test_gate_not_eligible() {
+        let error = ContractError::GateNotEligible {
+            gate: Address::repeat_byte(0x01),
+            account: Address::repeat_byte(0x02),
+        };
+        assert!(error.is_user_error());
+        assert!(!error.is_retryable());
+        assert!(error.to_string().contains("not eligible"));
+    }
+
+    #[test]
+    fn test_slippage_exceeded() {
+        let error = ContractError::SlippageExceeded {
+            previewed: U256::from(99),
+            limit: U256::from(100),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Slippage exceeded: previewed 99 shares, limit 100"
+        );
+        // Caused by a share price move, not by the call itself.
+        assert!(!error.is_user_error());
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_transaction_timeout() {
+        let error = ContractError::TransactionTimeout {
+            hash: B256::repeat_byte(0x01),
+        };
+        assert!(error.to_string().starts_with("Transaction 0x0101"));
+        assert!(error.to_string().ends_with("not confirmed before timeout"));
+        // Resending blindly could execute the call twice.
+        assert!(!error.is_retryable());
+        assert!(!error.is_user_error());
+    }
+
+    #[test]
+    fn test_user_operation_timeout() {
+        let error = ContractError::UserOperationTimeout {
+            hash: B256::repeat_byte(0x02),
+        };
+        assert!(error.to_string().starts_with("User operation 0x0202"));
+        assert!(!error.is_retryable());
+        assert!(!error.is_user_error());
+    }
 }
@@ -0,0 +1,488 @@
+//! Event log decoding for MetaMorpho vaults and Morpho Blue markets.
+//!
+//! Logs from transaction receipts or `eth_getLogs` ranges are decoded into
+//! [`MorphoEvent`] variants, wrapped in a [`DecodedEvent`] carrying the emitter and
+//! block position. Logs that are not one of the supported events are skipped.
+//!
+//! Supported events:
+//! - MetaMorpho: `Deposit`, `Withdraw`, `ReallocateSupply`, `ReallocateWithdraw`,
+//!   `SetCap`, `AccrueInterest`
+//! - Morpho Blue: `AccrueInterest`
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use futures::StreamExt;
+//! use morpho_rs_contracts::events::{decode_receipt, stream_events, MorphoEvent};
+//!
+//! // Events emitted by a transaction
+//! for event in decode_receipt(&receipt) {
+//!     println!("{:?}", event.event);
+//! }
+//!
+//! // Historical and live vault events
+//! let mut events = stream_events(&provider, vault, 19_000_000).await?;
+//! while let Some(event) = events.next().await {
+//!     if let MorphoEvent::Deposit(deposit) = event.event {
+//!         println!("{} deposited {}", deposit.owner, deposit.assets);
+//!     }
+//! }
+//! ```
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log, TransactionReceipt};
+use alloy::sol;
+use alloy::sol_types::SolEvent;
+use futures::{future, stream, Stream, StreamExt};
+
+use crate::error::{ContractError, Result};
+
+sol! {
+    /// MetaMorpho vault events.
+    interface IMetaMorphoEvents {
+        event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares);
+        event Withdraw(
+            address indexed sender,
+            address indexed receiver,
+            address indexed owner,
+            uint256 assets,
+            uint256 shares
+        );
+        event ReallocateSupply(address indexed caller, bytes32 indexed id, uint256 suppliedAssets, uint256 suppliedShares);
+        event ReallocateWithdraw(address indexed caller, bytes32 indexed id, uint256 withdrawnAssets, uint256 withdrawnShares);
+        event SetCap(address indexed caller, bytes32 indexed id, uint256 cap);
+        event AccrueInterest(uint256 newTotalAssets, uint256 feeShares);
+    }
+
+    /// Morpho Blue market events.
+    interface IMorphoEvents {
+        event AccrueInterest(bytes32 indexed id, uint256 prevBorrowRate, uint256 interest, uint256 feeShares);
+    }
+}
+
+/// Assets deposited into a vault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositEvent {
+    /// Account that sent the assets.
+    pub sender: Address,
+    /// Account that received the shares.
+    pub owner: Address,
+    /// Assets deposited.
+    pub assets: U256,
+    /// Shares minted.
+    pub shares: U256,
+}
+
+/// Assets withdrawn from a vault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawEvent {
+    /// Account that initiated the withdrawal.
+    pub sender: Address,
+    /// Account that received the assets.
+    pub receiver: Address,
+    /// Account whose shares were burned.
+    pub owner: Address,
+    /// Assets withdrawn.
+    pub assets: U256,
+    /// Shares burned.
+    pub shares: U256,
+}
+
+/// Assets supplied to a market during a reallocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReallocateSupplyEvent {
+    /// Allocator that called `reallocate`.
+    pub caller: Address,
+    /// Market id.
+    pub market_id: B256,
+    /// Assets supplied.
+    pub supplied_assets: U256,
+    /// Supply shares received.
+    pub supplied_shares: U256,
+}
+
+/// Assets withdrawn from a market during a reallocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReallocateWithdrawEvent {
+    /// Allocator that called `reallocate`.
+    pub caller: Address,
+    /// Market id.
+    pub market_id: B256,
+    /// Assets withdrawn.
+    pub withdrawn_assets: U256,
+    /// Supply shares burned.
+    pub withdrawn_shares: U256,
+}
+
+/// A vault's supply cap on a market was set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCapEvent {
+    /// Account that accepted or lowered the cap.
+    pub caller: Address,
+    /// Market id.
+    pub market_id: B256,
+    /// New supply cap.
+    pub cap: U256,
+}
+
+/// Interest accrued by a vault and the fee shares minted for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultAccrueInterestEvent {
+    /// Total assets after accrual.
+    pub new_total_assets: U256,
+    /// Fee shares minted to the fee recipient.
+    pub fee_shares: U256,
+}
+
+/// Interest accrued by a Morpho Blue market.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketAccrueInterestEvent {
+    /// Market id.
+    pub market_id: B256,
+    /// Borrow rate (per second, WAD-scaled) used for the accrual.
+    pub prev_borrow_rate: U256,
+    /// Interest accrued.
+    pub interest: U256,
+    /// Fee shares minted to the protocol fee recipient.
+    pub fee_shares: U256,
+}
+
+/// A decoded MetaMorpho or Morpho Blue event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MorphoEvent {
+    /// MetaMorpho `Deposit`.
+    Deposit(DepositEvent),
+    /// MetaMorpho `Withdraw`.
+    Withdraw(WithdrawEvent),
+    /// MetaMorpho `ReallocateSupply`.
+    ReallocateSupply(ReallocateSupplyEvent),
+    /// MetaMorpho `ReallocateWithdraw`.
+    ReallocateWithdraw(ReallocateWithdrawEvent),
+    /// MetaMorpho `SetCap`.
+    SetCap(SetCapEvent),
+    /// MetaMorpho `AccrueInterest`.
+    VaultAccrueInterest(VaultAccrueInterestEvent),
+    /// Morpho Blue `AccrueInterest`.
+    MarketAccrueInterest(MarketAccrueInterestEvent),
+}
+
+/// A decoded event with its position on chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedEvent {
+    /// Contract that emitted the event.
+    pub address: Address,
+    /// Block number (None for pending logs).
+    pub block_number: Option<u64>,
+    /// Transaction hash (None for pending logs).
+    pub transaction_hash: Option<B256>,
+    /// Log index within the block (None for pending logs).
+    pub log_index: Option<u64>,
+    /// The decoded event.
+    pub event: MorphoEvent,
+}
+
+/// Topic-0 hashes of all supported events.
+pub fn event_signatures() -> Vec<B256> {
+    vec![
+        IMetaMorphoEvents::Deposit::SIGNATURE_HASH,
+        IMetaMorphoEvents::Withdraw::SIGNATURE_HASH,
+        IMetaMorphoEvents::ReallocateSupply::SIGNATURE_HASH,
+        IMetaMorphoEvents::ReallocateWithdraw::SIGNATURE_HASH,
+        IMetaMorphoEvents::SetCap::SIGNATURE_HASH,
+        IMetaMorphoEvents::AccrueInterest::SIGNATURE_HASH,
+        IMorphoEvents::AccrueInterest::SIGNATURE_HASH,
+    ]
+}
+
+/// Decode a single log.
+///
+/// Returns `None` if the log is not a supported event or cannot be decoded.
+pub fn decode_log(log: &Log) -> Option<DecodedEvent> {
+    let topic0 = *log.topic0()?;
+
+    let event = if topic0 == IMetaMorphoEvents::Deposit::SIGNATURE_HASH {
+        let e = log
+            .log_decode::<IMetaMorphoEvents::Deposit>()
+            .ok()?
+            .inner
+            .data;
+        MorphoEvent::Deposit(DepositEvent {
+            sender: e.sender,
+            owner: e.owner,
+            assets: e.assets,
+            shares: e.shares,
+        })
+    } else if topic0 == IMetaMorphoEvents::Withdraw::SIGNATURE_HASH {
+        let e = log
+            .log_decode::<IMetaMorphoEvents::Withdraw>()
+            .ok()?
+            .inner
+            .data;
+        MorphoEvent::Withdraw(WithdrawEvent {
+            sender: e.sender,
+            receiver: e.receiver,
+            owner: e.owner,
+            assets: e.assets,
+            shares: e.shares,
+        })
+    } else if topic0 == IMetaMorphoEvents::ReallocateSupply::SIGNATURE_HASH {
+        let e = log
+            .log_decode::<IMetaMorphoEvents::ReallocateSupply>()
+            .ok()?
+            .inner
+            .data;
+        MorphoEvent::ReallocateSupply(ReallocateSupplyEvent {
+            caller: e.caller,
+            market_id: e.id,
+            supplied_assets: e.suppliedAssets,
+            supplied_shares: e.suppliedShares,
+        })
+    } else if topic0 == IMetaMorphoEvents::ReallocateWithdraw::SIGNATURE_HASH {
+        let e = log
+            .log_decode::<IMetaMorphoEvents::ReallocateWithdraw>()
+            .ok()?
+            .inner
+            .data;
+        MorphoEvent::ReallocateWithdraw(ReallocateWithdrawEvent {
+            caller: e.caller,
+            market_id: e.id,
+            withdrawn_assets: e.withdrawnAssets,
+            withdrawn_shares: e.withdrawnShares,
+        })
+    } else if topic0 == IMetaMorphoEvents::SetCap::SIGNATURE_HASH {
+        let e = log
+            .log_decode::<IMetaMorphoEvents::SetCap>()
+            .ok()?
+            .inner
+            .data;
+        MorphoEvent::SetCap(SetCapEvent {
+            caller: e.caller,
+            market_id: e.id,
+            cap: e.cap,
+        })
+    } else if topic0 == IMetaMorphoEvents::AccrueInterest::SIGNATURE_HASH {
+        let e = log
+            .log_decode::<IMetaMorphoEvents::AccrueInterest>()
+            .ok()?
+            .inner
+            .data;
+        MorphoEvent::VaultAccrueInterest(VaultAccrueInterestEvent {
+            new_total_assets: e.newTotalAssets,
+            fee_shares: e.feeShares,
+        })
+    } else if topic0 == IMorphoEvents::AccrueInterest::SIGNATURE_HASH {
+        let e = log
+            .log_decode::<IMorphoEvents::AccrueInterest>()
+            .ok()?
+            .inner
+            .data;
+        MorphoEvent::MarketAccrueInterest(MarketAccrueInterestEvent {
+            market_id: e.id,
+            prev_borrow_rate: e.prevBorrowRate,
+            interest: e.interest,
+            fee_shares: e.feeShares,
+        })
+    } else {
+        return None;
+    };
+
+    Some(DecodedEvent {
+        address: log.address(),
+        block_number: log.block_number,
+        transaction_hash: log.transaction_hash,
+        log_index: log.log_index,
+        event,
+    })
+}
+
+/// Decode all supported events emitted in a transaction.
+pub fn decode_receipt(receipt: &TransactionReceipt) -> Vec<DecodedEvent> {
+    receipt.inner.logs().iter().filter_map(decode_log).collect()
+}
+
+/// Build a log filter for the supported events emitted by `addresses`.
+///
+/// Include the Morpho Blue address to receive market `AccrueInterest` events.
+pub fn events_filter(addresses: Vec<Address>) -> Filter {
+    Filter::new()
+        .address(addresses)
+        .event_signature(event_signatures())
+}
+
+/// Fetch and decode events emitted by `addresses` in a block range (`eth_getLogs`).
+pub async fn get_events<P: Provider>(
+    provider: &P,
+    addresses: Vec<Address>,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<DecodedEvent>> {
+    let filter = events_filter(addresses)
+        .from_block(from_block)
+        .to_block(to_block);
+    let logs = provider
+        .get_logs(&filter)
+        .await
+        .map_err(|e| ContractError::RpcConnection(format!("Failed to get logs: {}", e)))?;
+    Ok(logs.iter().filter_map(decode_log).collect())
+}
+
+/// Stream a vault's events from `from_block` onwards.
+///
+/// Historical events up to the current block are fetched with `eth_getLogs` and
+/// yielded first, followed by new events as the provider's log filter reports them.
+/// The live filter is installed before the backfill, so no block is missed in between.
+pub async fn stream_events<P: Provider>(
+    provider: &P,
+    vault: Address,
+    from_block: u64,
+) -> Result<impl Stream<Item = DecodedEvent>> {
+    let live = provider
+        .watch_logs(&events_filter(vec![vault]))
+        .await
+        .map_err(|e| ContractError::RpcConnection(format!("Failed to watch logs: {}", e)))?;
+
+    let latest = provider
+        .get_block_number()
+        .await
+        .map_err(|e| ContractError::RpcConnection(format!("Failed to get block number: {}", e)))?;
+
+    let history = if from_block <= latest {
+        get_events(provider, vec![vault], from_block, latest).await?
+    } else {
+        Vec::new()
+    };
+
+    let live = live
+        .into_stream()
+        .flat_map(stream::iter)
+        .filter_map(move |log| {
+            let is_new = log.block_number.is_none_or(|block| block > latest);
+            future::ready(if is_new { decode_log(&log) } else { None })
+        });
+
+    Ok(stream::iter(history).chain(live))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::LogData;
+
+    const VAULT: Address = Address::repeat_byte(0xbe);
+
+    fn rpc_log(data: LogData, block_number: u64) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: VAULT,
+                data,
+            },
+            block_number: Some(block_number),
+            transaction_hash: Some(B256::repeat_byte(0x01)),
+            log_index: Some(3),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_deposit() {
+        let event = IMetaMorphoEvents::Deposit {
+            sender: Address::repeat_byte(0x01),
+            owner: Address::repeat_byte(0x02),
+            assets: U256::from(1_000_000u64),
+            shares: U256::from(990_000u64),
+        };
+
+        let decoded = decode_log(&rpc_log(event.encode_log_data(), 42)).unwrap();
+
+        assert_eq!(decoded.address, VAULT);
+        assert_eq!(decoded.block_number, Some(42));
+        assert_eq!(decoded.log_index, Some(3));
+        assert_eq!(
+            decoded.event,
+            MorphoEvent::Deposit(DepositEvent {
+                sender: Address::repeat_byte(0x01),
+                owner: Address::repeat_byte(0x02),
+                assets: U256::from(1_000_000u64),
+                shares: U256::from(990_000u64),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_reallocate_and_set_cap() {
+        let id = B256::repeat_byte(0xaa);
+        let supply = IMetaMorphoEvents::ReallocateSupply {
+            caller: Address::repeat_byte(0x05),
+            id,
+            suppliedAssets: U256::from(500u64),
+            suppliedShares: U256::from(499u64),
+        };
+        let cap = IMetaMorphoEvents::SetCap {
+            caller: Address::repeat_byte(0x06),
+            id,
+            cap: U256::from(1_000u64),
+        };
+
+        match decode_log(&rpc_log(supply.encode_log_data(), 1))
+            .unwrap()
+            .event
+        {
+            MorphoEvent::ReallocateSupply(e) => {
+                assert_eq!(e.market_id, id);
+                assert_eq!(e.supplied_assets, U256::from(500u64));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        match decode_log(&rpc_log(cap.encode_log_data(), 1))
+            .unwrap()
+            .event
+        {
+            MorphoEvent::SetCap(e) => assert_eq!(e.cap, U256::from(1_000u64)),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_distinguishes_accrue_interest() {
+        let vault = IMetaMorphoEvents::AccrueInterest {
+            newTotalAssets: U256::from(10u64),
+            feeShares: U256::from(1u64),
+        };
+        let market = IMorphoEvents::AccrueInterest {
+            id: B256::repeat_byte(0xcc),
+            prevBorrowRate: U256::from(2u64),
+            interest: U256::from(3u64),
+            feeShares: U256::from(4u64),
+        };
+
+        assert!(matches!(
+            decode_log(&rpc_log(vault.encode_log_data(), 1))
+                .unwrap()
+                .event,
+            MorphoEvent::VaultAccrueInterest(_)
+        ));
+        assert!(matches!(
+            decode_log(&rpc_log(market.encode_log_data(), 1)).unwrap().event,
+            MorphoEvent::MarketAccrueInterest(MarketAccrueInterestEvent { interest, .. })
+                if interest == U256::from(3u64)
+        ));
+    }
+
+    #[test]
+    fn test_decode_unknown_log_is_skipped() {
+        let data = LogData::new_unchecked(vec![B256::repeat_byte(0xff)], Default::default());
+        assert!(decode_log(&rpc_log(data, 1)).is_none());
+
+        let empty = LogData::new_unchecked(vec![], Default::default());
+        assert!(decode_log(&rpc_log(empty, 1)).is_none());
+    }
+
+    #[test]
+    fn test_event_signatures_are_distinct() {
+        let mut signatures = event_signatures();
+        signatures.sort();
+        signatures.dedup();
+        assert_eq!(signatures.len(), 7);
+    }
+}
@@ -0,0 +1,191 @@
+//! Vault V2 deposit gates and signature allowlists.
+//!
+//! A V2 vault can restrict deposits with two gates: its `receiveSharesGate` must allow
+//! the share receiver, and its `sendAssetsGate` must allow the depositor. A zero gate
+//! allows everyone. [`VaultV2TransactionClient::deposit_gates`] reads both gates and
+//! checks an account against them.
+//!
+//! Some gates are signature allowlists ([`ISignatureGate`]): the curator allowlists
+//! accounts, and an allowlisted account passes the gate once it registers an EIP-712
+//! [`Registration`] signed with its key. The gate publishes its EIP-712 domain through
+//! EIP-5267 `eip712Domain()`, and each registration consumes the account's nonce.
+//!
+//! [`VaultV2TransactionClient::deposit_gates`]: crate::VaultV2TransactionClient::deposit_gates
+
+use alloy::primitives::{Address, FixedBytes, U256};
+use alloy::sol;
+use alloy::sol_types::Eip712Domain;
+
+sol! {
+    #[sol(rpc)]
+    interface IReceiveSharesGate {
+        function canReceiveShares(address account) external view returns (bool);
+    }
+
+    #[sol(rpc)]
+    interface ISendAssetsGate {
+        function canSendAssets(address account) external view returns (bool);
+    }
+
+    #[sol(rpc)]
+    interface ISignatureGate {
+        function eip712Domain() external view returns (
+            bytes1 fields,
+            string name,
+            string version,
+            uint256 chainId,
+            address verifyingContract,
+            bytes32 salt,
+            uint256[] extensions
+        );
+        function isAllowlisted(address account) external view returns (bool);
+        function nonces(address account) external view returns (uint256);
+        function register(address account, uint256 deadline, bytes signature) external;
+    }
+
+    /// Message an allowlisted account signs to register with a signature gate.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Registration {
+        address account;
+        uint256 nonce;
+        uint256 deadline;
+    }
+}
+
+/// Which check a gate performs on a deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateKind {
+    /// `receiveSharesGate`, checked against the share receiver.
+    ReceiveShares,
+    /// `sendAssetsGate`, checked against the depositor.
+    SendAssets,
+}
+
+/// One gate a deposit has to pass, and whether an account passes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GateRequirement {
+    /// Which check the gate performs.
+    pub kind: GateKind,
+    /// Gate contract.
+    pub gate: Address,
+    /// Account checked against the gate.
+    pub account: Address,
+    /// Whether the gate currently allows the account.
+    pub allowed: bool,
+    /// Whether the account is on the gate's signature allowlist (`None` if the gate is
+    /// not a signature allowlist).
+    pub allowlisted: Option<bool>,
+}
+
+impl GateRequirement {
+    /// Returns true if the account has to register a signature before depositing.
+    pub fn needs_signature(&self) -> bool {
+        !self.allowed && self.allowlisted == Some(true)
+    }
+
+    /// Returns true if the account passes the gate, now or after registering.
+    pub fn is_eligible(&self) -> bool {
+        self.allowed || self.allowlisted == Some(true)
+    }
+}
+
+/// Build an EIP-712 domain from the fields returned by EIP-5267 `eip712Domain()`.
+///
+/// Bits of `fields` mark which of name, version, chain id, verifying contract, and
+/// salt are part of the domain.
+pub(crate) fn domain_from_eip5267(
+    fields: FixedBytes<1>,
+    name: String,
+    version: String,
+    chain_id: U256,
+    verifying_contract: Address,
+    salt: FixedBytes<32>,
+) -> Eip712Domain {
+    let has = |bit: u8| fields[0] & (1 << bit) != 0;
+    Eip712Domain::new(
+        has(0).then(|| name.into()),
+        has(1).then(|| version.into()),
+        has(2).then_some(chain_id),
+        has(3).then_some(verifying_contract),
+        has(4).then_some(salt),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::SignerSync;
+    use alloy::sol_types::SolStruct;
+
+    #[test]
+    fn test_requirement_eligibility() {
+        let requirement = GateRequirement {
+            kind: GateKind::ReceiveShares,
+            gate: Address::repeat_byte(0x01),
+            account: Address::repeat_byte(0x02),
+            allowed: false,
+            allowlisted: Some(true),
+        };
+        assert!(requirement.needs_signature());
+        assert!(requirement.is_eligible());
+
+        let allowed = GateRequirement {
+            allowed: true,
+            ..requirement
+        };
+        assert!(!allowed.needs_signature());
+        assert!(allowed.is_eligible());
+
+        let plain_gate = GateRequirement {
+            allowlisted: None,
+            ..requirement
+        };
+        assert!(!plain_gate.needs_signature());
+        assert!(!plain_gate.is_eligible());
+    }
+
+    #[test]
+    fn test_domain_from_eip5267() {
+        let gate = Address::repeat_byte(0x03);
+        // name, version, chain id, verifying contract; no salt
+        let domain = domain_from_eip5267(
+            FixedBytes([0x0f]),
+            "Allowlist".to_string(),
+            "1".to_string(),
+            U256::from(1),
+            gate,
+            FixedBytes::ZERO,
+        );
+        assert_eq!(domain.name.as_deref(), Some("Allowlist"));
+        assert_eq!(domain.version.as_deref(), Some("1"));
+        assert_eq!(domain.chain_id, Some(U256::from(1)));
+        assert_eq!(domain.verifying_contract, Some(gate));
+        assert_eq!(domain.salt, None);
+    }
+
+    #[test]
+    fn test_registration_signature_recovers_account() {
+        let signer = PrivateKeySigner::random();
+        let registration = Registration {
+            account: signer.address(),
+            nonce: U256::ZERO,
+            deadline: U256::from(1_700_000_000u64),
+        };
+        let domain = domain_from_eip5267(
+            FixedBytes([0x0f]),
+            "Allowlist".to_string(),
+            "1".to_string(),
+            U256::from(1),
+            Address::repeat_byte(0x03),
+            FixedBytes::ZERO,
+        );
+
+        let hash = registration.eip712_signing_hash(&domain);
+        let signature = signer.sign_hash_sync(&hash).unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&hash).unwrap(),
+            signer.address()
+        );
+    }
+}
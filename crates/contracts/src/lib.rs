@@ -24,19 +24,59 @@
 //! }
 //! ```
 
+pub mod bundler;
 pub mod erc20;
 pub mod erc4626;
 pub mod erc4626_client;
 pub mod error;
+pub mod events;
+pub mod gate;
+pub mod metamorpho;
+pub mod metrics;
+pub mod morpho_blue;
+pub mod morpho_vault_v2;
+pub mod oracle;
+pub mod outcome;
+mod platform;
+pub mod pre_liquidation;
 pub mod prepared_call;
 pub mod provider;
+pub mod public_allocator;
+pub mod rpc;
+#[cfg(feature = "safe")]
+pub mod safe;
+pub mod urd;
+pub mod user_operation;
 pub mod vault_tx_client;
 pub mod vault_v1;
 pub mod vault_v2;
 
+pub use bundler::{leverage_bundle, BundlerClient, LeverageParams, LeverageSwap};
+pub use erc20::{Erc20Client, TokenMetadata};
 pub use erc4626_client::Erc4626Client;
 pub use error::{ContractError, Result};
-pub use prepared_call::PreparedCall;
+pub use events::{
+    decode_log, decode_receipt, get_events, stream_events, DecodedEvent, MorphoEvent,
+};
+pub use gate::{GateKind, GateRequirement};
+pub use metamorpho::{market_id, MarketAllocation, MarketParams, MAX_FEE};
+pub use metrics::{Metrics, NoopMetrics};
+pub use morpho_vault_v2::TimelockedAction;
+pub use oracle::{PriceDeviation, ReportedPrice, DEFAULT_MAX_PRICE_DEVIATION};
+pub use outcome::{DepositOutcome, MigrationOutcome, WithdrawOutcome};
+pub use pre_liquidation::{CreatedPreLiquidation, PreLiquidationClient};
+pub use prepared_call::{
+    cancel_transaction, speed_up_transaction, CallDescription, PreparedCall, SendOptions,
+    DEFAULT_FEE_BUMP_PERCENT, MIN_FEE_BUMP_PERCENT,
+};
 pub use provider::HttpProvider;
-pub use vault_v1::VaultV1TransactionClient;
+pub use public_allocator::PublicAllocatorClient;
+pub use rpc::{FailoverTransport, RpcEndpointStatus};
+#[cfg(feature = "safe")]
+pub use safe::{SafeProposal, SafeProposer, SafeTx};
+pub use urd::UniversalRewardsDistributorClient;
+pub use user_operation::{
+    AccountCall, SmartAccount, UserOperationClient, UserOperationConfig, ENTRY_POINT_V07,
+};
+pub use vault_v1::{PendingValue, SubmitCapArgs, VaultV1TransactionClient};
 pub use vault_v2::VaultV2TransactionClient;
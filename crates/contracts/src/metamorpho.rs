@@ -0,0 +1,67 @@
+//! MetaMorpho (V1 vault) admin interface definitions.
+
+use alloy::primitives::{keccak256, B256, U256};
+use alloy::sol;
+use alloy::sol_types::SolValue;
+
+sol! {
+    /// Morpho Blue market parameters.
+    #[derive(Debug, PartialEq, Eq)]
+    struct MarketParams {
+        address loanToken;
+        address collateralToken;
+        address oracle;
+        address irm;
+        uint256 lltv;
+    }
+
+    /// Target supply of a vault in one market, as passed to `reallocate`.
+    #[derive(Debug, PartialEq, Eq)]
+    struct MarketAllocation {
+        MarketParams marketParams;
+        uint256 assets;
+    }
+
+    #[sol(rpc)]
+    interface IMetaMorpho {
+        // Curator functions
+        function submitCap(MarketParams memory marketParams, uint256 newSupplyCap) external;
+        function acceptCap(MarketParams memory marketParams) external;
+
+        // Allocator functions
+        function setSupplyQueue(bytes32[] calldata newSupplyQueue) external;
+        function updateWithdrawQueue(uint256[] calldata indexes) external;
+        function reallocate(MarketAllocation[] calldata allocations) external;
+
+        // Owner functions
+        function setFee(uint256 newFee) external;
+        function setFeeRecipient(address newFeeRecipient) external;
+
+        // View functions
+        function MORPHO() external view returns (address);
+        function asset() external view returns (address);
+        function totalAssets() external view returns (uint256);
+        function totalSupply() external view returns (uint256);
+        function lastTotalAssets() external view returns (uint256);
+        function supplyQueue(uint256 index) external view returns (bytes32);
+        function supplyQueueLength() external view returns (uint256);
+        function withdrawQueue(uint256 index) external view returns (bytes32);
+        function withdrawQueueLength() external view returns (uint256);
+        function owner() external view returns (address);
+        function curator() external view returns (address);
+        function isAllocator(address target) external view returns (bool);
+        function timelock() external view returns (uint256);
+        function fee() external view returns (uint96);
+        function feeRecipient() external view returns (address);
+        function config(bytes32 id) external view returns (uint184 cap, bool enabled, uint64 removableAt);
+        function pendingCap(bytes32 id) external view returns (uint192 value, uint64 validAt);
+    }
+}
+
+/// Maximum performance fee accepted by MetaMorpho (50%, WAD-scaled).
+pub const MAX_FEE: U256 = U256::from_limbs([500_000_000_000_000_000, 0, 0, 0]);
+
+/// Compute the Morpho Blue market id (`keccak256(abi.encode(marketParams))`).
+pub fn market_id(params: &MarketParams) -> B256 {
+    keccak256(params.abi_encode())
+}
@@ -0,0 +1,73 @@
+//! Metrics hooks for API and transaction clients.
+//!
+//! This module defines the [`Metrics`] trait, a set of callbacks that the API
+//! clients (in `morpho-rs-api`) and the transaction clients in this crate invoke
+//! for requests, retries, GraphQL errors, transaction submissions, and
+//! confirmations. Every method has a no-op default, so implementors only need to
+//! override the events they care about.
+//!
+//! Implementations can forward these events to any metrics backend (Prometheus,
+//! StatsD, the `metrics` crate, ...) to alert on API degradation or stuck
+//! transactions.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//! use std::time::Duration;
+//! use morpho_rs_contracts::Metrics;
+//!
+//! #[derive(Debug, Default)]
+//! struct RequestCounter {
+//!     requests: AtomicU64,
+//! }
+//!
+//! impl Metrics for RequestCounter {
+//!     fn record_api_request(&self, _operation: &str, _latency: Duration, _success: bool) {
+//!         self.requests.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! }
+//! ```
+
+use std::fmt::Debug;
+use std::time::Duration;
+
+use alloy::primitives::Address;
+
+/// Callbacks for client instrumentation.
+///
+/// All methods default to no-ops. Implementations must be cheap and non-blocking,
+/// as they are called inline on the request path.
+pub trait Metrics: Debug + Send + Sync {
+    /// Called once per HTTP attempt against the GraphQL API, with the GraphQL
+    /// operation name, the attempt latency, and whether it produced data.
+    fn record_api_request(&self, _operation: &str, _latency: Duration, _success: bool) {}
+
+    /// Called before a retry attempt is made. `attempt` starts at 1 for the first retry.
+    fn record_api_retry(&self, _operation: &str, _attempt: u32) {}
+
+    /// Called when the API responds with one or more GraphQL errors.
+    fn record_graphql_errors(&self, _operation: &str, _count: usize) {}
+
+    /// Called when a transaction to `to` has been accepted by the RPC node.
+    fn record_tx_submitted(&self, _to: Address) {}
+
+    /// Called when a transaction to `to` has been mined. `latency` is measured from
+    /// submission, and `success` reflects the receipt status.
+    fn record_tx_confirmed(&self, _to: Address, _latency: Duration, _success: bool) {}
+
+    /// Called when a transaction to `to` could not be submitted or its receipt
+    /// could not be retrieved.
+    fn record_tx_failed(&self, _to: Address) {}
+}
+
+/// A [`Metrics`] implementation that discards every event.
+///
+/// This is the default used by all clients.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {}
+
+/// Shared no-op instance used when no metrics sink is configured.
+pub(crate) static NOOP_METRICS: NoopMetrics = NoopMetrics;
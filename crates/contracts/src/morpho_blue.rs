@@ -0,0 +1,41 @@
+//! Morpho Blue, Adaptive Curve IRM, and oracle interface definitions.
+
+use alloy::sol;
+
+sol! {
+    #[sol(rpc)]
+    interface IMorpho {
+        function market(bytes32 id) external view returns (
+            uint128 totalSupplyAssets,
+            uint128 totalSupplyShares,
+            uint128 totalBorrowAssets,
+            uint128 totalBorrowShares,
+            uint128 lastUpdate,
+            uint128 fee
+        );
+        function idToMarketParams(bytes32 id) external view returns (
+            address loanToken,
+            address collateralToken,
+            address oracle,
+            address irm,
+            uint256 lltv
+        );
+        function position(bytes32 id, address user) external view returns (
+            uint256 supplyShares,
+            uint128 borrowShares,
+            uint128 collateral
+        );
+        function isAuthorized(address authorizer, address authorized) external view returns (bool);
+        function setAuthorization(address authorized, bool newIsAuthorized) external;
+    }
+
+    #[sol(rpc)]
+    interface IAdaptiveCurveIrm {
+        function rateAtTarget(bytes32 id) external view returns (int256);
+    }
+
+    #[sol(rpc)]
+    interface IOracle {
+        function price() external view returns (uint256);
+    }
+}
@@ -0,0 +1,138 @@
+//! Morpho Vault V2 admin interface definitions.
+//!
+//! Most curator actions on a V2 vault are timelocked: the curator first `submit`s the
+//! ABI-encoded call, and anyone can execute the same call once its timelock has
+//! elapsed. [`TimelockedAction`] encodes those calls for submission and revocation.
+
+use alloy::primitives::{Address, Bytes};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+
+sol! {
+    #[sol(rpc)]
+    interface IVaultV2 {
+        // Owner functions
+        function setCurator(address newCurator) external;
+        function setIsSentinel(address account, bool newIsSentinel) external;
+
+        // Timelocked curator functions
+        function setIsAllocator(address account, bool newIsAllocator) external;
+        function setReceiveSharesGate(address newReceiveSharesGate) external;
+        function setSendSharesGate(address newSendSharesGate) external;
+        function setReceiveAssetsGate(address newReceiveAssetsGate) external;
+        function setSendAssetsGate(address newSendAssetsGate) external;
+        function setAdapterRegistry(address newAdapterRegistry) external;
+        function addAdapter(address account) external;
+        function removeAdapter(address account) external;
+
+        // Timelock management
+        function submit(bytes calldata data) external;
+        function revoke(bytes calldata data) external;
+
+        // Allocator functions
+        function setLiquidityAdapterAndData(address newLiquidityAdapter, bytes memory newLiquidityData) external;
+
+        // View functions
+        function owner() external view returns (address);
+        function curator() external view returns (address);
+        function isAllocator(address account) external view returns (bool);
+        function isSentinel(address account) external view returns (bool);
+        function isAdapter(address account) external view returns (bool);
+        function liquidityAdapter() external view returns (address);
+        function liquidityData() external view returns (bytes memory);
+        function receiveSharesGate() external view returns (address);
+        function sendAssetsGate() external view returns (address);
+        function executableAt(bytes memory data) external view returns (uint256);
+        function timelock(bytes4 selector) external view returns (uint256);
+    }
+}
+
+/// A timelocked curator action on a V2 vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelockedAction {
+    /// Grant or revoke the allocator role.
+    SetIsAllocator {
+        /// Account whose role changes.
+        account: Address,
+        /// Whether the account is an allocator.
+        is_allocator: bool,
+    },
+    /// Register an adapter.
+    AddAdapter {
+        /// Adapter address.
+        adapter: Address,
+    },
+    /// Unregister an adapter.
+    RemoveAdapter {
+        /// Adapter address.
+        adapter: Address,
+    },
+    /// Set the gate checked when receiving shares (zero disables it).
+    SetReceiveSharesGate {
+        /// Gate address.
+        gate: Address,
+    },
+    /// Set the gate checked when sending shares (zero disables it).
+    SetSendSharesGate {
+        /// Gate address.
+        gate: Address,
+    },
+    /// Set the gate checked when receiving assets (zero disables it).
+    SetReceiveAssetsGate {
+        /// Gate address.
+        gate: Address,
+    },
+    /// Set the gate checked when sending assets (zero disables it).
+    SetSendAssetsGate {
+        /// Gate address.
+        gate: Address,
+    },
+    /// Set the registry that adapters must belong to (zero disables it).
+    SetAdapterRegistry {
+        /// Registry address.
+        registry: Address,
+    },
+}
+
+impl TimelockedAction {
+    /// ABI-encoded call, as passed to `submit`, `revoke`, and `executableAt`.
+    pub fn calldata(&self) -> Bytes {
+        let data = match *self {
+            TimelockedAction::SetIsAllocator {
+                account,
+                is_allocator,
+            } => IVaultV2::setIsAllocatorCall {
+                account,
+                newIsAllocator: is_allocator,
+            }
+            .abi_encode(),
+            TimelockedAction::AddAdapter { adapter } => {
+                IVaultV2::addAdapterCall { account: adapter }.abi_encode()
+            }
+            TimelockedAction::RemoveAdapter { adapter } => {
+                IVaultV2::removeAdapterCall { account: adapter }.abi_encode()
+            }
+            TimelockedAction::SetReceiveSharesGate { gate } => IVaultV2::setReceiveSharesGateCall {
+                newReceiveSharesGate: gate,
+            }
+            .abi_encode(),
+            TimelockedAction::SetSendSharesGate { gate } => IVaultV2::setSendSharesGateCall {
+                newSendSharesGate: gate,
+            }
+            .abi_encode(),
+            TimelockedAction::SetReceiveAssetsGate { gate } => IVaultV2::setReceiveAssetsGateCall {
+                newReceiveAssetsGate: gate,
+            }
+            .abi_encode(),
+            TimelockedAction::SetSendAssetsGate { gate } => IVaultV2::setSendAssetsGateCall {
+                newSendAssetsGate: gate,
+            }
+            .abi_encode(),
+            TimelockedAction::SetAdapterRegistry { registry } => IVaultV2::setAdapterRegistryCall {
+                newAdapterRegistry: registry,
+            }
+            .abi_encode(),
+        };
+        data.into()
+    }
+}
@@ -0,0 +1,192 @@
+//! Oracle price reads and sanity checks.
+//!
+//! Morpho Blue markets price collateral in loan assets through an oracle exposing
+//! `price()`, scaled by `1e36` (adjusted for the two tokens' decimals). Prices reported by
+//! the Morpho API are cached and can lag or disagree with what the oracle returns on-chain.
+//! [`check_oracle_prices`] reads each oracle and returns a [`PriceDeviation`] for every
+//! market whose reported price differs from the on-chain price by more than a relative
+//! threshold.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use alloy::rpc::types::BlockId;
+//! use morpho_rs_contracts::oracle::{check_oracle_prices, ReportedPrice, DEFAULT_MAX_PRICE_DEVIATION};
+//!
+//! let reported = [ReportedPrice { market_id, oracle, price: api_price }];
+//! let deviations =
+//!     check_oracle_prices(&provider, &reported, DEFAULT_MAX_PRICE_DEVIATION, BlockId::latest()).await?;
+//! for deviation in &deviations {
+//!     eprintln!("{deviation}");
+//! }
+//! ```
+
+use std::fmt;
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::BlockId;
+use futures::future::try_join_all;
+
+use crate::error::{ContractError, Result};
+use crate::morpho_blue::IOracle;
+
+/// Default relative deviation (1%) above which a reported price is flagged.
+pub const DEFAULT_MAX_PRICE_DEVIATION: f64 = 0.01;
+
+/// A market's price as reported off-chain (e.g. by the Morpho API).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportedPrice {
+    /// Market the price is for.
+    pub market_id: B256,
+    /// The market's oracle.
+    pub oracle: Address,
+    /// Reported price (collateral/loan, scaled by `1e36`).
+    pub price: U256,
+}
+
+/// A reported price that disagrees with the market's on-chain oracle price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceDeviation {
+    /// Market the price is for.
+    pub market_id: B256,
+    /// The market's oracle.
+    pub oracle: Address,
+    /// Price returned by the oracle on-chain.
+    pub onchain_price: U256,
+    /// Price reported off-chain.
+    pub reported_price: U256,
+    /// Relative deviation of the reported price from the on-chain price (`0.02` = 2%).
+    pub deviation: f64,
+}
+
+impl PriceDeviation {
+    /// Compare a reported price with the on-chain price, returning a deviation if they
+    /// differ by more than `max_deviation` (relative to the on-chain price).
+    pub fn check(
+        reported: &ReportedPrice,
+        onchain_price: U256,
+        max_deviation: f64,
+    ) -> Option<Self> {
+        let deviation = relative_deviation(onchain_price, reported.price);
+        (deviation > max_deviation).then_some(Self {
+            market_id: reported.market_id,
+            oracle: reported.oracle,
+            onchain_price,
+            reported_price: reported.price,
+            deviation,
+        })
+    }
+}
+
+impl fmt::Display for PriceDeviation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "market {} oracle {}: reported price {} deviates {:.2}% from on-chain price {}",
+            self.market_id,
+            self.oracle,
+            self.reported_price,
+            self.deviation * 100.0,
+            self.onchain_price
+        )
+    }
+}
+
+/// Read an oracle's price at `block`.
+pub async fn get_oracle_price<P: Provider>(
+    provider: &P,
+    oracle: Address,
+    block: BlockId,
+) -> Result<U256> {
+    IOracle::new(oracle, provider)
+        .price()
+        .call()
+        .block(block)
+        .await
+        .map_err(|e| ContractError::TransactionFailed(format!("Failed to get oracle price: {}", e)))
+}
+
+/// Read the oracle of every reported price at `block` and return the prices deviating
+/// from it by more than `max_deviation`.
+///
+/// # Errors
+///
+/// Returns [`ContractError::TransactionFailed`] if an oracle read fails.
+pub async fn check_oracle_prices<P: Provider>(
+    provider: &P,
+    reported: &[ReportedPrice],
+    max_deviation: f64,
+    block: BlockId,
+) -> Result<Vec<PriceDeviation>> {
+    let onchain = try_join_all(
+        reported
+            .iter()
+            .map(|r| get_oracle_price(provider, r.oracle, block)),
+    )
+    .await?;
+
+    Ok(reported
+        .iter()
+        .zip(onchain)
+        .filter_map(|(r, price)| PriceDeviation::check(r, price, max_deviation))
+        .collect())
+}
+
+/// Relative deviation of `reported` from `onchain`; infinite if only the on-chain price
+/// is zero.
+fn relative_deviation(onchain: U256, reported: U256) -> f64 {
+    if onchain == reported {
+        return 0.0;
+    }
+    if onchain.is_zero() {
+        return f64::INFINITY;
+    }
+    let diff = if onchain > reported {
+        onchain - reported
+    } else {
+        reported - onchain
+    };
+    u256_to_f64(diff) / u256_to_f64(onchain)
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(f64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reported(price: u128) -> ReportedPrice {
+        ReportedPrice {
+            market_id: B256::repeat_byte(0x11),
+            oracle: Address::repeat_byte(0x22),
+            price: U256::from(price),
+        }
+    }
+
+    #[test]
+    fn test_price_within_threshold() {
+        let onchain = U256::from(1_000_000u64);
+        assert!(PriceDeviation::check(&reported(1_005_000), onchain, 0.01).is_none());
+        assert!(PriceDeviation::check(&reported(1_000_000), onchain, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_price_deviation_flagged() {
+        let onchain = U256::from(1_000_000u64);
+        let deviation = PriceDeviation::check(&reported(970_000), onchain, 0.01).unwrap();
+        assert_eq!(deviation.onchain_price, onchain);
+        assert_eq!(deviation.reported_price, U256::from(970_000u64));
+        assert!((deviation.deviation - 0.03).abs() < 1e-12);
+        assert!(deviation.to_string().contains("3.00%"));
+    }
+
+    #[test]
+    fn test_zero_onchain_price() {
+        let deviation = PriceDeviation::check(&reported(1), U256::ZERO, 0.5).unwrap();
+        assert!(deviation.deviation.is_infinite());
+        assert!(PriceDeviation::check(&reported(0), U256::ZERO, 0.0).is_none());
+    }
+}
@@ -0,0 +1,268 @@
+//! Deposit and withdrawal outcomes decoded from transaction receipts.
+//!
+//! [`DepositOutcome`] and [`WithdrawOutcome`] sum the ERC4626 `Deposit` / `Withdraw`
+//! events a vault emitted in a transaction, so callers get the shares minted or burned
+//! without decoding logs themselves. [`MigrationOutcome`] pairs the two legs of moving
+//! a position from one vault to another.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::DepositOutcome;
+//!
+//! let receipt = client.deposit(vault, amount, receiver).send().await?;
+//! let outcome = DepositOutcome::from_receipt(vault, receipt)?;
+//! println!("minted {} shares for {} assets", outcome.shares_minted, outcome.assets_in);
+//! ```
+
+use alloy::primitives::{Address, U256};
+use alloy::rpc::types::TransactionReceipt;
+
+use crate::error::{ContractError, Result};
+use crate::events::{decode_receipt, DecodedEvent, MorphoEvent};
+
+/// One whole vault share (vault shares have 18 decimals).
+const WAD: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// Result of a confirmed vault deposit.
+#[derive(Debug, Clone)]
+pub struct DepositOutcome {
+    /// Shares minted by the vault.
+    pub shares_minted: U256,
+    /// Assets taken from the sender.
+    pub assets_in: U256,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Assets (in asset units) paid per whole share: `assets_in * 1e18 / shares_minted`.
+    pub effective_share_price: U256,
+    /// The underlying receipt.
+    pub receipt: TransactionReceipt,
+}
+
+impl DepositOutcome {
+    /// Decode the outcome from the `Deposit` events `vault` emitted in `receipt`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::TransactionFailed`] if the transaction reverted or
+    /// the vault emitted no `Deposit` event.
+    pub fn from_receipt(vault: Address, receipt: TransactionReceipt) -> Result<Self> {
+        ensure_success(&receipt)?;
+        let (assets_in, shares_minted) = sum_deposits(vault, &decode_receipt(&receipt))
+            .ok_or_else(|| missing_event("Deposit", vault))?;
+        Ok(Self {
+            shares_minted,
+            assets_in,
+            gas_used: receipt.gas_used,
+            effective_share_price: effective_share_price(assets_in, shares_minted),
+            receipt,
+        })
+    }
+}
+
+/// Result of a confirmed vault withdrawal.
+#[derive(Debug, Clone)]
+pub struct WithdrawOutcome {
+    /// Shares burned by the vault.
+    pub shares_burned: U256,
+    /// Assets sent to the receiver.
+    pub assets_out: U256,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Assets (in asset units) received per whole share: `assets_out * 1e18 / shares_burned`.
+    pub effective_share_price: U256,
+    /// The underlying receipt.
+    pub receipt: TransactionReceipt,
+}
+
+impl WithdrawOutcome {
+    /// Decode the outcome from the `Withdraw` events `vault` emitted in `receipt`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::TransactionFailed`] if the transaction reverted or
+    /// the vault emitted no `Withdraw` event.
+    pub fn from_receipt(vault: Address, receipt: TransactionReceipt) -> Result<Self> {
+        ensure_success(&receipt)?;
+        let (assets_out, shares_burned) = sum_withdrawals(vault, &decode_receipt(&receipt))
+            .ok_or_else(|| missing_event("Withdraw", vault))?;
+        Ok(Self {
+            shares_burned,
+            assets_out,
+            gas_used: receipt.gas_used,
+            effective_share_price: effective_share_price(assets_out, shares_burned),
+            receipt,
+        })
+    }
+}
+
+/// Result of moving a position from one vault to another.
+#[derive(Debug, Clone)]
+pub struct MigrationOutcome {
+    /// Redemption from the source vault.
+    pub withdraw: WithdrawOutcome,
+    /// Deposit into the destination vault.
+    pub deposit: DepositOutcome,
+    /// Whether both legs executed in the same transaction.
+    pub atomic: bool,
+}
+
+impl MigrationOutcome {
+    /// Decode both legs of a migration executed in a single transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::TransactionFailed`] if the transaction reverted or
+    /// either vault emitted no matching event.
+    pub fn from_receipt(
+        from_vault: Address,
+        to_vault: Address,
+        receipt: TransactionReceipt,
+    ) -> Result<Self> {
+        Ok(Self {
+            withdraw: WithdrawOutcome::from_receipt(from_vault, receipt.clone())?,
+            deposit: DepositOutcome::from_receipt(to_vault, receipt)?,
+            atomic: true,
+        })
+    }
+
+    /// Gas used by the migration, counting a shared transaction once.
+    pub fn gas_used(&self) -> u64 {
+        if self.atomic {
+            self.deposit.gas_used
+        } else {
+            self.withdraw.gas_used + self.deposit.gas_used
+        }
+    }
+}
+
+fn ensure_success(receipt: &TransactionReceipt) -> Result<()> {
+    if receipt.status() {
+        Ok(())
+    } else {
+        Err(ContractError::TransactionFailed(format!(
+            "Transaction {} reverted",
+            receipt.transaction_hash
+        )))
+    }
+}
+
+fn missing_event(event: &str, vault: Address) -> ContractError {
+    ContractError::TransactionFailed(format!(
+        "No {} event emitted by vault {} in receipt",
+        event, vault
+    ))
+}
+
+/// Total `(assets, shares)` over the `Deposit` events emitted by `vault`.
+fn sum_deposits(vault: Address, events: &[DecodedEvent]) -> Option<(U256, U256)> {
+    sum_events(vault, events, |event| match event {
+        MorphoEvent::Deposit(e) => Some((e.assets, e.shares)),
+        _ => None,
+    })
+}
+
+/// Total `(assets, shares)` over the `Withdraw` events emitted by `vault`.
+fn sum_withdrawals(vault: Address, events: &[DecodedEvent]) -> Option<(U256, U256)> {
+    sum_events(vault, events, |event| match event {
+        MorphoEvent::Withdraw(e) => Some((e.assets, e.shares)),
+        _ => None,
+    })
+}
+
+fn sum_events(
+    vault: Address,
+    events: &[DecodedEvent],
+    amounts: impl Fn(&MorphoEvent) -> Option<(U256, U256)>,
+) -> Option<(U256, U256)> {
+    events
+        .iter()
+        .filter(|e| e.address == vault)
+        .filter_map(|e| amounts(&e.event))
+        .reduce(|(a1, s1), (a2, s2)| (a1.saturating_add(a2), s1.saturating_add(s2)))
+}
+
+fn effective_share_price(assets: U256, shares: U256) -> U256 {
+    if shares.is_zero() {
+        return U256::ZERO;
+    }
+    assets.saturating_mul(WAD) / shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{DepositEvent, WithdrawEvent};
+
+    const VAULT: Address = Address::repeat_byte(0xbe);
+    const OTHER: Address = Address::repeat_byte(0xcd);
+
+    fn decoded(address: Address, event: MorphoEvent) -> DecodedEvent {
+        DecodedEvent {
+            address,
+            block_number: Some(1),
+            transaction_hash: None,
+            log_index: None,
+            event,
+        }
+    }
+
+    fn deposit(assets: u64, shares: u64) -> MorphoEvent {
+        MorphoEvent::Deposit(DepositEvent {
+            sender: Address::repeat_byte(0x01),
+            owner: Address::repeat_byte(0x01),
+            assets: U256::from(assets),
+            shares: U256::from(shares),
+        })
+    }
+
+    #[test]
+    fn test_sum_deposits_only_counts_vault_events() {
+        let events = vec![
+            decoded(VAULT, deposit(1_000_000, 990_000)),
+            // A deposit into an underlying vault in the same transaction.
+            decoded(OTHER, deposit(5, 5)),
+            decoded(VAULT, deposit(500_000, 495_000)),
+        ];
+
+        assert_eq!(
+            sum_deposits(VAULT, &events),
+            Some((U256::from(1_500_000u64), U256::from(1_485_000u64)))
+        );
+        assert_eq!(sum_withdrawals(VAULT, &events), None);
+    }
+
+    #[test]
+    fn test_sum_withdrawals() {
+        let events = vec![decoded(
+            VAULT,
+            MorphoEvent::Withdraw(WithdrawEvent {
+                sender: Address::repeat_byte(0x01),
+                receiver: Address::repeat_byte(0x02),
+                owner: Address::repeat_byte(0x01),
+                assets: U256::from(2_000u64),
+                shares: U256::from(1_000u64),
+            }),
+        )];
+
+        assert_eq!(
+            sum_withdrawals(VAULT, &events),
+            Some((U256::from(2_000u64), U256::from(1_000u64)))
+        );
+        assert_eq!(sum_deposits(VAULT, &events), None);
+    }
+
+    #[test]
+    fn test_effective_share_price() {
+        // 1.05 USDC (6 decimals) per whole share.
+        let shares = WAD * U256::from(2u64);
+        assert_eq!(
+            effective_share_price(U256::from(2_100_000u64), shares),
+            U256::from(1_050_000u64)
+        );
+        assert_eq!(
+            effective_share_price(U256::from(1u64), U256::ZERO),
+            U256::ZERO
+        );
+    }
+}
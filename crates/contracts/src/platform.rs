@@ -0,0 +1,17 @@
+//! Platform shims for native and `wasm32-unknown-unknown` targets.
+//!
+//! On wasm there is no Tokio timer, so polling delays go through `gloo-timers`.
+
+use std::time::Duration;
+
+/// Sleep for `duration` without blocking the executor.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Sleep for `duration` without blocking the executor.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
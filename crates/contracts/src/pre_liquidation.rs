@@ -0,0 +1,260 @@
+//! Pre-liquidation contract bindings and client.
+//!
+//! Morpho's pre-liquidation factory creates one pre-liquidation contract per market and
+//! parameter set. A borrower opts in by authorizing the contract on Morpho Blue
+//! (`setAuthorization`); from then on anyone can call `preLiquidate` on the contract to
+//! partially liquidate the borrower's position once its LTV passes `preLltv`, with a
+//! smaller incentive and a capped close factor compared to a regular liquidation.
+//!
+//! [`get_pre_liquidations`] lists the contracts a factory created, and
+//! [`PreLiquidationClient::pre_liquidate`] prepares a pre-liquidation. The liquidator
+//! must have approved the market's loan token to the pre-liquidation contract.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::pre_liquidation::get_pre_liquidations;
+//! use morpho_rs_contracts::PreLiquidationClient;
+//!
+//! let created = get_pre_liquidations(&provider, factory, Some(market_id), from, to).await?;
+//!
+//! let client = PreLiquidationClient::new(rpc_url, private_key)?;
+//! let receipt = client
+//!     .pre_liquidate(created[0].address, borrower, U256::ZERO, repaid_shares)
+//!     .send()
+//!     .await?;
+//! ```
+
+use std::sync::Arc;
+
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
+use alloy::sol;
+use alloy::sol_types::SolEvent;
+
+use crate::error::{ContractError, Result};
+use crate::metrics::{Metrics, NoopMetrics};
+use crate::prepared_call::PreparedCall;
+use crate::provider::{connect, HttpProvider};
+
+sol! {
+    /// Parameters of a pre-liquidation contract (WAD-scaled, except the oracle).
+    #[derive(Debug, PartialEq, Eq)]
+    struct PreLiquidationParams {
+        uint256 preLltv;
+        uint256 preLCF1;
+        uint256 preLCF2;
+        uint256 preLIF1;
+        uint256 preLIF2;
+        address preLiquidationOracle;
+    }
+
+    #[sol(rpc)]
+    interface IPreLiquidation {
+        function MORPHO() external view returns (address);
+        function ID() external view returns (bytes32);
+        function preLiquidationParams() external view returns (PreLiquidationParams memory);
+        function preLiquidate(address borrower, uint256 seizedAssets, uint256 repaidShares, bytes calldata data)
+            external returns (uint256, uint256);
+    }
+
+    #[sol(rpc)]
+    interface IPreLiquidationFactory {
+        function MORPHO() external view returns (address);
+        function isPreLiquidation(address preLiquidation) external view returns (bool);
+        function createPreLiquidation(bytes32 id, PreLiquidationParams calldata preLiquidationParams)
+            external returns (address preLiquidation);
+
+        event CreatePreLiquidation(address indexed preLiquidation, bytes32 id, PreLiquidationParams preLiquidationParams);
+    }
+}
+
+/// A pre-liquidation contract created by the factory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedPreLiquidation {
+    /// Pre-liquidation contract.
+    pub address: Address,
+    /// Market the contract pre-liquidates positions in.
+    pub market_id: B256,
+    /// Parameters the contract was created with.
+    pub params: PreLiquidationParams,
+}
+
+/// List the pre-liquidation contracts `factory` created in a block range, optionally
+/// only those for one market (`eth_getLogs`).
+pub async fn get_pre_liquidations<P: Provider>(
+    provider: &P,
+    factory: Address,
+    market_id: Option<B256>,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<CreatedPreLiquidation>> {
+    let filter = Filter::new()
+        .address(factory)
+        .event_signature(IPreLiquidationFactory::CreatePreLiquidation::SIGNATURE_HASH)
+        .from_block(from_block)
+        .to_block(to_block);
+    let logs = provider
+        .get_logs(&filter)
+        .await
+        .map_err(|e| ContractError::RpcConnection(format!("Failed to get logs: {}", e)))?;
+
+    Ok(logs
+        .iter()
+        .filter_map(decode_create_pre_liquidation)
+        .filter(|created| market_id.is_none_or(|id| created.market_id == id))
+        .collect())
+}
+
+/// Decode a factory `CreatePreLiquidation` log.
+fn decode_create_pre_liquidation(log: &Log) -> Option<CreatedPreLiquidation> {
+    let event = log
+        .log_decode::<IPreLiquidationFactory::CreatePreLiquidation>()
+        .ok()?
+        .inner
+        .data;
+    Some(CreatedPreLiquidation {
+        address: event.preLiquidation,
+        market_id: event.id,
+        params: event.preLiquidationParams,
+    })
+}
+
+/// Client for pre-liquidating positions.
+pub struct PreLiquidationClient {
+    provider: HttpProvider,
+    signer_address: Address,
+    metrics: Arc<dyn Metrics>,
+}
+
+impl PreLiquidationClient {
+    /// Create a new pre-liquidation client.
+    pub fn new(rpc_url: &str, private_key: &str) -> Result<Self> {
+        Self::new_with_fallbacks(&[rpc_url], private_key)
+    }
+
+    /// Create a new pre-liquidation client over several RPC endpoints, failing over
+    /// between them.
+    pub fn new_with_fallbacks(rpc_urls: &[&str], private_key: &str) -> Result<Self> {
+        let (provider, signer_address) = connect(rpc_urls, private_key)?;
+
+        Ok(Self {
+            provider,
+            signer_address,
+            metrics: Arc::new(NoopMetrics),
+        })
+    }
+
+    /// Set the metrics sink notified on transaction submission and confirmation.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Get the underlying provider.
+    pub fn provider(&self) -> &HttpProvider {
+        &self.provider
+    }
+
+    /// Get the signer's address.
+    pub fn signer_address(&self) -> Address {
+        self.signer_address
+    }
+
+    /// Create a prepared pre-liquidation of `borrower`.
+    ///
+    /// Exactly one of `seized_assets` (collateral to seize) and `repaid_shares` (borrow
+    /// shares to repay) must be non-zero. The seized collateral is sent to the signer,
+    /// which pays the repaid assets without a callback.
+    pub fn pre_liquidate(
+        &self,
+        pre_liquidation: Address,
+        borrower: Address,
+        seized_assets: U256,
+        repaid_shares: U256,
+    ) -> PreparedCall<'_, IPreLiquidation::preLiquidateCall> {
+        let call = IPreLiquidation::preLiquidateCall {
+            borrower,
+            seizedAssets: seized_assets,
+            repaidShares: repaid_shares,
+            data: Bytes::new(),
+        };
+        PreparedCall::new(pre_liquidation, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Get a pre-liquidation contract's parameters.
+    pub async fn params(&self, pre_liquidation: Address) -> Result<PreLiquidationParams> {
+        IPreLiquidation::new(pre_liquidation, &self.provider)
+            .preLiquidationParams()
+            .call()
+            .await
+            .map_err(|e| {
+                ContractError::RpcConnection(format!(
+                    "Failed to get pre-liquidation params: {}",
+                    e
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_pre_liquidate_prepares_call() {
+        let client = PreLiquidationClient::new("http://localhost:8545", PRIVATE_KEY).unwrap();
+        let pre_liquidation = Address::repeat_byte(0x33);
+        let borrower = Address::repeat_byte(0x01);
+
+        let prepared =
+            client.pre_liquidate(pre_liquidation, borrower, U256::ZERO, U256::from(1_000u64));
+        assert_eq!(prepared.value(), U256::ZERO);
+
+        let (to, call) = prepared.prepare();
+        assert_eq!(to, pre_liquidation);
+        assert_eq!(call.borrower, borrower);
+        assert_eq!(call.seizedAssets, U256::ZERO);
+        assert_eq!(call.repaidShares, U256::from(1_000u64));
+        assert!(call.data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_params_read_failure_is_retryable() {
+        let client = PreLiquidationClient::new("http://localhost:1", PRIVATE_KEY).unwrap();
+        let error = client.params(Address::repeat_byte(0x33)).await.unwrap_err();
+        assert!(matches!(error, ContractError::RpcConnection(_)));
+    }
+
+    #[test]
+    fn test_decode_create_pre_liquidation() {
+        let event = IPreLiquidationFactory::CreatePreLiquidation {
+            preLiquidation: Address::repeat_byte(0x33),
+            id: B256::repeat_byte(0x11),
+            preLiquidationParams: PreLiquidationParams {
+                preLltv: U256::from(800u64),
+                preLCF1: U256::from(1u64),
+                preLCF2: U256::from(2u64),
+                preLIF1: U256::from(3u64),
+                preLIF2: U256::from(4u64),
+                preLiquidationOracle: Address::repeat_byte(0x44),
+            },
+        };
+        let log = Log {
+            inner: alloy::primitives::Log {
+                address: Address::repeat_byte(0x55),
+                data: event.encode_log_data(),
+            },
+            ..Default::default()
+        };
+
+        let created = decode_create_pre_liquidation(&log).unwrap();
+        assert_eq!(created.address, event.preLiquidation);
+        assert_eq!(created.market_id, event.id);
+        assert_eq!(created.params, event.preLiquidationParams);
+    }
+}
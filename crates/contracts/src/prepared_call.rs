@@ -2,17 +2,236 @@
 //!
 //! This module provides `PreparedCall`, a type that represents a transaction
 //! that has been constructed but not yet sent. This enables:
-//! - Direct execution via `.send()`
+//! - Direct execution via `.send()`, or `.send_with(options)` to wait for several
+//!   confirmations and replace stuck transactions with higher fees
 //! - Integration with `safe-rs` `MulticallBuilder::add_typed()`
+//!
+//! [`speed_up_transaction`] and [`cancel_transaction`] replace an already-sent
+//! transaction, e.g. after [`ContractError::TransactionTimeout`].
+//!
+//! [`PreparedCall::describe`] decodes a call into a [`CallDescription`] for logging or
+//! approval prompts before it is signed, and [`PreparedCall::estimate_gas`] estimates its
+//! gas.
 
-use alloy::primitives::{Address, U256};
-use alloy::providers::Provider;
-use alloy::rpc::types::TransactionReceipt;
+use std::fmt;
+use std::time::Duration;
+
+use alloy::consensus::Transaction as _;
+use alloy::dyn_abi::{DynSolType, DynSolValue};
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::{
+    PendingTransactionBuilder, PendingTransactionError, Provider, WalletProvider, WatchTxError,
+};
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
 use alloy::sol_types::SolCall;
+use web_time::Instant;
 
 use crate::error::{ContractError, Result};
+use crate::metrics::{Metrics, NOOP_METRICS};
 use crate::provider::HttpProvider;
 
+/// Default fee increase for replacement transactions, in percent.
+pub const DEFAULT_FEE_BUMP_PERCENT: u64 = 15;
+
+/// Smallest fee increase for replacement transactions, in percent.
+///
+/// Nodes reject replacements that raise fees by less than 10% as underpriced.
+pub const MIN_FEE_BUMP_PERCENT: u64 = 10;
+
+/// How often a replaced transaction is checked while waiting for its replacement.
+const REPLACEMENT_CHECK_INTERVAL: Duration = Duration::from_secs(12);
+
+/// Options controlling how [`PreparedCall::send_with`] submits and tracks a transaction.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use morpho_rs_contracts::SendOptions;
+///
+/// let options = SendOptions::new()
+///     .with_confirmations(3)
+///     .with_timeout(Duration::from_secs(60))
+///     .replaceable(true);
+/// assert_eq!(options.confirmations, 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendOptions {
+    /// Number of confirmations to wait for (1 = included in a block).
+    pub confirmations: u64,
+    /// How long to wait for each submission before treating it as stuck.
+    /// `None` waits indefinitely.
+    pub timeout: Option<Duration>,
+    /// Whether a stuck transaction is replaced by the same call with higher fees.
+    pub replaceable: bool,
+    /// Fee increase applied to each replacement, in percent. Values below
+    /// [`MIN_FEE_BUMP_PERCENT`] are raised to it.
+    pub fee_bump_percent: u64,
+    /// Maximum number of replacements before giving up.
+    pub max_replacements: u32,
+}
+
+impl Default for SendOptions {
+    fn default() -> Self {
+        Self {
+            confirmations: 1,
+            timeout: None,
+            replaceable: false,
+            fee_bump_percent: DEFAULT_FEE_BUMP_PERCENT,
+            max_replacements: 3,
+        }
+    }
+}
+
+impl SendOptions {
+    /// Create options matching [`PreparedCall::send`]: one confirmation, no timeout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of confirmations to wait for.
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Set how long to wait for each submission before treating it as stuck.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Enable or disable fee-bumped replacement of stuck transactions.
+    pub fn replaceable(mut self, replaceable: bool) -> Self {
+        self.replaceable = replaceable;
+        self
+    }
+
+    /// Set the fee increase applied to each replacement, in percent, raised to at least
+    /// [`MIN_FEE_BUMP_PERCENT`].
+    pub fn with_fee_bump_percent(mut self, fee_bump_percent: u64) -> Self {
+        self.fee_bump_percent = fee_bump_percent.max(MIN_FEE_BUMP_PERCENT);
+        self
+    }
+
+    /// Set the maximum number of replacements.
+    pub fn with_max_replacements(mut self, max_replacements: u32) -> Self {
+        self.max_replacements = max_replacements;
+        self
+    }
+}
+
+/// A decoded view of a prepared call, for logs and approval prompts.
+///
+/// Arguments are decoded from the call's ABI encoding against its signature, so they
+/// are positional; parameter names are not part of the signature.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let description = client.deposit(vault, amount, receiver).describe();
+/// println!("{}", description); // deposit(1000000, 0x...) on 0x...
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallDescription {
+    /// Contract the call is sent to
+    pub to: Address,
+    /// Function name, e.g. `deposit`
+    pub function: String,
+    /// Full function signature, e.g. `deposit(uint256,address)`
+    pub signature: &'static str,
+    /// Decoded arguments, in declaration order
+    pub args: Vec<DynSolValue>,
+    /// ETH sent with the call
+    pub value: U256,
+}
+
+impl CallDescription {
+    /// Returns argument `index` if it is an address.
+    pub fn address_arg(&self, index: usize) -> Option<Address> {
+        self.args.get(index).and_then(DynSolValue::as_address)
+    }
+
+    /// Returns argument `index` if it is an unsigned integer.
+    pub fn uint_arg(&self, index: usize) -> Option<U256> {
+        self.args
+            .get(index)
+            .and_then(DynSolValue::as_uint)
+            .map(|(value, _)| value)
+    }
+
+    /// Arguments in human-readable form, as shown by `Display`.
+    pub fn formatted_args(&self) -> Vec<String> {
+        self.args.iter().map(format_sol_value).collect()
+    }
+
+    /// Decodes raw calldata sent to `to` against a known `signature`, e.g.
+    /// `deposit(uint256,address)`.
+    ///
+    /// Returns `None` if the calldata's selector is not the signature's or its arguments
+    /// do not decode.
+    pub fn decode(to: Address, signature: &'static str, data: &[u8], value: U256) -> Option<Self> {
+        let selector = &alloy::primitives::keccak256(signature.as_bytes())[..4];
+        if data.len() < 4 || &data[..4] != selector {
+            return None;
+        }
+        let (function, params) =
+            signature.split_at(signature.find('(').unwrap_or(signature.len()));
+        let args = match DynSolType::parse(params).ok()?.abi_decode_params(&data[4..]).ok()? {
+            DynSolValue::Tuple(values) => values,
+            _ => return None,
+        };
+        Some(Self {
+            to,
+            function: function.to_string(),
+            signature,
+            args,
+            value,
+        })
+    }
+}
+
+impl fmt::Display for CallDescription {
+    /// `function(arg, ...) on 0x...`, followed by the ETH value when non-zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}({}) on {}",
+            self.function,
+            self.formatted_args().join(", "),
+            self.to
+        )?;
+        if !self.value.is_zero() {
+            write!(f, " with {} wei", self.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Human-readable form of a decoded ABI value.
+fn format_sol_value(value: &DynSolValue) -> String {
+    match value {
+        DynSolValue::Bool(b) => b.to_string(),
+        DynSolValue::Int(i, _) => i.to_string(),
+        DynSolValue::Uint(u, _) => u.to_string(),
+        DynSolValue::Address(a) => a.to_string(),
+        DynSolValue::Function(f) => f.to_string(),
+        DynSolValue::FixedBytes(word, size) => {
+            format!("0x{}", alloy::hex::encode(&word[..*size]))
+        }
+        DynSolValue::Bytes(bytes) => format!("0x{}", alloy::hex::encode(bytes)),
+        DynSolValue::String(s) => format!("{:?}", s),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            let values: Vec<String> = values.iter().map(format_sol_value).collect();
+            format!("[{}]", values.join(", "))
+        }
+        DynSolValue::Tuple(values) => {
+            let values: Vec<String> = values.iter().map(format_sol_value).collect();
+            format!("({})", values.join(", "))
+        }
+    }
+}
+
 /// A prepared transaction that can be inspected, executed, or used with MulticallBuilder.
 ///
 /// This type is generic over the `SolCall` type, allowing type-safe integration
@@ -33,6 +252,7 @@ pub struct PreparedCall<'a, C: SolCall> {
     call: C,
     value: U256,
     provider: &'a HttpProvider,
+    metrics: &'a dyn Metrics,
 }
 
 impl<'a, C: SolCall> PreparedCall<'a, C> {
@@ -43,9 +263,16 @@ impl<'a, C: SolCall> PreparedCall<'a, C> {
             call,
             value,
             provider,
+            metrics: &NOOP_METRICS,
         }
     }
 
+    /// Attach a metrics sink that is notified when the call is sent and confirmed.
+    pub fn with_metrics(mut self, metrics: &'a dyn Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
     /// Consumes self and returns `(address, call)` for `MulticallBuilder::add_typed()`.
     ///
     /// # Example
@@ -68,25 +295,359 @@ impl<'a, C: SolCall> PreparedCall<'a, C> {
         self.value
     }
 
+    /// Returns the ABI-encoded calldata, selector included.
+    pub fn calldata(&self) -> Bytes {
+        self.call.abi_encode().into()
+    }
+
+    /// Decodes the call into a [`CallDescription`] for logging or approval prompts.
+    ///
+    /// Arguments are left empty if the call does not decode against its own signature,
+    /// which only happens for hand-written `SolCall` implementations.
+    pub fn describe(&self) -> CallDescription {
+        let signature = C::SIGNATURE;
+        let (function, params) =
+            signature.split_at(signature.find('(').unwrap_or(signature.len()));
+        let data = self.call.abi_encode();
+        let args = DynSolType::parse(params)
+            .ok()
+            .and_then(|ty| ty.abi_decode_params(&data[4..]).ok())
+            .and_then(|value| match value {
+                DynSolValue::Tuple(values) => Some(values),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        CallDescription {
+            to: self.to,
+            function: function.to_string(),
+            signature,
+            args,
+            value: self.value,
+        }
+    }
+
+    /// Estimates the gas of the call sent from the signer (`eth_estimateGas`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::TransactionFailed`] if the call would revert or the
+    /// estimate fails.
+    pub async fn estimate_gas(&self) -> Result<u64> {
+        let tx = TransactionRequest::default()
+            .from(self.provider.default_signer_address())
+            .to(self.to)
+            .input(self.call.abi_encode().into())
+            .value(self.value);
+        self.provider.estimate_gas(tx).await.map_err(|e| {
+            ContractError::TransactionFailed(format!("Failed to estimate gas: {}", e))
+        })
+    }
+
+    /// Returns the provider the call is sent through.
+    #[cfg(feature = "safe")]
+    pub(crate) fn provider(&self) -> &'a HttpProvider {
+        self.provider
+    }
+
     /// Sends the transaction and waits for the receipt.
     pub async fn send(self) -> Result<TransactionReceipt> {
-        use alloy::rpc::types::TransactionRequest;
+        self.send_with(SendOptions::default()).await
+    }
 
-        let calldata = self.call.abi_encode();
-        let tx = TransactionRequest::default()
+    /// Sends the transaction and waits for the receipt according to `options`.
+    ///
+    /// Each submission is given `options.timeout` to reach `options.confirmations`.
+    /// When it times out and `options.replaceable` is set, the call is resubmitted at
+    /// the same nonce with fees raised by `options.fee_bump_percent`, up to
+    /// `options.max_replacements` times. If an earlier submission is mined instead of
+    /// its replacement, that receipt is returned. A submission found mined after a
+    /// timeout is neither replaced nor returned early: it is waited on until it has
+    /// `options.confirmations`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::TransactionTimeout`] if no submission was confirmed in time
+    /// - [`ContractError::TransactionFailed`] if sending or watching the transaction fails
+    pub async fn send_with(self, options: SendOptions) -> Result<TransactionReceipt> {
+        let mut tx = TransactionRequest::default()
             .to(self.to)
-            .input(calldata.into())
+            .input(self.call.abi_encode().into())
             .value(self.value);
 
-        let pending = self.provider.send_transaction(tx).await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to send transaction: {}", e))
-        })?;
+        if options.replaceable {
+            // Pin the nonce and fees so replacements can target and outbid them.
+            let from = self.provider.default_signer_address();
+            let nonce = self
+                .provider
+                .get_transaction_count(from)
+                .pending()
+                .await
+                .map_err(|e| ContractError::RpcConnection(format!("Failed to get nonce: {}", e)))?;
+            let fees = self.provider.estimate_eip1559_fees().await.map_err(|e| {
+                ContractError::RpcConnection(format!("Failed to estimate fees: {}", e))
+            })?;
+            tx = tx
+                .from(from)
+                .nonce(nonce)
+                .max_fee_per_gas(fees.max_fee_per_gas)
+                .max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+        }
+
+        let submitted_at = Instant::now();
+        let mut hashes = Vec::new();
+        let mut replacements = 0;
+        loop {
+            let pending = match self.provider.send_transaction(tx.clone()).await {
+                Ok(pending) => pending,
+                Err(e) => {
+                    // A replacement is rejected once an earlier submission is mined.
+                    if let Some(receipt) = find_receipt(self.provider, &hashes).await {
+                        let receipt =
+                            wait_for_confirmations(self.provider, receipt, options.confirmations)
+                                .await?;
+                        return Ok(self.confirmed(receipt, submitted_at));
+                    }
+                    self.metrics.record_tx_failed(self.to);
+                    return Err(ContractError::TransactionFailed(format!(
+                        "Failed to send transaction: {}",
+                        e
+                    )));
+                }
+            };
+            self.metrics.record_tx_submitted(self.to);
+            let hash = *pending.tx_hash();
+            hashes.push(hash);
+
+            let result = pending
+                .with_required_confirmations(options.confirmations)
+                .with_timeout(options.timeout)
+                .get_receipt()
+                .await;
+            match result {
+                Ok(receipt) => return Ok(self.confirmed(receipt, submitted_at)),
+                Err(PendingTransactionError::TxWatcher(WatchTxError::Timeout)) => {
+                    // A mined submission cannot be replaced, so it is waited on
+                    if let Some(receipt) = find_receipt(self.provider, &hashes).await {
+                        let receipt =
+                            wait_for_confirmations(self.provider, receipt, options.confirmations)
+                                .await?;
+                        return Ok(self.confirmed(receipt, submitted_at));
+                    }
+                    if !options.replaceable || replacements >= options.max_replacements {
+                        self.metrics.record_tx_failed(self.to);
+                        return Err(ContractError::TransactionTimeout { hash });
+                    }
+                    tx = bump_fees(tx, options.fee_bump_percent);
+                    replacements += 1;
+                }
+                Err(e) => {
+                    self.metrics.record_tx_failed(self.to);
+                    return Err(ContractError::TransactionFailed(format!(
+                        "Failed to get receipt: {}",
+                        e
+                    )));
+                }
+            }
+        }
+    }
+
+    fn confirmed(&self, receipt: TransactionReceipt, submitted_at: Instant) -> TransactionReceipt {
+        self.metrics
+            .record_tx_confirmed(self.to, submitted_at.elapsed(), receipt.status());
+        receipt
+    }
+}
+
+/// Resubmit a pending transaction with the same call and fees raised by
+/// `options.fee_bump_percent`, and wait up to `options.timeout` for the receipt of
+/// whichever is mined.
+///
+/// # Errors
+///
+/// - [`ContractError::TransactionTimeout`] with the replacement's hash if neither
+///   transaction is mined within `options.timeout`
+/// - [`ContractError::TransactionFailed`] if the transaction is unknown or already
+///   mined, or if the replacement cannot be sent
+pub async fn speed_up_transaction(
+    provider: &HttpProvider,
+    hash: B256,
+    options: SendOptions,
+) -> Result<TransactionReceipt> {
+    replace_transaction(provider, hash, options, false).await
+}
+
+/// Cancel a pending transaction by replacing it with a zero-value transfer to the
+/// signer at the same nonce, with fees raised by `options.fee_bump_percent`.
+///
+/// Returns the receipt of whichever transaction is mined within `options.timeout`;
+/// check its hash to tell whether the cancellation won.
+///
+/// # Errors
+///
+/// - [`ContractError::TransactionTimeout`] with the replacement's hash if neither
+///   transaction is mined within `options.timeout`
+/// - [`ContractError::TransactionFailed`] if the transaction is unknown or already
+///   mined, or if the replacement cannot be sent
+pub async fn cancel_transaction(
+    provider: &HttpProvider,
+    hash: B256,
+    options: SendOptions,
+) -> Result<TransactionReceipt> {
+    replace_transaction(provider, hash, options, true).await
+}
+
+async fn replace_transaction(
+    provider: &HttpProvider,
+    hash: B256,
+    options: SendOptions,
+    cancel: bool,
+) -> Result<TransactionReceipt> {
+    let original = provider
+        .get_transaction_by_hash(hash)
+        .await
+        .map_err(|e| ContractError::RpcConnection(format!("Failed to get transaction: {}", e)))?
+        .ok_or_else(|| ContractError::TransactionFailed(format!("Unknown transaction {}", hash)))?;
+    if original.block_number.is_some() {
+        return Err(ContractError::TransactionFailed(format!(
+            "Transaction {} is already mined",
+            hash
+        )));
+    }
+
+    let signer = provider.default_signer_address();
+    let tx = if cancel {
+        TransactionRequest::default().to(signer).value(U256::ZERO)
+    } else {
+        let mut tx = TransactionRequest::default()
+            .input(original.input().clone().into())
+            .value(original.value())
+            .gas_limit(original.gas_limit());
+        if let Some(to) = original.to() {
+            tx = tx.to(to);
+        }
+        tx
+    };
+    let max_fee_per_gas = original.max_fee_per_gas();
+    let tx = tx
+        .from(signer)
+        .nonce(original.nonce())
+        .max_fee_per_gas(max_fee_per_gas)
+        .max_priority_fee_per_gas(
+            original
+                .max_priority_fee_per_gas()
+                .unwrap_or(max_fee_per_gas),
+        );
+    let tx = bump_fees(tx, options.fee_bump_percent);
+
+    let submitted_at = Instant::now();
+    let replacement = match provider.send_transaction(tx).await {
+        Ok(pending) => *pending.tx_hash(),
+        Err(e) => {
+            return match find_receipt(provider, &[hash]).await {
+                Some(receipt) => Ok(receipt),
+                None => Err(ContractError::TransactionFailed(format!(
+                    "Failed to send replacement: {}",
+                    e
+                ))),
+            }
+        }
+    };
+
+    // Either the original or the replacement will be mined.
+    loop {
+        let result = PendingTransactionBuilder::new(provider.root().clone(), replacement)
+            .with_timeout(Some(REPLACEMENT_CHECK_INTERVAL))
+            .get_receipt()
+            .await;
+        match result {
+            Ok(receipt) => return Ok(receipt),
+            Err(PendingTransactionError::TxWatcher(WatchTxError::Timeout)) => {
+                if let Some(receipt) = find_receipt(provider, &[hash]).await {
+                    return Ok(receipt);
+                }
+                if options
+                    .timeout
+                    .is_some_and(|timeout| submitted_at.elapsed() >= timeout)
+                {
+                    return Err(ContractError::TransactionTimeout { hash: replacement });
+                }
+            }
+            Err(e) => {
+                return Err(ContractError::TransactionFailed(format!(
+                    "Failed to get receipt: {}",
+                    e
+                )))
+            }
+        }
+    }
+}
+
+/// Receipt of the first of `hashes` that has been mined, if any.
+async fn find_receipt(provider: &HttpProvider, hashes: &[B256]) -> Option<TransactionReceipt> {
+    for hash in hashes {
+        if let Ok(Some(receipt)) = provider.get_transaction_receipt(*hash).await {
+            return Some(receipt);
+        }
+    }
+    None
+}
 
-        let receipt = pending.get_receipt().await.map_err(|e| {
-            ContractError::TransactionFailed(format!("Failed to get receipt: {}", e))
-        })?;
+/// Wait until the transaction of the mined `receipt` has `confirmations`, re-reading its
+/// receipt in case it was reorged into another block.
+///
+/// # Errors
+///
+/// Returns [`ContractError::TransactionFailed`] if watching the transaction fails.
+async fn wait_for_confirmations(
+    provider: &HttpProvider,
+    receipt: TransactionReceipt,
+    confirmations: u64,
+) -> Result<TransactionReceipt> {
+    let hash = receipt.transaction_hash;
+    let mut receipt = Some(receipt);
+    loop {
+        if let Some(receipt) = receipt.take() {
+            if let Some(block) = receipt.block_number {
+                let latest = provider.get_block_number().await.map_err(|e| {
+                    ContractError::RpcConnection(format!("Failed to get block number: {}", e))
+                })?;
+                if latest.saturating_sub(block) + 1 >= confirmations {
+                    return Ok(receipt);
+                }
+            }
+        }
+        let result = PendingTransactionBuilder::new(provider.root().clone(), hash)
+            .with_required_confirmations(confirmations)
+            .with_timeout(Some(REPLACEMENT_CHECK_INTERVAL))
+            .get_receipt()
+            .await;
+        match result {
+            Ok(confirmed) => return Ok(confirmed),
+            Err(PendingTransactionError::TxWatcher(WatchTxError::Timeout)) => {
+                receipt = find_receipt(provider, &[hash]).await;
+            }
+            Err(e) => {
+                return Err(ContractError::TransactionFailed(format!(
+                    "Failed to get receipt: {}",
+                    e
+                )))
+            }
+        }
+    }
+}
 
-        Ok(receipt)
+/// Raise both EIP-1559 fee caps by `percent`, at least [`MIN_FEE_BUMP_PERCENT`] (and by
+/// at least 1 wei).
+fn bump_fees(tx: TransactionRequest, percent: u64) -> TransactionRequest {
+    let percent = percent.max(MIN_FEE_BUMP_PERCENT);
+    let bump = |fee: u128| fee.saturating_add((fee.saturating_mul(percent as u128) / 100).max(1));
+    let max_fee_per_gas = tx.max_fee_per_gas.map(bump);
+    let max_priority_fee_per_gas = tx.max_priority_fee_per_gas.map(bump);
+    TransactionRequest {
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+        ..tx
     }
 }
 
@@ -219,4 +780,306 @@ mod tests {
         assert_eq!(prepared.to(), target);
         assert_eq!(prepared.value(), value);
     }
+
+    #[test]
+    fn test_with_metrics_preserves_fields() {
+        use crate::metrics::NoopMetrics;
+
+        let provider = create_test_provider();
+        let metrics = NoopMetrics;
+        let target = Address::repeat_byte(0x42);
+        let call = ITestContract::testFunctionCall {
+            value: U256::from(100),
+            receiver: Address::repeat_byte(0x01),
+        };
+
+        let prepared =
+            PreparedCall::new(target, call, U256::from(7), &provider).with_metrics(&metrics);
+
+        assert_eq!(prepared.to(), target);
+        assert_eq!(prepared.value(), U256::from(7));
+    }
+
+    #[test]
+    fn test_describe_decodes_arguments() {
+        let provider = create_test_provider();
+        let target = Address::repeat_byte(0x42);
+        let receiver = Address::repeat_byte(0x01);
+        let call = ITestContract::testFunctionCall {
+            value: U256::from(100),
+            receiver,
+        };
+
+        let description = PreparedCall::new(target, call, U256::ZERO, &provider).describe();
+
+        assert_eq!(description.to, target);
+        assert_eq!(description.function, "testFunction");
+        assert_eq!(description.signature, "testFunction(uint256,address)");
+        assert_eq!(description.uint_arg(0), Some(U256::from(100)));
+        assert_eq!(description.address_arg(1), Some(receiver));
+        assert_eq!(description.address_arg(0), None);
+        assert_eq!(description.uint_arg(2), None);
+        assert_eq!(
+            description.to_string(),
+            format!("testFunction(100, {}) on {}", receiver, target)
+        );
+    }
+
+    #[test]
+    fn test_describe_shows_value() {
+        let provider = create_test_provider();
+        let call = ITestContract::testFunctionCall {
+            value: U256::from(1),
+            receiver: Address::ZERO,
+        };
+
+        let description =
+            PreparedCall::new(Address::ZERO, call, U256::from(5), &provider).describe();
+
+        assert!(description.to_string().ends_with(" with 5 wei"));
+    }
+
+    #[test]
+    fn test_call_description_decode() {
+        let target = Address::repeat_byte(0x42);
+        let receiver = Address::repeat_byte(0x01);
+        let data = ITestContract::testFunctionCall {
+            value: U256::from(100),
+            receiver,
+        }
+        .abi_encode();
+
+        let description =
+            CallDescription::decode(target, "testFunction(uint256,address)", &data, U256::ZERO)
+                .unwrap();
+        assert_eq!(description.function, "testFunction");
+        assert_eq!(description.formatted_args(), vec!["100".to_string(), receiver.to_string()]);
+
+        assert!(CallDescription::decode(target, "other(uint256,address)", &data, U256::ZERO)
+            .is_none());
+        assert!(CallDescription::decode(target, "testFunction(uint256,address)", &[], U256::ZERO)
+            .is_none());
+    }
+
+    #[test]
+    fn test_send_options_default_matches_send() {
+        let options = SendOptions::default();
+
+        assert_eq!(options.confirmations, 1);
+        assert_eq!(options.timeout, None);
+        assert!(!options.replaceable);
+        assert_eq!(options.fee_bump_percent, DEFAULT_FEE_BUMP_PERCENT);
+    }
+
+    #[test]
+    fn test_send_options_builder() {
+        let options = SendOptions::new()
+            .with_confirmations(3)
+            .with_timeout(Duration::from_secs(30))
+            .replaceable(true)
+            .with_fee_bump_percent(25)
+            .with_max_replacements(5);
+
+        assert_eq!(options.confirmations, 3);
+        assert_eq!(options.timeout, Some(Duration::from_secs(30)));
+        assert!(options.replaceable);
+        assert_eq!(options.fee_bump_percent, 25);
+        assert_eq!(options.max_replacements, 5);
+    }
+
+    #[test]
+    fn test_fee_bump_percent_is_at_least_minimum() {
+        let options = SendOptions::new().with_fee_bump_percent(5);
+        assert_eq!(options.fee_bump_percent, MIN_FEE_BUMP_PERCENT);
+
+        // Fields set directly are clamped when the fees are bumped
+        let tx = TransactionRequest::default().max_fee_per_gas(1_000);
+        assert_eq!(bump_fees(tx, 1).max_fee_per_gas, Some(1_100));
+    }
+
+    #[test]
+    fn test_bump_fees() {
+        let tx = TransactionRequest::default()
+            .max_fee_per_gas(100_000_000_000)
+            .max_priority_fee_per_gas(2_000_000_000);
+
+        let bumped = bump_fees(tx, 15);
+
+        assert_eq!(bumped.max_fee_per_gas, Some(115_000_000_000));
+        assert_eq!(bumped.max_priority_fee_per_gas, Some(2_300_000_000));
+    }
+
+    #[test]
+    fn test_bump_fees_raises_tiny_fees() {
+        let tx = TransactionRequest::default()
+            .max_fee_per_gas(5)
+            .max_priority_fee_per_gas(0);
+
+        let bumped = bump_fees(tx, 10);
+
+        // Rounding never leaves a fee unchanged, or the node would reject the replacement.
+        assert_eq!(bumped.max_fee_per_gas, Some(6));
+        assert_eq!(bumped.max_priority_fee_per_gas, Some(1));
+        assert_eq!(
+            bump_fees(TransactionRequest::default(), 10).max_fee_per_gas,
+            None
+        );
+    }
+
+    #[derive(Debug, Default)]
+    struct FailureCounter {
+        failed: std::sync::atomic::AtomicU64,
+    }
+
+    impl Metrics for FailureCounter {
+        fn record_tx_failed(&self, _to: Address) {
+            self.failed.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_metrics_reports_failed_send() {
+        use alloy::providers::ProviderBuilder;
+
+        let signer: PrivateKeySigner = TEST_PRIVATE_KEY.parse().expect("invalid private key");
+        // Nothing listens on port 1, so the send fails
+        let url: url::Url = "http://127.0.0.1:1".parse().unwrap();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_http(url);
+        let metrics = FailureCounter::default();
+        let call = ITestContract::testFunctionCall {
+            value: U256::from(100),
+            receiver: Address::repeat_byte(0x01),
+        };
+
+        let result = PreparedCall::new(Address::repeat_byte(0x42), call, U256::ZERO, &provider)
+            .with_metrics(&metrics)
+            .send()
+            .await;
+
+        assert!(matches!(result, Err(ContractError::TransactionFailed(_))));
+        assert_eq!(metrics.failed.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+
+    /// JSON-RPC node that accepts any transaction and, from the second receipt query
+    /// on, reports it mined at block 10, with `latest` as its latest block.
+    struct MinedAtTen {
+        latest: u64,
+        queried: std::sync::atomic::AtomicBool,
+    }
+
+    impl wiremock::Respond for MinedAtTen {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let result = match body["method"].as_str().unwrap_or_default() {
+                "eth_chainId" => serde_json::json!("0x1"),
+                "eth_getTransactionCount" => serde_json::json!("0x0"),
+                "eth_estimateGas" => serde_json::json!("0x5208"),
+                "eth_feeHistory" => serde_json::json!({
+                    "oldestBlock": "0xa",
+                    "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00"],
+                    "gasUsedRatio": [0.5],
+                    "reward": [["0x3b9aca00"]],
+                }),
+                "eth_sendRawTransaction" => serde_json::json!(B256::repeat_byte(0x01)),
+                "eth_blockNumber" => serde_json::json!(format!("{:#x}", self.latest)),
+                "eth_getBlockByNumber" => serde_json::json!({
+                    "hash": B256::repeat_byte(0x0b),
+                    "parentHash": B256::ZERO,
+                    "sha3Uncles": B256::ZERO,
+                    "miner": Address::ZERO,
+                    "stateRoot": B256::ZERO,
+                    "transactionsRoot": B256::ZERO,
+                    "receiptsRoot": B256::ZERO,
+                    "logsBloom": format!("0x{}", "0".repeat(512)),
+                    "difficulty": "0x0",
+                    "number": body["params"][0],
+                    "gasLimit": "0x1c9c380",
+                    "gasUsed": "0x0",
+                    "timestamp": "0x1",
+                    "extraData": "0x",
+                    "mixHash": B256::ZERO,
+                    "nonce": "0x0000000000000000",
+                    "baseFeePerGas": "0x1",
+                    "uncles": [],
+                    "transactions": [],
+                }),
+                "eth_getTransactionReceipt"
+                    if !self.queried.swap(true, std::sync::atomic::Ordering::SeqCst) =>
+                {
+                    serde_json::Value::Null
+                }
+                "eth_getTransactionReceipt" => serde_json::json!({
+                    "transactionHash": B256::repeat_byte(0x01),
+                    "transactionIndex": "0x0",
+                    "blockHash": B256::repeat_byte(0x0a),
+                    "blockNumber": "0xa",
+                    "from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                    "to": Address::repeat_byte(0x42),
+                    "gasUsed": "0x5208",
+                    "effectiveGasPrice": "0x3b9aca00",
+                    "cumulativeGasUsed": "0x5208",
+                    "contractAddress": null,
+                    "logs": [],
+                    "logsBloom": format!("0x{}", "0".repeat(512)),
+                    "status": "0x1",
+                    "type": "0x2"
+                }),
+                _ => {
+                    return wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": body["id"],
+                        "error": { "code": -32601, "message": "method not found" },
+                    }))
+                }
+            };
+            wiremock::ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }),
+            )
+        }
+    }
+
+    async fn send_to_mined_at_ten(latest: u64) -> Option<Result<TransactionReceipt>> {
+        use alloy::providers::ProviderBuilder;
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(MinedAtTen {
+                latest,
+                queried: Default::default(),
+            })
+            .mount(&server)
+            .await;
+        let signer: PrivateKeySigner = TEST_PRIVATE_KEY.parse().expect("invalid private key");
+        let url: url::Url = server.uri().parse().unwrap();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_http(url);
+        let call = ITestContract::testFunctionCall {
+            value: U256::from(100),
+            receiver: Address::repeat_byte(0x01),
+        };
+        let options = SendOptions::new()
+            .with_confirmations(3)
+            .with_timeout(Duration::from_millis(100));
+
+        let prepared = PreparedCall::new(Address::repeat_byte(0x42), call, U256::ZERO, &provider);
+        tokio::time::timeout(Duration::from_secs(2), prepared.send_with(options))
+            .await
+            .ok()
+    }
+
+    #[tokio::test]
+    async fn test_send_with_waits_for_confirmations_after_timeout() {
+        // Mined with one confirmation when the submission times out: it is not returned
+        // early
+        assert!(send_to_mined_at_ten(10).await.is_none());
+
+        let receipt = send_to_mined_at_ten(12)
+            .await
+            .expect("confirmed in time")
+            .expect("receipt");
+        assert_eq!(receipt.block_number, Some(10));
+    }
 }
@@ -1,16 +1,24 @@
 //! Provider type definitions for contract clients.
 
+use std::time::Duration;
+
 use alloy::{
     network::EthereumWallet,
+    primitives::Address,
     providers::{
         fillers::{
             BlobGasFiller, ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller,
             WalletFiller,
         },
-        Identity, RootProvider,
+        Identity, Provider, ProviderBuilder, RootProvider,
     },
+    rpc::{client::RpcClient, types::BlockNumberOrTag},
+    signers::local::PrivateKeySigner,
 };
 
+use crate::error::{ContractError, Result};
+use crate::rpc::{FailoverTransport, DEFAULT_RPC_TIMEOUT_SECS};
+
 /// The recommended fillers type (default in alloy 1.x).
 pub type RecommendedFillers =
     JoinFill<GasFiller, JoinFill<BlobGasFiller, JoinFill<NonceFiller, ChainIdFiller>>>;
@@ -21,3 +29,47 @@ pub type HttpProvider = FillProvider<
     JoinFill<JoinFill<Identity, RecommendedFillers>, WalletFiller<EthereumWallet>>,
     RootProvider,
 >;
+
+/// Connect a signing provider to `rpc_urls`, returning it with the signer address.
+///
+/// A single URL uses a plain HTTP transport; several URLs go through a
+/// [`FailoverTransport`] with the first URL preferred until latencies are known.
+pub(crate) fn connect(rpc_urls: &[&str], private_key: &str) -> Result<(HttpProvider, Address)> {
+    let signer = parse_signer(private_key)?;
+    let signer_address = signer.address();
+    let wallet = EthereumWallet::from(signer);
+
+    let urls = rpc_urls
+        .iter()
+        .map(|rpc_url| rpc_url.parse::<url::Url>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| ContractError::RpcConnection(format!("{}", e)))?;
+
+    let builder = ProviderBuilder::new().wallet(wallet);
+    let provider = match urls.as_slice() {
+        [url] => builder.connect_http(url.clone()),
+        _ => {
+            let transport =
+                FailoverTransport::http(&urls, Duration::from_secs(DEFAULT_RPC_TIMEOUT_SECS))?;
+            builder.connect_client(RpcClient::new(transport, false))
+        }
+    };
+    Ok((provider, signer_address))
+}
+
+/// Parse a hex private key into a local signer.
+pub(crate) fn parse_signer(private_key: &str) -> Result<PrivateKeySigner> {
+    private_key
+        .parse()
+        .map_err(|_| ContractError::InvalidPrivateKey)
+}
+
+/// Timestamp of the latest block, used for timelock checks.
+pub(crate) async fn latest_block_timestamp(provider: &HttpProvider) -> Result<u64> {
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Latest)
+        .await
+        .map_err(|e| ContractError::RpcConnection(format!("Failed to get block: {}", e)))?
+        .ok_or_else(|| ContractError::RpcConnection("Latest block not found".to_string()))?;
+    Ok(block.header.timestamp)
+}
@@ -0,0 +1,257 @@
+//! Public Allocator bindings and reallocation client.
+//!
+//! The [Public Allocator](https://docs.morpho.org/public-allocator/overview) lets anyone
+//! move a MetaMorpho vault's liquidity into one of its markets, within flow caps set by
+//! the vault's curator. A `reallocateTo` call withdraws from some of the vault's markets
+//! and supplies the total to a target market; it must pay exactly the vault's fee as
+//! `msg.value`.
+//!
+//! Plan the withdrawals with `morpho_rs_sim::find_public_allocator_route`, then send one
+//! [`PublicAllocatorClient::reallocate_to`] per vault in the route.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::PublicAllocatorClient;
+//!
+//! let client = PublicAllocatorClient::new(rpc_url, private_key, public_allocator)?;
+//! let fee = client.fee(vault).await?;
+//! let receipt = client
+//!     .reallocate_to(vault, withdrawals, supply_market_params, fee)
+//!     .send()
+//!     .await?;
+//! ```
+
+use std::sync::Arc;
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::rpc::types::BlockId;
+
+use crate::error::{ContractError, Result};
+use crate::metamorpho::{market_id, MarketParams};
+use crate::metrics::{Metrics, NoopMetrics};
+use crate::prepared_call::PreparedCall;
+use crate::provider::{connect, HttpProvider};
+
+/// Public Allocator bindings. `sol!` cannot reference the crate's `MarketParams` from
+/// another invocation, so the interface has its own copy of the struct.
+pub mod bindings {
+    use alloy::sol;
+
+    sol! {
+        #[derive(Debug, PartialEq, Eq)]
+        struct MarketParams {
+            address loanToken;
+            address collateralToken;
+            address oracle;
+            address irm;
+            uint256 lltv;
+        }
+
+        /// Assets to withdraw from one market of the vault.
+        #[derive(Debug, PartialEq, Eq)]
+        struct Withdrawal {
+            MarketParams marketParams;
+            uint128 amount;
+        }
+
+        #[sol(rpc)]
+        interface IPublicAllocator {
+            function reallocateTo(
+                address vault,
+                Withdrawal[] calldata withdrawals,
+                MarketParams calldata supplyMarketParams
+            ) external payable;
+            function fee(address vault) external view returns (uint256);
+            function accruedFee(address vault) external view returns (uint256);
+            function flowCaps(address vault, bytes32 id) external view returns (uint128 maxIn, uint128 maxOut);
+        }
+    }
+
+    impl From<&crate::metamorpho::MarketParams> for MarketParams {
+        fn from(params: &crate::metamorpho::MarketParams) -> Self {
+            Self {
+                loanToken: params.loanToken,
+                collateralToken: params.collateralToken,
+                oracle: params.oracle,
+                irm: params.irm,
+                lltv: params.lltv,
+            }
+        }
+    }
+}
+
+use bindings::{IPublicAllocator, Withdrawal};
+
+/// Client for reallocating vault liquidity through the Public Allocator.
+pub struct PublicAllocatorClient {
+    provider: HttpProvider,
+    signer_address: Address,
+    public_allocator: Address,
+    metrics: Arc<dyn Metrics>,
+    block: BlockId,
+}
+
+impl PublicAllocatorClient {
+    /// Create a new Public Allocator client.
+    pub fn new(rpc_url: &str, private_key: &str, public_allocator: Address) -> Result<Self> {
+        Self::new_with_fallbacks(&[rpc_url], private_key, public_allocator)
+    }
+
+    /// Create a new Public Allocator client over several RPC endpoints, failing over
+    /// between them.
+    pub fn new_with_fallbacks(
+        rpc_urls: &[&str],
+        private_key: &str,
+        public_allocator: Address,
+    ) -> Result<Self> {
+        let (provider, signer_address) = connect(rpc_urls, private_key)?;
+
+        Ok(Self {
+            provider,
+            signer_address,
+            public_allocator,
+            metrics: Arc::new(NoopMetrics),
+            block: BlockId::latest(),
+        })
+    }
+
+    /// Set the metrics sink notified on transaction submission and confirmation.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Pin view reads to a block number instead of the latest block.
+    pub fn at_block(mut self, block: u64) -> Self {
+        self.block = BlockId::number(block);
+        self
+    }
+
+    /// Get the underlying provider.
+    pub fn provider(&self) -> &HttpProvider {
+        &self.provider
+    }
+
+    /// Get the signer's address.
+    pub fn signer_address(&self) -> Address {
+        self.signer_address
+    }
+
+    /// Get the Public Allocator address.
+    pub fn public_allocator(&self) -> Address {
+        self.public_allocator
+    }
+
+    /// Create a prepared `reallocateTo` call sending `fee` as the transaction value.
+    ///
+    /// Withdrawals are sorted by market ID, as the contract requires. Amounts are
+    /// bounded by the markets' `max_out` flow caps, which are `uint128`, so larger
+    /// amounts are clamped and the contract reverts on them. `fee` must equal the
+    /// vault's current fee ([`Self::fee`]).
+    pub fn reallocate_to(
+        &self,
+        vault: Address,
+        withdrawals: Vec<(MarketParams, U256)>,
+        supply_market_params: &MarketParams,
+        fee: U256,
+    ) -> PreparedCall<'_, IPublicAllocator::reallocateToCall> {
+        let mut withdrawals: Vec<(B256, Withdrawal)> = withdrawals
+            .iter()
+            .map(|(params, amount)| {
+                let withdrawal = Withdrawal {
+                    marketParams: params.into(),
+                    amount: amount.saturating_to::<u128>(),
+                };
+                (market_id(params), withdrawal)
+            })
+            .collect();
+        withdrawals.sort_by_key(|(id, _)| *id);
+
+        let call = IPublicAllocator::reallocateToCall {
+            vault,
+            withdrawals: withdrawals.into_iter().map(|(_, w)| w).collect(),
+            supplyMarketParams: supply_market_params.into(),
+        };
+        PreparedCall::new(self.public_allocator, call, fee, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Get the fee, in native token, to pay on each `reallocateTo` for `vault`.
+    pub async fn fee(&self, vault: Address) -> Result<U256> {
+        IPublicAllocator::new(self.public_allocator, &self.provider)
+            .fee(vault)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| ContractError::RpcConnection(format!("Failed to get fee: {}", e)))
+    }
+
+    /// Get the `(max_in, max_out)` flow caps of `vault` for a market.
+    pub async fn flow_caps(&self, vault: Address, market_id: B256) -> Result<(U256, U256)> {
+        let caps = IPublicAllocator::new(self.public_allocator, &self.provider)
+            .flowCaps(vault, market_id)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| ContractError::RpcConnection(format!("Failed to get flow caps: {}", e)))?;
+        Ok((U256::from(caps.maxIn), U256::from(caps.maxOut)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn market(lltv: u64) -> MarketParams {
+        MarketParams {
+            loanToken: Address::repeat_byte(0x01),
+            collateralToken: Address::repeat_byte(0x02),
+            oracle: Address::repeat_byte(0x03),
+            irm: Address::repeat_byte(0x04),
+            lltv: U256::from(lltv),
+        }
+    }
+
+    #[test]
+    fn test_invalid_private_key() {
+        let result =
+            PublicAllocatorClient::new("http://localhost:8545", "invalid_key", Address::ZERO);
+        assert!(matches!(result, Err(ContractError::InvalidPrivateKey)));
+    }
+
+    #[test]
+    fn test_reallocate_to_pays_fee_and_sorts_withdrawals() {
+        let public_allocator = Address::repeat_byte(0x44);
+        let client =
+            PublicAllocatorClient::new("http://localhost:8545", PRIVATE_KEY, public_allocator)
+                .unwrap();
+        let vault = Address::repeat_byte(0x55);
+        let (a, b, target) = (market(1), market(2), market(3));
+        let mut expected = [(market_id(&a), 100u128), (market_id(&b), 200u128)];
+        expected.sort();
+
+        let prepared = client.reallocate_to(
+            vault,
+            vec![(a, U256::from(100u64)), (b, U256::from(200u64))],
+            &target,
+            U256::from(1_000u64),
+        );
+        assert_eq!(prepared.value(), U256::from(1_000u64));
+
+        let (to, call) = prepared.prepare();
+        assert_eq!(to, public_allocator);
+        assert_eq!(call.vault, vault);
+        assert_eq!(call.supplyMarketParams.lltv, U256::from(3u64));
+        let amounts: Vec<u128> = call.withdrawals.iter().map(|w| w.amount).collect();
+        assert_eq!(
+            amounts,
+            expected
+                .iter()
+                .map(|(_, amount)| *amount)
+                .collect::<Vec<_>>()
+        );
+    }
+}
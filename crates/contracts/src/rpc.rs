@@ -0,0 +1,345 @@
+//! Multi-endpoint RPC transport with latency-based selection and failover.
+//!
+//! [`FailoverTransport`] wraps several RPC endpoints for the same chain: a primary
+//! followed by fallbacks. Each request goes to the endpoint with the lowest observed
+//! latency. The primary is preferred until it has been measured, while a fallback that
+//! has not answered yet ranks behind every measured endpoint, so fallbacks only take
+//! traffic once they have proven faster or the primary has failed. When a
+//! request fails at the transport level (connection error, timeout, non-2xx HTTP
+//! status, or an undecodable body) the same request is sent to the next endpoint, and
+//! the failing one is moved to the back of the order for
+//! [`DEFAULT_FAILURE_COOLDOWN_SECS`]. JSON-RPC error responses such as reverts are
+//! returned as-is, since every endpoint would give the same answer.
+//!
+//! Transaction clients use it through their `new_with_fallbacks` constructors:
+//!
+//! ```no_run
+//! use morpho_rs_contracts::VaultV1TransactionClient;
+//!
+//! # fn example() -> morpho_rs_contracts::Result<()> {
+//! let client = VaultV1TransactionClient::new_with_fallbacks(
+//!     &["https://rpc.example.com", "https://eth.llamarpc.com"],
+//!     "0x...", // private key
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::http::{reqwest, Http};
+use alloy::transports::{BoxTransport, TransportError, TransportErrorKind, TransportFut};
+use tower::Service;
+use url::Url;
+use web_time::Instant;
+
+use crate::error::{ContractError, Result};
+
+/// Default timeout for a single RPC request before failing over.
+pub const DEFAULT_RPC_TIMEOUT_SECS: u64 = 30;
+
+/// Default seconds a failed endpoint stays at the back of the order.
+pub const DEFAULT_FAILURE_COOLDOWN_SECS: u64 = 30;
+
+/// Weight of the newest sample in the latency moving average.
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+/// Health of one RPC endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcEndpointStatus {
+    /// Endpoint URL.
+    pub url: String,
+    /// Moving average of successful request latencies, if any succeeded.
+    pub latency: Option<Duration>,
+    /// Failures since the last success.
+    pub consecutive_failures: u32,
+    /// Whether the endpoint is outside its failure cooldown.
+    pub available: bool,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    latency: Option<Duration>,
+    consecutive_failures: u32,
+    failed_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    transport: BoxTransport,
+    stats: Mutex<Stats>,
+}
+
+impl Endpoint {
+    fn stats(&self) -> std::sync::MutexGuard<'_, Stats> {
+        self.stats
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// RPC transport that spreads requests over several endpoints and fails over between
+/// them.
+///
+/// Cloning is cheap; clones share endpoint statistics.
+#[derive(Debug, Clone)]
+pub struct FailoverTransport {
+    endpoints: Arc<[Endpoint]>,
+    cooldown: Duration,
+}
+
+impl FailoverTransport {
+    /// Create a transport over HTTP endpoints, each request timing out after `timeout`.
+    ///
+    /// On `wasm32`, the `fetch` backend has no client-level timeout and `timeout` is
+    /// ignored.
+    pub fn http(urls: &[Url], timeout: Duration) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(ContractError::RpcConnection(
+                "No RPC URLs configured".to_string(),
+            ));
+        }
+        let builder = reqwest::Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder.timeout(timeout);
+        #[cfg(target_arch = "wasm32")]
+        let _ = timeout;
+        let client = builder
+            .build()
+            .map_err(|e| ContractError::RpcConnection(format!("{}", e)))?;
+        Ok(Self::from_transports(urls.iter().map(|url| {
+            (
+                url.to_string(),
+                BoxTransport::new(Http::with_client(client.clone(), url.clone())),
+            )
+        })))
+    }
+
+    /// Create a transport over arbitrary transports, labelled for [`status`](Self::status).
+    ///
+    /// The first transport is the primary; the others are fallbacks, tried after every
+    /// measured endpoint until they have answered once.
+    pub fn from_transports(transports: impl IntoIterator<Item = (String, BoxTransport)>) -> Self {
+        Self {
+            endpoints: transports
+                .into_iter()
+                .map(|(url, transport)| Endpoint {
+                    url,
+                    transport,
+                    stats: Mutex::default(),
+                })
+                .collect(),
+            cooldown: Duration::from_secs(DEFAULT_FAILURE_COOLDOWN_SECS),
+        }
+    }
+
+    /// Set how long a failed endpoint stays at the back of the order.
+    pub fn with_failure_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Current health of each endpoint, in configuration order.
+    pub fn status(&self) -> Vec<RpcEndpointStatus> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let stats = endpoint.stats();
+                RpcEndpointStatus {
+                    url: endpoint.url.clone(),
+                    latency: stats.latency,
+                    consecutive_failures: stats.consecutive_failures,
+                    available: !self.cooling_down(&stats, now),
+                }
+            })
+            .collect()
+    }
+
+    fn cooling_down(&self, stats: &Stats, now: Instant) -> bool {
+        stats
+            .failed_at
+            .is_some_and(|failed_at| now.duration_since(failed_at) < self.cooldown)
+    }
+
+    /// Endpoint indices in the order they should be tried.
+    fn order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut keys: Vec<_> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, endpoint)| {
+                let stats = endpoint.stats();
+                let cooling_down = self.cooling_down(&stats, now);
+                let unmeasured_fallback = i > 0 && stats.latency.is_none();
+                (
+                    cooling_down,
+                    unmeasured_fallback,
+                    stats.latency.unwrap_or_default(),
+                    i,
+                )
+            })
+            .collect();
+        keys.sort();
+        keys.into_iter().map(|(_, _, _, i)| i).collect()
+    }
+
+    async fn request(
+        self,
+        req: RequestPacket,
+    ) -> std::result::Result<ResponsePacket, TransportError> {
+        let mut last_error = None;
+        for i in self.order() {
+            let endpoint = &self.endpoints[i];
+            let mut transport = endpoint.transport.clone();
+            let start = Instant::now();
+            match transport.call(req.clone()).await {
+                Ok(response) => {
+                    let elapsed = start.elapsed();
+                    let mut stats = endpoint.stats();
+                    stats.latency = Some(match stats.latency {
+                        Some(avg) => {
+                            avg.mul_f64(1.0 - LATENCY_SMOOTHING)
+                                + elapsed.mul_f64(LATENCY_SMOOTHING)
+                        }
+                        None => elapsed,
+                    });
+                    stats.consecutive_failures = 0;
+                    stats.failed_at = None;
+                    return Ok(response);
+                }
+                Err(e) => {
+                    let mut stats = endpoint.stats();
+                    stats.consecutive_failures = stats.consecutive_failures.saturating_add(1);
+                    stats.failed_at = Some(Instant::now());
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| TransportErrorKind::custom_str("No RPC endpoints")))
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().request(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::providers::{Provider, ProviderBuilder};
+    use alloy::rpc::client::RpcClient;
+    use alloy::transports::mock::{Asserter, MockTransport};
+
+    /// An endpoint nothing listens on.
+    fn dead_endpoint() -> (String, BoxTransport) {
+        let url: Url = "http://127.0.0.1:1".parse().unwrap();
+        (url.to_string(), BoxTransport::new(Http::new(url)))
+    }
+
+    fn mock_endpoint(asserter: &Asserter) -> (String, BoxTransport) {
+        (
+            "mock".to_string(),
+            BoxTransport::new(MockTransport::new(asserter.clone())),
+        )
+    }
+
+    #[test]
+    fn test_http_requires_urls() {
+        let result = FailoverTransport::http(&[], Duration::from_secs(1));
+        assert!(matches!(result, Err(ContractError::RpcConnection(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_endpoint() {
+        let asserter = Asserter::new();
+        asserter.push_success(&"0x2a");
+        let transport =
+            FailoverTransport::from_transports([dead_endpoint(), mock_endpoint(&asserter)]);
+        let provider =
+            ProviderBuilder::new().connect_client(RpcClient::new(transport.clone(), false));
+
+        assert_eq!(provider.get_block_number().await.unwrap(), 42);
+
+        let status = transport.status();
+        assert_eq!(status[0].consecutive_failures, 1);
+        assert!(!status[0].available);
+        assert!(status[0].latency.is_none());
+        assert!(status[1].available);
+        assert!(status[1].latency.is_some());
+        // The failed endpoint is tried last until its cooldown ends
+        assert_eq!(transport.order(), vec![1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_errors_do_not_fail_over() {
+        let first = Asserter::new();
+        first.push_failure_msg("execution reverted");
+        let second = Asserter::new();
+        second.push_success(&"0x1");
+        let transport =
+            FailoverTransport::from_transports([mock_endpoint(&first), mock_endpoint(&second)]);
+        let provider = ProviderBuilder::new().connect_client(RpcClient::new(transport, false));
+
+        assert!(provider.get_block_number().await.is_err());
+        assert_eq!(second.read_q().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_all_endpoints_failing() {
+        let transport = FailoverTransport::from_transports([dead_endpoint(), dead_endpoint()])
+            .with_failure_cooldown(Duration::ZERO);
+        let provider =
+            ProviderBuilder::new().connect_client(RpcClient::new(transport.clone(), false));
+
+        assert!(provider.get_block_number().await.is_err());
+        assert!(transport
+            .status()
+            .iter()
+            .all(|s| s.consecutive_failures == 1));
+        assert!(transport.status().iter().all(|s| s.available));
+    }
+
+    #[test]
+    fn test_prefers_lowest_latency() {
+        let asserter = Asserter::new();
+        let transport = FailoverTransport::from_transports([
+            mock_endpoint(&asserter),
+            mock_endpoint(&asserter),
+            mock_endpoint(&asserter),
+        ]);
+        transport.endpoints[0].stats().latency = Some(Duration::from_millis(200));
+        transport.endpoints[1].stats().latency = Some(Duration::from_millis(50));
+        // Unmeasured fallbacks are tried after measured endpoints
+        assert_eq!(transport.order(), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_prefers_unmeasured_primary_over_fallbacks() {
+        let asserter = Asserter::new();
+        let transport = FailoverTransport::from_transports([
+            mock_endpoint(&asserter),
+            mock_endpoint(&asserter),
+            mock_endpoint(&asserter),
+        ]);
+        assert_eq!(transport.order(), vec![0, 1, 2]);
+
+        transport.endpoints[2].stats().latency = Some(Duration::from_millis(50));
+        assert_eq!(transport.order(), vec![0, 2, 1]);
+    }
+}
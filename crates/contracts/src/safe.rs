@@ -0,0 +1,476 @@
+//! Safe (Gnosis) multisig transaction proposals.
+//!
+//! Vaults are often owned or curated by a Safe, so admin calls such as `submitCap` or
+//! `setFee` have to go through the Safe's owners instead of being sent directly.
+//! [`PreparedCall::propose_to_safe`] turns any prepared call into a Safe transaction,
+//! signs its EIP-712 hash with one owner's key, and submits it to the Safe Transaction
+//! Service, where the other owners can confirm and execute it.
+//!
+//! The proposal uses the Safe's next free nonce: the on-chain nonce, or one past the
+//! highest transaction already queued in the service.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::safe::{safe_transaction_service_url, SafeProposer};
+//!
+//! let service = safe_transaction_service_url(1).unwrap();
+//! let proposer = SafeProposer::new(safe_address, service, owner_private_key)?;
+//!
+//! let proposal = curator_client
+//!     .submit_cap(vault, args)
+//!     .await?
+//!     .propose_to_safe(&proposer)
+//!     .await?;
+//! println!("queued {} at nonce {}", proposal.safe_tx_hash, proposal.nonce);
+//! ```
+
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use alloy::sol;
+use alloy::sol_types::{eip712_domain, SolCall, SolStruct};
+use alloy::transports::http::reqwest;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ContractError, Result};
+use crate::prepared_call::PreparedCall;
+use crate::provider::HttpProvider;
+
+sol! {
+    #[sol(rpc)]
+    interface ISafe {
+        function nonce() external view returns (uint256);
+    }
+
+    /// Safe transaction, as hashed by `Safe.getTransactionHash`.
+    #[derive(Debug, PartialEq, Eq)]
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+}
+
+/// Safe Transaction Service URL for a chain, if Safe hosts one.
+pub fn safe_transaction_service_url(chain_id: u64) -> Option<&'static str> {
+    Some(match chain_id {
+        1 => "https://safe-transaction-mainnet.safe.global",
+        10 => "https://safe-transaction-optimism.safe.global",
+        137 => "https://safe-transaction-polygon.safe.global",
+        8453 => "https://safe-transaction-base.safe.global",
+        42161 => "https://safe-transaction-arbitrum.safe.global",
+        11_155_111 => "https://safe-transaction-sepolia.safe.global",
+        _ => return None,
+    })
+}
+
+impl SafeTx {
+    /// A plain call (no delegatecall, no gas refund) at `nonce`.
+    pub fn call(to: Address, value: U256, data: Bytes, nonce: U256) -> Self {
+        Self {
+            to,
+            value,
+            data,
+            operation: 0,
+            safeTxGas: U256::ZERO,
+            baseGas: U256::ZERO,
+            gasPrice: U256::ZERO,
+            gasToken: Address::ZERO,
+            refundReceiver: Address::ZERO,
+            nonce,
+        }
+    }
+
+    /// EIP-712 hash the owners sign (`safeTxHash`), for a Safe v1.3.0 or later.
+    pub fn safe_tx_hash(&self, safe: Address, chain_id: u64) -> B256 {
+        let domain = eip712_domain! {
+            chain_id: chain_id,
+            verifying_contract: safe,
+        };
+        self.eip712_signing_hash(&domain)
+    }
+}
+
+/// A transaction proposed to the Safe Transaction Service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeProposal {
+    /// Hash the remaining owners confirm.
+    pub safe_tx_hash: B256,
+    /// Safe nonce the transaction was queued at.
+    pub nonce: U256,
+    /// Owner that signed the proposal.
+    pub sender: Address,
+}
+
+/// Request body of `POST /api/v1/safes/{safe}/multisig-transactions/`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposeRequest {
+    to: String,
+    value: String,
+    data: Option<Bytes>,
+    operation: u8,
+    safe_tx_gas: String,
+    base_gas: String,
+    gas_price: String,
+    gas_token: String,
+    refund_receiver: String,
+    nonce: String,
+    contract_transaction_hash: B256,
+    sender: String,
+    signature: Bytes,
+    origin: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueuedTransactions {
+    results: Vec<QueuedTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueuedTransaction {
+    nonce: u64,
+}
+
+/// Proposes transactions to a Safe on behalf of one of its owners.
+#[derive(Debug, Clone)]
+pub struct SafeProposer {
+    safe: Address,
+    service_url: String,
+    signer: PrivateKeySigner,
+    api_key: Option<String>,
+    http_client: reqwest::Client,
+}
+
+impl SafeProposer {
+    /// Create a proposer for `safe` using the Safe Transaction Service at `service_url`,
+    /// signing with an owner's `private_key`.
+    pub fn new(safe: Address, service_url: &str, private_key: &str) -> Result<Self> {
+        let signer: PrivateKeySigner = private_key
+            .parse()
+            .map_err(|_| ContractError::InvalidPrivateKey)?;
+        let service_url: url::Url = service_url
+            .parse()
+            .map_err(|e| ContractError::RpcConnection(format!("{}", e)))?;
+        Ok(Self {
+            safe,
+            service_url: service_url.as_str().trim_end_matches('/').to_string(),
+            signer,
+            api_key: None,
+            http_client: reqwest::Client::new(),
+        })
+    }
+
+    /// Send an API key as bearer token, for service deployments that require one.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// The Safe transactions are proposed to.
+    pub fn safe(&self) -> Address {
+        self.safe
+    }
+
+    /// The owner signing proposals.
+    pub fn sender(&self) -> Address {
+        self.signer.address()
+    }
+
+    fn transactions_url(&self) -> String {
+        format!(
+            "{}/api/v1/safes/{}/multisig-transactions/",
+            self.service_url,
+            self.safe.to_checksum(None)
+        )
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    /// Next unused Safe nonce: the on-chain nonce, or one past the highest
+    /// transaction queued in the service.
+    pub async fn next_nonce(&self, provider: &HttpProvider) -> Result<U256> {
+        let onchain = ISafe::new(self.safe, provider)
+            .nonce()
+            .call()
+            .await
+            .map_err(|e| {
+                ContractError::RpcConnection(format!("Failed to get Safe nonce: {}", e))
+            })?;
+
+        let response = self
+            .request(self.http_client.get(self.transactions_url()))
+            .query(&[
+                ("executed", "false"),
+                ("nonce__gte", &onchain.to_string()),
+                ("ordering", "-nonce"),
+                ("limit", "1"),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| {
+                ContractError::RpcConnection(format!(
+                    "Failed to get queued Safe transactions: {}",
+                    e
+                ))
+            })?;
+        let queued: QueuedTransactions = response.json().await.map_err(|e| {
+            ContractError::RpcConnection(format!(
+                "Invalid Safe Transaction Service response: {}",
+                e
+            ))
+        })?;
+
+        Ok(queued.results.first().map_or(onchain, |tx| {
+            onchain.max(U256::from(tx.nonce) + U256::from(1))
+        }))
+    }
+
+    /// Sign `tx` and submit it to the service.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::TransactionFailed`] if the service rejects the
+    /// proposal (for example when the signer is not an owner).
+    pub async fn propose(&self, tx: &SafeTx, chain_id: u64) -> Result<SafeProposal> {
+        let safe_tx_hash = tx.safe_tx_hash(self.safe, chain_id);
+        let signature = self
+            .signer
+            .sign_hash_sync(&safe_tx_hash)
+            .map_err(|e| ContractError::TransactionFailed(format!("Failed to sign: {}", e)))?;
+
+        let body = ProposeRequest {
+            to: tx.to.to_checksum(None),
+            value: tx.value.to_string(),
+            data: (!tx.data.is_empty()).then(|| tx.data.clone()),
+            operation: tx.operation,
+            safe_tx_gas: tx.safeTxGas.to_string(),
+            base_gas: tx.baseGas.to_string(),
+            gas_price: tx.gasPrice.to_string(),
+            gas_token: tx.gasToken.to_checksum(None),
+            refund_receiver: tx.refundReceiver.to_checksum(None),
+            nonce: tx.nonce.to_string(),
+            contract_transaction_hash: safe_tx_hash,
+            sender: self.sender().to_checksum(None),
+            signature: signature.as_bytes().into(),
+            origin: "morpho-rs",
+        };
+        let response = self
+            .request(self.http_client.post(self.transactions_url()))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                ContractError::RpcConnection(format!(
+                    "Failed to reach Safe Transaction Service: {}",
+                    e
+                ))
+            })?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            return Err(ContractError::TransactionFailed(format!(
+                "Safe Transaction Service rejected proposal ({}): {}",
+                status, message
+            )));
+        }
+
+        Ok(SafeProposal {
+            safe_tx_hash,
+            nonce: tx.nonce,
+            sender: self.sender(),
+        })
+    }
+}
+
+impl<C: SolCall> PreparedCall<'_, C> {
+    /// Propose this call as a Safe transaction instead of sending it.
+    ///
+    /// Uses [`SafeProposer::next_nonce`] and the chain ID of the client's RPC node.
+    pub async fn propose_to_safe(self, proposer: &SafeProposer) -> Result<SafeProposal> {
+        let provider = self.provider();
+        let chain_id = provider
+            .get_chain_id()
+            .await
+            .map_err(|e| ContractError::RpcConnection(format!("Failed to get chain ID: {}", e)))?;
+        let nonce = proposer.next_nonce(provider).await?;
+        let value = self.value();
+        let (to, call) = self.prepare();
+        let tx = SafeTx::call(to, value, call.abi_encode().into(), nonce);
+        proposer.propose(&tx, chain_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::network::EthereumWallet;
+    use alloy::primitives::Signature;
+    use alloy::providers::ProviderBuilder;
+    use alloy::transports::mock::Asserter;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::erc20::IERC20;
+
+    // Anvil's default account 0
+    const OWNER_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn safe() -> Address {
+        Address::repeat_byte(0x5a)
+    }
+
+    fn mocked_provider(asserter: &Asserter) -> HttpProvider {
+        let signer: PrivateKeySigner = OWNER_KEY.parse().unwrap();
+        ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_mocked_client(asserter.clone())
+    }
+
+    #[test]
+    fn test_safe_tx_type() {
+        assert_eq!(
+            SafeTx::eip712_root_type(),
+            "SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,\
+             uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,\
+             uint256 nonce)"
+        );
+    }
+
+    #[test]
+    fn test_safe_tx_hash_binds_safe_and_chain() {
+        let tx = SafeTx::call(
+            Address::repeat_byte(1),
+            U256::ZERO,
+            Bytes::new(),
+            U256::from(3),
+        );
+        let hash = tx.safe_tx_hash(safe(), 1);
+        assert_ne!(hash, tx.safe_tx_hash(safe(), 8453));
+        assert_ne!(hash, tx.safe_tx_hash(Address::repeat_byte(0x5b), 1));
+    }
+
+    #[test]
+    fn test_service_urls() {
+        assert_eq!(
+            safe_transaction_service_url(8453),
+            Some("https://safe-transaction-base.safe.global")
+        );
+        assert!(safe_transaction_service_url(999_999).is_none());
+    }
+
+    #[test]
+    fn test_invalid_private_key() {
+        let result = SafeProposer::new(safe(), "https://safe.example.com", "invalid");
+        assert!(matches!(result, Err(ContractError::InvalidPrivateKey)));
+    }
+
+    #[tokio::test]
+    async fn test_propose_prepared_call() {
+        let server = MockServer::start().await;
+        let transactions = format!(
+            "/api/v1/safes/{}/multisig-transactions/",
+            safe().to_checksum(None)
+        );
+        // Nonces 4 and 5 are queued on top of on-chain nonce 4
+        Mock::given(method("GET"))
+            .and(path(transactions.as_str()))
+            .and(query_param("nonce__gte", "4"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "count": 2,
+                "results": [{ "nonce": 5 }],
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(transactions.as_str()))
+            .and(body_partial_json(
+                serde_json::json!({ "nonce": "6", "operation": 0 }),
+            ))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let asserter = Asserter::new();
+        asserter.push_success(&"0x1"); // eth_chainId
+        asserter.push_success(&B256::from(U256::from(4))); // Safe.nonce()
+        let provider = mocked_provider(&asserter);
+        let proposer = SafeProposer::new(safe(), &server.uri(), OWNER_KEY).unwrap();
+
+        let call = IERC20::approveCall {
+            spender: Address::repeat_byte(2),
+            amount: U256::from(100),
+        };
+        let proposal =
+            PreparedCall::new(Address::repeat_byte(1), call.clone(), U256::ZERO, &provider)
+                .propose_to_safe(&proposer)
+                .await
+                .unwrap();
+
+        assert_eq!(proposal.nonce, U256::from(6));
+        assert_eq!(proposal.sender, proposer.sender());
+        let tx = SafeTx::call(
+            Address::repeat_byte(1),
+            U256::ZERO,
+            call.abi_encode().into(),
+            U256::from(6),
+        );
+        assert_eq!(proposal.safe_tx_hash, tx.safe_tx_hash(safe(), 1));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_proposal() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(422).set_body_string("Signer is not an owner"))
+            .mount(&server)
+            .await;
+
+        let proposer = SafeProposer::new(safe(), &server.uri(), OWNER_KEY).unwrap();
+        let tx = SafeTx::call(
+            Address::repeat_byte(1),
+            U256::ZERO,
+            Bytes::new(),
+            U256::ZERO,
+        );
+        match proposer.propose(&tx, 1).await {
+            Err(ContractError::TransactionFailed(msg)) => assert!(msg.contains("not an owner")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_signature_recovers_owner() {
+        let tx = SafeTx::call(
+            Address::repeat_byte(1),
+            U256::ZERO,
+            Bytes::new(),
+            U256::ZERO,
+        );
+        let hash = tx.safe_tx_hash(safe(), 1);
+        let signer: PrivateKeySigner = OWNER_KEY.parse().unwrap();
+        let signature: Signature = signer.sign_hash_sync(&hash).unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&hash).unwrap(),
+            signer.address()
+        );
+        // Safe expects v in {27, 28} for EOA signatures over the hash
+        assert!(matches!(signature.as_bytes()[64], 27 | 28));
+    }
+}
@@ -0,0 +1,177 @@
+//! Universal Rewards Distributor (URD) bindings and claim client.
+//!
+//! Morpho rewards are distributed through URD contracts holding a Merkle root of
+//! cumulative `(account, reward, claimable)` entries. Claiming sends the cumulative
+//! claimable amount with its Merkle proof; the distributor transfers the difference
+//! from what was already claimed. Proofs come from the Morpho rewards API.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::UniversalRewardsDistributorClient;
+//!
+//! let client = UniversalRewardsDistributorClient::new(rpc_url, private_key)?;
+//! let receipt = client
+//!     .claim(distributor, account, reward_token, claimable, proof)
+//!     .send()
+//!     .await?;
+//! ```
+
+use std::sync::Arc;
+
+use alloy::primitives::{Address, B256, U256};
+use alloy::rpc::types::BlockId;
+use alloy::sol;
+
+use crate::error::{ContractError, Result};
+use crate::metrics::{Metrics, NoopMetrics};
+use crate::prepared_call::PreparedCall;
+use crate::provider::{connect, HttpProvider};
+
+sol! {
+    #[sol(rpc)]
+    interface IUniversalRewardsDistributor {
+        function claim(address account, address reward, uint256 claimable, bytes32[] calldata proof)
+            external returns (uint256 amount);
+        function claimed(address account, address reward) external view returns (uint256);
+        function root() external view returns (bytes32);
+    }
+}
+
+/// Client for claiming rewards from Universal Rewards Distributors.
+pub struct UniversalRewardsDistributorClient {
+    provider: HttpProvider,
+    signer_address: Address,
+    metrics: Arc<dyn Metrics>,
+    block: BlockId,
+}
+
+impl UniversalRewardsDistributorClient {
+    /// Create a new URD client.
+    pub fn new(rpc_url: &str, private_key: &str) -> Result<Self> {
+        Self::new_with_fallbacks(&[rpc_url], private_key)
+    }
+
+    /// Create a new URD client over several RPC endpoints, failing over between them.
+    pub fn new_with_fallbacks(rpc_urls: &[&str], private_key: &str) -> Result<Self> {
+        let (provider, signer_address) = connect(rpc_urls, private_key)?;
+
+        Ok(Self {
+            provider,
+            signer_address,
+            metrics: Arc::new(NoopMetrics),
+            block: BlockId::latest(),
+        })
+    }
+
+    /// Set the metrics sink notified on transaction submission and confirmation.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Pin view reads to a block number instead of the latest block.
+    pub fn at_block(mut self, block: u64) -> Self {
+        self.block = BlockId::number(block);
+        self
+    }
+
+    /// Get the underlying provider.
+    pub fn provider(&self) -> &HttpProvider {
+        &self.provider
+    }
+
+    /// Get the signer's address.
+    pub fn signer_address(&self) -> Address {
+        self.signer_address
+    }
+
+    /// Create a prepared claim transaction.
+    ///
+    /// `claimable` is the cumulative amount in the distributor's Merkle tree, not the
+    /// amount left to claim. Anyone can claim on behalf of `account`; the rewards are
+    /// always sent to `account`.
+    pub fn claim(
+        &self,
+        distributor: Address,
+        account: Address,
+        reward_token: Address,
+        claimable: U256,
+        proof: Vec<B256>,
+    ) -> PreparedCall<'_, IUniversalRewardsDistributor::claimCall> {
+        let call = IUniversalRewardsDistributor::claimCall {
+            account,
+            reward: reward_token,
+            claimable,
+            proof,
+        };
+        PreparedCall::new(distributor, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Get the amount of `reward_token` already claimed by `account`.
+    pub async fn claimed(
+        &self,
+        distributor: Address,
+        account: Address,
+        reward_token: Address,
+    ) -> Result<U256> {
+        let contract = IUniversalRewardsDistributor::new(distributor, &self.provider);
+        contract
+            .claimed(account, reward_token)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| ContractError::TransactionFailed(format!("Failed to get claimed: {}", e)))
+    }
+
+    /// Get the distributor's current Merkle root.
+    pub async fn root(&self, distributor: Address) -> Result<B256> {
+        let contract = IUniversalRewardsDistributor::new(distributor, &self.provider);
+        contract
+            .root()
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| ContractError::TransactionFailed(format!("Failed to get root: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_invalid_private_key() {
+        let result = UniversalRewardsDistributorClient::new("http://localhost:8545", "invalid_key");
+        assert!(matches!(result, Err(ContractError::InvalidPrivateKey)));
+    }
+
+    #[test]
+    fn test_invalid_rpc_url() {
+        let result = UniversalRewardsDistributorClient::new("not a valid url", PRIVATE_KEY);
+        assert!(matches!(result, Err(ContractError::RpcConnection(_))));
+    }
+
+    #[test]
+    fn test_claim_prepares_call() {
+        let client =
+            UniversalRewardsDistributorClient::new("http://localhost:8545", PRIVATE_KEY).unwrap();
+        let distributor = Address::repeat_byte(0x33);
+        let account = Address::repeat_byte(0x01);
+        let reward = Address::repeat_byte(0x02);
+        let proof = vec![B256::repeat_byte(0xaa), B256::repeat_byte(0xbb)];
+
+        let prepared = client.claim(distributor, account, reward, U256::from(1_000u64), proof);
+        assert_eq!(prepared.value(), U256::ZERO);
+
+        let (to, call) = prepared.prepare();
+        assert_eq!(to, distributor);
+        assert_eq!(call.account, account);
+        assert_eq!(call.reward, reward);
+        assert_eq!(call.claimable, U256::from(1_000u64));
+        assert_eq!(call.proof.len(), 2);
+    }
+}
@@ -0,0 +1,791 @@
+//! ERC-4337 user operations for smart-account users.
+//!
+//! [`UserOperationClient`] executes calls from a smart account instead of the signing
+//! EOA: it wraps the calls in the account's execute function, signs the resulting
+//! [`PackedUserOperation`] with the account owner's key, and submits it to a bundler,
+//! which includes it through the EntryPoint (v0.7 by default). The calls themselves are
+//! the same [`PreparedCall`]s the transaction clients produce, so a deposit built for an
+//! EOA can be sent from a Safe or Kernel account unchanged.
+//!
+//! Supported accounts ([`SmartAccount`]):
+//! - `Simple` - the reference `SimpleAccount` (`execute` / `executeBatch`)
+//! - `Safe` - a Safe with the Safe4337Module enabled as module and fallback handler;
+//!   batches go through `MultiSendCallOnly`
+//! - `Kernel` - a Kernel v3 account whose root validator is the ECDSA validator
+//!
+//! The account must already be deployed, and its deposit at the EntryPoint must cover
+//! gas; paymasters are not used.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_contracts::{SmartAccount, UserOperationClient, UserOperationConfig};
+//!
+//! let config = UserOperationConfig::new(bundler_url, safe_address, SmartAccount::Safe);
+//! let user_ops = UserOperationClient::new(&[rpc_url], owner_private_key, config)?;
+//!
+//! let approve = vault_client.approve(asset, vault, amount);
+//! let deposit = vault_client.deposit(vault, amount, safe_address);
+//! let receipt = user_ops.execute(&[approve.into(), deposit.into()]).await?;
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::primitives::{address, keccak256, Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::RpcError;
+use alloy::rpc::types::erc4337::{PackedUserOperation, UserOperationReceipt};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use alloy::sol;
+use alloy::sol_types::{eip712_domain, SolCall, SolStruct, SolValue};
+use alloy::transports::TransportErrorKind;
+use serde::Deserialize;
+use web_time::Instant;
+
+use crate::error::{ContractError, Result};
+use crate::metrics::{Metrics, NoopMetrics};
+use crate::platform;
+use crate::prepared_call::PreparedCall;
+use crate::provider::{connect, HttpProvider};
+
+/// Canonical EntryPoint v0.7 address (same on every chain).
+pub const ENTRY_POINT_V07: Address = address!("0000000071727De22E5E9d8BAf3edAe6cAaD0Fd8");
+
+/// Safe4337Module v0.3.0, the module and fallback handler used by Safe accounts.
+pub const SAFE_4337_MODULE: Address = address!("75cf11467937ce3F2f357CE24ffc3DBF8fD5c226");
+
+/// Safe `MultiSendCallOnly` v1.4.1, used to batch calls from a Safe.
+pub const SAFE_MULTI_SEND_CALL_ONLY: Address = address!("9641d764fc13c8B624c04430C7356C1C7C8102e2");
+
+/// Default time to wait for a user operation to be included.
+pub const DEFAULT_USER_OP_TIMEOUT_SECS: u64 = 120;
+
+/// How often the bundler is polled for a user operation receipt.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+sol! {
+    #[sol(rpc)]
+    interface IEntryPoint {
+        function getNonce(address sender, uint192 key) external view returns (uint256 nonce);
+    }
+
+    interface ISimpleAccount {
+        function execute(address dest, uint256 value, bytes calldata func) external;
+        function executeBatch(address[] calldata dest, uint256[] calldata value, bytes[] calldata func) external;
+    }
+
+    interface IKernel {
+        function execute(bytes32 execMode, bytes calldata executionCalldata) external payable;
+    }
+
+    struct Execution {
+        address target;
+        uint256 value;
+        bytes callData;
+    }
+
+    interface ISafe4337Module {
+        function executeUserOp(address to, uint256 value, bytes calldata data, uint8 operation) external;
+    }
+
+    interface IMultiSend {
+        function multiSend(bytes memory transactions) external payable;
+    }
+
+    struct SafeOp {
+        address safe;
+        uint256 nonce;
+        bytes initCode;
+        bytes callData;
+        uint128 verificationGasLimit;
+        uint128 callGasLimit;
+        uint256 preVerificationGas;
+        uint128 maxPriorityFeePerGas;
+        uint128 maxFeePerGas;
+        bytes paymasterAndData;
+        uint48 validAfter;
+        uint48 validUntil;
+        address entryPoint;
+    }
+}
+
+/// A call made by a smart account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountCall {
+    /// Target contract.
+    pub to: Address,
+    /// ETH sent with the call.
+    pub value: U256,
+    /// ABI-encoded calldata.
+    pub data: Bytes,
+}
+
+impl<C: SolCall> From<PreparedCall<'_, C>> for AccountCall {
+    fn from(call: PreparedCall<'_, C>) -> Self {
+        let value = call.value();
+        let (to, call) = call.prepare();
+        Self {
+            to,
+            value,
+            data: call.abi_encode().into(),
+        }
+    }
+}
+
+/// Smart account implementation, which determines how calls are encoded and signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartAccount {
+    /// Reference `SimpleAccount` owned by an ECDSA key.
+    Simple,
+    /// Safe with the Safe4337Module ([`SAFE_4337_MODULE`]) enabled.
+    Safe,
+    /// Kernel v3 with the ECDSA validator as root validator.
+    Kernel,
+}
+
+impl SmartAccount {
+    /// Encode `calls` as the account's execution calldata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::TransactionFailed`] if `calls` is empty.
+    pub fn encode_calls(&self, calls: &[AccountCall]) -> Result<Bytes> {
+        let data = match (self, calls) {
+            (_, []) => {
+                return Err(ContractError::TransactionFailed(
+                    "User operation has no calls".to_string(),
+                ))
+            }
+            (SmartAccount::Simple, [call]) => ISimpleAccount::executeCall {
+                dest: call.to,
+                value: call.value,
+                func: call.data.clone(),
+            }
+            .abi_encode(),
+            (SmartAccount::Simple, calls) => ISimpleAccount::executeBatchCall {
+                dest: calls.iter().map(|c| c.to).collect(),
+                value: calls.iter().map(|c| c.value).collect(),
+                func: calls.iter().map(|c| c.data.clone()).collect(),
+            }
+            .abi_encode(),
+            (SmartAccount::Safe, [call]) => ISafe4337Module::executeUserOpCall {
+                to: call.to,
+                value: call.value,
+                data: call.data.clone(),
+                operation: 0,
+            }
+            .abi_encode(),
+            (SmartAccount::Safe, calls) => {
+                let mut transactions = Vec::new();
+                for call in calls {
+                    // operation (0 = call), to, value, data length, data
+                    transactions.push(0u8);
+                    transactions.extend_from_slice(call.to.as_slice());
+                    transactions.extend_from_slice(&call.value.to_be_bytes::<32>());
+                    transactions
+                        .extend_from_slice(&U256::from(call.data.len()).to_be_bytes::<32>());
+                    transactions.extend_from_slice(&call.data);
+                }
+                ISafe4337Module::executeUserOpCall {
+                    to: SAFE_MULTI_SEND_CALL_ONLY,
+                    value: U256::ZERO,
+                    data: IMultiSend::multiSendCall {
+                        transactions: transactions.into(),
+                    }
+                    .abi_encode()
+                    .into(),
+                    // MultiSend must be delegatecalled
+                    operation: 1,
+                }
+                .abi_encode()
+            }
+            (SmartAccount::Kernel, [call]) => {
+                // Mode: call type 0x00 (single), default exec type, no selector/payload
+                let execution = (call.to, call.value, call.data.clone()).abi_encode_packed();
+                IKernel::executeCall {
+                    execMode: B256::ZERO,
+                    executionCalldata: execution.into(),
+                }
+                .abi_encode()
+            }
+            (SmartAccount::Kernel, calls) => {
+                let mut mode = B256::ZERO;
+                mode[0] = 0x01; // call type 0x01 (batch)
+                let executions: Vec<Execution> = calls
+                    .iter()
+                    .map(|c| Execution {
+                        target: c.to,
+                        value: c.value,
+                        callData: c.data.clone(),
+                    })
+                    .collect();
+                IKernel::executeCall {
+                    execMode: mode,
+                    executionCalldata: executions.abi_encode_params().into(),
+                }
+                .abi_encode()
+            }
+        };
+        Ok(data.into())
+    }
+
+    /// Placeholder signature of the right shape, used for gas estimation.
+    fn dummy_signature(&self) -> Bytes {
+        let mut signature = vec![0xff; 64];
+        signature.push(0x1c);
+        if *self == SmartAccount::Safe {
+            // validAfter and validUntil precede the owner signature
+            signature.splice(0..0, [0u8; 12]);
+        }
+        signature.into()
+    }
+}
+
+/// Bundler and account settings for [`UserOperationClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserOperationConfig {
+    /// ERC-4337 bundler JSON-RPC URL.
+    pub bundler_url: String,
+    /// Smart account address that holds the funds and sends the calls.
+    pub account: Address,
+    /// Smart account implementation.
+    pub kind: SmartAccount,
+    /// EntryPoint the account and bundler use. Defaults to [`ENTRY_POINT_V07`].
+    pub entry_point: Address,
+    /// How long to wait for inclusion before giving up.
+    pub timeout: Duration,
+}
+
+impl UserOperationConfig {
+    /// Create a configuration for `account` using EntryPoint v0.7.
+    pub fn new(bundler_url: impl Into<String>, account: Address, kind: SmartAccount) -> Self {
+        Self {
+            bundler_url: bundler_url.into(),
+            account,
+            kind,
+            entry_point: ENTRY_POINT_V07,
+            timeout: Duration::from_secs(DEFAULT_USER_OP_TIMEOUT_SECS),
+        }
+    }
+
+    /// Set the EntryPoint address (must be v0.7-compatible).
+    pub fn with_entry_point(mut self, entry_point: Address) -> Self {
+        self.entry_point = entry_point;
+        self
+    }
+
+    /// Set how long to wait for inclusion.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Gas limits returned by `eth_estimateUserOperationGas`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GasEstimate {
+    pre_verification_gas: U256,
+    verification_gas_limit: U256,
+    call_gas_limit: U256,
+}
+
+/// Hash of a v0.7 user operation, as computed by `EntryPoint.getUserOpHash`.
+pub fn user_operation_hash(op: &PackedUserOperation, entry_point: Address, chain_id: u64) -> B256 {
+    let encoded = (
+        op.sender,
+        op.nonce,
+        keccak256(init_code(op)),
+        keccak256(&op.call_data),
+        pack_u128s(op.verification_gas_limit, op.call_gas_limit),
+        op.pre_verification_gas,
+        pack_u128s(op.max_priority_fee_per_gas, op.max_fee_per_gas),
+        keccak256(paymaster_and_data(op)),
+    )
+        .abi_encode();
+    keccak256((keccak256(encoded), entry_point, U256::from(chain_id)).abi_encode())
+}
+
+fn init_code(op: &PackedUserOperation) -> Vec<u8> {
+    let mut code = Vec::new();
+    if let Some(factory) = op.factory {
+        code.extend_from_slice(factory.as_slice());
+        code.extend_from_slice(op.factory_data.as_ref().map_or(&[][..], |d| &d[..]));
+    }
+    code
+}
+
+fn paymaster_and_data(op: &PackedUserOperation) -> Vec<u8> {
+    let mut data = Vec::new();
+    if let Some(paymaster) = op.paymaster {
+        data.extend_from_slice(paymaster.as_slice());
+        data.extend_from_slice(
+            pack_u128s(
+                op.paymaster_verification_gas_limit.unwrap_or_default(),
+                op.paymaster_post_op_gas_limit.unwrap_or_default(),
+            )
+            .as_slice(),
+        );
+        data.extend_from_slice(op.paymaster_data.as_ref().map_or(&[][..], |d| &d[..]));
+    }
+    data
+}
+
+/// Two 128-bit values packed into one word, `high` first.
+fn pack_u128s(high: U256, low: U256) -> B256 {
+    B256::from((high << 128) | (low & U256::from(u128::MAX)))
+}
+
+/// Client that executes calls from a smart account via ERC-4337 user operations.
+pub struct UserOperationClient {
+    provider: HttpProvider,
+    bundler: RpcClient,
+    signer: PrivateKeySigner,
+    config: UserOperationConfig,
+    metrics: Arc<dyn Metrics>,
+}
+
+impl UserOperationClient {
+    /// Create a client reading chain state from `rpc_urls` and signing with the
+    /// account owner's `private_key`.
+    pub fn new(rpc_urls: &[&str], private_key: &str, config: UserOperationConfig) -> Result<Self> {
+        let signer: PrivateKeySigner = private_key
+            .parse()
+            .map_err(|_| ContractError::InvalidPrivateKey)?;
+        let (provider, _) = connect(rpc_urls, private_key)?;
+        let bundler_url: url::Url = config
+            .bundler_url
+            .parse()
+            .map_err(|e| ContractError::RpcConnection(format!("{}", e)))?;
+        Ok(Self::from_parts(
+            provider,
+            RpcClient::new_http(bundler_url),
+            signer,
+            config,
+        ))
+    }
+
+    fn from_parts(
+        provider: HttpProvider,
+        bundler: RpcClient,
+        signer: PrivateKeySigner,
+        config: UserOperationConfig,
+    ) -> Self {
+        Self {
+            provider,
+            bundler,
+            signer,
+            config,
+            metrics: Arc::new(NoopMetrics),
+        }
+    }
+
+    /// Set the metrics sink notified when user operations are submitted and included.
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Smart account that sends the calls.
+    pub fn account(&self) -> Address {
+        self.config.account
+    }
+
+    /// Address of the key that signs for the account.
+    pub fn owner(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// Get the client configuration.
+    pub fn config(&self) -> &UserOperationConfig {
+        &self.config
+    }
+
+    /// Build and sign a user operation executing `calls`, with gas limits estimated
+    /// by the bundler and fees estimated from the RPC node.
+    pub async fn build(&self, calls: &[AccountCall]) -> Result<PackedUserOperation> {
+        let call_data = self.config.kind.encode_calls(calls)?;
+        let chain_id =
+            self.provider.get_chain_id().await.map_err(|e| {
+                ContractError::RpcConnection(format!("Failed to get chain ID: {}", e))
+            })?;
+        let nonce = IEntryPoint::new(self.config.entry_point, &self.provider)
+            .getNonce(self.config.account, alloy::primitives::aliases::U192::ZERO)
+            .call()
+            .await
+            .map_err(|e| ContractError::RpcConnection(format!("Failed to get nonce: {}", e)))?;
+        let fees =
+            self.provider.estimate_eip1559_fees().await.map_err(|e| {
+                ContractError::RpcConnection(format!("Failed to estimate fees: {}", e))
+            })?;
+
+        let mut op = PackedUserOperation {
+            sender: self.config.account,
+            nonce,
+            factory: None,
+            factory_data: None,
+            call_data,
+            call_gas_limit: U256::ZERO,
+            verification_gas_limit: U256::ZERO,
+            pre_verification_gas: U256::ZERO,
+            max_fee_per_gas: U256::from(fees.max_fee_per_gas),
+            max_priority_fee_per_gas: U256::from(fees.max_priority_fee_per_gas),
+            paymaster: None,
+            paymaster_verification_gas_limit: None,
+            paymaster_post_op_gas_limit: None,
+            paymaster_data: None,
+            signature: self.config.kind.dummy_signature(),
+        };
+
+        let gas: GasEstimate = self
+            .bundler
+            .request(
+                "eth_estimateUserOperationGas",
+                (&op, self.config.entry_point),
+            )
+            .await
+            .map_err(|e| bundler_error("estimate user operation gas", e))?;
+        op.call_gas_limit = gas.call_gas_limit;
+        op.verification_gas_limit = gas.verification_gas_limit;
+        op.pre_verification_gas = gas.pre_verification_gas;
+
+        op.signature = self.sign(&op, chain_id)?;
+        Ok(op)
+    }
+
+    /// Signature authorizing `op` for the configured account.
+    fn sign(&self, op: &PackedUserOperation, chain_id: u64) -> Result<Bytes> {
+        let signature = match self.config.kind {
+            SmartAccount::Simple | SmartAccount::Kernel => {
+                let hash = user_operation_hash(op, self.config.entry_point, chain_id);
+                self.signer
+                    .sign_message_sync(hash.as_slice())
+                    .map_err(|e| {
+                        ContractError::TransactionFailed(format!("Failed to sign: {}", e))
+                    })?
+                    .as_bytes()
+                    .to_vec()
+            }
+            SmartAccount::Safe => {
+                let safe_op = SafeOp {
+                    safe: op.sender,
+                    nonce: op.nonce,
+                    initCode: init_code(op).into(),
+                    callData: op.call_data.clone(),
+                    verificationGasLimit: op.verification_gas_limit.saturating_to(),
+                    callGasLimit: op.call_gas_limit.saturating_to(),
+                    preVerificationGas: op.pre_verification_gas,
+                    maxPriorityFeePerGas: op.max_priority_fee_per_gas.saturating_to(),
+                    maxFeePerGas: op.max_fee_per_gas.saturating_to(),
+                    paymasterAndData: paymaster_and_data(op).into(),
+                    validAfter: alloy::primitives::aliases::U48::ZERO,
+                    validUntil: alloy::primitives::aliases::U48::ZERO,
+                    entryPoint: self.config.entry_point,
+                };
+                let domain = eip712_domain! {
+                    chain_id: chain_id,
+                    verifying_contract: SAFE_4337_MODULE,
+                };
+                let signature = self
+                    .signer
+                    .sign_hash_sync(&safe_op.eip712_signing_hash(&domain))
+                    .map_err(|e| {
+                        ContractError::TransactionFailed(format!("Failed to sign: {}", e))
+                    })?;
+                // validAfter (6 bytes) and validUntil (6 bytes), both unbounded
+                let mut bytes = vec![0u8; 12];
+                bytes.extend_from_slice(&signature.as_bytes());
+                bytes
+            }
+        };
+        Ok(signature.into())
+    }
+
+    /// Submit a signed user operation to the bundler, returning its hash.
+    pub async fn send(&self, op: &PackedUserOperation) -> Result<B256> {
+        let hash: B256 = self
+            .bundler
+            .request("eth_sendUserOperation", (op, self.config.entry_point))
+            .await
+            .map_err(|e| {
+                self.metrics.record_tx_failed(self.config.account);
+                bundler_error("send user operation", e)
+            })?;
+        self.metrics.record_tx_submitted(self.config.account);
+        Ok(hash)
+    }
+
+    /// Poll the bundler until the user operation is included or the timeout elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::UserOperationTimeout`] if the operation was not
+    /// included in time.
+    pub async fn wait_for_receipt(&self, hash: B256) -> Result<UserOperationReceipt> {
+        let started = Instant::now();
+        loop {
+            let receipt: Option<UserOperationReceipt> = self
+                .bundler
+                .request("eth_getUserOperationReceipt", (hash,))
+                .await
+                .map_err(|e| bundler_error("get user operation receipt", e))?;
+            if let Some(receipt) = receipt {
+                self.metrics.record_tx_confirmed(
+                    self.config.account,
+                    started.elapsed(),
+                    receipt.success,
+                );
+                return Ok(receipt);
+            }
+            if started.elapsed() >= self.config.timeout {
+                self.metrics.record_tx_failed(self.config.account);
+                return Err(ContractError::UserOperationTimeout { hash });
+            }
+            platform::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Build, sign, and submit a user operation executing `calls`, then wait for it to
+    /// be included.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContractError::TransactionFailed`] if the bundler rejects the
+    /// operation or the account's execution reverts.
+    pub async fn execute(&self, calls: &[AccountCall]) -> Result<UserOperationReceipt> {
+        let op = self.build(calls).await?;
+        let hash = self.send(&op).await?;
+        let receipt = self.wait_for_receipt(hash).await?;
+        if !receipt.success {
+            return Err(ContractError::TransactionFailed(format!(
+                "User operation {} reverted: {}",
+                hash, receipt.reason
+            )));
+        }
+        Ok(receipt)
+    }
+}
+
+/// Rejections from the bundler are failures of the operation; anything else is a
+/// connection problem.
+fn bundler_error(action: &str, error: RpcError<TransportErrorKind>) -> ContractError {
+    match error {
+        RpcError::ErrorResp(payload) => ContractError::TransactionFailed(format!(
+            "Bundler rejected user operation: {}",
+            payload.message
+        )),
+        e => ContractError::RpcConnection(format!("Failed to {}: {}", action, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::network::EthereumWallet;
+    use alloy::primitives::Signature;
+    use alloy::providers::ProviderBuilder;
+    use alloy::transports::mock::Asserter;
+
+    // Anvil's default account 0
+    const OWNER_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn calls() -> Vec<AccountCall> {
+        vec![
+            AccountCall {
+                to: Address::repeat_byte(0x11),
+                value: U256::ZERO,
+                data: Bytes::from_static(&[0xaa, 0xbb]),
+            },
+            AccountCall {
+                to: Address::repeat_byte(0x22),
+                value: U256::from(5),
+                data: Bytes::from_static(&[0xcc]),
+            },
+        ]
+    }
+
+    fn user_op() -> PackedUserOperation {
+        PackedUserOperation {
+            sender: Address::repeat_byte(0x33),
+            nonce: U256::from(7),
+            factory: None,
+            factory_data: None,
+            call_data: Bytes::from_static(&[0x01, 0x02]),
+            call_gas_limit: U256::from(100_000),
+            verification_gas_limit: U256::from(200_000),
+            pre_verification_gas: U256::from(50_000),
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            paymaster: None,
+            paymaster_verification_gas_limit: None,
+            paymaster_post_op_gas_limit: None,
+            paymaster_data: None,
+            signature: Bytes::new(),
+        }
+    }
+
+    fn client(kind: SmartAccount, rpc: &Asserter, bundler: &Asserter) -> UserOperationClient {
+        let signer: PrivateKeySigner = OWNER_KEY.parse().unwrap();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer.clone()))
+            .connect_mocked_client(rpc.clone());
+        let config =
+            UserOperationConfig::new("http://localhost:4337", Address::repeat_byte(0x33), kind);
+        UserOperationClient::from_parts(
+            provider,
+            RpcClient::mocked(bundler.clone()),
+            signer,
+            config,
+        )
+    }
+
+    #[test]
+    fn test_simple_account_encoding() {
+        let single = SmartAccount::Simple.encode_calls(&calls()[..1]).unwrap();
+        assert_eq!(single[..4], ISimpleAccount::executeCall::SELECTOR);
+        let decoded = ISimpleAccount::executeCall::abi_decode(&single).unwrap();
+        assert_eq!(decoded.dest, Address::repeat_byte(0x11));
+
+        let batch = SmartAccount::Simple.encode_calls(&calls()).unwrap();
+        let decoded = ISimpleAccount::executeBatchCall::abi_decode(&batch).unwrap();
+        assert_eq!(decoded.dest.len(), 2);
+        assert_eq!(decoded.value[1], U256::from(5));
+    }
+
+    #[test]
+    fn test_safe_batch_uses_multisend() {
+        let batch = SmartAccount::Safe.encode_calls(&calls()).unwrap();
+        let decoded = ISafe4337Module::executeUserOpCall::abi_decode(&batch).unwrap();
+        assert_eq!(decoded.to, SAFE_MULTI_SEND_CALL_ONLY);
+        assert_eq!(decoded.operation, 1);
+
+        let multisend = IMultiSend::multiSendCall::abi_decode(&decoded.data).unwrap();
+        // 1 + 20 + 32 + 32 header bytes per call, plus the calldata
+        assert_eq!(multisend.transactions.len(), 2 * 85 + 3);
+        assert_eq!(
+            multisend.transactions[1..21],
+            Address::repeat_byte(0x11)[..]
+        );
+    }
+
+    #[test]
+    fn test_kernel_encoding_modes() {
+        let single = SmartAccount::Kernel.encode_calls(&calls()[..1]).unwrap();
+        let decoded = IKernel::executeCall::abi_decode(&single).unwrap();
+        assert_eq!(decoded.execMode, B256::ZERO);
+        // target (20) + value (32) + calldata, packed
+        assert_eq!(decoded.executionCalldata.len(), 54);
+
+        let batch = SmartAccount::Kernel.encode_calls(&calls()).unwrap();
+        let decoded = IKernel::executeCall::abi_decode(&batch).unwrap();
+        assert_eq!(decoded.execMode[0], 0x01);
+        let executions = Vec::<Execution>::abi_decode_params(&decoded.executionCalldata).unwrap();
+        assert_eq!(executions.len(), 2);
+    }
+
+    #[test]
+    fn test_encode_requires_calls() {
+        let result = SmartAccount::Simple.encode_calls(&[]);
+        assert!(matches!(result, Err(ContractError::TransactionFailed(_))));
+    }
+
+    #[test]
+    fn test_user_operation_hash_binds_chain_and_entry_point() {
+        let op = user_op();
+        let hash = user_operation_hash(&op, ENTRY_POINT_V07, 1);
+        assert_ne!(hash, user_operation_hash(&op, ENTRY_POINT_V07, 8453));
+        assert_ne!(hash, user_operation_hash(&op, Address::ZERO, 1));
+        // The signature is not part of the hash
+        let mut signed = op.clone();
+        signed.signature = Bytes::from_static(&[1; 65]);
+        assert_eq!(hash, user_operation_hash(&signed, ENTRY_POINT_V07, 1));
+    }
+
+    #[test]
+    fn test_pack_u128s() {
+        let packed = pack_u128s(U256::from(1), U256::from(2));
+        assert_eq!(packed[15], 1);
+        assert_eq!(packed[31], 2);
+    }
+
+    #[test]
+    fn test_simple_signature_recovers_owner() {
+        let (rpc, bundler) = (Asserter::new(), Asserter::new());
+        let client = client(SmartAccount::Simple, &rpc, &bundler);
+        let op = user_op();
+        let signature = client.sign(&op, 1).unwrap();
+        let signature = Signature::try_from(signature.as_ref()).unwrap();
+        let hash = user_operation_hash(&op, ENTRY_POINT_V07, 1);
+        let recovered = signature.recover_address_from_msg(hash.as_slice()).unwrap();
+        assert_eq!(recovered, client.owner());
+    }
+
+    #[test]
+    fn test_safe_signature_layout() {
+        let (rpc, bundler) = (Asserter::new(), Asserter::new());
+        let client = client(SmartAccount::Safe, &rpc, &bundler);
+        let signature = client.sign(&user_op(), 1).unwrap();
+        assert_eq!(signature.len(), 12 + 65);
+        assert!(signature[..12].iter().all(|b| *b == 0));
+        assert_eq!(SmartAccount::Safe.dummy_signature().len(), signature.len());
+    }
+
+    #[tokio::test]
+    async fn test_build_fills_gas_and_signs() {
+        let (rpc, bundler) = (Asserter::new(), Asserter::new());
+        rpc.push_success(&"0x1"); // eth_chainId
+        rpc.push_success(&B256::from(U256::from(3))); // EntryPoint.getNonce
+        rpc.push_success(&serde_json::json!({
+            "oldestBlock": "0x1",
+            "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00"],
+            "gasUsedRatio": [0.5],
+            "reward": [["0x3b9aca00"]],
+        }));
+        bundler.push_success(&serde_json::json!({
+            "preVerificationGas": "0xc350",
+            "verificationGasLimit": "0x30d40",
+            "callGasLimit": "0x186a0",
+        }));
+
+        let client = client(SmartAccount::Simple, &rpc, &bundler);
+        let op = client.build(&calls()).await.unwrap();
+
+        assert_eq!(op.sender, client.account());
+        assert_eq!(op.nonce, U256::from(3));
+        assert_eq!(op.call_gas_limit, U256::from(100_000));
+        assert_eq!(op.verification_gas_limit, U256::from(200_000));
+        assert_eq!(op.pre_verification_gas, U256::from(50_000));
+        assert!(op.max_fee_per_gas >= op.max_priority_fee_per_gas);
+        assert_eq!(op.signature, client.sign(&op, 1).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_receipt_times_out() {
+        let (rpc, bundler) = (Asserter::new(), Asserter::new());
+        bundler.push_success(&serde_json::Value::Null);
+        let mut client = client(SmartAccount::Kernel, &rpc, &bundler);
+        client.config = client.config.clone().with_timeout(Duration::ZERO);
+
+        let hash = B256::repeat_byte(0x44);
+        let result = client.wait_for_receipt(hash).await;
+        assert!(
+            matches!(result, Err(ContractError::UserOperationTimeout { hash: h }) if h == hash)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bundler_rejection_is_transaction_failure() {
+        let (rpc, bundler) = (Asserter::new(), Asserter::new());
+        bundler.push_failure_msg("AA21 didn't pay prefund");
+        let client = client(SmartAccount::Simple, &rpc, &bundler);
+
+        let result = client.send(&user_op()).await;
+        match result {
+            Err(ContractError::TransactionFailed(msg)) => assert!(msg.contains("AA21")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -6,9 +6,12 @@
 /// Macro to define a complete vault transaction client.
 ///
 /// This macro generates:
-/// - The struct definition with `provider` and `signer_address` fields
-/// - The `new()` constructor
-/// - ERC-20 helper methods: `get_decimals`, `get_balance`, `get_allowance`, `approve`, `approve_if_needed`
+/// - The struct definition with `provider`, `signer`, `signer_address`, `metrics`, and `block` fields
+/// - The `new()` and `new_with_fallbacks()` constructors and `with_metrics()` / `at_block()` builders
+/// - ERC-20 helper methods: `get_decimals`, `get_symbol`, `get_balance`, `get_allowance`, `approve`,
+///   `approve_if_needed`
+/// - `wrap_native` / `unwrap_native` for the chain's wrapped native token (WETH)
+/// - `sign_typed_data` for EIP-712 messages signed with the client's key
 /// - `Erc4626Client` trait implementation
 /// - ERC-4626 transaction methods via `impl_erc4626_transactions!`
 ///
@@ -28,63 +31,125 @@ macro_rules! define_vault_transaction_client {
         $client_name:ident,
         $version:literal
     ) => {
+        use std::sync::Arc;
+
         use alloy::{
-            network::EthereumWallet,
-            primitives::{Address, U256},
-            providers::ProviderBuilder,
-            signers::local::PrivateKeySigner,
+            primitives::{Address, Signature, U256},
+            rpc::types::BlockId,
+            signers::{local::PrivateKeySigner, SignerSync},
+            sol_types::{Eip712Domain, SolStruct},
         };
 
-        use $crate::erc20::IERC20;
+        use $crate::erc20::{IERC20, IWETH};
         use $crate::erc4626_client::Erc4626Client;
         use $crate::error::{ContractError, Result};
+        use $crate::metrics::{Metrics, NoopMetrics};
         use $crate::prepared_call::PreparedCall;
         use $crate::provider::HttpProvider;
 
         $(#[$meta])*
         pub struct $client_name {
             provider: HttpProvider,
+            signer: PrivateKeySigner,
             signer_address: Address,
+            metrics: Arc<dyn Metrics>,
+            block: BlockId,
         }
 
         impl $client_name {
             #[doc = concat!("Create a new ", $version, " transaction client.")]
             pub fn new(rpc_url: &str, private_key: &str) -> Result<Self> {
-                let signer: PrivateKeySigner = private_key
-                    .parse()
-                    .map_err(|_| ContractError::InvalidPrivateKey)?;
-                let signer_address = signer.address();
-                let wallet = EthereumWallet::from(signer);
-
-                let url: url::Url = rpc_url
-                    .parse()
-                    .map_err(|e| ContractError::RpcConnection(format!("{}", e)))?;
+                Self::new_with_fallbacks(&[rpc_url], private_key)
+            }
 
-                let provider = ProviderBuilder::new()
-                    .wallet(wallet)
-                    .connect_http(url);
+            #[doc = concat!("Create a new ", $version, " transaction client over several RPC endpoints.")]
+            ///
+            /// Requests go to the fastest healthy endpoint and fail over to the others
+            /// on transport errors; see [`FailoverTransport`](crate::rpc::FailoverTransport).
+            pub fn new_with_fallbacks(rpc_urls: &[&str], private_key: &str) -> Result<Self> {
+                let signer = $crate::provider::parse_signer(private_key)?;
+                let (provider, signer_address) = $crate::provider::connect(rpc_urls, private_key)?;
 
                 Ok(Self {
                     provider,
+                    signer,
                     signer_address,
+                    metrics: Arc::new(NoopMetrics),
+                    block: BlockId::latest(),
                 })
             }
 
+            /// Set the metrics sink notified on transaction submission and confirmation.
+            pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+                self.metrics = metrics;
+                self
+            }
+
+            /// Pin view reads to a block number instead of the latest block.
+            ///
+            /// Applies to the client's query methods only. The on-chain checks made
+            /// before preparing a transaction (allowances, pending values, timelocks)
+            /// always read the latest state, which the transaction executes against.
+            pub fn at_block(mut self, block: u64) -> Self {
+                self.block = BlockId::number(block);
+                self
+            }
+
+            /// Get the configured metrics sink.
+            pub fn metrics(&self) -> &Arc<dyn Metrics> {
+                &self.metrics
+            }
+
+            /// Sign an EIP-712 message under `domain` with the client's key.
+            pub fn sign_typed_data<T: SolStruct>(
+                &self,
+                message: &T,
+                domain: &Eip712Domain,
+            ) -> Result<Signature> {
+                self.signer
+                    .sign_hash_sync(&message.eip712_signing_hash(domain))
+                    .map_err(|e| ContractError::TransactionFailed(format!("Failed to sign: {}", e)))
+            }
+
             /// Get the decimals of a token.
             pub async fn get_decimals(&self, token: Address) -> Result<u8> {
                 let contract = IERC20::new(token, &self.provider);
-                let result = contract.decimals().call().await.map_err(|e| {
-                    ContractError::TransactionFailed(format!("Failed to get decimals: {}", e))
-                })?;
+                let result = contract
+                    .decimals()
+                    .call()
+                    .block(self.block)
+                    .await
+                    .map_err(|e| {
+                        ContractError::TransactionFailed(format!("Failed to get decimals: {}", e))
+                    })?;
+                Ok(result)
+            }
+
+            /// Get the symbol of a token.
+            pub async fn get_symbol(&self, token: Address) -> Result<String> {
+                let contract = IERC20::new(token, &self.provider);
+                let result = contract
+                    .symbol()
+                    .call()
+                    .block(self.block)
+                    .await
+                    .map_err(|e| {
+                        ContractError::TransactionFailed(format!("Failed to get symbol: {}", e))
+                    })?;
                 Ok(result)
             }
 
             /// Get the balance of a token for an address.
             pub async fn get_balance(&self, token: Address, owner: Address) -> Result<U256> {
                 let contract = IERC20::new(token, &self.provider);
-                let result = contract.balanceOf(owner).call().await.map_err(|e| {
-                    ContractError::TransactionFailed(format!("Failed to get balance: {}", e))
-                })?;
+                let result = contract
+                    .balanceOf(owner)
+                    .call()
+                    .block(self.block)
+                    .await
+                    .map_err(|e| {
+                        ContractError::TransactionFailed(format!("Failed to get balance: {}", e))
+                    })?;
                 Ok(result)
             }
 
@@ -94,11 +159,26 @@ macro_rules! define_vault_transaction_client {
                 token: Address,
                 owner: Address,
                 spender: Address,
+            ) -> Result<U256> {
+                self.allowance_at(token, owner, spender, self.block).await
+            }
+
+            async fn allowance_at(
+                &self,
+                token: Address,
+                owner: Address,
+                spender: Address,
+                block: BlockId,
             ) -> Result<U256> {
                 let contract = IERC20::new(token, &self.provider);
-                let result = contract.allowance(owner, spender).call().await.map_err(|e| {
-                    ContractError::TransactionFailed(format!("Failed to get allowance: {}", e))
-                })?;
+                let result = contract
+                    .allowance(owner, spender)
+                    .call()
+                    .block(block)
+                    .await
+                    .map_err(|e| {
+                        ContractError::TransactionFailed(format!("Failed to get allowance: {}", e))
+                    })?;
                 Ok(result)
             }
 
@@ -112,10 +192,14 @@ macro_rules! define_vault_transaction_client {
             ) -> PreparedCall<'_, IERC20::approveCall> {
                 let call = IERC20::approveCall { spender, amount };
                 PreparedCall::new(token, call, U256::ZERO, &self.provider)
+                    .with_metrics(self.metrics.as_ref())
             }
 
             /// Approve a spender to use tokens if needed.
             /// Returns a `PreparedCall` if approval is needed, None otherwise.
+            ///
+            /// The allowance is read at the latest block, regardless of
+            /// [`at_block`](Self::at_block).
             pub async fn approve_if_needed(
                 &self,
                 token: Address,
@@ -123,7 +207,7 @@ macro_rules! define_vault_transaction_client {
                 amount: U256,
             ) -> Result<Option<PreparedCall<'_, IERC20::approveCall>>> {
                 let current_allowance = self
-                    .get_allowance(token, self.signer_address, spender)
+                    .allowance_at(token, self.signer_address, spender, BlockId::latest())
                     .await?;
 
                 if current_allowance >= amount {
@@ -132,6 +216,26 @@ macro_rules! define_vault_transaction_client {
 
                 Ok(Some(self.approve(token, spender, amount)))
             }
+
+            /// Create a prepared call wrapping `amount` of native currency into `weth`.
+            pub fn wrap_native(
+                &self,
+                weth: Address,
+                amount: U256,
+            ) -> PreparedCall<'_, IWETH::depositCall> {
+                PreparedCall::new(weth, IWETH::depositCall {}, amount, &self.provider)
+                    .with_metrics(self.metrics.as_ref())
+            }
+
+            /// Create a prepared call unwrapping `amount` of `weth` into native currency.
+            pub fn unwrap_native(
+                &self,
+                weth: Address,
+                amount: U256,
+            ) -> PreparedCall<'_, IWETH::withdrawCall> {
+                PreparedCall::new(weth, IWETH::withdrawCall { amount }, U256::ZERO, &self.provider)
+                    .with_metrics(self.metrics.as_ref())
+            }
         }
 
         // Implement the Erc4626Client trait for view functions
@@ -143,6 +247,10 @@ macro_rules! define_vault_transaction_client {
             fn signer_address(&self) -> Address {
                 self.signer_address
             }
+
+            fn block(&self) -> BlockId {
+                self.block
+            }
         }
 
         // Use macro to generate ERC-4626 transaction methods (deposit, withdraw, mint, redeem)
@@ -190,5 +298,49 @@ macro_rules! define_vault_client_tests {
             let result = $client_name::new("http://localhost:8545", private_key);
             assert!(result.is_ok());
         }
+
+        #[test]
+        fn test_new_with_fallbacks() {
+            let private_key = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+            let urls = ["http://localhost:8545", "http://localhost:8546"];
+            assert!($client_name::new_with_fallbacks(&urls, private_key).is_ok());
+            let result = $client_name::new_with_fallbacks(&[], private_key);
+            assert!(matches!(result, Err(ContractError::RpcConnection(_))));
+        }
+
+        #[test]
+        fn test_with_metrics() {
+            let private_key = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+            let client = $client_name::new("http://localhost:8545", private_key)
+                .unwrap()
+                .with_metrics(std::sync::Arc::new($crate::metrics::NoopMetrics));
+            assert!(format!("{:?}", client.metrics()).contains("NoopMetrics"));
+        }
+
+        #[test]
+        fn test_wrap_native() {
+            let private_key = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+            let client = $client_name::new("http://localhost:8545", private_key).unwrap();
+            let weth = alloy::primitives::Address::repeat_byte(0x42);
+            let amount = alloy::primitives::U256::from(1_000u64);
+
+            let wrap = client.wrap_native(weth, amount);
+            assert_eq!(wrap.value(), amount);
+            assert_eq!(wrap.prepare().0, weth);
+
+            let unwrap = client.unwrap_native(weth, amount);
+            assert!(unwrap.value().is_zero());
+            assert_eq!(unwrap.prepare().1.amount, amount);
+        }
+
+        #[test]
+        fn test_at_block() {
+            use alloy::rpc::types::BlockId;
+            let private_key = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+            let client = $client_name::new("http://localhost:8545", private_key).unwrap();
+            assert_eq!(client.block(), BlockId::latest());
+            let client = client.at_block(21_000_000);
+            assert_eq!(client.block(), BlockId::number(21_000_000));
+        }
     };
 }
@@ -1,6 +1,18 @@
 //! V1 Vault transaction client for executing deposits and withdrawals.
+//!
+//! Besides the shared ERC-4626 surface, the V1 client exposes the MetaMorpho admin
+//! functions used by curators, allocators, and owners. Calls that the vault would
+//! reject (an unexpired timelock, no pending value, a fee above the maximum, ...)
+//! are checked against on-chain state before a [`PreparedCall`] is returned, so
+//! they fail with a typed [`ContractError`] instead of a revert.
+
+use alloy::primitives::B256;
+use futures::Stream;
 
 use crate::define_vault_transaction_client;
+use crate::events::{self, DecodedEvent};
+use crate::metamorpho::{market_id, IMetaMorpho, MarketAllocation, MarketParams, MAX_FEE};
+use crate::provider::latest_block_timestamp;
 
 define_vault_transaction_client!(
     /// Client for executing transactions against V1 (MetaMorpho) vaults.
@@ -8,9 +20,419 @@ define_vault_transaction_client!(
     "V1"
 );
 
+/// Arguments for [`VaultV1TransactionClient::submit_cap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmitCapArgs {
+    /// The market whose supply cap changes.
+    pub market_params: MarketParams,
+    /// The new supply cap (in the vault asset's smallest unit).
+    pub new_supply_cap: U256,
+}
+
+/// A timelocked value awaiting acceptance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingValue {
+    /// The pending value.
+    pub value: U256,
+    /// Unix timestamp from which the value can be accepted (0 if nothing is pending).
+    pub valid_at: u64,
+}
+
+impl PendingValue {
+    /// Returns true if a value is pending.
+    pub fn is_pending(&self) -> bool {
+        self.valid_at != 0
+    }
+}
+
+impl VaultV1TransactionClient {
+    /// Get the vault's timelock in seconds.
+    pub async fn timelock(&self, vault: Address) -> Result<u64> {
+        let contract = IMetaMorpho::new(vault, &self.provider);
+        let result = contract
+            .timelock()
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get timelock: {}", e))
+            })?;
+        Ok(result.saturating_to())
+    }
+
+    /// Get the current supply cap of a market in the vault.
+    pub async fn supply_cap(&self, vault: Address, id: B256) -> Result<U256> {
+        self.supply_cap_at(vault, id, self.block).await
+    }
+
+    async fn supply_cap_at(&self, vault: Address, id: B256, block: BlockId) -> Result<U256> {
+        let contract = IMetaMorpho::new(vault, &self.provider);
+        let result = contract
+            .config(id)
+            .call()
+            .block(block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get market config: {}", e))
+            })?;
+        Ok(U256::from(result.cap))
+    }
+
+    /// Get the pending supply cap of a market in the vault.
+    pub async fn pending_cap(&self, vault: Address, id: B256) -> Result<PendingValue> {
+        self.pending_cap_at(vault, id, self.block).await
+    }
+
+    async fn pending_cap_at(
+        &self,
+        vault: Address,
+        id: B256,
+        block: BlockId,
+    ) -> Result<PendingValue> {
+        let contract = IMetaMorpho::new(vault, &self.provider);
+        let result = contract
+            .pendingCap(id)
+            .call()
+            .block(block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get pending cap: {}", e))
+            })?;
+        Ok(PendingValue {
+            value: U256::from(result.value),
+            valid_at: result.validAt,
+        })
+    }
+
+    /// Get the vault's performance fee (WAD-scaled).
+    pub async fn fee(&self, vault: Address) -> Result<U256> {
+        self.fee_at(vault, self.block).await
+    }
+
+    async fn fee_at(&self, vault: Address, block: BlockId) -> Result<U256> {
+        let contract = IMetaMorpho::new(vault, &self.provider);
+        let result =
+            contract.fee().call().block(block).await.map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get fee: {}", e))
+            })?;
+        Ok(U256::from(result))
+    }
+
+    /// Get the vault's fee recipient.
+    pub async fn fee_recipient(&self, vault: Address) -> Result<Address> {
+        self.fee_recipient_at(vault, self.block).await
+    }
+
+    async fn fee_recipient_at(&self, vault: Address, block: BlockId) -> Result<Address> {
+        let contract = IMetaMorpho::new(vault, &self.provider);
+        let result = contract
+            .feeRecipient()
+            .call()
+            .block(block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get fee recipient: {}", e))
+            })?;
+        Ok(result)
+    }
+
+    /// Create a prepared `submitCap` transaction (curator only).
+    ///
+    /// Lowering a cap takes effect immediately; raising it starts the vault's timelock
+    /// and must be followed by [`accept_cap`](Self::accept_cap).
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::AlreadySet`] if the cap already equals `new_supply_cap`
+    /// - [`ContractError::AlreadyPending`] if a cap change is already pending
+    pub async fn submit_cap(
+        &self,
+        vault: Address,
+        args: SubmitCapArgs,
+    ) -> Result<PreparedCall<'_, IMetaMorpho::submitCapCall>> {
+        let id = market_id(&args.market_params);
+        if self.supply_cap_at(vault, id, BlockId::latest()).await? == args.new_supply_cap {
+            return Err(ContractError::AlreadySet);
+        }
+        if self
+            .pending_cap_at(vault, id, BlockId::latest())
+            .await?
+            .is_pending()
+        {
+            return Err(ContractError::AlreadyPending);
+        }
+
+        let call = IMetaMorpho::submitCapCall {
+            marketParams: args.market_params,
+            newSupplyCap: args.new_supply_cap,
+        };
+        Ok(PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref()))
+    }
+
+    /// Create a prepared `acceptCap` transaction once the timelock has elapsed.
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::NoPendingValue`] if no cap change is pending
+    /// - [`ContractError::TimelockNotElapsed`] if the latest block is before `valid_at`
+    pub async fn accept_cap(
+        &self,
+        vault: Address,
+        market_params: MarketParams,
+    ) -> Result<PreparedCall<'_, IMetaMorpho::acceptCapCall>> {
+        let pending = self
+            .pending_cap_at(vault, market_id(&market_params), BlockId::latest())
+            .await?;
+        if !pending.is_pending() {
+            return Err(ContractError::NoPendingValue);
+        }
+        if latest_block_timestamp(&self.provider).await? < pending.valid_at {
+            return Err(ContractError::TimelockNotElapsed {
+                valid_at: pending.valid_at,
+            });
+        }
+
+        let call = IMetaMorpho::acceptCapCall {
+            marketParams: market_params,
+        };
+        Ok(PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref()))
+    }
+
+    /// Create a prepared `setSupplyQueue` transaction (allocator only).
+    ///
+    /// Every market in the new queue must have a non-zero cap.
+    pub fn set_supply_queue(
+        &self,
+        vault: Address,
+        new_supply_queue: Vec<B256>,
+    ) -> PreparedCall<'_, IMetaMorpho::setSupplyQueueCall> {
+        let call = IMetaMorpho::setSupplyQueueCall {
+            newSupplyQueue: new_supply_queue,
+        };
+        PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared `updateWithdrawQueue` transaction (allocator only).
+    ///
+    /// `indexes` lists, in the new order, the current withdraw queue positions to keep;
+    /// omitted markets are removed.
+    pub fn update_withdraw_queue(
+        &self,
+        vault: Address,
+        indexes: Vec<U256>,
+    ) -> PreparedCall<'_, IMetaMorpho::updateWithdrawQueueCall> {
+        let call = IMetaMorpho::updateWithdrawQueueCall { indexes };
+        PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared `reallocate` transaction (allocator only).
+    ///
+    /// Allocations are processed in order: withdrawals should come before the supplies
+    /// they fund. Use `U256::MAX` as the last target to supply all withdrawn assets.
+    pub fn reallocate(
+        &self,
+        vault: Address,
+        allocations: Vec<MarketAllocation>,
+    ) -> PreparedCall<'_, IMetaMorpho::reallocateCall> {
+        let call = IMetaMorpho::reallocateCall { allocations };
+        PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared `setFee` transaction (owner only).
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::MaxFeeExceeded`] if `new_fee` exceeds [`MAX_FEE`]
+    /// - [`ContractError::AlreadySet`] if the fee already equals `new_fee`
+    /// - [`ContractError::ZeroFeeRecipient`] if `new_fee` is non-zero and no fee
+    ///   recipient is set
+    pub async fn set_fee(
+        &self,
+        vault: Address,
+        new_fee: U256,
+    ) -> Result<PreparedCall<'_, IMetaMorpho::setFeeCall>> {
+        if new_fee > MAX_FEE {
+            return Err(ContractError::MaxFeeExceeded {
+                fee: new_fee,
+                max: MAX_FEE,
+            });
+        }
+        if self.fee_at(vault, BlockId::latest()).await? == new_fee {
+            return Err(ContractError::AlreadySet);
+        }
+        if !new_fee.is_zero()
+            && self.fee_recipient_at(vault, BlockId::latest()).await? == Address::ZERO
+        {
+            return Err(ContractError::ZeroFeeRecipient);
+        }
+
+        let call = IMetaMorpho::setFeeCall { newFee: new_fee };
+        Ok(PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref()))
+    }
+
+    /// Create a prepared `setFeeRecipient` transaction (owner only).
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::AlreadySet`] if the recipient is unchanged
+    /// - [`ContractError::ZeroFeeRecipient`] if `new_fee_recipient` is zero while the
+    ///   fee is non-zero
+    pub async fn set_fee_recipient(
+        &self,
+        vault: Address,
+        new_fee_recipient: Address,
+    ) -> Result<PreparedCall<'_, IMetaMorpho::setFeeRecipientCall>> {
+        if self.fee_recipient_at(vault, BlockId::latest()).await? == new_fee_recipient {
+            return Err(ContractError::AlreadySet);
+        }
+        if new_fee_recipient == Address::ZERO
+            && !self.fee_at(vault, BlockId::latest()).await?.is_zero()
+        {
+            return Err(ContractError::ZeroFeeRecipient);
+        }
+
+        let call = IMetaMorpho::setFeeRecipientCall {
+            newFeeRecipient: new_fee_recipient,
+        };
+        Ok(PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref()))
+    }
+
+    /// Stream the vault's decoded events from `from_block` onwards.
+    ///
+    /// See [`events::stream_events`].
+    pub async fn stream_events(
+        &self,
+        vault: Address,
+        from_block: u64,
+    ) -> Result<impl Stream<Item = DecodedEvent>> {
+        events::stream_events(&self.provider, vault, from_block).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::define_vault_client_tests;
+    use alloy::primitives::Bytes;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
     define_vault_client_tests!(VaultV1TransactionClient);
+
+    #[tokio::test]
+    async fn test_set_fee_above_max_fails_without_rpc() {
+        let private_key = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+        let client = VaultV1TransactionClient::new("http://localhost:1", private_key).unwrap();
+
+        let result = client.set_fee(Address::ZERO, MAX_FEE + U256::from(1)).await;
+        assert!(matches!(result, Err(ContractError::MaxFeeExceeded { .. })));
+    }
+
+    /// JSON-RPC node answering `eth_call`s made at `block` with `result`, and failing
+    /// any other request.
+    struct EthCallAt {
+        block: &'static str,
+        result: Bytes,
+    }
+
+    impl Respond for EthCallAt {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let reply = if body["method"] == "eth_call" && body["params"][1] == self.block {
+                serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": self.result })
+            } else {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "error": { "code": -32000, "message": "unexpected request" },
+                })
+            };
+            ResponseTemplate::new(200).set_body_json(reply)
+        }
+    }
+
+    fn pinned_client(server: &MockServer) -> VaultV1TransactionClient {
+        let private_key = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+        VaultV1TransactionClient::new(&server.uri(), private_key)
+            .unwrap()
+            .at_block(21_000_000)
+    }
+
+    #[tokio::test]
+    async fn test_pinned_client_reads_pre_flight_state_at_latest() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(EthCallAt {
+                block: "latest",
+                result: U256::MAX.to_be_bytes::<32>().to_vec().into(),
+            })
+            .mount(&server)
+            .await;
+        let client = pinned_client(&server);
+
+        let approval = client
+            .approve_if_needed(
+                Address::repeat_byte(0x01),
+                Address::repeat_byte(0x02),
+                U256::MAX,
+            )
+            .await
+            .unwrap();
+        assert!(approval.is_none());
+        // Query methods stay pinned
+        assert!(client
+            .get_allowance(Address::repeat_byte(0x01), Address::ZERO, Address::ZERO)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_client_reads_queries_at_pinned_block() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(EthCallAt {
+                block: "0x1406f40",
+                result: vec![0u8; 96].into(),
+            })
+            .mount(&server)
+            .await;
+        let client = pinned_client(&server);
+
+        assert_eq!(
+            client.supply_cap(Address::ZERO, B256::ZERO).await.unwrap(),
+            U256::ZERO
+        );
+        // Pre-flight checks read the latest block, which this node does not serve
+        let args = SubmitCapArgs {
+            market_params: MarketParams {
+                loanToken: Address::ZERO,
+                collateralToken: Address::ZERO,
+                oracle: Address::ZERO,
+                irm: Address::ZERO,
+                lltv: U256::ZERO,
+            },
+            new_supply_cap: U256::from(1),
+        };
+        assert!(client.submit_cap(Address::ZERO, args).await.is_err());
+    }
+
+    #[test]
+    fn test_pending_value_is_pending() {
+        let none = PendingValue {
+            value: U256::ZERO,
+            valid_at: 0,
+        };
+        let pending = PendingValue {
+            value: U256::from(100),
+            valid_at: 1_700_000_000,
+        };
+        assert!(!none.is_pending());
+        assert!(pending.is_pending());
+    }
 }
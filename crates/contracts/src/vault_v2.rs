@@ -1,6 +1,26 @@
 //! V2 Vault transaction client for executing deposits and withdrawals.
+//!
+//! Besides the shared ERC-4626 surface, the V2 client exposes the vault's role and
+//! configuration functions. Timelocked curator actions are described by
+//! [`TimelockedAction`]: submit one with [`submit`](VaultV2TransactionClient::submit),
+//! then execute it with the matching typed method once the timelock has elapsed.
+//! Execution is checked against `executableAt` first, so an early call fails with a
+//! typed [`ContractError`] instead of a revert.
+//!
+//! Deposits into gated vaults are checked with
+//! [`deposit_gates`](VaultV2TransactionClient::deposit_gates); see the [`gate`](crate::gate)
+//! module for signature allowlists.
+
+use alloy::primitives::Bytes;
+use alloy::sol_types::SolCall;
 
 use crate::define_vault_transaction_client;
+use crate::gate::{
+    domain_from_eip5267, GateKind, GateRequirement, IReceiveSharesGate, ISendAssetsGate,
+    ISignatureGate, Registration,
+};
+use crate::morpho_vault_v2::{IVaultV2, TimelockedAction};
+use crate::provider::latest_block_timestamp;
 
 define_vault_transaction_client!(
     /// Client for executing transactions against V2 vaults.
@@ -8,6 +28,416 @@ define_vault_transaction_client!(
     "V2"
 );
 
+impl VaultV2TransactionClient {
+    /// Get the timestamp from which a submitted action can be executed (0 if not submitted).
+    pub async fn executable_at(&self, vault: Address, action: &TimelockedAction) -> Result<u64> {
+        self.executable_at_data(vault, action.calldata(), self.block)
+            .await
+    }
+
+    /// Check whether an account is an allocator of the vault.
+    pub async fn is_allocator(&self, vault: Address, account: Address) -> Result<bool> {
+        let contract = IVaultV2::new(vault, &self.provider);
+        let result = contract
+            .isAllocator(account)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get allocator role: {}", e))
+            })?;
+        Ok(result)
+    }
+
+    /// Check whether an account is a sentinel of the vault.
+    pub async fn is_sentinel(&self, vault: Address, account: Address) -> Result<bool> {
+        let contract = IVaultV2::new(vault, &self.provider);
+        let result = contract
+            .isSentinel(account)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get sentinel role: {}", e))
+            })?;
+        Ok(result)
+    }
+
+    /// Check whether an adapter is registered on the vault.
+    pub async fn is_adapter(&self, vault: Address, adapter: Address) -> Result<bool> {
+        let contract = IVaultV2::new(vault, &self.provider);
+        let result = contract
+            .isAdapter(adapter)
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get adapter status: {}", e))
+            })?;
+        Ok(result)
+    }
+
+    /// Get the adapter that holds the vault's idle liquidity.
+    pub async fn liquidity_adapter(&self, vault: Address) -> Result<Address> {
+        let contract = IVaultV2::new(vault, &self.provider);
+        let result = contract
+            .liquidityAdapter()
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get liquidity adapter: {}", e))
+            })?;
+        Ok(result)
+    }
+
+    /// Check `account` against the vault's deposit gates.
+    ///
+    /// Returns one requirement per non-zero gate: the `receiveSharesGate` for the share
+    /// receiver and the `sendAssetsGate` for the depositor, both `account` here. Gates
+    /// that answer `isAllowlisted` are treated as signature allowlists.
+    pub async fn deposit_gates(
+        &self,
+        vault: Address,
+        account: Address,
+    ) -> Result<Vec<GateRequirement>> {
+        let contract = IVaultV2::new(vault, &self.provider);
+        let receive_shares_gate = contract
+            .receiveSharesGate()
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| ContractError::TransactionFailed(format!("Failed to get gate: {}", e)))?;
+        let send_assets_gate = contract
+            .sendAssetsGate()
+            .call()
+            .block(self.block)
+            .await
+            .map_err(|e| ContractError::TransactionFailed(format!("Failed to get gate: {}", e)))?;
+
+        let mut requirements = Vec::new();
+        for (kind, gate) in [
+            (GateKind::ReceiveShares, receive_shares_gate),
+            (GateKind::SendAssets, send_assets_gate),
+        ] {
+            if gate.is_zero() {
+                continue;
+            }
+            let allowed = match kind {
+                GateKind::ReceiveShares => IReceiveSharesGate::new(gate, &self.provider)
+                    .canReceiveShares(account)
+                    .call()
+                    .block(self.block)
+                    .await,
+                GateKind::SendAssets => ISendAssetsGate::new(gate, &self.provider)
+                    .canSendAssets(account)
+                    .call()
+                    .block(self.block)
+                    .await,
+            }
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to check gate: {}", e))
+            })?;
+            // Gates without an allowlist revert on the unknown selector
+            let allowlisted = ISignatureGate::new(gate, &self.provider)
+                .isAllowlisted(account)
+                .call()
+                .block(self.block)
+                .await
+                .ok();
+
+            requirements.push(GateRequirement {
+                kind,
+                gate,
+                account,
+                allowed,
+                allowlisted,
+            });
+        }
+        Ok(requirements)
+    }
+
+    /// Sign a [`Registration`] for `account` with a signature allowlist gate.
+    ///
+    /// Reads the gate's EIP-712 domain and the account's current nonce, and signs with
+    /// the client's key. The gate only accepts it if the signer is `account` (or, for a
+    /// smart account, if the account validates the signature via ERC-1271).
+    pub async fn sign_gate_registration(
+        &self,
+        gate: Address,
+        account: Address,
+        deadline: u64,
+    ) -> Result<Bytes> {
+        let contract = ISignatureGate::new(gate, &self.provider);
+        let domain = contract.eip712Domain().call().await.map_err(|e| {
+            ContractError::TransactionFailed(format!("Failed to get gate domain: {}", e))
+        })?;
+        let nonce = contract.nonces(account).call().await.map_err(|e| {
+            ContractError::TransactionFailed(format!("Failed to get gate nonce: {}", e))
+        })?;
+
+        let domain = domain_from_eip5267(
+            domain.fields,
+            domain.name,
+            domain.version,
+            domain.chainId,
+            domain.verifyingContract,
+            domain.salt,
+        );
+        let registration = Registration {
+            account,
+            nonce,
+            deadline: U256::from(deadline),
+        };
+        let signature = self.sign_typed_data(&registration, &domain)?;
+        Ok(signature.as_bytes().into())
+    }
+
+    /// Create a prepared `register` transaction submitting a signed registration.
+    pub fn register_with_gate(
+        &self,
+        gate: Address,
+        account: Address,
+        deadline: u64,
+        signature: Bytes,
+    ) -> PreparedCall<'_, ISignatureGate::registerCall> {
+        let call = ISignatureGate::registerCall {
+            account,
+            deadline: U256::from(deadline),
+            signature,
+        };
+        PreparedCall::new(gate, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared `setCurator` transaction (owner only).
+    pub fn set_curator(
+        &self,
+        vault: Address,
+        new_curator: Address,
+    ) -> PreparedCall<'_, IVaultV2::setCuratorCall> {
+        let call = IVaultV2::setCuratorCall {
+            newCurator: new_curator,
+        };
+        PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared `setIsSentinel` transaction (owner only).
+    pub fn set_is_sentinel(
+        &self,
+        vault: Address,
+        account: Address,
+        is_sentinel: bool,
+    ) -> PreparedCall<'_, IVaultV2::setIsSentinelCall> {
+        let call = IVaultV2::setIsSentinelCall {
+            account,
+            newIsSentinel: is_sentinel,
+        };
+        PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared `setLiquidityAdapterAndData` transaction (allocator only).
+    ///
+    /// The liquidity adapter receives deposits and serves withdrawals, so it defines
+    /// where the vault's idle assets sit. `data` is the adapter-specific market data
+    /// (e.g. ABI-encoded market params for a Morpho Blue adapter).
+    pub fn set_liquidity_adapter_and_data(
+        &self,
+        vault: Address,
+        adapter: Address,
+        data: Bytes,
+    ) -> PreparedCall<'_, IVaultV2::setLiquidityAdapterAndDataCall> {
+        let call = IVaultV2::setLiquidityAdapterAndDataCall {
+            newLiquidityAdapter: adapter,
+            newLiquidityData: data,
+        };
+        PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref())
+    }
+
+    /// Create a prepared `submit` transaction for a timelocked action (curator only).
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::AlreadyPending`] if the action is already submitted
+    pub async fn submit(
+        &self,
+        vault: Address,
+        action: &TimelockedAction,
+    ) -> Result<PreparedCall<'_, IVaultV2::submitCall>> {
+        let data = action.calldata();
+        if self
+            .executable_at_data(vault, data.clone(), BlockId::latest())
+            .await?
+            != 0
+        {
+            return Err(ContractError::AlreadyPending);
+        }
+
+        let call = IVaultV2::submitCall { data };
+        Ok(PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref()))
+    }
+
+    /// Create a prepared `revoke` transaction for a submitted action (curator or sentinel).
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::NoPendingValue`] if the action is not submitted
+    pub async fn revoke(
+        &self,
+        vault: Address,
+        action: &TimelockedAction,
+    ) -> Result<PreparedCall<'_, IVaultV2::revokeCall>> {
+        let data = action.calldata();
+        if self
+            .executable_at_data(vault, data.clone(), BlockId::latest())
+            .await?
+            == 0
+        {
+            return Err(ContractError::NoPendingValue);
+        }
+
+        let call = IVaultV2::revokeCall { data };
+        Ok(PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref()))
+    }
+
+    /// Execute a submitted [`TimelockedAction::SetIsAllocator`].
+    pub async fn set_is_allocator(
+        &self,
+        vault: Address,
+        account: Address,
+        is_allocator: bool,
+    ) -> Result<PreparedCall<'_, IVaultV2::setIsAllocatorCall>> {
+        let call = IVaultV2::setIsAllocatorCall {
+            account,
+            newIsAllocator: is_allocator,
+        };
+        self.execute_timelocked(vault, call).await
+    }
+
+    /// Execute a submitted [`TimelockedAction::AddAdapter`].
+    pub async fn add_adapter(
+        &self,
+        vault: Address,
+        adapter: Address,
+    ) -> Result<PreparedCall<'_, IVaultV2::addAdapterCall>> {
+        let call = IVaultV2::addAdapterCall { account: adapter };
+        self.execute_timelocked(vault, call).await
+    }
+
+    /// Execute a submitted [`TimelockedAction::RemoveAdapter`].
+    pub async fn remove_adapter(
+        &self,
+        vault: Address,
+        adapter: Address,
+    ) -> Result<PreparedCall<'_, IVaultV2::removeAdapterCall>> {
+        let call = IVaultV2::removeAdapterCall { account: adapter };
+        self.execute_timelocked(vault, call).await
+    }
+
+    /// Execute a submitted [`TimelockedAction::SetReceiveSharesGate`].
+    pub async fn set_receive_shares_gate(
+        &self,
+        vault: Address,
+        gate: Address,
+    ) -> Result<PreparedCall<'_, IVaultV2::setReceiveSharesGateCall>> {
+        let call = IVaultV2::setReceiveSharesGateCall {
+            newReceiveSharesGate: gate,
+        };
+        self.execute_timelocked(vault, call).await
+    }
+
+    /// Execute a submitted [`TimelockedAction::SetSendSharesGate`].
+    pub async fn set_send_shares_gate(
+        &self,
+        vault: Address,
+        gate: Address,
+    ) -> Result<PreparedCall<'_, IVaultV2::setSendSharesGateCall>> {
+        let call = IVaultV2::setSendSharesGateCall {
+            newSendSharesGate: gate,
+        };
+        self.execute_timelocked(vault, call).await
+    }
+
+    /// Execute a submitted [`TimelockedAction::SetReceiveAssetsGate`].
+    pub async fn set_receive_assets_gate(
+        &self,
+        vault: Address,
+        gate: Address,
+    ) -> Result<PreparedCall<'_, IVaultV2::setReceiveAssetsGateCall>> {
+        let call = IVaultV2::setReceiveAssetsGateCall {
+            newReceiveAssetsGate: gate,
+        };
+        self.execute_timelocked(vault, call).await
+    }
+
+    /// Execute a submitted [`TimelockedAction::SetSendAssetsGate`].
+    pub async fn set_send_assets_gate(
+        &self,
+        vault: Address,
+        gate: Address,
+    ) -> Result<PreparedCall<'_, IVaultV2::setSendAssetsGateCall>> {
+        let call = IVaultV2::setSendAssetsGateCall {
+            newSendAssetsGate: gate,
+        };
+        self.execute_timelocked(vault, call).await
+    }
+
+    /// Execute a submitted [`TimelockedAction::SetAdapterRegistry`].
+    pub async fn set_adapter_registry(
+        &self,
+        vault: Address,
+        registry: Address,
+    ) -> Result<PreparedCall<'_, IVaultV2::setAdapterRegistryCall>> {
+        let call = IVaultV2::setAdapterRegistryCall {
+            newAdapterRegistry: registry,
+        };
+        self.execute_timelocked(vault, call).await
+    }
+
+    async fn executable_at_data(&self, vault: Address, data: Bytes, block: BlockId) -> Result<u64> {
+        let contract = IVaultV2::new(vault, &self.provider);
+        let result = contract
+            .executableAt(data)
+            .call()
+            .block(block)
+            .await
+            .map_err(|e| {
+                ContractError::TransactionFailed(format!("Failed to get executable time: {}", e))
+            })?;
+        Ok(result.saturating_to())
+    }
+
+    /// Prepare a timelocked call after checking that its timelock has elapsed.
+    ///
+    /// # Errors
+    ///
+    /// - [`ContractError::NoPendingValue`] if the call was not submitted
+    /// - [`ContractError::TimelockNotElapsed`] if the latest block is before `executableAt`
+    async fn execute_timelocked<C: SolCall>(
+        &self,
+        vault: Address,
+        call: C,
+    ) -> Result<PreparedCall<'_, C>> {
+        let valid_at = self
+            .executable_at_data(vault, call.abi_encode().into(), BlockId::latest())
+            .await?;
+        if valid_at == 0 {
+            return Err(ContractError::NoPendingValue);
+        }
+        if latest_block_timestamp(&self.provider).await? < valid_at {
+            return Err(ContractError::TimelockNotElapsed { valid_at });
+        }
+
+        Ok(PreparedCall::new(vault, call, U256::ZERO, &self.provider)
+            .with_metrics(self.metrics.as_ref()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,12 +1,15 @@
 //! Unit tests for calldata encoding.
 //!
-//! These tests verify correct ABI encoding of ERC-4626 and ERC-20 function
+//! These tests verify correct ABI encoding of ERC-4626, ERC-20, and vault admin
 //! calldata without requiring RPC connections.
 
-use alloy::primitives::{address, keccak256, Address, U256};
+use alloy::primitives::{address, keccak256, Address, Bytes, B256, U256};
 use alloy::signers::local::PrivateKeySigner;
 use alloy::sol_types::SolCall;
-use morpho_rs_contracts::{Erc4626Client, VaultV1TransactionClient, VaultV2TransactionClient};
+use morpho_rs_contracts::{
+    market_id, Erc4626Client, MarketAllocation, MarketParams, TimelockedAction,
+    VaultV1TransactionClient, VaultV2TransactionClient,
+};
 
 // Anvil's default account 0 private key
 const TEST_PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
@@ -424,3 +427,198 @@ fn test_v2_approve_same_encoding_as_v1() {
 
     assert_eq!(v1_call.abi_encode(), v2_call.abi_encode());
 }
+
+// ============================================================================
+// MetaMorpho Admin Calldata Tests
+// ============================================================================
+
+fn test_market_params() -> MarketParams {
+    MarketParams {
+        loanToken: TEST_TOKEN,
+        collateralToken: address!("7f39C581F595B53c5cb19bD0b3f8dA6c935E2Ca0"),
+        oracle: address!("48F7E36EB6B826B2dF4B2E630B62Cd25e89E40e2"),
+        irm: address!("870aC11D48B15DB9a138Cf899d20F13F79Ba00BC"),
+        lltv: U256::from(860_000_000_000_000_000u64),
+    }
+}
+
+#[test]
+fn test_market_id_matches_abi_encoded_hash() {
+    let params = test_market_params();
+
+    let mut encoded = Vec::with_capacity(5 * 32);
+    for addr in [
+        params.loanToken,
+        params.collateralToken,
+        params.oracle,
+        params.irm,
+    ] {
+        encoded.extend_from_slice(&[0u8; 12]);
+        encoded.extend_from_slice(addr.as_slice());
+    }
+    encoded.extend_from_slice(&params.lltv.to_be_bytes::<32>());
+
+    assert_eq!(market_id(&params), keccak256(&encoded));
+}
+
+#[test]
+fn test_set_supply_queue_calldata() {
+    let client = create_v1_client();
+    let queue = vec![market_id(&test_market_params()), B256::repeat_byte(0x11)];
+
+    let (addr, call) = client.set_supply_queue(TEST_VAULT, queue.clone()).prepare();
+
+    assert_eq!(addr, TEST_VAULT);
+    assert_eq!(call.newSupplyQueue, queue);
+    assert_eq!(
+        &call.abi_encode()[..4],
+        &keccak256("setSupplyQueue(bytes32[])")[..4]
+    );
+}
+
+#[test]
+fn test_update_withdraw_queue_calldata() {
+    let client = create_v1_client();
+    let indexes = vec![U256::from(2), U256::from(0)];
+
+    let (addr, call) = client
+        .update_withdraw_queue(TEST_VAULT, indexes.clone())
+        .prepare();
+
+    assert_eq!(addr, TEST_VAULT);
+    assert_eq!(call.indexes, indexes);
+    assert_eq!(
+        &call.abi_encode()[..4],
+        &keccak256("updateWithdrawQueue(uint256[])")[..4]
+    );
+}
+
+#[test]
+fn test_reallocate_calldata() {
+    let client = create_v1_client();
+    let allocations = vec![
+        MarketAllocation {
+            marketParams: test_market_params(),
+            assets: U256::ZERO,
+        },
+        MarketAllocation {
+            marketParams: test_market_params(),
+            assets: U256::MAX,
+        },
+    ];
+
+    let prepared = client.reallocate(TEST_VAULT, allocations);
+    assert_eq!(prepared.value(), U256::ZERO);
+
+    let (_, call) = prepared.prepare();
+    assert_eq!(call.allocations.len(), 2);
+    assert_eq!(call.allocations[1].assets, U256::MAX);
+    assert_eq!(
+        &call.abi_encode()[..4],
+        &keccak256("reallocate(((address,address,address,address,uint256),uint256)[])")[..4]
+    );
+}
+
+// ============================================================================
+// Vault V2 Admin Calldata Tests
+// ============================================================================
+
+#[test]
+fn test_v2_set_is_sentinel_calldata() {
+    let client = create_v2_client();
+
+    let (addr, call) = client
+        .set_is_sentinel(TEST_VAULT, TEST_OWNER, true)
+        .prepare();
+
+    assert_eq!(addr, TEST_VAULT);
+    assert_eq!(call.account, TEST_OWNER);
+    assert!(call.newIsSentinel);
+    assert_eq!(
+        &call.abi_encode()[..4],
+        &keccak256("setIsSentinel(address,bool)")[..4]
+    );
+}
+
+#[test]
+fn test_v2_set_liquidity_adapter_calldata() {
+    let client = create_v2_client();
+    let data = Bytes::from(vec![0xab; 64]);
+
+    let (_, call) = client
+        .set_liquidity_adapter_and_data(TEST_VAULT, TEST_RECEIVER, data.clone())
+        .prepare();
+
+    assert_eq!(call.newLiquidityAdapter, TEST_RECEIVER);
+    assert_eq!(call.newLiquidityData, data);
+    assert_eq!(
+        &call.abi_encode()[..4],
+        &keccak256("setLiquidityAdapterAndData(address,bytes)")[..4]
+    );
+}
+
+#[test]
+fn test_timelocked_action_calldata() {
+    let cases = [
+        (
+            TimelockedAction::SetIsAllocator {
+                account: TEST_OWNER,
+                is_allocator: true,
+            },
+            "setIsAllocator(address,bool)",
+        ),
+        (
+            TimelockedAction::AddAdapter {
+                adapter: TEST_RECEIVER,
+            },
+            "addAdapter(address)",
+        ),
+        (
+            TimelockedAction::RemoveAdapter {
+                adapter: TEST_RECEIVER,
+            },
+            "removeAdapter(address)",
+        ),
+        (
+            TimelockedAction::SetReceiveSharesGate { gate: TEST_OWNER },
+            "setReceiveSharesGate(address)",
+        ),
+        (
+            TimelockedAction::SetSendSharesGate { gate: TEST_OWNER },
+            "setSendSharesGate(address)",
+        ),
+        (
+            TimelockedAction::SetReceiveAssetsGate { gate: TEST_OWNER },
+            "setReceiveAssetsGate(address)",
+        ),
+        (
+            TimelockedAction::SetSendAssetsGate { gate: TEST_OWNER },
+            "setSendAssetsGate(address)",
+        ),
+        (
+            TimelockedAction::SetAdapterRegistry {
+                registry: TEST_OWNER,
+            },
+            "setAdapterRegistry(address)",
+        ),
+    ];
+
+    for (action, signature) in cases {
+        let data = action.calldata();
+        assert_eq!(&data[..4], &keccak256(signature)[..4], "{}", signature);
+    }
+}
+
+#[test]
+fn test_timelocked_action_calldata_encodes_arguments() {
+    let data = TimelockedAction::SetIsAllocator {
+        account: TEST_OWNER,
+        is_allocator: true,
+    }
+    .calldata();
+
+    // selector + address word + bool word
+    assert_eq!(data.len(), 4 + 32 + 32);
+    assert_eq!(&data[16..36], TEST_OWNER.as_slice());
+    assert_eq!(data[67], 1);
+}
@@ -0,0 +1,480 @@
+//! Validating builders for hand-constructed markets and vaults.
+//!
+//! [`Market::new`] takes eight positional arguments and [`Vault`] is a ten-field struct
+//! literal, which makes it easy to swap two amounts or forget a queue entry when building
+//! a simulation by hand. [`MarketBuilder`] and [`VaultBuilder`] name every field, fill in
+//! sensible defaults, and check the state on [`build`](MarketBuilder::build):
+//!
+//! - **Markets**: the state must pass [`Market::check_invariants`] (borrow assets within
+//!   supply assets, protocol fee at most 100%). Share totals default to the asset totals.
+//! - **Vaults**: the performance fee may not exceed [`MAX_VAULT_FEE`], the management
+//!   fee may not exceed [`MAX_MANAGEMENT_FEE`], and every queue
+//!   entry must reference an allocation at most once ([`Vault::check_queues`]). Queues default to the order in which
+//!   markets were added, total assets to the sum of allocations, and the last recorded
+//!   total assets to the total assets.
+//!
+//! # Example
+//!
+//! ```rust
+//! use morpho_rs_sim::{Market, Vault, VaultMarketConfig, VaultSimulation, WAD};
+//! use alloy_primitives::{Address, FixedBytes, U256};
+//!
+//! let market_id = FixedBytes::repeat_byte(1);
+//! let market = Market::builder(market_id)
+//!     .with_supply_assets(U256::from(1_000_000) * WAD)
+//!     .with_borrow_assets(U256::from(800_000) * WAD)
+//!     .with_fee(WAD / U256::from(10))
+//!     .with_rate_at_target(U256::from(1_268_391_679u64))
+//!     .with_last_update(1704067200)
+//!     .build()
+//!     .unwrap();
+//!
+//! let vault = Vault::builder(Address::repeat_byte(2))
+//!     .with_fee(WAD / U256::from(10))
+//!     .with_total_supply(U256::from(100_000) * WAD)
+//!     .with_market(VaultMarketConfig {
+//!         market_id,
+//!         cap: U256::from(500_000) * WAD,
+//!         supply_assets: U256::from(100_000) * WAD,
+//!         enabled: true,
+//!         public_allocator_config: None,
+//!     })
+//!     .build()
+//!     .unwrap();
+//!
+//! let simulation = VaultSimulation::new(vault, [(market_id, market)].into());
+//! assert!(simulation.get_net_apy(1704067200).unwrap() > 0.0);
+//! ```
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, U256};
+
+use crate::error::{MarketId, SimError};
+use crate::irm::Irm;
+use crate::market::Market;
+use crate::vault::{PublicAllocatorConfig, Vault, VaultMarketConfig, MAX_VAULT_FEE};
+use crate::vault_v2::MAX_MANAGEMENT_FEE;
+
+/// Builder for a [`Market`].
+///
+/// Every amount defaults to zero, the IRM to the Adaptive Curve with no rate at target
+/// (0% APY), and share totals to the corresponding asset totals.
+#[derive(Debug, Clone)]
+pub struct MarketBuilder {
+    id: MarketId,
+    total_supply_assets: U256,
+    total_borrow_assets: U256,
+    total_supply_shares: Option<U256>,
+    total_borrow_shares: Option<U256>,
+    last_update: u64,
+    fee: U256,
+    rate_at_target: Option<U256>,
+    irm: Irm,
+    price: Option<U256>,
+    lltv: U256,
+}
+
+impl MarketBuilder {
+    /// Create a builder for an empty market.
+    pub fn new(id: MarketId) -> Self {
+        Self {
+            id,
+            total_supply_assets: U256::ZERO,
+            total_borrow_assets: U256::ZERO,
+            total_supply_shares: None,
+            total_borrow_shares: None,
+            last_update: 0,
+            fee: U256::ZERO,
+            rate_at_target: None,
+            irm: Irm::AdaptiveCurve,
+            price: None,
+            lltv: U256::ZERO,
+        }
+    }
+
+    /// Set the total assets supplied to the market.
+    pub fn with_supply_assets(mut self, assets: U256) -> Self {
+        self.total_supply_assets = assets;
+        self
+    }
+
+    /// Set the total assets borrowed from the market.
+    pub fn with_borrow_assets(mut self, assets: U256) -> Self {
+        self.total_borrow_assets = assets;
+        self
+    }
+
+    /// Set the total supply shares (defaults to the supply assets).
+    pub fn with_supply_shares(mut self, shares: U256) -> Self {
+        self.total_supply_shares = Some(shares);
+        self
+    }
+
+    /// Set the total borrow shares (defaults to the borrow assets).
+    pub fn with_borrow_shares(mut self, shares: U256) -> Self {
+        self.total_borrow_shares = Some(shares);
+        self
+    }
+
+    /// Set the timestamp interest was last accrued at.
+    pub fn with_last_update(mut self, timestamp: u64) -> Self {
+        self.last_update = timestamp;
+        self
+    }
+
+    /// Set the protocol fee (WAD-scaled).
+    pub fn with_fee(mut self, fee: U256) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    /// Set the Adaptive Curve IRM rate at target.
+    pub fn with_rate_at_target(mut self, rate_at_target: U256) -> Self {
+        self.rate_at_target = Some(rate_at_target);
+        self
+    }
+
+    /// Set the interest rate model (defaults to [`Irm::AdaptiveCurve`]).
+    pub fn with_irm(mut self, irm: Irm) -> Self {
+        self.irm = irm;
+        self
+    }
+
+    /// Set the oracle price (scaled by [`ORACLE_PRICE_SCALE`](crate::ORACLE_PRICE_SCALE)).
+    pub fn with_price(mut self, price: U256) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Set the liquidation LTV (WAD-scaled).
+    pub fn with_lltv(mut self, lltv: U256) -> Self {
+        self.lltv = lltv;
+        self
+    }
+
+    /// Validate and build the market.
+    ///
+    /// # Errors
+    ///
+    /// Any violation reported by [`Market::check_invariants`]:
+    ///
+    /// - [`SimError::BorrowExceedsSupply`] if borrow assets exceed supply assets
+    /// - [`SimError::InvalidMarketFee`] if the fee exceeds [`WAD`](crate::WAD)
+    /// - [`SimError::ExcessShares`] if a side has more than `VIRTUAL_SHARES` shares per asset
+    pub fn build(self) -> Result<Market, SimError> {
+        let mut market = Market::new_with_oracle(
+            self.id,
+            self.total_supply_assets,
+            self.total_borrow_assets,
+            self.total_supply_shares.unwrap_or(self.total_supply_assets),
+            self.total_borrow_shares.unwrap_or(self.total_borrow_assets),
+            self.last_update,
+            self.fee,
+            self.rate_at_target,
+            self.price,
+            self.lltv,
+        );
+        market.irm = self.irm;
+        market.check_invariants()?;
+        Ok(market)
+    }
+}
+
+/// Builder for a [`Vault`].
+///
+/// Defaults to an 18-decimal asset, no fees, zero owner, and no public allocator.
+#[derive(Debug, Clone)]
+pub struct VaultBuilder {
+    address: Address,
+    asset_decimals: u8,
+    fee: U256,
+    management_fee: U256,
+    total_assets: Option<U256>,
+    total_supply: U256,
+    last_total_assets: Option<U256>,
+    supply_queue: Option<Vec<MarketId>>,
+    withdraw_queue: Option<Vec<MarketId>>,
+    markets: Vec<VaultMarketConfig>,
+    owner: Address,
+    public_allocator_config: Option<PublicAllocatorConfig>,
+}
+
+impl VaultBuilder {
+    /// Create a builder for a vault with no markets.
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            asset_decimals: 18,
+            fee: U256::ZERO,
+            management_fee: U256::ZERO,
+            total_assets: None,
+            total_supply: U256::ZERO,
+            last_total_assets: None,
+            supply_queue: None,
+            withdraw_queue: None,
+            markets: Vec::new(),
+            owner: Address::ZERO,
+            public_allocator_config: None,
+        }
+    }
+
+    /// Set the decimals of the underlying asset.
+    pub fn with_asset_decimals(mut self, decimals: u8) -> Self {
+        self.asset_decimals = decimals;
+        self
+    }
+
+    /// Set the performance fee (WAD-scaled).
+    pub fn with_fee(mut self, fee: U256) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    /// Set the management fee (per-second rate on total assets, WAD-scaled).
+    pub fn with_management_fee(mut self, fee: U256) -> Self {
+        self.management_fee = fee;
+        self
+    }
+
+    /// Set the total assets (defaults to the sum of allocations).
+    pub fn with_total_assets(mut self, assets: U256) -> Self {
+        self.total_assets = Some(assets);
+        self
+    }
+
+    /// Set the total vault shares outstanding.
+    pub fn with_total_supply(mut self, shares: U256) -> Self {
+        self.total_supply = shares;
+        self
+    }
+
+    /// Set the last recorded total assets (defaults to the total assets).
+    pub fn with_last_total_assets(mut self, assets: U256) -> Self {
+        self.last_total_assets = Some(assets);
+        self
+    }
+
+    /// Add a market allocation, replacing any earlier allocation for the same market.
+    pub fn with_market(mut self, config: VaultMarketConfig) -> Self {
+        self.markets.retain(|m| m.market_id != config.market_id);
+        self.markets.push(config);
+        self
+    }
+
+    /// Set the supply queue (defaults to the order markets were added).
+    pub fn with_supply_queue(mut self, queue: Vec<MarketId>) -> Self {
+        self.supply_queue = Some(queue);
+        self
+    }
+
+    /// Set the withdraw queue (defaults to the order markets were added).
+    pub fn with_withdraw_queue(mut self, queue: Vec<MarketId>) -> Self {
+        self.withdraw_queue = Some(queue);
+        self
+    }
+
+    /// Set the vault owner.
+    pub fn with_owner(mut self, owner: Address) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Set the public allocator configuration.
+    pub fn with_public_allocator_config(mut self, config: PublicAllocatorConfig) -> Self {
+        self.public_allocator_config = Some(config);
+        self
+    }
+
+    /// Validate and build the vault.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::InvalidFee`] if the fee exceeds [`MAX_VAULT_FEE`] or the
+    ///   management fee exceeds [`MAX_MANAGEMENT_FEE`]
+    /// - [`SimError::QueueMarketNotAllocated`] if a queue references a market that was
+    ///   not added with [`with_market`](Self::with_market)
+    /// - [`SimError::DuplicateQueueMarket`] if a queue lists a market more than once
+    pub fn build(self) -> Result<Vault, SimError> {
+        if self.fee > MAX_VAULT_FEE {
+            return Err(SimError::InvalidFee {
+                fee: self.fee,
+                max: MAX_VAULT_FEE,
+            });
+        }
+        if self.management_fee > MAX_MANAGEMENT_FEE {
+            return Err(SimError::InvalidFee {
+                fee: self.management_fee,
+                max: MAX_MANAGEMENT_FEE,
+            });
+        }
+
+        let market_ids: Vec<MarketId> = self.markets.iter().map(|m| m.market_id).collect();
+        let supply_queue = self.supply_queue.unwrap_or_else(|| market_ids.clone());
+        let withdraw_queue = self.withdraw_queue.unwrap_or(market_ids);
+        let total_assets = self.total_assets.unwrap_or_else(|| {
+            self.markets
+                .iter()
+                .fold(U256::ZERO, |sum, m| sum.saturating_add(m.supply_assets))
+        });
+        let allocations: HashMap<MarketId, VaultMarketConfig> =
+            self.markets.into_iter().map(|m| (m.market_id, m)).collect();
+
+        let vault = Vault {
+            address: self.address,
+            asset_decimals: self.asset_decimals,
+            fee: self.fee,
+            management_fee: self.management_fee,
+            total_assets,
+            total_supply: self.total_supply,
+            last_total_assets: self.last_total_assets.unwrap_or(total_assets),
+            supply_queue,
+            withdraw_queue,
+            allocations,
+            owner: self.owner,
+            public_allocator_config: self.public_allocator_config,
+        };
+        vault.check_queues()?;
+        Ok(vault)
+    }
+}
+
+impl Market {
+    /// Start building a market with named, validated fields. See [`MarketBuilder`].
+    pub fn builder(id: MarketId) -> MarketBuilder {
+        MarketBuilder::new(id)
+    }
+}
+
+impl Vault {
+    /// Start building a vault with named, validated fields. See [`VaultBuilder`].
+    pub fn builder(address: Address) -> VaultBuilder {
+        VaultBuilder::new(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::WAD;
+    use alloy_primitives::FixedBytes;
+
+    fn market_config(id: MarketId, supply: u64) -> VaultMarketConfig {
+        VaultMarketConfig {
+            market_id: id,
+            cap: U256::from(supply * 2),
+            supply_assets: U256::from(supply),
+            enabled: true,
+            public_allocator_config: None,
+        }
+    }
+
+    #[test]
+    fn test_market_builder_defaults_shares_to_assets() {
+        let market = Market::builder(FixedBytes::ZERO)
+            .with_supply_assets(U256::from(1_000))
+            .with_borrow_assets(U256::from(800))
+            .with_borrow_shares(U256::from(750))
+            .with_lltv(WAD / U256::from(2))
+            .build()
+            .unwrap();
+
+        assert_eq!(market.total_supply_shares, U256::from(1_000));
+        assert_eq!(market.total_borrow_shares, U256::from(750));
+        assert_eq!(market.lltv, WAD / U256::from(2));
+        assert_eq!(market.liquidity(), U256::from(200));
+    }
+
+    #[test]
+    fn test_market_builder_rejects_borrow_above_supply() {
+        let result = Market::builder(FixedBytes::ZERO)
+            .with_supply_assets(U256::from(100))
+            .with_borrow_assets(U256::from(101))
+            .build();
+        assert!(matches!(result, Err(SimError::BorrowExceedsSupply { .. })));
+    }
+
+    #[test]
+    fn test_market_builder_rejects_fee_above_wad() {
+        let result = Market::builder(FixedBytes::ZERO)
+            .with_fee(WAD + U256::from(1))
+            .build();
+        assert!(matches!(result, Err(SimError::InvalidMarketFee { .. })));
+        assert!(Market::builder(FixedBytes::ZERO)
+            .with_fee(WAD)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_vault_builder_defaults() {
+        let a = FixedBytes::repeat_byte(1);
+        let b = FixedBytes::repeat_byte(2);
+        let vault = Vault::builder(Address::ZERO)
+            .with_market(market_config(a, 300))
+            .with_market(market_config(b, 700))
+            .with_withdraw_queue(vec![b, a])
+            .build()
+            .unwrap();
+
+        assert_eq!(vault.supply_queue, vec![a, b]);
+        assert_eq!(vault.withdraw_queue, vec![b, a]);
+        assert_eq!(vault.total_assets, U256::from(1_000));
+        assert_eq!(vault.last_total_assets, U256::from(1_000));
+        assert_eq!(vault.allocations.len(), 2);
+        assert_eq!(vault.asset_decimals, 18);
+    }
+
+    #[test]
+    fn test_vault_builder_replaces_market() {
+        let a = FixedBytes::repeat_byte(1);
+        let vault = Vault::builder(Address::ZERO)
+            .with_market(market_config(a, 300))
+            .with_market(market_config(a, 500))
+            .build()
+            .unwrap();
+
+        assert_eq!(vault.supply_queue, vec![a]);
+        assert_eq!(vault.allocations[&a].supply_assets, U256::from(500));
+    }
+
+    #[test]
+    fn test_vault_builder_rejects_unknown_queue_market() {
+        let a = FixedBytes::repeat_byte(1);
+        let unknown = FixedBytes::repeat_byte(9);
+        let result = Vault::builder(Address::ZERO)
+            .with_market(market_config(a, 300))
+            .with_supply_queue(vec![a, unknown])
+            .build();
+        assert!(matches!(
+            result,
+            Err(SimError::QueueMarketNotAllocated { market_id, .. }) if market_id == unknown
+        ));
+    }
+
+    #[test]
+    fn test_vault_builder_rejects_duplicate_queue_market() {
+        let a = FixedBytes::repeat_byte(1);
+        let result = Vault::builder(Address::ZERO)
+            .with_market(market_config(a, 300))
+            .with_withdraw_queue(vec![a, a])
+            .build();
+        assert!(matches!(result, Err(SimError::DuplicateQueueMarket { .. })));
+    }
+
+    #[test]
+    fn test_vault_builder_rejects_excessive_fee() {
+        let result = Vault::builder(Address::ZERO)
+            .with_fee(MAX_VAULT_FEE + U256::from(1))
+            .build();
+        assert!(matches!(result, Err(SimError::InvalidFee { .. })));
+
+        let result = Vault::builder(Address::ZERO)
+            .with_management_fee(MAX_MANAGEMENT_FEE + U256::from(1))
+            .build();
+        assert!(matches!(
+            result,
+            Err(SimError::InvalidFee { max, .. }) if max == MAX_MANAGEMENT_FEE
+        ));
+    }
+}
@@ -0,0 +1,438 @@
+//! Golden-vector conformance checks against the Morpho TypeScript SDK.
+//!
+//! Enabled with the `serde` feature. A conformance vector pairs an input state with the
+//! exact outputs the reference implementation produces for it: market interest
+//! accruals, share conversions, Adaptive Curve IRM adaptation, liquidation incentive
+//! factors, and vault deposits. [`ConformanceSuite::run`] replays every vector through
+//! this crate and reports each output that is not bit-identical.
+//!
+//! The crate ships a bundled suite, [`ConformanceSuite::bundled`], which the crate's own
+//! tests run. Vectors ported from the SDK's test files name that file as their `source`;
+//! `morpho-rs-sim regression` vectors pin this crate's current outputs until SDK exports
+//! for the same inputs replace them. Forks can run the suite, or vectors exported from a
+//! newer SDK release, in their CI to confirm they still agree with the SDK.
+//!
+//! # Vector format
+//!
+//! A suite is a JSON object with a `vectors` array. Each vector has a `name`, an
+//! optional `source` describing where its expected values came from, and a `kind`
+//! selecting its fields. Amounts are hex (`"0x..."`) or decimal strings, and markets and
+//! vault simulations use the same layout as [snapshots](crate::snapshot):
+//!
+//! ```json
+//! {
+//!   "vectors": [
+//!     {
+//!       "name": "lif at 86% lltv",
+//!       "source": "blue-sdk MarketUtils.test.ts",
+//!       "kind": "liquidation_incentive_factor",
+//!       "lltv": "860000000000000000",
+//!       "expected": "1043841336116910229"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_sim::conformance::ConformanceSuite;
+//!
+//! let report = ConformanceSuite::load("vectors/sdk-export.json")?.run();
+//! for mismatch in &report.mismatches {
+//!     eprintln!("{mismatch}");
+//! }
+//! assert!(report.is_success());
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use alloy_primitives::U256;
+use serde::Deserialize;
+
+use crate::error::SimError;
+use crate::irm::get_borrow_rate;
+use crate::market::{get_liquidation_incentive_factor, Market};
+use crate::math::RoundingDirection;
+use crate::vault::VaultSimulation;
+
+/// Vectors bundled with the crate, see [`ConformanceSuite::bundled`]
+const BUNDLED_VECTORS: &str = include_str!("../vectors/conformance.json");
+
+/// A set of conformance vectors
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConformanceSuite {
+    /// The vectors, run in order
+    pub vectors: Vec<ConformanceVector>,
+}
+
+/// One input state and the outputs expected for it
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConformanceVector {
+    /// Name used to identify the vector in mismatch reports
+    pub name: String,
+    /// Where the expected values came from, e.g. the SDK test file
+    #[serde(default)]
+    pub source: Option<String>,
+    /// The operation under test and its inputs and expected outputs
+    #[serde(flatten)]
+    pub case: VectorCase,
+}
+
+/// The operation a [`ConformanceVector`] exercises
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VectorCase {
+    /// [`Market::accrue_interest`] to `timestamp`
+    AccrueInterest {
+        market: Market,
+        timestamp: u64,
+        expected: ExpectedMarket,
+    },
+    /// Supply or borrow share/asset conversion on a market
+    ShareConversion {
+        market: Market,
+        conversion: Conversion,
+        amount: U256,
+        rounding: RoundingDirection,
+        expected: U256,
+    },
+    /// [`get_borrow_rate`] for a utilization held for `elapsed` seconds
+    BorrowRate {
+        utilization: U256,
+        rate_at_target: U256,
+        elapsed: u64,
+        expected: ExpectedBorrowRate,
+    },
+    /// [`get_liquidation_incentive_factor`] for an LLTV
+    LiquidationIncentiveFactor { lltv: U256, expected: U256 },
+    /// [`VaultSimulation::simulate_deposit`] of `amount` at `timestamp`
+    VaultDeposit {
+        simulation: VaultSimulation,
+        amount: U256,
+        timestamp: u64,
+        expected: ExpectedVaultDeposit,
+    },
+}
+
+/// Share/asset conversion exercised by [`VectorCase::ShareConversion`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Conversion {
+    /// [`Market::to_supply_shares`]
+    ToSupplyShares,
+    /// [`Market::to_supply_assets`]
+    ToSupplyAssets,
+    /// [`Market::to_borrow_shares`]
+    ToBorrowShares,
+    /// [`Market::to_borrow_assets`]
+    ToBorrowAssets,
+}
+
+/// Market totals expected after an accrual
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedMarket {
+    pub total_supply_assets: U256,
+    pub total_borrow_assets: U256,
+    pub total_supply_shares: U256,
+    pub total_borrow_shares: U256,
+    /// Omitted for markets without the Adaptive Curve IRM
+    #[serde(default)]
+    pub rate_at_target: Option<U256>,
+}
+
+/// IRM outputs expected by [`VectorCase::BorrowRate`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedBorrowRate {
+    pub avg_borrow_rate: U256,
+    pub end_borrow_rate: U256,
+    pub end_rate_at_target: U256,
+}
+
+/// Vault state expected after [`VectorCase::VaultDeposit`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExpectedVaultDeposit {
+    /// Shares minted to the depositor
+    pub shares: U256,
+    pub total_assets: U256,
+    pub total_supply: U256,
+}
+
+/// An output that differs from the vector's expected value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Name of the vector
+    pub vector: String,
+    /// Output field that differs, or `"error"` if the operation failed
+    pub field: &'static str,
+    /// Expected value, in decimal
+    pub expected: String,
+    /// Value this crate produced, in decimal
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} expected {}, got {}",
+            self.vector, self.field, self.expected, self.actual
+        )
+    }
+}
+
+/// Outcome of [`ConformanceSuite::run`]
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    /// Number of vectors whose outputs all matched
+    pub passed: usize,
+    /// Every mismatched output, in vector order
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ConformanceReport {
+    /// Whether every vector matched
+    pub fn is_success(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl ConformanceSuite {
+    /// The vectors bundled with this crate
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED_VECTORS).expect("bundled conformance vectors are valid")
+    }
+
+    /// Parses a suite from JSON
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::Snapshot`] if `json` is not a valid suite
+    pub fn from_json(json: &str) -> Result<Self, SimError> {
+        serde_json::from_str(json).map_err(|e| SimError::Snapshot {
+            path: "<json>".to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Reads a suite from a JSON file
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::Snapshot`] if the file cannot be read or is not a valid suite
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SimError> {
+        let path = path.as_ref();
+        let error = |reason: String| SimError::Snapshot {
+            path: path.display().to_string(),
+            reason,
+        };
+        let json = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        serde_json::from_str(&json).map_err(|e| error(e.to_string()))
+    }
+
+    /// Runs every vector and collects the mismatches
+    pub fn run(&self) -> ConformanceReport {
+        let mut report = ConformanceReport::default();
+        for vector in &self.vectors {
+            let mismatches = vector.run();
+            if mismatches.is_empty() {
+                report.passed += 1;
+            }
+            report.mismatches.extend(mismatches);
+        }
+        report
+    }
+}
+
+impl ConformanceVector {
+    /// Runs this vector, returning every output that differs from the expected value
+    pub fn run(&self) -> Vec<Mismatch> {
+        let mut checker = Checker {
+            vector: &self.name,
+            mismatches: Vec::new(),
+        };
+
+        match &self.case {
+            VectorCase::AccrueInterest {
+                market,
+                timestamp,
+                expected,
+            } => match market.accrue_interest(*timestamp) {
+                Ok(accrued) => {
+                    checker.check(
+                        "total_supply_assets",
+                        expected.total_supply_assets,
+                        accrued.total_supply_assets,
+                    );
+                    checker.check(
+                        "total_borrow_assets",
+                        expected.total_borrow_assets,
+                        accrued.total_borrow_assets,
+                    );
+                    checker.check(
+                        "total_supply_shares",
+                        expected.total_supply_shares,
+                        accrued.total_supply_shares,
+                    );
+                    checker.check(
+                        "total_borrow_shares",
+                        expected.total_borrow_shares,
+                        accrued.total_borrow_shares,
+                    );
+                    if let Some(rate_at_target) = expected.rate_at_target {
+                        checker.check(
+                            "rate_at_target",
+                            rate_at_target,
+                            accrued.rate_at_target.unwrap_or_default(),
+                        );
+                    }
+                }
+                Err(e) => checker.error(e),
+            },
+            VectorCase::ShareConversion {
+                market,
+                conversion,
+                amount,
+                rounding,
+                expected,
+            } => {
+                let actual = match conversion {
+                    Conversion::ToSupplyShares => market.to_supply_shares(*amount, *rounding),
+                    Conversion::ToSupplyAssets => market.to_supply_assets(*amount, *rounding),
+                    Conversion::ToBorrowShares => market.to_borrow_shares(*amount, *rounding),
+                    Conversion::ToBorrowAssets => market.to_borrow_assets(*amount, *rounding),
+                };
+                checker.check("result", *expected, actual);
+            }
+            VectorCase::BorrowRate {
+                utilization,
+                rate_at_target,
+                elapsed,
+                expected,
+            } => {
+                let result = get_borrow_rate(*utilization, *rate_at_target, *elapsed);
+                checker.check(
+                    "avg_borrow_rate",
+                    expected.avg_borrow_rate,
+                    result.avg_borrow_rate,
+                );
+                checker.check(
+                    "end_borrow_rate",
+                    expected.end_borrow_rate,
+                    result.end_borrow_rate,
+                );
+                checker.check(
+                    "end_rate_at_target",
+                    expected.end_rate_at_target,
+                    result.end_rate_at_target,
+                );
+            }
+            VectorCase::LiquidationIncentiveFactor { lltv, expected } => {
+                checker.check("result", *expected, get_liquidation_incentive_factor(*lltv));
+            }
+            VectorCase::VaultDeposit {
+                simulation,
+                amount,
+                timestamp,
+                expected,
+            } => match simulation.simulate_deposit(*amount, *timestamp) {
+                Ok((after, shares)) => {
+                    checker.check("shares", expected.shares, shares);
+                    checker.check(
+                        "total_assets",
+                        expected.total_assets,
+                        after.vault.total_assets,
+                    );
+                    checker.check(
+                        "total_supply",
+                        expected.total_supply,
+                        after.vault.total_supply,
+                    );
+                }
+                Err(e) => checker.error(e),
+            },
+        }
+
+        checker.mismatches
+    }
+}
+
+/// Accumulates mismatches for one vector
+struct Checker<'a> {
+    vector: &'a str,
+    mismatches: Vec<Mismatch>,
+}
+
+impl Checker<'_> {
+    fn check(&mut self, field: &'static str, expected: U256, actual: U256) {
+        if expected != actual {
+            self.push(field, expected.to_string(), actual.to_string());
+        }
+    }
+
+    fn error(&mut self, error: SimError) {
+        self.push("error", "success".to_string(), error.to_string());
+    }
+
+    fn push(&mut self, field: &'static str, expected: String, actual: String) {
+        self.mismatches.push(Mismatch {
+            vector: self.vector.to_string(),
+            field,
+            expected,
+            actual,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_vectors_pass() {
+        let report = ConformanceSuite::bundled().run();
+        let messages: Vec<String> = report.mismatches.iter().map(|m| m.to_string()).collect();
+        assert!(report.is_success(), "{}", messages.join("\n"));
+        assert_eq!(report.passed, ConformanceSuite::bundled().vectors.len());
+    }
+
+    #[test]
+    fn test_load_bundled_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("vectors/conformance.json");
+        let suite = ConformanceSuite::load(path).unwrap();
+        assert!(suite.run().is_success());
+    }
+
+    #[test]
+    fn test_mismatch_reported() {
+        let suite = ConformanceSuite::from_json(
+            r#"{"vectors": [{
+                "name": "wrong lif",
+                "kind": "liquidation_incentive_factor",
+                "lltv": "860000000000000000",
+                "expected": "0x1"
+            }]}"#,
+        )
+        .unwrap();
+
+        let report = suite.run();
+        assert_eq!(report.passed, 0);
+        assert_eq!(
+            report.mismatches,
+            [Mismatch {
+                vector: "wrong lif".to_string(),
+                field: "result",
+                expected: "1".to_string(),
+                actual: "1043841336116910229".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_invalid_suite_rejected() {
+        let result = ConformanceSuite::from_json(r#"{"vectors": [{"name": "x", "kind": "nope"}]}"#);
+        assert!(matches!(result, Err(SimError::Snapshot { .. })));
+    }
+}
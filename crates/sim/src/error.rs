@@ -18,17 +18,49 @@
 //! - [`SimError::InsufficientPosition`]: Trying to withdraw more than owned
 //! - [`SimError::InsufficientCollateral`]: Borrow would make position unhealthy
 //! - [`SimError::UnknownOraclePrice`]: No oracle price for health calculations
+//! - [`SimError::HealthyPosition`]: Liquidating a position that is still healthy
+//! - [`SimError::InvalidTargetLtv`]: Leverage target LTV at or above the market's LLTV
+//! - [`SimError::NotPreLiquidatable`]: Position LTV outside a pre-liquidation's range
+//! - [`SimError::CloseFactorExceeded`]: Pre-liquidation repays more than its close factor allows
 //!
 //! ## Vault Errors
 //! - [`SimError::MarketNotFound`]: Market not in vault allocations
 //! - [`SimError::MarketNotEnabled`]: Market disabled for operations
 //! - [`SimError::InconsistentReallocation`]: Supply/withdraw mismatch in reallocation
+//! - [`SimError::InvalidFee`]: Performance fee above the MetaMorpho maximum
+//!
+//! ## Vault V2 Errors
+//! - [`SimError::AdapterNotFound`]: Adapter not enabled on the vault
+//! - [`SimError::MissingAdapterMarket`]: Morpho Blue adapter used without a market
+//! - [`SimError::IdCapExceeded`]: Allocation exceeds an id's absolute or relative cap
+//!
+//! ## Builder Errors
+//! - [`SimError::BorrowExceedsSupply`]: Market borrows more than is supplied
+//! - [`SimError::InvalidMarketFee`]: Market fee above 100%
+//! - [`SimError::QueueMarketNotAllocated`]: Vault queue references an unknown market
+//! - [`SimError::DuplicateQueueMarket`]: Market listed twice in a vault queue
+//!
+//! ## Invariant Errors
+//! - [`SimError::ExcessShares`]: Market shares worth less than at the initial exchange rate
+//! - [`SimError::UnqueuedAllocation`]: Vault supplies to a market missing from its withdraw queue
+//! - [`SimError::AllocationMismatch`]: Vault allocations do not sum to its total assets
 //!
 //! ## Public Allocator Errors
 //! - [`SimError::PublicAllocatorNotConfigured`]: Vault has no public allocator
 //! - [`SimError::MaxInflowExceeded`]: Exceeds market's max_in limit
 //! - [`SimError::MaxOutflowExceeded`]: Exceeds market's max_out limit
 //!
+//! ## Projection Errors
+//! - [`SimError::InvalidTimeStep`]: Projection step is zero
+//! - [`SimError::InvalidCompoundingInterval`]: Compounding interval is zero
+//!
+//! ## Solver Errors
+//! - [`SimError::UnreachableTarget`]: Target APY or utilization cannot be reached
+//!
+//! ## Snapshot Errors
+//! - [`SimError::Snapshot`]: A simulation snapshot or conformance vector file could not be
+//!   written or read
+//!
 //! # Example
 //!
 //! ```rust
@@ -57,7 +89,7 @@
 //! }
 //! ```
 
-use alloy_primitives::{Address, FixedBytes};
+use alloy_primitives::{Address, FixedBytes, U256};
 use thiserror::Error;
 
 /// Type alias for a 32-byte market ID
@@ -106,6 +138,36 @@ pub enum SimError {
     #[error("Insufficient collateral for user {user} in market {market_id}")]
     InsufficientCollateral { user: Address, market_id: MarketId },
 
+    /// Liquidation of a healthy position
+    #[error("Position of user {user} in market {market_id} is healthy and cannot be liquidated")]
+    HealthyPosition { user: Address, market_id: MarketId },
+
+    /// Pre-liquidation of a position whose LTV is not above the pre-liquidation LLTV, or
+    /// is above the market LLTV (where regular liquidation applies)
+    #[error("Position of user {user} in market {market_id} has LTV {ltv} outside the pre-liquidation range")]
+    NotPreLiquidatable {
+        user: Address,
+        market_id: MarketId,
+        ltv: u128,
+    },
+
+    /// Pre-liquidation repays more borrow shares than its close factor allows
+    #[error("Pre-liquidation of user {user} in market {market_id} repays {repaid_shares} shares, above the close factor limit of {max_repaid_shares}")]
+    CloseFactorExceeded {
+        user: Address,
+        market_id: MarketId,
+        repaid_shares: u128,
+        max_repaid_shares: u128,
+    },
+
+    /// Leverage target LTV is at or above the liquidation LTV
+    #[error("Target LTV {target_ltv} must be below LLTV {lltv} in market {market_id}")]
+    InvalidTargetLtv {
+        market_id: MarketId,
+        target_ltv: u128,
+        lltv: u128,
+    },
+
     /// Insufficient liquidity in market
     #[error("Insufficient liquidity in market {market_id}")]
     InsufficientMarketLiquidity { market_id: MarketId },
@@ -161,6 +223,83 @@ pub enum SimError {
     /// Withdrawals not sorted
     #[error("Withdrawals not sorted for vault {vault}")]
     WithdrawalsNotSorted { vault: Address },
+
+    /// Time step for a projection is zero
+    #[error("Invalid time step: {step_secs} seconds (must be greater than zero)")]
+    InvalidTimeStep { step_secs: u64 },
+
+    /// Compounding interval for a projection is zero
+    #[error("Invalid compounding interval: {interval_secs} seconds (must be greater than zero)")]
+    InvalidCompoundingInterval { interval_secs: u64 },
+
+    /// No supply or withdrawal reaches the target rate or utilization
+    #[error("Target {target} is unreachable for market {market_id}")]
+    UnreachableTarget { market_id: MarketId, target: f64 },
+
+    /// Vault performance fee exceeds the MetaMorpho maximum
+    #[error("Invalid fee {fee} (max {max})")]
+    InvalidFee { fee: U256, max: U256 },
+
+    /// Market borrows exceed its supply
+    #[error("Borrow {borrow} exceeds supply {supply} in market {market_id}")]
+    BorrowExceedsSupply {
+        market_id: MarketId,
+        borrow: U256,
+        supply: U256,
+    },
+
+    /// Market protocol fee exceeds 100% (WAD)
+    #[error("Invalid fee {fee} for market {market_id} (max WAD)")]
+    InvalidMarketFee { market_id: MarketId, fee: U256 },
+
+    /// Supply or withdraw queue references a market without an allocation
+    #[error("Queue market {market_id} has no allocation in vault {vault}")]
+    QueueMarketNotAllocated { vault: Address, market_id: MarketId },
+
+    /// Supply or withdraw queue lists a market more than once
+    #[error("Market {market_id} is listed twice in a queue of vault {vault}")]
+    DuplicateQueueMarket { vault: Address, market_id: MarketId },
+
+    /// Market has more than `VIRTUAL_SHARES` shares per asset on one side
+    #[error("Market {market_id} has {shares} shares for {assets} assets")]
+    ExcessShares {
+        market_id: MarketId,
+        shares: U256,
+        assets: U256,
+    },
+
+    /// Vault supplies to a market that is not in its withdraw queue
+    #[error("Market {market_id} has supply from vault {vault} but is not in its withdraw queue")]
+    UnqueuedAllocation { vault: Address, market_id: MarketId },
+
+    /// Vault allocations do not sum to the vault's total assets
+    #[error("Allocations of vault {vault} sum to {allocated}, total assets are {total_assets}")]
+    AllocationMismatch {
+        vault: Address,
+        allocated: U256,
+        total_assets: U256,
+    },
+
+    /// Adapter is not enabled on a Vault V2
+    #[error("Adapter {adapter} not found in vault {vault}")]
+    AdapterNotFound { vault: Address, adapter: Address },
+
+    /// Morpho Blue adapter allocated or deallocated without a market
+    #[error("Adapter {adapter} requires a market to allocate or deallocate")]
+    MissingAdapterMarket { adapter: Address },
+
+    /// Vault V2 allocation exceeds an id's absolute or relative cap
+    #[error("Allocation {allocation} to id {id} in vault {vault} exceeds cap {cap}")]
+    IdCapExceeded {
+        vault: Address,
+        id: FixedBytes<32>,
+        allocation: u128,
+        cap: u128,
+    },
+
+    /// A simulation snapshot or conformance vector file could not be written or read
+    #[error("Snapshot {path}: {reason}")]
+    Snapshot { path: String, reason: String },
 }
 
 impl SimError {
@@ -183,12 +322,25 @@ impl SimError {
             self,
             SimError::InsufficientPosition { .. }
                 | SimError::InsufficientCollateral { .. }
+                | SimError::HealthyPosition { .. }
+                | SimError::InvalidTargetLtv { .. }
+                | SimError::NotPreLiquidatable { .. }
+                | SimError::CloseFactorExceeded { .. }
                 | SimError::InsufficientMarketLiquidity { .. }
                 | SimError::NotEnoughLiquidity { .. }
                 | SimError::AllCapsReached { .. }
+                | SimError::IdCapExceeded { .. }
                 | SimError::EmptyWithdrawals { .. }
                 | SimError::DepositMarketInWithdrawals { .. }
                 | SimError::WithdrawalsNotSorted { .. }
+                | SimError::InvalidTimeStep { .. }
+                | SimError::InvalidCompoundingInterval { .. }
+                | SimError::UnreachableTarget { .. }
+                | SimError::InvalidFee { .. }
+                | SimError::BorrowExceedsSupply { .. }
+                | SimError::InvalidMarketFee { .. }
+                | SimError::QueueMarketNotAllocated { .. }
+                | SimError::DuplicateQueueMarket { .. }
         )
     }
 }
@@ -249,6 +401,14 @@ mod tests {
             remaining: 100,
         };
         assert!(err.is_user_error());
+
+        let err = SimError::IdCapExceeded {
+            vault: Address::ZERO,
+            id: FixedBytes::ZERO,
+            allocation: 2,
+            cap: 1,
+        };
+        assert!(err.is_user_error());
     }
 
     #[test]
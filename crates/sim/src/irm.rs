@@ -30,6 +30,13 @@
 //! - **Below target utilization**: `rate_at_target` decreases (encourages more borrowing)
 //! - **Adaptation speed**: 50% per year (e.g., if above target for a full year, rate doubles)
 //!
+//! # Other Models
+//!
+//! Markets simulate the Adaptive Curve IRM by default. The [`InterestRateModel`] trait
+//! lets a market use any other model instead: set [`Market::irm`](crate::Market::irm) to
+//! [`Irm::FixedRate`] for a constant rate, or to [`Irm::Custom`] with your own
+//! implementation. The solvers only support the Adaptive Curve IRM.
+//!
 //! # Constants
 //!
 //! | Constant | Value | Description |
@@ -56,9 +63,14 @@
 //! assert!(apy > 0.03 && apy < 0.05); // Around 4% APY
 //! ```
 
+use std::fmt::Debug;
+use std::sync::Arc;
+
 use alloy_primitives::U256;
 
-use crate::math::{max, min, w_div_down, w_div_up, w_mul_down, zero_floor_sub, WAD};
+use crate::math::{
+    max, min, w_div_down, w_div_up, w_mul_down, zero_floor_sub, SECONDS_PER_YEAR, WAD,
+};
 
 /// Curve steepness parameter (4.0 in WAD)
 pub const CURVE_STEEPNESS: U256 = U256::from_limbs([4_000_000_000_000_000_000, 0, 0, 0]);
@@ -370,6 +382,168 @@ pub fn get_supply_for_borrow_rate(
     }
 }
 
+/// Borrow rates produced by an [`InterestRateModel`] over an accrual period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IrmRates {
+    /// Average borrow rate over the period (WAD-scaled per second)
+    pub avg_borrow_rate: U256,
+    /// Instantaneous borrow rate at the end of the period
+    pub end_borrow_rate: U256,
+    /// Rate at target to store on the market after the period, or `None` to keep the
+    /// market's current value
+    pub end_rate_at_target: Option<U256>,
+}
+
+/// An interest rate model a market can be simulated with.
+///
+/// Implementations receive the market's utilization and its stored
+/// [`rate_at_target`](crate::Market::rate_at_target), which models without adaptive
+/// state can ignore, and return the rates over `elapsed` seconds at that utilization.
+pub trait InterestRateModel: Debug + Send + Sync {
+    /// Borrow rates over `elapsed` seconds at constant `utilization`
+    fn borrow_rates(
+        &self,
+        utilization: U256,
+        rate_at_target: Option<U256>,
+        elapsed: u64,
+    ) -> IrmRates;
+}
+
+/// The Adaptive Curve IRM, computed with [`get_borrow_rate`].
+///
+/// Markets without a `rate_at_target` accrue no interest, matching markets whose IRM
+/// state is unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AdaptiveCurveIrm;
+
+impl InterestRateModel for AdaptiveCurveIrm {
+    fn borrow_rates(
+        &self,
+        utilization: U256,
+        rate_at_target: Option<U256>,
+        elapsed: u64,
+    ) -> IrmRates {
+        match rate_at_target {
+            None => IrmRates {
+                avg_borrow_rate: U256::ZERO,
+                end_borrow_rate: U256::ZERO,
+                end_rate_at_target: None,
+            },
+            Some(rate_at_target) => {
+                let result = get_borrow_rate(utilization, rate_at_target, elapsed);
+                IrmRates {
+                    avg_borrow_rate: result.avg_borrow_rate,
+                    end_borrow_rate: result.end_borrow_rate,
+                    end_rate_at_target: Some(result.end_rate_at_target),
+                }
+            }
+        }
+    }
+}
+
+/// A constant borrow rate, independent of utilization.
+///
+/// # Example
+///
+/// ```rust
+/// use morpho_rs_sim::irm::{FixedRateIrm, InterestRateModel};
+/// use morpho_rs_sim::WAD;
+/// use alloy_primitives::U256;
+///
+/// // 5% per year
+/// let irm = FixedRateIrm::from_annual_rate(WAD / U256::from(20));
+/// let rates = irm.borrow_rates(WAD / U256::from(2), None, 86400);
+/// assert_eq!(rates.avg_borrow_rate, irm.borrow_rate);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedRateIrm {
+    /// Borrow rate (WAD-scaled per second)
+    pub borrow_rate: U256,
+}
+
+impl FixedRateIrm {
+    /// A model with the given per-second borrow rate (WAD-scaled)
+    pub fn new(borrow_rate: U256) -> Self {
+        Self { borrow_rate }
+    }
+
+    /// A model with the given annual borrow rate (WAD-scaled, not compounded)
+    pub fn from_annual_rate(annual_rate: U256) -> Self {
+        Self::new(annual_rate / U256::from(SECONDS_PER_YEAR))
+    }
+}
+
+impl InterestRateModel for FixedRateIrm {
+    fn borrow_rates(
+        &self,
+        _utilization: U256,
+        _rate_at_target: Option<U256>,
+        _elapsed: u64,
+    ) -> IrmRates {
+        IrmRates {
+            avg_borrow_rate: self.borrow_rate,
+            end_borrow_rate: self.borrow_rate,
+            end_rate_at_target: None,
+        }
+    }
+}
+
+/// The interest rate model of a [`Market`](crate::Market).
+///
+/// Defaults to [`Irm::AdaptiveCurve`]. With the `serde` feature, [`Irm::Custom`] models
+/// cannot be serialized.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Irm {
+    /// The Adaptive Curve IRM, see [`AdaptiveCurveIrm`]
+    #[default]
+    AdaptiveCurve,
+    /// A constant borrow rate, see [`FixedRateIrm`]
+    FixedRate(FixedRateIrm),
+    /// Any other model
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn InterestRateModel>),
+}
+
+impl Irm {
+    /// Whether this is the Adaptive Curve IRM, the only model the solvers can invert
+    pub fn is_adaptive_curve(&self) -> bool {
+        matches!(self, Irm::AdaptiveCurve)
+    }
+}
+
+/// Custom models compare equal only to the same instance
+impl PartialEq for Irm {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Irm::AdaptiveCurve, Irm::AdaptiveCurve) => true,
+            (Irm::FixedRate(a), Irm::FixedRate(b)) => a == b,
+            (Irm::Custom(a), Irm::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Irm {}
+
+impl InterestRateModel for Irm {
+    fn borrow_rates(
+        &self,
+        utilization: U256,
+        rate_at_target: Option<U256>,
+        elapsed: u64,
+    ) -> IrmRates {
+        match self {
+            Irm::AdaptiveCurve => {
+                AdaptiveCurveIrm.borrow_rates(utilization, rate_at_target, elapsed)
+            }
+            Irm::FixedRate(irm) => irm.borrow_rates(utilization, rate_at_target, elapsed),
+            Irm::Custom(irm) => irm.borrow_rates(utilization, rate_at_target, elapsed),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -639,4 +813,48 @@ mod tests {
         // Should be clamped to MIN_RATE_AT_TARGET
         assert!(result_low.end_rate_at_target >= MIN_RATE_AT_TARGET);
     }
+
+    #[test]
+    fn test_adaptive_curve_irm_matches_get_borrow_rate() {
+        let utilization = U256::from(950_000_000_000_000_000u64);
+        let expected = get_borrow_rate(utilization, INITIAL_RATE_AT_TARGET, 86400);
+
+        let rates =
+            Irm::AdaptiveCurve.borrow_rates(utilization, Some(INITIAL_RATE_AT_TARGET), 86400);
+        assert_eq!(rates.avg_borrow_rate, expected.avg_borrow_rate);
+        assert_eq!(rates.end_borrow_rate, expected.end_borrow_rate);
+        assert_eq!(rates.end_rate_at_target, Some(expected.end_rate_at_target));
+
+        let no_state = AdaptiveCurveIrm.borrow_rates(utilization, None, 86400);
+        assert_eq!(no_state.avg_borrow_rate, U256::ZERO);
+        assert_eq!(no_state.end_rate_at_target, None);
+    }
+
+    #[test]
+    fn test_fixed_rate_irm_ignores_utilization() {
+        let irm = FixedRateIrm::from_annual_rate(WAD / U256::from(20));
+        assert_eq!(
+            irm.borrow_rate,
+            WAD / U256::from(20) / U256::from(math::SECONDS_PER_YEAR)
+        );
+
+        for utilization in [U256::ZERO, TARGET_UTILIZATION, WAD] {
+            let rates = irm.borrow_rates(utilization, Some(INITIAL_RATE_AT_TARGET), 86400);
+            assert_eq!(rates.avg_borrow_rate, irm.borrow_rate);
+            assert_eq!(rates.end_borrow_rate, irm.borrow_rate);
+            assert_eq!(rates.end_rate_at_target, None);
+        }
+    }
+
+    #[test]
+    fn test_custom_irm_equality() {
+        let custom: Arc<dyn InterestRateModel> = Arc::new(FixedRateIrm::new(U256::from(1)));
+        assert_eq!(Irm::Custom(custom.clone()), Irm::Custom(custom.clone()));
+        assert_ne!(
+            Irm::Custom(custom),
+            Irm::Custom(Arc::new(FixedRateIrm::new(U256::from(1))))
+        );
+        assert_eq!(Irm::default(), Irm::AdaptiveCurve);
+        assert_ne!(Irm::AdaptiveCurve, Irm::FixedRate(FixedRateIrm::default()));
+    }
 }
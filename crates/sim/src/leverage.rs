@@ -0,0 +1,258 @@
+//! Leveraged collateral positions.
+//!
+//! A leveraged position is opened in one transaction: the collateral is supplied
+//! together with extra collateral bought with borrowed loan assets, the purchase being
+//! funded by a flash loan (or Morpho's supply-collateral callback) that the borrow
+//! repays. [`simulate_leverage`] sizes that borrow for a target LTV and reports the
+//! resulting position's health before anything is sent on-chain.
+//!
+//! Starting from `collateral` worth `V` loan assets, reaching LTV `L` requires
+//! borrowing `B = V * L / (1 - L)`, which buys `B / price` more collateral. The total
+//! collateral is `collateral / (1 - L)`, so the leverage multiple is `1 / (1 - L)`.
+//! Swaps are assumed to execute at the oracle price; slippage lowers the collateral
+//! actually bought and raises the resulting LTV.
+//!
+//! # Example
+//!
+//! ```rust
+//! use morpho_rs_sim::{simulate_leverage, Market, ORACLE_PRICE_SCALE, WAD};
+//! use alloy_primitives::{FixedBytes, U256};
+//!
+//! let market = Market::new_with_oracle(
+//!     FixedBytes::ZERO,
+//!     U256::from(1_000_000) * WAD,
+//!     U256::from(500_000) * WAD,
+//!     U256::from(1_000_000) * WAD,
+//!     U256::from(500_000) * WAD,
+//!     1000,
+//!     U256::ZERO,
+//!     Some(U256::from(1_268_391_679u64)),
+//!     Some(ORACLE_PRICE_SCALE),
+//!     U256::from(860_000_000_000_000_000u64),  // 86% LLTV
+//! );
+//!
+//! // 100 collateral levered to 75% LTV: 4x
+//! let target_ltv = U256::from(750_000_000_000_000_000u64);
+//! let sim = simulate_leverage(&market, U256::from(100) * WAD, target_ltv).unwrap();
+//!
+//! assert_eq!(sim.borrow_assets, U256::from(300) * WAD);
+//! assert_eq!(sim.total_collateral, U256::from(400) * WAD);
+//! assert_eq!(sim.leverage, U256::from(4) * WAD);
+//! assert!(sim.health_factor > WAD);
+//! ```
+
+use alloy_primitives::{Address, U256};
+
+use crate::error::SimError;
+use crate::market::{Market, ORACLE_PRICE_SCALE};
+use crate::math::{mul_div_down, w_div_down, WAD};
+use crate::position::Position;
+
+/// A leveraged position sized for a target LTV.
+#[derive(Debug, Clone)]
+pub struct LeverageSimulation {
+    /// The position after leveraging (owned by `Address::ZERO`)
+    pub position: Position,
+    /// The market after the borrow
+    pub market: Market,
+    /// Collateral supplied by the user
+    pub initial_collateral: U256,
+    /// Collateral bought with the borrowed assets (the flash-loaned amount)
+    pub flash_collateral: U256,
+    /// Total collateral of the position
+    pub total_collateral: U256,
+    /// Loan assets borrowed to repay the flash loan
+    pub borrow_assets: U256,
+    /// Total collateral over initial collateral (WAD-scaled)
+    pub leverage: U256,
+    /// Resulting LTV (WAD-scaled)
+    pub ltv: U256,
+    /// Resulting health factor (WAD-scaled, `U256::MAX` without debt)
+    pub health_factor: U256,
+    /// Oracle price at which the position becomes liquidatable (`None` without debt)
+    pub liquidation_price: Option<U256>,
+    /// Relative price move to liquidation (WAD-scaled, negative = price must drop)
+    pub price_variation_to_liquidation: Option<i128>,
+}
+
+/// Simulates levering `collateral` up to `target_ltv` in `market`.
+///
+/// The borrow is simulated at the market's `last_update`, so no interest accrues.
+///
+/// # Arguments
+///
+/// * `market` - The market to borrow from, with an oracle price and LLTV
+/// * `collateral` - Collateral supplied by the user
+/// * `target_ltv` - LTV of the resulting position (WAD-scaled)
+///
+/// # Errors
+///
+/// - [`SimError::UnknownOraclePrice`] if the market has no oracle price
+/// - [`SimError::DivisionByZero`] if the oracle price is zero
+/// - [`SimError::InvalidTargetLtv`] if `target_ltv` is not below the market's LLTV
+/// - [`SimError::InsufficientMarketLiquidity`] if the market cannot lend the borrow
+pub fn simulate_leverage(
+    market: &Market,
+    collateral: U256,
+    target_ltv: U256,
+) -> Result<LeverageSimulation, SimError> {
+    let price = market.price.ok_or(SimError::UnknownOraclePrice {
+        market_id: market.id,
+    })?;
+    if price.is_zero() {
+        return Err(SimError::DivisionByZero);
+    }
+    if target_ltv >= market.lltv {
+        return Err(SimError::InvalidTargetLtv {
+            market_id: market.id,
+            target_ltv: target_ltv.saturating_to::<u128>(),
+            lltv: market.lltv.saturating_to::<u128>(),
+        });
+    }
+
+    // B = V * L / (1 - L); L < LLTV <= WAD so the denominator is positive
+    let collateral_value = mul_div_down(collateral, price, ORACLE_PRICE_SCALE);
+    let borrow_assets = mul_div_down(collateral_value, target_ltv, WAD - target_ltv);
+    let flash_collateral = mul_div_down(borrow_assets, ORACLE_PRICE_SCALE, price);
+    let total_collateral = collateral + flash_collateral;
+
+    let supplied = Position::empty(Address::ZERO, market.id).supply_collateral(total_collateral);
+    let (position, market) = if borrow_assets.is_zero() {
+        (supplied, market.clone())
+    } else {
+        let (position, market, _) = supplied.borrow(market, borrow_assets, market.last_update)?;
+        (position, market)
+    };
+
+    let unknown_price = || SimError::UnknownOraclePrice {
+        market_id: market.id,
+    };
+
+    Ok(LeverageSimulation {
+        initial_collateral: collateral,
+        flash_collateral,
+        total_collateral,
+        borrow_assets,
+        leverage: if collateral.is_zero() {
+            WAD
+        } else {
+            w_div_down(total_collateral, collateral)
+        },
+        ltv: position.ltv(&market).ok_or_else(unknown_price)?,
+        health_factor: position.health_factor(&market).ok_or_else(unknown_price)?,
+        liquidation_price: position.liquidation_price(&market),
+        price_variation_to_liquidation: position.price_variation_to_liquidation(&market),
+        position,
+        market,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::rate_to_f64;
+    use alloy_primitives::FixedBytes;
+
+    const LLTV: u64 = 860_000_000_000_000_000;
+
+    fn create_test_market(price: Option<U256>) -> Market {
+        Market::new_with_oracle(
+            FixedBytes::ZERO,
+            U256::from(1_000_000) * WAD,
+            U256::from(500_000) * WAD,
+            U256::from(1_000_000) * WAD,
+            U256::from(500_000) * WAD,
+            1000,
+            U256::ZERO,
+            Some(U256::from(1_268_391_679u64)),
+            price,
+            U256::from(LLTV),
+        )
+    }
+
+    fn wad(value: f64) -> U256 {
+        crate::math::f64_to_wad(value)
+    }
+
+    #[test]
+    fn test_leverage_sizing() {
+        // Collateral worth 2 loan assets per unit
+        let market = create_test_market(Some(ORACLE_PRICE_SCALE * U256::from(2)));
+        let sim = simulate_leverage(&market, U256::from(100) * WAD, wad(0.5)).unwrap();
+
+        // 200 of value at 50% LTV: borrow 200, buy 100 more collateral
+        assert_eq!(sim.borrow_assets, U256::from(200) * WAD);
+        assert_eq!(sim.flash_collateral, U256::from(100) * WAD);
+        assert_eq!(sim.total_collateral, U256::from(200) * WAD);
+        assert_eq!(sim.leverage, U256::from(2) * WAD);
+        assert_eq!(sim.position.collateral, sim.total_collateral);
+
+        let ltv = rate_to_f64(sim.ltv);
+        assert!((ltv - 0.5).abs() < 1e-9);
+        // HF = LLTV / LTV
+        let hf = rate_to_f64(sim.health_factor);
+        assert!((hf - 1.72).abs() < 1e-9);
+
+        // Liquidation when the price falls by 1 - LTV / LLTV
+        let variation = sim.price_variation_to_liquidation.unwrap() as f64 / 1e18;
+        assert!((variation + (1.0 - 0.5 / 0.86)).abs() < 1e-9);
+        assert!(sim.liquidation_price.unwrap() < ORACLE_PRICE_SCALE * U256::from(2));
+
+        // The borrow is reflected in the market
+        assert_eq!(
+            sim.market.total_borrow_assets,
+            market.total_borrow_assets + sim.borrow_assets
+        );
+    }
+
+    #[test]
+    fn test_higher_target_is_riskier() {
+        let market = create_test_market(Some(ORACLE_PRICE_SCALE));
+        let collateral = U256::from(100) * WAD;
+        let low = simulate_leverage(&market, collateral, wad(0.5)).unwrap();
+        let high = simulate_leverage(&market, collateral, wad(0.8)).unwrap();
+
+        assert!(high.leverage > low.leverage);
+        assert!(high.health_factor < low.health_factor);
+        assert!(high.liquidation_price.unwrap() > low.liquidation_price.unwrap());
+        assert!(high.health_factor > WAD);
+    }
+
+    #[test]
+    fn test_zero_target_is_unlevered() {
+        let market = create_test_market(Some(ORACLE_PRICE_SCALE));
+        let sim = simulate_leverage(&market, U256::from(100) * WAD, U256::ZERO).unwrap();
+
+        assert_eq!(sim.borrow_assets, U256::ZERO);
+        assert_eq!(sim.leverage, WAD);
+        assert_eq!(sim.health_factor, U256::MAX);
+        assert!(sim.liquidation_price.is_none());
+    }
+
+    #[test]
+    fn test_target_at_lltv_rejected() {
+        let market = create_test_market(Some(ORACLE_PRICE_SCALE));
+        let result = simulate_leverage(&market, U256::from(100) * WAD, U256::from(LLTV));
+        assert!(matches!(result, Err(SimError::InvalidTargetLtv { .. })));
+    }
+
+    #[test]
+    fn test_insufficient_liquidity() {
+        let market = create_test_market(Some(ORACLE_PRICE_SCALE));
+        // 500K liquidity; 200K collateral at 80% needs an 800K borrow
+        let result = simulate_leverage(&market, U256::from(200_000) * WAD, wad(0.8));
+        assert!(matches!(
+            result,
+            Err(SimError::InsufficientMarketLiquidity { .. })
+        ));
+    }
+
+    #[test]
+    fn test_price_required() {
+        let result = simulate_leverage(&create_test_market(None), WAD, wad(0.5));
+        assert!(matches!(result, Err(SimError::UnknownOraclePrice { .. })));
+
+        let result = simulate_leverage(&create_test_market(Some(U256::ZERO)), WAD, wad(0.5));
+        assert!(matches!(result, Err(SimError::DivisionByZero)));
+    }
+}
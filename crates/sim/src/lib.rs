@@ -11,9 +11,13 @@
 //! - **Market Simulation**: Supply, borrow, withdraw, and repay operations with accurate interest accrual
 //! - **APY Calculations**: Calculate supply/borrow APYs using the Adaptive Curve IRM
 //! - **Vault Operations**: Simulate MetaMorpho vault deposits, withdrawals, and reallocations
+//! - **Vault V2**: Simulate adapter allocations, id caps, and performance/management fees
 //! - **Position Tracking**: Monitor health factors, LTV, liquidation prices, and capacity limits
+//! - **Leverage**: Size flash-loan leveraged positions for a target LTV
 //! - **Yield Optimization**: Find optimal market allocations and best vaults for deposits
-//! - **Public Allocator**: Simulate public reallocation with flow limits
+//! - **Public Allocator**: Simulate public reallocation with flow limits and find the cheapest
+//!   reallocations sourcing liquidity across vaults
+//! - **Invariant Checks**: Validate market and vault state with `check_invariants()`
 //!
 //! ## Quick Start
 //!
@@ -93,48 +97,120 @@
 //!
 //! ## Modules
 //!
+//! - [`builder`]: Validating builders for hand-constructed markets and vaults
 //! - [`market`]: Market state and operations (supply, borrow, APY calculations)
 //! - [`vault`]: MetaMorpho vault simulation (deposits, withdrawals, reallocations)
+//! - [`vault_v2`]: Vault V2 simulation (adapters, id caps, liquidity adapter, fees)
 //! - [`position`]: Position tracking with health factor and liquidation metrics
-//! - [`irm`]: Adaptive Curve Interest Rate Model implementation
+//! - [`pre_liquidation`]: Pre-liquidation terms and simulation for opted-in borrowers
+//! - [`public_allocator`]: Cheapest public reallocations sourcing liquidity across vaults
+//! - [`leverage`]: Sizing flash-loan leveraged positions for a target LTV
+//! - [`projection`]: Projected deposit balances over time with compounding schedules
+//! - [`scenario`]: Price shock stress tests for borrow positions
+//! - [`solver`]: Exact supply/withdraw amounts for target APYs and utilizations
+//! - [`irm`]: Adaptive Curve Interest Rate Model implementation and the [`InterestRateModel`] trait
 //! - [`math`]: Fixed-point arithmetic utilities
+//! - `conformance`: Golden vectors checked against the TypeScript SDK (`serde` feature)
+//! - `snapshot`: Saving and loading simulation state as JSON (`serde` feature)
+//! - `parallel`: Rayon-parallel vault ranking and market allocation (`parallel` feature)
 //! - [`error`]: Error types for simulation operations
 
+pub mod builder;
+#[cfg(feature = "serde")]
+pub mod conformance;
 pub mod error;
 pub mod irm;
+pub mod leverage;
 pub mod market;
 pub mod math;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod position;
+pub mod pre_liquidation;
+pub mod public_allocator;
+pub mod projection;
+pub mod scenario;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+pub mod solver;
 pub mod vault;
+pub mod vault_v2;
 
 // Re-export commonly used types
+pub use builder::{MarketBuilder, VaultBuilder};
 pub use error::{MarketId, SimError};
 
 // Market exports
 pub use market::{
     borrow_apy_impact, find_best_market_for_supply, get_liquidation_incentive_factor,
     get_utilization, rank_markets_by_borrow_apy, rank_markets_by_supply_apy, supply_apy_impact,
-    BorrowApyImpact, Market, MarketRanking, SupplyApyImpact,
-    LIQUIDATION_CURSOR, MAX_LIQUIDATION_INCENTIVE_FACTOR, ORACLE_PRICE_SCALE,
+    AccrualCache, BorrowApyImpact, LiquidationAmount, LiquidationResult, Market, MarketRanking, RatePathPoint,
+    SupplyApyImpact, UtilizationPoint, LIQUIDATION_CURSOR, MAX_LIQUIDATION_INCENTIVE_FACTOR,
+    ORACLE_PRICE_SCALE,
 };
 
+// Leverage exports
+pub use leverage::{simulate_leverage, LeverageSimulation};
+
 // Math exports
 pub use math::{RoundingDirection, SECONDS_PER_YEAR, WAD};
 
+// Parallel exports
+#[cfg(feature = "parallel")]
+pub use parallel::{
+    par_find_optimal_market_allocation, par_rank_vaults_by_apy, par_rank_vaults_by_apy_cached,
+};
+
 // Position exports
-pub use position::{CapacityLimit, CapacityLimitReason, Position, PositionCapacities};
+pub use position::{
+    CapacityLimit, CapacityLimitReason, DebtPoint, Position, PositionCapacities,
+    PositionProjection,
+};
+
+// Pre-liquidation exports
+pub use pre_liquidation::{PreLiquidationParams, PreLiquidationTerms};
+
+// Public allocator exports
+pub use public_allocator::{find_public_allocator_route, PublicAllocatorRoute, PublicReallocation};
+
+// Projection exports
+pub use projection::{project_deposit, Compounding};
+
+// Scenario exports
+pub use scenario::{stress_test, PriceShock, ShockResult};
+
+// Snapshot exports
+#[cfg(feature = "serde")]
+pub use snapshot::Snapshot;
+
+// Solver exports
+pub use solver::{
+    supply_for_target_apy, supply_for_target_borrow_apy, supply_for_target_utilization,
+    withdraw_for_target_apy, withdraw_for_target_utilization,
+};
 
 // Vault exports
 pub use vault::{
     amount_for_vault_apy_impact, find_best_vault_for_deposit, find_optimal_market_allocation,
-    rank_vaults_by_apy, vault_deposit_apy_impact, vault_withdraw_apy_impact,
-    OptimalAllocation, PublicAllocatorConfig, PublicAllocatorMarketConfig,
-    ReallocationStep, Vault, VaultApyImpact, VaultMarketConfig, VaultRanking, VaultSimulation,
+    find_optimal_vault_split, rank_vaults_by_apy, suggest_reallocation, vault_deposit_apy_impact,
+    vault_withdraw_apy_impact, BadDebtExposure, FeeRevenueProjection, MarketBadDebtExposure,
+    MarketWithdrawalLeg, MarketWithdrawalLiquidity, OptimalAllocation, PublicAllocatorConfig,
+    PublicAllocatorMarketConfig, QueueFinding, QueueKind, ReallocationConstraints,
+    ReallocationStep, ReallocationSuggestion, Vault, VaultApyImpact, VaultMarketConfig,
+    VaultRanking, VaultSimulation, VaultSplit, VaultSplitAllocation, VaultSplitConstraints,
+    WithdrawalLiquidityProfile, WithdrawalPlan, DEFAULT_SPLIT_STEPS, MAX_VAULT_FEE,
+};
+
+// Vault V2 exports
+pub use vault_v2::{
+    AdapterMarketPosition, CapId, VaultV2Adapter, VaultV2AdapterKind, VaultV2Cap,
+    VaultV2FeeAccrual, VaultV2Simulation, MAX_MANAGEMENT_FEE, MAX_PERFORMANCE_FEE,
 };
 
 // IRM exports
 pub use irm::{
     get_borrow_rate, get_supply_for_borrow_rate, get_utilization_at_borrow_rate, w_exp,
-    BorrowRateResult, ADJUSTMENT_SPEED, CURVE_STEEPNESS, INITIAL_RATE_AT_TARGET,
+    AdaptiveCurveIrm, BorrowRateResult, FixedRateIrm, InterestRateModel, Irm, IrmRates,
+    ADJUSTMENT_SPEED, CURVE_STEEPNESS, INITIAL_RATE_AT_TARGET,
     MAX_RATE_AT_TARGET, MIN_RATE_AT_TARGET, TARGET_UTILIZATION,
 };
@@ -16,6 +16,8 @@
 //! - [`Market::supply`] / [`Market::withdraw`] - Lender operations
 //! - [`Market::borrow`] / [`Market::repay`] - Borrower operations
 //! - [`Market::accrue_interest`] - Update market state with accrued interest
+//! - [`Market::liquidate`] - Liquidate an unhealthy position, realizing bad debt
+//! - [`Market::simulate_utilization_path`] - Play utilization scenarios through the IRM
 //! - [`supply_apy_impact`] / [`borrow_apy_impact`] - Analyze APY changes
 //!
 //! # Example
@@ -41,15 +43,19 @@
 //! assert!(impact.apy_delta < 0.0); // APY decreases with more supply
 //! ```
 
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
 use alloy_primitives::U256;
 
 use crate::error::{MarketId, SimError};
-use crate::irm::get_borrow_rate;
+use crate::irm::{InterestRateModel, Irm};
 use crate::math::{
-    self, assets_to_shares, mul_div_down, mul_div_up, rate_to_apy, shares_to_assets,
+    self, assets_to_shares, mul_div_down, mul_div_up, rate_to_apy, rate_to_apy_wad, shares_to_assets,
     w_div_down, w_div_up, w_mul_down, w_mul_up, w_taylor_compounded, zero_floor_sub,
     RoundingDirection, WAD,
 };
+use crate::position::Position;
 
 /// Liquidation cursor used to calculate the liquidation incentive (30%)
 pub const LIQUIDATION_CURSOR: U256 = U256::from_limbs([300_000_000_000_000_000, 0, 0, 0]);
@@ -68,6 +74,7 @@ pub const ORACLE_PRICE_SCALE: U256 = U256::from_limbs([
 
 /// Represents a lending market on Morpho Blue.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Market {
     /// The market's unique identifier (keccak256 hash of market params)
     pub id: MarketId,
@@ -94,6 +101,10 @@ pub struct Market {
     /// None for markets using other IRMs.
     pub rate_at_target: Option<U256>,
 
+    /// The interest rate model borrow rates are computed with (Adaptive Curve by default)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub irm: Irm,
+
     /// Oracle price (collateral/loan, scaled by ORACLE_PRICE_SCALE)
     /// None if oracle is not set or reverts
     pub price: Option<U256>,
@@ -160,6 +171,7 @@ impl Market {
             last_update,
             fee,
             rate_at_target,
+            irm: Irm::AdaptiveCurve,
             price: None,
             lltv: U256::ZERO,
         }
@@ -188,6 +200,7 @@ impl Market {
             last_update,
             fee,
             rate_at_target,
+            irm: Irm::AdaptiveCurve,
             price,
             lltv,
         }
@@ -206,6 +219,54 @@ impl Market {
         get_utilization(self.total_supply_assets, self.total_borrow_assets)
     }
 
+    /// Checks that the market state is internally consistent.
+    ///
+    /// Every state produced by [`supply`](Self::supply), [`withdraw`](Self::withdraw),
+    /// [`borrow`](Self::borrow), [`repay`](Self::repay), and
+    /// [`accrue_interest`](Self::accrue_interest) from a consistent state is consistent,
+    /// so a violation means the state was built or hydrated incorrectly:
+    ///
+    /// - borrow assets do not exceed supply assets
+    /// - the protocol fee does not exceed 100% ([`WAD`])
+    /// - neither side has more than [`VIRTUAL_SHARES`](math::VIRTUAL_SHARES) shares per
+    ///   asset, i.e. shares are never worth less than at the initial exchange rate
+    ///
+    /// Markets that realized bad debt on-chain can fail the last check.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::BorrowExceedsSupply`] if borrow assets exceed supply assets
+    /// - [`SimError::InvalidMarketFee`] if the fee exceeds [`WAD`]
+    /// - [`SimError::ExcessShares`] if either side has too many shares for its assets
+    pub fn check_invariants(&self) -> Result<(), SimError> {
+        if self.total_borrow_assets > self.total_supply_assets {
+            return Err(SimError::BorrowExceedsSupply {
+                market_id: self.id,
+                borrow: self.total_borrow_assets,
+                supply: self.total_supply_assets,
+            });
+        }
+        if self.fee > WAD {
+            return Err(SimError::InvalidMarketFee {
+                market_id: self.id,
+                fee: self.fee,
+            });
+        }
+        for (shares, assets) in [
+            (self.total_supply_shares, self.total_supply_assets),
+            (self.total_borrow_shares, self.total_borrow_assets),
+        ] {
+            if shares > assets.saturating_mul(math::VIRTUAL_SHARES) {
+                return Err(SimError::ExcessShares {
+                    market_id: self.id,
+                    shares,
+                    assets,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Get the borrow rates for this market
     fn get_accrual_borrow_rates(&self, timestamp: u64) -> Result<AccrualRates, SimError> {
         if timestamp < self.last_update {
@@ -217,23 +278,15 @@ impl Market {
 
         let elapsed = timestamp - self.last_update;
 
-        match self.rate_at_target {
-            None => Ok(AccrualRates {
-                elapsed,
-                avg_borrow_rate: U256::ZERO,
-                end_borrow_rate: U256::ZERO,
-                end_rate_at_target: None,
-            }),
-            Some(rate_at_target) => {
-                let result = get_borrow_rate(self.utilization(), rate_at_target, elapsed);
-                Ok(AccrualRates {
-                    elapsed,
-                    avg_borrow_rate: result.avg_borrow_rate,
-                    end_borrow_rate: result.end_borrow_rate,
-                    end_rate_at_target: Some(result.end_rate_at_target),
-                })
-            }
-        }
+        let rates = self
+            .irm
+            .borrow_rates(self.utilization(), self.rate_at_target, elapsed);
+        Ok(AccrualRates {
+            elapsed,
+            avg_borrow_rate: rates.avg_borrow_rate,
+            end_borrow_rate: rates.end_borrow_rate,
+            end_rate_at_target: rates.end_rate_at_target,
+        })
     }
 
     /// Returns the instantaneous borrow rate at the given timestamp
@@ -284,6 +337,24 @@ impl Market {
         Ok(rate_to_apy(rate))
     }
 
+    /// Returns the instantaneous borrow APY, WAD-scaled and computed exactly
+    ///
+    /// See [`rate_to_apy_wad`]; [`get_borrow_apy`](Self::get_borrow_apy) is the f64
+    /// convenience.
+    pub fn get_borrow_apy_wad(&self, timestamp: u64) -> Result<U256, SimError> {
+        let rate = self.get_end_borrow_rate(timestamp)?;
+        Ok(rate_to_apy_wad(rate))
+    }
+
+    /// Returns the instantaneous supply APY, WAD-scaled and computed exactly
+    ///
+    /// See [`rate_to_apy_wad`]; [`get_supply_apy`](Self::get_supply_apy) is the f64
+    /// convenience.
+    pub fn get_supply_apy_wad(&self, timestamp: u64) -> Result<U256, SimError> {
+        let rate = self.get_supply_rate(timestamp)?;
+        Ok(rate_to_apy_wad(rate))
+    }
+
     /// Accrues interest on the market up to the given timestamp.
     ///
     /// This is the core function for advancing market state. Interest is calculated
@@ -356,6 +427,7 @@ impl Market {
             last_update: timestamp,
             fee: self.fee,
             rate_at_target: rates.end_rate_at_target.or(self.rate_at_target),
+            irm: self.irm.clone(),
             price: self.price,
             lltv: self.lltv,
         })
@@ -700,6 +772,302 @@ impl Market {
         let max_seizable = self.get_liquidation_seized_assets(borrow_shares)?;
         Some(math::min(collateral, max_seizable))
     }
+
+    /// Liquidates an unhealthy position, following Morpho Blue's `liquidate`.
+    ///
+    /// Interest is accrued first. The liquidator either names the collateral to seize
+    /// (the repaid shares are derived, rounding up) or the borrow shares to repay (the
+    /// seized collateral is derived, rounding down), with the liquidation incentive
+    /// applied in both cases. If the position is left with no collateral, its remaining
+    /// debt is realized as bad debt: it is removed from both total borrow and total
+    /// supply, so suppliers absorb the loss pro rata.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The borrower's position in this market
+    /// * `amount` - Collateral to seize or borrow shares to repay
+    /// * `timestamp` - Current Unix timestamp for interest accrual
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::UnknownOraclePrice`] if the market has no oracle price
+    /// - [`SimError::HealthyPosition`] if the position is healthy
+    /// - [`SimError::DivisionByZero`] if repaying shares at a zero oracle price
+    /// - [`SimError::InsufficientPosition`] if the liquidation repays more shares or
+    ///   seizes more collateral than the position holds
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morpho_rs_sim::{LiquidationAmount, Market, Position, ORACLE_PRICE_SCALE, WAD};
+    /// use alloy_primitives::{Address, FixedBytes, U256};
+    ///
+    /// // Collateral trades at half the loan asset's value
+    /// let market = Market::new_with_oracle(
+    ///     FixedBytes::ZERO,
+    ///     U256::from(1_000_000) * WAD,
+    ///     U256::from(800_000) * WAD,
+    ///     U256::from(1_000_000) * WAD,
+    ///     U256::from(800_000) * WAD,
+    ///     1000,
+    ///     U256::ZERO,
+    ///     None,
+    ///     Some(ORACLE_PRICE_SCALE / U256::from(2)),
+    ///     U256::from(800_000_000_000_000_000u64),  // 80% LLTV
+    /// );
+    ///
+    /// // 500 debt against 1000 collateral worth 500: underwater
+    /// let position = Position::new(
+    ///     Address::ZERO,
+    ///     FixedBytes::ZERO,
+    ///     U256::ZERO,
+    ///     U256::from(500) * WAD,
+    ///     U256::from(1000) * WAD,
+    /// );
+    ///
+    /// let result = market
+    ///     .liquidate(&position, LiquidationAmount::SeizedAssets(position.collateral), 1000)
+    ///     .unwrap();
+    ///
+    /// // All collateral is seized and the uncovered debt is socialized
+    /// assert!(result.position.collateral.is_zero());
+    /// assert!(result.position.borrow_shares.is_zero());
+    /// assert!(result.bad_debt_assets > U256::ZERO);
+    /// assert_eq!(
+    ///     result.market.total_supply_assets,
+    ///     market.total_supply_assets - result.bad_debt_assets
+    /// );
+    /// ```
+    pub fn liquidate(
+        &self,
+        position: &Position,
+        amount: LiquidationAmount,
+        timestamp: u64,
+    ) -> Result<LiquidationResult, SimError> {
+        let price = self
+            .price
+            .ok_or(SimError::UnknownOraclePrice { market_id: self.id })?;
+        let mut market = self.accrue_interest(timestamp)?;
+
+        if market.is_healthy(position.collateral, position.borrow_shares) != Some(false) {
+            return Err(SimError::HealthyPosition {
+                user: position.user,
+                market_id: self.id,
+            });
+        }
+
+        let incentive = market.get_liquidation_incentive_factor();
+        let (seized_assets, repaid_shares) = market.liquidation_amounts(price, incentive, amount)?;
+
+        if repaid_shares > position.borrow_shares || seized_assets > position.collateral {
+            return Err(SimError::InsufficientPosition {
+                user: position.user,
+                market_id: self.id,
+            });
+        }
+
+        let repaid_assets = market.to_borrow_assets(repaid_shares, RoundingDirection::Up);
+
+        let mut new_position = position.clone();
+        new_position.borrow_shares -= repaid_shares;
+        new_position.collateral -= seized_assets;
+        market.total_borrow_shares -= repaid_shares;
+        market.total_borrow_assets = zero_floor_sub(market.total_borrow_assets, repaid_assets);
+
+        let mut bad_debt_shares = U256::ZERO;
+        let mut bad_debt_assets = U256::ZERO;
+        if new_position.collateral.is_zero() {
+            bad_debt_shares = new_position.borrow_shares;
+            bad_debt_assets = math::min(
+                market.total_borrow_assets,
+                market.to_borrow_assets(bad_debt_shares, RoundingDirection::Up),
+            );
+            market.total_borrow_assets -= bad_debt_assets;
+            market.total_supply_assets -= bad_debt_assets;
+            market.total_borrow_shares -= bad_debt_shares;
+            new_position.borrow_shares = U256::ZERO;
+        }
+
+        Ok(LiquidationResult {
+            market,
+            position: new_position,
+            seized_assets,
+            repaid_assets,
+            repaid_shares,
+            bad_debt_assets,
+            bad_debt_shares,
+        })
+    }
+
+    /// Seized collateral and repaid borrow shares of a liquidation at `price` with the
+    /// given incentive factor, rounded as Morpho Blue does
+    pub(crate) fn liquidation_amounts(
+        &self,
+        price: U256,
+        incentive: U256,
+        amount: LiquidationAmount,
+    ) -> Result<(U256, U256), SimError> {
+        match amount {
+            LiquidationAmount::SeizedAssets(seized_assets) => {
+                let seized_assets_quoted = mul_div_up(seized_assets, price, ORACLE_PRICE_SCALE);
+                let repaid_shares = self.to_borrow_shares(
+                    w_div_up(seized_assets_quoted, incentive),
+                    RoundingDirection::Up,
+                );
+                Ok((seized_assets, repaid_shares))
+            }
+            LiquidationAmount::RepaidShares(repaid_shares) => {
+                if price.is_zero() {
+                    return Err(SimError::DivisionByZero);
+                }
+                let repaid_assets = self.to_borrow_assets(repaid_shares, RoundingDirection::Down);
+                let seized_assets = mul_div_down(
+                    w_mul_down(repaid_assets, incentive),
+                    ORACLE_PRICE_SCALE,
+                    price,
+                );
+                Ok((seized_assets, repaid_shares))
+            }
+        }
+    }
+
+    // ==================== Rate Scenarios ====================
+
+    /// Plays a sequence of utilization levels through the Adaptive Curve IRM.
+    ///
+    /// Starting at `last_update`, each [`UtilizationPoint`] sets the market's utilization
+    /// (by resizing total borrow against the current supply) and holds it for
+    /// `duration_secs`, accruing interest and adapting `rate_at_target` over the segment.
+    /// This shows how sustained utilization above target ratchets rates upward over days,
+    /// and how they decay once utilization drops back.
+    ///
+    /// Utilization levels above 100% are capped at 100%.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Utilization levels and how long each is held
+    ///
+    /// # Returns
+    ///
+    /// One [`RatePathPoint`] per segment, with rates at the end of the segment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morpho_rs_sim::{Market, UtilizationPoint, WAD};
+    /// use alloy_primitives::{FixedBytes, U256};
+    ///
+    /// let market = Market::new(
+    ///     FixedBytes::ZERO,
+    ///     U256::from(1_000_000) * WAD,
+    ///     U256::from(900_000) * WAD,
+    ///     U256::from(1_000_000) * WAD,
+    ///     U256::from(900_000) * WAD,
+    ///     1000,
+    ///     U256::from(100_000_000_000_000_000u64),
+    ///     Some(U256::from(1_268_391_679u64)),
+    /// );
+    ///
+    /// // 98% utilization for a week, one day at a time
+    /// let path = vec![UtilizationPoint::new(U256::from(98) * WAD / U256::from(100), 86_400); 7];
+    /// let rates = market.simulate_utilization_path(&path).unwrap();
+    ///
+    /// // Rate at target keeps rising while utilization stays above 90%
+    /// assert!(rates[6].rate_at_target > rates[0].rate_at_target);
+    /// assert!(rates[6].borrow_apy > rates[0].borrow_apy);
+    /// ```
+    pub fn simulate_utilization_path(
+        &self,
+        path: &[UtilizationPoint],
+    ) -> Result<Vec<RatePathPoint>, SimError> {
+        let mut market = self.clone();
+        let mut results = Vec::with_capacity(path.len());
+
+        for point in path {
+            let utilization = math::min(point.utilization, WAD);
+            let borrow_assets = w_mul_down(market.total_supply_assets, utilization);
+            market.total_borrow_shares =
+                market.to_borrow_shares(borrow_assets, RoundingDirection::Up);
+            market.total_borrow_assets = borrow_assets;
+
+            let end = market.last_update.saturating_add(point.duration_secs);
+            let borrow_rate = market.get_end_borrow_rate(end)?;
+            let supply_rate = market.get_supply_rate(end)?;
+            market = market.accrue_interest(end)?;
+
+            results.push(RatePathPoint {
+                timestamp: end,
+                utilization,
+                rate_at_target: market.rate_at_target,
+                borrow_apy: rate_to_apy(borrow_rate),
+                supply_apy: rate_to_apy(supply_rate),
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+/// A utilization level held for a period of time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtilizationPoint {
+    /// Target utilization (WAD-scaled, e.g., 0.95 WAD = 95%)
+    pub utilization: U256,
+    /// How long the utilization is held, in seconds
+    pub duration_secs: u64,
+}
+
+impl UtilizationPoint {
+    /// Create a utilization point
+    pub fn new(utilization: U256, duration_secs: u64) -> Self {
+        Self {
+            utilization,
+            duration_secs,
+        }
+    }
+}
+
+/// Market rates at the end of a utilization segment
+#[derive(Debug, Clone)]
+pub struct RatePathPoint {
+    /// Timestamp at the end of the segment
+    pub timestamp: u64,
+    /// Utilization held during the segment (WAD-scaled)
+    pub utilization: U256,
+    /// Rate at target after adaptation (None for non-adaptive IRMs)
+    pub rate_at_target: Option<U256>,
+    /// Instantaneous borrow APY at the end of the segment
+    pub borrow_apy: f64,
+    /// Instantaneous supply APY at the end of the segment
+    pub supply_apy: f64,
+}
+
+/// How much of a position to liquidate. Morpho Blue takes exactly one of the two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidationAmount {
+    /// Collateral to seize; the repaid borrow shares are derived from it
+    SeizedAssets(U256),
+    /// Borrow shares to repay; the seized collateral is derived from them
+    RepaidShares(U256),
+}
+
+/// Outcome of [`Market::liquidate`]
+#[derive(Debug, Clone)]
+pub struct LiquidationResult {
+    /// Market after interest accrual, repayment, and bad debt realization
+    pub market: Market,
+    /// Borrower's position after the liquidation
+    pub position: Position,
+    /// Collateral transferred to the liquidator
+    pub seized_assets: U256,
+    /// Loan assets paid by the liquidator
+    pub repaid_assets: U256,
+    /// Borrow shares burned by the repayment
+    pub repaid_shares: U256,
+    /// Debt written off against suppliers (zero unless all collateral was seized)
+    pub bad_debt_assets: U256,
+    /// Borrow shares written off with the bad debt
+    pub bad_debt_shares: U256,
 }
 
 /// Internal struct for accrual rate calculation results
@@ -996,16 +1364,98 @@ pub fn borrow_apy_impact(
     })
 }
 
+/// Market state that determines its interest rates at a given timestamp
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AccrualState {
+    total_supply_assets: U256,
+    total_borrow_assets: U256,
+    last_update: u64,
+    fee: U256,
+    rate_at_target: Option<U256>,
+    irm: Irm,
+}
+
+impl AccrualState {
+    fn of(market: &Market) -> Self {
+        Self {
+            total_supply_assets: market.total_supply_assets,
+            total_borrow_assets: market.total_borrow_assets,
+            last_update: market.last_update,
+            fee: market.fee,
+            rate_at_target: market.rate_at_target,
+            irm: market.irm.clone(),
+        }
+    }
+}
+
+/// Memoized interest accrual, keyed by (market id, timestamp)
+///
+/// Vaults scanned together often allocate to the same markets, and each vault otherwise
+/// re-runs the interest rate model for every market it holds. Each entry remembers the
+/// market state it was computed from, so a market with the same id but a different state
+/// (e.g. after a simulated deposit) is recomputed rather than served stale.
+///
+/// The cache can be shared between threads and reused across calls.
+#[derive(Debug, Default)]
+pub struct AccrualCache {
+    avg_supply_rates: Mutex<HashMap<(MarketId, u64), (AccrualState, U256)>>,
+}
+
+impl AccrualCache {
+    /// Creates an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns [`Market::get_avg_supply_rate`], computing it at most once per
+    /// (market id, timestamp) and market state
+    pub fn avg_supply_rate(&self, market: &Market, timestamp: u64) -> Result<U256, SimError> {
+        let key = (market.id, timestamp);
+        let state = AccrualState::of(market);
+        if let Some((cached_state, rate)) = self.entries().get(&key) {
+            if *cached_state == state {
+                return Ok(*rate);
+            }
+        }
+
+        // Computed without holding the lock so other threads are not blocked on the IRM
+        let rate = market.get_avg_supply_rate(timestamp)?;
+        self.entries().insert(key, (state, rate));
+        Ok(rate)
+    }
+
+    /// Number of cached (market id, timestamp) entries
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    /// Returns true if nothing has been cached
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<(MarketId, u64), (AccrualState, U256)>> {
+        self.avg_supply_rates
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
 /// Ranking entry for market comparison
 #[derive(Debug, Clone)]
 pub struct MarketRanking {
     pub market_id: MarketId,
     pub apy: f64,
+    /// Exact WAD-scaled APY, used as the sort key
+    pub apy_wad: U256,
     pub liquidity: U256,
     pub utilization: U256,
 }
 
 /// Rank markets by supply APY (descending)
+///
+/// Orders by the exact WAD-scaled APY, with ties broken by market id, so the ranking
+/// does not depend on the input order or floating-point rounding.
 pub fn rank_markets_by_supply_apy(
     markets: &[(MarketId, &Market)],
     timestamp: u64,
@@ -1013,20 +1463,25 @@ pub fn rank_markets_by_supply_apy(
     let mut rankings: Vec<MarketRanking> = markets
         .iter()
         .filter_map(|(id, market)| {
-            market.get_supply_apy(timestamp).ok().map(|apy| MarketRanking {
+            let apy = market.get_supply_apy(timestamp).ok()?;
+            let apy_wad = market.get_supply_apy_wad(timestamp).ok()?;
+            Some(MarketRanking {
                 market_id: *id,
                 apy,
+                apy_wad,
                 liquidity: market.liquidity(),
                 utilization: market.utilization(),
             })
         })
         .collect();
 
-    rankings.sort_by(|a, b| b.apy.partial_cmp(&a.apy).unwrap_or(std::cmp::Ordering::Equal));
+    rankings.sort_by(|a, b| b.apy_wad.cmp(&a.apy_wad).then(a.market_id.cmp(&b.market_id)));
     Ok(rankings)
 }
 
 /// Rank markets by borrow APY (ascending - lower is better for borrowers)
+///
+/// Orders by the exact WAD-scaled APY, with ties broken by market id.
 pub fn rank_markets_by_borrow_apy(
     markets: &[(MarketId, &Market)],
     timestamp: u64,
@@ -1034,16 +1489,19 @@ pub fn rank_markets_by_borrow_apy(
     let mut rankings: Vec<MarketRanking> = markets
         .iter()
         .filter_map(|(id, market)| {
-            market.get_borrow_apy(timestamp).ok().map(|apy| MarketRanking {
+            let apy = market.get_borrow_apy(timestamp).ok()?;
+            let apy_wad = market.get_borrow_apy_wad(timestamp).ok()?;
+            Some(MarketRanking {
                 market_id: *id,
                 apy,
+                apy_wad,
                 liquidity: market.liquidity(),
                 utilization: market.utilization(),
             })
         })
         .collect();
 
-    rankings.sort_by(|a, b| a.apy.partial_cmp(&b.apy).unwrap_or(std::cmp::Ordering::Equal));
+    rankings.sort_by(|a, b| a.apy_wad.cmp(&b.apy_wad).then(a.market_id.cmp(&b.market_id)));
     Ok(rankings)
 }
 
@@ -1356,6 +1814,36 @@ mod tests {
         assert_eq!(repay2, U256::ZERO);
     }
 
+    #[test]
+    fn test_accrue_interest_fixed_rate_irm() {
+        let mut market = create_test_market();
+        // 10% per year, regardless of utilization
+        market.irm = Irm::FixedRate(crate::irm::FixedRateIrm::from_annual_rate(
+            WAD / U256::from(10),
+        ));
+        let rate = WAD / U256::from(10) / U256::from(math::SECONDS_PER_YEAR);
+
+        assert_eq!(market.get_avg_borrow_rate(1000 + 86400).unwrap(), rate);
+        assert_eq!(market.get_end_borrow_rate(1000 + 86400).unwrap(), rate);
+
+        let accrued = market.accrue_interest(1000 + 86400).unwrap();
+        let expected_interest = w_mul_down(
+            market.total_borrow_assets,
+            w_taylor_compounded(rate, U256::from(86400)),
+        );
+        assert_eq!(
+            accrued.total_borrow_assets,
+            market.total_borrow_assets + expected_interest
+        );
+        // A fixed-rate IRM keeps no adaptive state
+        assert_eq!(accrued.rate_at_target, market.rate_at_target);
+        assert_eq!(accrued.irm, market.irm);
+
+        // Utilization does not change the rate
+        let (busier, _) = market.borrow(U256::from(100_000) * WAD, 1000).unwrap();
+        assert_eq!(busier.get_avg_borrow_rate(1000 + 86400).unwrap(), rate);
+    }
+
     #[test]
     fn test_accrue_interest_no_time_elapsed() {
         let market = create_test_market();
@@ -1540,5 +2028,243 @@ mod tests {
         // Higher utilization market should have higher supply APY (more interest distributed)
         assert_eq!(rankings.len(), 2);
         assert_eq!(rankings[0].market_id, market1.id);
+        assert!(rankings[0].apy_wad > rankings[1].apy_wad);
+    }
+
+    #[test]
+    fn test_accrual_cache_reuses_matching_state() {
+        let market = create_test_market();
+        let cache = AccrualCache::new();
+        let expected = market.get_avg_supply_rate(2000).unwrap();
+
+        assert_eq!(cache.avg_supply_rate(&market, 2000).unwrap(), expected);
+        assert_eq!(cache.avg_supply_rate(&market, 2000).unwrap(), expected);
+        assert_eq!(cache.len(), 1);
+
+        // Same id, different state: recomputed instead of served from the cache
+        let (supplied, _) = market.supply(U256::from(500_000) * WAD, 1000).unwrap();
+        assert_eq!(
+            cache.avg_supply_rate(&supplied, 2000).unwrap(),
+            supplied.get_avg_supply_rate(2000).unwrap()
+        );
+        assert_ne!(cache.avg_supply_rate(&supplied, 2000).unwrap(), expected);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_rank_markets_ties_broken_by_id() {
+        let mut market_a = create_test_market();
+        market_a.id = FixedBytes::from_slice(&[7; 32]);
+        let mut market_b = market_a.clone();
+        market_b.id = FixedBytes::from_slice(&[3; 32]);
+
+        let forward = [(market_a.id, &market_a), (market_b.id, &market_b)];
+        let backward = [(market_b.id, &market_b), (market_a.id, &market_a)];
+
+        for markets in [&forward[..], &backward[..]] {
+            let rankings = rank_markets_by_supply_apy(markets, 1000).unwrap();
+            assert_eq!(rankings[0].market_id, market_b.id);
+            assert_eq!(rankings[0].apy_wad, market_a.get_supply_apy_wad(1000).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_utilization_path_above_target_ratchets_up() {
+        let market = create_test_market();
+        let high = U256::from(98) * WAD / U256::from(100);
+        let path = vec![UtilizationPoint::new(high, 86_400); 5];
+
+        let rates = market.simulate_utilization_path(&path).unwrap();
+
+        assert_eq!(rates.len(), 5);
+        assert_eq!(rates[0].timestamp, 1000 + 86_400);
+        assert_eq!(rates[4].timestamp, 1000 + 5 * 86_400);
+        for pair in rates.windows(2) {
+            assert!(pair[1].rate_at_target > pair[0].rate_at_target);
+            assert!(pair[1].borrow_apy > pair[0].borrow_apy);
+        }
+        assert!(rates[0].rate_at_target > market.rate_at_target);
+    }
+
+    #[test]
+    fn test_utilization_path_below_target_decays() {
+        let market = create_test_market();
+        let low = U256::from(50) * WAD / U256::from(100);
+        let rates = market
+            .simulate_utilization_path(&[UtilizationPoint::new(low, 7 * 86_400)])
+            .unwrap();
+
+        assert!(rates[0].rate_at_target < market.rate_at_target);
+        assert!(rates[0].supply_apy < rates[0].borrow_apy);
+    }
+
+    #[test]
+    fn test_utilization_path_sets_utilization() {
+        let market = create_test_market();
+        let target = U256::from(95) * WAD / U256::from(100);
+        let rates = market
+            .simulate_utilization_path(&[
+                UtilizationPoint::new(target, 0),
+                UtilizationPoint::new(U256::from(2) * WAD, 0),
+            ])
+            .unwrap();
+
+        assert_eq!(rates[0].utilization, target);
+        assert_eq!(rates[0].rate_at_target, market.rate_at_target);
+        // Capped at 100%
+        assert_eq!(rates[1].utilization, WAD);
+    }
+
+    #[test]
+    fn test_check_invariants() {
+        let market = create_test_market();
+        assert!(market.check_invariants().is_ok());
+
+        let (supplied, _) = market.supply(U256::from(10_000) * WAD, 2000).unwrap();
+        let (borrowed, _) = supplied.borrow(U256::from(5_000) * WAD, 3000).unwrap();
+        assert!(borrowed.check_invariants().is_ok());
+
+        let mut over_borrowed = market.clone();
+        over_borrowed.total_borrow_assets = market.total_supply_assets + U256::from(1);
+        assert!(matches!(
+            over_borrowed.check_invariants(),
+            Err(SimError::BorrowExceedsSupply { .. })
+        ));
+
+        let mut diluted = market.clone();
+        diluted.total_supply_shares =
+            market.total_supply_assets * math::VIRTUAL_SHARES + U256::from(1);
+        assert!(matches!(
+            diluted.check_invariants(),
+            Err(SimError::ExcessShares { .. })
+        ));
+    }
+
+    fn underwater_position() -> Position {
+        // 900 debt against 1000 collateral at 80% LLTV
+        Position::new(
+            alloy_primitives::Address::ZERO,
+            FixedBytes::ZERO,
+            U256::ZERO,
+            U256::from(900) * WAD,
+            U256::from(1000) * WAD,
+        )
+    }
+
+    #[test]
+    fn test_liquidate_repaid_shares() {
+        let market = create_test_market_with_oracle();
+        let position = underwater_position();
+        let repaid_shares = U256::from(100) * WAD;
+
+        let result = market
+            .liquidate(
+                &position,
+                LiquidationAmount::RepaidShares(repaid_shares),
+                1000,
+            )
+            .unwrap();
+
+        // Seized collateral includes the liquidation incentive
+        let expected_seized = w_mul_down(
+            market.to_borrow_assets(repaid_shares, RoundingDirection::Down),
+            market.get_liquidation_incentive_factor(),
+        );
+        assert_eq!(result.seized_assets, expected_seized);
+        assert!(result.seized_assets > result.repaid_assets);
+        assert_eq!(result.repaid_shares, repaid_shares);
+        assert_eq!(
+            result.position.borrow_shares,
+            position.borrow_shares - repaid_shares
+        );
+        assert_eq!(
+            result.position.collateral,
+            position.collateral - expected_seized
+        );
+        assert!(result.bad_debt_assets.is_zero());
+        assert_eq!(
+            result.market.total_borrow_assets,
+            market.total_borrow_assets - result.repaid_assets
+        );
+        assert_eq!(
+            result.market.total_supply_assets,
+            market.total_supply_assets
+        );
+        assert!(result.market.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn test_liquidate_realizes_bad_debt() {
+        let mut market = create_test_market_with_oracle();
+        market.price = Some(ORACLE_PRICE_SCALE / U256::from(2));
+        let position = underwater_position();
+
+        let result = market
+            .liquidate(
+                &position,
+                LiquidationAmount::SeizedAssets(position.collateral),
+                1000,
+            )
+            .unwrap();
+
+        assert_eq!(result.seized_assets, position.collateral);
+        assert!(result.position.collateral.is_zero());
+        assert!(result.position.borrow_shares.is_zero());
+        assert_eq!(
+            result.repaid_shares + result.bad_debt_shares,
+            position.borrow_shares
+        );
+        assert!(result.bad_debt_assets > U256::ZERO);
+
+        // Suppliers absorb the bad debt
+        assert_eq!(
+            result.market.total_supply_assets,
+            market.total_supply_assets - result.bad_debt_assets
+        );
+        assert_eq!(
+            result.market.total_borrow_shares,
+            market.total_borrow_shares - position.borrow_shares
+        );
+        assert!(result.market.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn test_liquidate_errors() {
+        let market = create_test_market_with_oracle();
+        let position = underwater_position();
+
+        let healthy = Position::new(
+            position.user,
+            position.market_id,
+            U256::ZERO,
+            U256::from(500) * WAD,
+            position.collateral,
+        );
+        assert!(matches!(
+            market.liquidate(
+                &healthy,
+                LiquidationAmount::RepaidShares(U256::from(1)),
+                1000
+            ),
+            Err(SimError::HealthyPosition { .. })
+        ));
+
+        assert!(matches!(
+            market.liquidate(
+                &position,
+                LiquidationAmount::SeizedAssets(position.collateral + U256::from(1)),
+                1000
+            ),
+            Err(SimError::InsufficientPosition { .. })
+        ));
+
+        assert!(matches!(
+            create_test_market().liquidate(
+                &position,
+                LiquidationAmount::RepaidShares(U256::from(1)),
+                1000
+            ),
+            Err(SimError::UnknownOraclePrice { .. })
+        ));
     }
 }
@@ -35,6 +35,7 @@
 //!
 //! - [`w_taylor_compounded`]: Taylor series for e^(rate * time) - 1
 //! - [`rate_to_apy`]: Convert per-second rate to APY
+//! - [`rate_to_apy_wad`]: Convert per-second rate to a WAD-scaled APY, exactly
 //!
 //! # Rounding
 //!
@@ -61,6 +62,7 @@ pub const VIRTUAL_ASSETS: U256 = U256::from_limbs([1, 0, 0, 0]);
 
 /// Rounding direction for arithmetic operations
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RoundingDirection {
     /// Round toward zero (floor for positive numbers)
     Down,
@@ -315,6 +317,48 @@ pub fn rate_to_apy(rate: U256) -> f64 {
     annual_rate.exp_m1()
 }
 
+/// Converts a per-second rate to a WAD-scaled APY using integer arithmetic only.
+///
+/// Computes `e^(rate * SECONDS_PER_YEAR) - 1` by summing the Taylor series
+/// `x + x²/2! + x³/3! + ...` until the next term rounds down to zero, so the result is
+/// exact to within a few wei and identical on every platform. Unlike [`rate_to_apy`],
+/// it keeps full precision for very small and very large rates, which makes it suitable
+/// as a sort key. Saturates at `U256::MAX` if the APY does not fit.
+///
+/// # Example
+///
+/// ```rust
+/// use morpho_rs_sim::math::{rate_to_apy, rate_to_apy_wad, rate_to_f64};
+/// use alloy_primitives::U256;
+///
+/// let rate = U256::from(1_268_391_679u64);
+/// let apy = rate_to_apy_wad(rate);
+/// assert!((rate_to_f64(apy) - rate_to_apy(rate)).abs() < 1e-12);
+///
+/// assert_eq!(rate_to_apy_wad(U256::ZERO), U256::ZERO);
+/// ```
+pub fn rate_to_apy_wad(rate: U256) -> U256 {
+    let Some(x) = rate.checked_mul(U256::from(SECONDS_PER_YEAR)) else {
+        return U256::MAX;
+    };
+
+    let mut sum = U256::ZERO;
+    let mut term = x;
+    let mut k = 1u64;
+    while !term.is_zero() {
+        sum = match sum.checked_add(term) {
+            Some(sum) => sum,
+            None => return U256::MAX,
+        };
+        k += 1;
+        term = match term.checked_mul(x) {
+            Some(product) => product / (WAD * U256::from(k)),
+            None => return U256::MAX,
+        };
+    }
+    sum
+}
+
 /// Convert a WAD-scaled U256 to f64
 pub fn rate_to_f64(rate: U256) -> f64 {
     // Convert to string and parse as f64, then divide by WAD
@@ -397,6 +441,28 @@ mod tests {
         assert!(assets > U256::from(1990) * WAD);
     }
 
+    #[test]
+    fn test_rate_to_apy_wad_matches_f64() {
+        for annual_bps in [1u64, 300, 4_000, 50_000] {
+            let rate = U256::from(annual_bps) * WAD / U256::from(10_000 * SECONDS_PER_YEAR);
+            let exact = rate_to_f64(rate_to_apy_wad(rate));
+            let approx = rate_to_apy(rate);
+            assert!((exact - approx).abs() <= approx * 1e-9, "{exact} vs {approx}");
+        }
+    }
+
+    #[test]
+    fn test_rate_to_apy_wad_small_and_extreme_rates() {
+        assert_eq!(rate_to_apy_wad(U256::ZERO), U256::ZERO);
+        // 1 wei per second: x = SECONDS_PER_YEAR wei, higher-order terms vanish
+        assert_eq!(rate_to_apy_wad(U256::from(1)), U256::from(SECONDS_PER_YEAR));
+        // Strictly monotonic where f64 cannot tell the rates apart
+        let rate = U256::from(1_585_489_599u64);
+        assert!(rate_to_apy_wad(rate + U256::from(1)) > rate_to_apy_wad(rate));
+        assert_eq!(rate_to_apy_wad(U256::MAX), U256::MAX);
+        assert_eq!(rate_to_apy_wad(WAD), U256::MAX);
+    }
+
     // ==================== New Tests ====================
 
     #[test]
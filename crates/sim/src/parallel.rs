@@ -0,0 +1,153 @@
+//! Rayon-parallel ranking and allocation for large vault and market sets.
+//!
+//! Enabled with the `parallel` feature. Each function returns the same result as its
+//! serial counterpart in [`crate::vault`], computing per-vault and per-market rates on
+//! the rayon thread pool. Market rates are memoized in an [`AccrualCache`] shared by all
+//! threads, so a market held by many vaults goes through the interest rate model once.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_sim::parallel::par_rank_vaults_by_apy;
+//!
+//! // Hundreds of vaults across chains
+//! let sims: Vec<&VaultSimulation> = snapshots.iter().collect();
+//! let rankings = par_rank_vaults_by_apy(&sims, timestamp)?;
+//! let best = rankings.first();
+//! ```
+
+use std::collections::HashMap;
+
+use alloy_primitives::U256;
+use rayon::prelude::*;
+
+use crate::error::{MarketId, SimError};
+use crate::market::{AccrualCache, Market};
+use crate::vault::{
+    allocate_to_market, greedy_market_amounts, sort_vault_rankings, vault_ranking,
+    OptimalAllocation, VaultRanking, VaultSimulation,
+};
+
+/// Parallel [`rank_vaults_by_apy`](crate::vault::rank_vaults_by_apy)
+pub fn par_rank_vaults_by_apy(
+    vaults: &[&VaultSimulation],
+    timestamp: u64,
+) -> Result<Vec<VaultRanking>, SimError> {
+    par_rank_vaults_by_apy_cached(vaults, timestamp, &AccrualCache::new())
+}
+
+/// [`par_rank_vaults_by_apy`] with market rates looked up in, and added to, `cache`
+///
+/// Reusing the cache across calls at the same timestamp (e.g. ranking several vault
+/// subsets) skips markets already computed.
+pub fn par_rank_vaults_by_apy_cached(
+    vaults: &[&VaultSimulation],
+    timestamp: u64,
+    cache: &AccrualCache,
+) -> Result<Vec<VaultRanking>, SimError> {
+    let mut rankings: Vec<VaultRanking> = vaults
+        .par_iter()
+        .filter_map(|sim| vault_ranking(sim, timestamp, cache))
+        .collect();
+
+    sort_vault_rankings(&mut rankings);
+    Ok(rankings)
+}
+
+/// Parallel [`find_optimal_market_allocation`](crate::vault::find_optimal_market_allocation)
+///
+/// Market APYs and the post-allocation simulations run in parallel; the greedy split
+/// itself is sequential.
+pub fn par_find_optimal_market_allocation(
+    markets: &[(MarketId, &Market)],
+    total_amount: U256,
+    caps: &HashMap<MarketId, U256>,
+    timestamp: u64,
+) -> Result<Vec<OptimalAllocation>, SimError> {
+    let apys = markets
+        .par_iter()
+        .map(|(_, market)| market.get_supply_apy_wad(timestamp).ok())
+        .collect();
+
+    greedy_market_amounts(markets, apys, total_amount, caps)
+        .into_par_iter()
+        .map(|(market_id, market, amount)| allocate_to_market(market_id, market, amount, timestamp))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::{
+        find_optimal_market_allocation, rank_vaults_by_apy, Vault, VaultMarketConfig,
+    };
+    use crate::WAD;
+    use alloy_primitives::{Address, FixedBytes};
+
+    fn market(id: u8, utilization_pct: u64) -> Market {
+        Market::builder(FixedBytes::repeat_byte(id))
+            .with_supply_assets(U256::from(1_000_000) * WAD)
+            .with_borrow_assets(U256::from(10_000 * utilization_pct) * WAD)
+            .with_rate_at_target(U256::from(1_268_391_679u64))
+            .with_last_update(1000)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_par_rank_matches_serial() {
+        let markets: Vec<Market> = (1..=8).map(|i| market(i, 40 + u64::from(i) * 5)).collect();
+        let sims: Vec<VaultSimulation> = (0..64u8)
+            .map(|v| {
+                let mut builder = Vault::builder(Address::repeat_byte(v))
+                    .with_total_supply(U256::from(3_000) * WAD);
+                let mut held = HashMap::new();
+                for offset in 0..3 {
+                    let market = &markets[usize::from((v + offset) % 8)];
+                    builder = builder.with_market(VaultMarketConfig {
+                        market_id: market.id,
+                        cap: U256::MAX,
+                        supply_assets: U256::from(1_000 + u64::from(v)) * WAD,
+                        enabled: true,
+                        public_allocator_config: None,
+                    });
+                    held.insert(market.id, market.clone());
+                }
+                VaultSimulation::new(builder.build().unwrap(), held)
+            })
+            .collect();
+        let refs: Vec<&VaultSimulation> = sims.iter().collect();
+
+        let cache = AccrualCache::new();
+        let parallel = par_rank_vaults_by_apy_cached(&refs, 2000, &cache).unwrap();
+        let serial = rank_vaults_by_apy(&refs, 2000).unwrap();
+
+        assert_eq!(cache.len(), 8);
+        assert_eq!(parallel.len(), 64);
+        for (p, s) in parallel.iter().zip(&serial) {
+            assert_eq!(p.vault_address, s.vault_address);
+            assert_eq!(p.net_apy_wad, s.net_apy_wad);
+        }
+    }
+
+    #[test]
+    fn test_par_allocation_matches_serial() {
+        let markets: Vec<Market> = (1..=6).map(|i| market(i, 30 + u64::from(i) * 10)).collect();
+        let pairs: Vec<(MarketId, &Market)> = markets.iter().map(|m| (m.id, m)).collect();
+        let caps: HashMap<MarketId, U256> = markets
+            .iter()
+            .map(|m| (m.id, U256::from(200_000) * WAD))
+            .collect();
+        let total = U256::from(500_000) * WAD;
+
+        let parallel = par_find_optimal_market_allocation(&pairs, total, &caps, 2000).unwrap();
+        let serial = find_optimal_market_allocation(&pairs, total, &caps, 2000).unwrap();
+
+        assert_eq!(parallel.len(), 3);
+        for (p, s) in parallel.iter().zip(&serial) {
+            assert_eq!(p.market_id, s.market_id);
+            assert_eq!(p.amount, s.amount);
+            assert_eq!(p.expected_apy.to_bits(), s.expected_apy.to_bits());
+        }
+    }
+}
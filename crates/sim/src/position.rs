@@ -62,6 +62,7 @@ use crate::math::{w_div_down, w_div_up, zero_floor_sub, RoundingDirection};
 
 /// Represents a user's position in a Morpho Blue market.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     /// The user holding this position
     pub user: Address,
@@ -391,6 +392,136 @@ impl Position {
 
         Ok((new_position, new_market, shares))
     }
+
+    /// Projects this position forward in time under a constant oracle price.
+    ///
+    /// Starting from the market's `last_update`, interest is accrued every `step_secs`
+    /// until `horizon_secs` have elapsed. Each accrual compounds the debt and lets the
+    /// Adaptive Curve IRM adapt `rate_at_target` to the (rising) utilization, so the
+    /// borrow rate drifts over the projection just as it would on-chain with no other
+    /// market activity.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The market this position is in
+    /// * `horizon_secs` - Length of the projection
+    /// * `step_secs` - Seconds between accruals (the final step may be shorter)
+    ///
+    /// # Returns
+    ///
+    /// A [`PositionProjection`] with the debt trajectory (including the starting point)
+    /// and the first timestamp at which the position becomes unhealthy, if any.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::InvalidTimeStep`] if `step_secs` is zero
+    /// - [`SimError::UnknownOraclePrice`] if the market has no oracle price
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morpho_rs_sim::{Position, Market, ORACLE_PRICE_SCALE, WAD};
+    /// use alloy_primitives::{Address, FixedBytes, U256};
+    ///
+    /// let market = Market::new_with_oracle(
+    ///     FixedBytes::ZERO,
+    ///     U256::from(1_000_000) * WAD,
+    ///     U256::from(950_000) * WAD,
+    ///     U256::from(1_000_000) * WAD,
+    ///     U256::from(950_000) * WAD,
+    ///     1000,
+    ///     U256::ZERO,
+    ///     Some(U256::from(1_268_391_679u64)),
+    ///     Some(ORACLE_PRICE_SCALE),
+    ///     U256::from(800_000_000_000_000_000u64),  // 80% LLTV
+    /// );
+    ///
+    /// // 790 debt against 1000 collateral: just below the 800 limit
+    /// let position = Position::new(
+    ///     Address::ZERO,
+    ///     FixedBytes::ZERO,
+    ///     U256::ZERO,
+    ///     U256::from(790) * WAD,
+    ///     U256::from(1000) * WAD,
+    /// );
+    ///
+    /// let projection = position.project(&market, 365 * 86_400, 86_400).unwrap();
+    ///
+    /// assert_eq!(projection.trajectory.len(), 366);
+    /// assert!(projection.unhealthy_at.is_some());
+    /// ```
+    pub fn project(
+        &self,
+        market: &Market,
+        horizon_secs: u64,
+        step_secs: u64,
+    ) -> Result<PositionProjection, SimError> {
+        if step_secs == 0 {
+            return Err(SimError::InvalidTimeStep { step_secs });
+        }
+        if market.price.is_none() {
+            return Err(SimError::UnknownOraclePrice {
+                market_id: self.market_id,
+            });
+        }
+
+        let start = market.last_update;
+        let end = start.saturating_add(horizon_secs);
+        let mut trajectory = Vec::with_capacity((horizon_secs / step_secs) as usize + 2);
+        let mut unhealthy_at = None;
+
+        let mut market = market.clone();
+        let mut now = start;
+        loop {
+            let health_factor = self
+                .health_factor(&market)
+                .ok_or(SimError::UnknownOraclePrice {
+                    market_id: self.market_id,
+                })?;
+            if unhealthy_at.is_none() && self.is_healthy(&market) == Some(false) {
+                unhealthy_at = Some(now);
+            }
+            trajectory.push(DebtPoint {
+                timestamp: now,
+                borrow_assets: self.borrow_assets(&market),
+                health_factor,
+                rate_at_target: market.rate_at_target,
+            });
+
+            if now >= end {
+                break;
+            }
+            now = now.saturating_add(step_secs).min(end);
+            market = market.accrue_interest(now)?;
+        }
+
+        Ok(PositionProjection {
+            trajectory,
+            unhealthy_at,
+        })
+    }
+}
+
+/// A point on a projected debt trajectory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebtPoint {
+    /// Unix timestamp of this point
+    pub timestamp: u64,
+    /// Debt of the position in loan assets
+    pub borrow_assets: U256,
+    /// Health factor of the position (WAD-scaled, `U256::MAX` if no debt)
+    pub health_factor: U256,
+    /// The market's rate at target after adaptation (None for non-adaptive IRMs)
+    pub rate_at_target: Option<U256>,
+}
+
+/// Result of projecting a borrow position forward in time
+#[derive(Debug, Clone)]
+pub struct PositionProjection {
+    /// Debt trajectory, starting at the market's last update
+    pub trajectory: Vec<DebtPoint>,
+    /// First projected timestamp at which the position is unhealthy, if any
+    pub unhealthy_at: Option<u64>,
 }
 
 /// Capacity limit information
@@ -1000,4 +1131,104 @@ mod tests {
         // Withdraw should be limited by liquidity (only 500 available, less than position's 1000)
         assert_eq!(capacities2.withdraw.reason, CapacityLimitReason::Liquidity);
     }
+
+    #[test]
+    fn test_project_trajectory() {
+        let market = create_test_market();
+        let position = create_test_position();
+
+        let projection = position.project(&market, 10 * 86_400, 86_400).unwrap();
+
+        assert_eq!(projection.trajectory.len(), 11);
+        assert_eq!(projection.trajectory[0].timestamp, 1000);
+        assert_eq!(projection.trajectory[10].timestamp, 1000 + 10 * 86_400);
+        assert_eq!(
+            projection.trajectory[0].borrow_assets,
+            position.borrow_assets(&market)
+        );
+        for pair in projection.trajectory.windows(2) {
+            assert!(pair[1].borrow_assets > pair[0].borrow_assets);
+            assert!(pair[1].health_factor < pair[0].health_factor);
+        }
+        // 50% LTV stays healthy over 10 days
+        assert!(projection.unhealthy_at.is_none());
+    }
+
+    #[test]
+    fn test_project_rate_adaptation() {
+        // Utilization above target: rate at target adapts upward
+        let market = Market::new_with_oracle(
+            FixedBytes::ZERO,
+            U256::from(1_000_000) * WAD,
+            U256::from(990_000) * WAD,
+            U256::from(1_000_000) * WAD,
+            U256::from(990_000) * WAD,
+            1000,
+            U256::ZERO,
+            Some(U256::from(1_268_391_679u64)),
+            Some(ORACLE_PRICE_SCALE),
+            U256::from(800_000_000_000_000_000u64),
+        );
+        let position = create_test_position();
+
+        let projection = position.project(&market, 30 * 86_400, 86_400).unwrap();
+        let first = projection.trajectory[0].rate_at_target.unwrap();
+        let last = projection.trajectory[30].rate_at_target.unwrap();
+        assert!(last > first);
+    }
+
+    #[test]
+    fn test_project_becomes_unhealthy() {
+        let market = create_test_market();
+        // 790 debt against 800 max borrow
+        let position = Position::new(
+            Address::ZERO,
+            FixedBytes::ZERO,
+            U256::ZERO,
+            U256::from(790) * WAD,
+            U256::from(1000) * WAD,
+        );
+
+        let projection = position.project(&market, 365 * 86_400, 86_400).unwrap();
+        let unhealthy_at = projection.unhealthy_at.unwrap();
+
+        assert!(unhealthy_at > 1000);
+        let point = projection
+            .trajectory
+            .iter()
+            .find(|p| p.timestamp == unhealthy_at)
+            .unwrap();
+        assert!(point.health_factor < WAD);
+        // Every earlier point is healthy
+        assert!(projection
+            .trajectory
+            .iter()
+            .filter(|p| p.timestamp < unhealthy_at)
+            .all(|p| p.health_factor >= WAD));
+    }
+
+    #[test]
+    fn test_project_errors() {
+        let market = create_test_market();
+        let position = create_test_position();
+        assert!(matches!(
+            position.project(&market, 86_400, 0),
+            Err(SimError::InvalidTimeStep { step_secs: 0 })
+        ));
+
+        let no_oracle = Market::new(
+            FixedBytes::ZERO,
+            U256::from(1_000_000) * WAD,
+            U256::from(800_000) * WAD,
+            U256::from(1_000_000) * WAD,
+            U256::from(800_000) * WAD,
+            1000,
+            U256::ZERO,
+            None,
+        );
+        assert!(matches!(
+            position.project(&no_oracle, 86_400, 86_400),
+            Err(SimError::UnknownOraclePrice { .. })
+        ));
+    }
 }
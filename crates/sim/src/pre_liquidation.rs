@@ -0,0 +1,388 @@
+//! Pre-liquidations: borrower-opted softer liquidations ahead of the market LLTV.
+//!
+//! A borrower can authorize a pre-liquidation contract, created by Morpho's
+//! pre-liquidation factory, to partially liquidate their position once its LTV rises
+//! above a pre-liquidation LLTV (`pre_lltv`) that is lower than the market's LLTV.
+//! Instead of the market's fixed liquidation incentive and unlimited close factor, the
+//! incentive factor (`pre_lif`) and close factor (`pre_lcf`) grow linearly with the LTV
+//! between `pre_lltv` and the market LLTV:
+//!
+//! ```text
+//! quotient = (ltv - pre_lltv) / (lltv - pre_lltv)
+//! pre_lif  = pre_lif_1 + quotient * (pre_lif_2 - pre_lif_1)
+//! pre_lcf  = pre_lcf_1 + quotient * (pre_lcf_2 - pre_lcf_1)
+//! ```
+//!
+//! At most `pre_lcf` of the position's borrow shares can be repaid in one
+//! pre-liquidation. Above the market LLTV only regular [`Market::liquidate`] applies.
+//!
+//! # Example
+//!
+//! ```rust
+//! use morpho_rs_sim::{LiquidationAmount, Market, Position, PreLiquidationParams, ORACLE_PRICE_SCALE, WAD};
+//! use alloy_primitives::{Address, FixedBytes, U256};
+//!
+//! let market = Market::builder(FixedBytes::ZERO)
+//!     .with_supply_assets(U256::from(1_000_000) * WAD)
+//!     .with_borrow_assets(U256::from(800_000) * WAD)
+//!     .with_price(ORACLE_PRICE_SCALE)
+//!     .with_lltv(U256::from(860_000_000_000_000_000u64)) // 86%
+//!     .build()
+//!     .unwrap();
+//! let params = PreLiquidationParams {
+//!     pre_lltv: U256::from(800_000_000_000_000_000u64), // 80%
+//!     pre_lcf_1: U256::from(100_000_000_000_000_000u64), // 10%
+//!     pre_lcf_2: U256::from(500_000_000_000_000_000u64), // 50%
+//!     pre_lif_1: U256::from(1_010_000_000_000_000_000u64), // 1%
+//!     pre_lif_2: U256::from(1_040_000_000_000_000_000u64), // 4%
+//! };
+//!
+//! // 83% LTV: healthy, but past the pre-liquidation LLTV
+//! let position = Position::new(
+//!     Address::ZERO,
+//!     FixedBytes::ZERO,
+//!     U256::ZERO,
+//!     U256::from(830) * WAD,
+//!     U256::from(1000) * WAD,
+//! );
+//! let terms = market.get_pre_liquidation_terms(&position, &params).unwrap();
+//! assert!(terms.incentive_factor < market.get_liquidation_incentive_factor());
+//!
+//! let result = market
+//!     .pre_liquidate(&position, &params, LiquidationAmount::RepaidShares(terms.max_repaid_shares), 0)
+//!     .unwrap();
+//! assert!(result.position.borrow_shares > U256::ZERO);
+//! ```
+
+use alloy_primitives::U256;
+
+use crate::error::SimError;
+use crate::market::{LiquidationAmount, LiquidationResult, Market};
+use crate::math::{w_div_down, w_mul_down, zero_floor_sub, RoundingDirection};
+use crate::position::Position;
+
+/// Parameters of a pre-liquidation contract (all WAD-scaled)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreLiquidationParams {
+    /// LTV above which the position can be pre-liquidated
+    pub pre_lltv: U256,
+    /// Close factor at `pre_lltv`
+    pub pre_lcf_1: U256,
+    /// Close factor at the market LLTV
+    pub pre_lcf_2: U256,
+    /// Incentive factor at `pre_lltv`
+    pub pre_lif_1: U256,
+    /// Incentive factor at the market LLTV
+    pub pre_lif_2: U256,
+}
+
+/// Pre-liquidation terms of a position at its current LTV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreLiquidationTerms {
+    /// Position LTV (WAD-scaled)
+    pub ltv: U256,
+    /// Liquidation incentive factor applied to the repaid assets (WAD-scaled)
+    pub incentive_factor: U256,
+    /// Fraction of the borrow shares that can be repaid (WAD-scaled)
+    pub close_factor: U256,
+    /// Borrow shares that can be repaid in one pre-liquidation
+    pub max_repaid_shares: U256,
+}
+
+impl PreLiquidationParams {
+    /// Incentive and close factors at `ltv` in a market with `lltv`, or `None` if `ltv`
+    /// is not in `(pre_lltv, lltv]`
+    pub fn factors(&self, ltv: U256, lltv: U256) -> Option<(U256, U256)> {
+        if ltv <= self.pre_lltv || ltv > lltv {
+            return None;
+        }
+        let quotient = w_div_down(ltv - self.pre_lltv, lltv - self.pre_lltv);
+        let incentive_factor =
+            w_mul_down(quotient, zero_floor_sub(self.pre_lif_2, self.pre_lif_1)) + self.pre_lif_1;
+        let close_factor =
+            w_mul_down(quotient, zero_floor_sub(self.pre_lcf_2, self.pre_lcf_1)) + self.pre_lcf_1;
+        Some((incentive_factor, close_factor))
+    }
+}
+
+impl Market {
+    /// Returns the pre-liquidation terms of a position, or `None` if the market has no
+    /// oracle price or the position's LTV is not in `(pre_lltv, lltv]`
+    ///
+    /// Uses the position's stored borrow shares; accrue interest first to evaluate it at
+    /// a later timestamp.
+    pub fn get_pre_liquidation_terms(
+        &self,
+        position: &Position,
+        params: &PreLiquidationParams,
+    ) -> Option<PreLiquidationTerms> {
+        let ltv = self.get_ltv(position.collateral, position.borrow_shares)?;
+        let (incentive_factor, close_factor) = params.factors(ltv, self.lltv)?;
+        Some(PreLiquidationTerms {
+            ltv,
+            incentive_factor,
+            close_factor,
+            max_repaid_shares: w_mul_down(position.borrow_shares, close_factor),
+        })
+    }
+
+    /// Pre-liquidates a position, following the pre-liquidation contract's
+    /// `preLiquidate`.
+    ///
+    /// Interest is accrued first. Amounts are derived as in [`liquidate`](Self::liquidate),
+    /// with the pre-liquidation incentive factor, and the repaid shares are limited by the
+    /// close factor. Pre-liquidations never realize bad debt. The market's oracle price
+    /// is used; set it to the pre-liquidation oracle's price if the contract uses a
+    /// different oracle.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::UnknownOraclePrice`] if the market has no oracle price
+    /// - [`SimError::NotPreLiquidatable`] if the position's LTV is not in `(pre_lltv, lltv]`
+    /// - [`SimError::CloseFactorExceeded`] if more shares are repaid than the close factor
+    ///   allows
+    /// - [`SimError::InsufficientPosition`] if more collateral is seized than the position
+    ///   holds
+    pub fn pre_liquidate(
+        &self,
+        position: &Position,
+        params: &PreLiquidationParams,
+        amount: LiquidationAmount,
+        timestamp: u64,
+    ) -> Result<LiquidationResult, SimError> {
+        let price = self
+            .price
+            .ok_or(SimError::UnknownOraclePrice { market_id: self.id })?;
+        let mut market = self.accrue_interest(timestamp)?;
+
+        let terms = market
+            .get_pre_liquidation_terms(position, params)
+            .ok_or_else(|| SimError::NotPreLiquidatable {
+                user: position.user,
+                market_id: self.id,
+                ltv: market
+                    .get_ltv(position.collateral, position.borrow_shares)
+                    .map_or(u128::MAX, |ltv| ltv.saturating_to::<u128>()),
+            })?;
+
+        let (seized_assets, repaid_shares) =
+            market.liquidation_amounts(price, terms.incentive_factor, amount)?;
+        if repaid_shares > terms.max_repaid_shares {
+            return Err(SimError::CloseFactorExceeded {
+                user: position.user,
+                market_id: self.id,
+                repaid_shares: repaid_shares.saturating_to::<u128>(),
+                max_repaid_shares: terms.max_repaid_shares.saturating_to::<u128>(),
+            });
+        }
+        if seized_assets > position.collateral {
+            return Err(SimError::InsufficientPosition {
+                user: position.user,
+                market_id: self.id,
+            });
+        }
+
+        let repaid_assets = market.to_borrow_assets(repaid_shares, RoundingDirection::Up);
+
+        let mut new_position = position.clone();
+        new_position.borrow_shares -= repaid_shares;
+        new_position.collateral -= seized_assets;
+        market.total_borrow_shares -= repaid_shares;
+        market.total_borrow_assets = zero_floor_sub(market.total_borrow_assets, repaid_assets);
+
+        Ok(LiquidationResult {
+            market,
+            position: new_position,
+            seized_assets,
+            repaid_assets,
+            repaid_shares,
+            bad_debt_assets: U256::ZERO,
+            bad_debt_shares: U256::ZERO,
+        })
+    }
+}
+
+impl Position {
+    /// Check if this position can be pre-liquidated with `params`
+    ///
+    /// Returns `None` if the market has no oracle price.
+    pub fn is_pre_liquidatable(
+        &self,
+        market: &Market,
+        params: &PreLiquidationParams,
+    ) -> Option<bool> {
+        let ltv = market.get_ltv(self.collateral, self.borrow_shares)?;
+        Some(params.factors(ltv, market.lltv).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::ORACLE_PRICE_SCALE;
+    use crate::math::{VIRTUAL_SHARES, WAD};
+    use alloy_primitives::{Address, FixedBytes};
+
+    fn pct(value: u64) -> U256 {
+        U256::from(value) * WAD / U256::from(100)
+    }
+
+    /// Borrow shares worth `assets` whole tokens
+    fn shares(assets: u64) -> U256 {
+        U256::from(assets) * WAD * VIRTUAL_SHARES
+    }
+
+    fn market() -> Market {
+        Market::builder(FixedBytes::ZERO)
+            .with_supply_assets(U256::from(1_000_000) * WAD)
+            .with_borrow_assets(U256::from(800_000) * WAD)
+            .with_supply_shares(shares(1_000_000))
+            .with_borrow_shares(shares(800_000))
+            .with_price(ORACLE_PRICE_SCALE)
+            .with_lltv(pct(86))
+            .with_last_update(1000)
+            .build()
+            .unwrap()
+    }
+
+    fn params() -> PreLiquidationParams {
+        PreLiquidationParams {
+            pre_lltv: pct(80),
+            pre_lcf_1: pct(10),
+            pre_lcf_2: pct(50),
+            pre_lif_1: pct(101),
+            pre_lif_2: pct(104),
+        }
+    }
+
+    fn position(borrow: u64) -> Position {
+        Position::new(
+            Address::ZERO,
+            FixedBytes::ZERO,
+            U256::ZERO,
+            shares(borrow),
+            U256::from(1000) * WAD,
+        )
+    }
+
+    #[test]
+    fn test_factors_interpolate_between_bounds() {
+        let params = params();
+        assert_eq!(params.factors(pct(80), pct(86)), None);
+        assert_eq!(params.factors(pct(87), pct(86)), None);
+        assert_eq!(params.factors(pct(86), pct(86)), Some((pct(104), pct(50))));
+        // Halfway between pre_lltv and lltv
+        assert_eq!(
+            params.factors(pct(83), pct(86)),
+            Some((U256::from(1_025_000_000_000_000_000u64), pct(30)))
+        );
+    }
+
+    #[test]
+    fn test_pre_liquidatable_range() {
+        let market = market();
+        let params = params();
+        assert_eq!(
+            position(790).is_pre_liquidatable(&market, &params),
+            Some(false)
+        );
+        assert_eq!(
+            position(830).is_pre_liquidatable(&market, &params),
+            Some(true)
+        );
+        assert_eq!(
+            position(860).is_pre_liquidatable(&market, &params),
+            Some(true)
+        );
+        // Above the LLTV regular liquidation applies
+        assert_eq!(
+            position(870).is_pre_liquidatable(&market, &params),
+            Some(false)
+        );
+        assert_eq!(position(870).is_liquidatable(&market), Some(true));
+    }
+
+    #[test]
+    fn test_pre_liquidate_at_close_factor() {
+        let market = market();
+        let position = position(830);
+        let terms = market
+            .get_pre_liquidation_terms(&position, &params())
+            .unwrap();
+        assert_eq!(terms.ltv, pct(83));
+        assert_eq!(terms.close_factor, pct(30));
+        assert_eq!(terms.max_repaid_shares, shares(249));
+
+        let result = market
+            .pre_liquidate(
+                &position,
+                &params(),
+                LiquidationAmount::RepaidShares(terms.max_repaid_shares),
+                1000,
+            )
+            .unwrap();
+
+        // Price is 1: seized collateral is the repaid debt plus the 2.5% incentive
+        assert_eq!(result.repaid_shares, shares(249));
+        assert_eq!(
+            result.seized_assets,
+            w_mul_down(U256::from(249) * WAD, terms.incentive_factor)
+        );
+        assert_eq!(result.position.borrow_shares, shares(581));
+        assert_eq!(result.bad_debt_assets, U256::ZERO);
+        assert_eq!(
+            result.market.total_borrow_assets,
+            market.total_borrow_assets - result.repaid_assets
+        );
+        // A pre-liquidation pays less incentive than a liquidation would
+        assert!(terms.incentive_factor < market.get_liquidation_incentive_factor());
+    }
+
+    #[test]
+    fn test_pre_liquidate_errors() {
+        let market = market();
+        let params = params();
+
+        let healthy = position(790);
+        assert!(matches!(
+            market.pre_liquidate(&healthy, &params, LiquidationAmount::RepaidShares(WAD), 1000),
+            Err(SimError::NotPreLiquidatable { ltv, .. }) if U256::from(ltv) == pct(79)
+        ));
+
+        let underwater = position(870);
+        assert!(matches!(
+            market.pre_liquidate(
+                &underwater,
+                &params,
+                LiquidationAmount::RepaidShares(WAD),
+                1000
+            ),
+            Err(SimError::NotPreLiquidatable { .. })
+        ));
+
+        let position = position(830);
+        assert!(matches!(
+            market.pre_liquidate(
+                &position,
+                &params,
+                LiquidationAmount::RepaidShares(shares(250)),
+                1000
+            ),
+            Err(SimError::CloseFactorExceeded { max_repaid_shares, .. })
+                if U256::from(max_repaid_shares) == shares(249)
+        ));
+
+        let mut no_price = market.clone();
+        no_price.price = None;
+        assert!(matches!(
+            no_price.pre_liquidate(
+                &position,
+                &params,
+                LiquidationAmount::RepaidShares(WAD),
+                1000
+            ),
+            Err(SimError::UnknownOraclePrice { .. })
+        ));
+    }
+}
@@ -0,0 +1,383 @@
+//! Projected earnings for vault deposits.
+//!
+//! This module projects how a deposit into a MetaMorpho vault grows over time. The
+//! projection starts from the vault state *after* the deposit, so the dilution of
+//! market supply rates caused by the deposit itself is included, and then steps the
+//! simulation forward: each step accrues interest on every market, which moves
+//! utilization and lets the Adaptive Curve IRM adapt `rate_at_target`. The vault's net
+//! rate (after performance fee) is re-evaluated at every step.
+//!
+//! # Compounding
+//!
+//! The depositor's balance is tracked separately from the vault state so the
+//! compounding assumption can be chosen independently of the curve resolution:
+//!
+//! - [`Compounding::Periodic`]: Earned interest is added to the principal every
+//!   `interval_secs` (e.g. [`Compounding::daily`]), including at boundaries that fall
+//!   inside a step, where the step's rate applies on both sides
+//! - [`Compounding::Simple`]: Interest is earned on the original deposit only
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_sim::{project_deposit, Compounding, WAD};
+//!
+//! // What will 10k be worth in 90 days, compounding daily?
+//! let curve = project_deposit(
+//!     &simulation,
+//!     U256::from(10_000) * WAD,
+//!     timestamp,
+//!     90 * 86_400,
+//!     86_400,
+//!     Compounding::daily(),
+//! )?;
+//!
+//! let (end, assets) = curve.last().unwrap();
+//! println!("Balance at {}: {}", end, assets);
+//! ```
+
+use alloy_primitives::U256;
+
+use crate::error::SimError;
+use crate::math::w_mul_down;
+use crate::vault::VaultSimulation;
+
+/// Seconds in one hour
+const SECONDS_PER_HOUR: u64 = 60 * 60;
+
+/// Seconds in one day
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+/// How earned interest is reinvested over a projection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compounding {
+    /// Earned interest is added to the principal every `interval_secs`.
+    Periodic {
+        /// Seconds between compounding events
+        interval_secs: u64,
+    },
+    /// Interest is earned on the original deposit only and never reinvested.
+    Simple,
+}
+
+impl Compounding {
+    /// Compound every hour.
+    pub fn hourly() -> Self {
+        Compounding::Periodic {
+            interval_secs: SECONDS_PER_HOUR,
+        }
+    }
+
+    /// Compound every day.
+    pub fn daily() -> Self {
+        Compounding::Periodic {
+            interval_secs: SECONDS_PER_DAY,
+        }
+    }
+
+    /// Compound every week.
+    pub fn weekly() -> Self {
+        Compounding::Periodic {
+            interval_secs: 7 * SECONDS_PER_DAY,
+        }
+    }
+}
+
+/// Projects the balance of a vault deposit over time.
+///
+/// The deposit is simulated at `timestamp`, then the vault is stepped forward every
+/// `step_secs` until `timestamp + horizon_secs`. At each step the depositor earns the
+/// vault's average supply rate net of performance and management fees (the rate behind
+/// [`VaultSimulation::get_net_apy`]), as it drifts with market utilization and IRM rate
+/// adaptation.
+///
+/// # Arguments
+///
+/// * `simulation` - The vault simulation state before the deposit
+/// * `deposit` - Amount of assets to deposit (WAD-scaled)
+/// * `timestamp` - Deposit Unix timestamp
+/// * `horizon_secs` - Length of the projection
+/// * `step_secs` - Curve resolution (the final step may be shorter)
+/// * `compounding` - How earned interest is reinvested
+///
+/// # Returns
+///
+/// The projected balance curve as `(timestamp, assets)` points, starting with
+/// `(timestamp, deposit)` and ending at `timestamp + horizon_secs`.
+///
+/// # Errors
+///
+/// - [`SimError::InvalidTimeStep`] if `step_secs` is zero
+/// - [`SimError::InvalidCompoundingInterval`] if the compounding interval is zero
+/// - [`SimError::AllCapsReached`] if the deposit exceeds the vault's market caps
+/// - Interest accrual errors from underlying markets
+pub fn project_deposit(
+    simulation: &VaultSimulation,
+    deposit: U256,
+    timestamp: u64,
+    horizon_secs: u64,
+    step_secs: u64,
+    compounding: Compounding,
+) -> Result<Vec<(u64, U256)>, SimError> {
+    if step_secs == 0 {
+        return Err(SimError::InvalidTimeStep { step_secs });
+    }
+    if let Compounding::Periodic { interval_secs: 0 } = compounding {
+        return Err(SimError::InvalidCompoundingInterval { interval_secs: 0 });
+    }
+
+    let (mut sim, _) = simulation.simulate_deposit(deposit, timestamp)?;
+
+    let end = timestamp.saturating_add(horizon_secs);
+    let mut curve = Vec::with_capacity((horizon_secs / step_secs) as usize + 2);
+    curve.push((timestamp, deposit));
+
+    let mut principal = deposit;
+    let mut pending = U256::ZERO;
+    let mut last_compound = timestamp;
+    let mut now = timestamp;
+
+    while now < end {
+        let next = now.saturating_add(step_secs).min(end);
+        let net_rate = sim.net_supply_rate(sim.get_avg_supply_rate(next)?);
+
+        // Earn up to each compounding boundary within the step
+        let mut earned_until = now;
+        while earned_until < next {
+            let until = match compounding {
+                Compounding::Periodic { interval_secs } => {
+                    last_compound.saturating_add(interval_secs).min(next)
+                }
+                Compounding::Simple => next,
+            };
+            pending += w_mul_down(principal, net_rate * U256::from(until - earned_until));
+            if let Compounding::Periodic { interval_secs } = compounding {
+                if until - last_compound >= interval_secs {
+                    principal += pending;
+                    pending = U256::ZERO;
+                    last_compound = until;
+                }
+            }
+            earned_until = until;
+        }
+
+        sim.accrue_interest_mut(next)?;
+        now = next;
+        curve.push((now, principal + pending));
+    }
+
+    Ok(curve)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MarketId;
+    use crate::market::Market;
+    use crate::math::WAD;
+    use crate::vault::{Vault, VaultMarketConfig};
+    use alloy_primitives::{Address, FixedBytes};
+    use std::collections::HashMap;
+
+    const DAY: u64 = 86_400;
+
+    fn create_test_simulation(total_supply: u64, total_borrow: u64) -> VaultSimulation {
+        let market_id: MarketId = FixedBytes::from([1; 32]);
+        let market = Market::new(
+            market_id,
+            U256::from(total_supply) * WAD,
+            U256::from(total_borrow) * WAD,
+            U256::from(total_supply) * WAD,
+            U256::from(total_borrow) * WAD,
+            1000,
+            U256::ZERO,
+            Some(U256::from(1_268_391_679u64)),
+        );
+
+        let mut markets = HashMap::new();
+        markets.insert(market_id, market);
+
+        let mut allocations = HashMap::new();
+        allocations.insert(
+            market_id,
+            VaultMarketConfig {
+                market_id,
+                cap: U256::from(10_000_000) * WAD,
+                supply_assets: U256::from(total_supply / 2) * WAD,
+                enabled: true,
+                public_allocator_config: None,
+            },
+        );
+
+        let vault = Vault {
+            address: Address::ZERO,
+            asset_decimals: 18,
+            fee: U256::from(100_000_000_000_000_000u64),
+            management_fee: U256::ZERO,
+            total_assets: U256::from(total_supply / 2) * WAD,
+            total_supply: U256::from(total_supply / 2) * WAD,
+            last_total_assets: U256::from(total_supply / 2) * WAD,
+            supply_queue: vec![market_id],
+            withdraw_queue: vec![market_id],
+            allocations,
+            owner: Address::ZERO,
+            public_allocator_config: None,
+        };
+
+        VaultSimulation::new(vault, markets)
+    }
+
+    #[test]
+    fn test_curve_shape() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let deposit = U256::from(10_000) * WAD;
+
+        let curve =
+            project_deposit(&sim, deposit, 1000, 10 * DAY, DAY, Compounding::daily()).unwrap();
+
+        assert_eq!(curve.len(), 11);
+        assert_eq!(curve[0], (1000, deposit));
+        assert_eq!(curve.last().unwrap().0, 1000 + 10 * DAY);
+        for pair in curve.windows(2) {
+            assert!(pair[1].0 > pair[0].0);
+            assert!(pair[1].1 > pair[0].1);
+        }
+    }
+
+    #[test]
+    fn test_partial_final_step() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let curve = project_deposit(
+            &sim,
+            U256::from(1_000) * WAD,
+            1000,
+            DAY + DAY / 2,
+            DAY,
+            Compounding::Simple,
+        )
+        .unwrap();
+
+        let timestamps: Vec<u64> = curve.iter().map(|(t, _)| *t).collect();
+        assert_eq!(timestamps, vec![1000, 1000 + DAY, 1000 + DAY + DAY / 2]);
+    }
+
+    #[test]
+    fn test_compounding_beats_simple() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let deposit = U256::from(10_000) * WAD;
+        let horizon = 365 * DAY;
+
+        let simple =
+            project_deposit(&sim, deposit, 1000, horizon, DAY, Compounding::Simple).unwrap();
+        let daily =
+            project_deposit(&sim, deposit, 1000, horizon, DAY, Compounding::daily()).unwrap();
+
+        assert!(daily.last().unwrap().1 > simple.last().unwrap().1);
+    }
+
+    #[test]
+    fn test_deposit_dilutes_projection() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let horizon = 90 * DAY;
+
+        let small = U256::from(1_000) * WAD;
+        let large = U256::from(1_000_000) * WAD;
+
+        let small_curve =
+            project_deposit(&sim, small, 1000, horizon, DAY, Compounding::Simple).unwrap();
+        let large_curve =
+            project_deposit(&sim, large, 1000, horizon, DAY, Compounding::Simple).unwrap();
+
+        // A large deposit lowers utilization, so it earns a lower rate per unit
+        let small_gain = small_curve.last().unwrap().1 - small;
+        let large_gain = large_curve.last().unwrap().1 - large;
+        assert!(large_gain * small < small_gain * large);
+    }
+
+    #[test]
+    fn test_management_fee_reduces_projection() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let mut charged = sim.clone();
+        // About 1% of assets per year
+        charged.vault.management_fee = U256::from(317_097_919u64);
+        let deposit = U256::from(10_000) * WAD;
+        let horizon = 30 * DAY;
+
+        let curve =
+            project_deposit(&sim, deposit, 1000, horizon, DAY, Compounding::Simple).unwrap();
+        let charged_curve =
+            project_deposit(&charged, deposit, 1000, horizon, DAY, Compounding::Simple).unwrap();
+
+        // Each step forgoes the management fee on the principal, up to rounding
+        let forgone = curve.last().unwrap().1 - charged_curve.last().unwrap().1;
+        let expected = w_mul_down(deposit, charged.vault.management_fee * U256::from(horizon));
+        assert!(forgone.abs_diff(expected) <= U256::from(30));
+    }
+
+    #[test]
+    fn test_zero_horizon() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let deposit = U256::from(1_000) * WAD;
+        let curve = project_deposit(&sim, deposit, 1000, 0, DAY, Compounding::daily()).unwrap();
+        assert_eq!(curve, vec![(1000, deposit)]);
+    }
+
+    #[test]
+    fn test_invalid_steps() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let deposit = U256::from(1_000) * WAD;
+
+        let result = project_deposit(&sim, deposit, 1000, DAY, 0, Compounding::Simple);
+        assert!(matches!(
+            result,
+            Err(SimError::InvalidTimeStep { step_secs: 0 })
+        ));
+
+        let result = project_deposit(
+            &sim,
+            deposit,
+            1000,
+            DAY,
+            DAY,
+            Compounding::Periodic { interval_secs: 0 },
+        );
+        assert!(matches!(
+            result,
+            Err(SimError::InvalidCompoundingInterval { interval_secs: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_compounding_within_steps() {
+        let sim = create_test_simulation(1_000_000, 800_000);
+        let deposit = U256::from(10_000) * WAD;
+        let horizon = 30 * DAY;
+
+        // Compounding does not depend on the curve resolution
+        let coarse =
+            project_deposit(&sim, deposit, 1000, horizon, DAY, Compounding::hourly()).unwrap();
+        let daily =
+            project_deposit(&sim, deposit, 1000, horizon, DAY, Compounding::daily()).unwrap();
+        assert!(coarse.last().unwrap().1 > daily.last().unwrap().1);
+
+        // A 1.5 day interval compounds at 1.5, 3, ... days, not every 2 days
+        let interval = Compounding::Periodic {
+            interval_secs: DAY * 3 / 2,
+        };
+        let one_and_half =
+            project_deposit(&sim, deposit, 1000, horizon, DAY, interval).unwrap();
+        let two_days = project_deposit(
+            &sim,
+            deposit,
+            1000,
+            horizon,
+            DAY,
+            Compounding::Periodic {
+                interval_secs: 2 * DAY,
+            },
+        )
+        .unwrap();
+        assert!(one_and_half.last().unwrap().1 > two_days.last().unwrap().1);
+        assert!(one_and_half.last().unwrap().1 < daily.last().unwrap().1);
+    }
+}
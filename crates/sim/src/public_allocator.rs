@@ -0,0 +1,405 @@
+//! Public allocator route finding across vaults.
+//!
+//! The [Public Allocator](https://docs.morpho.org/public-allocator/overview) lets anyone
+//! move a vault's liquidity into a market, within flow caps set by the vault's curator,
+//! by paying the vault's flat fee per call. A borrower who needs more liquidity in a
+//! market than it has can source it from several vaults supplying to that market, each
+//! with one `reallocateTo` call withdrawing from the vault's other markets.
+//!
+//! [`find_public_allocator_route`] picks those calls: which vaults to reallocate, and
+//! how much to withdraw from each of their markets, so that the requested amount
+//! reaches the target market at the lowest total fee.
+//!
+//! Withdrawals are keyed by market ID; `reallocateTo` takes market parameters, which
+//! `morpho-rs-api` resolves from the vault's flow caps
+//! (`VaultV1::public_reallocation_withdrawals`).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_sim::find_public_allocator_route;
+//!
+//! let route = find_public_allocator_route(&vaults, market_id, amount, timestamp)?;
+//!
+//! for reallocation in &route.reallocations {
+//!     // Arguments of PublicAllocator.reallocateTo
+//!     println!("{}: {:?} (fee {})", reallocation.vault, reallocation.withdrawals, reallocation.fee);
+//! }
+//! if !route.is_complete() {
+//!     println!("only {} of {} can be sourced", route.sourced, amount);
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::{Address, U256};
+
+use crate::error::{MarketId, SimError};
+use crate::math::{self, zero_floor_sub};
+use crate::vault::VaultSimulation;
+
+/// One vault's public reallocation into the target market
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicReallocation {
+    /// Vault address
+    pub vault: Address,
+    /// Amounts withdrawn per market, sorted by market ID as `reallocateTo` and
+    /// [`VaultSimulation::simulate_public_reallocate`] expect
+    pub withdrawals: Vec<(MarketId, U256)>,
+    /// Total supplied to the target market
+    pub amount: U256,
+    /// Public allocator fee for the call (in native token)
+    pub fee: U256,
+}
+
+/// Public reallocations sourcing liquidity for a market
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicAllocatorRoute {
+    /// Market receiving the liquidity
+    pub supply_market_id: MarketId,
+    /// Reallocations, in the order vaults were chosen
+    pub reallocations: Vec<PublicReallocation>,
+    /// Total supplied to the target market
+    pub sourced: U256,
+    /// Total public allocator fees (in native token)
+    pub total_fee: U256,
+    /// Requested amount that could not be sourced
+    pub shortfall: U256,
+}
+
+impl PublicAllocatorRoute {
+    /// Returns true if the whole requested amount is sourced
+    pub fn is_complete(&self) -> bool {
+        self.shortfall.is_zero()
+    }
+}
+
+/// Finds the public reallocations that bring `amount` of liquidity into a market at the
+/// lowest total fee.
+///
+/// For each vault, the amount it can move into `supply_market_id` is bounded by the
+/// target market's flow cap (`max_in`) and supply cap room, and by what it can withdraw
+/// from its other enabled markets: their flow caps (`max_out`), the vault's supply, and
+/// the markets' liquidity. Liquidity is shared, so a market drained by one vault's
+/// withdrawals is not counted again for another vault.
+///
+/// # Algorithm
+///
+/// Vaults are chosen greedily by fee per asset moved, cheapest first (ties go to the
+/// vault moving more, then to the lower address). Within a vault, the markets with the
+/// most withdrawable liquidity are drawn from first, so each call touches as few
+/// markets as possible. Every chosen reallocation is checked with
+/// [`VaultSimulation::simulate_public_reallocate`]; vaults whose reallocation fails are
+/// skipped.
+///
+/// Like [`find_optimal_market_allocation`](crate::find_optimal_market_allocation), the
+/// greedy approach is fast but may not find the global optimum, e.g. when one
+/// expensive vault could replace several cheaper ones.
+///
+/// # Arguments
+///
+/// * `vaults` - Candidate vaults; vaults without a public allocator configuration or
+///   without the target market are ignored
+/// * `supply_market_id` - Market needing liquidity
+/// * `amount` - Liquidity to source
+/// * `timestamp` - Current Unix timestamp
+///
+/// # Errors
+///
+/// Returns an error if interest cannot be accrued on a source market.
+pub fn find_public_allocator_route(
+    vaults: &[&VaultSimulation],
+    supply_market_id: MarketId,
+    amount: U256,
+    timestamp: u64,
+) -> Result<PublicAllocatorRoute, SimError> {
+    // Liquidity left in each source market, shared across vaults
+    let mut liquidity: HashMap<MarketId, U256> = HashMap::new();
+    for sim in vaults {
+        for (market_id, market) in &sim.markets {
+            if *market_id != supply_market_id && !liquidity.contains_key(market_id) {
+                liquidity.insert(*market_id, market.accrue_interest(timestamp)?.liquidity());
+            }
+        }
+    }
+
+    let mut reallocations = Vec::new();
+    let mut used: HashSet<usize> = HashSet::new();
+    let mut remaining = amount;
+
+    while !remaining.is_zero() {
+        let mut best: Option<(usize, PublicReallocation)> = None;
+        for (i, sim) in vaults.iter().enumerate() {
+            if used.contains(&i) {
+                continue;
+            }
+            let Some(candidate) =
+                plan_vault_reallocation(sim, supply_market_id, remaining, &liquidity)
+            else {
+                continue;
+            };
+
+            let better = best.as_ref().is_none_or(|(_, best)| {
+                // fee / amount < best.fee / best.amount, compared without division
+                let cost = candidate.fee.saturating_mul(best.amount);
+                let best_cost = best.fee.saturating_mul(candidate.amount);
+                cost < best_cost
+                    || (cost == best_cost
+                        && (candidate.amount > best.amount
+                            || (candidate.amount == best.amount && candidate.vault < best.vault)))
+            });
+            if better {
+                best = Some((i, candidate));
+            }
+        }
+
+        let Some((i, reallocation)) = best else {
+            break;
+        };
+        used.insert(i);
+        if vaults[i]
+            .simulate_public_reallocate(&reallocation.withdrawals, supply_market_id, timestamp)
+            .is_err()
+        {
+            continue;
+        }
+
+        for (market_id, withdrawn) in &reallocation.withdrawals {
+            if let Some(available) = liquidity.get_mut(market_id) {
+                *available = zero_floor_sub(*available, *withdrawn);
+            }
+        }
+        remaining -= reallocation.amount;
+        reallocations.push(reallocation);
+    }
+
+    let total_fee = reallocations
+        .iter()
+        .fold(U256::ZERO, |acc, r| acc.saturating_add(r.fee));
+    Ok(PublicAllocatorRoute {
+        supply_market_id,
+        reallocations,
+        sourced: amount - remaining,
+        total_fee,
+        shortfall: remaining,
+    })
+}
+
+/// A vault's reallocation of up to `wanted` into `supply_market_id`, or `None` if it
+/// can move nothing
+fn plan_vault_reallocation(
+    sim: &VaultSimulation,
+    supply_market_id: MarketId,
+    wanted: U256,
+    liquidity: &HashMap<MarketId, U256>,
+) -> Option<PublicReallocation> {
+    let vault = &sim.vault;
+    let fee = vault.public_allocator_config.as_ref()?.fee;
+    let target = vault.allocations.get(&supply_market_id)?;
+    if !target.enabled {
+        return None;
+    }
+    let inflow = math::min(
+        target.public_allocator_config.as_ref()?.max_in,
+        zero_floor_sub(target.cap, target.supply_assets),
+    );
+
+    let mut sources: Vec<(MarketId, U256)> = vault
+        .allocations
+        .iter()
+        .filter(|(market_id, config)| **market_id != supply_market_id && config.enabled)
+        .filter_map(|(market_id, config)| {
+            let max_out = config.public_allocator_config.as_ref()?.max_out;
+            let available = math::min(
+                math::min(max_out, config.supply_assets),
+                liquidity.get(market_id).copied()?,
+            );
+            (!available.is_zero()).then_some((*market_id, available))
+        })
+        .collect();
+    // Most withdrawable first, then market ID for a deterministic order
+    sources.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut left = math::min(wanted, inflow);
+    let mut withdrawals = Vec::new();
+    for (market_id, available) in sources {
+        if left.is_zero() {
+            break;
+        }
+        let withdrawn = math::min(available, left);
+        withdrawals.push((market_id, withdrawn));
+        left -= withdrawn;
+    }
+    if withdrawals.is_empty() {
+        return None;
+    }
+
+    let amount = withdrawals
+        .iter()
+        .fold(U256::ZERO, |acc, (_, withdrawn)| acc + *withdrawn);
+    withdrawals.sort_by_key(|(market_id, _)| *market_id);
+    Some(PublicReallocation {
+        vault: vault.address,
+        withdrawals,
+        amount,
+        fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::Market;
+    use crate::math::WAD;
+    use crate::vault::{
+        PublicAllocatorConfig, PublicAllocatorMarketConfig, Vault, VaultMarketConfig,
+    };
+    use alloy_primitives::FixedBytes;
+
+    fn market(id: u8, total_supply: u64, total_borrow: u64) -> Market {
+        Market::new(
+            FixedBytes::from([id; 32]),
+            U256::from(total_supply) * WAD,
+            U256::from(total_borrow) * WAD,
+            U256::from(total_supply) * WAD,
+            U256::from(total_borrow) * WAD,
+            1000,
+            U256::from(100_000_000_000_000_000u64),
+            Some(U256::from(1_268_391_679u64)),
+        )
+    }
+
+    /// A vault supplying to the target market (id 1) and the given source markets, with
+    /// `(market id, supply, max_out)` per source and a flat public allocator `fee`
+    fn vault(address: u8, fee: u64, max_in: u64, sources: &[(u8, u64, u64)]) -> VaultSimulation {
+        let pa = |max_in: u64, max_out: u64| {
+            Some(PublicAllocatorMarketConfig {
+                max_in: U256::from(max_in) * WAD,
+                max_out: U256::from(max_out) * WAD,
+            })
+        };
+        let target_id = FixedBytes::from([1; 32]);
+        let mut allocations = HashMap::new();
+        let mut markets = HashMap::new();
+        allocations.insert(
+            target_id,
+            VaultMarketConfig {
+                market_id: target_id,
+                cap: U256::from(10_000_000) * WAD,
+                supply_assets: U256::ZERO,
+                enabled: true,
+                public_allocator_config: pa(max_in, 0),
+            },
+        );
+        markets.insert(target_id, market(1, 1_000_000, 990_000));
+
+        let mut total_assets = U256::ZERO;
+        for &(id, supply, max_out) in sources {
+            let market_id = FixedBytes::from([id; 32]);
+            allocations.insert(
+                market_id,
+                VaultMarketConfig {
+                    market_id,
+                    cap: U256::from(10_000_000) * WAD,
+                    supply_assets: U256::from(supply) * WAD,
+                    enabled: true,
+                    public_allocator_config: pa(0, max_out),
+                },
+            );
+            markets.insert(market_id, market(id, 1_000_000, 500_000));
+            total_assets += U256::from(supply) * WAD;
+        }
+
+        let mut queue: Vec<MarketId> = allocations.keys().copied().collect();
+        queue.sort();
+        VaultSimulation::new(
+            Vault {
+                address: Address::repeat_byte(address),
+                asset_decimals: 18,
+                fee: U256::ZERO,
+                management_fee: U256::ZERO,
+                total_assets,
+                total_supply: total_assets,
+                last_total_assets: total_assets,
+                supply_queue: queue.clone(),
+                withdraw_queue: queue,
+                allocations,
+                owner: Address::ZERO,
+                public_allocator_config: Some(PublicAllocatorConfig {
+                    fee: U256::from(fee),
+                    accrued_fee: U256::ZERO,
+                }),
+            },
+            markets,
+        )
+    }
+
+    #[test]
+    fn test_route_prefers_cheapest_vaults() {
+        let target = FixedBytes::from([1; 32]);
+        let cheap = vault(0xaa, 0, 50_000, &[(2, 100_000, 100_000)]);
+        let pricey = vault(0xbb, 1000, 1_000_000, &[(3, 200_000, 200_000)]);
+        let mid = vault(
+            0xcc,
+            100,
+            1_000_000,
+            &[(4, 300_000, 300_000), (5, 20_000, 20_000)],
+        );
+
+        let route = find_public_allocator_route(
+            &[&pricey, &cheap, &mid],
+            target,
+            U256::from(150_000) * WAD,
+            1000,
+        )
+        .unwrap();
+
+        assert!(route.is_complete());
+        assert_eq!(route.sourced, U256::from(150_000) * WAD);
+        assert_eq!(route.total_fee, U256::from(100));
+
+        // The free vault is capped by its 50K inflow limit, the rest comes from the
+        // cheaper of the other two, drawing on its largest market
+        let cheap_leg = &route.reallocations[0];
+        assert_eq!(cheap_leg.vault, cheap.vault.address);
+        assert_eq!(
+            cheap_leg.withdrawals,
+            vec![(FixedBytes::from([2; 32]), U256::from(50_000) * WAD)]
+        );
+        let mid_leg = &route.reallocations[1];
+        assert_eq!(mid_leg.vault, mid.vault.address);
+        assert_eq!(
+            mid_leg.withdrawals,
+            vec![(FixedBytes::from([4; 32]), U256::from(100_000) * WAD)]
+        );
+
+        // Withdrawal lists are executable
+        for (sim, leg) in [(&cheap, cheap_leg), (&mid, mid_leg)] {
+            assert!(sim
+                .simulate_public_reallocate(&leg.withdrawals, target, 1000)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_route_shares_liquidity_and_reports_shortfall() {
+        let target = FixedBytes::from([1; 32]);
+        // Both vaults draw on market 2, which has 500K of liquidity
+        let a = vault(0xaa, 0, 10_000_000, &[(2, 400_000, 400_000)]);
+        let b = vault(0xbb, 0, 10_000_000, &[(2, 400_000, 400_000)]);
+
+        let route =
+            find_public_allocator_route(&[&a, &b], target, U256::from(1_000_000) * WAD, 1000)
+                .unwrap();
+
+        assert!(!route.is_complete());
+        assert_eq!(route.sourced, U256::from(500_000) * WAD);
+        assert_eq!(route.shortfall, U256::from(500_000) * WAD);
+        assert_eq!(route.reallocations[0].amount, U256::from(400_000) * WAD);
+        assert_eq!(route.reallocations[1].amount, U256::from(100_000) * WAD);
+
+        let empty = find_public_allocator_route(&[], target, U256::from(1), 1000).unwrap();
+        assert_eq!(empty.shortfall, U256::from(1));
+        assert!(empty.reallocations.is_empty());
+    }
+}
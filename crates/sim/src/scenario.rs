@@ -0,0 +1,248 @@
+//! Price shock stress-testing for borrow positions.
+//!
+//! This module evaluates a position under a set of hypothetical collateral price moves,
+//! reusing the market's health, LTV, and liquidation math so risk dashboards do not
+//! have to re-implement it.
+//!
+//! Shocks are applied instantaneously to the oracle price (collateral priced in loan
+//! assets); market balances and the position's debt are left unchanged.
+//!
+//! # Example
+//!
+//! ```rust
+//! use morpho_rs_sim::{stress_test, Market, Position, PriceShock, ORACLE_PRICE_SCALE, WAD};
+//! use alloy_primitives::{Address, FixedBytes, U256};
+//!
+//! let market = Market::new_with_oracle(
+//!     FixedBytes::ZERO,
+//!     U256::from(1_000_000) * WAD,
+//!     U256::from(800_000) * WAD,
+//!     U256::from(1_000_000) * WAD,
+//!     U256::from(800_000) * WAD,
+//!     1000,
+//!     U256::ZERO,
+//!     Some(U256::from(1_268_391_679u64)),
+//!     Some(ORACLE_PRICE_SCALE),
+//!     U256::from(800_000_000_000_000_000u64),  // 80% LLTV
+//! );
+//!
+//! let position = Position::new(
+//!     Address::ZERO,
+//!     FixedBytes::ZERO,
+//!     U256::ZERO,
+//!     U256::from(500) * WAD,   // 500 debt
+//!     U256::from(1000) * WAD,  // 1000 collateral
+//! );
+//!
+//! let results = stress_test(
+//!     &position,
+//!     &market,
+//!     &[PriceShock::new(-0.1), PriceShock::new(-0.5)],
+//! )
+//! .unwrap();
+//!
+//! assert!(!results[0].is_liquidatable); // -10%: LTV ~56%
+//! assert!(results[1].is_liquidatable); // -50%: LTV ~100%
+//! ```
+
+use alloy_primitives::U256;
+
+use crate::error::SimError;
+use crate::market::Market;
+use crate::math::{f64_to_wad, mul_div_down, WAD};
+use crate::position::Position;
+
+/// A relative move in the collateral price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceShock {
+    /// Relative price change (e.g., -0.3 = collateral price drops 30%)
+    pub pct_change: f64,
+}
+
+impl PriceShock {
+    /// Create a price shock from a relative change
+    pub fn new(pct_change: f64) -> Self {
+        Self { pct_change }
+    }
+
+    /// Apply the shock to an oracle price (floored at zero)
+    pub fn apply(&self, price: U256) -> U256 {
+        let factor = (1.0 + self.pct_change).max(0.0);
+        mul_div_down(price, f64_to_wad(factor), WAD)
+    }
+}
+
+/// Position metrics under a single price shock
+#[derive(Debug, Clone)]
+pub struct ShockResult {
+    /// The applied shock
+    pub shock: PriceShock,
+    /// Shocked oracle price (scaled by ORACLE_PRICE_SCALE)
+    pub price: U256,
+    /// Health factor (WAD-scaled, `U256::MAX` if no debt)
+    pub health_factor: U256,
+    /// Loan-to-value (WAD-scaled, `U256::MAX` if collateral is worthless)
+    pub ltv: U256,
+    /// Whether the position can be liquidated at the shocked price
+    pub is_liquidatable: bool,
+    /// Collateral a liquidator could seize at the shocked price (zero if healthy)
+    pub seizable_collateral: U256,
+}
+
+/// Stress-tests a position against collateral price shocks.
+///
+/// Each shock is applied independently to the market's current oracle price.
+///
+/// # Arguments
+///
+/// * `position` - The position to evaluate
+/// * `market` - The market the position is in
+/// * `shocks` - Price shocks to evaluate
+///
+/// # Returns
+///
+/// One [`ShockResult`] per shock, in the same order.
+///
+/// # Errors
+///
+/// - [`SimError::UnknownOraclePrice`] if the market has no oracle price
+pub fn stress_test(
+    position: &Position,
+    market: &Market,
+    shocks: &[PriceShock],
+) -> Result<Vec<ShockResult>, SimError> {
+    let price = market.price.ok_or(SimError::UnknownOraclePrice {
+        market_id: position.market_id,
+    })?;
+
+    let unknown_price = || SimError::UnknownOraclePrice {
+        market_id: position.market_id,
+    };
+
+    shocks
+        .iter()
+        .map(|shock| {
+            let shocked_price = shock.apply(price);
+            let mut shocked = market.clone();
+            shocked.price = Some(shocked_price);
+
+            let is_liquidatable = position
+                .is_liquidatable(&shocked)
+                .ok_or_else(unknown_price)?;
+
+            Ok(ShockResult {
+                shock: *shock,
+                price: shocked_price,
+                health_factor: position.health_factor(&shocked).ok_or_else(unknown_price)?,
+                ltv: position.ltv(&shocked).ok_or_else(unknown_price)?,
+                is_liquidatable,
+                seizable_collateral: position
+                    .seizable_collateral(&shocked)
+                    .ok_or_else(unknown_price)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::ORACLE_PRICE_SCALE;
+    use alloy_primitives::{Address, FixedBytes};
+
+    fn create_test_market(price: Option<U256>) -> Market {
+        Market::new_with_oracle(
+            FixedBytes::ZERO,
+            U256::from(1_000_000) * WAD,
+            U256::from(800_000) * WAD,
+            U256::from(1_000_000) * WAD,
+            U256::from(800_000) * WAD,
+            1000,
+            U256::ZERO,
+            Some(U256::from(1_268_391_679u64)),
+            price,
+            U256::from(800_000_000_000_000_000u64), // 80% LLTV
+        )
+    }
+
+    fn create_test_position() -> Position {
+        Position::new(
+            Address::ZERO,
+            FixedBytes::ZERO,
+            U256::ZERO,
+            U256::from(500) * WAD,  // 500 debt
+            U256::from(1000) * WAD, // 1000 collateral
+        )
+    }
+
+    #[test]
+    fn test_shock_apply() {
+        let price = ORACLE_PRICE_SCALE;
+        assert_eq!(PriceShock::new(0.0).apply(price), price);
+        assert_eq!(PriceShock::new(-0.5).apply(price), price / U256::from(2));
+        assert_eq!(PriceShock::new(-1.5).apply(price), U256::ZERO);
+    }
+
+    #[test]
+    fn test_stress_test_results() {
+        let market = create_test_market(Some(ORACLE_PRICE_SCALE));
+        let position = create_test_position();
+
+        let shocks = [
+            PriceShock::new(0.2),
+            PriceShock::new(0.0),
+            PriceShock::new(-0.3),
+            PriceShock::new(-0.5),
+        ];
+        let results = stress_test(&position, &market, &shocks).unwrap();
+        assert_eq!(results.len(), 4);
+
+        // Unshocked matches the position's own metrics
+        assert_eq!(
+            results[1].health_factor,
+            position.health_factor(&market).unwrap()
+        );
+        assert_eq!(results[1].ltv, position.ltv(&market).unwrap());
+
+        // Health degrades as the price falls
+        for pair in results.windows(2) {
+            assert!(pair[1].health_factor < pair[0].health_factor);
+            assert!(pair[1].ltv > pair[0].ltv);
+        }
+
+        // -30%: LTV ~71% < 80% LLTV
+        assert!(!results[2].is_liquidatable);
+        assert_eq!(results[2].seizable_collateral, U256::ZERO);
+
+        // -50%: LTV ~100% > 80% LLTV
+        assert!(results[3].is_liquidatable);
+        assert!(results[3].seizable_collateral > U256::ZERO);
+        assert!(results[3].seizable_collateral <= position.collateral);
+    }
+
+    #[test]
+    fn test_stress_test_no_debt() {
+        let market = create_test_market(Some(ORACLE_PRICE_SCALE));
+        let position = Position::new(
+            Address::ZERO,
+            FixedBytes::ZERO,
+            U256::ZERO,
+            U256::ZERO,
+            U256::from(1000) * WAD,
+        );
+
+        let results = stress_test(&position, &market, &[PriceShock::new(-0.9)]).unwrap();
+        assert_eq!(results[0].health_factor, U256::MAX);
+        assert_eq!(results[0].ltv, U256::ZERO);
+        assert!(!results[0].is_liquidatable);
+    }
+
+    #[test]
+    fn test_stress_test_unknown_price() {
+        let market = create_test_market(None);
+        let position = create_test_position();
+
+        let result = stress_test(&position, &market, &[PriceShock::new(-0.1)]);
+        assert!(matches!(result, Err(SimError::UnknownOraclePrice { .. })));
+    }
+}
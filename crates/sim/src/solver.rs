@@ -0,0 +1,419 @@
+//! Exact-output inverse solvers for markets.
+//!
+//! These functions answer "how much must be supplied or withdrawn to reach X?" for a
+//! single Morpho Blue market in closed form, by inverting the Adaptive Curve IRM instead
+//! of binary-searching over simulated operations.
+//!
+//! The Adaptive Curve IRM is piecewise linear in utilization `u` on either side of the
+//! target, `borrow_rate = rate_at_target * (a + b * u)`, so the supply rate
+//! `borrow_rate * u * (1 - fee)` is quadratic in `u` and the utilization for a target
+//! supply APY has a closed-form solution. That `f64` solution is then refined with an
+//! integer search on the WAD rate functions, so the utilization is exact, and converted
+//! to an amount with the market's utilization-targeting primitives.
+//!
+//! All solvers first accrue interest to `timestamp`; the returned amount reaches the
+//! target when applied at that timestamp (up to WAD rounding).
+//!
+//! # Example
+//!
+//! ```rust
+//! use morpho_rs_sim::{supply_for_target_apy, Market, WAD};
+//! use alloy_primitives::{FixedBytes, U256};
+//!
+//! let market = Market::new(
+//!     FixedBytes::ZERO,
+//!     U256::from(1_000_000) * WAD,
+//!     U256::from(900_000) * WAD,
+//!     U256::from(1_000_000) * WAD,
+//!     U256::from(900_000) * WAD,
+//!     1000,
+//!     U256::from(100_000_000_000_000_000u64),
+//!     Some(U256::from(1_268_391_679u64)),
+//! );
+//!
+//! // How much supply brings the supply APY down to 3%?
+//! let amount = supply_for_target_apy(&market, 0.03, 1000).unwrap();
+//! let (after, _) = market.supply(amount, 1000).unwrap();
+//!
+//! assert!((after.get_supply_apy(1000).unwrap() - 0.03).abs() < 1e-6);
+//! ```
+
+use alloy_primitives::U256;
+
+use crate::error::SimError;
+use crate::irm::{
+    get_borrow_rate, get_supply_for_borrow_rate, CURVE_STEEPNESS, TARGET_UTILIZATION,
+};
+use crate::market::Market;
+use crate::math::{f64_to_wad, rate_to_f64, w_mul_down, w_mul_up, SECONDS_PER_YEAR, WAD};
+
+/// Supply needed to bring the market's supply APY down to `target_apy`.
+///
+/// Returns zero if the supply APY is already at or below the target.
+///
+/// # Errors
+///
+/// - [`SimError::UnreachableTarget`] if no utilization produces `target_apy` (e.g. the
+///   target is above the APY at 100% utilization, or the market has no adaptive IRM)
+/// - [`SimError::InvalidInterestAccrual`] if `timestamp` is before the last update
+pub fn supply_for_target_apy(
+    market: &Market,
+    target_apy: f64,
+    timestamp: u64,
+) -> Result<U256, SimError> {
+    let market = market.accrue_interest(timestamp)?;
+    let utilization = utilization_for_supply_apy(&market, target_apy)?;
+    Ok(market.get_supply_to_utilization(utilization))
+}
+
+/// Withdrawal needed to raise the market's supply APY to `target_apy`.
+///
+/// Returns zero if the supply APY is already at or above the target.
+///
+/// # Errors
+///
+/// - [`SimError::UnreachableTarget`] if no utilization produces `target_apy`
+/// - [`SimError::InvalidInterestAccrual`] if `timestamp` is before the last update
+pub fn withdraw_for_target_apy(
+    market: &Market,
+    target_apy: f64,
+    timestamp: u64,
+) -> Result<U256, SimError> {
+    let market = market.accrue_interest(timestamp)?;
+    let utilization = utilization_for_supply_apy(&market, target_apy)?;
+    Ok(market.get_withdraw_to_utilization(utilization))
+}
+
+/// Supply needed to bring the market's borrow APY down to `target_borrow_apy`.
+///
+/// Returns zero if the borrow APY is already at or below the target.
+///
+/// # Errors
+///
+/// - [`SimError::UnreachableTarget`] if the target is outside the IRM curve
+///   (`rate_at_target / 4` to `rate_at_target * 4`) or the market has no adaptive IRM
+/// - [`SimError::InvalidInterestAccrual`] if `timestamp` is before the last update
+pub fn supply_for_target_borrow_apy(
+    market: &Market,
+    target_borrow_apy: f64,
+    timestamp: u64,
+) -> Result<U256, SimError> {
+    let market = market.accrue_interest(timestamp)?;
+    let unreachable = SimError::UnreachableTarget {
+        market_id: market.id,
+        target: target_borrow_apy,
+    };
+
+    let rate_at_target = match market.rate_at_target {
+        Some(rate) if !rate.is_zero() && market.irm.is_adaptive_curve() => rate,
+        _ => return Err(unreachable),
+    };
+    if target_borrow_apy <= 0.0 {
+        return Err(unreachable);
+    }
+
+    let target_rate = f64_to_wad(apy_to_rate(target_borrow_apy));
+    let max_rate = rate_at_target * CURVE_STEEPNESS / WAD;
+    let min_rate = rate_at_target * WAD / CURVE_STEEPNESS;
+    if target_rate > max_rate || target_rate <= min_rate {
+        return Err(unreachable);
+    }
+
+    let (supply, _) = get_supply_for_borrow_rate(
+        market.total_supply_assets,
+        market.total_borrow_assets,
+        target_rate,
+        rate_at_target,
+    );
+    Ok(supply)
+}
+
+/// Supply needed to bring the market's utilization down to `target_utilization`.
+///
+/// Returns zero if utilization is already at or below the target.
+///
+/// # Errors
+///
+/// - [`SimError::UnreachableTarget`] if the target is zero while the market has
+///   borrows, or above 100%
+/// - [`SimError::InvalidInterestAccrual`] if `timestamp` is before the last update
+pub fn supply_for_target_utilization(
+    market: &Market,
+    target_utilization: U256,
+    timestamp: u64,
+) -> Result<U256, SimError> {
+    let market = market.accrue_interest(timestamp)?;
+    check_utilization_target(&market, target_utilization)?;
+    Ok(market.get_supply_to_utilization(target_utilization))
+}
+
+/// Withdrawal needed to raise the market's utilization to `target_utilization`.
+///
+/// Returns zero if utilization is already at or above the target.
+///
+/// # Errors
+///
+/// - [`SimError::UnreachableTarget`] if the target is zero while the market has
+///   borrows, or above 100%
+/// - [`SimError::InvalidInterestAccrual`] if `timestamp` is before the last update
+pub fn withdraw_for_target_utilization(
+    market: &Market,
+    target_utilization: U256,
+    timestamp: u64,
+) -> Result<U256, SimError> {
+    let market = market.accrue_interest(timestamp)?;
+    check_utilization_target(&market, target_utilization)?;
+    Ok(market.get_withdraw_to_utilization(target_utilization))
+}
+
+fn check_utilization_target(market: &Market, target_utilization: U256) -> Result<(), SimError> {
+    if target_utilization > WAD
+        || (target_utilization.is_zero() && !market.total_borrow_assets.is_zero())
+    {
+        return Err(SimError::UnreachableTarget {
+            market_id: market.id,
+            target: rate_to_f64(target_utilization),
+        });
+    }
+    Ok(())
+}
+
+/// Per-second rate whose continuously compounded APY is `apy` (inverse of `rate_to_apy`)
+fn apy_to_rate(apy: f64) -> f64 {
+    apy.ln_1p() / SECONDS_PER_YEAR as f64
+}
+
+/// Utilization (WAD-scaled) at which the market's supply APY equals `target_apy`.
+///
+/// Solves `b * k * u^2 + a * k * u - s = 0` for the IRM segment containing the target,
+/// where `s` is the target supply rate and `k = rate_at_target * (1 - fee)`. The `f64`
+/// root is only an estimate; the result is the smallest WAD utilization whose supply
+/// rate, computed as [`Market::get_supply_rate`] does, reaches `s` rounded to WAD.
+fn utilization_for_supply_apy(market: &Market, target_apy: f64) -> Result<U256, SimError> {
+    let unreachable = SimError::UnreachableTarget {
+        market_id: market.id,
+        target: target_apy,
+    };
+
+    let rate_at_target = match market.rate_at_target {
+        Some(rate) if !rate.is_zero() && market.irm.is_adaptive_curve() => rate,
+        _ => return Err(unreachable),
+    };
+    if target_apy <= 0.0 || market.fee >= WAD {
+        return Err(unreachable);
+    }
+
+    let s = apy_to_rate(target_apy);
+    let k = rate_to_f64(rate_at_target) * (1.0 - rate_to_f64(market.fee));
+    let steepness = rate_to_f64(CURVE_STEEPNESS);
+    let target = rate_to_f64(TARGET_UTILIZATION);
+
+    // borrow_rate = rate_at_target * (a + b * u) on each side of the target
+    let (a, b) = if s <= k * target {
+        (1.0 / steepness, (1.0 - 1.0 / steepness) / target)
+    } else {
+        (
+            1.0 - (steepness - 1.0) * target / (1.0 - target),
+            (steepness - 1.0) / (1.0 - target),
+        )
+    };
+
+    let utilization = (-a + (a * a + 4.0 * b * s / k).sqrt()) / (2.0 * b);
+    if !utilization.is_finite() || utilization > 1.0 {
+        return Err(unreachable);
+    }
+
+    let target_rate = f64_to_wad(s);
+    let reaches = |u: U256| supply_rate_at(market, rate_at_target, u) >= target_rate;
+    let estimate = f64_to_wad(utilization).min(WAD);
+
+    // Widen a bracket around the estimate until `lo` misses and `hi` reaches the target
+    let mut lo = estimate;
+    let mut step = U256::from(1u64);
+    while !lo.is_zero() && reaches(lo) {
+        lo = lo.saturating_sub(step);
+        step <<= 1;
+    }
+    let mut hi = estimate;
+    let mut step = U256::from(1u64);
+    while !reaches(hi) {
+        if hi == WAD {
+            return Err(unreachable);
+        }
+        hi = (hi + step).min(WAD);
+        step <<= 1;
+    }
+    if reaches(lo) {
+        return Ok(lo);
+    }
+
+    while hi - lo > U256::from(1u64) {
+        let mid = (lo + hi) / U256::from(2u64);
+        if reaches(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(hi)
+}
+
+/// Supply rate at `utilization` with no elapsed time, as [`Market::get_supply_rate`]
+/// computes it for an Adaptive Curve IRM market.
+fn supply_rate_at(market: &Market, rate_at_target: U256, utilization: U256) -> U256 {
+    let borrow_rate = get_borrow_rate(utilization, rate_at_target, 0).end_borrow_rate;
+    w_mul_up(w_mul_down(borrow_rate, utilization), WAD - market.fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::irm::{FixedRateIrm, Irm};
+    use alloy_primitives::FixedBytes;
+
+    fn create_test_market(total_borrow: u64) -> Market {
+        Market::new(
+            FixedBytes::ZERO,
+            U256::from(1_000_000) * WAD,
+            U256::from(total_borrow) * WAD,
+            U256::from(1_000_000) * WAD,
+            U256::from(total_borrow) * WAD,
+            1000,
+            U256::from(100_000_000_000_000_000u64), // 10% fee
+            Some(U256::from(1_268_391_679u64)),     // ~4% rate at target
+        )
+    }
+
+    #[test]
+    fn test_supply_for_target_apy_below_target_segment() {
+        let market = create_test_market(800_000);
+        let current = market.get_supply_apy(1000).unwrap();
+        let target = current * 0.5;
+
+        let amount = supply_for_target_apy(&market, target, 1000).unwrap();
+        let (after, _) = market.supply(amount, 1000).unwrap();
+
+        assert!((after.get_supply_apy(1000).unwrap() - target).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_supply_for_target_apy_above_target_segment() {
+        let market = create_test_market(980_000);
+        let current = market.get_supply_apy(1000).unwrap();
+        let target = current * 0.8;
+
+        let amount = supply_for_target_apy(&market, target, 1000).unwrap();
+        let (after, _) = market.supply(amount, 1000).unwrap();
+
+        assert!((after.get_supply_apy(1000).unwrap() - target).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_utilization_for_supply_apy_is_exact() {
+        for (total_borrow, factor) in [(800_000, 0.5), (980_000, 0.8), (800_000, 1.5)] {
+            let market = create_test_market(total_borrow);
+            let rate_at_target = market.rate_at_target.unwrap();
+            let target = market.get_supply_apy(1000).unwrap() * factor;
+            let target_rate = f64_to_wad(apy_to_rate(target));
+
+            let u = utilization_for_supply_apy(&market, target).unwrap();
+            assert!(supply_rate_at(&market, rate_at_target, u) >= target_rate);
+            assert!(supply_rate_at(&market, rate_at_target, u - U256::from(1u64)) < target_rate);
+        }
+    }
+
+    #[test]
+    fn test_supply_for_target_apy_already_below() {
+        let market = create_test_market(800_000);
+        let current = market.get_supply_apy(1000).unwrap();
+        assert_eq!(
+            supply_for_target_apy(&market, current * 2.0, 1000).unwrap(),
+            U256::ZERO
+        );
+    }
+
+    #[test]
+    fn test_withdraw_for_target_apy() {
+        let market = create_test_market(800_000);
+        let current = market.get_supply_apy(1000).unwrap();
+        let target = current * 1.5;
+
+        let amount = withdraw_for_target_apy(&market, target, 1000).unwrap();
+        let (after, _) = market.withdraw(amount, 1000).unwrap();
+
+        assert!((after.get_supply_apy(1000).unwrap() - target).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unreachable_apy() {
+        let market = create_test_market(800_000);
+        // Max supply APY at 100% utilization with a ~4% rate at target is ~15%
+        assert!(matches!(
+            withdraw_for_target_apy(&market, 0.5, 1000),
+            Err(SimError::UnreachableTarget { .. })
+        ));
+        assert!(matches!(
+            supply_for_target_apy(&market, 0.0, 1000),
+            Err(SimError::UnreachableTarget { .. })
+        ));
+
+        let mut no_irm = market.clone();
+        no_irm.rate_at_target = None;
+        assert!(matches!(
+            supply_for_target_apy(&no_irm, 0.01, 1000),
+            Err(SimError::UnreachableTarget { .. })
+        ));
+
+        let mut fixed = market.clone();
+        fixed.irm = Irm::FixedRate(FixedRateIrm::from_annual_rate(WAD / U256::from(20)));
+        assert!(matches!(
+            supply_for_target_apy(&fixed, 0.01, 1000),
+            Err(SimError::UnreachableTarget { .. })
+        ));
+    }
+
+    #[test]
+    fn test_supply_for_target_borrow_apy() {
+        let market = create_test_market(950_000);
+        let current = market.get_borrow_apy(1000).unwrap();
+        let target = current * 0.7;
+
+        let amount = supply_for_target_borrow_apy(&market, target, 1000).unwrap();
+        let (after, _) = market.supply(amount, 1000).unwrap();
+
+        assert!((after.get_borrow_apy(1000).unwrap() - target).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_supply_for_target_borrow_apy_out_of_curve() {
+        let market = create_test_market(800_000);
+        assert!(matches!(
+            supply_for_target_borrow_apy(&market, 0.5, 1000),
+            Err(SimError::UnreachableTarget { .. })
+        ));
+    }
+
+    #[test]
+    fn test_utilization_targets() {
+        let market = create_test_market(800_000);
+        let half = WAD / U256::from(2);
+        let ninety = U256::from(9) * WAD / U256::from(10);
+
+        let supply = supply_for_target_utilization(&market, half, 1000).unwrap();
+        assert_eq!(supply, U256::from(600_000) * WAD);
+
+        let withdraw = withdraw_for_target_utilization(&market, ninety, 1000).unwrap();
+        let (after, _) = market.withdraw(withdraw, 1000).unwrap();
+        assert!(after.utilization() >= ninety - U256::from(1_000));
+        assert!(after.utilization() <= ninety);
+
+        assert!(matches!(
+            withdraw_for_target_utilization(&market, U256::from(2) * WAD, 1000),
+            Err(SimError::UnreachableTarget { .. })
+        ));
+        assert!(matches!(
+            supply_for_target_utilization(&market, U256::ZERO, 1000),
+            Err(SimError::UnreachableTarget { .. })
+        ));
+    }
+}
@@ -10,7 +10,8 @@
 //! - **Diversify across markets**: Spread deposits across multiple lending markets
 //! - **Manage risk**: Set supply caps per market to limit concentration
 //! - **Optimize yield**: Allocators can rebalance to maximize returns
-//! - **Charge fees**: Performance fees taken from accrued interest
+//! - **Charge fees**: Performance fees taken from accrued interest, plus an optional
+//!   time-based management fee on total assets
 //!
 //! # Key Concepts
 //!
@@ -33,22 +34,29 @@
 //! println!("Net APY: {:.2}% -> {:.2}%", impact.apy_before * 100.0, impact.apy_after * 100.0);
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 use alloy_primitives::{Address, U256};
 
 use crate::error::{MarketId, SimError};
-use crate::market::Market;
+use crate::market::{AccrualCache, LiquidationAmount, Market};
 use crate::math::{
-    self, mul_div, mul_div_down, rate_to_apy, w_mul_down, zero_floor_sub,
+    self, mul_div, mul_div_down, rate_to_apy, rate_to_apy_wad, w_mul_down, zero_floor_sub,
     RoundingDirection, WAD,
 };
+use crate::position::Position;
+use crate::vault_v2::MAX_MANAGEMENT_FEE;
 
 /// Virtual assets constant for vault share calculations (1)
 pub const VAULT_VIRTUAL_ASSETS: U256 = U256::from_limbs([1, 0, 0, 0]);
 
+/// Maximum MetaMorpho performance fee (50%, WAD-scaled)
+pub const MAX_VAULT_FEE: U256 = U256::from_limbs([500_000_000_000_000_000, 0, 0, 0]);
+
 /// Configuration for a market within a vault
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VaultMarketConfig {
     /// The market's unique identifier
     pub market_id: MarketId,
@@ -64,6 +72,7 @@ pub struct VaultMarketConfig {
 
 /// Public allocator configuration for a specific market
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PublicAllocatorMarketConfig {
     /// Maximum assets that can flow into this market
     pub max_in: U256,
@@ -73,6 +82,7 @@ pub struct PublicAllocatorMarketConfig {
 
 /// Vault-level public allocator configuration
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PublicAllocatorConfig {
     /// Fee to use public allocator (in native token)
     pub fee: U256,
@@ -82,6 +92,7 @@ pub struct PublicAllocatorConfig {
 
 /// Represents a MetaMorpho vault state
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vault {
     /// The vault's address
     pub address: Address,
@@ -89,6 +100,8 @@ pub struct Vault {
     pub asset_decimals: u8,
     /// Performance fee (WAD-scaled)
     pub fee: U256,
+    /// Management fee as a per-second rate on total assets (WAD-scaled)
+    pub management_fee: U256,
     /// Total assets under management
     pub total_assets: U256,
     /// Total vault shares outstanding
@@ -143,6 +156,15 @@ impl Vault {
         zero_floor_sub(self.total_assets, self.last_total_assets)
     }
 
+    /// Management fee charged on the current total assets over `elapsed` seconds
+    pub fn management_fee_assets(&self, elapsed: u64) -> U256 {
+        mul_div_down(
+            self.total_assets * U256::from(elapsed),
+            self.management_fee,
+            WAD,
+        )
+    }
+
     /// Calculate the maximum deposit capacity based on market caps
     pub fn max_deposit(&self) -> U256 {
         let mut suppliable = U256::ZERO;
@@ -173,10 +195,39 @@ impl Vault {
 
         withdrawable
     }
+
+    /// Checks that both queues only reference allocated markets, each at most once.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::QueueMarketNotAllocated`] if a queue references a market without an
+    ///   allocation
+    /// - [`SimError::DuplicateQueueMarket`] if a queue lists a market more than once
+    pub fn check_queues(&self) -> Result<(), SimError> {
+        for queue in [&self.supply_queue, &self.withdraw_queue] {
+            let mut seen = HashSet::new();
+            for market_id in queue {
+                if !self.allocations.contains_key(market_id) {
+                    return Err(SimError::QueueMarketNotAllocated {
+                        vault: self.address,
+                        market_id: *market_id,
+                    });
+                }
+                if !seen.insert(market_id) {
+                    return Err(SimError::DuplicateQueueMarket {
+                        vault: self.address,
+                        market_id: *market_id,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Complete simulation state including vault and all its markets
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VaultSimulation {
     /// The vault state
     pub vault: Vault,
@@ -192,11 +243,38 @@ impl VaultSimulation {
 
     /// Accrue interest on all markets and update the vault state
     pub fn accrue_interest(&self, timestamp: u64) -> Result<VaultSimulation, SimError> {
-        let mut new_markets = HashMap::new();
-        let mut new_allocations = self.vault.allocations.clone();
-        let mut new_total_assets = U256::ZERO;
+        let mut sim = self.clone();
+        sim.accrue_interest_mut(timestamp)?;
+        Ok(sim)
+    }
+
+    /// In-place [`accrue_interest`](Self::accrue_interest)
+    ///
+    /// The simulation is left unchanged if accrual fails.
+    pub fn accrue_interest_mut(&mut self, timestamp: u64) -> Result<(), SimError> {
+        // Validate every market before touching any, and find when the vault was last
+        // synced (when its markets last accrued)
+        let mut last_update: Option<u64> = None;
+        for market_id in self.vault.allocations.keys() {
+            let market = self
+                .markets
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?;
+            if timestamp < market.last_update {
+                return Err(SimError::InvalidInterestAccrual {
+                    timestamp,
+                    last_update: market.last_update,
+                });
+            }
+            last_update = last_update.max(Some(market.last_update));
+        }
+        let last_update = last_update.unwrap_or(timestamp);
 
-        // Accrue interest on all markets and recalculate allocations
+        // Accrue interest on all markets and recalculate allocations, committing only
+        // once every market has accrued
+        let mut accrued = Vec::with_capacity(self.vault.allocations.len());
         for (market_id, config) in &self.vault.allocations {
             let market = self
                 .markets
@@ -210,43 +288,110 @@ impl VaultSimulation {
             // Recalculate the vault's supply assets in this market
             // The vault holds supply shares, convert to assets at new rate
             let supply_shares = market.to_supply_shares(config.supply_assets, RoundingDirection::Up);
-            let new_supply_assets =
+            let supply_assets =
                 accrued_market.to_supply_assets(supply_shares, RoundingDirection::Down);
+            accrued.push((*market_id, accrued_market, supply_assets));
+        }
 
-            new_allocations
-                .get_mut(market_id)
-                .ok_or(SimError::MarketNotFound { market_id: *market_id })?
-                .supply_assets = new_supply_assets;
-            new_total_assets += new_supply_assets;
-            new_markets.insert(*market_id, accrued_market);
+        let mut total_assets = U256::ZERO;
+        for (market_id, accrued_market, supply_assets) in accrued {
+            if let Some(config) = self.vault.allocations.get_mut(&market_id) {
+                config.supply_assets = supply_assets;
+            }
+            total_assets += supply_assets;
+            self.markets.insert(market_id, accrued_market);
         }
 
         // Calculate vault interest and fee
-        let mut new_vault = self.vault.clone();
-        new_vault.allocations = new_allocations;
-        new_vault.total_assets = new_total_assets;
+        let vault = &mut self.vault;
+        vault.total_assets = total_assets;
 
-        // Deduct performance fee
-        let fee_assets = w_mul_down(new_vault.total_interest(), new_vault.fee);
-        let temp_total_assets = new_vault.total_assets - fee_assets;
+        // Deduct performance fee on interest and management fee on total assets
+        let elapsed = timestamp.saturating_sub(last_update);
+        let fee_assets = (w_mul_down(vault.total_interest(), vault.fee)
+            + vault.management_fee_assets(elapsed))
+        .min(vault.total_assets);
+        let temp_total_assets = vault.total_assets - fee_assets;
 
         let fee_shares = mul_div_down(
             fee_assets,
-            new_vault.total_supply + new_vault.virtual_shares(),
+            vault.total_supply + vault.virtual_shares(),
             temp_total_assets + VAULT_VIRTUAL_ASSETS,
         );
 
-        new_vault.total_supply += fee_shares;
-        new_vault.last_total_assets = new_vault.total_assets;
+        vault.total_supply += fee_shares;
+        vault.last_total_assets = vault.total_assets;
 
-        Ok(VaultSimulation {
-            vault: new_vault,
-            markets: new_markets,
-        })
+        Ok(())
+    }
+
+    /// Restores the state saved in `checkpoint`, undoing any `*_mut` operations applied
+    /// since it was taken
+    ///
+    /// A checkpoint is a clone of the simulation (or the simulation it was cloned from).
+    /// Restoring reuses this simulation's allocations, so a solver can probe many
+    /// candidates on one scratch copy instead of cloning the vault and its markets for
+    /// every probe:
+    ///
+    /// ```rust,ignore
+    /// let mut scratch = simulation.clone();
+    /// for amount in candidates {
+    ///     scratch.rollback_to(&simulation);
+    ///     scratch.deposit_mut(amount, timestamp)?;
+    ///     println!("{}: {:.2}%", amount, scratch.get_net_apy(timestamp)? * 100.0);
+    /// }
+    /// ```
+    pub fn rollback_to(&mut self, checkpoint: &VaultSimulation) {
+        let Vault {
+            address,
+            asset_decimals,
+            fee,
+            management_fee,
+            total_assets,
+            total_supply,
+            last_total_assets,
+            supply_queue,
+            withdraw_queue,
+            allocations,
+            owner,
+            public_allocator_config,
+        } = &checkpoint.vault;
+        let vault = &mut self.vault;
+        vault.address = *address;
+        vault.asset_decimals = *asset_decimals;
+        vault.fee = *fee;
+        vault.management_fee = *management_fee;
+        vault.total_assets = *total_assets;
+        vault.total_supply = *total_supply;
+        vault.last_total_assets = *last_total_assets;
+        vault.supply_queue.clone_from(supply_queue);
+        vault.withdraw_queue.clone_from(withdraw_queue);
+        vault.allocations.clone_from(allocations);
+        vault.owner = *owner;
+        vault.public_allocator_config.clone_from(public_allocator_config);
+        self.markets.clone_from(&checkpoint.markets);
     }
 
     /// Calculate the weighted average supply rate across all allocations
     pub fn get_avg_supply_rate(&self, timestamp: u64) -> Result<U256, SimError> {
+        self.weighted_supply_rate(|market| market.get_avg_supply_rate(timestamp))
+    }
+
+    /// [`get_avg_supply_rate`](Self::get_avg_supply_rate) with market rates looked up in,
+    /// and added to, `cache`
+    pub fn get_avg_supply_rate_cached(
+        &self,
+        timestamp: u64,
+        cache: &AccrualCache,
+    ) -> Result<U256, SimError> {
+        self.weighted_supply_rate(|market| cache.avg_supply_rate(market, timestamp))
+    }
+
+    /// Supply-weighted average of `market_rate` across all allocations
+    fn weighted_supply_rate(
+        &self,
+        market_rate: impl Fn(&Market) -> Result<U256, SimError>,
+    ) -> Result<U256, SimError> {
         if self.vault.total_assets.is_zero() {
             return Ok(U256::ZERO);
         }
@@ -265,8 +410,7 @@ impl VaultSimulation {
                     market_id: *market_id,
                 })?;
 
-            let market_rate = market.get_avg_supply_rate(timestamp)?;
-            weighted_rate += market_rate * config.supply_assets;
+            weighted_rate += market_rate(market)? * config.supply_assets;
         }
 
         Ok(weighted_rate / self.vault.total_assets)
@@ -282,15 +426,264 @@ impl VaultSimulation {
         Ok(rate_to_apy(avg_rate))
     }
 
-    /// Calculate the vault's net APY (after vault fee)
+    /// Calculate the vault's net APY (after performance and management fees)
     pub fn get_net_apy(&self, timestamp: u64) -> Result<f64, SimError> {
         if self.vault.total_assets.is_zero() {
             return Ok(0.0);
         }
 
         let avg_rate = self.get_avg_supply_rate(timestamp)?;
-        let net_rate = w_mul_down(avg_rate, WAD - self.vault.fee);
-        Ok(rate_to_apy(net_rate))
+        Ok(rate_to_apy(self.net_supply_rate(avg_rate)))
+    }
+
+    /// Calculate the vault's gross APY, WAD-scaled and computed exactly
+    ///
+    /// See [`rate_to_apy_wad`]; [`get_apy`](Self::get_apy) is the f64 convenience.
+    pub fn get_apy_wad(&self, timestamp: u64) -> Result<U256, SimError> {
+        let avg_rate = self.get_avg_supply_rate(timestamp)?;
+        Ok(rate_to_apy_wad(avg_rate))
+    }
+
+    /// Calculate the vault's net APY, WAD-scaled and computed exactly
+    ///
+    /// See [`rate_to_apy_wad`]; [`get_net_apy`](Self::get_net_apy) is the f64 convenience.
+    pub fn get_net_apy_wad(&self, timestamp: u64) -> Result<U256, SimError> {
+        let avg_rate = self.get_avg_supply_rate(timestamp)?;
+        Ok(rate_to_apy_wad(self.net_supply_rate(avg_rate)))
+    }
+
+    /// Supply rate left to depositors after performance and management fees
+    pub(crate) fn net_supply_rate(&self, avg_rate: U256) -> U256 {
+        zero_floor_sub(
+            w_mul_down(avg_rate, WAD - self.vault.fee),
+            self.vault.management_fee,
+        )
+    }
+
+    /// Returns a copy of the simulation with a different performance fee.
+    ///
+    /// Useful for comparing fee revenue or net APY under alternative fee settings.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::InvalidFee`] if `fee` exceeds [`MAX_VAULT_FEE`]
+    pub fn with_fee(&self, fee: U256) -> Result<VaultSimulation, SimError> {
+        if fee > MAX_VAULT_FEE {
+            return Err(SimError::InvalidFee {
+                fee,
+                max: MAX_VAULT_FEE,
+            });
+        }
+
+        let mut sim = self.clone();
+        sim.vault.fee = fee;
+        Ok(sim)
+    }
+
+    /// Checks that the vault and its markets are internally consistent.
+    ///
+    /// Deposits, withdrawals, reallocations, and interest accrual preserve these
+    /// invariants, so a violation means the simulation was built or hydrated
+    /// incorrectly:
+    ///
+    /// - the performance fee does not exceed [`MAX_VAULT_FEE`], and the management fee
+    ///   does not exceed [`MAX_MANAGEMENT_FEE`]
+    /// - queues only reference allocated markets, each at most once
+    ///   ([`Vault::check_queues`])
+    /// - every allocation with supply is in the withdraw queue
+    /// - every allocation has a market, and every market passes
+    ///   [`Market::check_invariants`]
+    /// - allocations sum to the vault's total assets
+    ///
+    /// Allocations are checked in market ID order, so the first violation reported is
+    /// deterministic.
+    pub fn check_invariants(&self) -> Result<(), SimError> {
+        let vault = &self.vault;
+        if vault.fee > MAX_VAULT_FEE {
+            return Err(SimError::InvalidFee {
+                fee: vault.fee,
+                max: MAX_VAULT_FEE,
+            });
+        }
+        if vault.management_fee > MAX_MANAGEMENT_FEE {
+            return Err(SimError::InvalidFee {
+                fee: vault.management_fee,
+                max: MAX_MANAGEMENT_FEE,
+            });
+        }
+        vault.check_queues()?;
+
+        let mut market_ids: Vec<&MarketId> = vault.allocations.keys().collect();
+        market_ids.sort();
+        let mut allocated = U256::ZERO;
+        for market_id in market_ids {
+            let config = &vault.allocations[market_id];
+            let market = self
+                .markets
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?;
+            market.check_invariants()?;
+            if !config.supply_assets.is_zero() && !vault.withdraw_queue.contains(market_id) {
+                return Err(SimError::UnqueuedAllocation {
+                    vault: vault.address,
+                    market_id: *market_id,
+                });
+            }
+            allocated = allocated.saturating_add(config.supply_assets);
+        }
+
+        if allocated != vault.total_assets {
+            return Err(SimError::AllocationMismatch {
+                vault: vault.address,
+                allocated,
+                total_assets: vault.total_assets,
+            });
+        }
+        Ok(())
+    }
+
+    /// Lints the vault's queues for common curator misconfigurations.
+    ///
+    /// Unlike [`check_invariants`](Self::check_invariants), which stops at the first
+    /// violation, this reports every problem found:
+    ///
+    /// - queue entries without an allocation, and markets listed twice in a queue
+    /// - supply queue markets with a zero cap, which deposits skip
+    /// - enabled markets missing from the withdraw queue
+    /// - disabled allocations that hold supply but are not in the withdraw queue, so
+    ///   only a reallocation can move their assets
+    ///
+    /// Queue findings come first, in supply then withdraw queue order, followed by
+    /// allocation findings in market ID order. An empty result means the queues are
+    /// sound.
+    pub fn validate_queues(&self) -> Vec<QueueFinding> {
+        let vault = &self.vault;
+        let mut findings = Vec::new();
+
+        for (queue, market_ids) in [
+            (QueueKind::Supply, &vault.supply_queue),
+            (QueueKind::Withdraw, &vault.withdraw_queue),
+        ] {
+            let mut seen = HashSet::new();
+            for market_id in market_ids {
+                let market_id = *market_id;
+                if !seen.insert(market_id) {
+                    findings.push(QueueFinding::DuplicateMarket { queue, market_id });
+                    continue;
+                }
+                match vault.allocations.get(&market_id) {
+                    None => findings.push(QueueFinding::NotAllocated { queue, market_id }),
+                    Some(config) if queue == QueueKind::Supply && config.cap.is_zero() => {
+                        findings.push(QueueFinding::ZeroCapInSupplyQueue { market_id })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let mut market_ids: Vec<&MarketId> = vault.allocations.keys().collect();
+        market_ids.sort();
+        for market_id in market_ids {
+            if vault.withdraw_queue.contains(market_id) {
+                continue;
+            }
+            let config = &vault.allocations[market_id];
+            if config.enabled {
+                findings.push(QueueFinding::MissingFromWithdrawQueue {
+                    market_id: *market_id,
+                });
+            } else if !config.supply_assets.is_zero() {
+                findings.push(QueueFinding::UnreachableAllocation {
+                    market_id: *market_id,
+                    supply_assets: config.supply_assets,
+                });
+            }
+        }
+
+        findings
+    }
+
+    /// Projects the performance fee the vault charges over a period.
+    ///
+    /// Interest pending at `timestamp` is settled first, so only interest earned
+    /// within the horizon is counted. The vault is then stepped forward every
+    /// `step_secs` until `timestamp + horizon_secs`; each step accrues interest on
+    /// every market (letting the IRM adapt) and mints fee shares to the fee
+    /// recipient exactly as MetaMorpho does on each interaction. A shorter step
+    /// models a busier vault, where fee shares are minted more often and earn
+    /// interest themselves. A non-zero management fee is charged on total assets at
+    /// each step and included in the projected revenue.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - Start of the period (Unix timestamp)
+    /// * `horizon_secs` - Length of the period
+    /// * `step_secs` - Interval between fee accruals (the final step may be shorter)
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::InvalidTimeStep`] if `step_secs` is zero
+    /// - Interest accrual errors from underlying markets
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use alloy_primitives::U256;
+    ///
+    /// // Revenue over 30 days at the current fee vs. a 15% fee
+    /// let current = simulation.project_fee_revenue(timestamp, 30 * 86_400, 86_400)?;
+    /// let raised = simulation
+    ///     .with_fee(U256::from(150_000_000_000_000_000u64))?
+    ///     .project_fee_revenue(timestamp, 30 * 86_400, 86_400)?;
+    ///
+    /// println!("Fee shares: {} -> {}", current.fee_shares, raised.fee_shares);
+    /// ```
+    pub fn project_fee_revenue(
+        &self,
+        timestamp: u64,
+        horizon_secs: u64,
+        step_secs: u64,
+    ) -> Result<FeeRevenueProjection, SimError> {
+        if step_secs == 0 {
+            return Err(SimError::InvalidTimeStep { step_secs });
+        }
+
+        let mut sim = self.accrue_interest(timestamp)?;
+
+        let end = timestamp.saturating_add(horizon_secs);
+        let mut interest = U256::ZERO;
+        let mut fee_assets = U256::ZERO;
+        let mut fee_shares = U256::ZERO;
+        let mut now = timestamp;
+
+        while now < end {
+            let next = now.saturating_add(step_secs).min(end);
+            let assets_before = sim.vault.total_assets;
+            let supply_before = sim.vault.total_supply;
+
+            sim.accrue_interest_mut(next)?;
+
+            let step_interest = zero_floor_sub(sim.vault.total_assets, assets_before);
+            interest += step_interest;
+            fee_assets += w_mul_down(step_interest, sim.vault.fee)
+                + sim.vault.management_fee_assets(next - now);
+            fee_shares += sim.vault.total_supply - supply_before;
+            now = next;
+        }
+
+        let fee_shares_value = sim.vault.to_assets(fee_shares, RoundingDirection::Down);
+
+        Ok(FeeRevenueProjection {
+            start: timestamp,
+            end,
+            fee: sim.vault.fee,
+            interest,
+            fee_assets,
+            fee_shares,
+            fee_shares_value,
+        })
     }
 
     /// Simulates a deposit to the vault.
@@ -340,24 +733,34 @@ impl VaultSimulation {
         amount: U256,
         timestamp: u64,
     ) -> Result<(VaultSimulation, U256), SimError> {
+        let mut sim = self.clone();
+        let shares = sim.deposit_mut(amount, timestamp)?;
+        Ok((sim, shares))
+    }
+
+    /// In-place [`simulate_deposit`](Self::simulate_deposit), returning the shares minted
+    ///
+    /// On error the simulation may be partially updated; restore it with
+    /// [`rollback_to`](Self::rollback_to).
+    pub fn deposit_mut(&mut self, amount: U256, timestamp: u64) -> Result<U256, SimError> {
         // First accrue interest on all markets
-        let mut sim = self.accrue_interest(timestamp)?;
+        self.accrue_interest_mut(timestamp)?;
 
         // Calculate shares to mint
-        let shares = sim.vault.to_shares(amount, RoundingDirection::Down);
+        let shares = self.vault.to_shares(amount, RoundingDirection::Down);
 
         // Distribute deposit across supply queue
         let mut to_supply = amount;
 
-        for market_id in &sim.vault.supply_queue.clone() {
-            let config = sim
+        for i in 0..self.vault.supply_queue.len() {
+            let market_id = &self.vault.supply_queue[i];
+            let config = self
                 .vault
                 .allocations
-                .get(market_id)
+                .get_mut(market_id)
                 .ok_or(SimError::MarketNotFound {
                     market_id: *market_id,
-                })?
-                .clone();
+                })?;
 
             if config.cap.is_zero() {
                 continue;
@@ -371,20 +774,18 @@ impl VaultSimulation {
             let supply_amount = math::min(to_supply, suppliable);
 
             // Supply to the market
-            let market = sim
+            let market = self
                 .markets
-                .get(market_id)
+                .get_mut(market_id)
                 .ok_or(SimError::MarketNotFound {
                     market_id: *market_id,
                 })?;
 
             let (new_market, _) = market.supply(supply_amount, timestamp)?;
-            sim.markets.insert(*market_id, new_market);
+            *market = new_market;
 
             // Update allocation
-            let allocation = sim.vault.allocations.get_mut(market_id)
-                .ok_or(SimError::MarketNotFound { market_id: *market_id })?;
-            allocation.supply_assets += supply_amount;
+            config.supply_assets += supply_amount;
 
             to_supply -= supply_amount;
 
@@ -395,17 +796,17 @@ impl VaultSimulation {
 
         if !to_supply.is_zero() {
             return Err(SimError::AllCapsReached {
-                vault: sim.vault.address,
+                vault: self.vault.address,
                 remaining: to_supply.saturating_to::<u128>(),
             });
         }
 
         // Update vault state
-        sim.vault.total_assets += amount;
-        sim.vault.last_total_assets = sim.vault.total_assets;
-        sim.vault.total_supply += shares;
+        self.vault.total_assets += amount;
+        self.vault.last_total_assets = self.vault.total_assets;
+        self.vault.total_supply += shares;
 
-        Ok((sim, shares))
+        Ok(shares)
     }
 
     /// Simulate a withdrawal from the vault
@@ -416,28 +817,39 @@ impl VaultSimulation {
         shares: U256,
         timestamp: u64,
     ) -> Result<(VaultSimulation, U256), SimError> {
+        let mut sim = self.clone();
+        let assets = sim.withdraw_mut(shares, timestamp)?;
+        Ok((sim, assets))
+    }
+
+    /// In-place [`simulate_withdraw`](Self::simulate_withdraw), returning the assets
+    /// withdrawn
+    ///
+    /// On error the simulation may be partially updated; restore it with
+    /// [`rollback_to`](Self::rollback_to).
+    pub fn withdraw_mut(&mut self, shares: U256, timestamp: u64) -> Result<U256, SimError> {
         // First accrue interest on all markets
-        let mut sim = self.accrue_interest(timestamp)?;
+        self.accrue_interest_mut(timestamp)?;
 
         // Calculate assets to withdraw
-        let assets = sim.vault.to_assets(shares, RoundingDirection::Down);
+        let assets = self.vault.to_assets(shares, RoundingDirection::Down);
 
         // Distribute withdrawal across withdraw queue
         let mut to_withdraw = assets;
 
-        for market_id in &sim.vault.withdraw_queue.clone() {
-            let config = sim
+        for i in 0..self.vault.withdraw_queue.len() {
+            let market_id = &self.vault.withdraw_queue[i];
+            let config = self
                 .vault
                 .allocations
-                .get(market_id)
+                .get_mut(market_id)
                 .ok_or(SimError::MarketNotFound {
                     market_id: *market_id,
-                })?
-                .clone();
+                })?;
 
-            let market = sim
+            let market = self
                 .markets
-                .get(market_id)
+                .get_mut(market_id)
                 .ok_or(SimError::MarketNotFound {
                     market_id: *market_id,
                 })?;
@@ -454,12 +866,10 @@ impl VaultSimulation {
 
             // Withdraw from the market
             let (new_market, _) = market.withdraw(withdraw_amount, timestamp)?;
-            sim.markets.insert(*market_id, new_market);
+            *market = new_market;
 
             // Update allocation
-            let allocation = sim.vault.allocations.get_mut(market_id)
-                .ok_or(SimError::MarketNotFound { market_id: *market_id })?;
-            allocation.supply_assets -= withdraw_amount;
+            config.supply_assets -= withdraw_amount;
 
             to_withdraw -= withdraw_amount;
 
@@ -470,17 +880,305 @@ impl VaultSimulation {
 
         if !to_withdraw.is_zero() {
             return Err(SimError::NotEnoughLiquidity {
-                vault: sim.vault.address,
+                vault: self.vault.address,
                 remaining: to_withdraw.saturating_to::<u128>(),
             });
         }
 
         // Update vault state
-        sim.vault.total_assets -= assets;
-        sim.vault.last_total_assets = sim.vault.total_assets;
-        sim.vault.total_supply -= shares;
+        self.vault.total_assets -= assets;
+        self.vault.last_total_assets = self.vault.total_assets;
+        self.vault.total_supply -= shares;
 
-        Ok((sim, assets))
+        Ok(assets)
+    }
+
+    /// Analyzes how much of the vault can be withdrawn right now.
+    ///
+    /// After accruing interest to `timestamp`, walks the withdraw queue and splits each
+    /// market's vault supply into the part that can be withdrawn immediately (bounded by
+    /// the market's liquidity) and the part blocked by utilization (lent out to
+    /// borrowers). Markets with no supply from the vault are included with zero amounts.
+    ///
+    /// Liquidity in one market is only counted once, even if the vault supplies more
+    /// than the market's free liquidity.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let profile = simulation.withdrawal_liquidity_profile(timestamp)?;
+    ///
+    /// println!("Instantly redeemable: {:.1}%", profile.redeemable_fraction * 100.0);
+    /// for market in &profile.markets {
+    ///     println!("{:?}: {} withdrawable, {} blocked", market.market_id, market.withdrawable, market.blocked);
+    /// }
+    /// ```
+    pub fn withdrawal_liquidity_profile(
+        &self,
+        timestamp: u64,
+    ) -> Result<WithdrawalLiquidityProfile, SimError> {
+        let sim = self.accrue_interest(timestamp)?;
+
+        let mut markets = Vec::with_capacity(sim.vault.withdraw_queue.len());
+        let mut total_withdrawable = U256::ZERO;
+        let mut total_blocked = U256::ZERO;
+
+        for market_id in &sim.vault.withdraw_queue {
+            let config = sim
+                .vault
+                .allocations
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?;
+            let market = sim
+                .markets
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?;
+
+            let withdrawable = math::min(config.supply_assets, market.liquidity());
+            let blocked = config.supply_assets - withdrawable;
+
+            total_withdrawable += withdrawable;
+            total_blocked += blocked;
+            markets.push(MarketWithdrawalLiquidity {
+                market_id: *market_id,
+                supply_assets: config.supply_assets,
+                withdrawable,
+                blocked,
+            });
+        }
+
+        let redeemable_fraction = if sim.vault.total_assets.is_zero() {
+            1.0
+        } else {
+            math::rate_to_f64(math::w_div_down(total_withdrawable, sim.vault.total_assets))
+                .min(1.0)
+        };
+
+        Ok(WithdrawalLiquidityProfile {
+            markets,
+            total_withdrawable,
+            total_blocked,
+            redeemable_fraction,
+        })
+    }
+
+    /// Plans an exit of `assets` from the vault through its withdraw queue.
+    ///
+    /// After accruing interest to `timestamp`, follows the same withdraw queue walk as
+    /// [`Self::simulate_withdraw`] and reports which markets the assets come from. Instead
+    /// of failing when liquidity runs out, the remainder is attributed, in queue order, to
+    /// the vault supply that is lent out to borrowers (`blocked`); anything beyond the
+    /// vault's supply in queued markets is reported as `excess`.
+    ///
+    /// Each blocked leg carries a `wait_estimate` that assumes borrowers repay debt at a
+    /// pace set by the market's current borrow rate: a market borrowing `B` at per-second
+    /// rate `r` frees `B * r` assets per second, so freeing `x` takes `x / (B * r)`
+    /// seconds. Higher rates mean faster repayment; the estimate is a heuristic, not a
+    /// guarantee. The plan's overall estimate is the slowest leg, since markets unwind in
+    /// parallel.
+    ///
+    /// Markets the withdrawal does not need are omitted from the plan.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let plan = simulation.plan_withdrawal(assets, timestamp)?;
+    ///
+    /// if !plan.is_fully_available() {
+    ///     println!("{} blocked, wait ~{:?}s", plan.blocked, plan.wait_estimate);
+    /// }
+    /// ```
+    pub fn plan_withdrawal(
+        &self,
+        assets: U256,
+        timestamp: u64,
+    ) -> Result<WithdrawalPlan, SimError> {
+        let sim = self.accrue_interest(timestamp)?;
+
+        let mut legs: Vec<(MarketId, U256, U256, &Market)> = Vec::new();
+        let mut remaining = assets;
+
+        // Liquid supply first, as the vault itself would withdraw it
+        for market_id in &sim.vault.withdraw_queue {
+            if remaining.is_zero() {
+                break;
+            }
+            let config = sim
+                .vault
+                .allocations
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?;
+            let market = sim
+                .markets
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?;
+
+            let withdrawn = math::min(
+                remaining,
+                math::min(config.supply_assets, market.liquidity()),
+            );
+            remaining -= withdrawn;
+            legs.push((*market_id, config.supply_assets - withdrawn, withdrawn, market));
+        }
+        let available = assets - remaining;
+
+        // Then the utilization-blocked supply, again in queue order
+        let mut markets = Vec::with_capacity(legs.len());
+        let mut wait_estimate = Some(0u64);
+        for (market_id, unwithdrawn, withdrawn, market) in legs {
+            let liquid_left = math::zero_floor_sub(market.liquidity(), withdrawn);
+            let lent_out = math::zero_floor_sub(unwithdrawn, liquid_left);
+            let blocked = math::min(remaining, lent_out);
+            remaining -= blocked;
+
+            let borrow_rate = market.get_end_borrow_rate(market.last_update)?;
+            let leg_wait = if blocked.is_zero() {
+                Some(0)
+            } else {
+                repayment_wait(blocked, market.total_borrow_assets, borrow_rate)
+            };
+            wait_estimate = match (wait_estimate, leg_wait) {
+                (Some(total), Some(leg)) => Some(total.max(leg)),
+                _ => None,
+            };
+
+            if !withdrawn.is_zero() || !blocked.is_zero() {
+                markets.push(MarketWithdrawalLeg {
+                    market_id,
+                    withdrawn,
+                    blocked,
+                    borrow_rate,
+                    wait_estimate: leg_wait,
+                });
+            }
+        }
+
+        let blocked = assets - available - remaining;
+        if !remaining.is_zero() {
+            wait_estimate = None;
+        }
+
+        Ok(WithdrawalPlan {
+            requested: assets,
+            markets,
+            available,
+            blocked,
+            excess: remaining,
+            wait_estimate,
+        })
+    }
+
+    /// Estimates the bad debt the vault would absorb if unhealthy borrowers were
+    /// liquidated at the given oracle prices.
+    ///
+    /// For each market in the withdraw queue, every borrower in `borrowers` that is
+    /// unhealthy at the market's price in `prices` (or its current oracle price if absent)
+    /// has all of its collateral seized via [`Market::liquidate`]. Debt the collateral
+    /// does not cover is realized as bad debt and socialized to suppliers, so the vault
+    /// absorbs its pro-rata share of the market's supply. Each borrower is liquidated
+    /// independently against the current market state; no interest is accrued.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::MarketNotFound`] if a queued market is missing
+    /// - [`SimError::UnknownOraclePrice`] if a market with borrowers has no price
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Collateral in one market drops 40%
+    /// let mut prices = HashMap::new();
+    /// prices.insert(market_id, PriceShock::new(-0.4).apply(market.price.unwrap()));
+    ///
+    /// let exposure = simulation.bad_debt_exposure(&borrowers, &prices)?;
+    /// println!("Vault would lose {:.2}% of its assets", exposure.loss_fraction * 100.0);
+    /// ```
+    pub fn bad_debt_exposure(
+        &self,
+        borrowers: &HashMap<MarketId, Vec<Position>>,
+        prices: &HashMap<MarketId, U256>,
+    ) -> Result<BadDebtExposure, SimError> {
+        let mut markets = Vec::with_capacity(self.vault.withdraw_queue.len());
+        let mut total_bad_debt = U256::ZERO;
+
+        for market_id in &self.vault.withdraw_queue {
+            let config = self
+                .vault
+                .allocations
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?;
+            let mut market = self
+                .markets
+                .get(market_id)
+                .ok_or(SimError::MarketNotFound {
+                    market_id: *market_id,
+                })?
+                .clone();
+            if let Some(price) = prices.get(market_id) {
+                market.price = Some(*price);
+            }
+
+            let mut market_bad_debt = U256::ZERO;
+            let mut liquidatable_borrowers = 0;
+            for position in borrowers.get(market_id).into_iter().flatten() {
+                let seize_all = LiquidationAmount::SeizedAssets(position.collateral);
+                match market.liquidate(position, seize_all, market.last_update) {
+                    Ok(result) => {
+                        liquidatable_borrowers += 1;
+                        market_bad_debt += result.bad_debt_assets;
+                    }
+                    // Healthy, or the collateral covers the whole debt
+                    Err(SimError::HealthyPosition { .. }) => {}
+                    Err(SimError::InsufficientPosition { .. }) => liquidatable_borrowers += 1,
+                    Err(e) => return Err(e),
+                }
+            }
+            let market_bad_debt = math::min(market_bad_debt, market.total_borrow_assets);
+
+            let vault_bad_debt = if market.total_supply_assets.is_zero() {
+                U256::ZERO
+            } else {
+                math::min(
+                    mul_div_down(
+                        market_bad_debt,
+                        config.supply_assets,
+                        market.total_supply_assets,
+                    ),
+                    config.supply_assets,
+                )
+            };
+
+            total_bad_debt += vault_bad_debt;
+            markets.push(MarketBadDebtExposure {
+                market_id: *market_id,
+                price: market.price,
+                liquidatable_borrowers,
+                market_bad_debt,
+                vault_bad_debt,
+            });
+        }
+
+        let loss_fraction = if self.vault.total_assets.is_zero() {
+            0.0
+        } else {
+            math::rate_to_f64(math::w_div_down(total_bad_debt, self.vault.total_assets)).min(1.0)
+        };
+
+        Ok(BadDebtExposure {
+            markets,
+            total_bad_debt,
+            loss_fraction,
+        })
     }
 
     /// Simulate a reallocation of assets between markets
@@ -740,6 +1438,209 @@ impl VaultSimulation {
     }
 }
 
+/// Withdrawal liquidity of a vault's position in one market
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketWithdrawalLiquidity {
+    /// Market ID
+    pub market_id: MarketId,
+    /// Assets the vault supplies to this market
+    pub supply_assets: U256,
+    /// Assets that can be withdrawn immediately
+    pub withdrawable: U256,
+    /// Assets blocked by utilization (lent out to borrowers)
+    pub blocked: U256,
+}
+
+/// Exit liquidity of a vault, per market in withdraw queue order
+#[derive(Debug, Clone)]
+pub struct WithdrawalLiquidityProfile {
+    /// Per-market liquidity, in withdraw queue order
+    pub markets: Vec<MarketWithdrawalLiquidity>,
+    /// Total assets that can be withdrawn immediately
+    pub total_withdrawable: U256,
+    /// Total assets blocked by utilization
+    pub total_blocked: U256,
+    /// Fraction of the vault's total assets that is immediately redeemable (0.0 to 1.0)
+    pub redeemable_fraction: f64,
+}
+
+/// One market's share of a planned withdrawal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketWithdrawalLeg {
+    /// Market ID
+    pub market_id: MarketId,
+    /// Assets withdrawn from this market immediately
+    pub withdrawn: U256,
+    /// Assets still needed from this market, blocked by utilization
+    pub blocked: U256,
+    /// Current per-second borrow rate of the market (WAD-scaled)
+    pub borrow_rate: U256,
+    /// Estimated seconds until the blocked assets are repaid (`None` at a zero rate)
+    pub wait_estimate: Option<u64>,
+}
+
+/// Withdraw queue-aware plan for exiting a vault
+#[derive(Debug, Clone)]
+pub struct WithdrawalPlan {
+    /// Assets requested
+    pub requested: U256,
+    /// Markets the assets come from, in withdraw queue order
+    pub markets: Vec<MarketWithdrawalLeg>,
+    /// Assets that can be withdrawn immediately
+    pub available: U256,
+    /// Assets blocked by market utilization
+    pub blocked: U256,
+    /// Assets beyond the vault's supply in queued markets
+    pub excess: U256,
+    /// Estimated seconds until the full amount is available (`None` if it never is
+    /// under the repayment assumption)
+    pub wait_estimate: Option<u64>,
+}
+
+impl WithdrawalPlan {
+    /// Returns true if the full amount can be withdrawn now
+    pub fn is_fully_available(&self) -> bool {
+        self.available == self.requested
+    }
+}
+
+/// Seconds for borrowers to repay `assets` out of `borrow_assets` at `borrow_rate`,
+/// assuming repayments flow at `borrow_assets * borrow_rate` per second.
+fn repayment_wait(assets: U256, borrow_assets: U256, borrow_rate: U256) -> Option<u64> {
+    let repaid_per_second = math::w_mul_down(borrow_assets, borrow_rate);
+    if repaid_per_second.is_zero() {
+        return None;
+    }
+    let secs = math::mul_div_up(assets, U256::from(1), repaid_per_second);
+    Some(u64::try_from(secs).unwrap_or(u64::MAX))
+}
+
+/// Bad debt a vault would absorb from one market
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketBadDebtExposure {
+    /// Market ID
+    pub market_id: MarketId,
+    /// Oracle price used for the market
+    pub price: Option<U256>,
+    /// Number of borrowers that can be liquidated at that price
+    pub liquidatable_borrowers: usize,
+    /// Bad debt realized by the market, absorbed by all suppliers
+    pub market_bad_debt: U256,
+    /// The vault's pro-rata share of the market's bad debt
+    pub vault_bad_debt: U256,
+}
+
+/// Bad debt exposure of a vault, per market in withdraw queue order
+#[derive(Debug, Clone)]
+pub struct BadDebtExposure {
+    /// Per-market exposure, in withdraw queue order
+    pub markets: Vec<MarketBadDebtExposure>,
+    /// Total bad debt absorbed by the vault
+    pub total_bad_debt: U256,
+    /// Fraction of the vault's total assets lost to bad debt (0.0 to 1.0)
+    pub loss_fraction: f64,
+}
+
+/// Projected performance fee revenue of a vault over a period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeRevenueProjection {
+    /// Start of the period (Unix timestamp)
+    pub start: u64,
+    /// End of the period (Unix timestamp)
+    pub end: u64,
+    /// Performance fee used for the projection (WAD-scaled)
+    pub fee: U256,
+    /// Gross interest earned by the vault over the period
+    pub interest: U256,
+    /// Performance and management fees charged, in assets at the time of each accrual
+    pub fee_assets: U256,
+    /// Vault shares minted to the fee recipient
+    pub fee_shares: U256,
+    /// Value of the minted fee shares at the end of the period
+    pub fee_shares_value: U256,
+}
+
+/// A vault queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueueKind {
+    /// Markets deposits are supplied to, in order
+    Supply,
+    /// Markets withdrawals are taken from, in order
+    Withdraw,
+}
+
+impl fmt::Display for QueueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueKind::Supply => write!(f, "supply"),
+            QueueKind::Withdraw => write!(f, "withdraw"),
+        }
+    }
+}
+
+/// A queue misconfiguration found by [`VaultSimulation::validate_queues`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueFinding {
+    /// A queue references a market the vault has no allocation for
+    NotAllocated {
+        queue: QueueKind,
+        market_id: MarketId,
+    },
+    /// A queue lists a market more than once
+    DuplicateMarket {
+        queue: QueueKind,
+        market_id: MarketId,
+    },
+    /// The supply queue lists a market with a zero cap, which deposits skip
+    ZeroCapInSupplyQueue { market_id: MarketId },
+    /// An enabled market is missing from the withdraw queue
+    MissingFromWithdrawQueue { market_id: MarketId },
+    /// A disabled market holds supply but is not in the withdraw queue
+    UnreachableAllocation {
+        market_id: MarketId,
+        supply_assets: U256,
+    },
+}
+
+impl QueueFinding {
+    /// The market the finding is about
+    pub fn market_id(&self) -> MarketId {
+        match self {
+            QueueFinding::NotAllocated { market_id, .. }
+            | QueueFinding::DuplicateMarket { market_id, .. }
+            | QueueFinding::ZeroCapInSupplyQueue { market_id }
+            | QueueFinding::MissingFromWithdrawQueue { market_id }
+            | QueueFinding::UnreachableAllocation { market_id, .. } => *market_id,
+        }
+    }
+}
+
+impl fmt::Display for QueueFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueFinding::NotAllocated { queue, market_id } => {
+                write!(f, "{queue} queue market {market_id} has no allocation")
+            }
+            QueueFinding::DuplicateMarket { queue, market_id } => {
+                write!(f, "market {market_id} is listed twice in the {queue} queue")
+            }
+            QueueFinding::ZeroCapInSupplyQueue { market_id } => {
+                write!(f, "supply queue market {market_id} has a zero cap")
+            }
+            QueueFinding::MissingFromWithdrawQueue { market_id } => {
+                write!(f, "enabled market {market_id} is not in the withdraw queue")
+            }
+            QueueFinding::UnreachableAllocation {
+                market_id,
+                supply_assets,
+            } => write!(
+                f,
+                "disabled market {market_id} holds {supply_assets} assets outside the withdraw queue"
+            ),
+        }
+    }
+}
+
 /// A step in a reallocation operation
 #[derive(Debug, Clone)]
 pub struct ReallocationStep {
@@ -868,7 +1769,9 @@ pub fn amount_for_vault_apy_impact(
         return Ok(None);
     }
 
-    // Binary search for the deposit amount
+    // Binary search for the deposit amount, probing on one scratch copy rolled back
+    // between probes rather than cloning the simulation for each
+    let mut scratch = simulation.clone();
     let mut low = U256::ZERO;
     let mut high = max_deposit;
     let tolerance = 1e-8;
@@ -881,9 +1784,10 @@ pub fn amount_for_vault_apy_impact(
             return Ok(Some(mid));
         }
 
-        match simulation.simulate_deposit(mid, timestamp) {
-            Ok((new_sim, _)) => {
-                let new_apy = new_sim.get_net_apy(timestamp)?;
+        scratch.rollback_to(simulation);
+        match scratch.deposit_mut(mid, timestamp) {
+            Ok(_) => {
+                let new_apy = scratch.get_net_apy(timestamp)?;
                 let delta = new_apy - current_apy;
 
                 if (delta - target_apy_delta).abs() < tolerance {
@@ -911,38 +1815,57 @@ pub fn amount_for_vault_apy_impact(
 pub struct VaultRanking {
     pub vault_address: Address,
     pub net_apy: f64,
+    /// Exact WAD-scaled net APY, used as the sort key
+    pub net_apy_wad: U256,
     pub gross_apy: f64,
     pub total_assets: U256,
     pub available_capacity: U256,
 }
 
 /// Rank vaults by net APY (descending)
+///
+/// Orders by the exact WAD-scaled net APY, with ties broken by vault address, so the
+/// ranking does not depend on the input order or floating-point rounding. Market rates
+/// are computed once per market shared between vaults (see [`AccrualCache`]).
 pub fn rank_vaults_by_apy(
     vaults: &[&VaultSimulation],
     timestamp: u64,
 ) -> Result<Vec<VaultRanking>, SimError> {
+    let cache = AccrualCache::new();
     let mut rankings: Vec<VaultRanking> = vaults
         .iter()
-        .filter_map(|sim| {
-            let net_apy = sim.get_net_apy(timestamp).ok()?;
-            let gross_apy = sim.get_apy(timestamp).ok()?;
-            Some(VaultRanking {
-                vault_address: sim.vault.address,
-                net_apy,
-                gross_apy,
-                total_assets: sim.vault.total_assets,
-                available_capacity: sim.vault.max_deposit(),
-            })
-        })
+        .filter_map(|sim| vault_ranking(sim, timestamp, &cache))
         .collect();
 
+    sort_vault_rankings(&mut rankings);
+    Ok(rankings)
+}
+
+/// Ranking entry for one vault, or None if its rates cannot be computed
+pub(crate) fn vault_ranking(
+    sim: &VaultSimulation,
+    timestamp: u64,
+    cache: &AccrualCache,
+) -> Option<VaultRanking> {
+    let avg_rate = sim.get_avg_supply_rate_cached(timestamp, cache).ok()?;
+    let net_rate = sim.net_supply_rate(avg_rate);
+    Some(VaultRanking {
+        vault_address: sim.vault.address,
+        net_apy: rate_to_apy(net_rate),
+        net_apy_wad: rate_to_apy_wad(net_rate),
+        gross_apy: rate_to_apy(avg_rate),
+        total_assets: sim.vault.total_assets,
+        available_capacity: sim.vault.max_deposit(),
+    })
+}
+
+/// Sort by exact net APY descending, then vault address
+pub(crate) fn sort_vault_rankings(rankings: &mut [VaultRanking]) {
     rankings.sort_by(|a, b| {
-        b.net_apy
-            .partial_cmp(&a.net_apy)
-            .unwrap_or(std::cmp::Ordering::Equal)
+        b.net_apy_wad
+            .cmp(&a.net_apy_wad)
+            .then(a.vault_address.cmp(&b.vault_address))
     });
-
-    Ok(rankings)
 }
 
 /// Find the best vault for a given deposit amount
@@ -973,6 +1896,372 @@ pub fn find_best_vault_for_deposit(
     Ok(best)
 }
 
+/// Number of increments a deposit is split into by [`find_optimal_vault_split`]
+/// when `min_chunk` is smaller than `amount / DEFAULT_SPLIT_STEPS`
+pub const DEFAULT_SPLIT_STEPS: u64 = 100;
+
+/// Constraints for [`find_optimal_vault_split`]
+#[derive(Debug, Clone, Default)]
+pub struct VaultSplitConstraints {
+    /// Minimum amount placed in any vault that receives part of the deposit
+    pub min_chunk: U256,
+    /// Maximum number of vaults to split across (None = unlimited)
+    pub max_vaults: Option<usize>,
+}
+
+/// A vault's share of a split deposit
+#[derive(Debug, Clone)]
+pub struct VaultSplitAllocation {
+    /// Vault address
+    pub vault_address: Address,
+    /// Amount to deposit into the vault
+    pub amount: U256,
+    /// Vault net APY after the deposit
+    pub net_apy_after: f64,
+}
+
+/// Result of splitting a deposit across vaults
+#[derive(Debug, Clone)]
+pub struct VaultSplit {
+    /// Per-vault allocations, in the order vaults were first chosen
+    pub allocations: Vec<VaultSplitAllocation>,
+    /// Amount-weighted net APY across the allocations
+    pub blended_net_apy: f64,
+    /// Amount that could not be placed (all vault caps reached)
+    pub unallocated: U256,
+}
+
+/// Splits a deposit across multiple vaults to maximize blended net APY.
+///
+/// Unlike [`find_best_vault_for_deposit`], which places the whole amount in one vault,
+/// this distributes the deposit in increments, each going to the vault where it adds
+/// the most yield given what has already been placed there. Because every increment
+/// is simulated against the vault's markets, each vault's APY dilution curve is
+/// accounted for, and the result approximately equalizes marginal rates across vaults.
+///
+/// # Constraints
+///
+/// - **Min chunk**: The increment size is `max(min_chunk, amount / DEFAULT_SPLIT_STEPS)`,
+///   so every chosen vault receives at least `min_chunk` (unless the whole deposit is
+///   smaller). A final partial increment goes to an already-chosen vault.
+/// - **Max vaults**: Once `max_vaults` vaults are chosen, later increments only go to them.
+///
+/// # Arguments
+///
+/// * `vaults` - Candidate vault simulations
+/// * `amount` - Total amount to deposit (WAD-scaled)
+/// * `constraints` - Split constraints
+/// * `timestamp` - Current Unix timestamp
+///
+/// # Returns
+///
+/// A [`VaultSplit`] with per-vault amounts, the blended net APY, and any amount that
+/// could not be placed because vault caps were reached.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use morpho_rs_sim::{find_optimal_vault_split, VaultSplitConstraints};
+///
+/// let constraints = VaultSplitConstraints {
+///     min_chunk: U256::from(10_000) * WAD,
+///     max_vaults: Some(3),
+/// };
+/// let split = find_optimal_vault_split(&vaults, U256::from(1_000_000) * WAD, &constraints, timestamp)?;
+///
+/// for alloc in &split.allocations {
+///     println!("{}: {} ({:.2}%)", alloc.vault_address, alloc.amount, alloc.net_apy_after * 100.0);
+/// }
+/// println!("Blended: {:.2}%", split.blended_net_apy * 100.0);
+/// ```
+pub fn find_optimal_vault_split(
+    vaults: &[&VaultSimulation],
+    amount: U256,
+    constraints: &VaultSplitConstraints,
+    timestamp: u64,
+) -> Result<VaultSplit, SimError> {
+    let max_vaults = constraints.max_vaults.unwrap_or(vaults.len());
+    let mut chunk = math::max(constraints.min_chunk, amount / U256::from(DEFAULT_SPLIT_STEPS));
+    if chunk.is_zero() {
+        chunk = amount;
+    }
+
+    // Per vault: amount placed so far and net APY after placing it
+    let mut placed: Vec<(U256, f64)> = vec![(U256::ZERO, 0.0); vaults.len()];
+    let mut order: Vec<usize> = Vec::new();
+    let mut remaining = amount;
+    // One scratch copy per vault, rolled back before each probe
+    let mut scratch: Vec<VaultSimulation> = vaults.iter().map(|sim| (*sim).clone()).collect();
+
+    while !remaining.is_zero() {
+        let step = math::min(chunk, remaining);
+        let partial = step < chunk && step < constraints.min_chunk;
+        let can_add_vault = order.len() < max_vaults && (!partial || order.is_empty());
+
+        let mut best: Option<(usize, f64, f64)> = None;
+        for (i, sim) in vaults.iter().enumerate() {
+            let (current, current_apy) = placed[i];
+            if current.is_zero() && !can_add_vault {
+                continue;
+            }
+
+            scratch[i].rollback_to(sim);
+            if scratch[i].deposit_mut(current + step, timestamp).is_err() {
+                continue;
+            }
+            let apy_after = match scratch[i].get_net_apy(timestamp) {
+                Ok(apy) => apy,
+                Err(_) => continue,
+            };
+
+            // Yield added by this increment: earnings after minus earnings before
+            let gain = math::rate_to_f64(current + step) * apy_after
+                - math::rate_to_f64(current) * current_apy;
+
+            if best.is_none_or(|(_, best_gain, _)| gain > best_gain) {
+                best = Some((i, gain, apy_after));
+            }
+        }
+
+        let Some((i, _, apy_after)) = best else {
+            break;
+        };
+        if placed[i].0.is_zero() {
+            order.push(i);
+        }
+        placed[i] = (placed[i].0 + step, apy_after);
+        remaining -= step;
+    }
+
+    let allocated = amount - remaining;
+    let allocations: Vec<VaultSplitAllocation> = order
+        .iter()
+        .map(|&i| VaultSplitAllocation {
+            vault_address: vaults[i].vault.address,
+            amount: placed[i].0,
+            net_apy_after: placed[i].1,
+        })
+        .collect();
+
+    let blended_net_apy = if allocated.is_zero() {
+        0.0
+    } else {
+        allocations
+            .iter()
+            .map(|a| math::rate_to_f64(a.amount) * a.net_apy_after)
+            .sum::<f64>()
+            / math::rate_to_f64(allocated)
+    };
+
+    Ok(VaultSplit {
+        allocations,
+        blended_net_apy,
+        unallocated: remaining,
+    })
+}
+
+/// Constraints for [`suggest_reallocation`]
+#[derive(Debug, Clone, Default)]
+pub struct ReallocationConstraints {
+    /// Maximum assets withdrawn from markets (and supplied to others) in total
+    /// (None = unlimited)
+    pub max_movement: Option<U256>,
+    /// Minimum amount moved at once
+    pub min_chunk: U256,
+    /// Minimum net APY gain for the reallocation to be worth suggesting (0.0001 = 1 bp)
+    pub min_apy_gain: f64,
+}
+
+/// A reallocation proposed by [`suggest_reallocation`] and its projected effect
+#[derive(Debug, Clone)]
+pub struct ReallocationSuggestion {
+    /// Steps for [`VaultSimulation::simulate_reallocate`]: withdrawals first, then
+    /// supplies, each in market ID order. Empty if no reallocation is worth making.
+    pub steps: Vec<ReallocationStep>,
+    /// Total assets moved between markets
+    pub moved: U256,
+    /// Vault net APY before the reallocation
+    pub net_apy_before: f64,
+    /// Projected vault net APY after the reallocation
+    pub net_apy_after: f64,
+}
+
+impl ReallocationSuggestion {
+    /// Projected change in net APY
+    pub fn apy_delta(&self) -> f64 {
+        self.net_apy_after - self.net_apy_before
+    }
+
+    /// Returns true if no reallocation is suggested
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Suggests a reallocation between a vault's markets that raises its net APY.
+///
+/// The counterpart to [`VaultSimulation::simulate_reallocate`] for allocators: assets
+/// are moved in increments, each from the market and to the market that give the
+/// highest vault net APY once simulated, until no move raises it further. Because every
+/// move is simulated, each market's rate response to supply and withdrawals is
+/// accounted for.
+///
+/// # Constraints
+///
+/// - **Caps**: Markets are never filled above their supply cap, and only enabled markets
+///   are withdrawn from or supplied to.
+/// - **Liquidity**: Markets are never withdrawn from beyond their available liquidity.
+/// - **Max movement**: The assets withdrawn in total stay within `max_movement`.
+/// - **Min chunk**: The increment size is `max(min_chunk, total_assets / DEFAULT_SPLIT_STEPS)`;
+///   a smaller move is only made when a market's supply or cap room is smaller.
+/// - **Min APY gain**: If the projected gain is below `min_apy_gain`, no steps are
+///   suggested.
+///
+/// # Arguments
+///
+/// * `simulation` - The vault to reallocate
+/// * `constraints` - Reallocation constraints
+/// * `timestamp` - Current Unix timestamp
+///
+/// # Errors
+///
+/// Returns an error if the vault's current net APY cannot be computed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use morpho_rs_sim::{suggest_reallocation, ReallocationConstraints};
+///
+/// let constraints = ReallocationConstraints {
+///     max_movement: Some(U256::from(500_000) * WAD),
+///     min_apy_gain: 0.0005,
+///     ..Default::default()
+/// };
+/// let suggestion = suggest_reallocation(&simulation, &constraints, timestamp)?;
+///
+/// if !suggestion.is_empty() {
+///     println!("+{:.2}% net APY", suggestion.apy_delta() * 100.0);
+///     let reallocated = simulation.simulate_reallocate(&suggestion.steps, timestamp)?;
+/// }
+/// ```
+pub fn suggest_reallocation(
+    simulation: &VaultSimulation,
+    constraints: &ReallocationConstraints,
+    timestamp: u64,
+) -> Result<ReallocationSuggestion, SimError> {
+    let vault = &simulation.vault;
+    let net_apy_before = simulation.get_net_apy(timestamp)?;
+    let unchanged = ReallocationSuggestion {
+        steps: Vec::new(),
+        moved: U256::ZERO,
+        net_apy_before,
+        net_apy_after: net_apy_before,
+    };
+
+    let mut market_ids: Vec<MarketId> = vault
+        .allocations
+        .iter()
+        .filter(|(id, config)| config.enabled && simulation.markets.contains_key(*id))
+        .map(|(id, _)| *id)
+        .collect();
+    market_ids.sort();
+
+    let chunk = math::max(
+        constraints.min_chunk,
+        vault.total_assets / U256::from(DEFAULT_SPLIT_STEPS),
+    );
+    if chunk.is_zero() || market_ids.len() < 2 {
+        return Ok(unchanged);
+    }
+    let max_movement = constraints.max_movement.unwrap_or(U256::MAX);
+
+    let mut targets: HashMap<MarketId, U256> = market_ids
+        .iter()
+        .map(|id| (*id, vault.allocations[id].supply_assets))
+        .collect();
+    let mut net_apy = net_apy_before;
+
+    // Every accepted move strictly raises the net APY; the bound only guards run time
+    for _ in 0..DEFAULT_SPLIT_STEPS as usize * market_ids.len() {
+        let mut best: Option<(HashMap<MarketId, U256>, f64)> = None;
+        for from in &market_ids {
+            for to in &market_ids {
+                if from == to {
+                    continue;
+                }
+                let room = zero_floor_sub(vault.allocations[to].cap, targets[to]);
+                let amount = math::min(chunk, math::min(targets[from], room));
+                if amount.is_zero() {
+                    continue;
+                }
+
+                let mut probe = targets.clone();
+                probe.insert(*from, targets[from] - amount);
+                probe.insert(*to, targets[to] + amount);
+                let steps = reallocation_steps(vault, &market_ids, &probe);
+                if reallocation_movement(&steps, vault) > max_movement {
+                    continue;
+                }
+                let apy = match simulation
+                    .simulate_reallocate(&steps, timestamp)
+                    .and_then(|sim| sim.get_net_apy(timestamp))
+                {
+                    Ok(apy) => apy,
+                    Err(_) => continue,
+                };
+
+                if apy > net_apy && best.as_ref().is_none_or(|(_, best_apy)| apy > *best_apy) {
+                    best = Some((probe, apy));
+                }
+            }
+        }
+
+        let Some((probe, apy)) = best else {
+            break;
+        };
+        targets = probe;
+        net_apy = apy;
+    }
+
+    if net_apy - net_apy_before < constraints.min_apy_gain {
+        return Ok(unchanged);
+    }
+    let steps = reallocation_steps(vault, &market_ids, &targets);
+    Ok(ReallocationSuggestion {
+        moved: reallocation_movement(&steps, vault),
+        steps,
+        net_apy_before,
+        net_apy_after: net_apy,
+    })
+}
+
+/// Steps moving each market to its target: withdrawals first, then supplies
+fn reallocation_steps(
+    vault: &Vault,
+    market_ids: &[MarketId],
+    targets: &HashMap<MarketId, U256>,
+) -> Vec<ReallocationStep> {
+    let step = |market_id: &MarketId| ReallocationStep {
+        market_id: *market_id,
+        target_assets: targets[market_id],
+    };
+    let current = |market_id: &MarketId| vault.allocations[market_id].supply_assets;
+
+    let withdrawals = market_ids.iter().filter(|id| targets[*id] < current(id));
+    let supplies = market_ids.iter().filter(|id| targets[*id] > current(id));
+    withdrawals.chain(supplies).map(step).collect()
+}
+
+/// Assets withdrawn by reallocation steps
+fn reallocation_movement(steps: &[ReallocationStep], vault: &Vault) -> U256 {
+    steps.iter().fold(U256::ZERO, |acc, step| {
+        acc + zero_floor_sub(
+            vault.allocations[&step.market_id].supply_assets,
+            step.target_assets,
+        )
+    })
+}
+
 /// Optimal allocation result
 #[derive(Debug, Clone)]
 pub struct OptimalAllocation {
@@ -1050,19 +2339,39 @@ pub fn find_optimal_market_allocation(
     timestamp: u64,
 ) -> Result<Vec<OptimalAllocation>, SimError> {
     // Simple greedy allocation: allocate to highest APY markets first
+    let apys = markets
+        .iter()
+        .map(|(_, market)| market.get_supply_apy_wad(timestamp).ok())
+        .collect();
+
+    greedy_market_amounts(markets, apys, total_amount, caps)
+        .into_iter()
+        .map(|(market_id, market, amount)| allocate_to_market(market_id, market, amount, timestamp))
+        .collect()
+}
+
+/// Split `total_amount` across markets in order of supply APY, up to each market's cap
+///
+/// `apys[i]` is the WAD-scaled supply APY of `markets[i]`, or None to skip the market.
+pub(crate) fn greedy_market_amounts<'a>(
+    markets: &[(MarketId, &'a Market)],
+    apys: Vec<Option<U256>>,
+    total_amount: U256,
+    caps: &HashMap<MarketId, U256>,
+) -> Vec<(MarketId, &'a Market, U256)> {
     let mut rankings: Vec<_> = markets
         .iter()
-        .filter_map(|(id, market)| {
-            let apy = market.get_supply_apy(timestamp).ok()?;
+        .zip(apys)
+        .filter_map(|((id, market), apy)| {
             let cap = caps.get(id).copied().unwrap_or(U256::MAX);
-            Some((*id, *market, apy, cap))
+            Some((*id, *market, apy?, cap))
         })
         .collect();
 
-    // Sort by APY descending
-    rankings.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    // Sort by exact APY descending, then market id for a deterministic order
+    rankings.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
 
-    let mut allocations = Vec::new();
+    let mut amounts = Vec::new();
     let mut remaining = total_amount;
 
     for (market_id, market, _, cap) in rankings {
@@ -1070,27 +2379,32 @@ pub fn find_optimal_market_allocation(
             break;
         }
 
-        let available_cap = cap;
-        let allocate = math::min(remaining, available_cap);
+        let allocate = math::min(remaining, cap);
 
         if allocate.is_zero() {
             continue;
         }
 
-        // Calculate APY after allocation
-        let (new_market, _) = market.supply(allocate, timestamp)?;
-        let expected_apy = new_market.get_supply_apy(timestamp)?;
-
-        allocations.push(OptimalAllocation {
-            market_id,
-            amount: allocate,
-            expected_apy,
-        });
-
+        amounts.push((market_id, market, allocate));
         remaining -= allocate;
     }
 
-    Ok(allocations)
+    amounts
+}
+
+/// Simulate supplying `amount` to a market and report its APY afterwards
+pub(crate) fn allocate_to_market(
+    market_id: MarketId,
+    market: &Market,
+    amount: U256,
+    timestamp: u64,
+) -> Result<OptimalAllocation, SimError> {
+    let (new_market, _) = market.supply(amount, timestamp)?;
+    Ok(OptimalAllocation {
+        market_id,
+        amount,
+        expected_apy: new_market.get_supply_apy(timestamp)?,
+    })
 }
 
 #[cfg(test)]
@@ -1147,6 +2461,7 @@ mod tests {
             address: Address::ZERO,
             asset_decimals: 18,
             fee: U256::from(100_000_000_000_000_000u64),
+            management_fee: U256::ZERO,
             total_assets: U256::from(1_000_000) * WAD,
             total_supply: U256::from(1_000_000) * WAD,
             last_total_assets: U256::from(1_000_000) * WAD,
@@ -1188,6 +2503,37 @@ mod tests {
         assert!(apy < 0.5);
     }
 
+    #[test]
+    fn test_get_net_apy_with_management_fee() {
+        let sim = create_test_simulation();
+        let mut charged = sim.clone();
+        charged.vault.management_fee = MAX_MANAGEMENT_FEE;
+
+        let net_apy = sim.get_net_apy(1000).unwrap();
+        let charged_apy = charged.get_net_apy(1000).unwrap();
+        assert!(charged_apy < net_apy);
+        // Gross APY ignores fees
+        assert_eq!(charged.get_apy(1000).unwrap(), sim.get_apy(1000).unwrap());
+    }
+
+    #[test]
+    fn test_accrue_interest_charges_management_fee() {
+        let sim = create_test_simulation().with_fee(U256::ZERO).unwrap();
+        let mut charged = sim.clone();
+        charged.vault.management_fee = MAX_MANAGEMENT_FEE;
+        let day = 86_400;
+
+        let accrued = sim.accrue_interest(1000 + day).unwrap();
+        assert_eq!(accrued.vault.total_supply, sim.vault.total_supply);
+
+        let accrued = charged.accrue_interest(1000 + day).unwrap();
+        let fee_shares = accrued.vault.total_supply - sim.vault.total_supply;
+        let fee_value = accrued.vault.to_assets(fee_shares, RoundingDirection::Down);
+        let expected = accrued.vault.management_fee_assets(day);
+        assert!(fee_value <= expected);
+        assert!(fee_value > expected * U256::from(999) / U256::from(1000));
+    }
+
     #[test]
     fn test_simulate_deposit() {
         let sim = create_test_simulation();
@@ -1235,6 +2581,42 @@ mod tests {
         assert!(impact.apy_delta >= 0.0);
     }
 
+    #[test]
+    fn test_deposit_mut_matches_simulate_deposit() {
+        let sim = create_test_simulation();
+        let amount = U256::from(100_000) * WAD;
+        let (expected, expected_shares) = sim.simulate_deposit(amount, 2000).unwrap();
+
+        let mut scratch = sim.clone();
+        for _ in 0..3 {
+            scratch.rollback_to(&sim);
+            let shares = scratch.deposit_mut(amount, 2000).unwrap();
+            assert_eq!(shares, expected_shares);
+            assert_eq!(scratch.vault.total_assets, expected.vault.total_assets);
+            assert_eq!(scratch.vault.total_supply, expected.vault.total_supply);
+            for (id, market) in &expected.markets {
+                assert_eq!(scratch.markets[id].total_supply_assets, market.total_supply_assets);
+            }
+        }
+
+        scratch.rollback_to(&sim);
+        assert_eq!(scratch.vault.total_assets, sim.vault.total_assets);
+        assert_eq!(scratch.get_net_apy(1000).unwrap(), sim.get_net_apy(1000).unwrap());
+    }
+
+    #[test]
+    fn test_accrue_interest_mut_failure_leaves_state() {
+        let mut sim = create_test_simulation().accrue_interest(5000).unwrap();
+        let total_assets = sim.vault.total_assets;
+
+        assert!(matches!(
+            sim.accrue_interest_mut(4000),
+            Err(SimError::InvalidInterestAccrual { .. })
+        ));
+        assert_eq!(sim.vault.total_assets, total_assets);
+        assert!(sim.markets.values().all(|market| market.last_update == 5000));
+    }
+
     #[test]
     fn test_max_deposit() {
         let sim = create_test_simulation();
@@ -1430,6 +2812,7 @@ mod tests {
             address: Address::ZERO,
             asset_decimals: 18,
             fee: U256::from(100_000_000_000_000_000u64),
+            management_fee: U256::ZERO,
             total_assets: U256::from(1_000_000) * WAD,
             total_supply: U256::from(1_000_000) * WAD,
             last_total_assets: U256::from(1_000_000) * WAD,
@@ -1500,6 +2883,7 @@ mod tests {
             address: Address::ZERO,
             asset_decimals: 18,
             fee: U256::from(100_000_000_000_000_000u64),
+            management_fee: U256::ZERO,
             total_assets: U256::from(1_000_000) * WAD,
             total_supply: U256::from(1_000_000) * WAD,
             last_total_assets: U256::from(1_000_000) * WAD,
@@ -1572,6 +2956,7 @@ mod tests {
             address: Address::from([1u8; 20]),
             asset_decimals: 18,
             fee: U256::from(100_000_000_000_000_000u64),
+            management_fee: U256::ZERO,
             total_assets: U256::from(1_000_000) * WAD,
             total_supply: U256::from(1_000_000) * WAD,
             last_total_assets: U256::from(1_000_000) * WAD,
@@ -1622,6 +3007,7 @@ mod tests {
             address: Address::from([2u8; 20]),
             asset_decimals: 18,
             fee: U256::from(50_000_000_000_000_000u64), // Lower fee
+            management_fee: U256::ZERO,
             total_assets: U256::from(500_000) * WAD,
             total_supply: U256::from(500_000) * WAD,
             last_total_assets: U256::from(500_000) * WAD,
@@ -1694,4 +3080,438 @@ mod tests {
         // Interest should have accrued
         assert!(new_market.total_supply_assets > old_market.total_supply_assets);
     }
+
+    #[test]
+    fn test_suggest_reallocation() {
+        let mut sim = create_test_simulation();
+        let market_id_1 = FixedBytes::from([1; 32]);
+        let market_id_2 = FixedBytes::from([2; 32]);
+        // Market 1 at 50% utilization pays less than market 2 at 90%, where the vault
+        // is a small supplier
+        let (_, market_1) = create_test_market(1, 1_000_000, 500_000);
+        let (_, market_2) = create_test_market(2, 10_000_000, 9_000_000);
+        sim.markets.insert(market_id_1, market_1);
+        sim.markets.insert(market_id_2, market_2);
+
+        let suggestion =
+            suggest_reallocation(&sim, &ReallocationConstraints::default(), 1000).unwrap();
+        assert!(suggestion.apy_delta() > 0.0);
+        assert_eq!(suggestion.steps.len(), 2);
+        assert_eq!(suggestion.steps[0].market_id, market_id_1);
+        assert_eq!(suggestion.steps[1].market_id, market_id_2);
+        // Withdrawals stop at market 1's 500K of liquidity
+        assert_eq!(suggestion.moved, U256::from(500_000) * WAD);
+
+        let reallocated = sim.simulate_reallocate(&suggestion.steps, 1000).unwrap();
+        let net_apy = reallocated.get_net_apy(1000).unwrap();
+        assert!((net_apy - suggestion.net_apy_after).abs() < 1e-12);
+
+        let constraints = ReallocationConstraints {
+            max_movement: Some(U256::from(20_000) * WAD),
+            ..Default::default()
+        };
+        let limited = suggest_reallocation(&sim, &constraints, 1000).unwrap();
+        assert_eq!(limited.moved, U256::from(20_000) * WAD);
+        assert!(limited.apy_delta() < suggestion.apy_delta());
+
+        // Cap room limits supply to market 2
+        sim.vault.allocations.get_mut(&market_id_2).unwrap().cap = U256::from(405_000) * WAD;
+        let capped = suggest_reallocation(&sim, &ReallocationConstraints::default(), 1000).unwrap();
+        assert_eq!(capped.moved, U256::from(5_000) * WAD);
+
+        let constraints = ReallocationConstraints {
+            min_apy_gain: 1.0,
+            ..Default::default()
+        };
+        let not_worth_it = suggest_reallocation(&sim, &constraints, 1000).unwrap();
+        assert!(not_worth_it.is_empty());
+        assert_eq!(not_worth_it.apy_delta(), 0.0);
+    }
+
+    #[test]
+    fn test_vault_split_uses_multiple_vaults() {
+        let sim_a = create_test_simulation();
+        let mut sim_b = create_test_simulation();
+        sim_b.vault.address = Address::repeat_byte(0xBB);
+
+        let amount = U256::from(1_000_000) * WAD;
+        let split = find_optimal_vault_split(
+            &[&sim_a, &sim_b],
+            amount,
+            &VaultSplitConstraints::default(),
+            1000,
+        )
+        .unwrap();
+
+        assert_eq!(split.allocations.len(), 2);
+        assert_eq!(split.unallocated, U256::ZERO);
+        let total = split
+            .allocations
+            .iter()
+            .fold(U256::ZERO, |acc, a| acc + a.amount);
+        assert_eq!(total, amount);
+
+        // Identical vaults: the split beats depositing everything into one
+        let single = vault_deposit_apy_impact(&sim_a, amount, 1000).unwrap();
+        assert!(split.blended_net_apy > single.apy_after);
+    }
+
+    #[test]
+    fn test_vault_split_max_vaults() {
+        let sim_a = create_test_simulation();
+        let mut sim_b = create_test_simulation();
+        sim_b.vault.address = Address::repeat_byte(0xBB);
+
+        let amount = U256::from(500_000) * WAD;
+        let constraints = VaultSplitConstraints {
+            min_chunk: U256::ZERO,
+            max_vaults: Some(1),
+        };
+        let split = find_optimal_vault_split(&[&sim_a, &sim_b], amount, &constraints, 1000).unwrap();
+
+        assert_eq!(split.allocations.len(), 1);
+        assert_eq!(split.allocations[0].amount, amount);
+    }
+
+    #[test]
+    fn test_vault_split_min_chunk() {
+        let sim_a = create_test_simulation();
+        let mut sim_b = create_test_simulation();
+        sim_b.vault.address = Address::repeat_byte(0xBB);
+
+        let amount = U256::from(150_000) * WAD;
+        let min_chunk = U256::from(100_000) * WAD;
+        let constraints = VaultSplitConstraints {
+            min_chunk,
+            max_vaults: None,
+        };
+        let split = find_optimal_vault_split(&[&sim_a, &sim_b], amount, &constraints, 1000).unwrap();
+
+        // 100k chunk plus a 50k remainder that must join an existing vault
+        assert_eq!(split.allocations.len(), 1);
+        assert_eq!(split.allocations[0].amount, amount);
+        for alloc in &split.allocations {
+            assert!(alloc.amount >= min_chunk);
+        }
+    }
+
+    #[test]
+    fn test_vault_split_unallocated_when_caps_reached() {
+        let sim = create_test_simulation();
+        // Caps total 3M, vault already supplies 1M
+        let amount = U256::from(3_000_000) * WAD;
+        let split =
+            find_optimal_vault_split(&[&sim], amount, &VaultSplitConstraints::default(), 1000)
+                .unwrap();
+
+        assert!(split.unallocated > U256::ZERO);
+        assert_eq!(split.allocations[0].amount + split.unallocated, amount);
+    }
+
+    #[test]
+    fn test_withdrawal_liquidity_profile() {
+        let sim = create_test_simulation();
+        let profile = sim.withdrawal_liquidity_profile(1000).unwrap();
+
+        // Market 1: 600k supplied, 200k liquidity; market 2: 400k supplied, 100k liquidity
+        assert_eq!(profile.markets.len(), 2);
+        assert_eq!(profile.markets[0].supply_assets, U256::from(600_000) * WAD);
+        assert_eq!(profile.markets[0].withdrawable, U256::from(200_000) * WAD);
+        assert_eq!(profile.markets[0].blocked, U256::from(400_000) * WAD);
+        assert_eq!(profile.markets[1].withdrawable, U256::from(100_000) * WAD);
+        assert_eq!(profile.markets[1].blocked, U256::from(300_000) * WAD);
+
+        assert_eq!(profile.total_withdrawable, U256::from(300_000) * WAD);
+        assert_eq!(profile.total_blocked, U256::from(700_000) * WAD);
+        assert!((profile.redeemable_fraction - 0.3).abs() < 1e-9);
+        assert_eq!(
+            profile.total_withdrawable,
+            sim.vault.max_withdraw(&sim.markets)
+        );
+    }
+
+    #[test]
+    fn test_withdrawal_liquidity_profile_fully_liquid() {
+        let mut sim = create_test_simulation();
+        for market in sim.markets.values_mut() {
+            market.total_borrow_assets = U256::ZERO;
+            market.total_borrow_shares = U256::ZERO;
+        }
+
+        let profile = sim.withdrawal_liquidity_profile(1000).unwrap();
+        assert_eq!(profile.total_blocked, U256::ZERO);
+        assert!((profile.redeemable_fraction - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_plan_withdrawal_fully_available() {
+        let sim = create_test_simulation();
+        let plan = sim.plan_withdrawal(U256::from(150_000) * WAD, 1000).unwrap();
+
+        assert!(plan.is_fully_available());
+        assert_eq!(plan.markets.len(), 1);
+        assert_eq!(plan.markets[0].withdrawn, U256::from(150_000) * WAD);
+        assert_eq!(plan.blocked, U256::ZERO);
+        assert_eq!(plan.wait_estimate, Some(0));
+    }
+
+    #[test]
+    fn test_plan_withdrawal_blocked_by_utilization() {
+        let sim = create_test_simulation();
+        let plan = sim.plan_withdrawal(U256::from(500_000) * WAD, 1000).unwrap();
+
+        // 200k liquid in market 1, 100k in market 2; the rest waits on market 1 borrowers
+        assert!(!plan.is_fully_available());
+        assert_eq!(plan.available, U256::from(300_000) * WAD);
+        assert_eq!(plan.blocked, U256::from(200_000) * WAD);
+        assert_eq!(plan.excess, U256::ZERO);
+        assert_eq!(plan.markets[0].withdrawn, U256::from(200_000) * WAD);
+        assert_eq!(plan.markets[0].blocked, U256::from(200_000) * WAD);
+        assert_eq!(plan.markets[1].withdrawn, U256::from(100_000) * WAD);
+        assert_eq!(plan.markets[1].blocked, U256::ZERO);
+
+        // 200k repaid out of 800k borrowed at the market's rate
+        let leg = &plan.markets[0];
+        let per_second = math::w_mul_down(U256::from(800_000) * WAD, leg.borrow_rate);
+        let expected = math::mul_div_up(leg.blocked, U256::from(1), per_second);
+        assert_eq!(leg.wait_estimate, Some(expected.to::<u64>()));
+        assert_eq!(plan.wait_estimate, leg.wait_estimate);
+        assert!(plan.wait_estimate.unwrap() > 0);
+    }
+
+    #[test]
+    fn test_plan_withdrawal_excess() {
+        let sim = create_test_simulation();
+        let plan = sim.plan_withdrawal(U256::from(1_500_000) * WAD, 1000).unwrap();
+
+        assert_eq!(plan.available, U256::from(300_000) * WAD);
+        assert_eq!(plan.blocked, U256::from(700_000) * WAD);
+        assert_eq!(plan.excess, U256::from(500_000) * WAD);
+        assert_eq!(plan.wait_estimate, None);
+    }
+
+    #[test]
+    fn test_project_fee_revenue() {
+        let sim = create_test_simulation();
+        let day = 86_400;
+        let projection = sim.project_fee_revenue(1000, 30 * day, day).unwrap();
+
+        assert_eq!(projection.start, 1000);
+        assert_eq!(projection.end, 1000 + 30 * day);
+        assert_eq!(projection.fee, sim.vault.fee);
+        assert!(projection.interest > U256::ZERO);
+
+        // 10% fee on the interest earned
+        let expected = projection.interest / U256::from(10);
+        assert!(projection.fee_assets <= expected);
+        assert!(expected - projection.fee_assets <= U256::from(30));
+
+        // Fee shares are worth at least the fee charged (they earn interest too)
+        assert!(projection.fee_shares > U256::ZERO);
+        assert!(projection.fee_shares_value >= projection.fee_assets - U256::from(30));
+    }
+
+    #[test]
+    fn test_project_fee_revenue_excludes_pending_interest() {
+        let sim = create_test_simulation();
+
+        // Interest accrued between the markets' last update and the start is settled first
+        let projection = sim.project_fee_revenue(1000 + 86_400, 0, 3600).unwrap();
+        assert_eq!(projection.interest, U256::ZERO);
+        assert_eq!(projection.fee_shares, U256::ZERO);
+    }
+
+    #[test]
+    fn test_project_fee_revenue_fee_settings() {
+        let sim = create_test_simulation();
+        let day = 86_400;
+
+        let current = sim.project_fee_revenue(1000, 30 * day, day).unwrap();
+        let raised = sim
+            .with_fee(U256::from(200_000_000_000_000_000u64))
+            .unwrap()
+            .project_fee_revenue(1000, 30 * day, day)
+            .unwrap();
+        let zero = sim
+            .with_fee(U256::ZERO)
+            .unwrap()
+            .project_fee_revenue(1000, 30 * day, day)
+            .unwrap();
+
+        assert!(raised.fee_shares > current.fee_shares);
+        assert!(raised.fee_assets > current.fee_assets);
+        assert_eq!(zero.fee_shares, U256::ZERO);
+        assert_eq!(zero.fee_assets, U256::ZERO);
+        // The fee does not change the vault's gross interest
+        assert_eq!(zero.interest, current.interest);
+    }
+
+    #[test]
+    fn test_project_fee_revenue_invalid_inputs() {
+        let sim = create_test_simulation();
+
+        let result = sim.project_fee_revenue(1000, 86_400, 0);
+        assert!(matches!(
+            result,
+            Err(SimError::InvalidTimeStep { step_secs: 0 })
+        ));
+
+        let result = sim.with_fee(MAX_VAULT_FEE + U256::from(1));
+        assert!(matches!(result, Err(SimError::InvalidFee { .. })));
+        assert!(sim.with_fee(MAX_VAULT_FEE).is_ok());
+    }
+
+    #[test]
+    fn test_check_invariants() {
+        let sim = create_test_simulation();
+        assert!(sim.check_invariants().is_ok());
+
+        let (deposited, _) = sim
+            .simulate_deposit(U256::from(100_000) * WAD, 2000)
+            .unwrap();
+        assert!(deposited.check_invariants().is_ok());
+
+        let mut mismatched = sim.clone();
+        mismatched.vault.total_assets += U256::from(1);
+        assert!(matches!(
+            mismatched.check_invariants(),
+            Err(SimError::AllocationMismatch { .. })
+        ));
+
+        let market_id = sim.vault.withdraw_queue[1];
+        let mut unqueued = sim.clone();
+        unqueued.vault.withdraw_queue.pop();
+        assert!(matches!(
+            unqueued.check_invariants(),
+            Err(SimError::UnqueuedAllocation { market_id: id, .. }) if id == market_id
+        ));
+
+        let mut missing_market = sim.clone();
+        missing_market.markets.remove(&market_id);
+        assert!(matches!(
+            missing_market.check_invariants(),
+            Err(SimError::MarketNotFound { .. })
+        ));
+
+        let mut duplicated = sim;
+        duplicated.vault.supply_queue.push(market_id);
+        assert!(matches!(
+            duplicated.check_invariants(),
+            Err(SimError::DuplicateQueueMarket { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_queues() {
+        let sim = create_test_simulation();
+        assert!(sim.validate_queues().is_empty());
+
+        let market_id_1 = FixedBytes::from([1; 32]);
+        let market_id_2 = FixedBytes::from([2; 32]);
+        let market_id_3 = FixedBytes::from([3; 32]);
+        let market_id_4 = FixedBytes::from([4; 32]);
+        let mut sim = sim;
+        let config = |market_id, cap: u64, supply_assets: u64, enabled| VaultMarketConfig {
+            market_id,
+            cap: U256::from(cap),
+            supply_assets: U256::from(supply_assets),
+            enabled,
+            public_allocator_config: None,
+        };
+        sim.vault
+            .allocations
+            .insert(market_id_3, config(market_id_3, 0, 0, true));
+        sim.vault
+            .allocations
+            .insert(market_id_4, config(market_id_4, 0, 500, false));
+        sim.vault.supply_queue = vec![market_id_1, market_id_3, market_id_1];
+        sim.vault.withdraw_queue = vec![market_id_2, FixedBytes::from([9; 32])];
+
+        assert_eq!(
+            sim.validate_queues(),
+            vec![
+                QueueFinding::ZeroCapInSupplyQueue {
+                    market_id: market_id_3
+                },
+                QueueFinding::DuplicateMarket {
+                    queue: QueueKind::Supply,
+                    market_id: market_id_1
+                },
+                QueueFinding::NotAllocated {
+                    queue: QueueKind::Withdraw,
+                    market_id: FixedBytes::from([9; 32])
+                },
+                QueueFinding::MissingFromWithdrawQueue {
+                    market_id: market_id_1
+                },
+                QueueFinding::MissingFromWithdrawQueue {
+                    market_id: market_id_3
+                },
+                QueueFinding::UnreachableAllocation {
+                    market_id: market_id_4,
+                    supply_assets: U256::from(500)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bad_debt_exposure() {
+        use crate::market::ORACLE_PRICE_SCALE;
+
+        let mut sim = create_test_simulation();
+        let market_id_1 = FixedBytes::from([1; 32]);
+        let market_id_2 = FixedBytes::from([2; 32]);
+        let market_1 = sim.markets.get_mut(&market_id_1).unwrap();
+        market_1.price = Some(ORACLE_PRICE_SCALE);
+        market_1.lltv = U256::from(800_000_000_000_000_000u64);
+
+        let borrower = |debt: u64, collateral: u64| {
+            Position::new(
+                Address::ZERO,
+                market_id_1,
+                U256::ZERO,
+                U256::from(debt) * WAD,
+                U256::from(collateral) * WAD,
+            )
+        };
+        let mut borrowers = HashMap::new();
+        borrowers.insert(
+            market_id_1,
+            vec![borrower(100_000, 200_000), borrower(100_000, 100_000)],
+        );
+
+        // At the current price only the second borrower is underwater
+        let current = sim.bad_debt_exposure(&borrowers, &HashMap::new()).unwrap();
+        assert_eq!(current.markets.len(), 2);
+        assert_eq!(current.markets[0].liquidatable_borrowers, 1);
+        assert!(current.markets[0].market_bad_debt > U256::ZERO);
+        // The vault supplies 600K of the market's 1M
+        assert_eq!(
+            current.markets[0].vault_bad_debt,
+            current.markets[0].market_bad_debt * U256::from(6) / U256::from(10)
+        );
+        assert!(current.markets[1].market_bad_debt.is_zero());
+        assert_eq!(current.total_bad_debt, current.markets[0].vault_bad_debt);
+        assert!(current.loss_fraction > 0.0);
+
+        // Halving the collateral price pushes both borrowers into bad debt
+        let mut prices = HashMap::new();
+        prices.insert(market_id_1, ORACLE_PRICE_SCALE / U256::from(2));
+        let shocked = sim.bad_debt_exposure(&borrowers, &prices).unwrap();
+        assert_eq!(shocked.markets[0].liquidatable_borrowers, 2);
+        assert_eq!(
+            shocked.markets[0].price,
+            Some(ORACLE_PRICE_SCALE / U256::from(2))
+        );
+        assert!(shocked.total_bad_debt > current.total_bad_debt);
+        assert!(shocked.loss_fraction > current.loss_fraction);
+
+        // Borrowers in a market without an oracle price
+        borrowers.insert(market_id_2, vec![borrower(1, 1)]);
+        assert!(matches!(
+            sim.bad_debt_exposure(&borrowers, &HashMap::new()),
+            Err(SimError::UnknownOraclePrice { .. })
+        ));
+    }
 }
@@ -0,0 +1,996 @@
+//! Vault simulation for Morpho Vault V2.
+//!
+//! Vault V2 holds its assets idle or allocates them through *adapters*, each of which
+//! supplies to Morpho Blue markets or deposits into a MetaMorpho (V1) vault. Unlike
+//! MetaMorpho, V2 has no supply or withdraw queues.
+//!
+//! # Key Concepts
+//!
+//! - **Liquidity Adapter**: Deposits are allocated to a single liquidity adapter (or stay
+//!   idle if none is set). Withdrawals are served from idle assets first, then deallocated
+//!   from the liquidity adapter. Other adapters are only moved by allocators
+//!   ([`VaultV2Simulation::simulate_allocate`], [`VaultV2Simulation::simulate_deallocate`]).
+//! - **Id Caps**: Allocations are limited per *id* (an adapter, a collateral token, or a
+//!   market) by an absolute cap and a cap relative to the vault's total assets. Caps are
+//!   checked when allocating, never when deallocating.
+//! - **Fees**: A performance fee on interest and a management fee on total assets, each
+//!   minted as shares to its own recipient.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use morpho_rs_sim::VaultV2Simulation;
+//!
+//! let (after, shares) = simulation.simulate_deposit(amount, timestamp)?;
+//!
+//! println!("Net APY: {:.2}%", after.get_net_apy(timestamp)? * 100.0);
+//! ```
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, FixedBytes, U256};
+
+use crate::error::{MarketId, SimError};
+use crate::market::Market;
+use crate::math::{
+    self, mul_div, mul_div_down, rate_to_apy, rate_to_apy_wad, w_mul_down, zero_floor_sub,
+    RoundingDirection, WAD,
+};
+use crate::vault::{VaultSimulation, VAULT_VIRTUAL_ASSETS};
+
+/// Type alias for a 32-byte cap id
+pub type CapId = FixedBytes<32>;
+
+/// Maximum Vault V2 performance fee (50%, WAD-scaled)
+pub const MAX_PERFORMANCE_FEE: U256 = U256::from_limbs([500_000_000_000_000_000, 0, 0, 0]);
+
+/// Maximum Vault V2 management fee (5% per year, WAD-scaled per second)
+pub const MAX_MANAGEMENT_FEE: U256 = U256::from_limbs([1_585_489_599, 0, 0, 0]);
+
+/// Allocation caps on one id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultV2Cap {
+    /// Maximum assets allocated to the id
+    pub absolute_cap: U256,
+    /// Maximum fraction of the vault's total assets allocated to the id (WAD-scaled, WAD
+    /// disables the limit)
+    pub relative_cap: U256,
+}
+
+/// Supply of a Morpho Blue adapter in one market
+#[derive(Debug, Clone)]
+pub struct AdapterMarketPosition {
+    /// The market's unique identifier
+    pub market_id: MarketId,
+    /// Supply shares held by the adapter
+    pub supply_shares: U256,
+    /// Ids charged for this market on top of the adapter's own (e.g. collateral and market)
+    pub cap_ids: Vec<CapId>,
+}
+
+/// Where an adapter allocates its assets
+#[derive(Debug, Clone)]
+pub enum VaultV2AdapterKind {
+    /// Supplies directly to Morpho Blue markets (`MorphoMarketV1Adapter`)
+    MorphoMarketV1 {
+        /// Positions in the simulation's markets
+        positions: Vec<AdapterMarketPosition>,
+    },
+    /// Deposits into a MetaMorpho vault (`MorphoVaultV1Adapter`)
+    MetaMorpho {
+        /// The underlying MetaMorpho vault and its markets
+        vault: Box<VaultSimulation>,
+        /// Vault shares held by the adapter
+        shares: U256,
+    },
+    /// Adapter the simulation does not model: earns nothing and cannot be deallocated
+    Fixed {
+        /// Assets held by the adapter
+        assets: U256,
+    },
+}
+
+/// An adapter enabled on a Vault V2
+#[derive(Debug, Clone)]
+pub struct VaultV2Adapter {
+    /// The adapter's address
+    pub address: Address,
+    /// Ids charged for every allocation through this adapter (e.g. the adapter id)
+    pub cap_ids: Vec<CapId>,
+    /// Where the adapter allocates its assets
+    pub kind: VaultV2AdapterKind,
+}
+
+impl VaultV2Adapter {
+    /// Assets held by the adapter at the current state of its markets
+    pub fn assets(&self, markets: &HashMap<MarketId, Market>) -> Result<U256, SimError> {
+        match &self.kind {
+            VaultV2AdapterKind::MorphoMarketV1 { positions } => {
+                let mut assets = U256::ZERO;
+                for position in positions {
+                    assets += position_assets(position, markets)?;
+                }
+                Ok(assets)
+            }
+            VaultV2AdapterKind::MetaMorpho { vault, shares } => {
+                Ok(vault.vault.to_assets(*shares, RoundingDirection::Down))
+            }
+            VaultV2AdapterKind::Fixed { assets } => Ok(*assets),
+        }
+    }
+
+    /// Assets that can be deallocated from the adapter right now.
+    ///
+    /// Morpho Blue adapters deallocate from one market, so `market_id` selects it; without
+    /// one nothing can be deallocated.
+    pub fn withdrawable(
+        &self,
+        market_id: Option<MarketId>,
+        markets: &HashMap<MarketId, Market>,
+    ) -> Result<U256, SimError> {
+        match &self.kind {
+            VaultV2AdapterKind::MorphoMarketV1 { positions } => {
+                let Some(market_id) = market_id else {
+                    return Ok(U256::ZERO);
+                };
+                let Some(position) = positions.iter().find(|p| p.market_id == market_id) else {
+                    return Ok(U256::ZERO);
+                };
+                let market = markets
+                    .get(&market_id)
+                    .ok_or(SimError::MarketNotFound { market_id })?;
+                Ok(math::min(
+                    position_assets(position, markets)?,
+                    market.liquidity(),
+                ))
+            }
+            VaultV2AdapterKind::MetaMorpho { vault, shares } => Ok(math::min(
+                vault.vault.to_assets(*shares, RoundingDirection::Down),
+                vault.vault.max_withdraw(&vault.markets),
+            )),
+            VaultV2AdapterKind::Fixed { .. } => Ok(U256::ZERO),
+        }
+    }
+}
+
+fn position_assets(
+    position: &AdapterMarketPosition,
+    markets: &HashMap<MarketId, Market>,
+) -> Result<U256, SimError> {
+    let market = markets
+        .get(&position.market_id)
+        .ok_or(SimError::MarketNotFound {
+            market_id: position.market_id,
+        })?;
+    Ok(market.to_supply_assets(position.supply_shares, RoundingDirection::Down))
+}
+
+/// Interest and fees accrued by a Vault V2 over a period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultV2FeeAccrual {
+    /// Interest earned by the vault (zero if it lost assets)
+    pub interest: U256,
+    /// Performance fee charged on the interest
+    pub performance_fee_assets: U256,
+    /// Management fee charged on total assets
+    pub management_fee_assets: U256,
+    /// Shares minted to the performance fee recipient
+    pub performance_fee_shares: U256,
+    /// Shares minted to the management fee recipient
+    pub management_fee_shares: U256,
+}
+
+/// Complete simulation state of a Vault V2, its adapters, and their markets
+#[derive(Debug, Clone)]
+pub struct VaultV2Simulation {
+    /// The vault's address
+    pub address: Address,
+    /// Decimals of the underlying asset
+    pub asset_decimals: u8,
+    /// Performance fee (WAD-scaled)
+    pub performance_fee: U256,
+    /// Management fee (WAD-scaled, per second)
+    pub management_fee: U256,
+    /// Total assets as of the last update
+    pub total_assets: U256,
+    /// Total vault shares outstanding
+    pub total_supply: U256,
+    /// Timestamp of the last interest accrual
+    pub last_update: u64,
+    /// Assets held by the vault itself, outside any adapter
+    pub idle_assets: U256,
+    /// Enabled adapters
+    pub adapters: Vec<VaultV2Adapter>,
+    /// Adapter that deposits are allocated to and withdrawals deallocated from
+    pub liquidity_adapter: Option<Address>,
+    /// Market the liquidity adapter allocates to, for Morpho Blue adapters
+    pub liquidity_market: Option<MarketId>,
+    /// Caps by id; ids without a cap cannot be allocated to
+    pub caps: HashMap<CapId, VaultV2Cap>,
+    /// Morpho Blue markets the adapters supply to directly
+    pub markets: HashMap<MarketId, Market>,
+}
+
+impl VaultV2Simulation {
+    /// Calculate the decimals offset for share conversion
+    pub fn decimals_offset(&self) -> u8 {
+        18u8.saturating_sub(self.asset_decimals)
+    }
+
+    /// Virtual shares for this vault (10^decimals_offset)
+    pub fn virtual_shares(&self) -> U256 {
+        U256::from(10u64).pow(U256::from(self.decimals_offset()))
+    }
+
+    /// Convert vault shares to assets
+    pub fn to_assets(&self, shares: U256, rounding: RoundingDirection) -> U256 {
+        mul_div(
+            shares,
+            self.total_assets + VAULT_VIRTUAL_ASSETS,
+            self.total_supply + self.virtual_shares(),
+            rounding,
+        )
+    }
+
+    /// Convert assets to vault shares
+    pub fn to_shares(&self, assets: U256, rounding: RoundingDirection) -> U256 {
+        mul_div(
+            assets,
+            self.total_supply + self.virtual_shares(),
+            self.total_assets + VAULT_VIRTUAL_ASSETS,
+            rounding,
+        )
+    }
+
+    /// Look up an adapter by address
+    pub fn adapter(&self, address: Address) -> Result<&VaultV2Adapter, SimError> {
+        self.adapters
+            .iter()
+            .find(|adapter| adapter.address == address)
+            .ok_or(SimError::AdapterNotFound {
+                vault: self.address,
+                adapter: address,
+            })
+    }
+
+    fn adapter_index(&self, address: Address) -> Result<usize, SimError> {
+        self.adapters
+            .iter()
+            .position(|adapter| adapter.address == address)
+            .ok_or(SimError::AdapterNotFound {
+                vault: self.address,
+                adapter: address,
+            })
+    }
+
+    /// Idle assets plus the assets held by every adapter
+    pub fn real_assets(&self) -> Result<U256, SimError> {
+        let mut assets = self.idle_assets;
+        for adapter in &self.adapters {
+            assets += adapter.assets(&self.markets)?;
+        }
+        Ok(assets)
+    }
+
+    /// Assets currently allocated to `id` across all adapters and their markets
+    pub fn allocation(&self, id: CapId) -> Result<U256, SimError> {
+        let mut allocation = U256::ZERO;
+        for adapter in &self.adapters {
+            if adapter.cap_ids.contains(&id) {
+                allocation += adapter.assets(&self.markets)?;
+            }
+            if let VaultV2AdapterKind::MorphoMarketV1 { positions } = &adapter.kind {
+                for position in positions.iter().filter(|p| p.cap_ids.contains(&id)) {
+                    allocation += position_assets(position, &self.markets)?;
+                }
+            }
+        }
+        Ok(allocation)
+    }
+
+    /// Accrue interest on all markets and adapters and update the vault state
+    pub fn accrue_interest(&self, timestamp: u64) -> Result<VaultV2Simulation, SimError> {
+        Ok(self.accrue(timestamp)?.0)
+    }
+
+    /// Interest and fees the vault would accrue up to `timestamp`.
+    ///
+    /// Total assets follow the adapters' real assets, so losses are realized immediately
+    /// and only gains are charged the performance fee. The management fee is charged on the
+    /// new total assets for the elapsed time. Both fees are minted as shares at the
+    /// post-fee exchange rate.
+    pub fn fee_accrual(&self, timestamp: u64) -> Result<VaultV2FeeAccrual, SimError> {
+        Ok(self.accrue(timestamp)?.1)
+    }
+
+    fn accrue(&self, timestamp: u64) -> Result<(VaultV2Simulation, VaultV2FeeAccrual), SimError> {
+        if timestamp < self.last_update {
+            return Err(SimError::InvalidInterestAccrual {
+                timestamp,
+                last_update: self.last_update,
+            });
+        }
+
+        let mut sim = self.clone();
+        for (market_id, market) in &self.markets {
+            sim.markets
+                .insert(*market_id, market.accrue_interest(timestamp)?);
+        }
+        for adapter in &mut sim.adapters {
+            if let VaultV2AdapterKind::MetaMorpho { vault, .. } = &mut adapter.kind {
+                **vault = vault.accrue_interest(timestamp)?;
+            }
+        }
+
+        let elapsed = U256::from(timestamp - self.last_update);
+        let new_total_assets = sim.real_assets()?;
+        let interest = zero_floor_sub(new_total_assets, self.total_assets);
+
+        let performance_fee_assets = w_mul_down(interest, self.performance_fee);
+        let management_fee_assets =
+            mul_div_down(new_total_assets * elapsed, self.management_fee, WAD);
+        let new_total_assets_without_fees = zero_floor_sub(
+            zero_floor_sub(new_total_assets, performance_fee_assets),
+            management_fee_assets,
+        );
+
+        let performance_fee_shares = mul_div_down(
+            performance_fee_assets,
+            self.total_supply + self.virtual_shares(),
+            new_total_assets_without_fees + VAULT_VIRTUAL_ASSETS,
+        );
+        let management_fee_shares = mul_div_down(
+            management_fee_assets,
+            self.total_supply + self.virtual_shares(),
+            new_total_assets_without_fees + VAULT_VIRTUAL_ASSETS,
+        );
+
+        sim.total_assets = new_total_assets;
+        sim.total_supply += performance_fee_shares + management_fee_shares;
+        sim.last_update = timestamp;
+
+        Ok((
+            sim,
+            VaultV2FeeAccrual {
+                interest,
+                performance_fee_assets,
+                management_fee_assets,
+                performance_fee_shares,
+                management_fee_shares,
+            },
+        ))
+    }
+
+    /// Calculate the weighted average supply rate across all adapters.
+    ///
+    /// MetaMorpho adapters earn their vault's rate net of its performance fee. Idle assets
+    /// and unmodeled adapters earn nothing.
+    pub fn get_avg_supply_rate(&self, timestamp: u64) -> Result<U256, SimError> {
+        let total_assets = self.real_assets()?;
+        if total_assets.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let mut weighted_rate = U256::ZERO;
+
+        for adapter in &self.adapters {
+            match &adapter.kind {
+                VaultV2AdapterKind::MorphoMarketV1 { positions } => {
+                    for position in positions {
+                        let market = self.markets.get(&position.market_id).ok_or(
+                            SimError::MarketNotFound {
+                                market_id: position.market_id,
+                            },
+                        )?;
+                        let assets = position_assets(position, &self.markets)?;
+                        weighted_rate += market.get_avg_supply_rate(timestamp)? * assets;
+                    }
+                }
+                VaultV2AdapterKind::MetaMorpho { vault, shares } => {
+                    let assets = vault.vault.to_assets(*shares, RoundingDirection::Down);
+                    let rate = vault.net_supply_rate(vault.get_avg_supply_rate(timestamp)?);
+                    weighted_rate += rate * assets;
+                }
+                VaultV2AdapterKind::Fixed { .. } => {}
+            }
+        }
+
+        Ok(weighted_rate / total_assets)
+    }
+
+    /// Calculate the vault's gross APY (before vault fees)
+    pub fn get_apy(&self, timestamp: u64) -> Result<f64, SimError> {
+        let avg_rate = self.get_avg_supply_rate(timestamp)?;
+        Ok(rate_to_apy(avg_rate))
+    }
+
+    /// Calculate the vault's net APY (after performance and management fees)
+    pub fn get_net_apy(&self, timestamp: u64) -> Result<f64, SimError> {
+        let avg_rate = self.get_avg_supply_rate(timestamp)?;
+        let net_rate = zero_floor_sub(
+            w_mul_down(avg_rate, WAD - self.performance_fee),
+            self.management_fee,
+        );
+        Ok(rate_to_apy(net_rate))
+    }
+
+    /// Calculate the vault's gross APY, WAD-scaled and computed exactly
+    pub fn get_apy_wad(&self, timestamp: u64) -> Result<U256, SimError> {
+        let avg_rate = self.get_avg_supply_rate(timestamp)?;
+        Ok(rate_to_apy_wad(avg_rate))
+    }
+
+    /// Calculate the vault's net APY, WAD-scaled and computed exactly
+    pub fn get_net_apy_wad(&self, timestamp: u64) -> Result<U256, SimError> {
+        let avg_rate = self.get_avg_supply_rate(timestamp)?;
+        let net_rate = zero_floor_sub(
+            w_mul_down(avg_rate, WAD - self.performance_fee),
+            self.management_fee,
+        );
+        Ok(rate_to_apy_wad(net_rate))
+    }
+
+    /// Calculate the maximum withdraw capacity: idle assets plus what the liquidity
+    /// adapter can deallocate
+    pub fn max_withdraw(&self) -> Result<U256, SimError> {
+        let Some(liquidity_adapter) = self.liquidity_adapter else {
+            return Ok(self.idle_assets);
+        };
+        let withdrawable = self
+            .adapter(liquidity_adapter)?
+            .withdrawable(self.liquidity_market, &self.markets)?;
+        Ok(self.idle_assets.saturating_add(withdrawable))
+    }
+
+    /// Simulates an allocator moving idle assets into an adapter.
+    ///
+    /// After accruing interest, `assets` are taken from idle and allocated to `adapter`
+    /// (into `market_id` for Morpho Blue adapters). Every id the allocation touches must
+    /// stay within its absolute cap and its relative cap of the vault's total assets.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::AdapterNotFound`] if the adapter is not enabled on the vault
+    /// - [`SimError::NotEnoughLiquidity`] if `assets` exceeds the idle assets
+    /// - [`SimError::MissingAdapterMarket`] if a Morpho Blue adapter is given no market
+    /// - [`SimError::IdCapExceeded`] if the allocation breaks a cap
+    /// - Errors from the underlying markets or MetaMorpho vault
+    pub fn simulate_allocate(
+        &self,
+        adapter: Address,
+        market_id: Option<MarketId>,
+        assets: U256,
+        timestamp: u64,
+    ) -> Result<VaultV2Simulation, SimError> {
+        let mut sim = self.accrue_interest(timestamp)?;
+        let first_total_assets = sim.total_assets;
+        sim.allocate(adapter, market_id, assets, timestamp, first_total_assets)?;
+        Ok(sim)
+    }
+
+    /// Simulates an allocator moving assets out of an adapter back to idle.
+    ///
+    /// Caps are not checked when deallocating.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::AdapterNotFound`] if the adapter is not enabled on the vault
+    /// - [`SimError::MissingAdapterMarket`] if a Morpho Blue adapter is given no market
+    /// - [`SimError::InsufficientPosition`] if the adapter holds less than `assets`
+    /// - [`SimError::NotEnoughLiquidity`] if the adapter is not modeled
+    /// - Errors from the underlying markets or MetaMorpho vault
+    pub fn simulate_deallocate(
+        &self,
+        adapter: Address,
+        market_id: Option<MarketId>,
+        assets: U256,
+        timestamp: u64,
+    ) -> Result<VaultV2Simulation, SimError> {
+        let mut sim = self.accrue_interest(timestamp)?;
+        sim.deallocate(adapter, market_id, assets, timestamp)?;
+        Ok(sim)
+    }
+
+    /// Simulates a deposit to the vault.
+    ///
+    /// After accruing interest, shares are minted at the current exchange rate and the
+    /// deposit is allocated to the liquidity adapter. Without a liquidity adapter the
+    /// deposit stays idle.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of (new_simulation, shares_minted). The original simulation is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::IdCapExceeded`] if allocating the deposit breaks a cap; relative caps
+    ///   are measured against total assets before the deposit
+    /// - Errors from allocating to the liquidity adapter (see
+    ///   [`VaultV2Simulation::simulate_allocate`])
+    pub fn simulate_deposit(
+        &self,
+        amount: U256,
+        timestamp: u64,
+    ) -> Result<(VaultV2Simulation, U256), SimError> {
+        let mut sim = self.accrue_interest(timestamp)?;
+        let first_total_assets = sim.total_assets;
+
+        let shares = sim.to_shares(amount, RoundingDirection::Down);
+        sim.total_assets += amount;
+        sim.total_supply += shares;
+        sim.idle_assets += amount;
+
+        if let Some(adapter) = sim.liquidity_adapter {
+            sim.allocate(
+                adapter,
+                sim.liquidity_market,
+                amount,
+                timestamp,
+                first_total_assets,
+            )?;
+        }
+
+        Ok((sim, shares))
+    }
+
+    /// Simulates a withdrawal from the vault.
+    ///
+    /// After accruing interest, the redeemed assets are served from idle assets first and
+    /// the rest is deallocated from the liquidity adapter. Other adapters are never touched.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of (new_simulation, assets_withdrawn). The original simulation is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimError::NotEnoughLiquidity`] if idle assets and the liquidity adapter cannot
+    ///   cover the withdrawal
+    pub fn simulate_withdraw(
+        &self,
+        shares: U256,
+        timestamp: u64,
+    ) -> Result<(VaultV2Simulation, U256), SimError> {
+        let mut sim = self.accrue_interest(timestamp)?;
+
+        let assets = sim.to_assets(shares, RoundingDirection::Down);
+
+        if assets > sim.idle_assets {
+            if let Some(adapter) = sim.liquidity_adapter {
+                let withdrawable = sim
+                    .adapter(adapter)?
+                    .withdrawable(sim.liquidity_market, &sim.markets)?;
+                let to_deallocate = math::min(assets - sim.idle_assets, withdrawable);
+                if !to_deallocate.is_zero() {
+                    sim.deallocate(adapter, sim.liquidity_market, to_deallocate, timestamp)?;
+                }
+            }
+        }
+
+        if assets > sim.idle_assets {
+            return Err(SimError::NotEnoughLiquidity {
+                vault: sim.address,
+                remaining: (assets - sim.idle_assets).saturating_to::<u128>(),
+            });
+        }
+
+        sim.idle_assets -= assets;
+        sim.total_assets = zero_floor_sub(sim.total_assets, assets);
+        sim.total_supply -= shares;
+
+        Ok((sim, assets))
+    }
+
+    /// Moves idle assets into an adapter. Expects interest to be accrued to `timestamp`.
+    fn allocate(
+        &mut self,
+        adapter: Address,
+        market_id: Option<MarketId>,
+        assets: U256,
+        timestamp: u64,
+        first_total_assets: U256,
+    ) -> Result<(), SimError> {
+        if assets > self.idle_assets {
+            return Err(SimError::NotEnoughLiquidity {
+                vault: self.address,
+                remaining: (assets - self.idle_assets).saturating_to::<u128>(),
+            });
+        }
+
+        let index = self.adapter_index(adapter)?;
+        let VaultV2Simulation {
+            adapters, markets, ..
+        } = self;
+        match &mut adapters[index].kind {
+            VaultV2AdapterKind::MorphoMarketV1 { positions } => {
+                let market_id = market_id.ok_or(SimError::MissingAdapterMarket { adapter })?;
+                let position = positions
+                    .iter_mut()
+                    .find(|p| p.market_id == market_id)
+                    .ok_or(SimError::MarketNotFound { market_id })?;
+                let market = markets
+                    .get(&market_id)
+                    .ok_or(SimError::MarketNotFound { market_id })?;
+                let (new_market, shares) = market.supply(assets, timestamp)?;
+                markets.insert(market_id, new_market);
+                position.supply_shares += shares;
+            }
+            VaultV2AdapterKind::MetaMorpho { vault, shares } => {
+                let (new_vault, minted) = vault.simulate_deposit(assets, timestamp)?;
+                **vault = new_vault;
+                *shares += minted;
+            }
+            VaultV2AdapterKind::Fixed { assets: held } => *held += assets,
+        }
+        self.idle_assets -= assets;
+
+        self.check_caps(index, market_id, first_total_assets)
+    }
+
+    /// Moves assets from an adapter back to idle. Expects interest to be accrued to
+    /// `timestamp`.
+    fn deallocate(
+        &mut self,
+        adapter: Address,
+        market_id: Option<MarketId>,
+        assets: U256,
+        timestamp: u64,
+    ) -> Result<(), SimError> {
+        let index = self.adapter_index(adapter)?;
+        let vault_address = self.address;
+        let VaultV2Simulation {
+            adapters, markets, ..
+        } = self;
+        let received = match &mut adapters[index].kind {
+            VaultV2AdapterKind::MorphoMarketV1 { positions } => {
+                let market_id = market_id.ok_or(SimError::MissingAdapterMarket { adapter })?;
+                let position = positions
+                    .iter_mut()
+                    .find(|p| p.market_id == market_id)
+                    .ok_or(SimError::MarketNotFound { market_id })?;
+                if assets > position_assets(position, markets)? {
+                    return Err(SimError::InsufficientPosition {
+                        user: adapter,
+                        market_id,
+                    });
+                }
+                let market = markets
+                    .get(&market_id)
+                    .ok_or(SimError::MarketNotFound { market_id })?;
+                let (new_market, shares) = market.withdraw(assets, timestamp)?;
+                markets.insert(market_id, new_market);
+                position.supply_shares = zero_floor_sub(position.supply_shares, shares);
+                assets
+            }
+            VaultV2AdapterKind::MetaMorpho { vault, shares } => {
+                let burned = vault.vault.to_shares(assets, RoundingDirection::Up);
+                if burned > *shares {
+                    return Err(SimError::InsufficientPosition {
+                        user: adapter,
+                        market_id: vault.vault.address.into_word(),
+                    });
+                }
+                let (new_vault, withdrawn) = vault.simulate_withdraw(burned, timestamp)?;
+                **vault = new_vault;
+                *shares -= burned;
+                withdrawn
+            }
+            VaultV2AdapterKind::Fixed { .. } => {
+                return Err(SimError::NotEnoughLiquidity {
+                    vault: vault_address,
+                    remaining: assets.saturating_to::<u128>(),
+                });
+            }
+        };
+        self.idle_assets += received;
+        Ok(())
+    }
+
+    /// Checks every id touched by an allocation through the adapter at `index`
+    fn check_caps(
+        &self,
+        index: usize,
+        market_id: Option<MarketId>,
+        first_total_assets: U256,
+    ) -> Result<(), SimError> {
+        let adapter = &self.adapters[index];
+        let mut ids = adapter.cap_ids.clone();
+        if let (VaultV2AdapterKind::MorphoMarketV1 { positions }, Some(market_id)) =
+            (&adapter.kind, market_id)
+        {
+            if let Some(position) = positions.iter().find(|p| p.market_id == market_id) {
+                ids.extend(position.cap_ids.iter().copied());
+            }
+        }
+
+        for id in ids {
+            let (absolute_cap, relative_cap) =
+                self.caps.get(&id).map_or((U256::ZERO, U256::ZERO), |cap| {
+                    (cap.absolute_cap, cap.relative_cap)
+                });
+            let mut cap = absolute_cap;
+            if relative_cap < WAD {
+                cap = math::min(cap, mul_div_down(first_total_assets, relative_cap, WAD));
+            }
+
+            let allocation = self.allocation(id)?;
+            if allocation > cap {
+                return Err(SimError::IdCapExceeded {
+                    vault: self.address,
+                    id,
+                    allocation: allocation.saturating_to::<u128>(),
+                    cap: cap.saturating_to::<u128>(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{SECONDS_PER_YEAR, VIRTUAL_SHARES};
+    use crate::vault::{Vault, VaultMarketConfig};
+
+    const MARKET_ADAPTER: Address = Address::repeat_byte(0xA1);
+    const VAULT_ADAPTER: Address = Address::repeat_byte(0xA2);
+
+    /// A market at the initial exchange rate, so share conversions are exact
+    fn create_test_market(id: u8, total_supply: u64, total_borrow: u64) -> (MarketId, Market) {
+        let market_id = FixedBytes::from([id; 32]);
+        let market = Market::new(
+            market_id,
+            U256::from(total_supply) * WAD,
+            U256::from(total_borrow) * WAD,
+            U256::from(total_supply) * WAD * VIRTUAL_SHARES,
+            U256::from(total_borrow) * WAD * VIRTUAL_SHARES,
+            1000,
+            U256::from(100_000_000_000_000_000u64),
+            Some(U256::from(1_268_391_679u64)),
+        );
+        (market_id, market)
+    }
+
+    /// 100K idle, 400K supplied to market 1 through the liquidity adapter, 500K in a
+    /// MetaMorpho vault supplying to market 2. The market adapter is capped at 600K.
+    fn create_test_simulation() -> VaultV2Simulation {
+        let (market_id_1, market_1) = create_test_market(1, 1_000_000, 800_000);
+        let (market_id_2, market_2) = create_test_market(2, 1_000_000, 900_000);
+
+        let mut allocations = HashMap::new();
+        allocations.insert(
+            market_id_2,
+            VaultMarketConfig {
+                market_id: market_id_2,
+                cap: U256::from(1_000_000) * WAD,
+                supply_assets: U256::from(500_000) * WAD,
+                enabled: true,
+                public_allocator_config: None,
+            },
+        );
+        let meta_morpho = Vault {
+            address: Address::repeat_byte(0xBB),
+            asset_decimals: 18,
+            fee: U256::ZERO,
+            management_fee: U256::ZERO,
+            total_assets: U256::from(500_000) * WAD,
+            total_supply: U256::from(500_000) * WAD,
+            last_total_assets: U256::from(500_000) * WAD,
+            supply_queue: vec![market_id_2],
+            withdraw_queue: vec![market_id_2],
+            allocations,
+            owner: Address::ZERO,
+            public_allocator_config: None,
+        };
+        let meta_morpho =
+            VaultSimulation::new(meta_morpho, HashMap::from([(market_id_2, market_2)]));
+
+        let market_cap_id = FixedBytes::repeat_byte(0xC1);
+        let vault_cap_id = FixedBytes::repeat_byte(0xC2);
+
+        VaultV2Simulation {
+            address: Address::repeat_byte(0xAA),
+            asset_decimals: 18,
+            performance_fee: U256::from(100_000_000_000_000_000u64),
+            management_fee: U256::ZERO,
+            total_assets: U256::from(1_000_000) * WAD,
+            total_supply: U256::from(1_000_000) * WAD,
+            last_update: 1000,
+            idle_assets: U256::from(100_000) * WAD,
+            adapters: vec![
+                VaultV2Adapter {
+                    address: MARKET_ADAPTER,
+                    cap_ids: vec![market_cap_id],
+                    kind: VaultV2AdapterKind::MorphoMarketV1 {
+                        positions: vec![AdapterMarketPosition {
+                            market_id: market_id_1,
+                            supply_shares: U256::from(400_000) * WAD * VIRTUAL_SHARES,
+                            cap_ids: vec![],
+                        }],
+                    },
+                },
+                VaultV2Adapter {
+                    address: VAULT_ADAPTER,
+                    cap_ids: vec![vault_cap_id],
+                    kind: VaultV2AdapterKind::MetaMorpho {
+                        vault: Box::new(meta_morpho),
+                        shares: U256::from(500_000) * WAD,
+                    },
+                },
+            ],
+            liquidity_adapter: Some(MARKET_ADAPTER),
+            liquidity_market: Some(market_id_1),
+            caps: HashMap::from([
+                (
+                    market_cap_id,
+                    VaultV2Cap {
+                        absolute_cap: U256::from(600_000) * WAD,
+                        relative_cap: WAD,
+                    },
+                ),
+                (
+                    vault_cap_id,
+                    VaultV2Cap {
+                        absolute_cap: U256::MAX,
+                        relative_cap: WAD / U256::from(2),
+                    },
+                ),
+            ]),
+            markets: HashMap::from([(market_id_1, market_1)]),
+        }
+    }
+
+    #[test]
+    fn test_real_assets_and_allocation() {
+        let sim = create_test_simulation();
+        assert_eq!(sim.real_assets().unwrap(), U256::from(1_000_000) * WAD);
+        assert_eq!(
+            sim.allocation(FixedBytes::repeat_byte(0xC1)).unwrap(),
+            U256::from(400_000) * WAD
+        );
+        assert_eq!(
+            sim.allocation(FixedBytes::repeat_byte(0xC2)).unwrap(),
+            U256::from(500_000) * WAD
+        );
+    }
+
+    #[test]
+    fn test_simulate_deposit_allocates_to_liquidity_adapter() {
+        let sim = create_test_simulation();
+        let deposit = U256::from(100_000) * WAD;
+
+        let (new_sim, shares) = sim.simulate_deposit(deposit, 1000).unwrap();
+
+        assert_eq!(shares, sim.to_shares(deposit, RoundingDirection::Down));
+        assert_eq!(new_sim.idle_assets, sim.idle_assets);
+        assert_eq!(new_sim.total_assets, sim.total_assets + deposit);
+        assert_eq!(
+            new_sim.allocation(FixedBytes::repeat_byte(0xC1)).unwrap(),
+            U256::from(500_000) * WAD
+        );
+    }
+
+    #[test]
+    fn test_simulate_deposit_without_liquidity_adapter_stays_idle() {
+        let mut sim = create_test_simulation();
+        sim.liquidity_adapter = None;
+        let deposit = U256::from(100_000) * WAD;
+
+        let (new_sim, _) = sim.simulate_deposit(deposit, 1000).unwrap();
+
+        assert_eq!(new_sim.idle_assets, sim.idle_assets + deposit);
+        assert_eq!(
+            new_sim.allocation(FixedBytes::repeat_byte(0xC1)).unwrap(),
+            U256::from(400_000) * WAD
+        );
+    }
+
+    #[test]
+    fn test_simulate_deposit_respects_absolute_cap() {
+        let sim = create_test_simulation();
+
+        let result = sim.simulate_deposit(U256::from(300_000) * WAD, 1000);
+
+        assert!(matches!(
+            result,
+            Err(SimError::IdCapExceeded { id, cap, .. })
+                if id == FixedBytes::repeat_byte(0xC1) && cap == 600_000 * WAD.to::<u128>()
+        ));
+    }
+
+    #[test]
+    fn test_simulate_allocate_respects_relative_cap() {
+        let sim = create_test_simulation();
+
+        // The vault adapter already holds its 50% of 1M total assets
+        let result = sim.simulate_allocate(VAULT_ADAPTER, None, WAD, 1000);
+        assert!(matches!(result, Err(SimError::IdCapExceeded { .. })));
+
+        // Deallocating ignores caps and returns assets to idle
+        let new_sim = sim
+            .simulate_deallocate(VAULT_ADAPTER, None, U256::from(100_000) * WAD, 1000)
+            .unwrap();
+        assert_eq!(new_sim.idle_assets, U256::from(200_000) * WAD);
+        assert_eq!(new_sim.total_assets, sim.total_assets);
+    }
+
+    #[test]
+    fn test_simulate_allocate_unknown_adapter() {
+        let sim = create_test_simulation();
+        let result = sim.simulate_allocate(Address::ZERO, None, WAD, 1000);
+        assert!(matches!(result, Err(SimError::AdapterNotFound { .. })));
+
+        let result = sim.simulate_allocate(MARKET_ADAPTER, None, WAD, 1000);
+        assert!(matches!(result, Err(SimError::MissingAdapterMarket { .. })));
+    }
+
+    #[test]
+    fn test_simulate_withdraw_uses_idle_then_liquidity_adapter() {
+        let sim = create_test_simulation();
+
+        // Served entirely from idle
+        let (new_sim, assets) = sim
+            .simulate_withdraw(U256::from(50_000) * WAD, 1000)
+            .unwrap();
+        assert_eq!(new_sim.idle_assets, sim.idle_assets - assets);
+        assert_eq!(
+            new_sim.allocation(FixedBytes::repeat_byte(0xC1)).unwrap(),
+            U256::from(400_000) * WAD
+        );
+
+        // Idle plus 100K deallocated from market 1 (which has 200K liquidity)
+        let (new_sim, assets) = sim
+            .simulate_withdraw(U256::from(200_000) * WAD, 1000)
+            .unwrap();
+        assert_eq!(assets, U256::from(200_000) * WAD);
+        assert_eq!(new_sim.idle_assets, U256::ZERO);
+        assert_eq!(
+            new_sim.allocation(FixedBytes::repeat_byte(0xC1)).unwrap(),
+            U256::from(300_000) * WAD
+        );
+
+        // The MetaMorpho adapter is never used for withdrawals
+        assert_eq!(sim.max_withdraw().unwrap(), U256::from(300_000) * WAD);
+        let result = sim.simulate_withdraw(U256::from(400_000) * WAD, 1000);
+        assert!(matches!(result, Err(SimError::NotEnoughLiquidity { .. })));
+    }
+
+    #[test]
+    fn test_fee_accrual_splits_performance_and_management_fees() {
+        let mut sim = create_test_simulation();
+        sim.management_fee = MAX_MANAGEMENT_FEE;
+        let one_year = 1000 + SECONDS_PER_YEAR;
+
+        let accrual = sim.fee_accrual(one_year).unwrap();
+        assert!(accrual.interest > U256::ZERO);
+        assert_eq!(
+            accrual.performance_fee_assets,
+            w_mul_down(accrual.interest, sim.performance_fee)
+        );
+        // ~5% of total assets after a year
+        let management_fraction = accrual.management_fee_assets.saturating_to::<u128>() as f64
+            / sim.total_assets.saturating_to::<u128>() as f64;
+        assert!(management_fraction > 0.05 && management_fraction < 0.06);
+
+        let accrued = sim.accrue_interest(one_year).unwrap();
+        assert_eq!(
+            accrued.total_supply,
+            sim.total_supply + accrual.performance_fee_shares + accrual.management_fee_shares
+        );
+        assert_eq!(accrued.last_update, one_year);
+        assert!(sim.accrue_interest(999).is_err());
+    }
+
+    #[test]
+    fn test_net_apy_below_gross_apy() {
+        let mut sim = create_test_simulation();
+        sim.management_fee = MAX_MANAGEMENT_FEE;
+
+        let gross = sim.get_apy(1000).unwrap();
+        let net = sim.get_net_apy(1000).unwrap();
+
+        assert!(gross > 0.0);
+        assert!(net < gross * 0.9);
+    }
+}
@@ -0,0 +1,196 @@
+//! Property tests: market and vault operations preserve `check_invariants`.
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, FixedBytes, U256};
+use morpho_rs_sim::math::VIRTUAL_SHARES;
+use morpho_rs_sim::{
+    Market, RoundingDirection, SimError, Vault, VaultMarketConfig, VaultSimulation,
+    INITIAL_RATE_AT_TARGET, WAD,
+};
+use proptest::prelude::*;
+
+const START: u64 = 1_704_067_200;
+
+/// Up to 30 days between operations.
+const MAX_WAIT_SECS: u64 = 30 * 86_400;
+
+#[derive(Debug, Clone)]
+enum MarketOp {
+    /// Supply whole tokens.
+    Supply(u64),
+    /// Withdraw a percentage of the market's liquidity.
+    Withdraw(u8),
+    /// Borrow a percentage of the market's liquidity.
+    Borrow(u8),
+    /// Repay a percentage of the market's debt.
+    Repay(u8),
+    /// Let interest accrue.
+    Wait(u64),
+}
+
+#[derive(Debug, Clone)]
+enum VaultOp {
+    /// Deposit whole tokens.
+    Deposit(u64),
+    /// Redeem a percentage of the vault's shares.
+    Withdraw(u8),
+    /// Let interest accrue.
+    Wait(u64),
+}
+
+fn percent(amount: U256, pct: u8) -> U256 {
+    amount * U256::from(pct) / U256::from(100)
+}
+
+/// A market at the initial exchange rate of `VIRTUAL_SHARES` shares per asset.
+fn build_market(id: u8, supply_tokens: u64, utilization_pct: u8, fee_pct: u8) -> Market {
+    let supply = U256::from(supply_tokens) * WAD;
+    let borrow = percent(supply, utilization_pct);
+    Market::builder(FixedBytes::repeat_byte(id))
+        .with_supply_assets(supply)
+        .with_borrow_assets(borrow)
+        .with_supply_shares(supply * VIRTUAL_SHARES)
+        .with_borrow_shares(borrow * VIRTUAL_SHARES)
+        .with_fee(percent(WAD, fee_pct))
+        .with_rate_at_target(INITIAL_RATE_AT_TARGET)
+        .with_last_update(START)
+        .build()
+        .unwrap()
+}
+
+fn market_strategy() -> impl Strategy<Value = Market> {
+    (1u64..10_000_000, 0u8..=95, 0u8..=25)
+        .prop_map(|(supply, utilization, fee)| build_market(1, supply, utilization, fee))
+}
+
+fn market_op_strategy() -> impl Strategy<Value = MarketOp> {
+    prop_oneof![
+        (1u64..1_000_000).prop_map(MarketOp::Supply),
+        (0u8..=100).prop_map(MarketOp::Withdraw),
+        (0u8..=100).prop_map(MarketOp::Borrow),
+        (0u8..=100).prop_map(MarketOp::Repay),
+        (0..MAX_WAIT_SECS).prop_map(MarketOp::Wait),
+    ]
+}
+
+/// A vault supplying to one to three markets. Each market has at least as much
+/// external supply as the vault's cap, so the vault never owns the whole market.
+fn simulation_strategy() -> impl Strategy<Value = VaultSimulation> {
+    prop::collection::vec((1u64..1_000_000, 0u8..=90, 0u64..1_000_000), 1..=3).prop_map(|specs| {
+        let mut markets = HashMap::new();
+        let mut vault = Vault::builder(Address::repeat_byte(0xAA)).with_fee(percent(WAD, 10));
+        let mut total_assets = U256::ZERO;
+        for (i, (external, utilization, allocation)) in specs.into_iter().enumerate() {
+            let allocation = allocation.min(external);
+            let market = build_market(i as u8 + 1, external + allocation, utilization, 10);
+            vault = vault.with_market(VaultMarketConfig {
+                market_id: market.id,
+                cap: U256::from(external) * WAD,
+                supply_assets: U256::from(allocation) * WAD,
+                enabled: true,
+                public_allocator_config: None,
+            });
+            total_assets += U256::from(allocation) * WAD;
+            markets.insert(market.id, market);
+        }
+        let vault = vault.with_total_supply(total_assets).build().unwrap();
+        VaultSimulation::new(vault, markets)
+    })
+}
+
+fn vault_op_strategy() -> impl Strategy<Value = VaultOp> {
+    prop_oneof![
+        (1u64..1_000_000).prop_map(VaultOp::Deposit),
+        (0u8..=100).prop_map(VaultOp::Withdraw),
+        (0..MAX_WAIT_SECS).prop_map(VaultOp::Wait),
+    ]
+}
+
+/// Apply `op` at `timestamp`, or return `None` if the operation is invalid for the
+/// current state (it would burn more shares than exist).
+fn apply_market_op(market: &Market, op: &MarketOp, timestamp: u64) -> Option<Market> {
+    let accrued = market.accrue_interest(timestamp).unwrap();
+    let next = match *op {
+        MarketOp::Supply(tokens) => accrued.supply(U256::from(tokens) * WAD, timestamp),
+        MarketOp::Withdraw(pct) => {
+            let assets = percent(accrued.liquidity(), pct);
+            if accrued.to_supply_shares(assets, RoundingDirection::Up) > accrued.total_supply_shares
+            {
+                return None;
+            }
+            accrued.withdraw(assets, timestamp)
+        }
+        MarketOp::Borrow(pct) => accrued.borrow(percent(accrued.liquidity(), pct), timestamp),
+        MarketOp::Repay(pct) => {
+            let assets = percent(accrued.total_borrow_assets, pct);
+            if accrued.to_borrow_shares(assets, RoundingDirection::Down)
+                > accrued.total_borrow_shares
+            {
+                return None;
+            }
+            accrued.repay(assets, timestamp)
+        }
+        MarketOp::Wait(_) => return Some(accrued),
+    };
+    Some(next.unwrap().0)
+}
+
+proptest! {
+    #[test]
+    fn market_operations_preserve_invariants(
+        market in market_strategy(),
+        ops in prop::collection::vec(market_op_strategy(), 1..20),
+    ) {
+        prop_assert!(market.check_invariants().is_ok());
+
+        let mut market = market;
+        let mut timestamp = START;
+        for op in &ops {
+            if let MarketOp::Wait(secs) = op {
+                timestamp += secs;
+            }
+            let Some(next) = apply_market_op(&market, op, timestamp) else {
+                continue;
+            };
+            let result = next.check_invariants();
+            prop_assert!(result.is_ok(), "{:?} after {:?}", result, op);
+            market = next;
+        }
+    }
+
+    #[test]
+    fn vault_operations_preserve_invariants(
+        simulation in simulation_strategy(),
+        ops in prop::collection::vec(vault_op_strategy(), 1..20),
+    ) {
+        prop_assert!(simulation.check_invariants().is_ok());
+
+        let mut simulation = simulation;
+        let mut timestamp = START;
+        for op in &ops {
+            let result = match *op {
+                VaultOp::Deposit(tokens) => simulation
+                    .simulate_deposit(U256::from(tokens) * WAD, timestamp)
+                    .map(|(sim, _)| sim),
+                VaultOp::Withdraw(pct) => simulation
+                    .simulate_withdraw(percent(simulation.vault.total_supply, pct), timestamp)
+                    .map(|(sim, _)| sim),
+                VaultOp::Wait(secs) => {
+                    timestamp += secs;
+                    simulation.accrue_interest(timestamp)
+                }
+            };
+            let next = match result {
+                Ok(next) => next,
+                Err(SimError::AllCapsReached { .. } | SimError::NotEnoughLiquidity { .. }) => {
+                    continue;
+                }
+                Err(e) => return Err(TestCaseError::fail(format!("{e} after {op:?}"))),
+            };
+            let result = next.check_invariants();
+            prop_assert!(result.is_ok(), "{:?} after {:?}", result, op);
+            simulation = next;
+        }
+    }
+}
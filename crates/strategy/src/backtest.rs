@@ -0,0 +1,657 @@
+//! Backtesting harness for vault allocation strategies.
+//!
+//! A backtest replays a chronological series of [`Snapshot`]s through a [`Strategy`].
+//! On every tick the strategy sees the snapshot and returns [`Action`]s, which the
+//! harness executes against a simulated portfolio of idle cash and vault shares. Vault
+//! positions are marked to each snapshot's share price, so interest (net of the vault's
+//! performance fee) accrues through share price growth.
+//!
+//! Amounts are in units of the common underlying asset (e.g. USDC, not its smallest
+//! unit). Each executed action pays [`BacktestConfig::fee_rate`] on the amount moved and
+//! a flat [`BacktestConfig::gas_cost`].
+//!
+//! Snapshots can be built from vault lists recorded from the API
+//! ([`Snapshot::from_vaults_v1`]) or, with the `sim` feature, from on-chain block
+//! snapshots taken with `morpho_rs_api::SnapshotClient`
+//! ([`Snapshot::from_vault_snapshots`]).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_strategy::backtest::{Action, Backtest, BacktestConfig, Snapshot, Strategy};
+//!
+//! /// Keep everything in the highest-APY vault.
+//! struct ChaseApy;
+//!
+//! impl Strategy for ChaseApy {
+//!     fn on_tick(&mut self, snapshot: &Snapshot) -> Vec<Action> {
+//!         let Some(best) = snapshot.vaults.values().max_by(|a, b| a.net_apy.total_cmp(&b.net_apy))
+//!         else {
+//!             return Vec::new();
+//!         };
+//!         let mut actions: Vec<Action> = snapshot
+//!             .vaults
+//!             .keys()
+//!             .filter(|&&v| v != best.address)
+//!             .map(|&vault| Action::WithdrawAll { vault })
+//!             .collect();
+//!         actions.push(Action::DepositAll { vault: best.address });
+//!         actions
+//!     }
+//! }
+//!
+//! let snapshots: Vec<Snapshot> = Vec::new(); // recorded vault states
+//! let result = Backtest::new(BacktestConfig::new(10_000.0).with_gas_cost(2.0))
+//!     .run(&mut ChaseApy, &snapshots);
+//! println!(
+//!     "CAGR {:.2}%, max drawdown {:.2}%, Sharpe {:.2}",
+//!     result.stats.cagr * 100.0,
+//!     result.stats.max_drawdown * 100.0,
+//!     result.stats.sharpe_ratio
+//! );
+//! ```
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, U256};
+use morpho_rs_api::VaultV1;
+use serde::{Deserialize, Serialize};
+
+use crate::risk::u256_to_f64;
+
+/// Seconds in a (365-day) year, used to annualize returns.
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// State of one vault at a tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VaultObservation {
+    /// Vault address.
+    pub address: Address,
+    /// Underlying assets per whole vault share.
+    pub share_price: f64,
+    /// Net APY after fees (as a fraction).
+    pub net_apy: f64,
+}
+
+/// Vault states observed at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Unix timestamp of the observation.
+    pub timestamp: u64,
+    /// Block number, if the snapshot was read on-chain.
+    pub block: Option<u64>,
+    /// Observed vaults by address.
+    pub vaults: HashMap<Address, VaultObservation>,
+}
+
+impl Snapshot {
+    /// Create an empty snapshot at `timestamp`.
+    pub fn new(timestamp: u64) -> Self {
+        Self {
+            timestamp,
+            ..Self::default()
+        }
+    }
+
+    /// Set the block number the snapshot was read at.
+    pub fn with_block(mut self, block: u64) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Add or replace a vault observation.
+    pub fn with_vault(mut self, observation: VaultObservation) -> Self {
+        self.vaults.insert(observation.address, observation);
+        self
+    }
+
+    /// Build a snapshot from V1 vaults fetched from the API at `timestamp`.
+    ///
+    /// Vaults without state are skipped. The share price is derived from total assets
+    /// and total supply (MetaMorpho shares have 18 decimals).
+    pub fn from_vaults_v1(timestamp: u64, vaults: &[VaultV1]) -> Self {
+        let mut snapshot = Self::new(timestamp);
+        for vault in vaults {
+            let Some(state) = &vault.state else {
+                continue;
+            };
+            snapshot = snapshot.with_vault(VaultObservation {
+                address: vault.address,
+                share_price: share_price(
+                    state.total_assets,
+                    state.total_supply,
+                    vault.asset.decimals,
+                ),
+                net_apy: state.net_apy,
+            });
+        }
+        snapshot
+    }
+
+    /// Build one snapshot per block from on-chain vault snapshots.
+    ///
+    /// Vault snapshots taken at the same block are merged into one [`Snapshot`]. The net
+    /// APY is the simulation's instantaneous net APY at the block timestamp (zero if it
+    /// cannot be computed).
+    ///
+    /// # Feature Flag
+    ///
+    /// This method is only available when the `sim` feature is enabled.
+    #[cfg(feature = "sim")]
+    pub fn from_vault_snapshots(snapshots: &[morpho_rs_api::VaultSnapshot]) -> Vec<Self> {
+        let mut by_block: std::collections::BTreeMap<u64, Self> = std::collections::BTreeMap::new();
+        for vault_snapshot in snapshots {
+            let vault = &vault_snapshot.simulation.vault;
+            let observation = VaultObservation {
+                address: vault.address,
+                share_price: share_price(
+                    vault.total_assets,
+                    vault.total_supply,
+                    vault.asset_decimals,
+                ),
+                net_apy: vault_snapshot
+                    .simulation
+                    .get_net_apy(vault_snapshot.timestamp)
+                    .unwrap_or(0.0),
+            };
+            let snapshot = by_block
+                .remove(&vault_snapshot.block)
+                .unwrap_or_else(|| {
+                    Self::new(vault_snapshot.timestamp).with_block(vault_snapshot.block)
+                })
+                .with_vault(observation);
+            by_block.insert(vault_snapshot.block, snapshot);
+        }
+        by_block.into_values().collect()
+    }
+}
+
+/// An allocation change requested by a strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    /// Deposit `assets` of idle cash into a vault (capped at available cash).
+    Deposit {
+        /// Target vault.
+        vault: Address,
+        /// Amount of underlying assets to deposit.
+        assets: f64,
+    },
+    /// Deposit all idle cash into a vault.
+    DepositAll {
+        /// Target vault.
+        vault: Address,
+    },
+    /// Withdraw `assets` from a vault to idle cash (capped at the position's value).
+    Withdraw {
+        /// Source vault.
+        vault: Address,
+        /// Amount of underlying assets to withdraw.
+        assets: f64,
+    },
+    /// Withdraw the whole position in a vault.
+    WithdrawAll {
+        /// Source vault.
+        vault: Address,
+    },
+}
+
+/// A vault allocation strategy driven by snapshots.
+pub trait Strategy {
+    /// Decide the actions to take at a tick.
+    ///
+    /// Actions are executed in order at the snapshot's share prices.
+    fn on_tick(&mut self, snapshot: &Snapshot) -> Vec<Action>;
+}
+
+/// Costs and starting capital of a backtest.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    /// Idle cash at the start of the backtest.
+    pub initial_balance: f64,
+    /// Fee charged on the amount moved by each action (as a fraction).
+    pub fee_rate: f64,
+    /// Flat gas cost of each executed action, paid from idle cash.
+    pub gas_cost: f64,
+    /// Annual risk-free rate subtracted from returns in the Sharpe ratio.
+    pub risk_free_rate: f64,
+}
+
+impl BacktestConfig {
+    /// Create a config with no fees, gas, or risk-free rate.
+    pub fn new(initial_balance: f64) -> Self {
+        Self {
+            initial_balance,
+            fee_rate: 0.0,
+            gas_cost: 0.0,
+            risk_free_rate: 0.0,
+        }
+    }
+
+    /// Set the fee charged on the amount moved by each action.
+    pub fn with_fee_rate(mut self, fee_rate: f64) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    /// Set the flat gas cost of each executed action.
+    pub fn with_gas_cost(mut self, gas_cost: f64) -> Self {
+        self.gas_cost = gas_cost;
+        self
+    }
+
+    /// Set the annual risk-free rate used in the Sharpe ratio.
+    pub fn with_risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+}
+
+/// Portfolio value at a tick, after the tick's actions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    /// Unix timestamp of the tick.
+    pub timestamp: u64,
+    /// Idle cash plus the marked value of all vault positions.
+    pub equity: f64,
+}
+
+/// Performance statistics of an equity curve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceStats {
+    /// Total return over the backtest (as a fraction).
+    pub total_return: f64,
+    /// Compound annual growth rate (as a fraction).
+    pub cagr: f64,
+    /// Largest peak-to-trough decline of equity (as a positive fraction).
+    pub max_drawdown: f64,
+    /// Annualized Sharpe ratio of per-tick returns.
+    pub sharpe_ratio: f64,
+}
+
+impl PerformanceStats {
+    /// Compute statistics from an equity curve.
+    ///
+    /// Returns all zeros for curves with fewer than two points or a non-positive
+    /// starting equity. The Sharpe ratio annualizes per-tick returns using the average
+    /// tick interval and is zero when returns have no variance.
+    pub fn from_equity_curve(curve: &[EquityPoint], risk_free_rate: f64) -> Self {
+        let (Some(first), Some(last)) = (curve.first(), curve.last()) else {
+            return Self::default();
+        };
+        if curve.len() < 2 || first.equity <= 0.0 {
+            return Self::default();
+        }
+
+        let total_return = last.equity / first.equity - 1.0;
+        let years = last.timestamp.saturating_sub(first.timestamp) as f64 / SECONDS_PER_YEAR;
+        let cagr = if years > 0.0 && last.equity > 0.0 {
+            (last.equity / first.equity).powf(1.0 / years) - 1.0
+        } else {
+            0.0
+        };
+
+        Self {
+            total_return,
+            cagr,
+            max_drawdown: max_drawdown(curve),
+            sharpe_ratio: sharpe_ratio(curve, years, risk_free_rate),
+        }
+    }
+}
+
+/// Outcome of a backtest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestResult {
+    /// Equity after each tick.
+    pub equity_curve: Vec<EquityPoint>,
+    /// Equity after the last tick.
+    pub final_equity: f64,
+    /// Idle cash after the last tick.
+    pub final_cash: f64,
+    /// Vault shares held after the last tick.
+    pub final_positions: HashMap<Address, f64>,
+    /// Total fees paid on moved amounts.
+    pub total_fees: f64,
+    /// Total gas paid.
+    pub total_gas: f64,
+    /// Number of actions executed.
+    pub actions_executed: usize,
+    /// Number of actions skipped (unknown vault, nothing to move, or cash below gas).
+    pub actions_skipped: usize,
+    /// Performance statistics of the equity curve.
+    pub stats: PerformanceStats,
+}
+
+/// Replays snapshots through a [`Strategy`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backtest {
+    config: BacktestConfig,
+}
+
+impl Backtest {
+    /// Create a backtest with the given configuration.
+    pub fn new(config: BacktestConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &BacktestConfig {
+        &self.config
+    }
+
+    /// Run `strategy` over `snapshots`, which must be in chronological order.
+    ///
+    /// Vaults missing from a snapshot keep their last observed share price.
+    pub fn run<S: Strategy>(&self, strategy: &mut S, snapshots: &[Snapshot]) -> BacktestResult {
+        let mut portfolio = Portfolio {
+            cash: self.config.initial_balance,
+            ..Portfolio::default()
+        };
+        let mut equity_curve = Vec::with_capacity(snapshots.len());
+
+        for snapshot in snapshots {
+            for (address, vault) in &snapshot.vaults {
+                portfolio.prices.insert(*address, vault.share_price);
+            }
+            for action in strategy.on_tick(snapshot) {
+                portfolio.execute(action, &self.config);
+            }
+            equity_curve.push(EquityPoint {
+                timestamp: snapshot.timestamp,
+                equity: portfolio.equity(),
+            });
+        }
+
+        let stats = PerformanceStats::from_equity_curve(&equity_curve, self.config.risk_free_rate);
+        BacktestResult {
+            final_equity: portfolio.equity(),
+            final_cash: portfolio.cash,
+            final_positions: portfolio.shares,
+            equity_curve,
+            total_fees: portfolio.fees,
+            total_gas: portfolio.gas,
+            actions_executed: portfolio.executed,
+            actions_skipped: portfolio.skipped,
+            stats,
+        }
+    }
+}
+
+/// Simulated holdings during a backtest.
+#[derive(Debug, Default)]
+struct Portfolio {
+    cash: f64,
+    shares: HashMap<Address, f64>,
+    prices: HashMap<Address, f64>,
+    fees: f64,
+    gas: f64,
+    executed: usize,
+    skipped: usize,
+}
+
+impl Portfolio {
+    fn equity(&self) -> f64 {
+        self.cash
+            + self
+                .shares
+                .iter()
+                .map(|(vault, shares)| shares * self.prices.get(vault).copied().unwrap_or(0.0))
+                .sum::<f64>()
+    }
+
+    fn position_value(&self, vault: &Address, price: f64) -> f64 {
+        self.shares.get(vault).copied().unwrap_or(0.0) * price
+    }
+
+    fn execute(&mut self, action: Action, config: &BacktestConfig) {
+        let (vault, deposit, requested) = match action {
+            Action::Deposit { vault, assets } => (vault, true, assets),
+            Action::DepositAll { vault } => (vault, true, f64::INFINITY),
+            Action::Withdraw { vault, assets } => (vault, false, assets),
+            Action::WithdrawAll { vault } => (vault, false, f64::INFINITY),
+        };
+        let Some(price) = self.prices.get(&vault).copied().filter(|p| *p > 0.0) else {
+            self.skipped += 1;
+            return;
+        };
+
+        let available = if deposit {
+            self.cash - config.gas_cost
+        } else {
+            self.position_value(&vault, price)
+        };
+        let assets = requested.min(available);
+        let fee = assets * config.fee_rate;
+        let cash_after = if deposit {
+            self.cash - assets
+        } else {
+            self.cash + assets - fee
+        };
+        if assets.is_nan() || assets <= 0.0 || cash_after < config.gas_cost {
+            self.skipped += 1;
+            return;
+        }
+
+        let shares = self.shares.entry(vault).or_insert(0.0);
+        if deposit {
+            *shares += (assets - fee) / price;
+            self.cash -= assets;
+        } else {
+            *shares = (*shares - assets / price).max(0.0);
+            self.cash += assets - fee;
+        }
+        self.cash -= config.gas_cost;
+        self.fees += fee;
+        self.gas += config.gas_cost;
+        self.executed += 1;
+    }
+}
+
+/// Underlying assets per whole vault share (18-decimal shares).
+fn share_price(total_assets: U256, total_supply: U256, asset_decimals: u8) -> f64 {
+    if total_supply.is_zero() {
+        return 1.0;
+    }
+    let assets = u256_to_f64(total_assets) / 10f64.powi(asset_decimals as i32);
+    let shares = u256_to_f64(total_supply) / 1e18;
+    assets / shares
+}
+
+fn max_drawdown(curve: &[EquityPoint]) -> f64 {
+    let mut peak = f64::MIN;
+    let mut max_drawdown: f64 = 0.0;
+    for point in curve {
+        peak = peak.max(point.equity);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - point.equity) / peak);
+        }
+    }
+    max_drawdown
+}
+
+fn sharpe_ratio(curve: &[EquityPoint], years: f64, risk_free_rate: f64) -> f64 {
+    let returns: Vec<f64> = curve
+        .windows(2)
+        .filter(|w| w[0].equity > 0.0)
+        .map(|w| w[1].equity / w[0].equity - 1.0)
+        .collect();
+    if returns.len() < 2 || years <= 0.0 {
+        return 0.0;
+    }
+
+    let n = returns.len() as f64;
+    let periods_per_year = n / years;
+    let excess: Vec<f64> = returns
+        .iter()
+        .map(|r| r - risk_free_rate / periods_per_year)
+        .collect();
+    let mean = excess.iter().sum::<f64>() / n;
+    let variance = excess.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let std_dev = variance.sqrt();
+    if std_dev < 1e-12 {
+        return 0.0;
+    }
+    mean / std_dev * periods_per_year.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn vault(byte: u8) -> Address {
+        Address::repeat_byte(byte)
+    }
+
+    fn snapshot(day: u64, prices: &[(u8, f64, f64)]) -> Snapshot {
+        prices.iter().fold(
+            Snapshot::new(day * DAY),
+            |s, &(byte, share_price, net_apy)| {
+                s.with_vault(VaultObservation {
+                    address: vault(byte),
+                    share_price,
+                    net_apy,
+                })
+            },
+        )
+    }
+
+    /// Deposits everything into one vault on the first tick.
+    struct BuyAndHold(Address);
+
+    impl Strategy for BuyAndHold {
+        fn on_tick(&mut self, _snapshot: &Snapshot) -> Vec<Action> {
+            vec![Action::DepositAll { vault: self.0 }]
+        }
+    }
+
+    /// Moves everything into the highest-APY vault on every tick.
+    struct ChaseApy;
+
+    impl Strategy for ChaseApy {
+        fn on_tick(&mut self, snapshot: &Snapshot) -> Vec<Action> {
+            let best = snapshot
+                .vaults
+                .values()
+                .max_by(|a, b| a.net_apy.total_cmp(&b.net_apy))
+                .unwrap()
+                .address;
+            let mut actions: Vec<Action> = snapshot
+                .vaults
+                .keys()
+                .filter(|&&v| v != best)
+                .map(|&vault| Action::WithdrawAll { vault })
+                .collect();
+            actions.push(Action::DepositAll { vault: best });
+            actions
+        }
+    }
+
+    #[test]
+    fn test_buy_and_hold_tracks_share_price() {
+        let snapshots = vec![
+            snapshot(0, &[(1, 1.0, 0.05)]),
+            snapshot(365, &[(1, 1.1, 0.05)]),
+        ];
+        let result =
+            Backtest::new(BacktestConfig::new(1000.0)).run(&mut BuyAndHold(vault(1)), &snapshots);
+
+        assert!((result.final_equity - 1100.0).abs() < 1e-9);
+        assert!((result.stats.total_return - 0.1).abs() < 1e-9);
+        assert!((result.stats.cagr - 0.1).abs() < 1e-9);
+        assert_eq!(result.stats.max_drawdown, 0.0);
+        // Second DepositAll has no cash left to move.
+        assert_eq!(result.actions_executed, 1);
+        assert_eq!(result.actions_skipped, 1);
+    }
+
+    #[test]
+    fn test_fees_and_gas_are_charged() {
+        let snapshots = vec![snapshot(0, &[(1, 1.0, 0.05)])];
+        let config = BacktestConfig::new(1000.0)
+            .with_fee_rate(0.001)
+            .with_gas_cost(10.0);
+        let result = Backtest::new(config).run(&mut BuyAndHold(vault(1)), &snapshots);
+
+        // 990 deposited after gas, 0.99 of it taken as fee.
+        assert!((result.total_gas - 10.0).abs() < 1e-9);
+        assert!((result.total_fees - 0.99).abs() < 1e-9);
+        assert!((result.final_equity - 989.01).abs() < 1e-9);
+        assert!(result.final_cash.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_chase_apy_rebalances() {
+        let snapshots = vec![
+            snapshot(0, &[(1, 1.0, 0.05), (2, 1.0, 0.03)]),
+            snapshot(1, &[(1, 1.01, 0.02), (2, 1.0, 0.06)]),
+        ];
+        let result = Backtest::new(BacktestConfig::new(100.0)).run(&mut ChaseApy, &snapshots);
+
+        assert!((result.final_equity - 101.0).abs() < 1e-9);
+        assert!(result.final_positions[&vault(1)].abs() < 1e-9);
+        assert!((result.final_positions[&vault(2)] - 101.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unknown_vault_is_skipped() {
+        let snapshots = vec![snapshot(0, &[(1, 1.0, 0.05)])];
+        let result =
+            Backtest::new(BacktestConfig::new(100.0)).run(&mut BuyAndHold(vault(9)), &snapshots);
+
+        assert_eq!(result.actions_skipped, 1);
+        assert_eq!(result.final_equity, 100.0);
+    }
+
+    #[test]
+    fn test_max_drawdown() {
+        let curve: Vec<EquityPoint> = [100.0, 120.0, 90.0, 130.0, 117.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &equity)| EquityPoint {
+                timestamp: i as u64 * DAY,
+                equity,
+            })
+            .collect();
+        let stats = PerformanceStats::from_equity_curve(&curve, 0.0);
+        assert!((stats.max_drawdown - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sharpe_ratio_sign_and_degenerate_cases() {
+        let steady: Vec<EquityPoint> = (0..10)
+            .map(|i| EquityPoint {
+                timestamp: i * DAY,
+                equity: 100.0 * 1.001f64.powi(i as i32),
+            })
+            .collect();
+        // Constant returns have no variance.
+        assert_eq!(
+            PerformanceStats::from_equity_curve(&steady, 0.0).sharpe_ratio,
+            0.0
+        );
+
+        let noisy: Vec<EquityPoint> = [100.0, 101.0, 101.5, 103.0, 103.2, 105.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &equity)| EquityPoint {
+                timestamp: i as u64 * DAY,
+                equity,
+            })
+            .collect();
+        assert!(PerformanceStats::from_equity_curve(&noisy, 0.0).sharpe_ratio > 0.0);
+        assert_eq!(
+            PerformanceStats::from_equity_curve(&noisy[..1], 0.0),
+            PerformanceStats::default()
+        );
+    }
+
+    #[test]
+    fn test_share_price() {
+        // 1.05 USDC per 18-decimal share
+        let price = share_price(U256::from(1_050_000u64), U256::from(10u64.pow(18)), 6);
+        assert!((price - 1.05).abs() < 1e-12);
+        assert_eq!(share_price(U256::ZERO, U256::ZERO, 6), 1.0);
+    }
+}
@@ -0,0 +1,91 @@
+//! Break-even and net-of-gas yield analysis for moving funds between vaults.
+//!
+//! Moving a position pays gas once, while the extra yield of the new vault accrues over
+//! time. [`break_even_horizon`] is the number of days the extra yield needs to pay for
+//! the gas, and [`net_of_gas_apy`] spreads the gas over a holding period so a move can be
+//! compared with staying put on a single APY. The [`MigrationPlanner`] skips moves that
+//! do not break even within [`DEFAULT_BREAK_EVEN_DAYS`] unless configured otherwise.
+//!
+//! Amounts and gas costs only need a common unit (usually USD).
+//!
+//! # Example
+//!
+//! ```
+//! use morpho_rs_strategy::breakeven::{break_even_horizon, net_of_gas_apy};
+//!
+//! // +1% APY on $10,000 pays for $2.50 of gas in about 9 days
+//! let days = break_even_horizon(0.04, 0.05, 10_000.0, 2.5).unwrap();
+//! assert!((days - 9.125).abs() < 1e-9);
+//!
+//! // Held for 30 days, the gas costs about 0.3% APY
+//! let net = net_of_gas_apy(0.05, 10_000.0, 2.5, 30.0);
+//! assert!(net > 0.04 && net < 0.05);
+//! ```
+//!
+//! [`MigrationPlanner`]: crate::migration::MigrationPlanner
+
+/// Days a move must pay back its gas within for planners to keep it, by default.
+pub const DEFAULT_BREAK_EVEN_DAYS: f64 = 90.0;
+
+/// Days of extra yield needed for moving `amount` from a vault earning `current_apy` to
+/// one earning `candidate_apy` to pay for `gas_cost`.
+///
+/// APYs are fractions (`0.05` = 5%), and `amount` and `gas_cost` share a unit. Returns
+/// `None` if the candidate does not earn more or `amount` is not positive.
+pub fn break_even_horizon(
+    current_apy: f64,
+    candidate_apy: f64,
+    amount: f64,
+    gas_cost: f64,
+) -> Option<f64> {
+    let annual_gain = amount * (candidate_apy - current_apy);
+    (annual_gain > 0.0).then(|| gas_cost.max(0.0) / annual_gain * 365.0)
+}
+
+/// APY of holding `amount` at `apy` for `horizon_days` after paying `gas_cost` to enter.
+///
+/// The gas is spread over the holding period as a yearly rate, so a short horizon costs
+/// more APY than a long one. Returns `apy` unchanged if `amount` or `horizon_days` is not
+/// positive.
+pub fn net_of_gas_apy(apy: f64, amount: f64, gas_cost: f64, horizon_days: f64) -> f64 {
+    if amount > 0.0 && horizon_days > 0.0 {
+        apy - gas_cost / amount * 365.0 / horizon_days
+    } else {
+        apy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_break_even_horizon() {
+        let days = break_even_horizon(0.04, 0.05, 10_000.0, 2.5).unwrap();
+        assert!((days - 9.125).abs() < 1e-9);
+        assert_eq!(break_even_horizon(0.04, 0.05, 10_000.0, 0.0), Some(0.0));
+
+        // No gain, or nothing to move
+        assert!(break_even_horizon(0.05, 0.05, 10_000.0, 2.5).is_none());
+        assert!(break_even_horizon(0.05, 0.04, 10_000.0, 2.5).is_none());
+        assert!(break_even_horizon(0.04, 0.05, 0.0, 2.5).is_none());
+        assert!(break_even_horizon(0.04, f64::NAN, 10_000.0, 2.5).is_none());
+    }
+
+    #[test]
+    fn test_net_of_gas_apy() {
+        // $36.50 of gas on $10,000 held for a year costs 0.365% APY
+        assert!((net_of_gas_apy(0.05, 10_000.0, 36.5, 365.0) - 0.04635).abs() < 1e-12);
+        // Held for a tenth of the time, it costs ten times as much
+        assert!((net_of_gas_apy(0.05, 10_000.0, 36.5, 36.5) - 0.0135).abs() < 1e-12);
+        assert_eq!(net_of_gas_apy(0.05, 0.0, 36.5, 365.0), 0.05);
+        assert_eq!(net_of_gas_apy(0.05, 10_000.0, 36.5, 0.0), 0.05);
+    }
+
+    #[test]
+    fn test_net_apys_match_at_break_even() {
+        let days = break_even_horizon(0.04, 0.05, 10_000.0, 2.5).unwrap();
+        let net = net_of_gas_apy(0.05, 10_000.0, 2.5, days);
+        assert!((net - 0.04).abs() < 1e-12);
+    }
+}
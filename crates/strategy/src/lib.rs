@@ -0,0 +1,66 @@
+//! # Morpho Vault Strategies
+//!
+//! Vault selection strategies built on [`morpho_rs_api`] vault data.
+//!
+//! ## Modules
+//!
+//! - [`backtest`]: Replays historical vault snapshots through a [`Strategy`], tracking
+//!   fees and gas and reporting CAGR, max drawdown, and Sharpe ratio
+//! - [`breakeven`]: Days for a move's extra yield to pay for its gas, and APY net of gas
+//!   over a holding period
+//! - [`migration`]: [`MigrationPlanner`] comparing V1 positions against same-asset V2
+//!   vaults on APY, risk, and gas, producing a [`MigrationPlan`] executable through a
+//!   [`MorphoClient`](morpho_rs_api::MorphoClient)
+//! - [`monitor`]: [`AllocationMonitor`] comparing a vault's allocation against a
+//!   [`TargetAllocation`] of market weights or cap utilization, emitting [`DriftAlert`]s
+//! - [`pnl`]: [`PnlEngine`] replaying vault transactions into FIFO or average-cost
+//!   lots and splitting PnL into realized, unrealized, fees, and rewards
+//! - [`position`]: [`UnifiedPosition`] over V1/V2 vault shares and market
+//!   supply/borrow/collateral, with common value, APY, and exposure accessors
+//! - [`risk`]: Composite risk scoring for V1 and V2 vaults from allocation concentration,
+//!   collateral diversity, LLTV exposure, warnings, curator track record, and liquidity
+//!
+//! ## Quick Start
+//!
+//! ```no_run
+//! use morpho_rs_api::{MorphoApiClient, NamedChain};
+//! use morpho_rs_strategy::score_vault;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoApiClient::new();
+//!     for vault in client.v1.get_vaults_by_chain(NamedChain::Mainnet).await? {
+//!         let risk = score_vault(&vault);
+//!         println!("{}: {:.1}", vault.name, risk.score);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+pub mod backtest;
+pub mod breakeven;
+pub mod migration;
+pub mod monitor;
+pub mod pnl;
+pub mod position;
+pub mod risk;
+
+pub use backtest::{
+    Action, Backtest, BacktestConfig, BacktestResult, EquityPoint, PerformanceStats, Snapshot,
+    Strategy, VaultObservation,
+};
+pub use breakeven::{break_even_horizon, net_of_gas_apy, DEFAULT_BREAK_EVEN_DAYS};
+pub use migration::{
+    MigrationMove, MigrationPlan, MigrationPlanner, APPROVE_GAS, MARKET_INTERACTION_GAS,
+    VAULT_BASE_GAS,
+};
+pub use monitor::{AllocationMonitor, DriftAlert, TargetAllocation, DEFAULT_WEIGHT_TOLERANCE};
+pub use pnl::{CostBasisMethod, PnlEngine, PnlReport, VaultPnl};
+pub use position::{
+    enrich_with_vaults, net_exposure, portfolio_net_apy, total_value_usd, Exposure, MarketLeg,
+    UnifiedPosition, VaultShares,
+};
+pub use risk::{
+    score_vault, score_vault_v2, top_vaults_under_risk, RiskBreakdown, RiskScorer, RiskWeights,
+    DEFAULT_CURATOR_REPUTATION,
+};
@@ -0,0 +1,722 @@
+//! Migration planning from V1 vaults to V2 vaults.
+//!
+//! A [`MigrationPlanner`] looks at a user's V1 vault positions and, for each, at the
+//! candidate V2 vaults on the same chain holding the same asset. Every candidate is
+//! compared with the current vault on net APY and on risk score (see [`RiskScorer`]),
+//! and the one with the largest APY gain that passes the planner's thresholds becomes a
+//! [`MigrationMove`]. The resulting [`MigrationPlan`] can be executed through a
+//! [`MorphoClient`] with transaction support.
+//!
+//! Without the `sim` feature APYs are the API's current net APYs. With it, the current
+//! APY comes from simulating the V1 vault and the projected APY from simulating the
+//! deposit into the V2 vault, so large positions see their own dilution of the target's
+//! yield; candidates whose caps cannot take the deposit are skipped. Simulations run at
+//! the latest market update of the two vaults.
+//!
+//! Gas is estimated per move from the number of markets the withdrawal may touch and
+//! whether the deposit is allocated to an adapter, plus an approval. It is priced in USD
+//! when a gas price is configured with [`MigrationPlanner::with_gas_price_usd`], and moves
+//! whose extra yield does not pay for it within [`DEFAULT_BREAK_EVEN_DAYS`] (see
+//! [`MigrationPlanner::with_max_break_even_days`]) are skipped.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use alloy_primitives::address;
+//! use morpho_rs_api::{MorphoClient, MorphoClientConfig, NamedChain};
+//! use morpho_rs_strategy::migration::MigrationPlanner;
+//! use morpho_rs_strategy::position::{enrich_with_vaults, UnifiedPosition};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoClient::with_config(
+//!         MorphoClientConfig::new()
+//!             .with_rpc_url("https://eth.llamarpc.com")
+//!             .with_private_key("0x..."),
+//!     )?;
+//!     let user = address!("0x0000000000000000000000000000000000000001");
+//!     let overview = client.get_user_account_overview(user, NamedChain::Mainnet).await?;
+//!     let vaults_v1 = client.api().v1.get_vaults_by_chain(NamedChain::Mainnet).await?;
+//!     let vaults_v2 = client.api().v2.get_vaults_by_chain(NamedChain::Mainnet).await?;
+//!
+//!     let mut positions = UnifiedPosition::from_account_overview(&overview);
+//!     enrich_with_vaults(&mut positions, &vaults_v1, &vaults_v2);
+//!
+//!     let plan = MigrationPlanner::new()
+//!         .with_min_apy_gain(0.005)
+//!         .with_max_risk_increase(5.0)
+//!         .plan(&positions, &vaults_v1, &vaults_v2);
+//!     for m in &plan.moves {
+//!         println!("{} -> {}: {:+.2}% APY", m.from_vault, m.to_vault, m.apy_delta() * 100.0);
+//!     }
+//!     plan.execute(&client).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use alloy_primitives::{Address, U256};
+use morpho_rs_api::{MigrationOutcome, MorphoClient, NamedChain, VaultV1, VaultV2};
+use serde::{Deserialize, Serialize};
+
+use crate::breakeven::{break_even_horizon, net_of_gas_apy, DEFAULT_BREAK_EVEN_DAYS};
+use crate::position::{UnifiedPosition, VaultShares};
+use crate::risk::{RiskBreakdown, RiskScorer};
+
+/// Estimated gas of an ERC-4626 deposit or withdrawal, before market interactions.
+pub const VAULT_BASE_GAS: u64 = 100_000;
+
+/// Estimated gas per market (or adapter) a deposit or withdrawal touches.
+pub const MARKET_INTERACTION_GAS: u64 = 40_000;
+
+/// Estimated gas of an ERC-20 approval.
+pub const APPROVE_GAS: u64 = 50_000;
+
+/// Moving one V1 position into a V2 vault.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationMove {
+    /// Chain both vaults are deployed on.
+    #[serde(with = "morpho_rs_api::chain_serde")]
+    pub chain: NamedChain,
+    /// V1 vault the shares are redeemed from.
+    pub from_vault: Address,
+    /// V2 vault the assets are deposited into.
+    pub to_vault: Address,
+    /// Underlying asset of both vaults.
+    pub asset: Address,
+    /// V1 shares to migrate.
+    pub shares: U256,
+    /// Underlying assets the shares are worth.
+    pub assets: U256,
+    /// Underlying assets value in USD, if known.
+    pub assets_usd: Option<f64>,
+    /// Net APY of the V1 vault (as a fraction).
+    pub current_apy: f64,
+    /// Net APY of the V2 vault after the deposit (as a fraction).
+    pub projected_apy: f64,
+    /// Risk breakdown of the V1 vault.
+    pub current_risk: RiskBreakdown,
+    /// Risk breakdown of the V2 vault.
+    pub projected_risk: RiskBreakdown,
+    /// Estimated gas of the withdrawal, approval, and deposit.
+    pub gas: u64,
+    /// Estimated gas cost in USD, if a gas price is configured.
+    pub gas_cost_usd: Option<f64>,
+}
+
+impl MigrationMove {
+    /// Net APY gained by migrating (negative if the V2 vault earns less).
+    pub fn apy_delta(&self) -> f64 {
+        self.projected_apy - self.current_apy
+    }
+
+    /// Risk score change of migrating (positive if the V2 vault is riskier).
+    pub fn risk_delta(&self) -> f64 {
+        self.projected_risk.score - self.current_risk.score
+    }
+
+    /// Extra yield per year in USD, if the position's USD value is known.
+    pub fn annual_gain_usd(&self) -> Option<f64> {
+        Some(self.assets_usd? * self.apy_delta())
+    }
+
+    /// Days of extra yield needed to pay for the gas.
+    ///
+    /// Returns `None` if the gas cost or position value is unknown, or if the move does
+    /// not gain yield.
+    pub fn break_even_days(&self) -> Option<f64> {
+        break_even_horizon(
+            self.current_apy,
+            self.projected_apy,
+            self.assets_usd?,
+            self.gas_cost_usd?,
+        )
+    }
+
+    /// Net APY gained by migrating and holding for `days`, after paying for the gas.
+    ///
+    /// Returns `None` if the gas cost or position value is unknown.
+    pub fn net_of_gas_apy_delta(&self, days: f64) -> Option<f64> {
+        let projected = net_of_gas_apy(
+            self.projected_apy,
+            self.assets_usd?,
+            self.gas_cost_usd?,
+            days,
+        );
+        Some(projected - self.current_apy)
+    }
+}
+
+/// V1 to V2 moves selected by a [`MigrationPlanner`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MigrationPlan {
+    /// Moves, one per migrated V1 position.
+    pub moves: Vec<MigrationMove>,
+}
+
+impl MigrationPlan {
+    /// Returns true if no position is worth migrating.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Total estimated gas of all moves.
+    pub fn total_gas(&self) -> u64 {
+        self.moves.iter().map(|m| m.gas).sum()
+    }
+
+    /// Total estimated gas cost in USD, if known for every move.
+    pub fn total_gas_cost_usd(&self) -> Option<f64> {
+        self.moves.iter().map(|m| m.gas_cost_usd).sum()
+    }
+
+    /// Total extra yield per year in USD, if known for every move.
+    pub fn annual_gain_usd(&self) -> Option<f64> {
+        self.moves.iter().map(MigrationMove::annual_gain_usd).sum()
+    }
+
+    /// Execute the moves in order through `client`'s V1 and V2 vault operations.
+    ///
+    /// Each move redeems the current value of its shares from the V1 vault, then
+    /// deposits exactly the assets received into the V2 vault (approving if the client's
+    /// `auto_approve` is enabled). The moves must be on the chain of the client's RPC
+    /// URL. Execution stops at the first failure; moves already executed stay executed,
+    /// and if a deposit fails its assets stay with the signer.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError::TransactionNotConfigured`](morpho_rs_api::ApiError::TransactionNotConfigured)
+    ///   if the client has no transaction support
+    /// - Errors from the withdrawal or deposit transactions
+    pub async fn execute(
+        &self,
+        client: &MorphoClient,
+    ) -> morpho_rs_api::Result<Vec<MigrationOutcome>> {
+        let v1 = client.vault_v1()?;
+        let v2 = client.vault_v2()?;
+        let mut outcomes = Vec::with_capacity(self.moves.len());
+        for m in &self.moves {
+            let assets = v1.convert_to_assets(m.from_vault, m.shares).await?;
+            let withdraw = v1.withdraw_with_outcome(m.from_vault, assets).await?;
+            let deposit = v2
+                .deposit_with_outcome(m.to_vault, withdraw.assets_out)
+                .await?;
+            outcomes.push(MigrationOutcome {
+                withdraw,
+                deposit,
+                atomic: false,
+            });
+        }
+        Ok(outcomes)
+    }
+}
+
+/// Selects V2 vaults to migrate V1 positions into.
+#[derive(Debug, Clone)]
+pub struct MigrationPlanner {
+    scorer: RiskScorer,
+    min_apy_gain: f64,
+    max_risk_increase: Option<f64>,
+    gas_price_usd: Option<f64>,
+    max_break_even_days: f64,
+}
+
+impl Default for MigrationPlanner {
+    fn default() -> Self {
+        Self {
+            scorer: RiskScorer::default(),
+            min_apy_gain: 0.0,
+            max_risk_increase: None,
+            gas_price_usd: None,
+            max_break_even_days: DEFAULT_BREAK_EVEN_DAYS,
+        }
+    }
+}
+
+impl MigrationPlanner {
+    /// Create a planner that accepts any APY gain at any risk that pays for its gas within
+    /// [`DEFAULT_BREAK_EVEN_DAYS`], with default risk weights.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the scorer used for both vault versions.
+    pub fn with_scorer(mut self, scorer: RiskScorer) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// Set the minimum net APY gain (as a fraction) a move must bring.
+    ///
+    /// Moves must gain strictly more than this, so the default of zero only keeps moves
+    /// that improve the APY.
+    pub fn with_min_apy_gain(mut self, gain: f64) -> Self {
+        self.min_apy_gain = gain;
+        self
+    }
+
+    /// Set the maximum risk score increase (`0..=100` scale) a move may bring.
+    pub fn with_max_risk_increase(mut self, increase: f64) -> Self {
+        self.max_risk_increase = Some(increase);
+        self
+    }
+
+    /// Set the USD cost of one unit of gas (gas price times the native token price).
+    pub fn with_gas_price_usd(mut self, price: f64) -> Self {
+        self.gas_price_usd = Some(price);
+        self
+    }
+
+    /// Skip moves whose extra yield takes longer than `days` to pay for their gas
+    /// (default [`DEFAULT_BREAK_EVEN_DAYS`]; `f64::INFINITY` keeps them all).
+    ///
+    /// Only applies to moves with a known gas cost and position value.
+    pub fn with_max_break_even_days(mut self, days: f64) -> Self {
+        self.max_break_even_days = days;
+        self
+    }
+
+    /// Plan the migration of the V1 vault positions in `positions`.
+    ///
+    /// Positions whose vault is not in `vaults_v1` (matched on address and chain) are
+    /// skipped, as are all non-V1 positions.
+    pub fn plan(
+        &self,
+        positions: &[UnifiedPosition],
+        vaults_v1: &[VaultV1],
+        vaults_v2: &[VaultV2],
+    ) -> MigrationPlan {
+        let moves = positions
+            .iter()
+            .filter_map(|position| match position {
+                UnifiedPosition::VaultV1(shares) if !shares.shares.is_zero() => Some(shares),
+                _ => None,
+            })
+            .filter_map(|shares| {
+                let from = vaults_v1
+                    .iter()
+                    .find(|v| v.address == shares.vault.address && v.chain == shares.vault.chain)?;
+                vaults_v2
+                    .iter()
+                    .filter(|to| to.chain == from.chain && to.asset.address == from.asset.address)
+                    .filter_map(|to| self.evaluate(shares, from, to))
+                    .max_by(|a, b| {
+                        a.apy_delta()
+                            .total_cmp(&b.apy_delta())
+                            .then(b.risk_delta().total_cmp(&a.risk_delta()))
+                    })
+            })
+            .collect();
+        MigrationPlan { moves }
+    }
+
+    /// Evaluate moving `shares` of `from` into `to`.
+    ///
+    /// Returns `None` if either APY is unknown, `to` cannot take the deposit, or the move
+    /// does not pass the planner's thresholds.
+    pub fn evaluate(
+        &self,
+        shares: &VaultShares,
+        from: &VaultV1,
+        to: &VaultV2,
+    ) -> Option<MigrationMove> {
+        let (current_apy, projected_apy) = net_apys(from, to, shares.assets)?;
+        let gas = estimate_gas(from, to);
+        let m = MigrationMove {
+            chain: from.chain,
+            from_vault: from.address,
+            to_vault: to.address,
+            asset: from.asset.address,
+            shares: shares.shares,
+            assets: shares.assets,
+            assets_usd: shares.assets_usd,
+            current_apy,
+            projected_apy,
+            current_risk: self.scorer.score(from),
+            projected_risk: self.scorer.score_v2(to),
+            gas,
+            gas_cost_usd: self.gas_price_usd.map(|price| gas as f64 * price),
+        };
+
+        if m.apy_delta() <= self.min_apy_gain {
+            return None;
+        }
+        if self
+            .max_risk_increase
+            .is_some_and(|max| m.risk_delta() > max)
+        {
+            return None;
+        }
+        if m.break_even_days()
+            .is_some_and(|days| days > self.max_break_even_days)
+        {
+            return None;
+        }
+        Some(m)
+    }
+}
+
+/// Gas of withdrawing from every supplied market of `from`, approving, and depositing
+/// into `to` through its liquidity adapter.
+fn estimate_gas(from: &VaultV1, to: &VaultV2) -> u64 {
+    let withdraw_markets = from
+        .state
+        .as_ref()
+        .map(|s| {
+            s.allocation
+                .iter()
+                .filter(|a| !a.supply_assets.is_zero())
+                .count()
+        })
+        .unwrap_or(0) as u64;
+    let deposit_markets = u64::from(to.liquidity_adapter.is_some());
+    2 * VAULT_BASE_GAS + (withdraw_markets + deposit_markets) * MARKET_INTERACTION_GAS + APPROVE_GAS
+}
+
+/// Current net APY of `from` and net APY of `to` after depositing `assets`.
+#[cfg(not(feature = "sim"))]
+fn net_apys(from: &VaultV1, to: &VaultV2, _assets: U256) -> Option<(f64, f64)> {
+    Some((from.state.as_ref()?.net_apy, to.net_apy?))
+}
+
+/// Current net APY of `from` and net APY of `to` after depositing `assets`.
+///
+/// Falls back to the API's APYs for vaults whose simulation lacks data.
+#[cfg(feature = "sim")]
+fn net_apys(from: &VaultV1, to: &VaultV2, assets: U256) -> Option<(f64, f64)> {
+    let current = from.to_vault_simulation();
+    let target = to.to_vault_simulation();
+    let timestamp = current
+        .iter()
+        .flat_map(|sim| sim.markets.values().map(|m| m.last_update))
+        .chain([target.last_update])
+        .max()
+        .unwrap_or(0);
+
+    let (after, _) = target.simulate_deposit(assets, timestamp).ok()?;
+    let projected_apy = after.get_net_apy(timestamp).ok().or(to.net_apy)?;
+    let current_apy = current
+        .and_then(|sim| sim.get_net_apy(timestamp).ok())
+        .or_else(|| Some(from.state.as_ref()?.net_apy))?;
+    Some((current_apy, projected_apy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use morpho_rs_api::{Asset, VaultInfo, VaultStateV1};
+
+    fn asset(byte: u8) -> Asset {
+        Asset {
+            address: Address::repeat_byte(byte),
+            symbol: "USDC".to_string(),
+            name: None,
+            decimals: 6,
+            price_usd: Some(1.0),
+        }
+    }
+
+    fn v1(net_apy: f64) -> VaultV1 {
+        VaultV1 {
+            address: Address::repeat_byte(0x01),
+            name: "V1".to_string(),
+            symbol: "V1".to_string(),
+            chain: NamedChain::Mainnet,
+            listed: true,
+            featured: false,
+            whitelisted: true,
+            asset: asset(0xAA),
+            state: Some(VaultStateV1 {
+                curator: Some(Address::repeat_byte(0xC1)),
+                owner: None,
+                guardian: None,
+                total_assets: U256::ZERO,
+                total_assets_usd: None,
+                total_supply: U256::ZERO,
+                fee: 0.1,
+                timelock: 86_400,
+                apy: net_apy,
+                net_apy,
+                share_price: U256::ZERO,
+                allocation: vec![],
+                rewards: vec![],
+            }),
+            allocators: vec![],
+            warnings: vec![],
+            public_allocator_config: None,
+        }
+    }
+
+    fn v2(byte: u8, asset_byte: u8, net_apy: Option<f64>, curator: Option<Address>) -> VaultV2 {
+        VaultV2 {
+            address: Address::repeat_byte(byte),
+            name: "V2".to_string(),
+            symbol: "V2".to_string(),
+            chain: NamedChain::Mainnet,
+            listed: true,
+            whitelisted: true,
+            asset: asset(asset_byte),
+            curator,
+            owner: None,
+            total_assets: U256::ZERO,
+            total_assets_usd: None,
+            total_supply: U256::ZERO,
+            share_price: None,
+            performance_fee: None,
+            management_fee: None,
+            avg_apy: None,
+            avg_net_apy: None,
+            apy: net_apy,
+            net_apy,
+            liquidity: U256::ZERO,
+            liquidity_usd: None,
+            idle_assets: U256::ZERO,
+            liquidity_adapter: None,
+            caps: vec![],
+            adapters: vec![],
+            rewards: vec![],
+            warnings: vec![],
+        }
+    }
+
+    fn position(value_usd: Option<f64>) -> UnifiedPosition {
+        UnifiedPosition::VaultV1(VaultShares {
+            vault: VaultInfo {
+                address: Address::repeat_byte(0x01),
+                name: "V1".to_string(),
+                symbol: "V1".to_string(),
+                chain: NamedChain::Mainnet,
+            },
+            shares: U256::from(1_000_000),
+            assets: U256::from(1_000_000),
+            assets_usd: value_usd,
+            asset: Some(Address::repeat_byte(0xAA)),
+            net_apy: Some(0.04),
+        })
+    }
+
+    // APYs come from the API only without the `sim` feature
+    #[cfg(not(feature = "sim"))]
+    #[test]
+    fn test_picks_best_same_asset_vault() {
+        let vaults_v2 = vec![
+            v2(0x02, 0xAA, Some(0.05), None),
+            v2(0x03, 0xAA, Some(0.06), None),
+            // Higher APY, different asset
+            v2(0x04, 0xBB, Some(0.20), None),
+            // Unknown APY
+            v2(0x05, 0xAA, None, None),
+        ];
+        let plan = MigrationPlanner::new().plan(&[position(Some(1.0))], &[v1(0.04)], &vaults_v2);
+
+        assert_eq!(plan.moves.len(), 1);
+        let m = &plan.moves[0];
+        assert_eq!(m.from_vault, Address::repeat_byte(0x01));
+        assert_eq!(m.to_vault, Address::repeat_byte(0x03));
+        assert_eq!(m.asset, Address::repeat_byte(0xAA));
+        assert_eq!(m.shares, U256::from(1_000_000));
+        assert!((m.apy_delta() - 0.02).abs() < 1e-9);
+        assert_eq!(m.gas, 2 * VAULT_BASE_GAS + APPROVE_GAS);
+        assert_eq!(plan.total_gas(), m.gas);
+    }
+
+    // APYs come from the API only without the `sim` feature
+    #[cfg(not(feature = "sim"))]
+    #[test]
+    fn test_min_apy_gain() {
+        let vaults_v2 = vec![v2(0x02, 0xAA, Some(0.045), None)];
+        let positions = [position(Some(1.0))];
+        let planner = MigrationPlanner::new();
+        assert!(!planner.plan(&positions, &[v1(0.04)], &vaults_v2).is_empty());
+
+        let planner = planner.with_min_apy_gain(0.01);
+        assert!(planner.plan(&positions, &[v1(0.04)], &vaults_v2).is_empty());
+
+        // No gain at all
+        let vaults_v2 = vec![v2(0x02, 0xAA, Some(0.03), None)];
+        assert!(MigrationPlanner::new()
+            .plan(&positions, &[v1(0.04)], &vaults_v2)
+            .is_empty());
+    }
+
+    // APYs come from the API only without the `sim` feature
+    #[cfg(not(feature = "sim"))]
+    #[test]
+    fn test_max_risk_increase() {
+        let curator = Address::repeat_byte(0xC0);
+        let vaults_v2 = vec![
+            // No curator: riskier than the V1 vault
+            v2(0x02, 0xAA, Some(0.08), None),
+            v2(0x03, 0xAA, Some(0.06), Some(curator)),
+        ];
+        let positions = [position(Some(1.0))];
+        let vaults_v1 = [v1(0.04)];
+
+        let plan = MigrationPlanner::new().plan(&positions, &vaults_v1, &vaults_v2);
+        assert_eq!(plan.moves[0].to_vault, Address::repeat_byte(0x02));
+
+        let scorer = RiskScorer::new().with_curator_reputation(curator, 1.0);
+        let plan = MigrationPlanner::new()
+            .with_scorer(scorer)
+            .with_max_risk_increase(0.0)
+            .plan(&positions, &vaults_v1, &vaults_v2);
+        assert_eq!(plan.moves.len(), 1);
+        assert_eq!(plan.moves[0].to_vault, Address::repeat_byte(0x03));
+        assert!(plan.moves[0].risk_delta() <= 0.0);
+    }
+
+    // APYs come from the API only without the `sim` feature
+    #[cfg(not(feature = "sim"))]
+    #[test]
+    fn test_gas_cost_and_break_even() {
+        let vaults_v2 = vec![v2(0x02, 0xAA, Some(0.05), None)];
+        let vaults_v1 = [v1(0.04)];
+        // 1e-5 USD per gas: 2.5 USD for 250k gas
+        let planner = MigrationPlanner::new().with_gas_price_usd(1e-5);
+
+        let plan = planner.plan(&[position(Some(10_000.0))], &vaults_v1, &vaults_v2);
+        let m = &plan.moves[0];
+        assert!((m.gas_cost_usd.unwrap() - 2.5).abs() < 1e-9);
+        // 100 USD per year pays for 2.5 USD in 9.125 days
+        assert!((m.annual_gain_usd().unwrap() - 100.0).abs() < 1e-9);
+        assert!((m.break_even_days().unwrap() - 9.125).abs() < 1e-9);
+        assert!((plan.total_gas_cost_usd().unwrap() - 2.5).abs() < 1e-9);
+        // Held for 9.125 days, the move only pays for its gas
+        assert!(m.net_of_gas_apy_delta(9.125).unwrap().abs() < 1e-9);
+        assert!(m.net_of_gas_apy_delta(365.0).unwrap() > 0.0);
+
+        // 2.5 USD of gas on 100 USD takes over 90 days to pay back
+        assert!(planner
+            .plan(&[position(Some(100.0))], &vaults_v1, &vaults_v2)
+            .is_empty());
+        assert!(!planner
+            .clone()
+            .with_max_break_even_days(f64::INFINITY)
+            .plan(&[position(Some(100.0))], &vaults_v1, &vaults_v2)
+            .is_empty());
+
+        let planner = planner.with_max_break_even_days(7.0);
+        assert!(planner
+            .plan(&[position(Some(10_000.0))], &vaults_v1, &vaults_v2)
+            .is_empty());
+        // Unknown value: the break-even limit does not apply
+        assert!(!planner
+            .plan(&[position(None)], &vaults_v1, &vaults_v2)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_skips_unknown_and_non_v1_positions() {
+        let vaults_v2 = vec![v2(0x02, 0xAA, Some(0.05), None)];
+        let UnifiedPosition::VaultV1(shares) = position(None) else {
+            unreachable!()
+        };
+        let positions = [UnifiedPosition::VaultV2(shares)];
+        assert!(MigrationPlanner::new()
+            .plan(&positions, &[v1(0.04)], &vaults_v2)
+            .is_empty());
+        assert!(MigrationPlanner::new()
+            .plan(&[position(None)], &[], &vaults_v2)
+            .is_empty());
+    }
+
+    #[cfg(feature = "sim")]
+    #[test]
+    fn test_simulated_deposit_dilutes_apy() {
+        use alloy_primitives::B256;
+        use morpho_rs_api::{
+            MarketStateV1, MarketStateV2, MorphoMarketPosition, VaultAdapter, VaultAdapterData,
+            VaultAllocation,
+        };
+
+        const SUPPLY: u64 = 1_000_000;
+        const BORROW: u64 = 900_000;
+        let rate_at_target = Some(U256::from(1_268_391_679u64));
+        let lltv = U256::from(860_000_000_000_000_000u64);
+
+        // Same market on both sides; the V1 vault charges a 10% fee, the V2 vault none
+        let mut from = v1(0.0);
+        let state = from.state.as_mut().unwrap();
+        state.total_assets = U256::from(SUPPLY);
+        state.total_supply = U256::from(SUPPLY);
+        state.allocation = vec![VaultAllocation {
+            market_key: "market".to_string(),
+            loan_asset_symbol: None,
+            loan_asset_address: None,
+            collateral_asset_symbol: None,
+            collateral_asset_address: Some(Address::repeat_byte(0xCC)),
+            supply_assets: U256::from(SUPPLY),
+            supply_assets_usd: None,
+            supply_cap: U256::MAX,
+            enabled: true,
+            supply_queue_index: Some(0),
+            withdraw_queue_index: Some(0),
+            market_state: Some(MarketStateV1 {
+                id: B256::repeat_byte(0x0A),
+                total_supply_assets: U256::from(SUPPLY),
+                total_borrow_assets: U256::from(BORROW),
+                total_supply_shares: U256::from(SUPPLY),
+                total_borrow_shares: U256::from(BORROW),
+                last_update: 1000,
+                fee: U256::ZERO,
+                rate_at_target,
+                price: None,
+                lltv,
+                liquidity: U256::from(SUPPLY - BORROW),
+                realized_bad_debt: None,
+            }),
+        }];
+
+        // Deposits stay idle without a liquidity adapter
+        let mut to = v2(0x02, 0xAA, None, None);
+        to.total_assets = U256::from(SUPPLY);
+        to.total_supply = U256::from(SUPPLY);
+        to.adapters = vec![VaultAdapter {
+            id: "adapter".to_string(),
+            address: Address::repeat_byte(0xAD),
+            adapter_type: "MorphoMarketV1".to_string(),
+            assets: U256::from(SUPPLY),
+            assets_usd: None,
+            data: Some(VaultAdapterData::MorphoMarketV1 {
+                positions: vec![MorphoMarketPosition {
+                    supply_assets: U256::from(SUPPLY),
+                    supply_shares: U256::from(SUPPLY),
+                    market_id: B256::repeat_byte(0x0A),
+                    market_state: Some(MarketStateV2 {
+                        id: B256::repeat_byte(0x0A),
+                        total_supply_assets: U256::from(SUPPLY),
+                        total_borrow_assets: U256::from(BORROW),
+                        total_supply_shares: U256::from(SUPPLY),
+                        total_borrow_shares: U256::from(BORROW),
+                        last_update: 1000,
+                        fee: U256::ZERO,
+                        rate_at_target,
+                        price: None,
+                        lltv,
+                    }),
+                }],
+            }),
+        }];
+
+        let shares = |assets: u64| VaultShares {
+            vault: VaultInfo {
+                address: from.address,
+                name: "V1".to_string(),
+                symbol: "V1".to_string(),
+                chain: NamedChain::Mainnet,
+            },
+            shares: U256::from(assets),
+            assets: U256::from(assets),
+            assets_usd: None,
+            asset: Some(from.asset.address),
+            net_apy: None,
+        };
+
+        let planner = MigrationPlanner::new();
+        let small = planner.evaluate(&shares(1_000), &from, &to).unwrap();
+        assert!(small.current_apy > 0.0);
+        assert!((small.projected_apy - small.current_apy / 0.9).abs() < 1e-3);
+
+        // Doubling the V2 vault with idle assets halves its APY below the V1 vault's
+        assert!(planner.evaluate(&shares(SUPPLY), &from, &to).is_none());
+    }
+}
@@ -0,0 +1,393 @@
+//! Allocation drift monitoring for V1 vaults.
+//!
+//! An [`AllocationMonitor`] compares a vault's current allocation against a
+//! [`TargetAllocation`] and returns a [`DriftAlert`] for every market that is out of
+//! bounds. A target can specify:
+//!
+//! - **Per-market weights**: the share of vault supply each market should hold. Weights
+//!   are normalized by their total, so `{A: 2, B: 1}` targets two thirds in `A`. A market
+//!   drifts when its actual share differs from its target by more than the monitor's
+//!   tolerance; supplied markets missing from the target are reported as unexpected.
+//! - **Cap utilization**: the maximum share of a market's supply cap the vault may use.
+//!   Markets with a zero supply cap are skipped.
+//!
+//! Allocator bots can act on the alerts directly; alerting pipelines can serialize them.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use alloy_primitives::address;
+//! use morpho_rs_api::{MorphoApiClient, NamedChain};
+//! use morpho_rs_strategy::monitor::{AllocationMonitor, TargetAllocation};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoApiClient::new();
+//!     let vault = client
+//!         .v1
+//!         .get_vault(address!("0x0000000000000000000000000000000000000001"), NamedChain::Mainnet)
+//!         .await?;
+//!
+//!     let target = TargetAllocation::new()
+//!         .with_weight("0xmarket-a", 0.6)
+//!         .with_weight("0xmarket-b", 0.4)
+//!         .with_max_cap_utilization(0.9);
+//!     for alert in AllocationMonitor::new(target).with_tolerance(0.05).check(&vault) {
+//!         println!("{}: {alert:?}", alert.market_key());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::U256;
+use morpho_rs_api::VaultV1;
+use serde::{Deserialize, Serialize};
+
+use crate::risk::u256_to_f64;
+
+/// Default maximum absolute difference between actual and target weight.
+pub const DEFAULT_WEIGHT_TOLERANCE: f64 = 0.05;
+
+/// Target allocation for a vault.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TargetAllocation {
+    /// Target weight per market unique key (normalized by the total).
+    pub weights: HashMap<String, f64>,
+    /// Maximum share of each market's supply cap the vault may use (`0..=1`).
+    pub max_cap_utilization: Option<f64>,
+}
+
+impl TargetAllocation {
+    /// Create an empty target (no weights, no cap utilization limit).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a market's target weight.
+    pub fn with_weight(mut self, market_key: impl Into<String>, weight: f64) -> Self {
+        self.weights.insert(market_key.into(), weight.max(0.0));
+        self
+    }
+
+    /// Set the maximum cap utilization.
+    pub fn with_max_cap_utilization(mut self, max: f64) -> Self {
+        self.max_cap_utilization = Some(max);
+        self
+    }
+
+    /// Normalized target weight of a market (`0.0` if not targeted).
+    pub fn normalized_weight(&self, market_key: &str) -> f64 {
+        let total: f64 = self.weights.values().sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        self.weights.get(market_key).copied().unwrap_or(0.0) / total
+    }
+}
+
+/// A market whose allocation is out of bounds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DriftAlert {
+    /// The market's share of vault supply differs from its target by more than the tolerance.
+    WeightDrift {
+        /// Market unique key.
+        market_key: String,
+        /// Normalized target weight.
+        target: f64,
+        /// Actual share of vault supply.
+        actual: f64,
+    },
+    /// The vault supplies to a market that is not in the target weights.
+    UnexpectedMarket {
+        /// Market unique key.
+        market_key: String,
+        /// Actual share of vault supply.
+        actual: f64,
+    },
+    /// The vault uses more of the market's supply cap than allowed.
+    CapUtilization {
+        /// Market unique key.
+        market_key: String,
+        /// Supplied assets divided by the supply cap.
+        utilization: f64,
+        /// Configured maximum utilization.
+        max: f64,
+    },
+}
+
+impl DriftAlert {
+    /// Unique key of the market the alert refers to.
+    pub fn market_key(&self) -> &str {
+        match self {
+            Self::WeightDrift { market_key, .. }
+            | Self::UnexpectedMarket { market_key, .. }
+            | Self::CapUtilization { market_key, .. } => market_key,
+        }
+    }
+
+    /// Size of the breach: absolute weight difference, unexpected weight, or utilization
+    /// above the maximum.
+    pub fn magnitude(&self) -> f64 {
+        match self {
+            Self::WeightDrift { target, actual, .. } => (actual - target).abs(),
+            Self::UnexpectedMarket { actual, .. } => *actual,
+            Self::CapUtilization {
+                utilization, max, ..
+            } => utilization - max,
+        }
+    }
+}
+
+/// Compares vault allocations against a target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationMonitor {
+    target: TargetAllocation,
+    tolerance: f64,
+}
+
+impl AllocationMonitor {
+    /// Create a monitor with [`DEFAULT_WEIGHT_TOLERANCE`].
+    pub fn new(target: TargetAllocation) -> Self {
+        Self {
+            target,
+            tolerance: DEFAULT_WEIGHT_TOLERANCE,
+        }
+    }
+
+    /// Set the maximum absolute difference between actual and target weight.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance.max(0.0);
+        self
+    }
+
+    /// Get the target allocation.
+    pub fn target(&self) -> &TargetAllocation {
+        &self.target
+    }
+
+    /// Get the weight tolerance.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Alerts for every out-of-bounds market in the vault, in allocation order followed
+    /// by targeted markets the vault does not allocate to.
+    pub fn check(&self, vault: &VaultV1) -> Vec<DriftAlert> {
+        let allocations = vault
+            .state
+            .as_ref()
+            .map(|s| s.allocation.as_slice())
+            .unwrap_or(&[]);
+        let total: f64 = allocations
+            .iter()
+            .map(|a| u256_to_f64(a.supply_assets))
+            .sum();
+        let has_weights = !self.target.weights.is_empty();
+
+        let mut alerts = Vec::new();
+        let mut seen = HashSet::new();
+        for allocation in allocations {
+            let key = allocation.market_key.as_str();
+            seen.insert(key);
+            let supply = u256_to_f64(allocation.supply_assets);
+            let actual = if total > 0.0 { supply / total } else { 0.0 };
+
+            if has_weights {
+                if self.target.weights.contains_key(key) {
+                    let target = self.target.normalized_weight(key);
+                    if (actual - target).abs() > self.tolerance {
+                        alerts.push(DriftAlert::WeightDrift {
+                            market_key: key.to_string(),
+                            target,
+                            actual,
+                        });
+                    }
+                } else if actual > 0.0 {
+                    alerts.push(DriftAlert::UnexpectedMarket {
+                        market_key: key.to_string(),
+                        actual,
+                    });
+                }
+            }
+
+            if let Some(max) = self.target.max_cap_utilization {
+                if allocation.supply_cap > U256::ZERO {
+                    let utilization = supply / u256_to_f64(allocation.supply_cap);
+                    if utilization > max {
+                        alerts.push(DriftAlert::CapUtilization {
+                            market_key: key.to_string(),
+                            utilization,
+                            max,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut missing: Vec<&String> = self
+            .target
+            .weights
+            .keys()
+            .filter(|k| !seen.contains(k.as_str()))
+            .collect();
+        missing.sort();
+        for key in missing {
+            let target = self.target.normalized_weight(key);
+            if target > self.tolerance {
+                alerts.push(DriftAlert::WeightDrift {
+                    market_key: key.clone(),
+                    target,
+                    actual: 0.0,
+                });
+            }
+        }
+
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Address;
+    use morpho_rs_api::{Asset, NamedChain, VaultAllocation, VaultStateV1};
+
+    fn allocation(key: &str, supply: u64, cap: u64) -> VaultAllocation {
+        VaultAllocation {
+            market_key: key.to_string(),
+            loan_asset_symbol: Some("USDC".to_string()),
+            loan_asset_address: Some(Address::repeat_byte(0xAA)),
+            collateral_asset_symbol: None,
+            collateral_asset_address: None,
+            supply_assets: U256::from(supply),
+            supply_assets_usd: None,
+            supply_cap: U256::from(cap),
+            enabled: true,
+            supply_queue_index: Some(0),
+            withdraw_queue_index: Some(0),
+            market_state: None,
+        }
+    }
+
+    fn vault(allocations: Vec<VaultAllocation>) -> VaultV1 {
+        VaultV1 {
+            address: Address::repeat_byte(0x01),
+            name: "Vault".to_string(),
+            symbol: "V".to_string(),
+            chain: NamedChain::Mainnet,
+            listed: true,
+            featured: false,
+            whitelisted: true,
+            asset: Asset {
+                address: Address::repeat_byte(0xAA),
+                symbol: "USDC".to_string(),
+                name: None,
+                decimals: 6,
+                price_usd: Some(1.0),
+            },
+            state: Some(VaultStateV1 {
+                curator: None,
+                owner: None,
+                guardian: None,
+                total_assets: U256::ZERO,
+                total_assets_usd: None,
+                total_supply: U256::ZERO,
+                fee: 0.1,
+                timelock: 86_400,
+                apy: 0.05,
+                net_apy: 0.05,
+                share_price: U256::ZERO,
+                allocation: allocations,
+                rewards: vec![],
+            }),
+            allocators: vec![],
+            warnings: vec![],
+            public_allocator_config: None,
+        }
+    }
+
+    #[test]
+    fn test_within_tolerance() {
+        let v = vault(vec![
+            allocation("a", 620, 10_000),
+            allocation("b", 380, 10_000),
+        ]);
+        let target = TargetAllocation::new()
+            .with_weight("a", 0.6)
+            .with_weight("b", 0.4);
+        assert!(AllocationMonitor::new(target).check(&v).is_empty());
+    }
+
+    #[test]
+    fn test_weight_drift_normalizes_target() {
+        let v = vault(vec![
+            allocation("a", 900, 10_000),
+            allocation("b", 100, 10_000),
+        ]);
+        let target = TargetAllocation::new()
+            .with_weight("a", 1.0)
+            .with_weight("b", 1.0);
+        let alerts = AllocationMonitor::new(target).check(&v);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(
+            alerts[0],
+            DriftAlert::WeightDrift {
+                market_key: "a".to_string(),
+                target: 0.5,
+                actual: 0.9,
+            }
+        );
+        assert!((alerts[1].magnitude() - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unexpected_and_missing_markets() {
+        let v = vault(vec![
+            allocation("a", 500, 10_000),
+            allocation("x", 500, 10_000),
+        ]);
+        let target = TargetAllocation::new()
+            .with_weight("a", 0.5)
+            .with_weight("b", 0.5);
+        let alerts = AllocationMonitor::new(target).check(&v);
+        let keys: Vec<&str> = alerts.iter().map(DriftAlert::market_key).collect();
+        assert_eq!(keys, vec!["x", "b"]);
+        assert!(matches!(alerts[0], DriftAlert::UnexpectedMarket { .. }));
+        assert!(matches!(
+            alerts[1],
+            DriftAlert::WeightDrift { actual, .. } if actual.abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn test_cap_utilization() {
+        let v = vault(vec![
+            allocation("a", 950, 1_000),
+            allocation("b", 500, 1_000),
+            allocation("c", 100, 0),
+        ]);
+        let target = TargetAllocation::new().with_max_cap_utilization(0.9);
+        let alerts = AllocationMonitor::new(target).check(&v);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].market_key(), "a");
+        assert!((alerts[0].magnitude() - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_custom_tolerance() {
+        let v = vault(vec![
+            allocation("a", 620, 10_000),
+            allocation("b", 380, 10_000),
+        ]);
+        let target = TargetAllocation::new()
+            .with_weight("a", 0.6)
+            .with_weight("b", 0.4);
+        let alerts = AllocationMonitor::new(target)
+            .with_tolerance(0.01)
+            .check(&v);
+        assert_eq!(alerts.len(), 2);
+    }
+}
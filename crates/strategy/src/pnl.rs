@@ -0,0 +1,555 @@
+//! Realized and unrealized PnL of vault positions, reconstructed from transaction history.
+//!
+//! The API reports one opaque `pnl_usd` per position. [`PnlEngine`] replays a user's
+//! vault deposits and withdrawals ([`UserTransaction`]s from
+//! `MorphoApiClient::get_user_transactions`) into per-vault share lots and splits the PnL
+//! of each vault into:
+//!
+//! - **realized**: withdrawal proceeds against the cost of the shares they redeemed,
+//! - **unrealized**: the current value of the shares still held against their cost,
+//! - **fees**: the performance fee the vault took from the yield earned on the user's
+//!   shares, estimated from the vault's fee rate (already netted out of the share price,
+//!   so it is reported separately and not subtracted again),
+//! - **rewards**: reward token value recorded with [`PnlEngine::record_reward`].
+//!
+//! Lots are consumed first-in first-out ([`CostBasisMethod::Fifo`]) or at their average
+//! cost ([`CostBasisMethod::Average`]). Share transfers and fee mints are not replayed.
+//! Replaying a [`UserTransactionHistory`] that skipped or cut off transactions marks every
+//! vault incomplete.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use alloy_primitives::address;
+//! use morpho_rs_api::{MorphoApiClient, NamedChain};
+//! use morpho_rs_strategy::pnl::{CostBasisMethod, PnlEngine};
+//! use morpho_rs_strategy::position::UnifiedPosition;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoApiClient::new();
+//!     let user = address!("0x0000000000000000000000000000000000000001");
+//!     let history = client
+//!         .get_user_transactions(user, NamedChain::Mainnet, None, None)
+//!         .await?;
+//!     let overview = client.get_user_account_overview(user, NamedChain::Mainnet).await?;
+//!     let vaults_v1 = client.v1.get_vaults_by_chain(NamedChain::Mainnet).await?;
+//!
+//!     let mut engine = PnlEngine::new(CostBasisMethod::Fifo).with_vaults(&vaults_v1, &[]);
+//!     engine.record_history(&history);
+//!
+//!     let report = engine.report(&UnifiedPosition::from_account_overview(&overview));
+//!     for vault in &report.vaults {
+//!         println!(
+//!             "{}: realized ${:.2}, unrealized ${:.2}, fees ${:.2}",
+//!             vault.vault, vault.realized_pnl_usd, vault.unrealized_pnl_usd, vault.fees_usd
+//!         );
+//!     }
+//!     println!("total: ${:.2}", report.total_pnl_usd);
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use alloy_primitives::{Address, U256};
+use morpho_rs_api::{
+    TransactionData, TransactionKind, UserTransaction, UserTransactionHistory, VaultV1, VaultV2,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::position::UnifiedPosition;
+use crate::risk::u256_to_f64;
+
+/// Which shares a withdrawal redeems, for cost basis purposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBasisMethod {
+    /// The oldest shares first.
+    #[default]
+    Fifo,
+    /// A pro-rata slice of all shares held, at their average cost.
+    Average,
+}
+
+/// Realized and unrealized PnL of one vault position, in USD.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultPnl {
+    /// The vault's contract address.
+    pub vault: Address,
+    /// Shares still held according to the replayed transactions.
+    pub shares: U256,
+    /// USD cost basis of the shares still held.
+    pub cost_basis_usd: f64,
+    /// Current USD value of the position (0 once closed).
+    pub current_usd: f64,
+    /// Withdrawal proceeds minus the cost basis of the shares redeemed.
+    pub realized_pnl_usd: f64,
+    /// Current value minus the cost basis of the shares still held.
+    pub unrealized_pnl_usd: f64,
+    /// Estimated performance fees charged on the position's yield. Already reflected in
+    /// the realized and unrealized PnL.
+    pub fees_usd: f64,
+    /// Recorded reward value.
+    pub rewards_usd: f64,
+    /// Realized plus unrealized PnL plus rewards.
+    pub total_pnl_usd: f64,
+    /// Whether every transaction, the current position, and the vault's fee rate were
+    /// known, and no transaction was missing from the history. If not, the missing
+    /// values count as zero.
+    pub complete: bool,
+}
+
+/// PnL of every vault position a [`PnlEngine`] has seen, with portfolio totals in USD.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PnlReport {
+    /// Per-vault PnL, ordered by vault address.
+    pub vaults: Vec<VaultPnl>,
+    /// Sum of the vaults' realized PnL.
+    pub realized_pnl_usd: f64,
+    /// Sum of the vaults' unrealized PnL.
+    pub unrealized_pnl_usd: f64,
+    /// Sum of the vaults' estimated fees.
+    pub fees_usd: f64,
+    /// Sum of the vaults' rewards.
+    pub rewards_usd: f64,
+    /// Sum of the vaults' total PnL.
+    pub total_pnl_usd: f64,
+}
+
+/// Shares bought by one deposit, with the assets and USD paid for them.
+#[derive(Debug, Clone)]
+struct Lot {
+    shares: U256,
+    assets: f64,
+    cost_usd: f64,
+}
+
+impl Lot {
+    /// Split off `shares` of this lot (at most all of it), returning the part taken.
+    fn take(&mut self, shares: U256) -> Lot {
+        let shares = shares.min(self.shares);
+        let fraction = if self.shares.is_zero() {
+            0.0
+        } else {
+            u256_to_f64(shares) / u256_to_f64(self.shares)
+        };
+        let taken = Lot {
+            shares,
+            assets: self.assets * fraction,
+            cost_usd: self.cost_usd * fraction,
+        };
+        self.shares -= shares;
+        self.assets -= taken.assets;
+        self.cost_usd -= taken.cost_usd;
+        taken
+    }
+}
+
+/// Replayed state of one vault position.
+#[derive(Debug, Clone)]
+struct Ledger {
+    lots: VecDeque<Lot>,
+    realized_usd: f64,
+    /// Assets withdrawn beyond the assets deposited for the redeemed shares.
+    realized_yield_assets: f64,
+    /// Latest known USD price of one (raw) asset unit.
+    last_price_usd: Option<f64>,
+    rewards_usd: f64,
+    complete: bool,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self {
+            lots: VecDeque::new(),
+            realized_usd: 0.0,
+            realized_yield_assets: 0.0,
+            last_price_usd: None,
+            rewards_usd: 0.0,
+            complete: true,
+        }
+    }
+}
+
+impl Ledger {
+    fn shares(&self) -> U256 {
+        self.lots.iter().map(|lot| lot.shares).sum()
+    }
+
+    fn deposit(&mut self, method: CostBasisMethod, lot: Lot) {
+        match (method, self.lots.front_mut()) {
+            (CostBasisMethod::Average, Some(pooled)) => {
+                pooled.shares += lot.shares;
+                pooled.assets += lot.assets;
+                pooled.cost_usd += lot.cost_usd;
+            }
+            _ => self.lots.push_back(lot),
+        }
+    }
+
+    /// Redeem `shares` from the oldest lots, returning the redeemed cost basis.
+    fn withdraw(&mut self, mut shares: U256) -> Lot {
+        let mut redeemed = Lot {
+            shares: U256::ZERO,
+            assets: 0.0,
+            cost_usd: 0.0,
+        };
+        while !shares.is_zero() {
+            let Some(lot) = self.lots.front_mut() else {
+                // More shares withdrawn than deposited: the history is incomplete.
+                self.complete = false;
+                break;
+            };
+            let taken = lot.take(shares);
+            if lot.shares.is_zero() {
+                self.lots.pop_front();
+            }
+            shares -= taken.shares;
+            redeemed.shares += taken.shares;
+            redeemed.assets += taken.assets;
+            redeemed.cost_usd += taken.cost_usd;
+        }
+        redeemed
+    }
+}
+
+/// Replays vault transactions into cost basis lots and reports PnL attribution.
+#[derive(Debug, Clone)]
+pub struct PnlEngine {
+    method: CostBasisMethod,
+    fee_rates: HashMap<Address, f64>,
+    ledgers: BTreeMap<Address, Ledger>,
+    /// Whether every replayed history was complete.
+    history_complete: bool,
+}
+
+impl Default for PnlEngine {
+    fn default() -> Self {
+        Self::new(CostBasisMethod::default())
+    }
+}
+
+impl PnlEngine {
+    /// Create an engine using `method` to pick the shares each withdrawal redeems.
+    pub fn new(method: CostBasisMethod) -> Self {
+        Self {
+            method,
+            fee_rates: HashMap::new(),
+            ledgers: BTreeMap::new(),
+            history_complete: true,
+        }
+    }
+
+    /// The engine's cost basis method.
+    pub fn method(&self) -> CostBasisMethod {
+        self.method
+    }
+
+    /// Set a vault's performance fee (as a fraction, e.g., 0.1 = 10%).
+    pub fn with_fee_rate(mut self, vault: Address, fee: f64) -> Self {
+        self.fee_rates.insert(vault, fee);
+        self
+    }
+
+    /// Take performance fees from V1 vault state and V2 vault data.
+    pub fn with_vaults(mut self, vaults_v1: &[VaultV1], vaults_v2: &[VaultV2]) -> Self {
+        for vault in vaults_v1 {
+            if let Some(state) = &vault.state {
+                self.fee_rates.insert(vault.address, state.fee);
+            }
+        }
+        for vault in vaults_v2 {
+            if let Some(fee) = vault.performance_fee {
+                self.fee_rates.insert(vault.address, fee);
+            }
+        }
+        self
+    }
+
+    /// Replay one transaction. Anything but a vault deposit or withdrawal is ignored.
+    pub fn record(&mut self, tx: &UserTransaction) {
+        let TransactionData::Vault {
+            vault,
+            shares,
+            assets,
+            assets_usd,
+        } = &tx.data
+        else {
+            return;
+        };
+        if !matches!(
+            tx.kind,
+            TransactionKind::VaultDeposit | TransactionKind::VaultWithdraw
+        ) {
+            return;
+        }
+
+        let method = self.method;
+        let ledger = &mut self.ledgers.entry(*vault).or_default();
+        let assets = u256_to_f64(*assets);
+        match assets_usd {
+            Some(usd) if assets > 0.0 => ledger.last_price_usd = Some(usd / assets),
+            Some(_) => {}
+            None => ledger.complete = false,
+        }
+        let usd = assets_usd.unwrap_or(0.0);
+
+        if tx.kind == TransactionKind::VaultDeposit {
+            ledger.deposit(
+                method,
+                Lot {
+                    shares: *shares,
+                    assets,
+                    cost_usd: usd,
+                },
+            );
+        } else {
+            let redeemed = ledger.withdraw(*shares);
+            ledger.realized_usd += usd - redeemed.cost_usd;
+            ledger.realized_yield_assets += assets - redeemed.assets;
+        }
+    }
+
+    /// Replay transactions in chronological order (by block and log index).
+    pub fn record_all(&mut self, txs: &[UserTransaction]) {
+        let mut txs: Vec<_> = txs.iter().collect();
+        txs.sort_by_key(|tx| (tx.block_number, tx.log_index));
+        for tx in txs {
+            self.record(tx);
+        }
+    }
+
+    /// Replay a transaction history. If it skipped or cut off transactions, every vault
+    /// is reported incomplete, since the missing ones may belong to any of them.
+    pub fn record_history(&mut self, history: &UserTransactionHistory) {
+        self.history_complete &= history.is_complete();
+        self.record_all(&history.transactions);
+    }
+
+    /// Add reward value earned by holding `vault`, e.g. from claimed reward tokens.
+    pub fn record_reward(&mut self, vault: Address, usd: f64) {
+        self.ledgers.entry(vault).or_default().rewards_usd += usd;
+    }
+
+    /// Attribute the PnL of every replayed vault against the user's current positions.
+    ///
+    /// Vaults missing from `positions` are treated as closed. Positions in vaults without
+    /// replayed transactions have no cost basis and are not reported.
+    pub fn report(&self, positions: &[UnifiedPosition]) -> PnlReport {
+        let current: HashMap<Address, (U256, f64, Option<f64>)> = positions
+            .iter()
+            .filter_map(|position| match position {
+                UnifiedPosition::VaultV1(v) | UnifiedPosition::VaultV2(v) => Some((
+                    v.vault.address,
+                    (v.shares, u256_to_f64(v.assets), v.assets_usd),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        let vaults: Vec<VaultPnl> = self
+            .ledgers
+            .iter()
+            .map(|(vault, ledger)| {
+                let mut complete = ledger.complete && self.history_complete;
+                let (shares, assets, current_usd) = match current.get(vault) {
+                    Some(&(shares, assets, usd)) => {
+                        complete &= usd.is_some();
+                        (shares, assets, usd.unwrap_or(0.0))
+                    }
+                    None => (U256::ZERO, 0.0, 0.0),
+                };
+                let held_shares = ledger.shares();
+                if held_shares != shares {
+                    complete = false;
+                }
+                let cost_basis_usd: f64 = ledger.lots.iter().map(|lot| lot.cost_usd).sum();
+                let cost_basis_assets: f64 = ledger.lots.iter().map(|lot| lot.assets).sum();
+
+                let price = (assets > 0.0 && current_usd > 0.0)
+                    .then(|| current_usd / assets)
+                    .or(ledger.last_price_usd)
+                    .unwrap_or(0.0);
+                let unrealized_yield_assets = if assets > 0.0 {
+                    assets - cost_basis_assets
+                } else {
+                    0.0
+                };
+                let net_yield_assets = ledger.realized_yield_assets + unrealized_yield_assets;
+                let fees_usd = match self.fee_rates.get(vault) {
+                    Some(&fee) if fee < 1.0 && net_yield_assets > 0.0 => {
+                        net_yield_assets * fee / (1.0 - fee) * price
+                    }
+                    Some(_) => 0.0,
+                    None => {
+                        complete = false;
+                        0.0
+                    }
+                };
+
+                let unrealized_pnl_usd = current_usd - cost_basis_usd;
+                VaultPnl {
+                    vault: *vault,
+                    shares: held_shares,
+                    cost_basis_usd,
+                    current_usd,
+                    realized_pnl_usd: ledger.realized_usd,
+                    unrealized_pnl_usd,
+                    fees_usd,
+                    rewards_usd: ledger.rewards_usd,
+                    total_pnl_usd: ledger.realized_usd + unrealized_pnl_usd + ledger.rewards_usd,
+                    complete,
+                }
+            })
+            .collect();
+
+        PnlReport {
+            realized_pnl_usd: vaults.iter().map(|v| v.realized_pnl_usd).sum(),
+            unrealized_pnl_usd: vaults.iter().map(|v| v.unrealized_pnl_usd).sum(),
+            fees_usd: vaults.iter().map(|v| v.fees_usd).sum(),
+            rewards_usd: vaults.iter().map(|v| v.rewards_usd).sum(),
+            total_pnl_usd: vaults.iter().map(|v| v.total_pnl_usd).sum(),
+            vaults,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use morpho_rs_api::{NamedChain, VaultInfo};
+
+    use crate::position::VaultShares;
+
+    const VAULT: Address = Address::repeat_byte(0x11);
+
+    fn tx(
+        block: u64,
+        kind: TransactionKind,
+        shares: u64,
+        assets: u64,
+        usd: f64,
+    ) -> UserTransaction {
+        UserTransaction {
+            hash: B256::repeat_byte(block as u8),
+            timestamp: block * 12,
+            block_number: block,
+            log_index: 0,
+            chain: NamedChain::Mainnet,
+            kind,
+            data: TransactionData::Vault {
+                vault: VAULT,
+                shares: U256::from(shares),
+                assets: U256::from(assets),
+                assets_usd: Some(usd),
+            },
+        }
+    }
+
+    fn position(shares: u64, assets: u64, usd: f64) -> UnifiedPosition {
+        UnifiedPosition::VaultV1(VaultShares {
+            vault: VaultInfo {
+                address: VAULT,
+                name: "Vault".to_string(),
+                symbol: "VLT".to_string(),
+                chain: NamedChain::Mainnet,
+            },
+            shares: U256::from(shares),
+            assets: U256::from(assets),
+            assets_usd: Some(usd),
+            asset: None,
+            net_apy: None,
+        })
+    }
+
+    /// Deposits of 100 shares at $1 and at $2, then a withdrawal of 100 shares for $300.
+    fn history() -> Vec<UserTransaction> {
+        vec![
+            tx(3, TransactionKind::VaultWithdraw, 100, 300, 300.0),
+            tx(1, TransactionKind::VaultDeposit, 100, 100, 100.0),
+            tx(2, TransactionKind::VaultDeposit, 100, 200, 200.0),
+        ]
+    }
+
+    #[test]
+    fn test_fifo_redeems_oldest_lot() {
+        let mut engine = PnlEngine::new(CostBasisMethod::Fifo).with_fee_rate(VAULT, 0.0);
+        engine.record_all(&history());
+        let report = engine.report(&[position(100, 300, 300.0)]);
+
+        let vault = &report.vaults[0];
+        assert_eq!(vault.shares, U256::from(100u64));
+        assert_eq!(vault.realized_pnl_usd, 200.0);
+        assert_eq!(vault.cost_basis_usd, 200.0);
+        assert_eq!(vault.unrealized_pnl_usd, 100.0);
+        assert_eq!(vault.total_pnl_usd, 300.0);
+        assert!(vault.complete);
+        assert_eq!(report.total_pnl_usd, 300.0);
+    }
+
+    #[test]
+    fn test_average_redeems_at_average_cost() {
+        let mut engine = PnlEngine::new(CostBasisMethod::Average).with_fee_rate(VAULT, 0.0);
+        engine.record_all(&history());
+        let report = engine.report(&[position(100, 300, 300.0)]);
+
+        let vault = &report.vaults[0];
+        assert_eq!(vault.realized_pnl_usd, 150.0);
+        assert_eq!(vault.cost_basis_usd, 150.0);
+        assert_eq!(vault.unrealized_pnl_usd, 150.0);
+        assert_eq!(vault.total_pnl_usd, 300.0);
+    }
+
+    #[test]
+    fn test_fees_and_rewards() {
+        let mut engine = PnlEngine::new(CostBasisMethod::Fifo).with_fee_rate(VAULT, 0.2);
+        engine.record_all(&history());
+        engine.record_reward(VAULT, 25.0);
+        let report = engine.report(&[position(100, 300, 300.0)]);
+
+        // 200 assets withdrawn over cost and 100 unrealized: 300 net, 375 gross at a 20% fee.
+        let vault = &report.vaults[0];
+        assert!((vault.fees_usd - 75.0).abs() < 1e-9);
+        assert_eq!(vault.rewards_usd, 25.0);
+        assert_eq!(vault.total_pnl_usd, 325.0);
+        assert_eq!(report.rewards_usd, 25.0);
+    }
+
+    #[test]
+    fn test_incomplete_history() {
+        let mut history = UserTransactionHistory {
+            transactions: history(),
+            ..Default::default()
+        };
+        let mut engine = PnlEngine::new(CostBasisMethod::Fifo).with_fee_rate(VAULT, 0.0);
+        engine.record_history(&history);
+        assert!(engine.report(&[position(100, 300, 300.0)]).vaults[0].complete);
+
+        // A transaction the API returned but that could not be parsed may touch any vault
+        history.skipped = 1;
+        let mut engine = PnlEngine::new(CostBasisMethod::Fifo).with_fee_rate(VAULT, 0.0);
+        engine.record_history(&history);
+        let report = engine.report(&[position(100, 300, 300.0)]);
+        assert_eq!(report.total_pnl_usd, 300.0);
+        assert!(!report.vaults[0].complete);
+    }
+
+    #[test]
+    fn test_closed_and_incomplete_positions() {
+        let mut engine = PnlEngine::new(CostBasisMethod::Fifo);
+        engine.record_all(&[
+            tx(1, TransactionKind::VaultDeposit, 100, 100, 100.0),
+            tx(2, TransactionKind::VaultWithdraw, 150, 160, 160.0),
+        ]);
+        let report = engine.report(&[]);
+
+        let vault = &report.vaults[0];
+        assert_eq!(vault.shares, U256::ZERO);
+        assert_eq!(vault.current_usd, 0.0);
+        assert_eq!(vault.realized_pnl_usd, 60.0);
+        assert_eq!(vault.unrealized_pnl_usd, 0.0);
+        assert!(!vault.complete);
+    }
+}
@@ -0,0 +1,422 @@
+//! Unified positions across V1 vaults, V2 vaults, and Morpho Blue markets.
+//!
+//! [`UnifiedPosition`] puts every kind of user position behind the same three questions:
+//! what is it worth ([`current_value_usd`](UnifiedPosition::current_value_usd)), what does
+//! it earn ([`net_apy`](UnifiedPosition::net_apy)), and which asset is it exposed to
+//! ([`underlying_exposure`](UnifiedPosition::underlying_exposure)). Borrows are liabilities:
+//! their value and exposure are negative, and their APY is a cost.
+//!
+//! Account overviews from the API carry no vault APYs or vault assets, and no market
+//! rates. Fill vault data in with [`enrich_with_vaults`] and market rates with
+//! [`UnifiedPosition::with_apy`]; until then those methods return `None`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use alloy_primitives::address;
+//! use morpho_rs_api::{MorphoApiClient, NamedChain};
+//! use morpho_rs_strategy::position::{
+//!     enrich_with_vaults, net_exposure, portfolio_net_apy, total_value_usd, UnifiedPosition,
+//! };
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoApiClient::new();
+//!     let overview = client
+//!         .get_user_account_overview(
+//!             address!("0x0000000000000000000000000000000000000001"),
+//!             NamedChain::Mainnet,
+//!         )
+//!         .await?;
+//!
+//!     let mut positions = UnifiedPosition::from_account_overview(&overview);
+//!     let vaults_v1 = client.v1.get_vaults_by_chain(NamedChain::Mainnet).await?;
+//!     let vaults_v2 = client.v2.get_vaults_by_chain(NamedChain::Mainnet).await?;
+//!     enrich_with_vaults(&mut positions, &vaults_v1, &vaults_v2);
+//!
+//!     println!("value: ${:.2}", total_value_usd(&positions));
+//!     println!("net APY: {:?}", portfolio_net_apy(&positions));
+//!     for (asset, usd) in net_exposure(&positions) {
+//!         println!("{asset}: ${usd:.2}");
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, U256};
+use morpho_rs_api::{
+    MarketInfo, UserAccountOverview, UserMarketPosition, UserVaultV1Position, UserVaultV2Position,
+    VaultInfo, VaultV1, VaultV2,
+};
+use serde::{Deserialize, Serialize};
+
+/// Shares held in a V1 or V2 vault.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultShares {
+    /// The vault.
+    pub vault: VaultInfo,
+    /// Number of shares held.
+    pub shares: U256,
+    /// Underlying assets the shares are worth.
+    pub assets: U256,
+    /// Underlying assets value in USD.
+    pub assets_usd: Option<f64>,
+    /// The vault's underlying asset, if known.
+    pub asset: Option<Address>,
+    /// The vault's net APY (as a fraction), if known.
+    pub net_apy: Option<f64>,
+}
+
+/// One side (supply, borrow, or collateral) of a market position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketLeg {
+    /// The market.
+    pub market: MarketInfo,
+    /// Shares held (zero for collateral, which is not share-based).
+    pub shares: U256,
+    /// Amount of the leg's asset.
+    pub assets: U256,
+    /// Amount in USD.
+    pub assets_usd: Option<f64>,
+    /// Supply or borrow APY of the leg (as a positive fraction), if known.
+    pub apy: Option<f64>,
+}
+
+/// A signed USD exposure to one asset.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Exposure {
+    /// The asset.
+    pub asset: Address,
+    /// USD value (negative for borrows).
+    pub value_usd: f64,
+}
+
+/// A user position of any kind.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UnifiedPosition {
+    /// Shares in a V1 (MetaMorpho) vault.
+    VaultV1(VaultShares),
+    /// Shares in a V2 vault.
+    VaultV2(VaultShares),
+    /// Loan assets supplied to a market.
+    MarketSupply(MarketLeg),
+    /// Loan assets borrowed from a market.
+    MarketBorrow(MarketLeg),
+    /// Collateral posted in a market.
+    MarketCollateral(MarketLeg),
+}
+
+impl UnifiedPosition {
+    /// Flatten an account overview into positions.
+    ///
+    /// Each market position yields one position per non-zero leg (supply, borrow,
+    /// collateral).
+    pub fn from_account_overview(overview: &UserAccountOverview) -> Vec<Self> {
+        overview
+            .vault_positions
+            .iter()
+            .map(Self::from)
+            .chain(overview.vault_v2_positions.iter().map(Self::from))
+            .chain(
+                overview
+                    .market_positions
+                    .iter()
+                    .flat_map(Self::from_market_position),
+            )
+            .collect()
+    }
+
+    /// Split a market position into its non-zero supply, borrow, and collateral legs.
+    pub fn from_market_position(position: &UserMarketPosition) -> Vec<Self> {
+        let leg = |shares: U256, assets: U256, assets_usd: Option<f64>| MarketLeg {
+            market: position.market.clone(),
+            shares,
+            assets,
+            assets_usd,
+            apy: None,
+        };
+
+        let mut legs = Vec::new();
+        if !position.supply_assets.is_zero() {
+            legs.push(Self::MarketSupply(leg(
+                position.supply_shares,
+                position.supply_assets,
+                position.supply_assets_usd,
+            )));
+        }
+        if !position.borrow_assets.is_zero() {
+            legs.push(Self::MarketBorrow(leg(
+                position.borrow_shares,
+                position.borrow_assets,
+                position.borrow_assets_usd,
+            )));
+        }
+        if !position.collateral.is_zero() {
+            legs.push(Self::MarketCollateral(leg(
+                U256::ZERO,
+                position.collateral,
+                position.collateral_usd,
+            )));
+        }
+        legs
+    }
+
+    /// Set the position's APY: the vault net APY, or the market leg's supply/borrow APY.
+    ///
+    /// Pass the APY as a positive fraction for borrows too; [`net_apy`](Self::net_apy)
+    /// reports it as a cost.
+    pub fn with_apy(mut self, apy: f64) -> Self {
+        match &mut self {
+            Self::VaultV1(v) | Self::VaultV2(v) => v.net_apy = Some(apy),
+            Self::MarketSupply(m) | Self::MarketBorrow(m) | Self::MarketCollateral(m) => {
+                m.apy = Some(apy)
+            }
+        }
+        self
+    }
+
+    /// Returns true for borrow positions.
+    pub fn is_liability(&self) -> bool {
+        matches!(self, Self::MarketBorrow(_))
+    }
+
+    /// Current USD value, negative for borrows. `None` if the API has no USD price.
+    pub fn current_value_usd(&self) -> Option<f64> {
+        let value = match self {
+            Self::VaultV1(v) | Self::VaultV2(v) => v.assets_usd,
+            Self::MarketSupply(m) | Self::MarketBorrow(m) | Self::MarketCollateral(m) => {
+                m.assets_usd
+            }
+        }?;
+        Some(if self.is_liability() { -value } else { value })
+    }
+
+    /// Net APY earned on the position (negative for borrows, whose APY is a cost).
+    ///
+    /// Collateral earns nothing on Morpho Blue, so it reports zero unless an APY was set
+    /// with [`with_apy`](Self::with_apy). `None` if the APY is unknown.
+    pub fn net_apy(&self) -> Option<f64> {
+        match self {
+            Self::VaultV1(v) | Self::VaultV2(v) => v.net_apy,
+            Self::MarketSupply(m) => m.apy,
+            Self::MarketBorrow(m) => m.apy.map(|apy| -apy),
+            Self::MarketCollateral(m) => Some(m.apy.unwrap_or(0.0)),
+        }
+    }
+
+    /// The asset the position is exposed to and the signed USD value of that exposure.
+    ///
+    /// Supply and borrow legs are exposed to the loan asset, collateral to the collateral
+    /// asset, and vault shares to the vault's asset. `None` if the asset or USD value is
+    /// unknown.
+    pub fn underlying_exposure(&self) -> Option<Exposure> {
+        let asset = match self {
+            Self::VaultV1(v) | Self::VaultV2(v) => v.asset,
+            Self::MarketSupply(m) | Self::MarketBorrow(m) => m.market.loan_asset_address,
+            Self::MarketCollateral(m) => m.market.collateral_asset_address,
+        }?;
+        Some(Exposure {
+            asset,
+            value_usd: self.current_value_usd()?,
+        })
+    }
+}
+
+impl From<&UserVaultV1Position> for UnifiedPosition {
+    fn from(position: &UserVaultV1Position) -> Self {
+        Self::VaultV1(VaultShares {
+            vault: position.vault.clone(),
+            shares: position.shares,
+            assets: position.assets,
+            assets_usd: position.assets_usd,
+            asset: None,
+            net_apy: None,
+        })
+    }
+}
+
+impl From<&UserVaultV2Position> for UnifiedPosition {
+    fn from(position: &UserVaultV2Position) -> Self {
+        Self::VaultV2(VaultShares {
+            vault: position.vault.clone(),
+            shares: position.shares,
+            assets: position.assets,
+            assets_usd: position.assets_usd,
+            asset: None,
+            net_apy: None,
+        })
+    }
+}
+
+/// Fill in vault assets and net APYs from vault data, matching on address and chain.
+///
+/// Positions in vaults not found in `vaults_v1`/`vaults_v2` are left unchanged.
+pub fn enrich_with_vaults(
+    positions: &mut [UnifiedPosition],
+    vaults_v1: &[VaultV1],
+    vaults_v2: &[VaultV2],
+) {
+    for position in positions {
+        match position {
+            UnifiedPosition::VaultV1(v) => {
+                if let Some(vault) = vaults_v1
+                    .iter()
+                    .find(|vault| vault.address == v.vault.address && vault.chain == v.vault.chain)
+                {
+                    v.asset = Some(vault.asset.address);
+                    v.net_apy = vault.state.as_ref().map(|s| s.net_apy).or(v.net_apy);
+                }
+            }
+            UnifiedPosition::VaultV2(v) => {
+                if let Some(vault) = vaults_v2
+                    .iter()
+                    .find(|vault| vault.address == v.vault.address && vault.chain == v.vault.chain)
+                {
+                    v.asset = Some(vault.asset.address);
+                    v.net_apy = vault.net_apy.or(v.net_apy);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Sum of the known USD values of `positions` (borrows subtract).
+pub fn total_value_usd(positions: &[UnifiedPosition]) -> f64 {
+    positions
+        .iter()
+        .filter_map(UnifiedPosition::current_value_usd)
+        .sum()
+}
+
+/// Value-weighted net APY of the positions with both a known value and APY, on net equity.
+///
+/// Returns `None` if no such positions exist or their net equity is not positive.
+pub fn portfolio_net_apy(positions: &[UnifiedPosition]) -> Option<f64> {
+    let (earned, equity) = positions
+        .iter()
+        .filter_map(|p| Some((p.current_value_usd()?, p.net_apy()?)))
+        .fold((0.0, 0.0), |(earned, equity), (value, apy)| {
+            (earned + value.abs() * apy, equity + value)
+        });
+    (equity > 0.0).then(|| earned / equity)
+}
+
+/// Net signed USD exposure per underlying asset.
+pub fn net_exposure(positions: &[UnifiedPosition]) -> HashMap<Address, f64> {
+    let mut exposure = HashMap::new();
+    for e in positions
+        .iter()
+        .filter_map(UnifiedPosition::underlying_exposure)
+    {
+        *exposure.entry(e.asset).or_insert(0.0) += e.value_usd;
+    }
+    exposure
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use morpho_rs_api::NamedChain;
+
+    const USDC: Address = Address::repeat_byte(0xAA);
+    const WETH: Address = Address::repeat_byte(0xBB);
+
+    fn vault_info(byte: u8) -> VaultInfo {
+        VaultInfo {
+            address: Address::repeat_byte(byte),
+            name: "Vault".to_string(),
+            symbol: "VLT".to_string(),
+            chain: NamedChain::Mainnet,
+        }
+    }
+
+    fn market_position(supply: u64, borrow: u64, collateral: u64) -> UserMarketPosition {
+        UserMarketPosition {
+            id: "pos".to_string(),
+            supply_shares: U256::from(supply) * U256::from(1_000_000u64),
+            supply_assets: U256::from(supply),
+            supply_assets_usd: Some(supply as f64),
+            borrow_shares: U256::from(borrow) * U256::from(1_000_000u64),
+            borrow_assets: U256::from(borrow),
+            borrow_assets_usd: Some(borrow as f64),
+            collateral: U256::from(collateral),
+            collateral_usd: Some(collateral as f64),
+            health_factor: None,
+            market: MarketInfo {
+                unique_key: "0x01".to_string(),
+                loan_asset_symbol: Some("USDC".to_string()),
+                loan_asset_address: Some(USDC),
+                collateral_asset_symbol: Some("WETH".to_string()),
+                collateral_asset_address: Some(WETH),
+            },
+        }
+    }
+
+    fn v1_position(byte: u8, assets_usd: f64) -> UserVaultV1Position {
+        UserVaultV1Position {
+            id: "v1".to_string(),
+            shares: U256::from(1u64),
+            assets: U256::from(1u64),
+            assets_usd: Some(assets_usd),
+            vault: vault_info(byte),
+            state: None,
+        }
+    }
+
+    #[test]
+    fn test_market_position_splits_into_non_zero_legs() {
+        let legs = UnifiedPosition::from_market_position(&market_position(0, 400, 1000));
+        assert_eq!(legs.len(), 2);
+        assert!(legs[0].is_liability());
+        assert_eq!(legs[0].current_value_usd(), Some(-400.0));
+        assert!(matches!(legs[1], UnifiedPosition::MarketCollateral(_)));
+        assert_eq!(legs[1].current_value_usd(), Some(1000.0));
+    }
+
+    #[test]
+    fn test_net_apy_signs() {
+        let legs = UnifiedPosition::from_market_position(&market_position(100, 400, 1000));
+        let supply = legs[0].clone().with_apy(0.04);
+        let borrow = legs[1].clone().with_apy(0.06);
+        assert_eq!(supply.net_apy(), Some(0.04));
+        assert_eq!(borrow.net_apy(), Some(-0.06));
+        assert_eq!(legs[2].net_apy(), Some(0.0));
+
+        let vault = UnifiedPosition::from(&v1_position(1, 100.0));
+        assert_eq!(vault.net_apy(), None);
+        assert_eq!(vault.with_apy(0.05).net_apy(), Some(0.05));
+    }
+
+    #[test]
+    fn test_underlying_exposure() {
+        let legs = UnifiedPosition::from_market_position(&market_position(100, 400, 1000));
+        let exposure = net_exposure(&legs);
+        assert_eq!(exposure[&USDC], -300.0);
+        assert_eq!(exposure[&WETH], 1000.0);
+
+        // Vault asset is unknown until enriched.
+        let vault = UnifiedPosition::from(&v1_position(1, 100.0));
+        assert_eq!(vault.underlying_exposure(), None);
+    }
+
+    #[test]
+    fn test_portfolio_totals() {
+        let mut positions = UnifiedPosition::from_market_position(&market_position(0, 400, 1000));
+        positions[0] = positions[0].clone().with_apy(0.05);
+        positions.push(UnifiedPosition::from(&v1_position(1, 400.0)).with_apy(0.10));
+
+        assert_eq!(total_value_usd(&positions), 1000.0);
+        // Earns 40 on the vault, pays 20 on the borrow, over 1000 of equity.
+        let apy = portfolio_net_apy(&positions).unwrap();
+        assert!((apy - 0.02).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_portfolio_net_apy_without_data() {
+        let positions = vec![UnifiedPosition::from(&v1_position(1, 100.0))];
+        assert_eq!(portfolio_net_apy(&positions), None);
+    }
+}
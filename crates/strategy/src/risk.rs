@@ -0,0 +1,856 @@
+//! Composite risk scoring for V1 and V2 vaults.
+//!
+//! A vault's risk score combines six factors, each normalized to `[0, 1]` where higher
+//! is riskier:
+//!
+//! | Factor | Measure |
+//! |--------|---------|
+//! | `concentration` | Herfindahl-Hirschman index (HHI) of supply across markets |
+//! | `collateral_concentration` | HHI of supply across collateral assets |
+//! | `lltv_exposure` | Supply-weighted average LLTV |
+//! | `warnings` | `1.0` for any red warning, `0.25` per yellow warning or mispriced market (capped at `1.0`) |
+//! | `curator` | `1 - reputation` of the curator (see [`RiskScorer::with_curator_reputation`]) |
+//! | `illiquidity` | Share of vault assets that cannot be withdrawn from markets right now |
+//!
+//! The composite [`RiskBreakdown::score`] is the weighted average of the factors scaled to
+//! `0..=100`. Idle supply (markets without collateral) counts toward diversification and
+//! liquidity but carries no LLTV exposure.
+//!
+//! Markets flagged by an oracle price check (see
+//! [`RiskScorer::with_price_deviations`]) count as one yellow warning each when the vault
+//! supplies to them.
+//!
+//! V2 vaults are scored with [`RiskScorer::score_v2`] on the markets their adapters
+//! supply to, looked through MetaMorpho adapters to the underlying allocations. Assets
+//! held idle by the vault count as idle supply, and adapters the SDK does not model
+//! count as one market of unknown collateral. Illiquidity is the share of total assets
+//! the vault reports as not withdrawable.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use morpho_rs_api::{MorphoApiClient, NamedChain};
+//! use morpho_rs_strategy::risk::{top_vaults_under_risk, RiskScorer};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_api::ApiError> {
+//!     let client = MorphoApiClient::new();
+//!     let vaults = client.v1.get_vaults_by_chain(NamedChain::Mainnet).await?;
+//!
+//!     // Top 5 vaults by net APY with a risk score of at most 40
+//!     for (vault, risk) in top_vaults_under_risk(&RiskScorer::new(), &vaults, 40.0, 5) {
+//!         println!("{}: score {:.1}", vault.name, risk.score);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::{Address, B256, U256};
+use morpho_rs_api::{
+    MarketStateV2, PriceDeviation, VaultAdapterData, VaultAllocation, VaultCapData, VaultV1,
+    VaultV2, WarningLevel,
+};
+use serde::{Deserialize, Serialize};
+
+/// Reputation assumed for curators without a configured track record.
+pub const DEFAULT_CURATOR_REPUTATION: f64 = 0.5;
+
+/// Relative weights of the risk factors.
+///
+/// Weights need not sum to one; the composite score is normalized by their total.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskWeights {
+    /// Weight of market concentration.
+    pub concentration: f64,
+    /// Weight of collateral asset concentration.
+    pub collateral_concentration: f64,
+    /// Weight of LLTV exposure.
+    pub lltv_exposure: f64,
+    /// Weight of warning flags.
+    pub warnings: f64,
+    /// Weight of curator track record.
+    pub curator: f64,
+    /// Weight of illiquidity.
+    pub illiquidity: f64,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            concentration: 0.2,
+            collateral_concentration: 0.15,
+            lltv_exposure: 0.2,
+            warnings: 0.25,
+            curator: 0.1,
+            illiquidity: 0.1,
+        }
+    }
+}
+
+impl RiskWeights {
+    /// Set the market concentration weight.
+    pub fn with_concentration(mut self, weight: f64) -> Self {
+        self.concentration = weight;
+        self
+    }
+
+    /// Set the collateral concentration weight.
+    pub fn with_collateral_concentration(mut self, weight: f64) -> Self {
+        self.collateral_concentration = weight;
+        self
+    }
+
+    /// Set the LLTV exposure weight.
+    pub fn with_lltv_exposure(mut self, weight: f64) -> Self {
+        self.lltv_exposure = weight;
+        self
+    }
+
+    /// Set the warnings weight.
+    pub fn with_warnings(mut self, weight: f64) -> Self {
+        self.warnings = weight;
+        self
+    }
+
+    /// Set the curator weight.
+    pub fn with_curator(mut self, weight: f64) -> Self {
+        self.curator = weight;
+        self
+    }
+
+    /// Set the illiquidity weight.
+    pub fn with_illiquidity(mut self, weight: f64) -> Self {
+        self.illiquidity = weight;
+        self
+    }
+
+    fn total(&self) -> f64 {
+        self.concentration
+            + self.collateral_concentration
+            + self.lltv_exposure
+            + self.warnings
+            + self.curator
+            + self.illiquidity
+    }
+}
+
+/// Per-factor risk components and the composite score for a vault.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskBreakdown {
+    /// Market concentration (HHI, `0..=1`).
+    pub concentration: f64,
+    /// Collateral asset concentration (HHI, `0..=1`).
+    pub collateral_concentration: f64,
+    /// Supply-weighted average LLTV (`0..=1`).
+    pub lltv_exposure: f64,
+    /// Warning severity (`0..=1`).
+    pub warnings: f64,
+    /// Curator risk (`0..=1`).
+    pub curator: f64,
+    /// Share of assets not immediately withdrawable (`0..=1`).
+    pub illiquidity: f64,
+    /// Weighted composite score (`0..=100`, higher is riskier).
+    pub score: f64,
+}
+
+/// Scores vaults with configurable weights and curator reputations.
+#[derive(Debug, Clone, Default)]
+pub struct RiskScorer {
+    weights: RiskWeights,
+    curator_reputation: HashMap<Address, f64>,
+    mispriced_markets: HashSet<B256>,
+}
+
+impl RiskScorer {
+    /// Create a scorer with default weights and no curator reputations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the factor weights.
+    pub fn with_weights(mut self, weights: RiskWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Set a curator's reputation (`0` = no track record / poor, `1` = excellent).
+    ///
+    /// Curators without a configured reputation use [`DEFAULT_CURATOR_REPUTATION`].
+    pub fn with_curator_reputation(mut self, curator: Address, reputation: f64) -> Self {
+        self.curator_reputation
+            .insert(curator, reputation.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Flag markets whose reported oracle price deviates from the on-chain price.
+    ///
+    /// Each flagged market a vault supplies to counts as a yellow warning.
+    pub fn with_price_deviations(mut self, deviations: &[PriceDeviation]) -> Self {
+        self.mispriced_markets
+            .extend(deviations.iter().map(|d| d.market_id));
+        self
+    }
+
+    /// Get the factor weights.
+    pub fn weights(&self) -> &RiskWeights {
+        &self.weights
+    }
+
+    /// Compute the risk breakdown for a vault.
+    pub fn score(&self, vault: &VaultV1) -> RiskBreakdown {
+        let allocations: &[VaultAllocation] = vault
+            .state
+            .as_ref()
+            .map(|s| s.allocation.as_slice())
+            .unwrap_or(&[]);
+        let supplies: Vec<f64> = allocations
+            .iter()
+            .map(|a| u256_to_f64(a.supply_assets))
+            .collect();
+        let total: f64 = supplies.iter().sum();
+
+        let concentration = hhi(&supplies, total);
+
+        let mut by_collateral: HashMap<Option<Address>, f64> = HashMap::new();
+        for (allocation, supply) in allocations.iter().zip(&supplies) {
+            *by_collateral
+                .entry(allocation.collateral_asset_address)
+                .or_default() += supply;
+        }
+        let collateral_supplies: Vec<f64> = by_collateral.into_values().collect();
+        let collateral_concentration = hhi(&collateral_supplies, total);
+
+        let lltv_exposure = if total > 0.0 {
+            allocations
+                .iter()
+                .zip(&supplies)
+                .filter(|(a, _)| a.collateral_asset_address.is_some())
+                .filter_map(|(a, supply)| {
+                    let lltv = a.market_state.as_ref()?.lltv;
+                    Some(wad_to_f64(lltv) * supply)
+                })
+                .sum::<f64>()
+                / total
+        } else {
+            0.0
+        };
+
+        let yellow = vault
+            .warnings
+            .iter()
+            .filter(|w| w.level == WarningLevel::Yellow)
+            .count()
+            + self.mispriced_count(
+                allocations
+                    .iter()
+                    .filter(|a| !a.supply_assets.is_zero())
+                    .filter_map(|a| a.market_state.as_ref().map(|m| m.id)),
+            );
+        let warnings = warning_risk(vault.has_red_warnings(), yellow);
+
+        let curator = self.curator_risk(vault.state.as_ref().and_then(|s| s.curator));
+
+        let illiquidity = if total > 0.0 {
+            let withdrawable: f64 = allocations
+                .iter()
+                .zip(&supplies)
+                .map(|(a, supply)| match &a.market_state {
+                    Some(m) => supply.min(u256_to_f64(m.liquidity)),
+                    None => 0.0,
+                })
+                .sum();
+            1.0 - (withdrawable / total).min(1.0)
+        } else {
+            0.0
+        };
+
+        let mut breakdown = RiskBreakdown {
+            concentration,
+            collateral_concentration,
+            lltv_exposure,
+            warnings,
+            curator,
+            illiquidity,
+            score: 0.0,
+        };
+        breakdown.score = self.composite(&breakdown);
+        breakdown
+    }
+
+    /// Compute the risk breakdown for a V2 vault.
+    ///
+    /// Collateral assets are taken from the vault's market caps; markets without a known
+    /// collateral count as their own collateral unless their LLTV is zero (idle markets).
+    pub fn score_v2(&self, vault: &VaultV2) -> RiskBreakdown {
+        let collaterals: HashMap<B256, Address> = vault
+            .caps
+            .iter()
+            .filter_map(|cap| match &cap.data {
+                Some(VaultCapData::MarketV1 {
+                    market_id,
+                    collateral,
+                    ..
+                }) => Some((*market_id, *collateral)),
+                _ => None,
+            })
+            .collect();
+
+        // (market bucket, collateral bucket, supply, LLTV)
+        let mut exposures: Vec<(Bucket, Bucket, f64, f64)> = Vec::new();
+        if !vault.idle_assets.is_zero() {
+            exposures.push((
+                Bucket::Idle,
+                Bucket::Idle,
+                u256_to_f64(vault.idle_assets),
+                0.0,
+            ));
+        }
+        for adapter in &vault.adapters {
+            let markets: Vec<(B256, U256, U256)> = match &adapter.data {
+                Some(VaultAdapterData::MorphoMarketV1 { positions }) => positions
+                    .iter()
+                    .map(|p| (p.market_id, p.supply_assets, market_lltv(&p.market_state)))
+                    .collect(),
+                Some(VaultAdapterData::MetaMorpho { allocations, .. }) => allocations
+                    .iter()
+                    .map(|a| (a.market_id, a.supply_assets, market_lltv(&a.market_state)))
+                    .collect(),
+                Some(VaultAdapterData::Unknown { .. }) | None => {
+                    let bucket = Bucket::Adapter(adapter.address);
+                    exposures.push((bucket, bucket, u256_to_f64(adapter.assets), 0.0));
+                    continue;
+                }
+            };
+            for (market_id, supply, lltv) in markets {
+                let collateral = match collaterals.get(&market_id) {
+                    Some(collateral) => Bucket::Collateral(*collateral),
+                    None if lltv.is_zero() => Bucket::Idle,
+                    None => Bucket::Market(market_id),
+                };
+                exposures.push((
+                    Bucket::Market(market_id),
+                    collateral,
+                    u256_to_f64(supply),
+                    wad_to_f64(lltv),
+                ));
+            }
+        }
+        let total: f64 = exposures.iter().map(|(_, _, supply, _)| supply).sum();
+
+        let mut by_market: HashMap<Bucket, f64> = HashMap::new();
+        let mut by_collateral: HashMap<Bucket, f64> = HashMap::new();
+        for (market, collateral, supply, _) in &exposures {
+            *by_market.entry(*market).or_default() += supply;
+            *by_collateral.entry(*collateral).or_default() += supply;
+        }
+        let concentration = hhi(&by_market.into_values().collect::<Vec<_>>(), total);
+        let collateral_concentration =
+            hhi(&by_collateral.into_values().collect::<Vec<_>>(), total);
+
+        let lltv_exposure = if total > 0.0 {
+            exposures
+                .iter()
+                .map(|(_, _, supply, lltv)| lltv * supply)
+                .sum::<f64>()
+                / total
+        } else {
+            0.0
+        };
+
+        let yellow = vault
+            .warnings
+            .iter()
+            .filter(|w| w.level == WarningLevel::Yellow)
+            .count()
+            + self.mispriced_count(exposures.iter().filter_map(
+                |(market, _, supply, _)| match market {
+                    Bucket::Market(id) if *supply > 0.0 => Some(*id),
+                    _ => None,
+                },
+            ));
+        let warnings = warning_risk(vault.has_red_warnings(), yellow);
+
+        let mut breakdown = RiskBreakdown {
+            concentration,
+            collateral_concentration,
+            lltv_exposure,
+            warnings,
+            curator: self.curator_risk(vault.curator),
+            illiquidity: 1.0 - vault.liquidity_ratio(),
+            score: 0.0,
+        };
+        breakdown.score = self.composite(&breakdown);
+        breakdown
+    }
+
+    /// Number of distinct flagged markets among `markets`.
+    fn mispriced_count(&self, markets: impl Iterator<Item = B256>) -> usize {
+        markets
+            .filter(|id| self.mispriced_markets.contains(id))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn curator_risk(&self, curator: Option<Address>) -> f64 {
+        match curator {
+            Some(curator) => {
+                1.0 - self
+                    .curator_reputation
+                    .get(&curator)
+                    .copied()
+                    .unwrap_or(DEFAULT_CURATOR_REPUTATION)
+            }
+            None => 1.0,
+        }
+    }
+
+    fn composite(&self, b: &RiskBreakdown) -> f64 {
+        let w = &self.weights;
+        let total = w.total();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let weighted = w.concentration * b.concentration
+            + w.collateral_concentration * b.collateral_concentration
+            + w.lltv_exposure * b.lltv_exposure
+            + w.warnings * b.warnings
+            + w.curator * b.curator
+            + w.illiquidity * b.illiquidity;
+        weighted / total * 100.0
+    }
+}
+
+/// Compute the risk breakdown for a vault with default weights.
+pub fn score_vault(vault: &VaultV1) -> RiskBreakdown {
+    RiskScorer::new().score(vault)
+}
+
+/// Compute the risk breakdown for a V2 vault with default weights.
+pub fn score_vault_v2(vault: &VaultV2) -> RiskBreakdown {
+    RiskScorer::new().score_v2(vault)
+}
+
+/// Vaults with a risk score at or below `max_score`, highest net APY first, up to `limit`.
+pub fn top_vaults_under_risk<'a>(
+    scorer: &RiskScorer,
+    vaults: &'a [VaultV1],
+    max_score: f64,
+    limit: usize,
+) -> Vec<(&'a VaultV1, RiskBreakdown)> {
+    let mut scored: Vec<(&VaultV1, RiskBreakdown)> = vaults
+        .iter()
+        .map(|v| (v, scorer.score(v)))
+        .filter(|(_, risk)| risk.score <= max_score)
+        .collect();
+    scored.sort_by(|(a, _), (b, _)| net_apy(b).total_cmp(&net_apy(a)));
+    scored.truncate(limit);
+    scored
+}
+
+fn net_apy(vault: &VaultV1) -> f64 {
+    vault.state.as_ref().map(|s| s.net_apy).unwrap_or(0.0)
+}
+
+/// A diversification bucket of V2 vault supply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Bucket {
+    Idle,
+    Market(B256),
+    Collateral(Address),
+    Adapter(Address),
+}
+
+fn market_lltv(state: &Option<MarketStateV2>) -> U256 {
+    state.as_ref().map(|m| m.lltv).unwrap_or(U256::ZERO)
+}
+
+fn warning_risk(has_red: bool, yellow: usize) -> f64 {
+    if has_red {
+        return 1.0;
+    }
+    (yellow as f64 * 0.25).min(1.0)
+}
+
+/// Herfindahl-Hirschman index of `shares` out of `total` (`1.0` = fully concentrated).
+fn hhi(values: &[f64], total: f64) -> f64 {
+    if total <= 0.0 {
+        return 0.0;
+    }
+    values.iter().map(|v| (v / total).powi(2)).sum()
+}
+
+pub(crate) fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(0.0)
+}
+
+fn wad_to_f64(value: U256) -> f64 {
+    u256_to_f64(value) / 1e18
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use morpho_rs_api::{
+        Asset, MarketStateV1, MorphoMarketPosition, NamedChain, VaultAdapter, VaultCap,
+        VaultStateV1, VaultV2Warning, VaultWarning, WarningType,
+    };
+
+    const WAD: u128 = 1_000_000_000_000_000_000;
+
+    fn allocation(
+        collateral: Option<u8>,
+        supply: u64,
+        lltv_pct: u128,
+        liquidity: u64,
+    ) -> VaultAllocation {
+        VaultAllocation {
+            market_key: format!("{:?}", collateral),
+            loan_asset_symbol: Some("USDC".to_string()),
+            loan_asset_address: Some(Address::repeat_byte(0xAA)),
+            collateral_asset_symbol: collateral.map(|c| format!("C{c}")),
+            collateral_asset_address: collateral.map(Address::repeat_byte),
+            supply_assets: U256::from(supply),
+            supply_assets_usd: None,
+            supply_cap: U256::MAX,
+            enabled: true,
+            supply_queue_index: Some(0),
+            withdraw_queue_index: Some(0),
+            market_state: Some(MarketStateV1 {
+                id: B256::ZERO,
+                total_supply_assets: U256::from(supply),
+                total_borrow_assets: U256::ZERO,
+                total_supply_shares: U256::from(supply),
+                total_borrow_shares: U256::ZERO,
+                last_update: 0,
+                fee: U256::ZERO,
+                rate_at_target: None,
+                price: None,
+                lltv: U256::from(lltv_pct * WAD / 100),
+                liquidity: U256::from(liquidity),
+                realized_bad_debt: None,
+            }),
+        }
+    }
+
+    fn vault(
+        allocations: Vec<VaultAllocation>,
+        net_apy: f64,
+        warnings: Vec<VaultWarning>,
+    ) -> VaultV1 {
+        VaultV1 {
+            address: Address::repeat_byte(0x01),
+            name: format!("Vault {net_apy}"),
+            symbol: "V".to_string(),
+            chain: NamedChain::Mainnet,
+            listed: true,
+            featured: false,
+            whitelisted: true,
+            asset: Asset {
+                address: Address::repeat_byte(0xAA),
+                symbol: "USDC".to_string(),
+                name: None,
+                decimals: 6,
+                price_usd: Some(1.0),
+            },
+            state: Some(VaultStateV1 {
+                curator: Some(Address::repeat_byte(0xC0)),
+                owner: None,
+                guardian: None,
+                total_assets: U256::ZERO,
+                total_assets_usd: None,
+                total_supply: U256::ZERO,
+                fee: 0.1,
+                timelock: 86_400,
+                apy: net_apy,
+                net_apy,
+                share_price: U256::ZERO,
+                allocation: allocations,
+                rewards: vec![],
+            }),
+            allocators: vec![],
+            warnings,
+            public_allocator_config: None,
+        }
+    }
+
+    #[test]
+    fn test_single_market_fully_concentrated() {
+        let v = vault(vec![allocation(Some(1), 1000, 86, 1000)], 0.05, vec![]);
+        let risk = score_vault(&v);
+        assert!((risk.concentration - 1.0).abs() < 1e-9);
+        assert!((risk.collateral_concentration - 1.0).abs() < 1e-9);
+        assert!((risk.lltv_exposure - 0.86).abs() < 1e-9);
+        assert!(risk.illiquidity.abs() < 1e-9);
+        assert!((risk.curator - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_diversified_vault_scores_lower() {
+        let concentrated = vault(vec![allocation(Some(1), 1000, 86, 1000)], 0.05, vec![]);
+        let diversified = vault(
+            vec![
+                allocation(Some(1), 250, 86, 250),
+                allocation(Some(2), 250, 86, 250),
+                allocation(Some(3), 250, 86, 250),
+                allocation(Some(4), 250, 86, 250),
+            ],
+            0.05,
+            vec![],
+        );
+        let a = score_vault(&concentrated);
+        let b = score_vault(&diversified);
+        assert!((b.concentration - 0.25).abs() < 1e-9);
+        assert!(b.score < a.score);
+    }
+
+    #[test]
+    fn test_idle_supply_has_no_lltv_exposure() {
+        let v = vault(
+            vec![
+                allocation(Some(1), 500, 90, 500),
+                allocation(None, 500, 0, 500),
+            ],
+            0.05,
+            vec![],
+        );
+        let risk = score_vault(&v);
+        assert!((risk.lltv_exposure - 0.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_illiquidity() {
+        let v = vault(vec![allocation(Some(1), 1000, 86, 250)], 0.05, vec![]);
+        assert!((score_vault(&v).illiquidity - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_warning_risk() {
+        let yellow = VaultWarning {
+            warning_type: WarningType::ShortTimelock,
+            level: WarningLevel::Yellow,
+        };
+        let red = VaultWarning {
+            warning_type: WarningType::BadDebtUnrealized,
+            level: WarningLevel::Red,
+        };
+        let alloc = || vec![allocation(Some(1), 1000, 86, 1000)];
+        assert!(
+            (score_vault(&vault(alloc(), 0.05, vec![yellow.clone()])).warnings - 0.25).abs() < 1e-9
+        );
+        assert!(
+            (score_vault(&vault(alloc(), 0.05, vec![yellow, red])).warnings - 1.0).abs() < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_price_deviations_count_as_warnings() {
+        let deviation = PriceDeviation {
+            market_id: B256::ZERO,
+            oracle: Address::repeat_byte(0x0A),
+            onchain_price: U256::from(100u64),
+            reported_price: U256::from(90u64),
+            deviation: 0.1,
+        };
+        let v = vault(vec![allocation(Some(1), 1000, 86, 1000)], 0.05, vec![]);
+        let scorer = RiskScorer::new().with_price_deviations(&[deviation]);
+        assert!((scorer.score(&v).warnings - 0.25).abs() < 1e-9);
+
+        let other = PriceDeviation {
+            market_id: B256::repeat_byte(0x01),
+            ..deviation
+        };
+        let scorer = RiskScorer::new().with_price_deviations(&[other]);
+        assert_eq!(scorer.score(&v).warnings, 0.0);
+    }
+
+    #[test]
+    fn test_curator_reputation() {
+        let v = vault(vec![allocation(Some(1), 1000, 86, 1000)], 0.05, vec![]);
+        let scorer = RiskScorer::new().with_curator_reputation(Address::repeat_byte(0xC0), 0.9);
+        assert!((scorer.score(&v).curator - 0.1).abs() < 1e-9);
+        assert!(scorer.score(&v).score < score_vault(&v).score);
+    }
+
+    #[test]
+    fn test_custom_weights() {
+        let v = vault(vec![allocation(Some(1), 1000, 86, 1000)], 0.05, vec![]);
+        let weights = RiskWeights {
+            concentration: 1.0,
+            collateral_concentration: 0.0,
+            lltv_exposure: 0.0,
+            warnings: 0.0,
+            curator: 0.0,
+            illiquidity: 0.0,
+        };
+        let risk = RiskScorer::new().with_weights(weights).score(&v);
+        assert!((risk.score - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_top_vaults_under_risk() {
+        let safe_low = vault(
+            vec![
+                allocation(Some(1), 500, 77, 500),
+                allocation(Some(2), 500, 77, 500),
+            ],
+            0.03,
+            vec![],
+        );
+        let safe_high = vault(
+            vec![
+                allocation(Some(1), 500, 77, 500),
+                allocation(Some(2), 500, 77, 500),
+            ],
+            0.06,
+            vec![],
+        );
+        let risky = vault(
+            vec![allocation(Some(1), 1000, 98, 0)],
+            0.20,
+            vec![VaultWarning {
+                warning_type: WarningType::BadDebtRealized,
+                level: WarningLevel::Red,
+            }],
+        );
+        let vaults = vec![safe_low, risky, safe_high];
+        let max = score_vault(&vaults[0]).score;
+        let top = top_vaults_under_risk(&RiskScorer::new(), &vaults, max, 10);
+        let apys: Vec<f64> = top.iter().map(|(v, _)| net_apy(v)).collect();
+        assert_eq!(apys, vec![0.06, 0.03]);
+
+        let top1 = top_vaults_under_risk(&RiskScorer::new(), &vaults, max, 1);
+        assert_eq!(top1.len(), 1);
+    }
+
+    #[test]
+    fn test_empty_vault() {
+        let v = vault(vec![], 0.0, vec![]);
+        let risk = score_vault(&v);
+        assert_eq!(risk.concentration, 0.0);
+        assert_eq!(risk.illiquidity, 0.0);
+    }
+
+    fn vault_v2(positions: Vec<(u8, u64, u128)>, idle: u64, liquidity: u64) -> VaultV2 {
+        let total: u64 = idle + positions.iter().map(|(_, supply, _)| supply).sum::<u64>();
+        let caps = positions
+            .iter()
+            .filter(|(_, _, lltv_pct)| *lltv_pct > 0)
+            .map(|(id, _, _)| VaultCap {
+                id: B256::repeat_byte(0xC0 + id),
+                absolute_cap: U256::MAX,
+                relative_cap: U256::from(WAD),
+                allocation: U256::ZERO,
+                data: Some(VaultCapData::MarketV1 {
+                    adapter: Address::repeat_byte(0xAD),
+                    market_id: B256::repeat_byte(*id),
+                    collateral: Address::repeat_byte(*id),
+                }),
+            })
+            .collect();
+        let positions = positions
+            .into_iter()
+            .map(|(id, supply, lltv_pct)| MorphoMarketPosition {
+                supply_assets: U256::from(supply),
+                supply_shares: U256::from(supply),
+                market_id: B256::repeat_byte(id),
+                market_state: Some(MarketStateV2 {
+                    id: B256::repeat_byte(id),
+                    total_supply_assets: U256::from(supply),
+                    total_borrow_assets: U256::ZERO,
+                    total_supply_shares: U256::from(supply),
+                    total_borrow_shares: U256::ZERO,
+                    last_update: 0,
+                    fee: U256::ZERO,
+                    rate_at_target: None,
+                    price: None,
+                    lltv: U256::from(lltv_pct * WAD / 100),
+                }),
+            })
+            .collect();
+        VaultV2 {
+            address: Address::repeat_byte(0x02),
+            name: "Vault V2".to_string(),
+            symbol: "V2".to_string(),
+            chain: NamedChain::Mainnet,
+            listed: true,
+            whitelisted: true,
+            asset: Asset {
+                address: Address::repeat_byte(0xAA),
+                symbol: "USDC".to_string(),
+                name: None,
+                decimals: 6,
+                price_usd: Some(1.0),
+            },
+            curator: Some(Address::repeat_byte(0xC0)),
+            owner: None,
+            total_assets: U256::from(total),
+            total_assets_usd: None,
+            total_supply: U256::from(total),
+            share_price: None,
+            performance_fee: None,
+            management_fee: None,
+            avg_apy: None,
+            avg_net_apy: None,
+            apy: None,
+            net_apy: None,
+            liquidity: U256::from(liquidity),
+            liquidity_usd: None,
+            idle_assets: U256::from(idle),
+            liquidity_adapter: None,
+            caps,
+            adapters: vec![VaultAdapter {
+                id: "adapter".to_string(),
+                address: Address::repeat_byte(0xAD),
+                adapter_type: "MorphoMarketV1".to_string(),
+                assets: U256::from(total - idle),
+                assets_usd: None,
+                data: Some(VaultAdapterData::MorphoMarketV1 { positions }),
+            }],
+            rewards: vec![],
+            warnings: vec![],
+        }
+    }
+
+    #[test]
+    fn test_score_v2_single_market() {
+        let v = vault_v2(vec![(1, 1000, 86)], 0, 1000);
+        let risk = score_vault_v2(&v);
+        assert!((risk.concentration - 1.0).abs() < 1e-9);
+        assert!((risk.collateral_concentration - 1.0).abs() < 1e-9);
+        assert!((risk.lltv_exposure - 0.86).abs() < 1e-9);
+        assert!(risk.illiquidity.abs() < 1e-9);
+        assert!((risk.curator - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_score_v2_idle_and_diversification() {
+        let v = vault_v2(vec![(1, 250, 90), (2, 250, 90), (3, 0, 0)], 500, 250);
+        let risk = score_vault_v2(&v);
+        // Idle assets and the zero-LLTV market share one collateral bucket
+        assert!((risk.concentration - (0.25f64.powi(2) * 2.0 + 0.25)).abs() < 1e-9);
+        assert!((risk.collateral_concentration - (0.25f64.powi(2) * 2.0 + 0.25)).abs() < 1e-9);
+        assert!((risk.lltv_exposure - 0.45).abs() < 1e-9);
+        assert!((risk.illiquidity - 0.75).abs() < 1e-9);
+
+        let concentrated = vault_v2(vec![(1, 1000, 90)], 0, 250);
+        assert!(risk.score < score_vault_v2(&concentrated).score);
+    }
+
+    #[test]
+    fn test_score_v2_warnings() {
+        let mut v = vault_v2(vec![(1, 1000, 86)], 0, 1000);
+        v.warnings.push(VaultV2Warning {
+            warning_type: WarningType::ShortTimelock,
+            level: WarningLevel::Yellow,
+        });
+        assert!((score_vault_v2(&v).warnings - 0.25).abs() < 1e-9);
+        v.warnings.push(VaultV2Warning {
+            warning_type: WarningType::BadDebtUnrealized,
+            level: WarningLevel::Red,
+        });
+        assert!((score_vault_v2(&v).warnings - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,361 @@
+//! ERC20 balance manipulation through storage writes.
+//!
+//! An ERC20 balance lives in the token's `balanceOf` mapping, so an account is funded by
+//! writing the amount to the mapping entry for it with `anvil_setStorageAt`. The entry
+//! is located by, in order:
+//!
+//! 1. [`KNOWN_TOKENS`], a registry of balance slots for common tokens across chains
+//! 2. The storage keys `balanceOf(account)` reads, from `eth_createAccessList`; this
+//!    finds entries at any slot, including ERC-7201 namespaced storage behind proxies
+//! 3. Probing the first [`MAX_PROBED_SLOTS`] slots in both Solidity and Vyper layouts
+//!
+//! Each discovered candidate is verified by writing a marker value and checking that
+//! `balanceOf` returns it.
+//!
+//! Only the balance is written: the total supply is left unchanged, and tokens that
+//! compute balances (rebasing tokens, share-based tokens like stETH) cannot be funded
+//! this way.
+
+use alloy::network::Ethereum;
+use alloy::primitives::{address, keccak256, Address, U256};
+use alloy::providers::ext::AnvilApi;
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol;
+use alloy::sol_types::{SolCall, SolValue};
+
+use crate::error::{ForkError, Result};
+
+sol! {
+    #[sol(rpc)]
+    interface IERC20Balance {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// Number of storage slots [`find_balance_slot`] probes.
+pub const MAX_PROBED_SLOTS: u64 = 20;
+
+/// USDC on Ethereum mainnet.
+pub const USDC_MAINNET: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+/// USDT on Ethereum mainnet.
+pub const USDT_MAINNET: Address = address!("dAC17F958D2ee523a2206206994597C13D831ec7");
+/// DAI on Ethereum mainnet.
+pub const DAI_MAINNET: Address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+/// WETH on Ethereum mainnet.
+pub const WETH_MAINNET: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+/// WBTC on Ethereum mainnet.
+pub const WBTC_MAINNET: Address = address!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599");
+/// wstETH on Ethereum mainnet.
+pub const WSTETH_MAINNET: Address = address!("7f39C581F595B53c5cb19bD0b3f8dA6c935E2Ca0");
+/// USDC on Base.
+pub const USDC_BASE: Address = address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+/// WETH on Base (and other OP Stack chains).
+pub const WETH_BASE: Address = address!("4200000000000000000000000000000000000006");
+/// USDC on Arbitrum.
+pub const USDC_ARBITRUM: Address = address!("af88d065e77c8cC2239327C5EDb3A432268e5831");
+/// USDC on Optimism.
+pub const USDC_OPTIMISM: Address = address!("0b2C639c533813f4Aa9D7837CAf62653d097Ff85");
+/// USDC on Polygon.
+pub const USDC_POLYGON: Address = address!("3c499c542cEF5E3811e1192ce70d8cC03d5c3359");
+
+/// How a compiler lays out `mapping(address => uint256)` entries in storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotLayout {
+    /// `keccak256(abi.encode(key, slot))`.
+    Solidity,
+    /// `keccak256(abi.encode(slot, key))`.
+    Vyper,
+}
+
+/// Storage slot of a token's `balanceOf` mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceSlot {
+    /// Slot the mapping is declared at.
+    pub slot: U256,
+    /// Layout of the mapping's entries.
+    pub layout: SlotLayout,
+}
+
+impl BalanceSlot {
+    /// A Solidity mapping declared at `slot`.
+    pub const fn solidity(slot: u64) -> Self {
+        Self {
+            slot: U256::from_limbs([slot, 0, 0, 0]),
+            layout: SlotLayout::Solidity,
+        }
+    }
+
+    /// A Vyper mapping declared at `slot`.
+    pub const fn vyper(slot: u64) -> Self {
+        Self {
+            slot: U256::from_limbs([slot, 0, 0, 0]),
+            layout: SlotLayout::Vyper,
+        }
+    }
+
+    /// Storage key of `account`'s balance.
+    pub fn key(&self, account: Address) -> U256 {
+        let hash = match self.layout {
+            SlotLayout::Solidity => keccak256((account, self.slot).abi_encode()),
+            SlotLayout::Vyper => keccak256((self.slot, account).abi_encode()),
+        };
+        hash.into()
+    }
+}
+
+/// A token with a known `balanceOf` slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownToken {
+    /// Token symbol.
+    pub symbol: &'static str,
+    /// Chain the token is deployed on.
+    pub chain_id: u64,
+    /// Token address.
+    pub address: Address,
+    /// Slot of the token's `balanceOf` mapping.
+    pub slot: BalanceSlot,
+}
+
+impl KnownToken {
+    const fn new(symbol: &'static str, chain_id: u64, address: Address, slot: BalanceSlot) -> Self {
+        Self {
+            symbol,
+            chain_id,
+            address,
+            slot,
+        }
+    }
+}
+
+/// Registry of `balanceOf` slots for common tokens.
+///
+/// WETH on Base is the OP Stack predeploy, so the entry also applies to other OP Stack
+/// chains.
+pub const KNOWN_TOKENS: &[KnownToken] = &[
+    KnownToken::new("USDC", 1, USDC_MAINNET, BalanceSlot::solidity(9)),
+    KnownToken::new("USDT", 1, USDT_MAINNET, BalanceSlot::solidity(2)),
+    KnownToken::new("DAI", 1, DAI_MAINNET, BalanceSlot::solidity(2)),
+    KnownToken::new("WETH", 1, WETH_MAINNET, BalanceSlot::solidity(3)),
+    KnownToken::new("WBTC", 1, WBTC_MAINNET, BalanceSlot::solidity(0)),
+    KnownToken::new("wstETH", 1, WSTETH_MAINNET, BalanceSlot::solidity(0)),
+    KnownToken::new("USDC", 8453, USDC_BASE, BalanceSlot::solidity(9)),
+    KnownToken::new("WETH", 8453, WETH_BASE, BalanceSlot::solidity(3)),
+    KnownToken::new("USDC", 42161, USDC_ARBITRUM, BalanceSlot::solidity(9)),
+    KnownToken::new("USDC", 10, USDC_OPTIMISM, BalanceSlot::solidity(9)),
+    KnownToken::new("USDC", 137, USDC_POLYGON, BalanceSlot::solidity(9)),
+];
+
+/// The `balanceOf` slot of `token`, if it is in [`KNOWN_TOKENS`].
+pub fn known_balance_slot(token: Address) -> Option<BalanceSlot> {
+    KNOWN_TOKENS
+        .iter()
+        .find(|known| known.address == token)
+        .map(|known| known.slot)
+}
+
+/// The known token with `symbol` on `chain_id`, matched case-insensitively.
+pub fn known_token(symbol: &str, chain_id: u64) -> Option<&'static KnownToken> {
+    KNOWN_TOKENS
+        .iter()
+        .find(|known| known.chain_id == chain_id && known.symbol.eq_ignore_ascii_case(symbol))
+}
+
+/// Find the storage key holding `account`'s balance of `token`.
+///
+/// Tries the storage keys of `token` that `balanceOf(account)` reads, as reported by
+/// `eth_createAccessList`, then falls back to [`find_balance_slot`].
+///
+/// # Errors
+///
+/// - [`ForkError::BalanceSlotNotFound`] if no candidate key holds the balance
+/// - [`ForkError::Rpc`] if a request to the fork fails
+pub async fn find_balance_key<P: Provider<Ethereum>>(
+    provider: &P,
+    token: Address,
+    account: Address,
+) -> Result<U256> {
+    for key in accessed_storage_keys(provider, token, account).await? {
+        if is_balance_key(provider, token, account, key).await? {
+            return Ok(key);
+        }
+    }
+    let slot = find_balance_slot(provider, token, account).await?;
+    Ok(slot.key(account))
+}
+
+/// Storage keys of `token` read by `balanceOf(account)`.
+async fn accessed_storage_keys<P: Provider<Ethereum>>(
+    provider: &P,
+    token: Address,
+    account: Address,
+) -> Result<Vec<U256>> {
+    let call = IERC20Balance::balanceOfCall { account };
+    let request = TransactionRequest::default()
+        .to(token)
+        .input(call.abi_encode().into());
+    let result = provider
+        .create_access_list(&request)
+        .await
+        .map_err(|e| ForkError::Rpc(format!("Failed to create access list: {}", e)))?;
+    Ok(result
+        .access_list
+        .0
+        .into_iter()
+        .filter(|item| item.address == token)
+        .flat_map(|item| item.storage_keys)
+        .map(|key| U256::from_be_bytes(key.0))
+        .collect())
+}
+
+/// Whether writing to `key` changes `balanceOf(account)`.
+///
+/// The original value of `key` is restored afterwards.
+async fn is_balance_key<P: Provider<Ethereum>>(
+    provider: &P,
+    token: Address,
+    account: Address,
+    key: U256,
+) -> Result<bool> {
+    let marker = U256::from(0x6d6f7270686fu64); // "morpho"
+    let original = provider
+        .get_storage_at(token, key)
+        .await
+        .map_err(|e| ForkError::Rpc(format!("Failed to read storage: {}", e)))?;
+    set_storage(provider, token, key, marker).await?;
+    let balance = balance_of(provider, token, account).await;
+    set_storage(provider, token, key, original).await?;
+    Ok(balance.is_ok_and(|balance| balance == marker))
+}
+
+/// Find the `balanceOf` slot of `token` by writing a marker value to each candidate
+/// entry for `account` and checking whether `balanceOf(account)` returns it.
+///
+/// Every probed entry is restored afterwards.
+///
+/// # Errors
+///
+/// - [`ForkError::BalanceSlotNotFound`] if none of the first [`MAX_PROBED_SLOTS`] slots
+///   matches in either layout
+/// - [`ForkError::Rpc`] if a request to the fork fails
+pub async fn find_balance_slot<P: Provider<Ethereum>>(
+    provider: &P,
+    token: Address,
+    account: Address,
+) -> Result<BalanceSlot> {
+    for slot in 0..MAX_PROBED_SLOTS {
+        for candidate in [BalanceSlot::solidity(slot), BalanceSlot::vyper(slot)] {
+            if is_balance_key(provider, token, account, candidate.key(account)).await? {
+                return Ok(candidate);
+            }
+        }
+    }
+    Err(ForkError::BalanceSlotNotFound { token })
+}
+
+/// Set `account`'s balance of `token` to `amount`.
+///
+/// The storage key comes from [`known_balance_slot`], or is found with
+/// [`find_balance_key`] for other tokens.
+///
+/// # Errors
+///
+/// - [`ForkError::BalanceSlotNotFound`] if the token's balance slot cannot be found
+/// - [`ForkError::Rpc`] if a request to the fork fails
+pub async fn fund_erc20<P: Provider<Ethereum>>(
+    provider: &P,
+    token: Address,
+    account: Address,
+    amount: U256,
+) -> Result<()> {
+    let key = match known_balance_slot(token) {
+        Some(slot) => slot.key(account),
+        None => find_balance_key(provider, token, account).await?,
+    };
+    set_storage(provider, token, key, amount).await
+}
+
+/// `account`'s balance of `token`.
+///
+/// # Errors
+///
+/// Returns [`ForkError::Rpc`] if the call fails.
+pub async fn balance_of<P: Provider<Ethereum>>(
+    provider: &P,
+    token: Address,
+    account: Address,
+) -> Result<U256> {
+    IERC20Balance::new(token, provider)
+        .balanceOf(account)
+        .call()
+        .await
+        .map_err(|e| ForkError::Rpc(format!("Failed to get balance: {}", e)))
+}
+
+async fn set_storage<P: Provider<Ethereum>>(
+    provider: &P,
+    address: Address,
+    key: U256,
+    value: U256,
+) -> Result<()> {
+    provider
+        .anvil_set_storage_at(address, key, value.into())
+        .await
+        .map_err(|e| ForkError::Rpc(format!("Failed to set storage: {}", e)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_balance_slot() {
+        assert_eq!(
+            known_balance_slot(USDC_MAINNET),
+            Some(BalanceSlot::solidity(9))
+        );
+        assert_eq!(
+            known_balance_slot(WETH_BASE),
+            Some(BalanceSlot::solidity(3))
+        );
+        assert_eq!(known_balance_slot(Address::repeat_byte(0x01)), None);
+    }
+
+    #[test]
+    fn test_known_token() {
+        assert_eq!(
+            known_token("wsteth", 1).map(|t| t.address),
+            Some(WSTETH_MAINNET)
+        );
+        assert_eq!(
+            known_token("USDC", 42161).map(|t| t.address),
+            Some(USDC_ARBITRUM)
+        );
+        assert_eq!(known_token("USDC", 59144), None);
+    }
+
+    #[test]
+    fn test_known_tokens_unique() {
+        for (i, a) in KNOWN_TOKENS.iter().enumerate() {
+            for b in &KNOWN_TOKENS[i + 1..] {
+                assert_ne!(a.address, b.address, "{} listed twice", a.symbol);
+            }
+        }
+    }
+
+    #[test]
+    fn test_balance_slot_key() {
+        let account = Address::repeat_byte(0xaa);
+        let solidity = BalanceSlot::solidity(9);
+        let vyper = BalanceSlot::vyper(9);
+
+        let expected: U256 = keccak256((account, U256::from(9)).abi_encode()).into();
+        assert_eq!(solidity.key(account), expected);
+        assert_ne!(vyper.key(account), expected);
+        assert_ne!(
+            solidity.key(account),
+            solidity.key(Address::repeat_byte(0xbb))
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//! Error types for the testing crate.
+
+use alloy::primitives::Address;
+use thiserror::Error;
+
+/// Errors that can occur when spawning or manipulating a fork.
+#[derive(Debug, Error)]
+pub enum ForkError {
+    /// Anvil could not be spawned.
+    #[error("Failed to spawn Anvil: {0}")]
+    Spawn(String),
+
+    /// An RPC request to the fork failed.
+    #[error("RPC request failed: {0}")]
+    Rpc(String),
+
+    /// No storage slot holding `balanceOf` could be found for the token.
+    #[error("Balance slot not found for token {token}")]
+    BalanceSlotNotFound {
+        /// The token whose balance could not be set.
+        token: Address,
+    },
+}
+
+/// Result type alias for fork operations.
+pub type Result<T> = std::result::Result<T, ForkError>;
@@ -0,0 +1,269 @@
+//! Forked Anvil nodes.
+
+use alloy::network::Ethereum;
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::{address, Address, U256};
+use alloy::providers::ext::AnvilApi;
+use alloy::providers::RootProvider;
+
+use crate::balance;
+use crate::error::{ForkError, Result};
+
+/// Private key of Anvil's default account 0.
+pub const ANVIL_PRIVATE_KEY: &str =
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Address of Anvil's default account 0.
+pub const ANVIL_ADDRESS: Address = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+
+/// Default compute units per second requested from the upstream RPC.
+pub const DEFAULT_COMPUTE_UNITS_PER_SECOND: u64 = 100;
+/// Default number of retries for failed upstream requests.
+pub const DEFAULT_RETRIES: u32 = 5;
+/// Default backoff between upstream retries, in milliseconds.
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 1000;
+/// Default time to wait for Anvil to start, in milliseconds.
+pub const DEFAULT_TIMEOUT_MS: u64 = 45000;
+
+/// Configuration for a forked Anvil node.
+///
+/// The defaults keep the upstream request rate low enough for free-tier RPC providers.
+#[derive(Debug, Clone)]
+pub struct ForkConfig {
+    /// RPC URL of the chain to fork.
+    pub rpc_url: String,
+    /// Block to fork at. `None` forks at the latest block.
+    pub block_number: Option<u64>,
+    /// Compute units per second requested from the upstream RPC.
+    pub compute_units_per_second: u64,
+    /// Number of retries for failed upstream requests.
+    pub retries: u32,
+    /// Backoff between upstream retries, in milliseconds.
+    pub retry_backoff_ms: u64,
+    /// Time to wait for Anvil to start, in milliseconds.
+    pub timeout_ms: u64,
+}
+
+impl ForkConfig {
+    /// Fork `rpc_url` at the latest block with default limits.
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            block_number: None,
+            compute_units_per_second: DEFAULT_COMPUTE_UNITS_PER_SECOND,
+            retries: DEFAULT_RETRIES,
+            retry_backoff_ms: DEFAULT_RETRY_BACKOFF_MS,
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        }
+    }
+
+    /// Read the configuration from environment variables:
+    /// - `ETH_RPC_URL` (required): The RPC URL to fork from
+    /// - `FORK_BLOCK_NUMBER` (default: latest): The block to fork at
+    /// - `ANVIL_COMPUTE_UNITS_PER_SECOND` (default: 100): Compute units per second
+    /// - `ANVIL_RETRIES` (default: 5): Number of retries for failed requests
+    /// - `ANVIL_FORK_RETRY_BACKOFF` (default: 1000): Backoff in ms between retries
+    /// - `ANVIL_TIMEOUT` (default: 45000): Timeout in ms for Anvil to start
+    ///
+    /// Returns `None` if `ETH_RPC_URL` is not set, so tests can skip themselves.
+    pub fn from_env() -> Option<Self> {
+        let rpc_url = std::env::var("ETH_RPC_URL").ok()?;
+        Some(Self {
+            rpc_url,
+            block_number: env_var("FORK_BLOCK_NUMBER"),
+            compute_units_per_second: env_var("ANVIL_COMPUTE_UNITS_PER_SECOND")
+                .unwrap_or(DEFAULT_COMPUTE_UNITS_PER_SECOND),
+            retries: env_var("ANVIL_RETRIES").unwrap_or(DEFAULT_RETRIES),
+            retry_backoff_ms: env_var("ANVIL_FORK_RETRY_BACKOFF")
+                .unwrap_or(DEFAULT_RETRY_BACKOFF_MS),
+            timeout_ms: env_var("ANVIL_TIMEOUT").unwrap_or(DEFAULT_TIMEOUT_MS),
+        })
+    }
+
+    /// Fork at `block_number` instead of the latest block.
+    pub fn at_block(mut self, block_number: u64) -> Self {
+        self.block_number = Some(block_number);
+        self
+    }
+
+    /// Set the compute units per second requested from the upstream RPC.
+    pub fn with_compute_units_per_second(mut self, compute_units: u64) -> Self {
+        self.compute_units_per_second = compute_units;
+        self
+    }
+
+    /// Set the number of retries for failed upstream requests.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the backoff between upstream retries, in milliseconds.
+    pub fn with_retry_backoff_ms(mut self, backoff_ms: u64) -> Self {
+        self.retry_backoff_ms = backoff_ms;
+        self
+    }
+
+    /// Set the time to wait for Anvil to start, in milliseconds.
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+}
+
+/// Reads an env var, returning `None` if not set or invalid.
+fn env_var<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.parse().ok())
+}
+
+/// A running forked Anvil node.
+///
+/// The node is killed when the `Fork` is dropped.
+#[derive(Debug)]
+pub struct Fork {
+    anvil: AnvilInstance,
+    provider: RootProvider<Ethereum>,
+}
+
+impl Fork {
+    /// Spawn Anvil forking the chain described by `config`.
+    ///
+    /// Requires the `anvil` binary on `PATH`.
+    pub fn spawn(config: &ForkConfig) -> Result<Self> {
+        let mut anvil = Anvil::new()
+            .fork(&config.rpc_url)
+            .arg("--compute-units-per-second")
+            .arg(config.compute_units_per_second.to_string())
+            .arg("--retries")
+            .arg(config.retries.to_string())
+            .arg("--fork-retry-backoff")
+            .arg(config.retry_backoff_ms.to_string())
+            .timeout(config.timeout_ms);
+        if let Some(block_number) = config.block_number {
+            anvil = anvil
+                .arg("--fork-block-number")
+                .arg(block_number.to_string());
+        }
+        let anvil = anvil
+            .try_spawn()
+            .map_err(|e| ForkError::Spawn(e.to_string()))?;
+        let provider = RootProvider::new_http(anvil.endpoint_url());
+        Ok(Self { anvil, provider })
+    }
+
+    /// Spawn a fork configured by [`ForkConfig::from_env`].
+    ///
+    /// Returns `None` if `ETH_RPC_URL` is not set.
+    pub fn from_env() -> Option<Result<Self>> {
+        ForkConfig::from_env().map(|config| Self::spawn(&config))
+    }
+
+    /// HTTP endpoint of the node.
+    pub fn endpoint(&self) -> String {
+        self.anvil.endpoint()
+    }
+
+    /// HTTP endpoint of the node as a URL.
+    pub fn endpoint_url(&self) -> url::Url {
+        self.anvil.endpoint_url()
+    }
+
+    /// A provider connected to the node.
+    pub fn provider(&self) -> &RootProvider<Ethereum> {
+        &self.provider
+    }
+
+    /// The underlying Anvil instance.
+    pub fn anvil(&self) -> &AnvilInstance {
+        &self.anvil
+    }
+
+    /// Set `account`'s ETH balance to `amount`.
+    pub async fn fund_eth(&self, account: Address, amount: U256) -> Result<()> {
+        self.provider
+            .anvil_set_balance(account, amount)
+            .await
+            .map_err(|e| ForkError::Rpc(format!("Failed to set balance: {}", e)))
+    }
+
+    /// Set `account`'s balance of `token` to `amount`.
+    ///
+    /// See [`balance::fund_erc20`].
+    pub async fn fund_erc20(&self, token: Address, account: Address, amount: U256) -> Result<()> {
+        balance::fund_erc20(&self.provider, token, account, amount).await
+    }
+
+    /// `account`'s balance of `token`.
+    pub async fn erc20_balance(&self, token: Address, account: Address) -> Result<U256> {
+        balance::balance_of(&self.provider, token, account).await
+    }
+
+    /// Let transactions from `address` be sent without its key.
+    ///
+    /// Send them with `eth_sendTransaction` through [`Fork::provider`], e.g. via a
+    /// contract binding's `.from(address).send()`. Contract accounts (multisigs,
+    /// vault curators) can be impersonated too; fund them with [`Fork::fund_eth`]
+    /// to pay for gas.
+    pub async fn impersonate(&self, address: Address) -> Result<()> {
+        self.provider
+            .anvil_impersonate_account(address)
+            .await
+            .map_err(|e| ForkError::Rpc(format!("Failed to impersonate account: {}", e)))
+    }
+
+    /// Stop impersonating `address`.
+    pub async fn stop_impersonating(&self, address: Address) -> Result<()> {
+        self.provider
+            .anvil_stop_impersonating_account(address)
+            .await
+            .map_err(|e| ForkError::Rpc(format!("Failed to stop impersonating: {}", e)))
+    }
+
+    /// Advance the next block's timestamp by `seconds`, e.g. to accrue interest.
+    pub async fn increase_time(&self, seconds: u64) -> Result<()> {
+        self.provider
+            .anvil_increase_time(seconds)
+            .await
+            .map(|_| ())
+            .map_err(|e| ForkError::Rpc(format!("Failed to increase time: {}", e)))
+    }
+
+    /// Mine `blocks` blocks.
+    pub async fn mine(&self, blocks: u64) -> Result<()> {
+        self.provider
+            .anvil_mine(Some(blocks), None)
+            .await
+            .map_err(|e| ForkError::Rpc(format!("Failed to mine: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_config_defaults() {
+        let config = ForkConfig::new("http://localhost:8545");
+        assert_eq!(config.rpc_url, "http://localhost:8545");
+        assert_eq!(config.block_number, None);
+        assert_eq!(config.compute_units_per_second, 100);
+        assert_eq!(config.retries, 5);
+        assert_eq!(config.retry_backoff_ms, 1000);
+        assert_eq!(config.timeout_ms, 45000);
+    }
+
+    #[test]
+    fn test_fork_config_builders() {
+        let config = ForkConfig::new("http://localhost:8545")
+            .at_block(21_000_000)
+            .with_compute_units_per_second(300)
+            .with_retries(2)
+            .with_retry_backoff_ms(500)
+            .with_timeout_ms(10_000);
+        assert_eq!(config.block_number, Some(21_000_000));
+        assert_eq!(config.compute_units_per_second, 300);
+        assert_eq!(config.retries, 2);
+        assert_eq!(config.retry_backoff_ms, 500);
+        assert_eq!(config.timeout_ms, 10_000);
+    }
+}
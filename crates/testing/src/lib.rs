@@ -0,0 +1,46 @@
+//! # Morpho Fork Testing
+//!
+//! Anvil fork test harness for writing integration tests against live Morpho contracts.
+//!
+//! ## Modules
+//!
+//! - [`fork`]: [`Fork`] spawning Anvil against a [`ForkConfig`], with ETH and ERC20
+//!   funding, account impersonation, and time travel
+//! - [`balance`]: ERC20 funding through `balanceOf` storage writes, with a registry of
+//!   known balance slots across chains and slot discovery for other tokens
+//!
+//! ## Quick Start
+//!
+//! ```no_run
+//! use alloy::primitives::{address, U256};
+//! use morpho_rs_testing::{Fork, ForkConfig, ANVIL_ADDRESS, USDC_MAINNET};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), morpho_rs_testing::ForkError> {
+//!     let fork = Fork::spawn(&ForkConfig::new("https://eth.llamarpc.com"))?;
+//!     fork.fund_erc20(USDC_MAINNET, ANVIL_ADDRESS, U256::from(1_000_000_000u64))
+//!         .await?;
+//!
+//!     let curator = address!("827e86072B06674a077f592A531dcE4590aDeCdB");
+//!     fork.impersonate(curator).await?;
+//!     fork.fund_eth(curator, U256::from(10u128.pow(18))).await?;
+//!     println!("fork running at {}", fork.endpoint());
+//!     Ok(())
+//! }
+//! ```
+
+pub mod balance;
+pub mod error;
+pub mod fork;
+
+pub use balance::{
+    balance_of, find_balance_key, find_balance_slot, fund_erc20, known_balance_slot, known_token,
+    BalanceSlot, KnownToken, SlotLayout, DAI_MAINNET, KNOWN_TOKENS, MAX_PROBED_SLOTS,
+    USDC_ARBITRUM, USDC_BASE, USDC_MAINNET, USDC_OPTIMISM, USDC_POLYGON, USDT_MAINNET,
+    WBTC_MAINNET, WETH_BASE, WETH_MAINNET, WSTETH_MAINNET,
+};
+pub use error::{ForkError, Result};
+pub use fork::{
+    Fork, ForkConfig, ANVIL_ADDRESS, ANVIL_PRIVATE_KEY, DEFAULT_COMPUTE_UNITS_PER_SECOND,
+    DEFAULT_RETRIES, DEFAULT_RETRY_BACKOFF_MS, DEFAULT_TIMEOUT_MS,
+};